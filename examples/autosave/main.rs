@@ -3,6 +3,7 @@ use std::convert::TryInto;
 use std::fs::File;
 use std::{env, io::BufRead, io::BufReader, io::Read};
 use weasel::event::EventSinkId;
+use weasel::server::{ShutdownPolicy, ShutdownReason};
 use weasel::team::TeamId;
 use weasel::{
     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateCreature,
@@ -64,6 +65,10 @@ fn game_loop() {
             }
         }
     }
+    // Shut down the server, to be sure that the autosave is persisted.
+    server
+        .shutdown(ShutdownPolicy::new(ShutdownReason::Terminated))
+        .unwrap();
 }
 
 /// Retrieves how many creatures are in the battle.
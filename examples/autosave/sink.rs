@@ -2,7 +2,7 @@ use std::env;
 use std::fs::{File, OpenOptions};
 use std::io::Write;
 use weasel::event::{ClientSink, EventSink, EventSinkId};
-use weasel::{BattleRules, FlatVersionedEvent, VersionedEventWrapper, WeaselResult};
+use weasel::{BattleRules, FlatVersionedEvent, VersionedEventWrapper, WeaselError, WeaselResult};
 
 /// A sink that dumps events into a file.
 pub struct AutosaveSink<R: BattleRules> {
@@ -50,4 +50,11 @@ impl<R: BattleRules + 'static> ClientSink<R> for AutosaveSink<R> {
         self.file.write_all(b"#").unwrap();
         Ok(())
     }
+
+    fn flush(&mut self) -> WeaselResult<(), R> {
+        // Make sure everything is persisted before the server goes away.
+        self.file
+            .sync_all()
+            .map_err(|err| WeaselError::EventSinkError(err.to_string()))
+    }
 }
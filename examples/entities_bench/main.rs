@@ -0,0 +1,119 @@
+use std::time::{Duration, Instant};
+use weasel::creature::CreatureId;
+use weasel::team::TeamId;
+use weasel::util::Id;
+use weasel::{
+    battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateCreature,
+    CreateTeam, EventTrigger, RemoveCreature, Server,
+};
+
+// Use the empty rules, so that only the cost of the storage is measured.
+battle_rules! {}
+
+/// Number of creatures spawned in the battle.
+const CREATURES: u32 = 10_000;
+/// How many times each lookup is repeated.
+const ROUNDS: u32 = 10;
+
+static TEAM_ID: TeamId<CustomRules> = 1;
+
+/// A simple performance counter, accumulating the time spent in a set of operations.
+struct Counter {
+    name: &'static str,
+    operations: u32,
+    elapsed: Duration,
+}
+
+impl Counter {
+    fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            operations: 0,
+            elapsed: Duration::default(),
+        }
+    }
+
+    /// Runs `f` and records its duration as `operations` operations.
+    fn measure<F: FnOnce()>(&mut self, operations: u32, f: F) {
+        let start = Instant::now();
+        f();
+        self.elapsed += start.elapsed();
+        self.operations += operations;
+    }
+
+    fn report(&self) {
+        let per_op = self.elapsed.as_nanos() / u128::from(self.operations.max(1));
+        println!(
+            "{:<28} {:>8} ops {:>12.3?} total {:>8} ns/op",
+            self.name, self.operations, self.elapsed, per_op
+        );
+    }
+}
+
+fn main() {
+    println!("Entities storage benchmark, {} creatures", CREATURES);
+    println!();
+    // Compare spawning with and without pre-allocated storage.
+    let mut spawn = Counter::new("spawn");
+    let server = spawn_creatures(Battle::builder(CustomRules::new()).build(), &mut spawn);
+    let mut spawn_reserved = Counter::new("spawn (pre-allocated)");
+    let _ = spawn_creatures(
+        Battle::builder(CustomRules::new())
+            .entities_capacity(CREATURES as usize, 0)
+            .build(),
+        &mut spawn_reserved,
+    );
+    spawn.report();
+    spawn_reserved.report();
+    // Measure lookups.
+    let mut server = server;
+    let ids: Vec<CreatureId<CustomRules>> = (0..CREATURES).collect();
+    let mut lookup = Counter::new("lookup");
+    let mut iteration = Counter::new("iteration");
+    for _ in 0..ROUNDS {
+        lookup.measure(CREATURES, || {
+            let entities = server.battle().entities();
+            for id in &ids {
+                assert!(entities.creature(id).is_some());
+            }
+        });
+        iteration.measure(CREATURES, || {
+            let sum: u64 = server
+                .battle()
+                .entities()
+                .creatures()
+                .map(|creature| u64::from(*creature.id()))
+                .sum();
+            assert_eq!(sum, u64::from(CREATURES) * u64::from(CREATURES - 1) / 2);
+        });
+    }
+    lookup.report();
+    iteration.report();
+    // Measure removal of all creatures.
+    let mut removal = Counter::new("removal");
+    removal.measure(CREATURES, || {
+        for id in ids {
+            RemoveCreature::trigger(&mut server, id).fire().unwrap();
+        }
+    });
+    removal.report();
+    assert_eq!(server.battle().entities().creatures_len(), 0);
+}
+
+/// Creates a server and spawns `CREATURES` creatures, measuring the time spent.
+fn spawn_creatures(battle: Battle<CustomRules>, counter: &mut Counter) -> Server<CustomRules> {
+    let mut server = Server::builder(battle).build();
+    CreateTeam::trigger(&mut server, TEAM_ID).fire().unwrap();
+    counter.measure(CREATURES, || {
+        for id in 0..CREATURES {
+            CreateCreature::trigger(&mut server, id, TEAM_ID, ())
+                .fire()
+                .unwrap();
+        }
+    });
+    assert_eq!(
+        server.battle().entities().creatures_len(),
+        CREATURES as usize
+    );
+    server
+}
@@ -51,11 +51,10 @@ fn turn(server: &mut Server<CustomRules>) {
     );
     println!();
     // Display the order of initiative.
-    let initiative = server.battle().rounds().model();
-    println!("{}", initiative);
+    println!("{}", server.battle().rounds().model());
     // Find out who should act.
     // In this game it's always the creature at the top of the initiative table.
-    let actor_id = initiative.top();
+    let actor_id = server.next_actor().unwrap();
     println!("It's the turn of: {}", actor_id);
     println!();
     // Start the turn.
@@ -1,3 +1,5 @@
+#[cfg(feature = "serialization")]
+use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter, Result};
 use weasel::character::StatisticId;
 use weasel::rules::entropy::UniformDistribution;
@@ -79,6 +81,15 @@ impl RoundsRules<CustomRules> for CustomRoundsRules {
         }
     }
 
+    fn order(
+        &self,
+        model: &Self::RoundsModel,
+        _: &Entities<CustomRules>,
+    ) -> Vec<EntityId<CustomRules>> {
+        // Actors are already sorted by their initiative score.
+        model.actors.iter().map(|(actor_id, _)| *actor_id).collect()
+    }
+
     fn on_end(
         &self,
         entities: &Entities<CustomRules>,
@@ -129,6 +140,7 @@ impl RoundsRules<CustomRules> for CustomRoundsRules {
 }
 
 #[derive(Default)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub(crate) struct InitiativeModel {
     // A vector where we store all actors with their current initiative score.
     actors: Vec<(EntityId<CustomRules>, u32)>,
@@ -150,11 +162,6 @@ impl InitiativeModel {
         self.sort();
     }
 
-    /// Returns the actor with the highest score of initiative.
-    pub(crate) fn top(&self) -> EntityId<CustomRules> {
-        self.actors[0].0
-    }
-
     /// Sets the initiative score of the given actor to 0.
     fn reset(&mut self, actor: &dyn Actor<CustomRules>) {
         if let Some(index) = self.actor_index(actor) {
@@ -7,9 +7,9 @@ use std::{io::Read, thread, time};
 use weasel::round::TurnsCount;
 use weasel::team::TeamId;
 use weasel::{
-    ActivateAbility, Actor, Battle, BattleController, BattleState, Character, CreateCreature,
-    CreateTeam, Creature, EndRound, EndTurn, EntityId, EventKind, EventProcessor, EventQueue,
-    EventTrigger, EventWrapper, Id, RemoveEntity, ResetObjectives, Server, StartTurn,
+    ActivateAbility, Actor, AddScore, Battle, BattleController, BattleState, Character,
+    CreateCreature, CreateTeam, Creature, EndRound, EndTurn, EntityId, EventProcessor, EventQueue,
+    EventTrigger, EventWrapper, Id, RemoveEntity, Server, StartTurn,
 };
 
 mod rules;
@@ -145,8 +145,8 @@ fn client_game_loop(mut client: TcpClient) {
 fn game_status(battle: &Battle<CustomRules>, id: TeamId<CustomRules>) {
     // Print the game state.
     print_separator();
-    for team in battle.entities().teams() {
-        println!("Player {} points: {}", team.id() + 1, team.objectives());
+    for team in battle.entities().leaderboard() {
+        println!("Player {} points: {}", team.id() + 1, team.score());
     }
     // Print the player's hand.
     println!("\nYour hand:");
@@ -220,17 +220,7 @@ fn server_end_turn(server: &mut Arc<Mutex<Server<CustomRules>>>) {
     // Decide the winner.
     let winner = winner(server);
     // Update the winner's score.
-    let new_score = server
-        .lock()
-        .unwrap()
-        .battle()
-        .entities()
-        .team(&winner)
-        .unwrap()
-        .objectives()
-        + 1;
-    ResetObjectives::trigger(&mut *server.lock().unwrap(), winner)
-        .seed(new_score)
+    AddScore::trigger(&mut *server.lock().unwrap(), winner, 1)
         .fire()
         .unwrap();
     // Remove all played cards.
@@ -288,12 +278,7 @@ fn event_callback(
     _: &BattleState<CustomRules>,
     _: &mut Option<EventQueue<CustomRules>>,
 ) {
-    if let EventKind::ResetObjectives = event.kind() {
-        let event: &ResetObjectives<CustomRules> =
-            match event.as_any().downcast_ref::<ResetObjectives<_>>() {
-                Some(e) => e,
-                None => panic!("incorrect cast!"),
-            };
+    if let Some(event) = event.downcast::<AddScore<_>>() {
         println!("Player {} won a turn!", event.id() + 1);
     }
 }
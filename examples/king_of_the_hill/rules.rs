@@ -57,14 +57,10 @@ impl TeamRules<CustomRules> for MyTeamRules {
     type PowersSeed = ();
     type Invocation = ();
     type PowersAlteration = ();
-    // How many turns a team has won.
-    type ObjectivesSeed = u8;
-    // Our objective is to win 'turns', so a simple counter will suffice.
-    type Objectives = u8;
-
-    fn generate_objectives(&self, seed: &Option<Self::ObjectivesSeed>) -> Self::Objectives {
-        seed.unwrap_or_default()
-    }
+    // Teams don't have objectives. The number of turns won is tracked by the team's score.
+    type ObjectivesSeed = ();
+    type Objectives = ();
+    type TeamData = ();
 }
 
 // We define the round rules to impose an ordering to player's moves.
@@ -113,6 +109,7 @@ impl SpaceRules<CustomRules> for MySpaceRules {
     type Position = bool;
     type SpaceSeed = ();
     // Array with the id of cards on the table.
+    type Area = ();
     type SpaceModel = [Option<EntityId<CustomRules>>; 3];
     type SpaceAlteration = ();
 
@@ -1,116 +1,31 @@
 use crate::rules::CustomRules;
-use std::io::{BufRead, BufReader, Write};
-use std::net::{Shutdown, TcpListener, TcpStream};
+use serde::{de::DeserializeOwned, Serialize};
+use std::net::{TcpListener, TcpStream};
 use std::sync::{Arc, Mutex};
 use std::{io, thread, thread::JoinHandle, time};
-use weasel::event::{ClientSink, EventSink, EventSinkId, ServerSink};
+use weasel::sinks::tcp::{Codec, FrameKind, TcpClientSink, TcpReader, TcpServerSink};
 use weasel::team::TeamId;
-use weasel::{
-    Battle, BattleRules, Client, ClientEventPrototype, EventReceiver, EventServer, FlatClientEvent,
-    FlatVersionedEvent, Server, VersionedEventWrapper, WeaselResult,
-};
+use weasel::{Battle, Client, EventReceiver, EventServer, Server};
 
 const REMOTE_CLIENTS: usize = 2;
-/// Shutdown byte.
-const BYE: u8 = 0xFD;
-/// Delimiter byte between messages.
-const DELIMITER: u8 = 0xFE;
-/// This byte followed by another byte (containing the team id)
-/// signals to the client that the game is ready.
-const READY_BYTE: u8 = 0xFB;
-/// This bytes followed by a serialized event is used to transfer events
-/// to remote clients.
-const EVENT_BYTE: u8 = 0xFC;
 
-/// A tpc `ServerSink` sending events to a remote server.
-pub(crate) struct TcpServerSink<R: BattleRules> {
-    /// A tcp stream to send data.
-    stream: TcpStream,
-    _phantom: std::marker::PhantomData<R>,
-}
-
-impl<R: BattleRules> Drop for TcpServerSink<R> {
-    fn drop(&mut self) {
-        // Send a bye message and shutdown the stream.
-        self.stream.write_all(&[BYE, DELIMITER]).unwrap();
-        let _ = self.stream.shutdown(Shutdown::Both);
-    }
-}
-
-impl<R: BattleRules + 'static> TcpServerSink<R> {
-    pub(crate) fn new(stream: TcpStream) -> Self {
-        Self {
-            stream,
-            _phantom: std::marker::PhantomData,
-        }
-    }
-}
+/// Codec to encode events in json.
+/// We use json for simplicity. There are more network friendly formats.
+struct JsonCodec;
 
-impl<R: BattleRules> EventSink for TcpServerSink<R> {
-    fn id(&self) -> EventSinkId {
-        0
+impl Codec for JsonCodec {
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, String> {
+        serde_json::to_vec(value).map_err(|err| err.to_string())
     }
 
-    fn on_disconnect(&mut self) {
-        println!("Server sink disconnected!");
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, String> {
+        serde_json::from_slice(bytes).map_err(|err| err.to_string())
     }
 }
 
-impl<R: BattleRules + 'static> ServerSink<R> for TcpServerSink<R> {
-    fn send(&mut self, event: &ClientEventPrototype<R>) -> WeaselResult<(), R> {
-        // Serialize the event and send it over tcp.
-        // We use json for simplicity. There are more network friendly formats.
-        let event: FlatClientEvent<R> = event.clone().into();
-        let mut buffer = serde_json::to_vec(&event).unwrap();
-        // Write the delimiter.
-        buffer.push(DELIMITER);
-        self.stream.write_all(&buffer).unwrap();
-        Ok(())
-    }
-}
-
-/// A tcp `ClientSink` sending events to a remote client.
-pub(crate) struct TcpClientSink<R: BattleRules> {
-    /// The id of this sink.
-    id: EventSinkId,
-    /// A tcp stream to send data.
-    stream: TcpStream,
-    _phantom: std::marker::PhantomData<R>,
-}
-
-impl<R: BattleRules + 'static> TcpClientSink<R> {
-    pub(crate) fn new(id: EventSinkId, stream: TcpStream) -> Self {
-        Self {
-            id,
-            stream,
-            _phantom: std::marker::PhantomData,
-        }
-    }
-}
-
-impl<R: BattleRules> EventSink for TcpClientSink<R> {
-    fn id(&self) -> EventSinkId {
-        self.id
-    }
-
-    fn on_disconnect(&mut self) {
-        println!("Client sink {} disconnected!", self.id);
-    }
-}
-
-impl<R: BattleRules + 'static> ClientSink<R> for TcpClientSink<R> {
-    fn send(&mut self, event: &VersionedEventWrapper<R>) -> WeaselResult<(), R> {
-        // Serialize the event and send it over tcp.
-        // We use json for simplicity. There are more network friendly formats.
-        let event: FlatVersionedEvent<R> = event.clone().into();
-        // Write the event byte to signal this's an event.
-        let mut buffer = vec![EVENT_BYTE];
-        buffer.append(&mut serde_json::to_vec(&event).unwrap());
-        // Write the delimiter.
-        buffer.push(DELIMITER);
-        self.stream.write_all(&buffer).unwrap();
-        Ok(())
-    }
+/// Returns true if a read failed only because no data is available yet.
+fn would_block(err: &io::Error) -> bool {
+    err.kind() == io::ErrorKind::WouldBlock || err.kind() == io::ErrorKind::TimedOut
 }
 
 /// A game server working over tcp
@@ -193,12 +108,27 @@ impl TcpServer {
     }
 
     fn handle_client(
-        mut stream: TcpStream,
+        stream: TcpStream,
         game_server: Arc<Mutex<Server<CustomRules>>>,
         running: Arc<Mutex<bool>>,
     ) {
+        println!("A client connected");
+        // The listener is non-blocking, but we want to wait for the client's handshake.
+        stream.set_nonblocking(false).unwrap();
+        let mut reader = TcpReader::new(stream.try_clone().unwrap(), JsonCodec);
+        // Verify that the client plays with the same rules.
+        let handshake = match reader.read_handshake() {
+            Ok(handshake) => handshake,
+            Err(err) => {
+                println!("Invalid handshake: {}", err);
+                return;
+            }
+        };
+        if let Err(err) = game_server.lock().unwrap().verify_handshake(&handshake) {
+            println!("Rejecting client: {}", err);
+            return;
+        }
         let id = {
-            println!("A client connected");
             // Find out the id of the newly connected player.
             let mut server = game_server.lock().unwrap();
             let id = if server.client_sinks().sinks().any(|s| s.id() == 1) {
@@ -206,65 +136,48 @@ impl TcpServer {
             } else {
                 1 as u8
             };
-            // Register a client sink and share the battle history, from the beginning.
-            let sink = Box::new(TcpClientSink::new(id.into(), stream.try_clone().unwrap()));
-            server.client_sinks_mut().add_sink_from(sink, 0).unwrap();
+            // Send the player's id to the client, inside a custom frame.
+            let mut sink = TcpClientSink::new(id.into(), stream, JsonCodec);
+            if sink.send_custom(&[id]).is_err() {
+                println!("An error occurred, terminating connection.");
+                return;
+            }
+            // Register the client sink and share the battle history, from the beginning.
+            server
+                .client_sinks_mut()
+                .add_sink_from(Box::new(sink), 0)
+                .unwrap();
             id
         };
-        // Send the ready signal to the client.
-        if stream.write_all(&[READY_BYTE, id, DELIMITER]).is_err() {
-            println!(
-                "An error occurred, terminating connection with {}",
-                stream.peer_addr().unwrap()
-            );
-            stream.shutdown(Shutdown::Both).unwrap();
-            game_server
-                .lock()
-                .unwrap()
-                .client_sinks_mut()
-                .remove_sink(id.into());
-            return;
-        }
-        // Listen for the client's events.
-        stream
-            .set_nonblocking(true)
-            .expect("Cannot set non-blocking");
-        let mut buffer = Vec::new();
-        let mut reader = BufReader::new(stream);
+        // Listen for the client's events. Wake up periodically to check if we are closing.
+        reader
+            .stream()
+            .set_read_timeout(Some(time::Duration::from_millis(10)))
+            .unwrap();
         // Keep the connection until we get an error or we are closing the server.
         loop {
-            match reader.read_until(DELIMITER, &mut buffer) {
-                Ok(size) => {
-                    if size == 0 {
+            match reader.read_frame() {
+                Ok(frame) => match frame.kind() {
+                    FrameKind::Event => {
+                        // Process the event.
+                        let event = reader.decode_client_event(&frame).unwrap();
+                        game_server.lock().unwrap().process_client(event).unwrap();
+                    }
+                    FrameKind::Bye => {
                         println!("A client disconnected");
                         break;
                     }
-                    if size > 1 {
-                        if size == 2 && buffer[0] == BYE {
-                            println!("A client disconnected");
-                            break;
-                        }
-                        // Process the event.
-                        let event: FlatClientEvent<CustomRules> =
-                            serde_json::from_slice(&buffer[..size - 1]).unwrap();
-                        game_server
-                            .lock()
-                            .unwrap()
-                            .process_client(event.into())
-                            .unwrap();
-                    }
-                }
-                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                    _ => {}
+                },
+                Err(e) if would_block(&e) => {}
                 Err(_) => {
-                    println!("An error occurred, terminating connection.");
+                    println!("A client disconnected");
                     break;
                 }
             }
-            buffer.clear();
             if !*running.lock().unwrap() {
                 break;
             }
-            thread::sleep(time::Duration::from_millis(10));
         }
         game_server
             .lock()
@@ -292,86 +205,61 @@ impl Drop for TcpClient {
 
 impl TcpClient {
     pub(crate) fn new(server_address: &str) -> Self {
-        // Open a connection to the server.
-        let stream = TcpStream::connect(server_address).unwrap();
-        stream
-            .set_nonblocking(true)
-            .expect("Cannot set non-blocking");
-        println!("Connected to the server!");
         // Create a battle object with our game rules.
         let battle = Battle::builder(CustomRules::new()).build();
-        // Create a server sink to send event over tcp.
-        let sink = Box::new(TcpServerSink::new(stream.try_clone().unwrap()));
-        let game_client = Arc::new(Mutex::new(Client::builder(battle, sink).build()));
-        // Read everything the server has to send to us until the READY_BYTE.
+        // Open a connection to the server, sending our handshake.
+        let sink = TcpServerSink::connect(server_address, battle.handshake(), JsonCodec).unwrap();
+        println!("Connected to the server!");
+        let stream = sink.stream().unwrap().try_clone().unwrap();
+        let mut reader = TcpReader::new(stream, JsonCodec);
+        let game_client = Arc::new(Mutex::new(Client::builder(battle, Box::new(sink)).build()));
+        // Read everything the server has to send to us until we get our id.
         println!("Waiting for the game to start...");
         let game_client_clone = game_client.clone();
-        let mut buffer = Vec::new();
-        let mut reader = BufReader::new(stream);
-        let id;
-        loop {
-            match reader.read_until(DELIMITER, &mut buffer) {
-                Ok(size) => {
-                    if size == 0 {
-                        panic!("Disconnected from the server during initialization!");
-                    }
-                    // Wait for the ready byte.
-                    if size == 3 && buffer[0] == READY_BYTE {
-                        id = Some(buffer[1] as u8);
-                        break;
-                    } else if size > 1 && buffer[0] == EVENT_BYTE {
-                        let event: FlatVersionedEvent<CustomRules> =
-                            serde_json::from_slice(&buffer[1..size - 1]).unwrap();
-                        game_client_clone
-                            .lock()
-                            .unwrap()
-                            .receive(event.into())
-                            .unwrap();
-                    }
-                }
-                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
-                Err(_) => {
-                    panic!("An error occurred, terminating connection.");
+        let id = loop {
+            let frame = reader
+                .read_frame()
+                .expect("Disconnected from the server during initialization!");
+            match frame.kind() {
+                FrameKind::Custom => break frame.payload()[0],
+                FrameKind::Event => {
+                    let event = reader.decode_event(&frame).unwrap();
+                    game_client_clone.lock().unwrap().receive(event).unwrap();
                 }
+                _ => panic!("Disconnected from the server during initialization!"),
             }
-            buffer.clear();
-            thread::sleep(time::Duration::from_millis(10));
-        }
-        let id = id.unwrap();
+        };
         println!("You are player {}", id + 1);
+        // Wake up periodically to check if we are closing.
+        reader
+            .stream()
+            .set_read_timeout(Some(time::Duration::from_millis(10)))
+            .unwrap();
         let running = Arc::new(Mutex::new(true));
         let running_clone = running.clone();
         // Keep the tcp channel open in another thread.
-        let thread = thread::spawn(move || {
-            loop {
-                // Read events coming from the server.
-                match reader.read_until(DELIMITER, &mut buffer) {
-                    Ok(size) => {
-                        if size == 0 {
-                            println!("Disconnected from the server.");
-                            break;
-                        }
-                        if size > 1 && buffer[0] == EVENT_BYTE {
-                            let event: FlatVersionedEvent<CustomRules> =
-                                serde_json::from_slice(&buffer[1..size - 1]).unwrap();
-                            game_client_clone
-                                .lock()
-                                .unwrap()
-                                .receive(event.into())
-                                .unwrap();
-                        }
+        let thread = thread::spawn(move || loop {
+            // Read events coming from the server.
+            match reader.read_frame() {
+                Ok(frame) => match frame.kind() {
+                    FrameKind::Event => {
+                        let event = reader.decode_event(&frame).unwrap();
+                        game_client_clone.lock().unwrap().receive(event).unwrap();
                     }
-                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
-                    Err(_) => {
-                        println!("An error occurred, terminating connection.");
+                    FrameKind::Bye => {
+                        println!("Disconnected from the server.");
                         break;
                     }
-                }
-                buffer.clear();
-                if !*running_clone.lock().unwrap() {
+                    _ => {}
+                },
+                Err(e) if would_block(&e) => {}
+                Err(_) => {
+                    println!("An error occurred, terminating connection.");
                     break;
                 }
-                thread::sleep(time::Duration::from_millis(10));
+            }
+            if !*running_clone.lock().unwrap() {
+                break;
             }
         });
         Self {
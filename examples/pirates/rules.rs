@@ -30,6 +30,7 @@ impl TeamRules<PiratesRules> for PiratesTeamRules {
     // goal of sinking the enemy ship was achieved.
     type ObjectivesSeed = Self::Id;
     type Objectives = Self::ObjectivesSeed;
+    type TeamData = ();
 
     // Generate the objectives for a team. We said the seed and the objective are both
     // the enemy team id.
@@ -48,14 +49,7 @@ impl TeamRules<PiratesRules> for PiratesTeamRules {
         // Get the objective of the team. Which is equal to its enemy id.
         let enemy_id = team.objectives();
         // Now check if the enemy has any creatures left.
-        if state
-            .entities()
-            .team(&enemy_id)
-            .unwrap()
-            .creatures()
-            .count()
-            == 0
-        {
+        if state.entities().team(enemy_id).unwrap().creatures().count() == 0 {
             // We won.
             Some(Conclusion::Victory)
         } else {
@@ -20,6 +20,7 @@ impl SpaceRules<CustomRules> for CustomSpaceRules {
     // The seed to initialize the battlefield.
     type SpaceSeed = BattlefieldSeed;
     // Our space model.
+    type Area = ();
     type SpaceModel = Battlefield;
     // A vector containing the position of new traps.
     type SpaceAlteration = Vec<Square>;
@@ -116,6 +117,7 @@ pub(crate) enum BattlefieldSeed {
 
 /// A struct containing an optional entity id and whether or not the square has a trap on it.
 #[derive(Default, Clone, Copy)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub(crate) struct BattlefieldCell {
     entity: Option<EntityId<CustomRules>>,
     trap: bool,
@@ -123,6 +125,7 @@ pub(crate) struct BattlefieldCell {
 
 /// The space model for this game.
 #[allow(clippy::large_enum_variant)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub(crate) enum Battlefield {
     // Battlefield is empty a the start of the game.
     Empty,
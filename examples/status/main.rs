@@ -1,6 +1,7 @@
 use crate::rules::*;
 use weasel::creature::CreatureId;
 use weasel::object::ObjectId;
+use weasel::rules::status::SimplePotency;
 use weasel::team::TeamId;
 use weasel::{
     Battle, BattleController, ClearStatus, CreateCreature, CreateObject, CreateTeam, EndTurn,
@@ -37,13 +38,13 @@ fn main() {
     // Inflict a power-up status effect on the creature, with no time limit.
     println!("Inflicting a power-up on the creature...");
     InflictStatus::trigger(&mut server, ENTITY_1_ID, VIGOR)
-        .potency((50, None))
+        .potency(SimplePotency::permanent(50))
         .fire()
         .unwrap();
     // Inflict a DoT status effect on the object, for two turns.
     println!("Inflicting a DoT on the object...");
     InflictStatus::trigger(&mut server, ENTITY_2_ID, DOT)
-        .potency((10, Some(2)))
+        .potency(SimplePotency::lasting(10, 2))
         .fire()
         .unwrap();
     // Display the entities' state.
@@ -115,9 +116,7 @@ fn print_dot_effects(server: &Server<CustomRules>) {
         .unwrap();
     println!("{:?}", inflict_event.event());
     // Get all events with inflict_event as origin.
-    for event in events {
-        if event.origin() == Some(inflict_event.id()) {
-            println!("+-- {:?}", event.event());
-        }
+    for event in server.battle().history().derived_events(inflict_event.id()) {
+        println!("+-- {:?}", event.event());
     }
 }
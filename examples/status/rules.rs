@@ -1,5 +1,6 @@
-use weasel::rules::{statistic::SimpleStatistic, status::SimpleStatus};
-use weasel::status::{Application, AppliedStatus, Potency, Status, StatusDuration, StatusId};
+use weasel::rules::statistic::SimpleStatistic;
+use weasel::rules::status::{SimplePotency, SimpleStatus};
+use weasel::status::{Application, AppliedStatus, Potency, Status, StatusId};
 use weasel::{
     battle_rules, rules::empty::*, AlterStatistics, BattleRules, BattleState, Character,
     CharacterRules, Entropy, EventQueue, EventTrigger, FightRules, Id, LinkedQueue, Transmutation,
@@ -87,11 +88,9 @@ impl CharacterRules<CustomRules> for CustomCharacterRules {
     ) -> Option<Status<CustomRules>> {
         // We expect to always have a valid potency.
         let potency = potency.unwrap();
-        let effect = potency.0;
-        let duration = potency.1;
         // Return a new status in any case. If it already exists on the character,
         // the old one is replaced (anyway it doesn't happen in this example).
-        Some(SimpleStatus::new(*status_id, effect, duration))
+        Some(SimpleStatus::from_potency(*status_id, potency))
     }
 }
 
@@ -103,7 +102,7 @@ impl FightRules<CustomRules> for CustomFightRules {
     // We don't use impacts.
     type Impact = ();
     // Potency will tell how strong a status is and how long will it lasts.
-    type Potency = (i8, Option<StatusDuration>);
+    type Potency = SimplePotency<i8>;
 
     fn apply_status(
         &self,
@@ -140,11 +139,7 @@ impl FightRules<CustomRules> for CustomFightRules {
             AlterStatistics::trigger(linked_queue, *character.entity_id(), -status.effect()).fire();
         }
         // Terminate the status if its duration expired.
-        if let Some(max_duration) = status.max_duration() {
-            status.duration() == max_duration
-        } else {
-            false
-        }
+        status.is_expired(status.duration())
     }
 
     fn delete_status(
@@ -44,6 +44,7 @@ impl SpaceRules<CustomRules> for CustomSpaceRules {
     // We always initialize the space in the same way, so no seed.
     type SpaceSeed = ();
     // Our space model.
+    type Area = ();
     type SpaceModel = Battlefield;
     // In this example we don't alter the space.
     type SpaceAlteration = ();
@@ -134,6 +135,7 @@ impl Display for Square {
 }
 
 /// The space model for this game.
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub struct Battlefield {
     // A simple 2D battlefield. We only store if the square is occupied or not.
     squares: [[bool; BATTLEFIELD_LENGTH]; BATTLEFIELD_LENGTH],
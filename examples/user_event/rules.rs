@@ -2,12 +2,15 @@ use serde::{Deserialize, Serialize};
 use std::any::Any;
 use weasel::{
     battle_rules, battle_rules_with_user, rules::empty::*, Battle, BattleRules, Event, EventKind,
-    EventProcessor, EventQueue, EventTrigger, UserEventPacker, UserRules, WeaselError,
-    WeaselResult,
+    EventProcessor, EventQueue, EventTrigger, UserEventId, UserEventPacker, UserEventRegistry,
+    UserRules, WeaselError, WeaselResult,
 };
 
 pub(crate) const PIZZAS_CREATED_METRIC: &str = "pizzas_created";
 
+/// Id of the `MakePizza` user event.
+pub(crate) const MAKE_PIZZA_ID: UserEventId = 0;
+
 // It's not a real game so we can use generic no-op battle rules.
 // We still want to override the UserRules to define how to serialize our custom event and to
 // add custom metrics.
@@ -22,6 +25,14 @@ impl UserRules<CustomRules> for CustomUserRules {
     type UserMetricId = String;
     // The type we will use to serialize and deserialize all user events.
     type UserEventPackage = EventPackage;
+
+    // Register all user events, so that two events can't accidentally share the same id.
+    fn register_user_events(
+        &self,
+        registry: &mut UserEventRegistry<CustomRules>,
+    ) -> WeaselResult<(), CustomRules> {
+        registry.register(MAKE_PIZZA_ID, "make_pizza")
+    }
 }
 
 /// An user defined event.
@@ -63,8 +74,8 @@ impl Event<CustomRules> for MakePizza {
     }
 
     fn kind(&self) -> EventKind {
-        // This user event has id 0. If you add a second user event, it should have another id.
-        EventKind::UserEvent(0)
+        // If you add a second user event, it should have another id.
+        EventKind::UserEvent(MAKE_PIZZA_ID)
     }
 
     fn box_clone(&self) -> Box<dyn Event<CustomRules> + Send> {
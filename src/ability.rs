@@ -1,10 +1,12 @@
 //! Module to manage abilities.
 
-use crate::actor::{Action, ActorRules};
+use crate::actor::{Action, ActivationRecord, ActorRules};
 use crate::battle::{Battle, BattleRules};
 use crate::entity::EntityId;
 use crate::error::{WeaselError, WeaselResult};
 use crate::event::{Event, EventKind, EventProcessor, EventQueue, EventRights, EventTrigger};
+use crate::fight::FightRules;
+use crate::round::TurnsCount;
 use crate::util::Id;
 #[cfg(feature = "serialization")]
 use serde::{Deserialize, Serialize};
@@ -33,6 +35,11 @@ pub type AbilitiesAlteration<R> = <<R as BattleRules>::AR as ActorRules<R>>::Abi
 
 /// Event to make an actor activate an ability.
 ///
+/// The activation can be delayed by a number of turns, to model abilities with a cast time.
+/// In such case the activation is stored as a `PendingActivation` and it is resolved
+/// automatically at the start of the actor's turn in which the delay expires.
+/// A pending activation can be canceled with an `InterruptAbility` event.
+///
 /// # Examples
 /// ```
 /// use weasel::{
@@ -92,6 +99,9 @@ pub struct ActivateAbility<R: BattleRules> {
         ))
     )]
     activation: Option<Activation<R>>,
+
+    #[cfg_attr(feature = "serialization", serde(default))]
+    delay: TurnsCount,
 }
 
 impl<R: BattleRules> ActivateAbility<R> {
@@ -106,6 +116,7 @@ impl<R: BattleRules> ActivateAbility<R> {
             entity_id,
             ability_id,
             activation: None,
+            delay: 0,
         }
     }
 
@@ -123,14 +134,20 @@ impl<R: BattleRules> ActivateAbility<R> {
     pub fn activation(&self) -> &Option<Activation<R>> {
         &self.activation
     }
+
+    /// Returns the number of the actor's turns after which the ability will be activated.
+    /// Zero means that the ability is activated immediately.
+    pub fn delay(&self) -> TurnsCount {
+        self.delay
+    }
 }
 
 impl<R: BattleRules> std::fmt::Debug for ActivateAbility<R> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "ActivateAbility {{ entity_id: {:?}, ability_id: {:?}, activation: {:?} }}",
-            self.entity_id, self.ability_id, self.activation
+            "ActivateAbility {{ entity_id: {:?}, ability_id: {:?}, activation: {:?}, delay: {:?} }}",
+            self.entity_id, self.ability_id, self.activation, self.delay
         )
     }
 }
@@ -141,6 +158,7 @@ impl<R: BattleRules> Clone for ActivateAbility<R> {
             entity_id: self.entity_id.clone(),
             ability_id: self.ability_id.clone(),
             activation: self.activation.clone(),
+            delay: self.delay,
         }
     }
 }
@@ -157,20 +175,71 @@ impl<R: BattleRules + 'static> Event<R> for ActivateAbility<R> {
             if !battle.state.rounds.is_acting(&self.entity_id) {
                 return Err(WeaselError::ActorNotReady(self.entity_id.clone()));
             }
+            // Verify that the actor isn't already preparing another ability.
+            if self.delay > 0
+                && battle
+                    .state
+                    .rounds
+                    .pending_activation(&self.entity_id)
+                    .is_some()
+            {
+                return Err(WeaselError::ActivationPending(self.entity_id.clone()));
+            }
+            // Verify that the actor didn't exhaust its activations for this turn.
+            if let Some(max) = battle
+                .rules
+                .actor_rules()
+                .max_activations(&battle.state, actor)
+            {
+                if battle.state.rounds.activations(&self.entity_id) >= max {
+                    return Err(WeaselError::ActivationsExhausted(self.entity_id.clone()));
+                }
+            }
             // Verify if the creature knowns this ability.
             if let Some(ability) = actor.ability(&self.ability_id) {
-                // Verify if this ability can be activated.
-                battle
-                    .rules
-                    .actor_rules()
-                    .activable(&battle.state, Action::new(actor, ability, &self.activation))
-                    .map_err(|err| {
-                        WeaselError::AbilityNotActivable(
+                // Verify that no status is blocking this ability.
+                if let Some(status) = actor.statuses().find(|status| {
+                    battle
+                        .rules
+                        .fight_rules()
+                        .ability_blocked_by(status, ability)
+                }) {
+                    return Err(WeaselError::AbilityBlocked(
+                        self.entity_id.clone(),
+                        self.ability_id.clone(),
+                        status.id().clone(),
+                    ));
+                }
+                // Verify that the ability is not recharging.
+                if let Some(tracked) = actor.tracked_ability(&self.ability_id) {
+                    let turns = battle
+                        .rules
+                        .actor_rules()
+                        .recharge(&battle.state, actor, tracked);
+                    if turns > 0 {
+                        return Err(WeaselError::AbilityOnCooldown(
                             self.entity_id.clone(),
                             self.ability_id.clone(),
-                            Box::new(err),
-                        )
-                    })
+                            turns,
+                        ));
+                    }
+                }
+                // Verify if this ability can be activated.
+                watch!(
+                    battle.verify_watcher(self.kind()),
+                    "ActorRules::activable",
+                    battle
+                        .rules
+                        .actor_rules()
+                        .activable(&battle.state, Action::new(actor, ability, &self.activation))
+                )
+                .map_err(|err| {
+                    WeaselError::AbilityNotActivable(
+                        self.entity_id.clone(),
+                        self.ability_id.clone(),
+                        Box::new(err),
+                    )
+                })
             } else {
                 Err(WeaselError::AbilityNotKnown(
                     self.entity_id.clone(),
@@ -183,6 +252,48 @@ impl<R: BattleRules + 'static> Event<R> for ActivateAbility<R> {
     }
 
     fn apply(&self, battle: &mut Battle<R>, event_queue: &mut Option<EventQueue<R>>) {
+        battle.state.rounds.increase_activations(&self.entity_id);
+        // Remember when the ability was used, to compute its cooldown.
+        if let EntityId::Creature(id) = &self.entity_id {
+            let turn = battle.state.rounds.completed_turns();
+            battle
+                .state
+                .entities
+                .creature_mut(id)
+                .unwrap_or_else(|| panic!("constraint violated: creature {:?} not found", id))
+                .mark_ability_used(&self.ability_id, turn);
+        }
+        // Remember the activation in the actor.
+        let capacity = battle.rules.actor_rules().max_recent_activations();
+        if capacity > 0 {
+            if let EntityId::Creature(id) = &self.entity_id {
+                // The id of this event will be equal to history's next_id().
+                let record = ActivationRecord::new(
+                    self.ability_id.clone(),
+                    battle.history.next_id(),
+                    battle.state.rounds.completed_turns(),
+                );
+                battle
+                    .state
+                    .entities
+                    .creature_mut(id)
+                    .unwrap_or_else(|| panic!("constraint violated: creature {:?} not found", id))
+                    .record_activation(record, capacity);
+            }
+        }
+        if self.delay > 0 {
+            // Postpone the activation.
+            battle
+                .state
+                .rounds
+                .add_pending_activation(PendingActivation {
+                    entity_id: self.entity_id.clone(),
+                    ability_id: self.ability_id.clone(),
+                    activation: self.activation.clone(),
+                    turns: self.delay,
+                });
+            return;
+        }
         let actor = battle
             .state
             .entities
@@ -196,12 +307,16 @@ impl<R: BattleRules + 'static> Event<R> for ActivateAbility<R> {
                 self.ability_id, self.entity_id
             )
         });
-        battle.rules.actor_rules().activate(
-            &battle.state,
-            Action::new(actor, ability, &self.activation),
-            event_queue,
-            &mut battle.entropy,
-            &mut battle.metrics.write_handle(),
+        watch!(
+            battle.apply_watcher(),
+            "ActorRules::activate",
+            battle.rules.actor_rules().activate(
+                &battle.state,
+                Action::new(actor, ability, &self.activation),
+                event_queue,
+                &mut battle.entropy,
+                &mut battle.metrics.write_handle(),
+            )
         );
     }
 
@@ -217,15 +332,8 @@ impl<R: BattleRules + 'static> Event<R> for ActivateAbility<R> {
         self
     }
 
-    fn rights<'a>(&'a self, battle: &'a Battle<R>) -> EventRights<'a, R> {
-        let actor = battle
-            .state
-            .entities
-            .actor(&self.entity_id)
-            .unwrap_or_else(|| {
-                panic!("constraint violated: entity {:?} not found", self.entity_id)
-            });
-        EventRights::Team(actor.team_id())
+    fn rights<'a>(&'a self, _battle: &'a Battle<R>) -> EventRights<'a, R> {
+        EventRights::Entity(&self.entity_id)
     }
 }
 
@@ -239,6 +347,7 @@ where
     entity_id: EntityId<R>,
     ability_id: AbilityId<R>,
     activation: Option<Activation<R>>,
+    delay: TurnsCount,
 }
 
 impl<'a, R, P> ActivateAbilityTrigger<'a, R, P>
@@ -251,6 +360,12 @@ where
         self.activation = Some(activation);
         self
     }
+
+    /// Delays the activation by the given number of the actor's turns.
+    pub fn delay(&'a mut self, turns: TurnsCount) -> &'a mut Self {
+        self.delay = turns;
+        self
+    }
 }
 
 impl<'a, R, P> EventTrigger<'a, R, P> for ActivateAbilityTrigger<'a, R, P>
@@ -268,6 +383,284 @@ where
             entity_id: self.entity_id.clone(),
             ability_id: self.ability_id.clone(),
             activation: self.activation.clone(),
+            delay: self.delay,
+        })
+    }
+}
+
+/// An ability activation waiting for its delay to expire.
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct PendingActivation<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "EntityId<R>: Serialize",
+            deserialize = "EntityId<R>: Deserialize<'de>"
+        ))
+    )]
+    entity_id: EntityId<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "AbilityId<R>: Serialize",
+            deserialize = "AbilityId<R>: Deserialize<'de>"
+        ))
+    )]
+    ability_id: AbilityId<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Option<Activation<R>>: Serialize",
+            deserialize = "Option<Activation<R>>: Deserialize<'de>"
+        ))
+    )]
+    activation: Option<Activation<R>>,
+
+    turns: TurnsCount,
+}
+
+impl<R: BattleRules> PendingActivation<R> {
+    /// Returns the id of the actor who is activating the ability.
+    pub fn entity_id(&self) -> &EntityId<R> {
+        &self.entity_id
+    }
+
+    /// Returns the id of the ability to be activated.
+    pub fn ability_id(&self) -> &AbilityId<R> {
+        &self.ability_id
+    }
+
+    /// Returns the activation profile for the ability.
+    pub fn activation(&self) -> &Option<Activation<R>> {
+        &self.activation
+    }
+
+    /// Returns the number of the actor's turns left before the ability is activated.
+    pub fn turns(&self) -> TurnsCount {
+        self.turns
+    }
+
+    /// Changes the id of the actor who is activating the ability.
+    pub(crate) fn set_entity_id(&mut self, entity_id: EntityId<R>) {
+        self.entity_id = entity_id;
+    }
+}
+
+impl<R: BattleRules> std::fmt::Debug for PendingActivation<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "PendingActivation {{ entity_id: {:?}, ability_id: {:?}, activation: {:?}, turns: {:?} }}",
+            self.entity_id, self.ability_id, self.activation, self.turns
+        )
+    }
+}
+
+impl<R: BattleRules> Clone for PendingActivation<R> {
+    fn clone(&self) -> Self {
+        PendingActivation {
+            entity_id: self.entity_id.clone(),
+            ability_id: self.ability_id.clone(),
+            activation: self.activation.clone(),
+            turns: self.turns,
+        }
+    }
+}
+
+/// Advances the pending ability activation of an actor by one turn.
+/// The ability is activated once its delay expires.
+pub(crate) fn update_pending_activation<R: BattleRules + 'static>(
+    entity_id: &EntityId<R>,
+    battle: &mut Battle<R>,
+    event_queue: &mut Option<EventQueue<R>>,
+) {
+    let expired = if let Some(pending) = battle.state.rounds.pending_activation_mut(entity_id) {
+        pending.turns = pending.turns.saturating_sub(1);
+        pending.turns == 0
+    } else {
+        false
+    };
+    if !expired {
+        return;
+    }
+    let pending = battle
+        .state
+        .rounds
+        .remove_pending_activation(entity_id)
+        .unwrap_or_else(|| {
+            panic!(
+                "constraint violated: no pending activation for {:?}",
+                entity_id
+            )
+        });
+    let actor = battle
+        .state
+        .entities
+        .actor(entity_id)
+        .unwrap_or_else(|| panic!("constraint violated: entity {:?} not found", entity_id));
+    // The actor might have lost the ability in the meantime.
+    if let Some(ability) = actor.ability(&pending.ability_id) {
+        watch!(
+            battle.apply_watcher(),
+            "ActorRules::activate",
+            battle.rules.actor_rules().activate(
+                &battle.state,
+                Action::new(actor, ability, &pending.activation),
+                event_queue,
+                &mut battle.entropy,
+                &mut battle.metrics.write_handle(),
+            )
+        );
+    }
+}
+
+/// Event to cancel the pending ability activation of an actor.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     ability::InterruptAbility, battle_rules, rules::empty::*, Battle, BattleRules,
+///     CreateCreature, CreateTeam, EntityId, EventTrigger, Server, WeaselError,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// let team_id = 1;
+/// CreateTeam::trigger(&mut server, team_id).fire().unwrap();
+/// let creature_id = 1;
+/// let position = ();
+/// CreateCreature::trigger(&mut server, creature_id, team_id, position)
+///     .fire()
+///     .unwrap();
+///
+/// let entity_id = EntityId::Creature(creature_id);
+/// let result = InterruptAbility::trigger(&mut server, entity_id).fire();
+/// // We get an error because the creature isn't preparing any ability.
+/// assert_eq!(
+///     result.err().map(|e| e.unfold()),
+///     Some(WeaselError::NoActivationPending(entity_id))
+/// );
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct InterruptAbility<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "EntityId<R>: Serialize",
+            deserialize = "EntityId<R>: Deserialize<'de>"
+        ))
+    )]
+    entity_id: EntityId<R>,
+}
+
+impl<R: BattleRules> InterruptAbility<R> {
+    /// Returns a trigger for this event.
+    pub fn trigger<P: EventProcessor<R>>(
+        processor: &mut P,
+        entity_id: EntityId<R>,
+    ) -> InterruptAbilityTrigger<R, P> {
+        InterruptAbilityTrigger {
+            processor,
+            entity_id,
+        }
+    }
+
+    /// Returns the id of the actor whose ability activation will be interrupted.
+    pub fn entity_id(&self) -> &EntityId<R> {
+        &self.entity_id
+    }
+}
+
+impl<R: BattleRules> std::fmt::Debug for InterruptAbility<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "InterruptAbility {{ entity_id: {:?} }}", self.entity_id)
+    }
+}
+
+impl<R: BattleRules> Clone for InterruptAbility<R> {
+    fn clone(&self) -> Self {
+        InterruptAbility {
+            entity_id: self.entity_id.clone(),
+        }
+    }
+}
+
+impl<R: BattleRules + 'static> Event<R> for InterruptAbility<R> {
+    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
+        // Check if this entity is an actor.
+        if !self.entity_id.is_actor() {
+            return Err(WeaselError::NotAnActor(self.entity_id.clone()));
+        }
+        // Verify that the actor exists.
+        if battle.entities().actor(&self.entity_id).is_none() {
+            return Err(WeaselError::EntityNotFound(self.entity_id.clone()));
+        }
+        // Verify that the actor is preparing an ability.
+        if battle
+            .state
+            .rounds
+            .pending_activation(&self.entity_id)
+            .is_none()
+        {
+            return Err(WeaselError::NoActivationPending(self.entity_id.clone()));
+        }
+        Ok(())
+    }
+
+    fn apply(&self, battle: &mut Battle<R>, _: &mut Option<EventQueue<R>>) {
+        battle
+            .state
+            .rounds
+            .remove_pending_activation(&self.entity_id)
+            .unwrap_or_else(|| {
+                panic!(
+                    "constraint violated: no pending activation for {:?}",
+                    self.entity_id
+                )
+            });
+    }
+
+    fn kind(&self) -> EventKind {
+        EventKind::InterruptAbility
+    }
+
+    fn box_clone(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Trigger to build and fire an `InterruptAbility` event.
+pub struct InterruptAbilityTrigger<'a, R, P>
+where
+    R: BattleRules,
+    P: EventProcessor<R>,
+{
+    processor: &'a mut P,
+    entity_id: EntityId<R>,
+}
+
+impl<'a, R, P> EventTrigger<'a, R, P> for InterruptAbilityTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    fn processor(&'a mut self) -> &'a mut P {
+        self.processor
+    }
+
+    /// Returns an `InterruptAbility` event.
+    fn event(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(InterruptAbility {
+            entity_id: self.entity_id.clone(),
         })
     }
 }
@@ -6,14 +6,18 @@ use crate::character::Character;
 use crate::entity::{Entities, EntityId};
 use crate::entropy::Entropy;
 use crate::error::{WeaselError, WeaselResult};
-use crate::event::{Event, EventKind, EventProcessor, EventQueue, EventTrigger};
+use crate::event::{
+    Event, EventId, EventKind, EventProcessor, EventPrototype, EventQueue, EventTrigger,
+};
 use crate::metric::WriteMetrics;
+use crate::round::TurnsCount;
 use crate::team::TeamId;
-use crate::util::Id;
+use crate::util::{Id, RegenerationPolicy};
 #[cfg(feature = "serialization")]
 use serde::{Deserialize, Serialize};
 use std::any::Any;
 use std::fmt::{Debug, Formatter, Result};
+use std::hash::{Hash, Hasher};
 
 /// A trait for objects which possess abilities and can act during a turn.
 pub trait Actor<R: BattleRules>: Character<R> {
@@ -37,15 +41,163 @@ pub trait Actor<R: BattleRules>: Character<R> {
     /// Returns the removed ability, if present.
     fn remove_ability(&mut self, id: &AbilityId<R>) -> Option<Ability<R>>;
 
+    /// Returns the ability with the given id, together with its usage information.
+    fn tracked_ability(&self, id: &AbilityId<R>) -> Option<&TrackedAbility<R>>;
+
     /// Returns the id of the team to which this actor belongs.
     fn team_id(&self) -> &TeamId<R>;
+
+    /// Returns an iterator over the most recent activations of this actor,
+    /// from the oldest to the newest.
+    ///
+    /// The number of remembered activations is decided by
+    /// [max_recent_activations](trait.ActorRules.html#method.max_recent_activations).
+    fn recent_activations<'a>(&'a self) -> Box<dyn Iterator<Item = &'a ActivationRecord<R>> + 'a>;
+}
+
+/// Stores an `Ability` and additional information about its usage.
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct TrackedAbility<R: BattleRules> {
+    /// The ability.
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Ability<R>: Serialize",
+            deserialize = "Ability<R>: Deserialize<'de>"
+        ))
+    )]
+    ability: Ability<R>,
+    /// Number of turns completed when the ability was last activated.
+    last_used_turn: Option<TurnsCount>,
+}
+
+impl<R: BattleRules> TrackedAbility<R> {
+    /// Creates a new `TrackedAbility` that was never used.
+    pub fn new(ability: Ability<R>) -> Self {
+        Self {
+            ability,
+            last_used_turn: None,
+        }
+    }
+
+    /// Returns a reference to the ability.
+    pub fn ability(&self) -> &Ability<R> {
+        &self.ability
+    }
+
+    /// Returns a mutable reference to the ability.
+    pub fn ability_mut(&mut self) -> &mut Ability<R> {
+        &mut self.ability
+    }
+
+    /// Consumes this object, returning the ability.
+    pub fn into_ability(self) -> Ability<R> {
+        self.ability
+    }
+
+    /// Returns the number of turns completed when the ability was last activated,
+    /// or `None` if the ability is fully recharged.
+    pub fn last_used_turn(&self) -> Option<TurnsCount> {
+        self.last_used_turn
+    }
+
+    /// Remembers that the ability was activated after `turn` completed turns.
+    pub(crate) fn set_last_used_turn(&mut self, turn: TurnsCount) {
+        self.last_used_turn = Some(turn);
+    }
+
+    /// Forgets the last usage of the ability.
+    pub(crate) fn recharge(&mut self) {
+        self.last_used_turn = None;
+    }
+}
+
+impl<R: BattleRules> std::ops::Deref for TrackedAbility<R> {
+    type Target = Ability<R>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.ability
+    }
+}
+
+impl<R: BattleRules> std::ops::DerefMut for TrackedAbility<R> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.ability
+    }
+}
+
+/// A past activation of an ability, remembered by the actor who activated it.
+///
+/// Delayed activations are recorded when they are declared.
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct ActivationRecord<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "AbilityId<R>: Serialize",
+            deserialize = "AbilityId<R>: Deserialize<'de>"
+        ))
+    )]
+    ability_id: AbilityId<R>,
+
+    event_id: EventId,
+    turn: TurnsCount,
+}
+
+impl<R: BattleRules> ActivationRecord<R> {
+    pub(crate) fn new(ability_id: AbilityId<R>, event_id: EventId, turn: TurnsCount) -> Self {
+        Self {
+            ability_id,
+            event_id,
+            turn,
+        }
+    }
+
+    /// Returns the id of the activated ability.
+    pub fn ability_id(&self) -> &AbilityId<R> {
+        &self.ability_id
+    }
+
+    /// Returns the id of the `ActivateAbility` event.
+    pub fn event_id(&self) -> EventId {
+        self.event_id
+    }
+
+    /// Returns the number of turns completed when the ability was activated.
+    pub fn turn(&self) -> TurnsCount {
+        self.turn
+    }
+}
+
+impl<R: BattleRules> Debug for ActivationRecord<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "ActivationRecord {{ ability_id: {:?}, event_id: {:?}, turn: {:?} }}",
+            self.ability_id, self.event_id, self.turn
+        )
+    }
+}
+
+impl<R: BattleRules> Clone for ActivationRecord<R> {
+    fn clone(&self) -> Self {
+        Self {
+            ability_id: self.ability_id.clone(),
+            event_id: self.event_id,
+            turn: self.turn,
+        }
+    }
 }
 
 /// Set of rules that handle how abilities are represented and how they can alter
 /// the state of the world when activated.
 pub trait ActorRules<R: BattleRules> {
+    #[cfg(not(feature = "serialization"))]
     /// See [Ability](../ability/type.Ability.html).
     type Ability: Id + 'static;
+    #[cfg(feature = "serialization")]
+    /// See [Ability](../ability/type.Ability.html).
+    type Ability: Id + Serialize + for<'a> Deserialize<'a> + 'static;
 
     #[cfg(not(feature = "serialization"))]
     /// See [AbilitiesSeed](../ability/type.AbilitiesSeed.html).
@@ -81,6 +233,20 @@ pub trait ActorRules<R: BattleRules> {
         Box::new(std::iter::empty())
     }
 
+    /// Adapts the `current` ability of an actor to its `new` regenerated version.\
+    /// This method is invoked only when abilities are regenerated with
+    /// `RegenerationPolicy::ClampToNew`.
+    ///
+    /// The provided implementation leaves `current` untouched.
+    fn clamp_ability(&self, _current: &mut Self::Ability, _new: &Self::Ability) {}
+
+    /// Writes into `hasher` the data of `ability` to be included in `Battle::state_digest`.
+    ///
+    /// The provided implementation writes only the ability's id.
+    fn digest_ability(&self, ability: &Self::Ability, mut hasher: &mut dyn Hasher) {
+        ability.id().hash(&mut hasher);
+    }
+
     /// Returns `Ok` if `action.actor` can activate `action.ability` with `action.activation`,
     /// otherwise returns an error describing the issue preventing the activation.\
     /// The ability is guaranteed to be known by the actor.
@@ -90,6 +256,24 @@ pub trait ActorRules<R: BattleRules> {
         Ok(())
     }
 
+    /// Returns the maximum number of abilities `actor` can activate in a single turn,
+    /// or `None` if there's no limit.\
+    /// Delayed activations count towards the limit in the turn they are declared.
+    ///
+    /// The provided implementation sets no limit.
+    fn max_activations(&self, _state: &BattleState<R>, _actor: &dyn Actor<R>) -> Option<u32> {
+        None
+    }
+
+    /// Returns how many of their most recent activations actors remember.
+    ///
+    /// See [recent_activations](trait.Actor.html#method.recent_activations).
+    ///
+    /// The provided implementation returns 8.
+    fn max_recent_activations(&self) -> usize {
+        8
+    }
+
     /// Activates an ability.
     /// `action.ability` is guaranteed to be known by `action.actor`.\
     /// In order to change the state of the world, abilities should insert
@@ -143,6 +327,88 @@ pub trait ActorRules<R: BattleRules> {
         _metrics: &mut WriteMetrics<R>,
     ) {
     }
+
+    /// Returns how many turns `ability` still needs to recharge before it can be
+    /// activated again. Activations are rejected while this value is greater than zero.
+    ///
+    /// The turn of the ability's last activation is available in `ability.last_used_turn()`.
+    /// Use the `RechargeAbilities` event to recharge abilities instantly.
+    ///
+    /// The provided implementation returns zero, meaning that abilities have no cooldown.
+    fn recharge(
+        &self,
+        _state: &BattleState<R>,
+        _actor: &dyn Actor<R>,
+        _ability: &TrackedAbility<R>,
+    ) -> TurnsCount {
+        0
+    }
+
+    /// Returns the activation profiles to consider when enumerating the legal actions
+    /// of an actor, for instance one profile for each valid target of `ability`.
+    ///
+    /// Each candidate is then verified like any other activation.
+    /// See [legal_actions](../battle/struct.Battle.html#method.legal_actions).
+    ///
+    /// The provided implementation returns a single activation without profile.
+    fn valid_activations(
+        &self,
+        _state: &BattleState<R>,
+        _actor: &dyn Actor<R>,
+        _ability: &Ability<R>,
+    ) -> Vec<Option<Activation<R>>> {
+        vec![None]
+    }
+
+    /// Suggests what a computer-controlled `actor` should do in its turn.
+    ///
+    /// The suggested events are fired by
+    /// [run_ai_turn](../server/struct.Server.html#method.run_ai_turn).
+    ///
+    /// The provided implementation suggests nothing.
+    fn suggest_action(
+        &self,
+        _state: &BattleState<R>,
+        _actor: &dyn Actor<R>,
+    ) -> Option<ActionSuggestion<R>> {
+        None
+    }
+
+    /// Returns the names of the optional features enabled in these actor rules.
+    /// They are reported by `Battle::describe`.
+    ///
+    /// The provided implementation returns no features.
+    fn features(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+}
+
+/// A sequence of events suggested by `ActorRules::suggest_action` to a computer-controlled actor.
+pub struct ActionSuggestion<R: BattleRules> {
+    events: Vec<EventPrototype<R>>,
+}
+
+impl<R: BattleRules> ActionSuggestion<R> {
+    /// Creates a new suggestion made of `events`, to be fired in order.
+    pub fn new(events: Vec<EventPrototype<R>>) -> Self {
+        Self { events }
+    }
+
+    /// Returns the suggested events.
+    pub fn events(&self) -> &[EventPrototype<R>] {
+        &self.events
+    }
+
+    /// Consumes this suggestion, returning the suggested events.
+    pub fn into_events(self) -> Vec<EventPrototype<R>> {
+        self.events
+    }
+}
+
+impl<R: BattleRules> From<EventPrototype<R>> for ActionSuggestion<R> {
+    fn from(event: EventPrototype<R>) -> Self {
+        Self::new(vec![event])
+    }
 }
 
 /// An action is comprised by an actor who activates an ability with a given activation profile.
@@ -272,6 +538,7 @@ impl<R: BattleRules + 'static> Event<R> for AlterAbilities<R> {
     }
 
     fn apply(&self, battle: &mut Battle<R>, _: &mut Option<EventQueue<R>>) {
+        let watcher = battle.apply_watcher();
         // Retrieve the actor.
         let actor = battle
             .state
@@ -279,11 +546,15 @@ impl<R: BattleRules + 'static> Event<R> for AlterAbilities<R> {
             .actor_mut(&self.id)
             .unwrap_or_else(|| panic!("constraint violated: actor {:?} not found", self.id));
         // Alter the actor.
-        battle.rules.actor_rules().alter_abilities(
-            actor,
-            &self.alteration,
-            &mut battle.entropy,
-            &mut battle.metrics.write_handle(),
+        watch!(
+            watcher,
+            "ActorRules::alter_abilities",
+            battle.rules.actor_rules().alter_abilities(
+                actor,
+                &self.alteration,
+                &mut battle.entropy,
+                &mut battle.metrics.write_handle(),
+            )
         );
     }
 
@@ -332,7 +603,8 @@ where
 /// An event to regenerate the abilities of an actor.
 ///
 /// A new set of abilities is created from a seed.\
-/// - Abilities already known by the actor won't be modified.
+/// - Abilities already known by the actor are handled according to the
+///   `RegenerationPolicy` (by default, they won't be modified).
 /// - Abilities that the actor didn't have before will be added.
 /// - Current actor's abilities that are not present in the new set will be removed
 ///   from the actor.
@@ -384,6 +656,9 @@ pub struct RegenerateAbilities<R: BattleRules> {
         ))
     )]
     seed: Option<AbilitiesSeed<R>>,
+
+    #[cfg_attr(feature = "serialization", serde(default))]
+    policy: RegenerationPolicy,
 }
 
 impl<R: BattleRules> RegenerateAbilities<R> {
@@ -396,6 +671,7 @@ impl<R: BattleRules> RegenerateAbilities<R> {
             processor,
             id,
             seed: None,
+            policy: RegenerationPolicy::default(),
         }
     }
 
@@ -408,14 +684,19 @@ impl<R: BattleRules> RegenerateAbilities<R> {
     pub fn seed(&self) -> &Option<AbilitiesSeed<R>> {
         &self.seed
     }
+
+    /// Returns the policy applied to the abilities already known by the actor.
+    pub fn policy(&self) -> RegenerationPolicy {
+        self.policy
+    }
 }
 
 impl<R: BattleRules> Debug for RegenerateAbilities<R> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         write!(
             f,
-            "RegenerateAbilities {{ id: {:?}, seed: {:?} }}",
-            self.id, self.seed
+            "RegenerateAbilities {{ id: {:?}, seed: {:?}, policy: {:?} }}",
+            self.id, self.seed, self.policy
         )
     }
 }
@@ -425,6 +706,7 @@ impl<R: BattleRules> Clone for RegenerateAbilities<R> {
         Self {
             id: self.id.clone(),
             seed: self.seed.clone(),
+            policy: self.policy,
         }
     }
 }
@@ -435,6 +717,7 @@ impl<R: BattleRules + 'static> Event<R> for RegenerateAbilities<R> {
     }
 
     fn apply(&self, battle: &mut Battle<R>, _: &mut Option<EventQueue<R>>) {
+        let watcher = battle.apply_watcher();
         // Retrieve the actor.
         let actor = battle
             .state
@@ -442,15 +725,19 @@ impl<R: BattleRules + 'static> Event<R> for RegenerateAbilities<R> {
             .actor_mut(&self.id)
             .unwrap_or_else(|| panic!("constraint violated: actor {:?} not found", self.id));
         // Generate a new set of abilities.
-        let abilities: Vec<_> = battle
-            .rules
-            .actor_rules()
-            .generate_abilities(
-                &self.seed,
-                &mut battle.entropy,
-                &mut battle.metrics.write_handle(),
-            )
-            .collect();
+        let abilities: Vec<_> = watch!(
+            watcher,
+            "ActorRules::generate_abilities",
+            battle
+                .rules
+                .actor_rules()
+                .generate_abilities(
+                    &self.seed,
+                    &mut battle.entropy,
+                    &mut battle.metrics.write_handle(),
+                )
+                .collect()
+        );
         let mut to_remove = Vec::new();
         // Remove all actor's abilities not present in the new set.
         for ability in actor.abilities() {
@@ -462,8 +749,18 @@ impl<R: BattleRules + 'static> Event<R> for RegenerateAbilities<R> {
             actor.remove_ability(&ability_id);
         }
         // Add all abilities present in the new set but not in the actor.
+        // Existing abilities are handled according to the policy.
+        let rules = battle.rules.actor_rules();
         for ability in abilities {
-            if actor.ability(ability.id()).is_none() {
+            if let Some(current) = actor.ability_mut(ability.id()) {
+                match self.policy {
+                    RegenerationPolicy::PreserveCurrent => {}
+                    RegenerationPolicy::ResetToSeed => {
+                        actor.add_ability(ability);
+                    }
+                    RegenerationPolicy::ClampToNew => rules.clamp_ability(current, &ability),
+                }
+            } else {
                 actor.add_ability(ability);
             }
         }
@@ -491,6 +788,7 @@ where
     processor: &'a mut P,
     id: EntityId<R>,
     seed: Option<AbilitiesSeed<R>>,
+    policy: RegenerationPolicy,
 }
 
 impl<'a, R, P> RegenerateAbilitiesTrigger<'a, R, P>
@@ -503,6 +801,12 @@ where
         self.seed = Some(seed);
         self
     }
+
+    /// Sets the policy for the abilities already known by the actor.
+    pub fn policy(&'a mut self, policy: RegenerationPolicy) -> &'a mut Self {
+        self.policy = policy;
+        self
+    }
 }
 
 impl<'a, R, P> EventTrigger<'a, R, P> for RegenerateAbilitiesTrigger<'a, R, P>
@@ -519,6 +823,190 @@ where
         Box::new(RegenerateAbilities {
             id: self.id.clone(),
             seed: self.seed.clone(),
+            policy: self.policy,
+        })
+    }
+}
+
+/// An event to recharge the abilities of an actor, so that they can be activated
+/// again regardless of their cooldown.
+///
+/// By default all abilities of the actor are recharged.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateCreature,
+///     CreateTeam, EntityId, EventKind, EventTrigger, RechargeAbilities, Server,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// let team_id = 1;
+/// CreateTeam::trigger(&mut server, team_id).fire().unwrap();
+/// let creature_id = 1;
+/// let position = ();
+/// CreateCreature::trigger(&mut server, creature_id, team_id, position)
+///     .fire()
+///     .unwrap();
+///
+/// RechargeAbilities::trigger(&mut server, EntityId::Creature(creature_id))
+///     .fire()
+///     .unwrap();
+/// assert_eq!(
+///     server.battle().history().events().iter().last().unwrap().kind(),
+///     EventKind::RechargeAbilities
+/// );
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct RechargeAbilities<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "EntityId<R>: Serialize",
+            deserialize = "EntityId<R>: Deserialize<'de>"
+        ))
+    )]
+    id: EntityId<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Option<Vec<AbilityId<R>>>: Serialize",
+            deserialize = "Option<Vec<AbilityId<R>>>: Deserialize<'de>"
+        ))
+    )]
+    abilities: Option<Vec<AbilityId<R>>>,
+}
+
+impl<R: BattleRules> RechargeAbilities<R> {
+    /// Returns a trigger for this event.
+    pub fn trigger<P: EventProcessor<R>>(
+        processor: &'_ mut P,
+        id: EntityId<R>,
+    ) -> RechargeAbilitiesTrigger<'_, R, P> {
+        RechargeAbilitiesTrigger {
+            processor,
+            id,
+            abilities: None,
+        }
+    }
+
+    /// Returns the actor's entity id.
+    pub fn id(&self) -> &EntityId<R> {
+        &self.id
+    }
+
+    /// Returns the abilities to recharge. `None` means all abilities.
+    pub fn abilities(&self) -> &Option<Vec<AbilityId<R>>> {
+        &self.abilities
+    }
+}
+
+impl<R: BattleRules> Debug for RechargeAbilities<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "RechargeAbilities {{ id: {:?}, abilities: {:?} }}",
+            self.id, self.abilities
+        )
+    }
+}
+
+impl<R: BattleRules> Clone for RechargeAbilities<R> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+            abilities: self.abilities.clone(),
+        }
+    }
+}
+
+impl<R: BattleRules + 'static> Event<R> for RechargeAbilities<R> {
+    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
+        verify_is_actor(battle.entities(), &self.id)?;
+        // Verify that the actor knows all abilities.
+        if let Some(abilities) = &self.abilities {
+            let actor = battle
+                .entities()
+                .actor(&self.id)
+                .ok_or_else(|| WeaselError::EntityNotFound(self.id.clone()))?;
+            for ability_id in abilities {
+                if actor.ability(ability_id).is_none() {
+                    return Err(WeaselError::AbilityNotKnown(
+                        self.id.clone(),
+                        ability_id.clone(),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn apply(&self, battle: &mut Battle<R>, _: &mut Option<EventQueue<R>>) {
+        if let EntityId::Creature(id) = &self.id {
+            battle
+                .state
+                .entities
+                .creature_mut(id)
+                .unwrap_or_else(|| panic!("constraint violated: creature {:?} not found", id))
+                .recharge_abilities(self.abilities.as_deref());
+        }
+    }
+
+    fn kind(&self) -> EventKind {
+        EventKind::RechargeAbilities
+    }
+
+    fn box_clone(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Trigger to build and fire a `RechargeAbilities` event.
+pub struct RechargeAbilitiesTrigger<'a, R, P>
+where
+    R: BattleRules,
+    P: EventProcessor<R>,
+{
+    processor: &'a mut P,
+    id: EntityId<R>,
+    abilities: Option<Vec<AbilityId<R>>>,
+}
+
+impl<'a, R, P> RechargeAbilitiesTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    /// Restricts the recharge to the given abilities.
+    pub fn abilities(&'a mut self, abilities: Vec<AbilityId<R>>) -> &'a mut Self {
+        self.abilities = Some(abilities);
+        self
+    }
+}
+
+impl<'a, R, P> EventTrigger<'a, R, P> for RechargeAbilitiesTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    fn processor(&'a mut self) -> &'a mut P {
+        self.processor
+    }
+
+    /// Returns a `RechargeAbilities` event.
+    fn event(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(RechargeAbilities {
+            id: self.id.clone(),
+            abilities: self.abilities.clone(),
         })
     }
 }
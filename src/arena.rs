@@ -0,0 +1,291 @@
+//! Generational arena used as an alternative storage for entities.
+
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A stable reference to an element stored in an arena.
+///
+/// Handles stay valid when other elements are inserted or removed, and even if the element
+/// changes its id. Once the element is removed, its handle is never reused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Handle {
+    index: u32,
+    generation: u32,
+}
+
+impl Handle {
+    /// Returns the index of the slot this handle refers to.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Returns the generation of the slot at the time the handle was created.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+/// A slot pointing to the position of an element in the dense storage.
+#[derive(Clone, Copy)]
+struct Slot {
+    generation: u32,
+    dense: Option<usize>,
+}
+
+/// Storage that keeps its elements packed in contiguous memory, to make iterations
+/// cache friendly, while offering stable handles and lookups by key.
+///
+/// Removing an element moves the last element in its place, like `IndexMap::swap_remove`.
+pub struct Arena<K, V> {
+    index: HashMap<K, Handle>,
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+    keys: Vec<K>,
+    values: Vec<V>,
+    handles: Vec<Handle>,
+}
+
+impl<K, V> Arena<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    /// Creates a new arena with room for at least `capacity` elements.
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self {
+            index: HashMap::with_capacity(capacity),
+            slots: Vec::with_capacity(capacity),
+            free: Vec::new(),
+            keys: Vec::with_capacity(capacity),
+            values: Vec::with_capacity(capacity),
+            handles: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Reserves room for at least `additional` more elements.
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        self.index.reserve(additional);
+        self.slots
+            .reserve(additional.saturating_sub(self.free.len()));
+        self.keys.reserve(additional);
+        self.values.reserve(additional);
+        self.handles.reserve(additional);
+    }
+
+    /// Returns the number of elements.
+    pub(crate) fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns the number of elements that can be stored without reallocating.
+    pub(crate) fn capacity(&self) -> usize {
+        self.values.capacity()
+    }
+
+    /// Returns an iterator over all keys, in storage order.
+    pub(crate) fn keys(&self) -> impl Iterator<Item = &K> {
+        self.keys.iter()
+    }
+
+    /// Returns an iterator over all values, in storage order.
+    pub(crate) fn values(&self) -> impl Iterator<Item = &V> {
+        self.values.iter()
+    }
+
+    /// Returns a mutable iterator over all values, in storage order.
+    pub(crate) fn values_mut(&mut self) -> impl Iterator<Item = &mut V> {
+        self.values.iter_mut()
+    }
+
+    /// Returns whether an element with the given key exists.
+    pub(crate) fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.index.contains_key(key)
+    }
+
+    /// Returns the position in the dense storage of the element with the given handle.
+    fn dense(&self, handle: Handle) -> Option<usize> {
+        self.slots
+            .get(handle.index as usize)
+            .filter(|slot| slot.generation == handle.generation)
+            .and_then(|slot| slot.dense)
+    }
+
+    /// Returns the handle of the element with the given key.
+    pub(crate) fn handle<Q>(&self, key: &Q) -> Option<Handle>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.index.get(key).copied()
+    }
+
+    /// Returns the value with the given key.
+    pub(crate) fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_by_handle(self.handle(key)?)
+    }
+
+    /// Returns the position, the key and a mutable reference to the value with the given key.
+    pub(crate) fn get_full_mut<Q>(&mut self, key: &Q) -> Option<(usize, &K, &mut V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let dense = self.dense(self.handle(key)?)?;
+        Some((dense, &self.keys[dense], &mut self.values[dense]))
+    }
+
+    /// Returns the value with the given handle.
+    pub(crate) fn get_by_handle(&self, handle: Handle) -> Option<&V> {
+        self.dense(handle).map(|dense| &self.values[dense])
+    }
+
+    /// Returns the key and a mutable reference to the value with the given handle.
+    pub(crate) fn get_by_handle_mut(&mut self, handle: Handle) -> Option<(&K, &mut V)> {
+        let dense = self.dense(handle)?;
+        Some((&self.keys[dense], &mut self.values[dense]))
+    }
+
+    /// Returns a mutable reference to the value with the given key.
+    pub(crate) fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_full_mut(key).map(|(_, _, value)| value)
+    }
+
+    /// Inserts a value. If the key already exists, its value is replaced and returned.
+    pub(crate) fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some((_, _, existing)) = self.get_full_mut(&key) {
+            return Some(std::mem::replace(existing, value));
+        }
+        let dense = self.values.len();
+        let handle = match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize];
+                slot.dense = Some(dense);
+                Handle {
+                    index,
+                    generation: slot.generation,
+                }
+            }
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    dense: Some(dense),
+                });
+                Handle {
+                    index: (self.slots.len() - 1) as u32,
+                    generation: 0,
+                }
+            }
+        };
+        self.index.insert(key.clone(), handle);
+        self.keys.push(key);
+        self.values.push(value);
+        self.handles.push(handle);
+        None
+    }
+
+    /// Removes the value with the given key and returns it.
+    ///
+    /// The last element takes the place of the removed one.
+    pub(crate) fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let handle = self.index.remove(key)?;
+        let slot = &mut self.slots[handle.index as usize];
+        let dense = slot.dense.take()?;
+        // Bump the generation to invalidate existing handles.
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(handle.index);
+        self.keys.swap_remove(dense);
+        self.handles.swap_remove(dense);
+        let value = self.values.swap_remove(dense);
+        if let Some(moved) = self.handles.get(dense) {
+            self.slots[moved.index as usize].dense = Some(dense);
+        }
+        Some(value)
+    }
+
+    /// Changes the key of an element, keeping its position and its handle.
+    ///
+    /// Returns false if no element has the key `key`.
+    pub(crate) fn rekey(&mut self, key: &K, new_key: K) -> bool {
+        match self.index.remove(key) {
+            Some(handle) => {
+                let dense = self.dense(handle).unwrap();
+                self.keys[dense] = new_key.clone();
+                self.index.insert(new_key, handle);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a Arena<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = std::iter::Zip<std::slice::Iter<'a, K>, std::slice::Iter<'a, V>>;
+
+    /// Returns an iterator over all keys and values, in storage order.
+    fn into_iter(self) -> Self::IntoIter {
+        self.keys.iter().zip(self.values.iter())
+    }
+}
+
+impl<K, V> std::iter::FromIterator<(K, V)> for Arena<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    /// Creates an arena from key-value pairs, inserted in order.
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        let mut arena = Self::with_capacity(iter.size_hint().0);
+        for (key, value) in iter {
+            arena.insert(key, value);
+        }
+        arena
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn handles_survive_removals() {
+        let mut arena = Arena::with_capacity(2);
+        arena.insert(1, "a");
+        arena.insert(2, "b");
+        arena.insert(3, "c");
+        let handle = arena.handle(&3).unwrap();
+        // Removal moves the last element in place of the removed one.
+        assert_eq!(arena.remove(&1), Some("a"));
+        assert_eq!(arena.values().copied().collect::<Vec<_>>(), vec!["c", "b"]);
+        assert_eq!(arena.get_by_handle(handle), Some(&"c"));
+        // Handles of removed elements are invalidated, even if the slot is reused.
+        let handle = arena.handle(&2).unwrap();
+        assert_eq!(arena.remove(&2), Some("b"));
+        arena.insert(4, "d");
+        assert_eq!(arena.get_by_handle(handle), None);
+        assert_eq!(arena.get(&4), Some(&"d"));
+        // Handles are kept after changing key.
+        let handle = arena.handle(&3).unwrap();
+        assert!(arena.rekey(&3, 5));
+        assert_eq!(arena.handle(&5), Some(handle));
+        assert!(!arena.contains_key(&3));
+        assert_eq!(arena.keys().copied().collect::<Vec<_>>(), vec![5, 4]);
+        assert_eq!(arena.len(), 2);
+    }
+}
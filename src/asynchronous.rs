@@ -0,0 +1,342 @@
+//! Asynchronous counterparts of event processors and sinks.
+//!
+//! Battles are always processed synchronously, since verifying and applying an event
+//! only touches memory. What can take time is moving events over the network.
+//! This module lets sinks do it with futures, so that weasel can be integrated into
+//! the event loop of any async runtime.
+//!
+//! - `AsyncServerSink` is driven by an `AsyncClient`, which queues the events fired locally
+//!   and sends them when `flush` is awaited.
+//! - `AsyncClientSink` is driven by an `AsyncForwarder`, paired with a regular `ClientSink`
+//!   that can be added to a server or client through `bridge`.
+//!
+//! Only futures from the standard library are used. No particular runtime is required.
+
+use crate::battle::BattleRules;
+use crate::client::Client;
+use crate::error::{WeaselError, WeaselResult};
+use crate::event::{
+    ClientEventPrototype, ClientSink, EventProcessor, EventPrototype, EventReceiver, EventSink,
+    EventSinkId, VersionedEventWrapper,
+};
+use crate::server::Server;
+use std::collections::VecDeque;
+use std::future::{ready, Future};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+/// An owned, pinned and boxed future that can be sent to other threads.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// A trait for objects that can process new local event prototypes asynchronously.
+pub trait AsyncEventProcessor<R: BattleRules> {
+    /// Return type of the future returned by `process_async()`.
+    type AsyncOutput;
+
+    /// Processes a local event prototype.
+    fn process_async(&mut self, event: EventPrototype<R>) -> BoxFuture<'_, Self::AsyncOutput>;
+}
+
+/// Servers process events immediately. The returned future is always ready.
+impl<R: BattleRules + 'static> AsyncEventProcessor<R> for Server<R> {
+    type AsyncOutput = WeaselResult<(), R>;
+
+    fn process_async(&mut self, event: EventPrototype<R>) -> BoxFuture<'_, Self::AsyncOutput> {
+        Box::pin(ready(self.process(event)))
+    }
+}
+
+/// Asynchronous counterpart of `ServerSink`.
+pub trait AsyncServerSink<R: BattleRules>: EventSink {
+    /// Sends a client event prototype to a remote or local server.
+    fn send(&mut self, event: ClientEventPrototype<R>) -> BoxFuture<'_, WeaselResult<(), R>>;
+}
+
+/// Asynchronous counterpart of `ClientSink`.
+pub trait AsyncClientSink<R: BattleRules>: EventSink {
+    /// Sends an already accepted event to a remote or local client.
+    fn send(&mut self, event: VersionedEventWrapper<R>) -> BoxFuture<'_, WeaselResult<(), R>>;
+}
+
+/// A client sending its events to the server through an `AsyncServerSink`.
+///
+/// Events fired on an `AsyncClient` with `fire` are verified and kept in an
+/// outbox until `flush` is awaited. `fire_async` does both things at once.
+///
+/// # Examples
+/// ```
+/// use weasel::asynchronous::{AsyncClient, AsyncServerSink, BoxFuture};
+/// use weasel::event::{DummyEvent, EventSink, EventSinkId, ServerSink};
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleRules, Client, ClientEventPrototype,
+///     EventTrigger, WeaselResult,
+/// };
+///
+/// battle_rules! {}
+///
+/// struct Sink;
+///
+/// impl EventSink for Sink {
+///     fn id(&self) -> EventSinkId {
+///         0
+///     }
+/// }
+///
+/// impl AsyncServerSink<CustomRules> for Sink {
+///     fn send(
+///         &mut self,
+///         _: ClientEventPrototype<CustomRules>,
+///     ) -> BoxFuture<'_, WeaselResult<(), CustomRules>> {
+///         Box::pin(async { Ok(()) })
+///     }
+/// }
+///
+/// impl ServerSink<CustomRules> for Sink {
+///     fn send(&mut self, _: &ClientEventPrototype<CustomRules>) -> WeaselResult<(), CustomRules> {
+///         Ok(())
+///     }
+/// }
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// // The server sink given to the inner client is never used.
+/// let client = Client::builder(battle, Box::new(Sink)).build();
+/// let mut client = AsyncClient::new(client, Box::new(Sink));
+///
+/// DummyEvent::trigger(&mut client).fire().unwrap();
+/// assert_eq!(client.pending_events().len(), 1);
+/// ```
+pub struct AsyncClient<R: BattleRules> {
+    client: Client<R>,
+    server_sink: Box<dyn AsyncServerSink<R> + Send>,
+    outbox: Vec<ClientEventPrototype<R>>,
+}
+
+impl<R: BattleRules + 'static> AsyncClient<R> {
+    /// Creates a new asynchronous client.
+    ///
+    /// The server sink and the outbox of `client` are never used by the asynchronous client.
+    pub fn new(client: Client<R>, server_sink: Box<dyn AsyncServerSink<R> + Send>) -> Self {
+        Self {
+            client,
+            server_sink,
+            outbox: Vec::new(),
+        }
+    }
+
+    /// Returns the inner client.
+    pub fn client(&self) -> &Client<R> {
+        &self.client
+    }
+
+    /// Returns a mutable reference to the inner client.
+    pub fn client_mut(&mut self) -> &mut Client<R> {
+        &mut self.client
+    }
+
+    /// Returns the asynchronous server sink.
+    pub fn server_sink(&self) -> &(dyn AsyncServerSink<R> + Send) {
+        &*self.server_sink
+    }
+
+    /// Consumes this asynchronous client, returning the inner client.
+    pub fn into_inner(self) -> Client<R> {
+        self.client
+    }
+
+    /// Returns the events waiting in the outbox to be sent to the server, in firing order.
+    pub fn pending_events(&self) -> &[ClientEventPrototype<R>] {
+        &self.outbox
+    }
+
+    /// Cancels all pending events in the outbox.
+    pub fn clear_pending(&mut self) {
+        self.outbox.clear();
+    }
+
+    /// Sends all pending events in the outbox to the server, in firing order.
+    ///
+    /// Stops at the first event that fails to be sent and returns the error.
+    /// The failed event and the ones after it are kept in the outbox.
+    pub fn flush(&mut self) -> BoxFuture<'_, WeaselResult<(), R>> {
+        flush(&mut *self.server_sink, &mut self.outbox)
+    }
+}
+
+impl<R: BattleRules + 'static> EventProcessor<R> for AsyncClient<R> {
+    type ProcessOutput = WeaselResult<(), R>;
+
+    fn process(&mut self, event: EventPrototype<R>) -> Self::ProcessOutput {
+        let event = self.client.prepare(event)?;
+        self.outbox.push(event);
+        Ok(())
+    }
+}
+
+impl<R: BattleRules + 'static> AsyncEventProcessor<R> for AsyncClient<R> {
+    type AsyncOutput = WeaselResult<(), R>;
+
+    fn process_async(&mut self, event: EventPrototype<R>) -> BoxFuture<'_, Self::AsyncOutput> {
+        let result = self.process(event);
+        let flush = self.flush();
+        Box::pin(async move {
+            result?;
+            flush.await
+        })
+    }
+}
+
+fn flush<'a, R: BattleRules>(
+    server_sink: &'a mut (dyn AsyncServerSink<R> + Send),
+    outbox: &'a mut Vec<ClientEventPrototype<R>>,
+) -> BoxFuture<'a, WeaselResult<(), R>> {
+    Box::pin(async move {
+        while !outbox.is_empty() {
+            server_sink.send(outbox[0].clone()).await?;
+            outbox.remove(0);
+        }
+        Ok(())
+    })
+}
+
+impl<R: BattleRules + 'static> EventReceiver<R> for AsyncClient<R> {
+    fn receive(&mut self, event: VersionedEventWrapper<R>) -> WeaselResult<(), R> {
+        self.client.receive(event)
+    }
+}
+
+type SharedQueue<R> = Arc<Mutex<VecDeque<VersionedEventWrapper<R>>>>;
+
+/// Creates a pair made of a `ClientSink` and an `AsyncForwarder`.
+///
+/// The client sink only queues the events it receives. The forwarder sends them
+/// through `sink` once `forward` is awaited.
+///
+/// # Examples
+/// ```
+/// use weasel::asynchronous::{bridge, AsyncClientSink, BoxFuture};
+/// use weasel::event::{DummyEvent, EventSink, EventSinkId};
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleRules, EventTrigger, Server,
+///     VersionedEventWrapper, WeaselResult,
+/// };
+///
+/// battle_rules! {}
+///
+/// struct Sink;
+///
+/// impl EventSink for Sink {
+///     fn id(&self) -> EventSinkId {
+///         1
+///     }
+/// }
+///
+/// impl AsyncClientSink<CustomRules> for Sink {
+///     fn send(
+///         &mut self,
+///         _: VersionedEventWrapper<CustomRules>,
+///     ) -> BoxFuture<'_, WeaselResult<(), CustomRules>> {
+///         Box::pin(async { Ok(()) })
+///     }
+/// }
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+/// let (sink, forwarder) = bridge(Sink);
+/// server.client_sinks_mut().add_sink(Box::new(sink)).unwrap();
+///
+/// DummyEvent::trigger(&mut server).fire().unwrap();
+/// assert_eq!(forwarder.pending(), 1);
+/// ```
+pub fn bridge<R, S>(sink: S) -> (BridgeClientSink<R>, AsyncForwarder<R, S>)
+where
+    R: BattleRules,
+    S: AsyncClientSink<R>,
+{
+    let queue = Arc::new(Mutex::new(VecDeque::new()));
+    (
+        BridgeClientSink {
+            id: sink.id(),
+            queue: queue.clone(),
+        },
+        AsyncForwarder { sink, queue },
+    )
+}
+
+/// A `ClientSink` queueing events for an `AsyncForwarder`.
+///
+/// It has the same id as the forwarder's asynchronous sink.
+pub struct BridgeClientSink<R: BattleRules> {
+    id: EventSinkId,
+    queue: SharedQueue<R>,
+}
+
+impl<R: BattleRules> EventSink for BridgeClientSink<R> {
+    fn id(&self) -> EventSinkId {
+        self.id
+    }
+}
+
+impl<R: BattleRules> ClientSink<R> for BridgeClientSink<R> {
+    fn send(&mut self, event: &VersionedEventWrapper<R>) -> WeaselResult<(), R> {
+        self.queue
+            .lock()
+            .map_err(|err| WeaselError::EventSinkError(err.to_string()))?
+            .push_back(event.clone());
+        Ok(())
+    }
+}
+
+/// Sends the events queued by a `BridgeClientSink` through an `AsyncClientSink`.
+pub struct AsyncForwarder<R: BattleRules, S> {
+    sink: S,
+    queue: SharedQueue<R>,
+}
+
+impl<R: BattleRules, S: AsyncClientSink<R>> AsyncForwarder<R, S> {
+    /// Returns the asynchronous sink.
+    pub fn sink(&self) -> &S {
+        &self.sink
+    }
+
+    /// Returns a mutable reference to the asynchronous sink.
+    pub fn sink_mut(&mut self) -> &mut S {
+        &mut self.sink
+    }
+
+    /// Returns how many events are waiting to be forwarded.
+    pub fn pending(&self) -> usize {
+        self.queue.lock().map(|queue| queue.len()).unwrap_or(0)
+    }
+
+    /// Sends all queued events through the asynchronous sink, in order.
+    /// Returns the number of events sent.
+    ///
+    /// Stops at the first event that fails to be sent and returns the error.
+    /// The failed event and the ones after it stay in the queue.
+    pub async fn forward(&mut self) -> WeaselResult<usize, R> {
+        let mut sent = 0;
+        while let Some(event) = self.front()? {
+            self.sink.send(event).await?;
+            self.pop()?;
+            sent += 1;
+        }
+        Ok(sent)
+    }
+
+    fn front(&self) -> WeaselResult<Option<VersionedEventWrapper<R>>, R> {
+        Ok(self
+            .queue
+            .lock()
+            .map_err(|err| WeaselError::EventSinkError(err.to_string()))?
+            .front()
+            .cloned())
+    }
+
+    fn pop(&self) -> WeaselResult<(), R> {
+        self.queue
+            .lock()
+            .map_err(|err| WeaselError::EventSinkError(err.to_string()))?
+            .pop_front();
+        Ok(())
+    }
+}
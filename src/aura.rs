@@ -0,0 +1,207 @@
+//! Statuses projected by a character onto an area.
+//!
+//! A status is an aura if `FightRules::aura` returns an `Aura` for it. Each aura inflicts
+//! a derived status on all characters inside its area, as long as they stay there.\
+//! Auras are updated automatically after the following events:
+//! - `MoveEntity` and `MoveEntityAlongPath`.
+//! - Status updates at the start of a turn and during `EnvironmentTurn`.
+//! - `InflictStatus` and `ClearStatus` of an aura.
+//!
+//! Derived statuses are inflicted and cleared through regular `InflictStatus` and
+//! `ClearStatus` events, generated by the server.
+//! Derived statuses don't project auras of their own.
+//!
+//! Events bootstrapping a new client reproduce the derived statuses, provided that
+//! the auras are restored by the server's bootstrap callback.
+
+use crate::battle::{Battle, BattleRules};
+use crate::entity::EntityId;
+use crate::event::{Event, EventQueue, EventTrigger};
+use crate::fight::FightRules;
+use crate::space::Area;
+use crate::status::{ClearStatus, InflictStatus, Potency, StatusId};
+use crate::util::Id;
+use std::fmt::{Debug, Formatter, Result};
+
+/// Describes the status projected by an aura and the area it covers.
+pub struct Aura<R: BattleRules> {
+    area: Area<R>,
+    status_id: StatusId<R>,
+    potency: Option<Potency<R>>,
+}
+
+impl<R: BattleRules> Aura<R> {
+    /// Creates a new aura inflicting the status with id `status_id` on all characters
+    /// inside `area`.
+    ///
+    /// The area may contain the character projecting the aura.
+    pub fn new(area: Area<R>, status_id: StatusId<R>) -> Self {
+        Self {
+            area,
+            status_id,
+            potency: None,
+        }
+    }
+
+    /// Sets the potency of the derived status.
+    pub fn with_potency(mut self, potency: Potency<R>) -> Self {
+        self.potency = Some(potency);
+        self
+    }
+
+    /// Returns the area covered by this aura.
+    pub fn area(&self) -> &Area<R> {
+        &self.area
+    }
+
+    /// Returns the id of the status inflicted on characters inside the area.
+    pub fn status_id(&self) -> &StatusId<R> {
+        &self.status_id
+    }
+
+    /// Returns the potency of the derived status.
+    pub fn potency(&self) -> &Option<Potency<R>> {
+        &self.potency
+    }
+}
+
+impl<R: BattleRules> Debug for Aura<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "Aura {{ area: {:?}, status_id: {:?}, potency: {:?} }}",
+            self.area, self.status_id, self.potency
+        )
+    }
+}
+
+impl<R: BattleRules> Clone for Aura<R> {
+    fn clone(&self) -> Self {
+        Self {
+            area: self.area.clone(),
+            status_id: self.status_id.clone(),
+            potency: self.potency.clone(),
+        }
+    }
+}
+
+/// A derived status that should be affecting a character.
+struct Projection<R: BattleRules> {
+    target: EntityId<R>,
+    source: EntityId<R>,
+    status_id: StatusId<R>,
+    potency: Option<Potency<R>>,
+}
+
+/// Inflicts derived statuses on the characters who entered the area of an aura and
+/// clears them from the characters who left it.
+///
+/// Does nothing if `event_queue` is `None`.
+pub(crate) fn update_auras<R: BattleRules + 'static>(
+    battle: &Battle<R>,
+    event_queue: &mut Option<EventQueue<R>>,
+) {
+    if event_queue.is_none() {
+        return;
+    }
+    let state = &battle.state;
+    let projections = projections(battle);
+    // Clear the derived statuses of characters outside the area.
+    for character in state.entities.characters() {
+        for status in character.statuses() {
+            let projected = projections.iter().any(|projection| {
+                projection.target == *character.entity_id() && projection.status_id == *status.id()
+            });
+            if status.aura_source().is_some() && !projected {
+                ClearStatus::trigger(
+                    event_queue,
+                    character.entity_id().clone(),
+                    status.id().clone(),
+                )
+                .fire();
+            }
+        }
+    }
+    // Inflict the derived statuses on characters inside the area.
+    for projection in projections {
+        if !is_inflicted(battle, &projection) {
+            InflictStatus::trigger_projection(
+                event_queue,
+                projection.target,
+                projection.status_id,
+                projection.potency,
+                projection.source,
+            )
+            .fire();
+        }
+    }
+}
+
+/// Returns the events inflicting the derived statuses currently projected by auras.
+///
+/// Clients don't generate derived statuses on their own, thus these events are needed
+/// to bootstrap a client after the auras themselves have been restored.
+pub(crate) fn projected_statuses<R: BattleRules + 'static>(
+    battle: &Battle<R>,
+) -> Vec<Box<dyn Event<R> + Send>> {
+    projections(battle)
+        .into_iter()
+        .filter(|projection| is_inflicted(battle, projection))
+        .map(|projection| {
+            InflictStatus::trigger_projection(
+                &mut (),
+                projection.target,
+                projection.status_id,
+                projection.potency,
+                projection.source,
+            )
+            .event()
+        })
+        .collect()
+}
+
+/// Returns true if the derived status of `projection` is inflicted on its target.
+fn is_inflicted<R: BattleRules>(battle: &Battle<R>, projection: &Projection<R>) -> bool {
+    battle
+        .state
+        .entities
+        .character(&projection.target)
+        .and_then(|character| character.status(&projection.status_id))
+        .is_some()
+}
+
+/// Finds all derived statuses projected by auras.
+fn projections<R: BattleRules + 'static>(battle: &Battle<R>) -> Vec<Projection<R>> {
+    let state = &battle.state;
+    let mut projections: Vec<Projection<R>> = Vec::new();
+    for source in state.entities.characters() {
+        for status in source.statuses() {
+            if status.aura_source().is_some() {
+                continue;
+            }
+            let aura = match watch!(
+                battle.apply_watcher(),
+                "FightRules::aura",
+                battle.rules.fight_rules().aura(state, source, status)
+            ) {
+                Some(aura) => aura,
+                None => continue,
+            };
+            for target in state.space.entities_in_area(&state.entities, &aura.area) {
+                let target = target.entity_id();
+                let duplicated = projections.iter().any(|projection| {
+                    projection.target == *target && projection.status_id == aura.status_id
+                });
+                if target.is_character() && !duplicated {
+                    projections.push(Projection {
+                        target: target.clone(),
+                        source: source.entity_id().clone(),
+                        status_id: aura.status_id.clone(),
+                        potency: aura.potency.clone(),
+                    });
+                }
+            }
+        }
+    }
+    projections
+}
@@ -1,29 +1,44 @@
 //! Battle module.
 
+use crate::ability::ActivateAbility;
 use crate::actor::ActorRules;
 use crate::character::CharacterRules;
-use crate::entity::Entities;
+use crate::entity::{Entities, Entity, EntityId};
 use crate::entropy::{Entropy, EntropyRules};
 use crate::error::{WeaselError, WeaselResult};
+#[cfg(feature = "serialization")]
+use crate::event::normalize_range;
 use crate::event::{
-    ClientEventPrototype, Event, EventKind, EventProcessor, EventPrototype, EventQueue,
+    ClientEventPrototype, Event, EventId, EventKind, EventProcessor, EventPrototype, EventQueue,
     EventTrigger, EventWrapper, Prioritized, VersionedEventWrapper,
 };
 use crate::fight::FightRules;
+#[cfg(feature = "serialization")]
+use crate::history::ExportFormat;
 use crate::history::History;
 use crate::metric::{Metrics, ReadMetrics, WriteMetrics};
-use crate::player::{Rights, RightsHandle, RightsHandleMut};
-use crate::round::{Rounds, RoundsRules};
-use crate::space::{Space, SpaceRules};
+use crate::object::ObjectOwner;
+use crate::player::{PlayerRules, Players, Rights, RightsHandle, RightsHandleMut};
+use crate::reaction::ReactionRules;
+use crate::round::{EndTurn, Rounds, RoundsCount, RoundsRules};
+#[cfg(feature = "serialization")]
+use crate::serde::{BattleSnapshot, EventFormat, FlatVersionedEvent, OwnedBattleSnapshot};
+use crate::space::{Area, Distance, MoveEntity, Position, Space, SpaceRules};
 use crate::team::{ConcludeObjectives, TeamId, TeamRules};
-use crate::user::UserRules;
+use crate::user::{UserEventRegistry, UserRules};
 use crate::util::Id;
+use crate::visibility::Visibility;
+use log::warn;
 #[cfg(feature = "serialization")]
 use serde::{Deserialize, Serialize};
-use std::any::Any;
+use std::any::{type_name, Any, TypeId};
+use std::collections::HashMap;
 use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
 use std::ops::Range;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Type to define a callback invoked each time an event is processed.
 ///
@@ -44,7 +59,11 @@ pub struct Battle<R: BattleRules> {
     pub(crate) rules: R,
     pub(crate) event_callback: Option<EventCallback<R>>,
     pub(crate) metrics: Metrics<R>,
-    rights: Rights<R>,
+    watchdog: Option<Watchdog>,
+    applying: Option<(EventId, EventKind)>,
+    pub(crate) rights: Rights<R>,
+    user_events: UserEventRegistry<R>,
+    pub(crate) setup_phase: bool,
 }
 
 impl<R: BattleRules + 'static> Battle<R> {
@@ -53,15 +72,43 @@ impl<R: BattleRules + 'static> Battle<R> {
         BattleBuilder {
             rules,
             event_callback: None,
+            watchdog: None,
+            round_limit: None,
+            extensions: Extensions::default(),
+            creatures_capacity: 0,
+            objects_capacity: 0,
+            setup_phase: false,
         }
     }
 
     /// Verifies the consistency of an event.
     pub(crate) fn verify_event(&self, event: &(dyn Event<R> + Send)) -> WeaselResult<(), R> {
-        if self.phase() == BattlePhase::Ended {
-            Err(WeaselError::BattleEnded)
+        self.verify_event_in_phase(event, true)
+    }
+
+    /// Verifies the consistency of an event.
+    /// The restrictions of the current battle phase are enforced only if `check_phase` is true.
+    fn verify_event_in_phase(
+        &self,
+        event: &(dyn Event<R> + Send),
+        check_phase: bool,
+    ) -> WeaselResult<(), R> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("verify", kind = ?event.kind()).entered();
+        if let Some(reason) = self.end_reason() {
+            Err(WeaselError::BattleEnded(reason))
+        } else if check_phase
+            && !self
+                .rules
+                .allow_in_phase(&self.state, event.kind(), self.phase())
+        {
+            Err(WeaselError::WrongBattlePhase(event.kind(), self.phase()))
         } else {
-            event.verify(&self)
+            watch!(
+                self.verify_watcher(event.kind()),
+                "Event::verify",
+                event.verify(self)
+            )
         }
     }
 
@@ -78,7 +125,16 @@ impl<R: BattleRules + 'static> Battle<R> {
     }
 
     /// Verifies the consistency of a `VersionedEventWrapper`.
-    pub(crate) fn verify_wrapper(&self, event: &VersionedEventWrapper<R>) -> WeaselResult<(), R> {
+    ///
+    /// Events marked as `unverified` skip the verification of their content
+    /// only if `sandbox` is true.
+    pub(crate) fn verify_wrapper(
+        &self,
+        event: &VersionedEventWrapper<R>,
+        sandbox: bool,
+    ) -> WeaselResult<(), R> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("verify_wrapper", id = event.id()).entered();
         // Verify version.
         let version = self.rules.version();
         if event.version() != version {
@@ -89,8 +145,14 @@ impl<R: BattleRules + 'static> Battle<R> {
         }
         // Verify timeline consistency.
         self.history.verify_event(event.wrapper())?;
-        // Verify event.
-        self.verify_event(&****event)
+        // Events forced by a server in sandbox mode are known to fail verification.
+        // Trust the flag only if sandbox mode was explicitly enabled.
+        if sandbox && event.wrapper().unverified() {
+            return Ok(());
+        }
+        // Verify event. Synthetic events recreate a state that was already reached,
+        // thus they are not bound to the current phase.
+        self.verify_event_in_phase(&****event, !event.wrapper().synthetic())
     }
 
     pub(crate) fn verify_client(&self, event: &ClientEventPrototype<R>) -> WeaselResult<(), R> {
@@ -114,27 +176,98 @@ impl<R: BattleRules + 'static> Battle<R> {
     /// Apply an event to the world.
     /// Takes in a optional `EventQueue`, to eventually store new prototypes derived from `event`.
     pub(crate) fn apply(&mut self, event: &EventWrapper<R>, queue: &mut Option<EventQueue<R>>) {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("apply", id = event.id(), kind = ?event.kind()).entered();
+        // Forget the changes made by the previous event.
+        self.state.entities.clear_changes();
+        self.state.space.clear_changes();
+        self.applying = Some((event.id(), event.kind()));
+        let watcher = self.apply_watcher();
+        // Remember the entropy before the event.
+        self.entropy.record_checkpoint(event.id());
         // Apply the event to the world.
-        event.apply(self, queue);
+        watch!(watcher, "Event::apply", event.apply(self, queue));
         // Save into history.
         self.history.archive(event);
         // Check teams' objectives.
         Battle::check_objectives(
-            &self.state,
-            &self.rules.team_rules(),
+            &mut self.state,
+            self.rules.team_rules(),
             &self.metrics.read_handle(),
             &mut queue.as_mut().map(|queue| Prioritized::new(queue)),
             Checkpoint::EventEnd,
+            watcher,
+        );
+        // Check if the whole battle is over, unless its end is already on the way.
+        if self.state.phase != BattlePhase::Ended {
+            if let Some(queue) = queue {
+                if !queue
+                    .iter()
+                    .any(|event| event.kind() == EventKind::EndBattle)
+                {
+                    if let Some(outcome) = watch!(
+                        watcher,
+                        "BattleRules::check_battle_end",
+                        self.rules.check_battle_end(&self.state)
+                    ) {
+                        EndBattle::trigger(queue).outcome(outcome).fire();
+                    }
+                }
+            }
+        }
+        // Let the rules react to the event.
+        watch!(
+            watcher,
+            "ReactionRules::react",
+            self.rules.reaction_rules().react(
+                &self.state,
+                event,
+                queue,
+                &mut self.entropy,
+                &mut self.metrics.write_handle(),
+            )
         );
         // Invoke user callback.
         if let Some(cb) = &mut self.event_callback {
-            cb(event, &self.state, queue);
+            let state = &self.state;
+            watch!(watcher, "EventCallback", cb(event, state, queue));
         }
+        self.applying = None;
     }
 
-    /// Ends the battle.
-    pub(crate) fn end(&mut self) {
+    /// Ends the battle for the given reason.
+    pub(crate) fn end(&mut self, reason: EndReason) {
         self.state.phase = BattlePhase::Ended;
+        self.state.end_reason = Some(reason);
+    }
+
+    /// Returns the changes made to the battle state by the last applied event.
+    pub fn last_changes(&self) -> Changes<'_, R> {
+        Changes {
+            entities: &self.state.entities,
+            space: &self.state.space,
+        }
+    }
+
+    /// Returns a watcher for the hooks invoked while verifying an event of the given kind.
+    pub(crate) fn verify_watcher(&self, kind: EventKind) -> Watcher {
+        Watcher {
+            watchdog: self.watchdog,
+            event: Some((None, kind)),
+        }
+    }
+
+    /// Returns a watcher for the hooks invoked while applying the current event.
+    pub(crate) fn apply_watcher(&self) -> Watcher {
+        Watcher {
+            watchdog: self.watchdog,
+            event: self.applying.map(|(id, kind)| (Some(id), kind)),
+        }
+    }
+
+    /// Returns the watchdog configuration of this battle, if any.
+    pub fn watchdog(&self) -> Option<&Watchdog> {
+        self.watchdog.as_ref()
     }
 
     /// Returns in which phase is the battle.
@@ -142,6 +275,19 @@ impl<R: BattleRules + 'static> Battle<R> {
         self.state.phase
     }
 
+    /// Returns why the battle ended, or `None` if the battle is still going on.
+    pub fn end_reason(&self) -> Option<EndReason> {
+        self.state.end_reason
+    }
+
+    /// Returns the outcome of the battle.
+    ///
+    /// The outcome is `None` while the battle is still going on, as well as when
+    /// the battle ended without an outcome, for instance because it was aborted.
+    pub fn outcome(&self) -> Option<&BattleOutcome<R>> {
+        self.state.outcome.as_ref()
+    }
+
     /// Returns a reference to the entities manager for this battle.
     pub fn entities(&self) -> &Entities<R> {
         &self.state.entities
@@ -152,6 +298,16 @@ impl<R: BattleRules + 'static> Battle<R> {
         &mut self.state.entities
     }
 
+    /// Returns the map of the entities revealed to each team.
+    pub fn visibility(&self) -> &Visibility<R> {
+        &self.state.visibility
+    }
+
+    /// Returns the registry of the players taking part in this battle.
+    pub fn players(&self) -> &Players<R> {
+        &self.state.players
+    }
+
     /// Returns the history of this battle.
     pub fn history(&self) -> &History<R> {
         &self.history
@@ -162,6 +318,184 @@ impl<R: BattleRules + 'static> Battle<R> {
         &self.rules
     }
 
+    /// Returns the static data of this battle's rules.
+    pub fn rules_data(&self) -> &Arc<RulesData<R>> {
+        &self.state.rules_data
+    }
+
+    /// Returns the host services injected into this battle.
+    pub fn extensions(&self) -> &Extensions {
+        &self.state.extensions
+    }
+
+    /// Returns the registry of all user events known by this battle.
+    pub fn user_events(&self) -> &UserEventRegistry<R> {
+        &self.user_events
+    }
+
+    /// Returns the handshake to be sent to a server, to verify that the rules are compatible.
+    pub fn handshake(&self) -> Handshake<R> {
+        Handshake {
+            version: self.rules.version().clone(),
+            data_hash: self.state.rules_data.data_hash(),
+        }
+    }
+
+    /// Returns a deterministic hash of the current state of this battle.
+    ///
+    /// The digest covers the battle phase, the progress of rounds, teams with their powers
+    /// and resources, creatures, objects with their owners, their positions, their tags,
+    /// their statistics, statuses and abilities, the visibility of entities and the ids of
+    /// registered players.
+    /// Statistics, statuses, abilities, powers and positions are written by the `digest_*`
+    /// methods of the respective rules, which by default cover only their ids
+    /// (the debug representation for positions).\
+    /// The state of the rules themselves, such as the models of rounds or space,
+    /// is opaque to weasel: rules can add it through `BattleRules::digest`.\
+    /// Two battles that processed the same events have the same digest, thus clients
+    /// can compare their digest with the server's one to detect a desynchronization.
+    ///
+    /// # Examples
+    /// ```
+    /// use weasel::{
+    ///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateTeam,
+    ///     EventTrigger, Server,
+    /// };
+    ///
+    /// battle_rules! {}
+    ///
+    /// let mut first = Server::builder(Battle::builder(CustomRules::new()).build()).build();
+    /// let second = Server::builder(Battle::builder(CustomRules::new()).build()).build();
+    /// assert_eq!(first.battle().state_digest(), second.battle().state_digest());
+    ///
+    /// CreateTeam::trigger(&mut first, 1).fire().unwrap();
+    /// assert_ne!(first.battle().state_digest(), second.battle().state_digest());
+    /// ```
+    pub fn state_digest(&self) -> u64 {
+        let mut hasher = DigestHasher::new();
+        let state = &self.state;
+        state.phase.hash(&mut hasher);
+        let rounds = &state.rounds;
+        hasher.write_u32(rounds.completed_rounds());
+        hasher.write_u32(rounds.completed_turns());
+        hasher.write_u64(rounds.ticks());
+        let team_rules = self.rules.team_rules();
+        let character_rules = self.rules.character_rules();
+        let actor_rules = self.rules.actor_rules();
+        let space_rules = state.space.rules();
+        let entities = &state.entities;
+        for team in entities.teams() {
+            team.id().hash(&mut hasher);
+            team.creatures().for_each(|id| id.hash(&mut hasher));
+            team.powers()
+                .for_each(|power| team_rules.digest_power(power, &mut hasher));
+            for (resource, amount) in team.resources() {
+                hasher.write_u16(resource);
+                hasher.write_u64(amount);
+            }
+            team.conclusion().hash(&mut hasher);
+            hasher.write_i64(team.score());
+        }
+        for entity in entities.entities() {
+            entity.entity_id().hash(&mut hasher);
+            space_rules.digest_position(entity.position(), &mut hasher);
+            entities
+                .tags(entity.entity_id())
+                .for_each(|tag| hasher.write_u16(tag));
+        }
+        for character in entities.characters() {
+            for statistic in character.statistics() {
+                character_rules.digest_statistic(statistic, &mut hasher);
+            }
+            for status in character.statuses() {
+                character_rules.digest_status(status.status(), &mut hasher);
+                hasher.write_u32(status.duration());
+            }
+        }
+        for actor in entities.actors() {
+            actor.team_id().hash(&mut hasher);
+            for ability in actor.abilities() {
+                actor_rules.digest_ability(ability, &mut hasher);
+            }
+        }
+        for object in entities.objects() {
+            match object.owner() {
+                None => hasher.write_u8(0),
+                Some(ObjectOwner::Team(team_id)) => {
+                    hasher.write_u8(1);
+                    team_id.hash(&mut hasher);
+                }
+                Some(ObjectOwner::Player(player_id)) => {
+                    hasher.write_u8(2);
+                    hasher.write_u64(*player_id);
+                }
+            }
+        }
+        for (team_id, entity_id) in state.visibility.revealed() {
+            team_id.hash(&mut hasher);
+            entity_id.hash(&mut hasher);
+        }
+        for player in state.players.players() {
+            hasher.write_u64(player.id());
+        }
+        self.rules.digest(state, &mut hasher);
+        hasher.finish()
+    }
+
+    /// Returns a description of the rules used by this battle, listing the type and the
+    /// enabled features of each rules module.
+    pub fn describe(&self) -> RulesDescription<R> {
+        let modules = vec![
+            RulesDescriptor::new::<R::TR>("team", TeamRules::features(self.rules.team_rules())),
+            RulesDescriptor::new::<R::CR>(
+                "character",
+                CharacterRules::features(self.rules.character_rules()),
+            ),
+            RulesDescriptor::new::<R::AR>("actor", ActorRules::features(self.rules.actor_rules())),
+            RulesDescriptor::new::<R::FR>("fight", FightRules::features(self.rules.fight_rules())),
+            RulesDescriptor::new::<R::UR>("user", UserRules::features(self.rules.user_rules())),
+            RulesDescriptor::new::<R::SR>("space", SpaceRules::features(self.state.space.rules())),
+            RulesDescriptor::new::<R::RR>(
+                "rounds",
+                RoundsRules::features(self.state.rounds.rules()),
+            ),
+            RulesDescriptor::new::<R::ER>("entropy", EntropyRules::features(self.entropy.rules())),
+            RulesDescriptor::new::<R::XR>(
+                "reaction",
+                ReactionRules::features(self.rules.reaction_rules()),
+            ),
+            RulesDescriptor::new::<R::PR>(
+                "player",
+                PlayerRules::features(self.rules.player_rules()),
+            ),
+        ];
+        RulesDescription {
+            name: type_name::<R>().to_string(),
+            version: self.rules.version().clone(),
+            data_hash: self.state.rules_data.data_hash(),
+            modules,
+        }
+    }
+
+    /// Verifies that the handshake of another controller is compatible with this battle.
+    pub(crate) fn verify_handshake(&self, handshake: &Handshake<R>) -> WeaselResult<(), R> {
+        let version = self.rules.version();
+        if handshake.version != *version {
+            return Err(WeaselError::IncompatibleVersions(
+                handshake.version.clone(),
+                version.clone(),
+            ));
+        }
+        let data_hash = self.state.rules_data.data_hash();
+        if handshake.data_hash != data_hash {
+            return Err(WeaselError::IncompatibleRulesData(
+                handshake.data_hash,
+                data_hash,
+            ));
+        }
+        Ok(())
+    }
+
     /// Returns a mutable reference to this battle's rules.
     pub fn rules_mut(&mut self) -> &mut R {
         &mut self.rules
@@ -187,6 +521,66 @@ impl<R: BattleRules + 'static> Battle<R> {
         &mut self.entropy
     }
 
+    /// Returns all events that the actor with the given id could legally fire right now.
+    ///
+    /// Candidates are collected from the rules:
+    /// - an `ActivateAbility` for each ability and activation returned by
+    ///   `ActorRules::valid_activations`.
+    /// - a `MoveEntity` for each position returned by `SpaceRules::allowed_moves`.
+    /// - an `EndTurn`, if the actor is acting in the current turn.
+    ///
+    /// Only the candidates passing verification are returned.
+    /// The list is empty if the entity is not an actor.
+    pub fn legal_actions(&self, entity_id: &EntityId<R>) -> Vec<Box<dyn Event<R> + Send>>
+    where
+        R: 'static,
+    {
+        let (actor, entity) = match (
+            self.entities().actor(entity_id),
+            self.entities().entity(entity_id),
+        ) {
+            (Some(actor), Some(entity)) => (actor, entity),
+            _ => return Vec::new(),
+        };
+        let mut candidates = Vec::new();
+        // Abilities.
+        for ability in actor.abilities() {
+            for activation in
+                self.rules
+                    .actor_rules()
+                    .valid_activations(&self.state, actor, ability)
+            {
+                let ability_id = ability.id().clone();
+                candidates.push(match activation {
+                    Some(activation) => {
+                        ActivateAbility::trigger(&mut (), entity_id.clone(), ability_id)
+                            .activation(activation)
+                            .event()
+                    }
+                    None => {
+                        ActivateAbility::trigger(&mut (), entity_id.clone(), ability_id).event()
+                    }
+                });
+            }
+        }
+        // Movements.
+        for position in self
+            .space()
+            .rules()
+            .allowed_moves(self.space().model(), entity)
+        {
+            candidates.push(MoveEntity::trigger(&mut (), entity_id.clone(), position).event());
+        }
+        // End of turn.
+        if self.rounds().is_acting(entity_id) {
+            candidates.push(EndTurn::trigger(&mut ()).event());
+        }
+        candidates
+            .into_iter()
+            .filter(|event| event.verify(self).is_ok())
+            .collect()
+    }
+
     /// Returns a reference to the rounds manager for this battle.
     pub fn rounds(&self) -> &Rounds<R> {
         &self.state.rounds
@@ -218,29 +612,183 @@ impl<R: BattleRules + 'static> Battle<R> {
     ) -> RightsHandleMut<R, impl Iterator<Item = &'a TeamId<R>>> {
         RightsHandleMut::new(
             &mut self.rights,
-            self.state.entities().teams().map(|team| team.id()),
+            self.state.entities.teams().map(|team| team.id()),
+            &self.state.entities,
         )
     }
 
-    /// Returns an iterator over all history events in a range, versioned.
+    /// Returns an iterator over all history events in a range of ids, versioned.
     ///
     /// The range must be valid.
     pub fn versioned_events<'a>(
         &'a self,
         range: Range<usize>,
     ) -> impl Iterator<Item = VersionedEventWrapper<R>> + 'a {
-        self.history().events()[range]
+        let first_id = self.history().first_id() as usize;
+        self.history().events()[range.start - first_id..range.end - first_id]
             .iter()
             .map(move |e| e.clone().version(self.rules().version().clone()))
     }
 
+    /// Writes the history events in a range of ids into `writer`, encoded with `format`.
+    ///
+    /// Events are converted and written one at a time, thus the memory used doesn't grow
+    /// with the size of the range.
+    ///
+    /// # Examples
+    /// ```
+    /// use weasel::{
+    ///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateTeam,
+    ///     EventTrigger, Server,
+    /// };
+    ///
+    /// battle_rules! {}
+    ///
+    /// let battle = Battle::builder(CustomRules::new()).build();
+    /// let mut server = Server::builder(battle).build();
+    /// CreateTeam::trigger(&mut server, 1).fire().unwrap();
+    /// CreateTeam::trigger(&mut server, 2).fire().unwrap();
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut format = |writer: &mut dyn std::io::Write, event: &_| {
+    ///     serde_json::to_writer(&mut *writer, event)?;
+    ///     writeln!(writer)
+    /// };
+    /// let result = server.battle().write_events(0..2, &mut buffer, &mut format);
+    /// assert!(result.is_ok());
+    /// assert_eq!(String::from_utf8(buffer).unwrap().lines().count(), 2);
+    /// ```
+    #[cfg(feature = "serialization")]
+    pub fn write_events<W, F>(
+        &self,
+        range: Range<EventId>,
+        writer: &mut W,
+        format: &mut F,
+    ) -> WeaselResult<(), R>
+    where
+        W: std::io::Write,
+        F: EventFormat<R>,
+    {
+        self.write_events_with_progress(range, writer, format, |_, _| {})
+    }
+
+    /// Same as `write_events`, but invokes `progress` after each event is written.
+    ///
+    /// `progress` receives the number of events written so far and the total.
+    #[cfg(feature = "serialization")]
+    pub fn write_events_with_progress<W, F, P>(
+        &self,
+        range: Range<EventId>,
+        writer: &mut W,
+        format: &mut F,
+        mut progress: P,
+    ) -> WeaselResult<(), R>
+    where
+        W: std::io::Write,
+        F: EventFormat<R>,
+        P: FnMut(usize, usize),
+    {
+        let range = normalize_range(range, &self.history)?;
+        let total = range.end - range.start;
+        let start = range.start as EventId;
+        let writer: &mut dyn std::io::Write = writer;
+        let error = |id, err: std::io::Error| WeaselError::EventWriteError(id, err.to_string());
+        format.begin(writer).map_err(|err| error(start, err))?;
+        for (index, event) in self.versioned_events(range).enumerate() {
+            let id = event.id();
+            let event = FlatVersionedEvent::from(event);
+            format
+                .write_event(writer, &event, index)
+                .map_err(|err| error(id, err))?;
+            progress(index + 1, total);
+        }
+        format
+            .end(writer)
+            .map_err(|err| error(start + total as EventId, err))?;
+        writer
+            .flush()
+            .map_err(|err| error(start + total as EventId, err))
+    }
+
+    /// Writes the whole history into `writer`, encoded with `format`.
+    ///
+    /// Events are summarized with `BattleRules::summarize`.
+    #[cfg(feature = "serialization")]
+    pub fn export_history<W: std::io::Write>(
+        &self,
+        writer: &mut W,
+        format: ExportFormat,
+    ) -> WeaselResult<(), R> {
+        self.history
+            .export_with_summary(writer, format, |event| self.rules.summarize(event))
+    }
+
+    /// Takes a snapshot of the state of this battle, to restore it later with `from_snapshot`.
+    ///
+    /// The snapshot borrows the battle's state and it can be serialized as is.
+    /// See `BattleSnapshot` for the list of what is saved.
+    ///
+    /// # Examples
+    /// ```
+    /// use weasel::{
+    ///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateTeam,
+    ///     EventTrigger, RenameTeam, Server,
+    /// };
+    ///
+    /// battle_rules! {}
+    ///
+    /// let battle = Battle::builder(CustomRules::new()).build();
+    /// let mut server = Server::builder(battle).build();
+    /// CreateTeam::trigger(&mut server, 1).fire().unwrap();
+    /// RenameTeam::trigger(&mut server, 1, 2).fire().unwrap();
+    /// RenameTeam::trigger(&mut server, 2, 3).fire().unwrap();
+    ///
+    /// let snapshot = server.battle().snapshot();
+    /// let json = serde_json::to_string(&snapshot).unwrap();
+    /// let snapshot = serde_json::from_str(&json).unwrap();
+    ///
+    /// let battle = Battle::from_snapshot(Battle::builder(CustomRules::new()), snapshot).unwrap();
+    /// assert!(battle.entities().team(&3).is_some());
+    /// assert!(battle.history().is_empty());
+    /// assert_eq!(battle.history().first_id(), 3);
+    /// ```
+    #[cfg(feature = "serialization")]
+    pub fn snapshot(&self) -> BattleSnapshot<'_, R> {
+        BattleSnapshot::new(self)
+    }
+
+    /// Creates a new battle from `builder` and restores in it the state contained in `snapshot`.
+    ///
+    /// The history of the new battle is empty and it continues from the id of the next event
+    /// at the time the snapshot was taken.\
+    /// Returns an error if the snapshot was taken with a different version of the rules.
+    #[cfg(feature = "serialization")]
+    pub fn from_snapshot(
+        builder: BattleBuilder<R>,
+        snapshot: OwnedBattleSnapshot<R>,
+    ) -> WeaselResult<Self, R> {
+        let mut battle = builder.build();
+        let version = battle.rules.version();
+        if snapshot.version() != version {
+            return Err(WeaselError::IncompatibleVersions(
+                version.clone(),
+                snapshot.version().clone(),
+            ));
+        }
+        snapshot.restore(&mut battle);
+        let turns = battle.state.rounds.completed_turns();
+        battle.metrics.set_turn(turns);
+        Ok(battle)
+    }
+
     /// Checks if one or more teams have completed their objectives and creates events accordingly.
     pub(crate) fn check_objectives<P>(
-        state: &BattleState<R>,
+        state: &mut BattleState<R>,
         rules: &R::TR,
         metrics: &ReadMetrics<R>,
         processor: &mut P,
         checkpoint: Checkpoint,
+        watcher: Watcher,
     ) where
         P: EventProcessor<R>,
     {
@@ -252,7 +800,12 @@ impl<R: BattleRules + 'static> Battle<R> {
                     .teams()
                     .filter(|team| team.conclusion().is_none())
                 {
-                    if let Some(conclusion) = rules.$function(state, team, metrics) {
+                    let conclusion = watch!(
+                        watcher,
+                        concat!("TeamRules::", stringify!($function)),
+                        rules.$function(state, team, metrics)
+                    );
+                    if let Some(conclusion) = conclusion {
                         // Team has a conclusion, fire an event.
                         ConcludeObjectives::trigger(processor, team.id().clone(), conclusion)
                             .fire();
@@ -270,6 +823,22 @@ impl<R: BattleRules + 'static> Battle<R> {
                 run_check!(check_objectives_on_event);
             }
         }
+        // Update the progress towards the objectives of every team.
+        let progress: Vec<_> = state
+            .entities
+            .teams()
+            .map(|team| {
+                let progress = watch!(
+                    watcher,
+                    "TeamRules::objectives_progress",
+                    rules.objectives_progress(state, team, metrics)
+                );
+                (team.id().clone(), progress)
+            })
+            .collect();
+        for (team_id, progress) in progress {
+            state.entities.set_team_progress(&team_id, progress);
+        }
     }
 }
 
@@ -287,6 +856,12 @@ pub struct BattleState<R: BattleRules> {
     pub(crate) space: Space<R>,
     pub(crate) rounds: Rounds<R>,
     pub(crate) phase: BattlePhase,
+    pub(crate) end_reason: Option<EndReason>,
+    pub(crate) outcome: Option<BattleOutcome<R>>,
+    pub(crate) visibility: Visibility<R>,
+    pub(crate) players: Players<R>,
+    rules_data: Arc<RulesData<R>>,
+    extensions: Extensions,
 }
 
 impl<R: BattleRules> BattleState<R> {
@@ -300,6 +875,24 @@ impl<R: BattleRules> BattleState<R> {
         &self.space
     }
 
+    /// Returns an iterator over all entities whose position lies inside `area`.
+    pub fn entities_in_area<'a>(
+        &'a self,
+        area: &'a Area<R>,
+    ) -> impl Iterator<Item = &'a dyn Entity<R>> {
+        self.space.entities_in_area(&self.entities, area)
+    }
+
+    /// Returns an iterator over all entities within `radius` from `position`,
+    /// excluding those standing exactly in `position`.
+    pub fn neighbors<'a>(
+        &'a self,
+        position: &'a Position<R>,
+        radius: Distance,
+    ) -> impl Iterator<Item = &'a dyn Entity<R>> {
+        self.space.neighbors(&self.entities, position, radius)
+    }
+
     /// Returns the rounds manager for this battle.
     pub fn rounds(&self) -> &Rounds<R> {
         &self.rounds
@@ -309,17 +902,103 @@ impl<R: BattleRules> BattleState<R> {
     pub fn phase(&self) -> BattlePhase {
         self.phase
     }
+
+    /// Returns why the battle ended, or `None` if the battle is still going on.
+    pub fn end_reason(&self) -> Option<EndReason> {
+        self.end_reason
+    }
+
+    /// Returns the map of the entities revealed to each team.
+    pub fn visibility(&self) -> &Visibility<R> {
+        &self.visibility
+    }
+
+    /// Returns the registry of the players taking part in this battle.
+    pub fn players(&self) -> &Players<R> {
+        &self.players
+    }
+
+    /// Returns the static data of the rules this battle was built with.
+    pub fn rules_data(&self) -> &Arc<RulesData<R>> {
+        &self.rules_data
+    }
+
+    /// Returns the host services injected into this battle.
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+}
+
+/// A type-safe map of read-only host services, such as an asset database or
+/// a configuration, made available to the rules.
+///
+/// Extensions are set when the battle is built, through `BattleBuilder::extension`.
+/// Rules can access them from any hook receiving the `BattleState`,
+/// without resorting to global statics.\
+/// Extensions are local to the process: they are not part of the battle's history and
+/// they don't take part in the handshake between clients and server.
+#[derive(Default)]
+pub struct Extensions {
+    map: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl Extensions {
+    /// Returns the extension of type `T`, if present.
+    pub fn get<T: Any + Send + Sync>(&self) -> Option<&T> {
+        self.map
+            .get(&TypeId::of::<T>())
+            .and_then(|extension| extension.downcast_ref())
+    }
+
+    /// Returns true if there's an extension of type `T`.
+    pub fn contains<T: Any + Send + Sync>(&self) -> bool {
+        self.map.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Returns the number of extensions.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns true if there are no extensions.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Inserts an extension, replacing the previous one of the same type.
+    fn insert<T: Any + Send + Sync>(&mut self, extension: T) {
+        self.map.insert(TypeId::of::<T>(), Box::new(extension));
+    }
 }
 
 /// All possible phases in which a battle can be.
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub enum BattlePhase {
-    /// The battle has started.
+    /// The battle is being prepared, for instance to draft or ban characters.\
+    /// Only battles built with `BattleBuilder::setup_phase` begin in this phase.
+    /// A `StartBattle` event moves the battle to the `Started` phase.
+    Setup,
+    /// The battle has started and the fighting is in progress.
     Started,
     /// The battle has ended.
     Ended,
 }
 
+/// Why a battle ended.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum EndReason {
+    /// One or more teams achieved their objectives.
+    Victory,
+    /// No team prevailed.
+    Draw,
+    /// The battle was stopped by an administrator or by the host.
+    Aborted,
+    /// The battle ran out of time, for instance because it reached the round limit.
+    Timeout,
+}
+
 /// Contains the set of rules for this battle.
 /// It's a trait that uses composition to gather all other subsystem rules in a single place.
 ///
@@ -341,6 +1020,10 @@ pub trait BattleRules: Sized + Send {
     type RR: RoundsRules<Self>;
     /// Type defining the `EntropyRules`.
     type ER: EntropyRules;
+    /// Type defining the `ReactionRules`.
+    type XR: ReactionRules<Self>;
+    /// Type defining the `PlayerRules`.
+    type PR: PlayerRules<Self>;
 
     #[cfg(not(feature = "serialization"))]
     /// See [Version](type.Version.html).
@@ -364,6 +1047,12 @@ pub trait BattleRules: Sized + Send {
     /// Returns a reference to the user rules.
     fn user_rules(&self) -> &Self::UR;
 
+    /// Returns a reference to the reaction rules.
+    fn reaction_rules(&self) -> &Self::XR;
+
+    /// Returns a reference to the player rules.
+    fn player_rules(&self) -> &Self::PR;
+
     /// Consumes and returns the space rules.
     fn space_rules(&mut self) -> Self::SR;
 
@@ -373,117 +1062,811 @@ pub trait BattleRules: Sized + Send {
     /// Consumes and returns the entropy rules.
     fn entropy_rules(&mut self) -> Self::ER;
 
+    /// See [RulesData](type.RulesData.html).
+    type RulesData: StaticData;
+
     /// Returns the version of this battle rules.
     fn version(&self) -> &Self::Version;
-}
 
-/// Type to represent the version of this battle rules.
-/// It is used to verify each event. You can use `()` to disable versioning.
-pub type Version<R> = <R as BattleRules>::Version;
+    /// Returns a shared reference to the static data of this battle rules.
+    fn rules_data(&self) -> &Arc<Self::RulesData>;
 
-/// A trait for types that have control over a battle.
-pub trait BattleController<R: BattleRules> {
-    /// Returns a reference to the battle.
-    fn battle(&self) -> &Battle<R>;
+    /// Checks whether the battle as a whole is over.
+    /// This check is called after every event, once the teams' objectives are checked.
+    ///
+    /// When an outcome is returned, an `EndBattle` event carrying it is fired.
+    ///
+    /// The provided implementation never ends the battle.
+    fn check_battle_end(&self, _state: &BattleState<Self>) -> Option<BattleOutcome<Self>> {
+        None
+    }
 
-    /// Returns the current event callback set to the battle.
-    fn event_callback(&self) -> &Option<EventCallback<R>>;
+    /// Writes into `hasher` the data of `state` that is not known to weasel,
+    /// for instance the rounds and space models, to be included in `Battle::state_digest`.
+    ///
+    /// The provided implementation doesn't write anything.
+    fn digest(&self, _state: &BattleState<Self>, _hasher: &mut dyn Hasher) {}
 
-    /// Sets a new event callback for the battle.
-    /// The current callback is discarded.
-    fn set_event_callback(&mut self, callback: Option<EventCallback<R>>);
+    /// Returns whether events of the given kind can be fired while the battle is in `phase`.
+    /// Events not allowed fail their verification with `WeaselError::WrongBattlePhase`.
+    ///
+    /// Override this method to enforce stricter rules, for instance to allow the creation
+    /// of creatures only during the setup phase.
+    ///
+    /// The provided implementation forbids events related to turns, rounds, abilities and
+    /// powers during the setup phase, and allows everything else.
+    fn allow_in_phase(
+        &self,
+        _state: &BattleState<Self>,
+        kind: EventKind,
+        phase: BattlePhase,
+    ) -> bool {
+        match phase {
+            BattlePhase::Setup => !matches!(
+                kind,
+                EventKind::StartTurn
+                    | EventKind::EndTurn
+                    | EventKind::TurnTimedOut
+                    | EventKind::AddTurnActor
+                    | EventKind::RemoveTurnActor
+                    | EventKind::EndRound
+                    | EventKind::EnvironmentTurn
+                    | EventKind::Tick
+                    | EventKind::ActivateAbility
+                    | EventKind::InterruptAbility
+                    | EventKind::InvokePower
+            ),
+            _ => true,
+        }
+    }
+
+    /// Returns a short, human readable summary of `event`, used by `Battle::export_history`.
+    ///
+    /// The provided implementation returns the event's debug representation.
+    fn summarize(&self, event: &EventWrapper<Self>) -> String {
+        format!("{:?}", event.event())
+    }
 }
 
-/// A builder object to create a battle.
-pub struct BattleBuilder<R: BattleRules> {
-    rules: R,
-    event_callback: Option<EventCallback<R>>,
+/// Hasher producing the same digest regardless of the platform, based on FNV-1a.
+struct DigestHasher(u64);
+
+impl DigestHasher {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
 }
 
-impl<R: BattleRules> BattleBuilder<R> {
-    /// Sets an event callback that will be invoked each time an event is applied to the world.
-    pub fn event_callback(mut self, event_callback: EventCallback<R>) -> Self {
-        self.event_callback = Some(event_callback);
-        self
+impl Hasher for DigestHasher {
+    fn finish(&self) -> u64 {
+        self.0
     }
 
-    /// Creates a new battle.
-    pub fn build(mut self) -> Battle<R> {
-        Battle {
-            state: BattleState {
-                entities: Entities::new(),
-                space: Space::new(None, self.rules.space_rules()),
-                rounds: Rounds::new(None, self.rules.rounds_rules()),
-                phase: BattlePhase::Started,
-            },
-            entropy: Entropy::new(None, self.rules.entropy_rules()),
-            history: History::new(),
-            rules: self.rules,
-            event_callback: self.event_callback,
-            metrics: Metrics::new(),
-            rights: Rights::new(),
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
         }
     }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
 }
 
-/// Event to end the battle. After the battle has ended new events can't be processed.
-///
-/// # Examples
-/// ```
-/// use weasel::{
-///     battle_rules, rules::empty::*, battle::BattlePhase, Battle, BattleController,
-///     BattleRules, EndBattle, EventKind, EventTrigger, Server,
-/// };
-///
-/// battle_rules! {}
-///
-/// let battle = Battle::builder(CustomRules::new()).build();
-/// let mut server = Server::builder(battle).build();
-///
-/// EndBattle::trigger(&mut server).fire().unwrap();
-/// assert_eq!(server.battle().phase(), BattlePhase::Ended);
-/// ```
+/// Final result of a battle.
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
-pub struct EndBattle<R> {
-    #[cfg_attr(feature = "serialization", serde(skip))]
-    _phantom: PhantomData<R>,
+pub enum BattleOutcome<R: BattleRules> {
+    /// The given teams won the battle.
+    Victory(
+        #[cfg_attr(
+            feature = "serialization",
+            serde(bound(
+                serialize = "Vec<TeamId<R>>: Serialize",
+                deserialize = "Vec<TeamId<R>>: Deserialize<'de>"
+            ))
+        )]
+        Vec<TeamId<R>>,
+    ),
+    /// No team prevailed.
+    Draw,
 }
 
-impl<R: BattleRules> EndBattle<R> {
-    /// Returns a trigger for this event.
-    pub fn trigger<P: EventProcessor<R>>(processor: &mut P) -> EndBattleTrigger<R, P> {
-        EndBattleTrigger {
-            processor,
-            _phantom: PhantomData,
+impl<R: BattleRules> BattleOutcome<R> {
+    /// Returns the `EndReason` corresponding to this outcome.
+    pub fn reason(&self) -> EndReason {
+        match self {
+            BattleOutcome::Victory(_) => EndReason::Victory,
+            BattleOutcome::Draw => EndReason::Draw,
         }
     }
 }
 
-impl<R> std::fmt::Debug for EndBattle<R> {
+impl<R: BattleRules> Debug for BattleOutcome<R> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "EndBattle {{ }}")
+        match self {
+            BattleOutcome::Victory(teams) => write!(f, "BattleOutcome::Victory({:?})", teams),
+            BattleOutcome::Draw => write!(f, "BattleOutcome::Draw"),
+        }
     }
 }
 
-impl<R> Clone for EndBattle<R> {
+impl<R: BattleRules> Clone for BattleOutcome<R> {
     fn clone(&self) -> Self {
-        Self {
-            _phantom: PhantomData,
+        match self {
+            BattleOutcome::Victory(teams) => BattleOutcome::Victory(teams.clone()),
+            BattleOutcome::Draw => BattleOutcome::Draw,
         }
     }
 }
 
-impl<R: BattleRules + 'static> Event<R> for EndBattle<R> {
-    fn verify(&self, _battle: &Battle<R>) -> WeaselResult<(), R> {
-        // Battle can be ended at any moment.
-        Ok(())
+impl<R: BattleRules> PartialEq for BattleOutcome<R> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (BattleOutcome::Victory(lhs), BattleOutcome::Victory(rhs)) => lhs == rhs,
+            (BattleOutcome::Draw, BattleOutcome::Draw) => true,
+            _ => false,
+        }
     }
+}
 
-    fn apply(&self, battle: &mut Battle<R>, _: &mut Option<EventQueue<R>>) {
-        battle.end();
-    }
+/// Type to represent the version of this battle rules.
+/// It is used to verify each event. You can use `()` to disable versioning.
+pub type Version<R> = <R as BattleRules>::Version;
 
-    fn kind(&self) -> EventKind {
+/// Type to represent large static tables used by the rules, such as ability definitions
+/// or status templates.
+///
+/// Rules data is meant to be loaded once and shared through an `Arc` among all battles and
+/// controllers, instead of being duplicated in each battle. You can use `()` if your rules
+/// don't need any data.
+pub type RulesData<R> = <R as BattleRules>::RulesData;
+
+/// Trait for the static data used by battle rules.
+pub trait StaticData: Send + Sync {
+    /// Returns a hash of the content of this data.
+    ///
+    /// Clients and servers must have data with the same hash to play together.
+    /// The hash must not depend on the platform or on the compiler's version.
+    fn data_hash(&self) -> u64;
+}
+
+impl StaticData for () {
+    fn data_hash(&self) -> u64 {
+        0
+    }
+}
+
+/// Information exchanged by a client and a server to verify that they use compatible rules.
+///
+/// # Examples
+/// ```
+/// use weasel::{battle_rules, rules::empty::*, Battle, BattleRules, Server};
+///
+/// battle_rules! {}
+///
+/// let server = Server::builder(Battle::builder(CustomRules::new()).build()).build();
+/// let client_battle = Battle::builder(CustomRules::new()).build();
+///
+/// let handshake = client_battle.handshake();
+/// assert_eq!(server.verify_handshake(&handshake).err(), None);
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Handshake<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Version<R>: Serialize",
+            deserialize = "Version<R>: Deserialize<'de>"
+        ))
+    )]
+    version: Version<R>,
+
+    data_hash: u64,
+}
+
+impl<R: BattleRules> Handshake<R> {
+    /// Returns the version of the rules.
+    pub fn version(&self) -> &Version<R> {
+        &self.version
+    }
+
+    /// Returns the hash of the rules data.
+    pub fn data_hash(&self) -> u64 {
+        self.data_hash
+    }
+}
+
+impl<R: BattleRules> Debug for Handshake<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Handshake {{ version: {:?}, data_hash: {:?} }}",
+            self.version, self.data_hash
+        )
+    }
+}
+
+impl<R: BattleRules> Clone for Handshake<R> {
+    fn clone(&self) -> Self {
+        Self {
+            version: self.version.clone(),
+            data_hash: self.data_hash,
+        }
+    }
+}
+
+/// Description of the rules used by a battle, meant for debugging tools.
+///
+/// # Examples
+/// ```
+/// use weasel::{battle_rules, rules::empty::*, Battle, BattleRules};
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let description = battle.describe();
+/// assert_eq!(description.modules().count(), 10);
+/// assert_eq!(description.module("rounds").unwrap().features().count(), 0);
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct RulesDescription<R: BattleRules> {
+    name: String,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Version<R>: Serialize",
+            deserialize = "Version<R>: Deserialize<'de>"
+        ))
+    )]
+    version: Version<R>,
+
+    data_hash: u64,
+
+    modules: Vec<RulesDescriptor>,
+}
+
+impl<R: BattleRules> RulesDescription<R> {
+    /// Returns the type name of the battle rules.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the version of the rules.
+    pub fn version(&self) -> &Version<R> {
+        &self.version
+    }
+
+    /// Returns the hash of the rules data.
+    pub fn data_hash(&self) -> u64 {
+        self.data_hash
+    }
+
+    /// Returns an iterator over the descriptors of all rules modules.
+    pub fn modules(&self) -> impl Iterator<Item = &RulesDescriptor> {
+        self.modules.iter()
+    }
+
+    /// Returns the descriptor of the rules module with the given role, if any.
+    pub fn module(&self, role: &str) -> Option<&RulesDescriptor> {
+        self.modules.iter().find(|module| module.role == role)
+    }
+}
+
+impl<R: BattleRules> Debug for RulesDescription<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "RulesDescription {{ name: {:?}, version: {:?}, data_hash: {:?}, modules: {:?} }}",
+            self.name, self.version, self.data_hash, self.modules
+        )
+    }
+}
+
+impl<R: BattleRules> Clone for RulesDescription<R> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            version: self.version.clone(),
+            data_hash: self.data_hash,
+            modules: self.modules.clone(),
+        }
+    }
+}
+
+/// Description of a single rules module, such as the team rules or the rounds rules.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct RulesDescriptor {
+    role: String,
+    name: String,
+    features: Vec<String>,
+}
+
+impl RulesDescriptor {
+    fn new<T: ?Sized>(role: &str, features: Vec<&'static str>) -> Self {
+        Self {
+            role: role.to_string(),
+            name: type_name::<T>().to_string(),
+            features: features.into_iter().map(str::to_string).collect(),
+        }
+    }
+
+    /// Returns the role of this module inside the battle rules, for instance `"team"`.
+    pub fn role(&self) -> &str {
+        &self.role
+    }
+
+    /// Returns the type name of this module.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns an iterator over the names of the features enabled in this module.
+    pub fn features(&self) -> impl Iterator<Item = &str> {
+        self.features.iter().map(String::as_str)
+    }
+}
+
+/// A trait for types that have control over a battle.
+pub trait BattleController<R: BattleRules> {
+    /// Returns a reference to the battle.
+    fn battle(&self) -> &Battle<R>;
+
+    /// Returns the current event callback set to the battle.
+    fn event_callback(&self) -> &Option<EventCallback<R>>;
+
+    /// Sets a new event callback for the battle.
+    /// The current callback is discarded.
+    fn set_event_callback(&mut self, callback: Option<EventCallback<R>>);
+}
+
+/// A builder object to create a battle.
+pub struct BattleBuilder<R: BattleRules> {
+    rules: R,
+    event_callback: Option<EventCallback<R>>,
+    watchdog: Option<Watchdog>,
+    round_limit: Option<RoundsCount>,
+    extensions: Extensions,
+    creatures_capacity: usize,
+    objects_capacity: usize,
+    setup_phase: bool,
+}
+
+impl<R: BattleRules> BattleBuilder<R> {
+    /// Sets an event callback that will be invoked each time an event is applied to the world.
+    pub fn event_callback(mut self, event_callback: EventCallback<R>) -> Self {
+        self.event_callback = Some(event_callback);
+        self
+    }
+
+    /// Sets a watchdog to report rules callbacks exceeding a time budget.
+    pub fn watchdog(mut self, watchdog: Watchdog) -> Self {
+        self.watchdog = Some(watchdog);
+        self
+    }
+
+    /// Ends the battle automatically after `rounds` rounds have been completed.
+    ///
+    /// When the last round ends, every team without a conclusion is concluded according to
+    /// [conclusion_on_round_limit](../team/trait.TeamRules.html#method.conclusion_on_round_limit)
+    /// and then the battle ends.\
+    /// The derived events are generated by the server, thus clients don't need this option.
+    ///
+    /// # Examples
+    /// ```
+    /// use weasel::battle::BattlePhase;
+    /// use weasel::{
+    ///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, EndRound,
+    ///     EventTrigger, Server,
+    /// };
+    ///
+    /// battle_rules! {}
+    ///
+    /// let battle = Battle::builder(CustomRules::new()).round_limit(2).build();
+    /// let mut server = Server::builder(battle).build();
+    ///
+    /// EndRound::trigger(&mut server).fire().unwrap();
+    /// assert_eq!(server.battle().phase(), BattlePhase::Started);
+    /// EndRound::trigger(&mut server).fire().unwrap();
+    /// assert_eq!(server.battle().phase(), BattlePhase::Ended);
+    /// ```
+    pub fn round_limit(mut self, rounds: RoundsCount) -> Self {
+        self.round_limit = Some(rounds);
+        self
+    }
+
+    /// Injects a read-only host service, retrievable by the rules through
+    /// `BattleState::extensions`. An extension replaces any previous one of the same type.
+    ///
+    /// # Examples
+    /// ```
+    /// use weasel::{battle_rules, rules::empty::*, Battle, BattleRules};
+    ///
+    /// battle_rules! {}
+    ///
+    /// struct Config {
+    ///     difficulty: u8,
+    /// }
+    ///
+    /// let battle = Battle::builder(CustomRules::new())
+    ///     .extension(Config { difficulty: 3 })
+    ///     .build();
+    /// let config = battle.extensions().get::<Config>().unwrap();
+    /// assert_eq!(config.difficulty, 3);
+    /// ```
+    pub fn extension<T: Any + Send + Sync>(mut self, extension: T) -> Self {
+        self.extensions.insert(extension);
+        self
+    }
+
+    /// Pre-allocates the entities storage to hold at least `creatures` creatures and
+    /// `objects` objects without reallocating.
+    ///
+    /// # Examples
+    /// ```
+    /// use weasel::{battle_rules, rules::empty::*, Battle, BattleRules};
+    ///
+    /// battle_rules! {}
+    ///
+    /// let battle = Battle::builder(CustomRules::new())
+    ///     .entities_capacity(1000, 10)
+    ///     .build();
+    /// assert!(battle.entities().creatures_capacity() >= 1000);
+    /// assert!(battle.entities().objects_capacity() >= 10);
+    /// ```
+    pub fn entities_capacity(mut self, creatures: usize, objects: usize) -> Self {
+        self.creatures_capacity = creatures;
+        self.objects_capacity = objects;
+        self
+    }
+
+    /// Begins the battle in the `Setup` phase, rather than in the `Started` one.
+    ///
+    /// Which events can be fired in each phase is decided by `BattleRules::allow_in_phase`.
+    /// Clients must build their battles in the same way as the server.
+    ///
+    /// # Examples
+    /// ```
+    /// use weasel::battle::BattlePhase;
+    /// use weasel::{
+    ///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateTeam,
+    ///     EndRound, EventTrigger, Server, StartBattle,
+    /// };
+    ///
+    /// battle_rules! {}
+    ///
+    /// let battle = Battle::builder(CustomRules::new()).setup_phase().build();
+    /// let mut server = Server::builder(battle).build();
+    /// assert_eq!(server.battle().phase(), BattlePhase::Setup);
+    ///
+    /// CreateTeam::trigger(&mut server, 1).fire().unwrap();
+    /// assert!(EndRound::trigger(&mut server).fire().is_err());
+    ///
+    /// StartBattle::trigger(&mut server).fire().unwrap();
+    /// assert_eq!(server.battle().phase(), BattlePhase::Started);
+    /// assert!(EndRound::trigger(&mut server).fire().is_ok());
+    /// ```
+    pub fn setup_phase(mut self) -> Self {
+        self.setup_phase = true;
+        self
+    }
+
+    /// Creates a new battle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the user rules fail to register their user events.
+    pub fn build(mut self) -> Battle<R> {
+        let mut user_events = UserEventRegistry::new();
+        if let Err(err) = self
+            .rules
+            .user_rules()
+            .register_user_events(&mut user_events)
+        {
+            panic!("failed to register user events: {}", err);
+        }
+        let mut rounds = Rounds::new(None, self.rules.rounds_rules());
+        rounds.set_round_limit(self.round_limit);
+        Battle {
+            state: BattleState {
+                entities: Entities::with_capacity(self.creatures_capacity, self.objects_capacity),
+                space: Space::new(None, self.rules.space_rules()),
+                rounds,
+                phase: if self.setup_phase {
+                    BattlePhase::Setup
+                } else {
+                    BattlePhase::Started
+                },
+                end_reason: None,
+                outcome: None,
+                visibility: Visibility::new(),
+                players: Players::new(),
+                rules_data: self.rules.rules_data().clone(),
+                extensions: self.extensions,
+            },
+            entropy: Entropy::new(None, self.rules.entropy_rules()),
+            history: History::new(),
+            rules: self.rules,
+            event_callback: self.event_callback,
+            metrics: Metrics::new(),
+            watchdog: self.watchdog,
+            applying: None,
+            rights: Rights::new(),
+            user_events,
+            setup_phase: self.setup_phase,
+        }
+    }
+}
+
+/// Parts of the battle state touched by the last applied event.
+///
+/// Changes are reset each time a new event is applied. They allow to refresh views or caches
+/// incrementally, rather than reading again the whole battle state.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateTeam,
+///     EventTrigger, Server,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// let team_id = 1;
+/// CreateTeam::trigger(&mut server, team_id).fire().unwrap();
+/// let changes = server.battle().last_changes();
+/// assert_eq!(changes.teams().collect::<Vec<_>>(), vec![&team_id]);
+/// assert_eq!(changes.entities().count(), 0);
+/// ```
+pub struct Changes<'a, R: BattleRules> {
+    entities: &'a Entities<R>,
+    space: &'a Space<R>,
+}
+
+impl<'a, R: BattleRules> Changes<'a, R> {
+    /// Returns an iterator over the ids of all entities that were added, removed or modified.
+    pub fn entities(&self) -> impl Iterator<Item = &'a EntityId<R>> {
+        self.entities.changed_entities()
+    }
+
+    /// Returns an iterator over the ids of all teams that were added, removed or modified.
+    pub fn teams(&self) -> impl Iterator<Item = &'a TeamId<R>> {
+        self.entities.changed_teams()
+    }
+
+    /// Returns an iterator over all positions left or occupied by an entity.
+    pub fn positions(&self) -> impl Iterator<Item = &'a Position<R>> {
+        self.space.changed_positions()
+    }
+
+    /// Returns true if the space model was altered as a whole.
+    pub fn space_altered(&self) -> bool {
+        self.space.altered()
+    }
+
+    /// Returns true if nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.entities().next().is_none()
+            && self.teams().next().is_none()
+            && self.positions().next().is_none()
+            && !self.space_altered()
+    }
+}
+
+/// Configuration to detect rules callbacks taking longer than a given time budget.
+///
+/// Each time an event is verified or applied, the watchdog measures how long the hooks
+/// invoked by the battle take. Hooks exceeding the budget are logged as warnings,
+/// together with the offending event and the name of the hook,
+/// for instance `CharacterRules::on_threshold_crossed`.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use weasel::{battle::Watchdog, battle_rules, rules::empty::*, Battle, BattleRules};
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new())
+///     .watchdog(Watchdog::new(Duration::from_millis(10)))
+///     .build();
+/// assert_eq!(
+///     battle.watchdog().map(|w| w.budget()),
+///     Some(Duration::from_millis(10))
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Watchdog {
+    budget: Duration,
+    abort: bool,
+}
+
+impl Watchdog {
+    /// Creates a new watchdog that logs all hooks taking longer than `budget`.
+    pub fn new(budget: Duration) -> Self {
+        Self {
+            budget,
+            abort: false,
+        }
+    }
+
+    /// Sets whether or not the watchdog should panic when a hook exceeds the time budget.
+    ///
+    /// This option is effective only in debug builds.
+    pub fn abort(mut self, abort: bool) -> Self {
+        self.abort = abort;
+        self
+    }
+
+    /// Returns the time budget allotted to each hook.
+    pub fn budget(&self) -> Duration {
+        self.budget
+    }
+
+    /// Returns `true` if this watchdog panics in debug builds when the time budget is exceeded.
+    pub fn aborts(&self) -> bool {
+        self.abort
+    }
+
+    /// Reports `hook` if `elapsed` is greater than the time budget.
+    fn check(&self, hook: &str, id: Option<EventId>, kind: EventKind, elapsed: Duration) {
+        if elapsed <= self.budget {
+            return;
+        }
+        let message = format!(
+            "hook {} of event {:?} (id: {:?}) exceeded time budget: {:?} > {:?}",
+            hook, kind, id, elapsed, self.budget
+        );
+        if cfg!(debug_assertions) && self.abort {
+            panic!("{}", message);
+        }
+        warn!("{}", message);
+    }
+}
+
+/// Reports the rules hooks invoked while processing an event to the battle's watchdog.
+#[derive(Clone, Copy)]
+pub(crate) struct Watcher {
+    watchdog: Option<Watchdog>,
+    event: Option<(Option<EventId>, EventKind)>,
+}
+
+impl Watcher {
+    /// Returns the instant in which a hook started, if there's a watchdog.
+    pub(crate) fn start(self) -> Option<Instant> {
+        self.watchdog.map(|_| Instant::now())
+    }
+
+    /// Reports `hook` if the time elapsed since `start` exceeds the time budget.
+    pub(crate) fn finish(self, hook: &str, start: Option<Instant>) {
+        if let (Some(watchdog), Some((id, kind)), Some(start)) = (self.watchdog, self.event, start)
+        {
+            watchdog.check(hook, id, kind, start.elapsed());
+        }
+    }
+}
+
+/// Event to end the battle. After the battle has ended new events can't be processed.
+///
+/// The reason why the battle ended is stored in the battle and it's reported
+/// by the error returned for any event fired afterwards.
+/// If not specified, the reason is `EndReason::Aborted`.\
+/// Optionally, the event can carry the `BattleOutcome`, later returned by `Battle::outcome`.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, rules::empty::*, battle::BattlePhase, Battle, BattleController,
+///     BattleRules, EndBattle, EndReason, EventKind, EventTrigger, ResetEntropy, Server,
+///     WeaselError,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// EndBattle::trigger(&mut server)
+///     .reason(EndReason::Draw)
+///     .fire()
+///     .unwrap();
+/// assert_eq!(server.battle().phase(), BattlePhase::Ended);
+/// assert_eq!(server.battle().end_reason(), Some(EndReason::Draw));
+///
+/// let result = ResetEntropy::trigger(&mut server).fire();
+/// assert_eq!(
+///     result.err().map(|e| e.unfold()),
+///     Some(WeaselError::BattleEnded(EndReason::Draw))
+/// );
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct EndBattle<R: BattleRules> {
+    reason: EndReason,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Option<BattleOutcome<R>>: Serialize",
+            deserialize = "Option<BattleOutcome<R>>: Deserialize<'de>"
+        ))
+    )]
+    outcome: Option<BattleOutcome<R>>,
+
+    #[cfg_attr(feature = "serialization", serde(skip))]
+    _phantom: PhantomData<R>,
+}
+
+impl<R: BattleRules> EndBattle<R> {
+    /// Returns a trigger for this event.
+    pub fn trigger<P: EventProcessor<R>>(processor: &mut P) -> EndBattleTrigger<R, P> {
+        EndBattleTrigger {
+            processor,
+            reason: EndReason::Aborted,
+            outcome: None,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns why the battle ended.
+    pub fn reason(&self) -> EndReason {
+        self.reason
+    }
+
+    /// Returns the outcome of the battle, if any.
+    pub fn outcome(&self) -> &Option<BattleOutcome<R>> {
+        &self.outcome
+    }
+}
+
+impl<R: BattleRules> std::fmt::Debug for EndBattle<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "EndBattle {{ reason: {:?}, outcome: {:?} }}",
+            self.reason, self.outcome
+        )
+    }
+}
+
+impl<R: BattleRules> Clone for EndBattle<R> {
+    fn clone(&self) -> Self {
+        Self {
+            reason: self.reason,
+            outcome: self.outcome.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<R: BattleRules + 'static> Event<R> for EndBattle<R> {
+    fn verify(&self, _battle: &Battle<R>) -> WeaselResult<(), R> {
+        // Battle can be ended at any moment.
+        Ok(())
+    }
+
+    fn apply(&self, battle: &mut Battle<R>, _: &mut Option<EventQueue<R>>) {
+        battle.end(self.reason);
+        battle.state.outcome = self.outcome.clone();
+    }
+
+    fn kind(&self) -> EventKind {
         EventKind::EndBattle
     }
 
@@ -503,9 +1886,30 @@ where
     P: EventProcessor<R>,
 {
     processor: &'a mut P,
+    reason: EndReason,
+    outcome: Option<BattleOutcome<R>>,
     _phantom: PhantomData<R>,
 }
 
+impl<'a, R, P> EndBattleTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    /// Sets why the battle ended.
+    pub fn reason(&'a mut self, reason: EndReason) -> &'a mut Self {
+        self.reason = reason;
+        self
+    }
+
+    /// Sets the outcome of the battle. The reason is set accordingly.
+    pub fn outcome(&'a mut self, outcome: BattleOutcome<R>) -> &'a mut Self {
+        self.reason = outcome.reason();
+        self.outcome = Some(outcome);
+        self
+    }
+}
+
 impl<'a, R, P> EventTrigger<'a, R, P> for EndBattleTrigger<'a, R, P>
 where
     R: BattleRules + 'static,
@@ -518,11 +1922,239 @@ where
     /// Returns an `EndBattle` event.
     fn event(&self) -> Box<dyn Event<R> + Send> {
         Box::new(EndBattle {
+            reason: self.reason,
+            outcome: self.outcome.clone(),
             _phantom: self._phantom,
         })
     }
 }
 
+/// Event to move a battle from the `Setup` phase to the `Started` one.
+///
+/// # Examples
+/// ```
+/// use weasel::battle::BattlePhase;
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, EventTrigger,
+///     Server, StartBattle,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).setup_phase().build();
+/// let mut server = Server::builder(battle).build();
+///
+/// StartBattle::trigger(&mut server).fire().unwrap();
+/// assert_eq!(server.battle().phase(), BattlePhase::Started);
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct StartBattle<R> {
+    #[cfg_attr(feature = "serialization", serde(skip))]
+    _phantom: PhantomData<R>,
+}
+
+impl<R: BattleRules> StartBattle<R> {
+    /// Returns a trigger for this event.
+    pub fn trigger<P: EventProcessor<R>>(processor: &mut P) -> StartBattleTrigger<'_, R, P> {
+        StartBattleTrigger {
+            processor,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<R> std::fmt::Debug for StartBattle<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "StartBattle {{ }}")
+    }
+}
+
+impl<R> Clone for StartBattle<R> {
+    fn clone(&self) -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<R: BattleRules + 'static> Event<R> for StartBattle<R> {
+    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
+        // The battle can start only from the setup phase.
+        if battle.phase() != BattlePhase::Setup {
+            return Err(WeaselError::WrongBattlePhase(
+                EventKind::StartBattle,
+                battle.phase(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn apply(&self, battle: &mut Battle<R>, _: &mut Option<EventQueue<R>>) {
+        battle.state.phase = BattlePhase::Started;
+    }
+
+    fn kind(&self) -> EventKind {
+        EventKind::StartBattle
+    }
+
+    fn box_clone(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Trigger to build and fire a `StartBattle` event.
+pub struct StartBattleTrigger<'a, R, P>
+where
+    R: BattleRules,
+    P: EventProcessor<R>,
+{
+    processor: &'a mut P,
+    _phantom: PhantomData<R>,
+}
+
+impl<'a, R, P> EventTrigger<'a, R, P> for StartBattleTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    fn processor(&'a mut self) -> &'a mut P {
+        self.processor
+    }
+
+    /// Returns a `StartBattle` event.
+    fn event(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(StartBattle {
+            _phantom: self._phantom,
+        })
+    }
+}
+
+/// Event to verify that the state of a battle matches the one of the server.
+///
+/// The event carries the server's `Battle::state_digest`. Its verification fails
+/// with `WeaselError::StateMismatch` if the digest of the battle is different,
+/// thus a desynchronized client detects the divergence as soon as it receives the event.\
+/// Servers can emit this event periodically, see `ServerBuilder::state_verification`.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, EventKind,
+///     EventTrigger, Server, VerifyState,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// let digest = server.battle().state_digest();
+/// VerifyState::trigger(&mut server, digest).fire().unwrap();
+/// assert_eq!(
+///     server.battle().history().events()[0].kind(),
+///     EventKind::VerifyState
+/// );
+/// assert!(VerifyState::trigger(&mut server, digest + 1).fire().is_err());
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct VerifyState<R> {
+    digest: u64,
+
+    #[cfg_attr(feature = "serialization", serde(skip))]
+    _phantom: PhantomData<R>,
+}
+
+impl<R: BattleRules> VerifyState<R> {
+    /// Returns a trigger for this event.
+    pub fn trigger<'a, P: EventProcessor<R>>(
+        processor: &'a mut P,
+        digest: u64,
+    ) -> VerifyStateTrigger<'a, R, P> {
+        VerifyStateTrigger {
+            processor,
+            digest,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the expected digest of the battle state.
+    pub fn digest(&self) -> u64 {
+        self.digest
+    }
+}
+
+impl<R> std::fmt::Debug for VerifyState<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "VerifyState {{ digest: {:?} }}", self.digest)
+    }
+}
+
+impl<R> Clone for VerifyState<R> {
+    fn clone(&self) -> Self {
+        Self {
+            digest: self.digest,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<R: BattleRules + 'static> Event<R> for VerifyState<R> {
+    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
+        let digest = battle.state_digest();
+        if self.digest != digest {
+            return Err(WeaselError::StateMismatch(self.digest, digest));
+        }
+        Ok(())
+    }
+
+    fn apply(&self, _: &mut Battle<R>, _: &mut Option<EventQueue<R>>) {}
+
+    fn kind(&self) -> EventKind {
+        EventKind::VerifyState
+    }
+
+    fn box_clone(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Trigger to build and fire a `VerifyState` event.
+pub struct VerifyStateTrigger<'a, R, P>
+where
+    R: BattleRules,
+    P: EventProcessor<R>,
+{
+    processor: &'a mut P,
+    digest: u64,
+    _phantom: PhantomData<R>,
+}
+
+impl<'a, R, P> EventTrigger<'a, R, P> for VerifyStateTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    fn processor(&'a mut self) -> &'a mut P {
+        self.processor
+    }
+
+    /// Returns a `VerifyState` event.
+    fn event(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(VerifyState {
+            digest: self.digest,
+            _phantom: PhantomData,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -551,6 +2183,55 @@ mod tests {
         }
     }
 
+    #[test]
+    fn watchdog() {
+        let watcher = Watcher {
+            watchdog: Some(Watchdog::new(Duration::from_secs(0))),
+            event: Some((Some(0), EventKind::DummyEvent)),
+        };
+        // Hooks exceeding the budget are only logged.
+        let result = watch!(watcher, "test", {
+            std::thread::sleep(Duration::from_millis(1));
+            1
+        });
+        assert_eq!(result, 1);
+        // Without a watchdog, hooks are simply invoked.
+        let watcher = Watcher {
+            watchdog: None,
+            event: Some((None, EventKind::DummyEvent)),
+        };
+        assert_eq!(watch!(watcher, "test", 2), 2);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn watchdog_abort() {
+        let watcher = Watcher {
+            watchdog: Some(Watchdog::new(Duration::from_secs(0)).abort(true)),
+            event: Some((None, EventKind::DummyEvent)),
+        };
+        watch!(
+            watcher,
+            "test",
+            std::thread::sleep(Duration::from_millis(1))
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "hook EventCallback of event CreateTeam")]
+    fn watchdog_hook_name() {
+        let battle = Battle::builder(CustomRules::new())
+            .event_callback(Box::new(|_, _, _| {
+                std::thread::sleep(Duration::from_millis(20))
+            }))
+            .watchdog(Watchdog::new(Duration::from_millis(10)).abort(true))
+            .build();
+        let mut server = Server::builder(battle).build();
+        team(&mut server, 1);
+    }
+
     #[test]
     fn event_callback() {
         let battle = Battle::builder(CustomRules::new())
@@ -0,0 +1,529 @@
+//! Campaign layer to carry teams and creatures across multiple battles.
+
+use crate::ability::AbilitiesSeed;
+use crate::battle::{Battle, BattlePhase, BattleRules};
+use crate::character::StatisticsSeed;
+use crate::creature::{CreateCreature, Creature, CreatureId};
+use crate::error::{WeaselError, WeaselResult};
+use crate::event::{EventProcessor, EventTrigger};
+use crate::server::Server;
+use crate::space::Position;
+use crate::team::{CreateTeam, ObjectivesSeed, TeamId};
+#[cfg(feature = "serialization")]
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter, Result};
+
+/// Template from which a team is created at the beginning of each battle.
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct TeamTemplate<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "TeamId<R>: Serialize",
+            deserialize = "TeamId<R>: Deserialize<'de>"
+        ))
+    )]
+    id: TeamId<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Option<ObjectivesSeed<R>>: Serialize",
+            deserialize = "Option<ObjectivesSeed<R>>: Deserialize<'de>"
+        ))
+    )]
+    objectives_seed: Option<ObjectivesSeed<R>>,
+}
+
+impl<R: BattleRules> TeamTemplate<R> {
+    /// Creates a new team template.
+    pub fn new(id: TeamId<R>) -> Self {
+        Self {
+            id,
+            objectives_seed: None,
+        }
+    }
+
+    /// Returns the id of the team.
+    pub fn id(&self) -> &TeamId<R> {
+        &self.id
+    }
+
+    /// Returns the seed used to generate the team's objectives.
+    pub fn objectives_seed(&self) -> &Option<ObjectivesSeed<R>> {
+        &self.objectives_seed
+    }
+
+    /// Sets the seed used to generate the team's objectives.
+    pub fn set_objectives_seed(&mut self, seed: Option<ObjectivesSeed<R>>) {
+        self.objectives_seed = seed;
+    }
+}
+
+impl<R: BattleRules> Debug for TeamTemplate<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "TeamTemplate {{ id: {:?}, objectives_seed: {:?} }}",
+            self.id, self.objectives_seed
+        )
+    }
+}
+
+impl<R: BattleRules> Clone for TeamTemplate<R> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+            objectives_seed: self.objectives_seed.clone(),
+        }
+    }
+}
+
+/// Template from which a creature is created at the beginning of each battle.
+///
+/// Creatures in a battle are generated from seeds. After a battle, the campaign rules
+/// can write the state of a surviving creature back into its template, typically by
+/// updating the statistics seed.
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct CreatureTemplate<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "CreatureId<R>: Serialize",
+            deserialize = "CreatureId<R>: Deserialize<'de>"
+        ))
+    )]
+    id: CreatureId<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "TeamId<R>: Serialize",
+            deserialize = "TeamId<R>: Deserialize<'de>"
+        ))
+    )]
+    team_id: TeamId<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Position<R>: Serialize",
+            deserialize = "Position<R>: Deserialize<'de>"
+        ))
+    )]
+    position: Position<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Option<StatisticsSeed<R>>: Serialize",
+            deserialize = "Option<StatisticsSeed<R>>: Deserialize<'de>"
+        ))
+    )]
+    statistics_seed: Option<StatisticsSeed<R>>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Option<AbilitiesSeed<R>>: Serialize",
+            deserialize = "Option<AbilitiesSeed<R>>: Deserialize<'de>"
+        ))
+    )]
+    abilities_seed: Option<AbilitiesSeed<R>>,
+}
+
+impl<R: BattleRules> CreatureTemplate<R> {
+    /// Creates a new creature template.
+    pub fn new(id: CreatureId<R>, team_id: TeamId<R>, position: Position<R>) -> Self {
+        Self {
+            id,
+            team_id,
+            position,
+            statistics_seed: None,
+            abilities_seed: None,
+        }
+    }
+
+    /// Returns the id of the creature.
+    pub fn id(&self) -> &CreatureId<R> {
+        &self.id
+    }
+
+    /// Returns the id of the team to which the creature belongs.
+    pub fn team_id(&self) -> &TeamId<R> {
+        &self.team_id
+    }
+
+    /// Returns the position where the creature is spawned.
+    pub fn position(&self) -> &Position<R> {
+        &self.position
+    }
+
+    /// Sets the position where the creature is spawned.
+    pub fn set_position(&mut self, position: Position<R>) {
+        self.position = position;
+    }
+
+    /// Returns the seed used to generate the creature's statistics.
+    pub fn statistics_seed(&self) -> &Option<StatisticsSeed<R>> {
+        &self.statistics_seed
+    }
+
+    /// Sets the seed used to generate the creature's statistics.
+    pub fn set_statistics_seed(&mut self, seed: Option<StatisticsSeed<R>>) {
+        self.statistics_seed = seed;
+    }
+
+    /// Returns the seed used to generate the creature's abilities.
+    pub fn abilities_seed(&self) -> &Option<AbilitiesSeed<R>> {
+        &self.abilities_seed
+    }
+
+    /// Sets the seed used to generate the creature's abilities.
+    pub fn set_abilities_seed(&mut self, seed: Option<AbilitiesSeed<R>>) {
+        self.abilities_seed = seed;
+    }
+}
+
+impl<R: BattleRules> Debug for CreatureTemplate<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "CreatureTemplate {{ id: {:?}, team_id: {:?}, position: {:?}, \
+             statistics_seed: {:?}, abilities_seed: {:?} }}",
+            self.id, self.team_id, self.position, self.statistics_seed, self.abilities_seed
+        )
+    }
+}
+
+impl<R: BattleRules> Clone for CreatureTemplate<R> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+            team_id: self.team_id.clone(),
+            position: self.position.clone(),
+            statistics_seed: self.statistics_seed.clone(),
+            abilities_seed: self.abilities_seed.clone(),
+        }
+    }
+}
+
+/// A persistent collection of team and creature templates.
+///
+/// The roster can be serialized to save the progress of a campaign.
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Roster<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Vec<TeamTemplate<R>>: Serialize",
+            deserialize = "Vec<TeamTemplate<R>>: Deserialize<'de>"
+        ))
+    )]
+    teams: Vec<TeamTemplate<R>>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Vec<CreatureTemplate<R>>: Serialize",
+            deserialize = "Vec<CreatureTemplate<R>>: Deserialize<'de>"
+        ))
+    )]
+    creatures: Vec<CreatureTemplate<R>>,
+}
+
+impl<R: BattleRules> Roster<R> {
+    /// Creates an empty roster.
+    pub fn new() -> Self {
+        Self {
+            teams: Vec::new(),
+            creatures: Vec::new(),
+        }
+    }
+
+    /// Returns an iterator over all team templates.
+    pub fn teams(&self) -> impl Iterator<Item = &TeamTemplate<R>> {
+        self.teams.iter()
+    }
+
+    /// Returns the template of the team with the given id.
+    pub fn team(&self, id: &TeamId<R>) -> Option<&TeamTemplate<R>> {
+        self.teams.iter().find(|team| team.id() == id)
+    }
+
+    /// Returns a mutable reference to the template of the team with the given id.
+    pub fn team_mut(&mut self, id: &TeamId<R>) -> Option<&mut TeamTemplate<R>> {
+        self.teams.iter_mut().find(|team| team.id() == id)
+    }
+
+    /// Adds a team template to the roster.
+    /// Returns the template previously stored with the same id, if any.
+    pub fn add_team(&mut self, template: TeamTemplate<R>) -> Option<TeamTemplate<R>> {
+        match self.team_mut(template.id()) {
+            Some(team) => Some(std::mem::replace(team, template)),
+            None => {
+                self.teams.push(template);
+                None
+            }
+        }
+    }
+
+    /// Removes a team template and the templates of all its creatures from the roster.
+    /// Returns the removed team template, if it was present.
+    pub fn remove_team(&mut self, id: &TeamId<R>) -> Option<TeamTemplate<R>> {
+        let index = self.teams.iter().position(|team| team.id() == id)?;
+        self.creatures.retain(|creature| creature.team_id() != id);
+        Some(self.teams.remove(index))
+    }
+
+    /// Returns an iterator over all creature templates.
+    pub fn creatures(&self) -> impl Iterator<Item = &CreatureTemplate<R>> {
+        self.creatures.iter()
+    }
+
+    /// Returns the template of the creature with the given id.
+    pub fn creature(&self, id: &CreatureId<R>) -> Option<&CreatureTemplate<R>> {
+        self.creatures.iter().find(|creature| creature.id() == id)
+    }
+
+    /// Returns a mutable reference to the template of the creature with the given id.
+    pub fn creature_mut(&mut self, id: &CreatureId<R>) -> Option<&mut CreatureTemplate<R>> {
+        self.creatures
+            .iter_mut()
+            .find(|creature| creature.id() == id)
+    }
+
+    /// Adds a creature template to the roster.
+    /// Returns the template previously stored with the same id, if any.
+    ///
+    /// The creature's team must be part of the roster.
+    pub fn add_creature(
+        &mut self,
+        template: CreatureTemplate<R>,
+    ) -> WeaselResult<Option<CreatureTemplate<R>>, R> {
+        if self.team(template.team_id()).is_none() {
+            return Err(WeaselError::TeamNotFound(template.team_id().clone()));
+        }
+        match self.creature_mut(template.id()) {
+            Some(creature) => Ok(Some(std::mem::replace(creature, template))),
+            None => {
+                self.creatures.push(template);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Removes a creature template from the roster.
+    /// Returns the removed template, if it was present.
+    pub fn remove_creature(&mut self, id: &CreatureId<R>) -> Option<CreatureTemplate<R>> {
+        let index = self
+            .creatures
+            .iter()
+            .position(|creature| creature.id() == id)?;
+        Some(self.creatures.remove(index))
+    }
+}
+
+impl<R: BattleRules> Default for Roster<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R: BattleRules> Debug for Roster<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "Roster {{ teams: {:?}, creatures: {:?} }}",
+            self.teams, self.creatures
+        )
+    }
+}
+
+impl<R: BattleRules> Clone for Roster<R> {
+    fn clone(&self) -> Self {
+        Self {
+            teams: self.teams.clone(),
+            creatures: self.creatures.clone(),
+        }
+    }
+}
+
+/// Rules to move creatures between the campaign's roster and its battles.
+pub trait CampaignRules<R: BattleRules> {
+    /// Imports a creature template into a new battle.
+    /// Returns the template from which the creature will be created,
+    /// or `None` if the creature shouldn't take part in the battle.
+    ///
+    /// The provided implementation imports every template as it is.
+    fn import(&self, template: &CreatureTemplate<R>) -> Option<CreatureTemplate<R>> {
+        Some(template.clone())
+    }
+
+    /// Exports the state of a creature that survived a battle into its template.
+    ///
+    /// The provided implementation does nothing.
+    fn export(&self, _creature: &Creature<R>, _template: &mut CreatureTemplate<R>) {}
+}
+
+/// Manages the lifecycle of the battles in a campaign.
+///
+/// A campaign spawns each battle from the templates in its roster. Once the battle has
+/// ended, the survivors are exported back into the roster while the creatures that
+/// didn't make it are removed from it.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, EndBattle,
+///     EventTrigger, RemoveCreature,
+/// };
+/// use weasel::campaign::{Campaign, CampaignRules, CreatureTemplate, Roster, TeamTemplate};
+///
+/// battle_rules! {}
+///
+/// struct CustomCampaignRules {}
+///
+/// impl CampaignRules<CustomRules> for CustomCampaignRules {}
+///
+/// let mut roster = Roster::new();
+/// roster.add_team(TeamTemplate::new(1));
+/// roster.add_creature(CreatureTemplate::new(1, 1, ())).unwrap();
+/// roster.add_creature(CreatureTemplate::new(2, 1, ())).unwrap();
+/// let mut campaign = Campaign::new(roster, CustomCampaignRules {});
+///
+/// let mut server = campaign.start_battle(CustomRules::new()).unwrap();
+/// assert_eq!(server.battle().entities().creatures().count(), 2);
+/// RemoveCreature::trigger(&mut server, 2).fire().unwrap();
+/// EndBattle::trigger(&mut server).fire().unwrap();
+///
+/// let fallen = campaign.end_battle(server.battle()).unwrap();
+/// assert_eq!(fallen, vec![2]);
+/// assert_eq!(campaign.roster().creatures().count(), 1);
+/// assert_eq!(campaign.battles(), 1);
+/// ```
+pub struct Campaign<R: BattleRules, C: CampaignRules<R>> {
+    roster: Roster<R>,
+    rules: C,
+    battles: u32,
+    enlisted: Vec<CreatureId<R>>,
+}
+
+impl<R: BattleRules + 'static, C: CampaignRules<R>> Campaign<R, C> {
+    /// Creates a new campaign from a roster and a set of campaign rules.
+    pub fn new(roster: Roster<R>, rules: C) -> Self {
+        Self {
+            roster,
+            rules,
+            battles: 0,
+            enlisted: Vec::new(),
+        }
+    }
+
+    /// Returns the roster of this campaign.
+    pub fn roster(&self) -> &Roster<R> {
+        &self.roster
+    }
+
+    /// Returns a mutable reference to the roster of this campaign.
+    pub fn roster_mut(&mut self) -> &mut Roster<R> {
+        &mut self.roster
+    }
+
+    /// Consumes the campaign, returning its roster.
+    pub fn into_roster(self) -> Roster<R> {
+        self.roster
+    }
+
+    /// Returns the campaign rules.
+    pub fn rules(&self) -> &C {
+        &self.rules
+    }
+
+    /// Returns the number of battles concluded in this campaign.
+    pub fn battles(&self) -> u32 {
+        self.battles
+    }
+
+    /// Fires the events to create all teams and imported creatures of the roster.
+    /// Imported creatures are enlisted for the current battle.
+    ///
+    /// Stops at the first event that fails.
+    pub fn spawn<P>(&mut self, processor: &mut P) -> WeaselResult<(), R>
+    where
+        P: EventProcessor<R, ProcessOutput = WeaselResult<(), R>>,
+    {
+        for team in self.roster.teams() {
+            let mut trigger = CreateTeam::trigger(processor, team.id().clone());
+            if let Some(seed) = team.objectives_seed() {
+                trigger.objectives_seed(seed.clone()).fire()?;
+            } else {
+                trigger.fire()?;
+            }
+        }
+        self.enlisted.clear();
+        for template in self.roster.creatures() {
+            if let Some(creature) = self.rules.import(template) {
+                self.enlisted.push(template.id().clone());
+                let mut trigger = CreateCreature::trigger(
+                    processor,
+                    creature.id().clone(),
+                    creature.team_id().clone(),
+                    creature.position().clone(),
+                );
+                trigger.statistics_seed = creature.statistics_seed().clone();
+                trigger.abilities_seed = creature.abilities_seed().clone();
+                trigger.fire()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates a server for a new battle and spawns the roster into it.
+    pub fn start_battle(&mut self, rules: R) -> WeaselResult<Server<R>, R> {
+        let battle = Battle::builder(rules).build();
+        let mut server = Server::builder(battle).build();
+        self.spawn(&mut server)?;
+        Ok(server)
+    }
+
+    /// Concludes a battle of this campaign.
+    ///
+    /// Every enlisted creature still present in the battle is exported into its template,
+    /// while the enlisted creatures that didn't survive are removed from the roster.
+    /// Returns the ids of the removed creatures.
+    ///
+    /// The battle must have ended.
+    pub fn end_battle(&mut self, battle: &Battle<R>) -> WeaselResult<Vec<CreatureId<R>>, R> {
+        if battle.phase() != BattlePhase::Ended {
+            return Err(WeaselError::BattleInProgress);
+        }
+        let mut fallen = Vec::new();
+        for id in self.enlisted.drain(..) {
+            match battle.entities().creature(&id) {
+                Some(creature) => {
+                    if let Some(template) = self.roster.creature_mut(&id) {
+                        self.rules.export(creature, template);
+                    }
+                }
+                None => {
+                    if self.roster.remove_creature(&id).is_some() {
+                        fallen.push(id);
+                    }
+                }
+            }
+        }
+        self.battles += 1;
+        Ok(fallen)
+    }
+}
+
+impl<R: BattleRules, C: CampaignRules<R>> Debug for Campaign<R, C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "Campaign {{ roster: {:?}, battles: {:?}, enlisted: {:?} }}",
+            self.roster, self.battles, self.enlisted
+        )
+    }
+}
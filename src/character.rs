@@ -7,12 +7,13 @@ use crate::error::{WeaselError, WeaselResult};
 use crate::event::{Event, EventKind, EventProcessor, EventQueue, EventTrigger, Prioritized};
 use crate::metric::WriteMetrics;
 use crate::status::{AppliedStatus, Potency, Status, StatusId};
-use crate::util::Id;
+use crate::util::{Id, RegenerationPolicy};
+use indexmap::IndexMap;
 #[cfg(feature = "serialization")]
 use serde::{Deserialize, Serialize};
 use std::any::Any;
 use std::fmt::{Debug, Formatter, Result};
-use std::hash::Hash;
+use std::hash::{Hash, Hasher};
 
 /// Rules to define the structure and the behavior of characters.
 pub trait CharacterRules<R: BattleRules> {
@@ -30,8 +31,12 @@ pub trait CharacterRules<R: BattleRules> {
     /// See [ObjectId](../object/type.ObjectId.html).
     type ObjectId: Hash + Eq + Clone + Debug + Send + Serialize + for<'a> Deserialize<'a>;
 
+    #[cfg(not(feature = "serialization"))]
     /// See [Statistic](type.Statistic.html).
     type Statistic: Id + 'static;
+    #[cfg(feature = "serialization")]
+    /// See [Statistic](type.Statistic.html).
+    type Statistic: Id + Serialize + for<'a> Deserialize<'a> + 'static;
 
     #[cfg(not(feature = "serialization"))]
     /// See [StatisticsSeed](type.StatisticsSeed.html).
@@ -47,8 +52,12 @@ pub trait CharacterRules<R: BattleRules> {
     /// See [StatisticsAlteration](type.StatisticsAlteration.html).
     type StatisticsAlteration: Clone + Debug + Send + Serialize + for<'a> Deserialize<'a>;
 
+    #[cfg(not(feature = "serialization"))]
     /// See [Status](../status/type.Status.html).
     type Status: Id + 'static;
+    #[cfg(feature = "serialization")]
+    /// See [Status](../status/type.Status.html).
+    type Status: Id + Serialize + for<'a> Deserialize<'a> + 'static;
 
     #[cfg(not(feature = "serialization"))]
     /// See [StatusesAlteration](../status/type.StatusesAlteration.html).
@@ -85,6 +94,27 @@ pub trait CharacterRules<R: BattleRules> {
         None
     }
 
+    /// Adapts the `current` statistic of a character to its `new` regenerated version.\
+    /// This method is invoked only when statistics are regenerated with
+    /// `RegenerationPolicy::ClampToNew`.
+    ///
+    /// The provided implementation leaves `current` untouched.
+    fn clamp_statistic(&self, _current: &mut Self::Statistic, _new: &Self::Statistic) {}
+
+    /// Writes into `hasher` the data of `statistic` to be included in `Battle::state_digest`.
+    ///
+    /// The provided implementation writes only the statistic's id.
+    fn digest_statistic(&self, statistic: &Self::Statistic, mut hasher: &mut dyn Hasher) {
+        statistic.id().hash(&mut hasher);
+    }
+
+    /// Writes into `hasher` the data of `status` to be included in `Battle::state_digest`.
+    ///
+    /// The provided implementation writes only the status' id.
+    fn digest_status(&self, status: &Self::Status, mut hasher: &mut dyn Hasher) {
+        status.id().hash(&mut hasher);
+    }
+
     /// Generates a status to be applied to the given character.\
     /// Returns the new status or nothing if no status should be added. Existing status with
     /// the same id will be replaced.
@@ -139,6 +169,103 @@ pub trait CharacterRules<R: BattleRules> {
         _metrics: &mut WriteMetrics<R>,
     ) {
     }
+
+    /// Returns the threshold level in which the current value of `statistic` lies.\
+    /// Each time the level of a statistic changes, `on_threshold_crossed` is invoked.
+    /// Return `None` for statistics that don't have any threshold.
+    ///
+    /// The provided implementation returns `None`.
+    fn threshold_level(
+        &self,
+        _character: &dyn Character<R>,
+        _statistic: &Self::Statistic,
+    ) -> Option<ThresholdLevel> {
+        None
+    }
+
+    /// Invoked when a statistic of a character crossed a threshold, as defined by
+    /// `threshold_level`.\
+    /// It's possible to insert event prototypes in `event_queue`, for instance to
+    /// kill the character.
+    ///
+    /// The provided implementation does nothing.
+    fn on_threshold_crossed(
+        &self,
+        _state: &BattleState<R>,
+        _character: &dyn Character<R>,
+        _crossing: ThresholdCrossing<R>,
+        _event_queue: &mut Option<EventQueue<R>>,
+        _entropy: &mut Entropy<R>,
+        _metrics: &mut WriteMetrics<R>,
+    ) {
+    }
+
+    /// Invoked when a creature is transformed by a `TransformCreature` event,
+    /// after its statistics and abilities have been regenerated.
+    ///
+    /// The provided implementation does nothing.
+    fn on_transform(
+        &self,
+        _state: &BattleState<R>,
+        _character: &dyn Character<R>,
+        _event_queue: &mut Option<EventQueue<R>>,
+        _entropy: &mut Entropy<R>,
+        _metrics: &mut WriteMetrics<R>,
+    ) {
+    }
+
+    /// Invoked for each summon of a creature removed from the battle, after the removal.\
+    /// Return true to remove the summon as well.
+    ///
+    /// The provided implementation returns false.
+    fn on_summoner_removed(
+        &self,
+        _state: &BattleState<R>,
+        _summoner: &dyn Character<R>,
+        _summon: &dyn Character<R>,
+    ) -> bool {
+        false
+    }
+
+    /// Returns the names of the optional features enabled in these character rules.
+    /// They are reported by `Battle::describe`.
+    ///
+    /// The provided implementation returns no features.
+    fn features(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+}
+
+/// Type to identify the threshold level of a statistic.
+///
+/// Levels are meaningful only to the `CharacterRules` that define them. For example,
+/// health points might have a level for *dead*, one for *critical* and one for *healthy*.
+pub type ThresholdLevel = u32;
+
+/// Describes a change in the threshold level of a statistic.
+pub struct ThresholdCrossing<'a, R: BattleRules> {
+    /// The id of the statistic.
+    pub statistic_id: &'a StatisticId<R>,
+    /// The level before the change. `None` if the statistic had no threshold level
+    /// or didn't exist.
+    pub previous: Option<ThresholdLevel>,
+    /// The current level. `None` if the statistic has no threshold level or has been removed.
+    pub current: Option<ThresholdLevel>,
+}
+
+impl<'a, R: BattleRules> ThresholdCrossing<'a, R> {
+    /// Creates a new threshold crossing.
+    pub fn new(
+        statistic_id: &'a StatisticId<R>,
+        previous: Option<ThresholdLevel>,
+        current: Option<ThresholdLevel>,
+    ) -> Self {
+        Self {
+            statistic_id,
+            previous,
+            current,
+        }
+    }
 }
 
 /// Type to represent an individual statistic.
@@ -302,19 +429,28 @@ impl<R: BattleRules + 'static> Event<R> for AlterStatistics<R> {
     }
 
     fn apply(&self, battle: &mut Battle<R>, event_queue: &mut Option<EventQueue<R>>) {
+        let watcher = battle.apply_watcher();
         // Retrieve the character.
         let character = battle
             .state
             .entities
             .character_mut(&self.id)
             .unwrap_or_else(|| panic!("constraint violated: character {:?} not found", self.id));
+        // Remember the statistics' threshold levels before the alteration.
+        let levels = threshold_levels(battle.rules.character_rules(), character);
         // Alter the character.
-        let transmutation = battle.rules.character_rules().alter_statistics(
-            character,
-            &self.alteration,
-            &mut battle.entropy,
-            &mut battle.metrics.write_handle(),
+        let transmutation = watch!(
+            watcher,
+            "CharacterRules::alter_statistics",
+            battle.rules.character_rules().alter_statistics(
+                character,
+                &self.alteration,
+                &mut battle.entropy,
+                &mut battle.metrics.write_handle(),
+            )
         );
+        // Notify the rules about any threshold crossing.
+        notify_threshold_crossings(battle, &self.id, levels, event_queue);
         // Change the character's existence if needed.
         if let Some(transmutation) = transmutation {
             transmute_entity(
@@ -370,7 +506,8 @@ where
 /// An event to regenerate the statistics of a character.
 ///
 /// A new set of statistics is created from a seed.\
-/// - Statistics already present in the character won't be modified.
+/// - Statistics already present in the character are handled according to the
+///   `RegenerationPolicy` (by default, they won't be modified).
 /// - Statistics that the character didn't have before will be added.
 /// - Current character's statistics that are not present in the new set will be removed
 ///   from the character.
@@ -422,6 +559,9 @@ pub struct RegenerateStatistics<R: BattleRules> {
         ))
     )]
     seed: Option<StatisticsSeed<R>>,
+
+    #[cfg_attr(feature = "serialization", serde(default))]
+    policy: RegenerationPolicy,
 }
 
 impl<R: BattleRules> RegenerateStatistics<R> {
@@ -434,6 +574,7 @@ impl<R: BattleRules> RegenerateStatistics<R> {
             processor,
             id,
             seed: None,
+            policy: RegenerationPolicy::default(),
         }
     }
 
@@ -446,14 +587,19 @@ impl<R: BattleRules> RegenerateStatistics<R> {
     pub fn seed(&self) -> &Option<StatisticsSeed<R>> {
         &self.seed
     }
+
+    /// Returns the policy applied to the statistics already owned by the character.
+    pub fn policy(&self) -> RegenerationPolicy {
+        self.policy
+    }
 }
 
 impl<R: BattleRules> Debug for RegenerateStatistics<R> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         write!(
             f,
-            "RegenerateStatistics {{ id: {:?}, seed: {:?} }}",
-            self.id, self.seed
+            "RegenerateStatistics {{ id: {:?}, seed: {:?}, policy: {:?} }}",
+            self.id, self.seed, self.policy
         )
     }
 }
@@ -463,6 +609,7 @@ impl<R: BattleRules> Clone for RegenerateStatistics<R> {
         Self {
             id: self.id.clone(),
             seed: self.seed.clone(),
+            policy: self.policy,
         }
     }
 }
@@ -472,23 +619,30 @@ impl<R: BattleRules + 'static> Event<R> for RegenerateStatistics<R> {
         verify_get_character(battle.entities(), &self.id).map(|_| ())
     }
 
-    fn apply(&self, battle: &mut Battle<R>, _: &mut Option<EventQueue<R>>) {
+    fn apply(&self, battle: &mut Battle<R>, event_queue: &mut Option<EventQueue<R>>) {
+        let watcher = battle.apply_watcher();
         // Retrieve the character.
         let character = battle
             .state
             .entities
             .character_mut(&self.id)
             .unwrap_or_else(|| panic!("constraint violated: character {:?} not found", self.id));
+        // Remember the statistics' threshold levels before the regeneration.
+        let levels = threshold_levels(battle.rules.character_rules(), character);
         // Generate a new set of statistics.
-        let statistics: Vec<_> = battle
-            .rules
-            .character_rules()
-            .generate_statistics(
-                &self.seed,
-                &mut battle.entropy,
-                &mut battle.metrics.write_handle(),
-            )
-            .collect();
+        let statistics: Vec<_> = watch!(
+            watcher,
+            "CharacterRules::generate_statistics",
+            battle
+                .rules
+                .character_rules()
+                .generate_statistics(
+                    &self.seed,
+                    &mut battle.entropy,
+                    &mut battle.metrics.write_handle(),
+                )
+                .collect()
+        );
         let mut to_remove = Vec::new();
         // Remove all character's statistics not present in the new set.
         for statistic in character.statistics() {
@@ -504,11 +658,23 @@ impl<R: BattleRules + 'static> Event<R> for RegenerateStatistics<R> {
             character.remove_statistic(&statistic_id);
         }
         // Add all statistics present in the new set but not in the character.
+        // Existing statistics are handled according to the policy.
+        let rules = battle.rules.character_rules();
         for statistic in statistics {
-            if character.statistic(statistic.id()).is_none() {
+            if let Some(current) = character.statistic_mut(statistic.id()) {
+                match self.policy {
+                    RegenerationPolicy::PreserveCurrent => {}
+                    RegenerationPolicy::ResetToSeed => {
+                        character.add_statistic(statistic);
+                    }
+                    RegenerationPolicy::ClampToNew => rules.clamp_statistic(current, &statistic),
+                }
+            } else {
                 character.add_statistic(statistic);
             }
         }
+        // Notify the rules about any threshold crossing.
+        notify_threshold_crossings(battle, &self.id, levels, event_queue);
     }
 
     fn kind(&self) -> EventKind {
@@ -533,6 +699,7 @@ where
     processor: &'a mut P,
     id: EntityId<R>,
     seed: Option<StatisticsSeed<R>>,
+    policy: RegenerationPolicy,
 }
 
 impl<'a, R, P> RegenerateStatisticsTrigger<'a, R, P>
@@ -545,6 +712,12 @@ where
         self.seed = Some(seed);
         self
     }
+
+    /// Sets the policy for the statistics already owned by the character.
+    pub fn policy(&'a mut self, policy: RegenerationPolicy) -> &'a mut Self {
+        self.policy = policy;
+        self
+    }
 }
 
 impl<'a, R, P> EventTrigger<'a, R, P> for RegenerateStatisticsTrigger<'a, R, P>
@@ -561,6 +734,7 @@ where
         Box::new(RegenerateStatistics {
             id: self.id.clone(),
             seed: self.seed.clone(),
+            policy: self.policy,
         })
     }
 }
@@ -584,3 +758,67 @@ where
         .ok_or_else(|| WeaselError::EntityNotFound(id.clone()))?;
     Ok(character)
 }
+
+/// Collects the threshold levels of all statistics of a character.
+pub(crate) fn threshold_levels<R: BattleRules>(
+    rules: &R::CR,
+    character: &dyn Character<R>,
+) -> Vec<(StatisticId<R>, Option<ThresholdLevel>)> {
+    character
+        .statistics()
+        .map(|statistic| {
+            (
+                statistic.id().clone(),
+                rules.threshold_level(character, statistic),
+            )
+        })
+        .collect()
+}
+
+/// Compares the `previous` threshold levels of a character's statistics with the current ones
+/// and invokes `on_threshold_crossed` for each statistic whose level has changed.
+pub(crate) fn notify_threshold_crossings<R: BattleRules + 'static>(
+    battle: &mut Battle<R>,
+    id: &EntityId<R>,
+    previous: Vec<(StatisticId<R>, Option<ThresholdLevel>)>,
+    event_queue: &mut Option<EventQueue<R>>,
+) {
+    let character = battle
+        .state
+        .entities
+        .character(id)
+        .unwrap_or_else(|| panic!("constraint violated: character {:?} not found", id));
+    let rules = battle.rules.character_rules();
+    // Keep the original order, so that callbacks are always invoked in the same sequence.
+    let mut previous: IndexMap<_, _> = previous.into_iter().collect();
+    // Statistics currently owned by the character.
+    let mut crossings: Vec<_> = threshold_levels(rules, character)
+        .into_iter()
+        .map(|(statistic_id, current)| {
+            let old = previous.shift_remove(&statistic_id).flatten();
+            (statistic_id, old, current)
+        })
+        .collect();
+    // Statistics removed from the character.
+    crossings.extend(
+        previous
+            .into_iter()
+            .map(|(statistic_id, old)| (statistic_id, old, None)),
+    );
+    for (statistic_id, old, current) in crossings {
+        if old != current {
+            watch!(
+                battle.apply_watcher(),
+                "CharacterRules::on_threshold_crossed",
+                rules.on_threshold_crossed(
+                    &battle.state,
+                    character,
+                    ThresholdCrossing::new(&statistic_id, old, current),
+                    event_queue,
+                    &mut battle.entropy,
+                    &mut battle.metrics.write_handle(),
+                )
+            );
+        }
+    }
+}
@@ -1,11 +1,12 @@
 //! A battle client.
 
 use crate::battle::{Battle, BattleController, BattleRules, EventCallback};
-use crate::error::WeaselResult;
+use crate::error::{WeaselError, WeaselResult};
 use crate::event::{
-    EventProcessor, EventPrototype, EventReceiver, MultiClientSink, MultiClientSinkHandle,
-    MultiClientSinkHandleMut, ServerSink, VersionedEventWrapper,
+    ClientEventPrototype, EventId, EventProcessor, EventPrototype, EventReceiver, MultiClientSink,
+    MultiClientSinkHandle, MultiClientSinkHandleMut, ServerSink, VersionedEventWrapper,
 };
+use crate::interceptor::{EventInterceptor, Interceptors};
 use crate::player::PlayerId;
 
 /// A client event processor.
@@ -15,11 +16,24 @@ use crate::player::PlayerId;
 ///
 /// One or more client sinks can be connected to a client. Events received from
 /// the server are propagated to these sinks.
+///
+/// Clients with an outbox hold local events until `flush` is called, instead of sending
+/// them right away. Pending events can be inspected and cancelled before being sent.
+///
+/// Clients keep track of the state of their connection with the server.
+/// See [ConnectionState](enum.ConnectionState.html) for the possible transitions.
 pub struct Client<R: BattleRules> {
     battle: Battle<R>,
     server_sink: Box<dyn ServerSink<R> + Send>,
     client_sinks: MultiClientSink<R>,
     player: Option<PlayerId>,
+    outbox: Option<Vec<ClientEventPrototype<R>>>,
+    connection_state: ConnectionState,
+    sync_target: Option<EventId>,
+    connection_callback: Option<ConnectionCallback>,
+    auto_resync: bool,
+    sandbox: bool,
+    interceptors: Interceptors<R>,
 }
 
 impl<R: BattleRules + 'static> Client<R> {
@@ -32,6 +46,10 @@ impl<R: BattleRules + 'static> Client<R> {
             battle,
             server_sink,
             player: None,
+            outbox: false,
+            connection_callback: None,
+            auto_resync: false,
+            sandbox: false,
         }
     }
 
@@ -45,6 +63,11 @@ impl<R: BattleRules + 'static> Client<R> {
         &self.player
     }
 
+    /// Returns true if this client accepts events forced by a server in sandbox mode.
+    pub fn sandbox(&self) -> bool {
+        self.sandbox
+    }
+
     /// Returns a reference to the server sink to which all event prototypes
     /// initiated by this client are sent.
     pub fn server_sink(&self) -> &(dyn ServerSink<R> + Send) {
@@ -52,9 +75,109 @@ impl<R: BattleRules + 'static> Client<R> {
     }
 
     /// Disconnects the current server sink and sets a new one.
+    ///
+    /// If the client was disconnected, it starts syncing again.
     pub fn set_server_sink(&mut self, sink: Box<dyn ServerSink<R> + Send>) {
         self.server_sink.on_disconnect();
         self.server_sink = sink;
+        if self.connection_state == ConnectionState::Disconnected {
+            self.sync_target = None;
+            self.transition(ConnectionState::Syncing);
+        }
+    }
+
+    /// Returns the state of the connection with the server.
+    pub fn connection_state(&self) -> ConnectionState {
+        self.connection_state
+    }
+
+    /// Sets a callback invoked each time the connection state changes.
+    pub fn set_connection_callback(&mut self, callback: Option<ConnectionCallback>) {
+        self.connection_callback = callback;
+    }
+
+    /// Starts syncing with the server, which is expected to send all events
+    /// up to `next_id` (excluded).
+    ///
+    /// The client becomes `Ready` as soon as it has received all those events.
+    ///
+    /// # Examples
+    /// ```
+    /// use weasel::client::ConnectionState;
+    /// use weasel::event::{EventSink, EventSinkId, ServerSink};
+    /// use weasel::{
+    ///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, Client,
+    ///     ClientEventPrototype, CreateTeam, EventReceiver, EventTrigger, Server, WeaselResult,
+    /// };
+    ///
+    /// battle_rules! {}
+    ///
+    /// struct Sink;
+    ///
+    /// impl EventSink for Sink {
+    ///     fn id(&self) -> EventSinkId {
+    ///         0
+    ///     }
+    /// }
+    ///
+    /// impl ServerSink<CustomRules> for Sink {
+    ///     fn send(&mut self, _: &ClientEventPrototype<CustomRules>) -> WeaselResult<(), CustomRules> {
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let mut server = Server::builder(Battle::builder(CustomRules::new()).build()).build();
+    /// CreateTeam::trigger(&mut server, 1).fire().unwrap();
+    /// CreateTeam::trigger(&mut server, 2).fire().unwrap();
+    ///
+    /// let battle = Battle::builder(CustomRules::new()).build();
+    /// let mut client = Client::builder(battle, Box::new(Sink)).build();
+    /// client.sync_to(2);
+    /// assert_eq!(client.connection_state(), ConnectionState::Syncing);
+    ///
+    /// for event in server.battle().versioned_events(0..2) {
+    ///     client.receive(event).unwrap();
+    /// }
+    /// assert_eq!(client.connection_state(), ConnectionState::Ready);
+    /// ```
+    pub fn sync_to(&mut self, next_id: EventId) {
+        self.sync_target = Some(next_id);
+        if self.battle.history().next_id() >= next_id {
+            self.transition(ConnectionState::Ready);
+        } else {
+            self.transition(ConnectionState::Syncing);
+        }
+    }
+
+    /// Asks the server to send again all events after the last one received by this client.
+    ///
+    /// The request is sent through `ServerSink::request_resync` and the client becomes
+    /// `Syncing`. Once the server answers, call `sync_to` with the id returned by
+    /// `Server::sync_events_for` to know when the client is `Ready` again.
+    pub fn request_resync(&mut self) -> WeaselResult<(), R> {
+        let from = self.battle.history().next_id();
+        self.server_sink.request_resync(from)?;
+        self.sync_target = None;
+        self.transition(ConnectionState::Syncing);
+        Ok(())
+    }
+
+    /// Changes the connection state, invoking the callback if the state is different.
+    fn transition(&mut self, state: ConnectionState) {
+        let previous = self.connection_state;
+        if previous != state {
+            self.connection_state = state;
+            if let Some(callback) = &mut self.connection_callback {
+                callback(previous, state);
+            }
+        }
+    }
+
+    /// Updates the connection state according to the result of sending events to the server.
+    fn on_send_result(&mut self, result: &WeaselResult<(), R>) {
+        if result.is_err() {
+            self.transition(ConnectionState::Disconnected);
+        }
     }
 
     /// Returns a handle to access the client sinks of this client.
@@ -66,6 +189,85 @@ impl<R: BattleRules + 'static> Client<R> {
     pub fn client_sinks_mut(&mut self) -> MultiClientSinkHandleMut<'_, R> {
         MultiClientSinkHandleMut::new(&mut self.client_sinks, &self.battle)
     }
+
+    /// Adds an interceptor at the end of this client's chain of interceptors.
+    ///
+    /// Interceptors are invoked for the local events fired on the client,
+    /// and for the events received from the server.
+    pub fn add_interceptor(&mut self, interceptor: Box<dyn EventInterceptor<R> + Send>) {
+        self.interceptors.add(interceptor);
+    }
+
+    /// Removes all interceptors from this client.
+    pub fn clear_interceptors(&mut self) {
+        self.interceptors.clear();
+    }
+
+    /// Verifies a local event prototype and decorates it with the information
+    /// needed by the server.
+    pub(crate) fn prepare(
+        &mut self,
+        mut event: EventPrototype<R>,
+    ) -> WeaselResult<ClientEventPrototype<R>, R> {
+        self.interceptors
+            .before_verification(&self.battle, &mut event, self.player)?;
+        self.battle.verify_prototype(&event)?;
+        Ok(event.client_prototype(self.battle().rules().version().clone(), self.player))
+    }
+
+    /// Returns whether or not this client holds local events in an outbox.
+    pub fn has_outbox(&self) -> bool {
+        self.outbox.is_some()
+    }
+
+    /// Returns the events waiting in the outbox to be sent to the server, in firing order.
+    ///
+    /// Pending events are always empty if this client has no outbox.
+    pub fn pending_events(&self) -> &[ClientEventPrototype<R>] {
+        match &self.outbox {
+            Some(outbox) => outbox,
+            None => &[],
+        }
+    }
+
+    /// Cancels the pending event at position `index` in the outbox.
+    /// Returns the cancelled event, if present.
+    pub fn cancel_pending(&mut self, index: usize) -> Option<ClientEventPrototype<R>> {
+        match &mut self.outbox {
+            Some(outbox) if index < outbox.len() => Some(outbox.remove(index)),
+            _ => None,
+        }
+    }
+
+    /// Cancels all pending events in the outbox.
+    pub fn clear_pending(&mut self) {
+        if let Some(outbox) = &mut self.outbox {
+            outbox.clear();
+        }
+    }
+
+    /// Sends all pending events in the outbox to the server, in firing order.
+    ///
+    /// If the server sink fails to send an event, flushing stops and returns the error.
+    /// The failed event and the ones after it are kept in the outbox.
+    pub fn flush(&mut self) -> WeaselResult<(), R> {
+        if let Some(outbox) = &mut self.outbox {
+            let mut sent = 0;
+            let mut result = Ok(());
+            for event in outbox.iter() {
+                result = self.server_sink.send(event);
+                if result.is_err() {
+                    break;
+                }
+                sent += 1;
+            }
+            outbox.drain(..sent);
+            self.on_send_result(&result);
+            result
+        } else {
+            Ok(())
+        }
+    }
 }
 
 impl<R: BattleRules> BattleController<R> for Client<R> {
@@ -86,22 +288,51 @@ impl<R: BattleRules + 'static> EventProcessor<R> for Client<R> {
     type ProcessOutput = WeaselResult<(), R>;
 
     fn process(&mut self, event: EventPrototype<R>) -> Self::ProcessOutput {
-        self.battle.verify_prototype(&event)?;
-        // Decorate the prototype with additional information.
-        let event = event.client_prototype(self.battle().rules().version().clone(), self.player);
-        // Send the event to the server.
-        self.server_sink.send(&event)
+        let event = self.prepare(event)?;
+        if let Some(outbox) = &mut self.outbox {
+            // Hold the event until the next flush.
+            outbox.push(event);
+            Ok(())
+        } else {
+            // Send the event to the server.
+            let result = self.server_sink.send(&event);
+            self.on_send_result(&result);
+            result
+        }
     }
 }
 
 impl<R: BattleRules + 'static> EventReceiver<R> for Client<R> {
     fn receive(&mut self, event: VersionedEventWrapper<R>) -> WeaselResult<(), R> {
-        // Verify the event.
-        self.battle.verify_wrapper(&event)?;
+        // Verify the event. A client failing to verify an event accepted by the server
+        // is no longer in sync with it.
+        if let Err(err) = self.battle.verify_wrapper(&event, self.sandbox) {
+            // Ask for the missing events if there's a gap in the history.
+            let gap =
+                matches!(err, WeaselError::NonContiguousEventId(id, expected) if id > expected);
+            let resync = gap
+                && self.auto_resync
+                && self.connection_state != ConnectionState::Syncing
+                && self.request_resync().is_ok();
+            if !resync {
+                self.transition(ConnectionState::Desynced);
+            }
+            return Err(err);
+        }
         // Apply the event on the battle.
         self.battle.apply(&event.wrapper(), &mut None);
+        self.interceptors
+            .after_application(&self.battle, event.wrapper());
         // Send the event to all client sinks.
         self.client_sinks.send_all(&event);
+        // Check if the sync is complete.
+        if self.connection_state == ConnectionState::Syncing {
+            if let Some(target) = self.sync_target {
+                if self.battle.history().next_id() >= target {
+                    self.transition(ConnectionState::Ready);
+                }
+            }
+        }
         Ok(())
     }
 }
@@ -111,6 +342,10 @@ pub struct ClientBuilder<R: BattleRules> {
     battle: Battle<R>,
     server_sink: Box<dyn ServerSink<R> + Send>,
     player: Option<PlayerId>,
+    outbox: bool,
+    connection_callback: Option<ConnectionCallback>,
+    auto_resync: bool,
+    sandbox: bool,
 }
 
 impl<R: BattleRules> ClientBuilder<R> {
@@ -121,6 +356,38 @@ impl<R: BattleRules> ClientBuilder<R> {
         self
     }
 
+    /// Enable the outbox on the new client.
+    /// Local events will be sent to the server only when `flush` is called.
+    pub fn outbox(mut self) -> Self {
+        self.outbox = true;
+        self
+    }
+
+    /// Sets a callback invoked each time the connection state of the client changes.
+    pub fn connection_callback(mut self, callback: ConnectionCallback) -> Self {
+        self.connection_callback = Some(callback);
+        self
+    }
+
+    /// Makes the new client request a resynchronization as soon as it detects
+    /// a gap in the events received from the server.
+    ///
+    /// See `Client::request_resync`.
+    pub fn auto_resync(mut self) -> Self {
+        self.auto_resync = true;
+        self
+    }
+
+    /// Makes the new client accept the events that a server in sandbox mode applied
+    /// despite failing verification.
+    ///
+    /// Without this option, events marked as `unverified` are verified as any other event.
+    /// See `ServerBuilder::sandbox`.
+    pub fn sandbox(mut self) -> Self {
+        self.sandbox = true;
+        self
+    }
+
     /// Creates a new client.
     pub fn build(self) -> Client<R> {
         Client {
@@ -128,6 +395,44 @@ impl<R: BattleRules> ClientBuilder<R> {
             server_sink: self.server_sink,
             client_sinks: MultiClientSink::new(),
             player: self.player,
+            outbox: if self.outbox { Some(Vec::new()) } else { None },
+            connection_state: ConnectionState::Ready,
+            sync_target: None,
+            connection_callback: self.connection_callback,
+            auto_resync: self.auto_resync,
+            sandbox: self.sandbox,
+            interceptors: Interceptors::new(),
         }
     }
 }
+
+/// State of the connection between a client and its server.
+///
+/// A new client is `Ready`. Then:
+/// - `Client::sync_to` makes the client `Syncing` until it has received all events
+///   up to the given id, after which the client becomes `Ready` again.
+/// - A failure to send an event to the server makes the client `Disconnected`.
+///   Setting a new server sink on a disconnected client makes it `Syncing`.
+/// - A failure to verify an event received from the server, such as a
+///   `NonContiguousEventId` error, makes the client `Desynced`.
+/// - `Client::request_resync` makes the client `Syncing`. Clients built with
+///   `ClientBuilder::auto_resync` request a resynchronization by themselves,
+///   instead of becoming `Desynced`, when they miss some events.
+///
+/// Only `Client::sync_to` can bring a `Desynced` client back in sync.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConnectionState {
+    /// The client can't reach the server.
+    Disconnected,
+    /// The client is catching up with the server's history.
+    Syncing,
+    /// The client is in sync with the server.
+    Ready,
+    /// The client's history diverged from the server's one.
+    Desynced,
+}
+
+/// Type to define a callback invoked each time the connection state of a client changes.
+///
+/// The arguments are the previous and the new state.
+pub type ConnectionCallback = Box<dyn FnMut(ConnectionState, ConnectionState) + Send>;
@@ -1,22 +1,28 @@
 //! Main entity in the game.
 
 use crate::ability::{AbilitiesSeed, Ability, AbilityId};
-use crate::actor::{Actor, ActorRules};
-use crate::battle::{Battle, BattleRules, Checkpoint};
-use crate::character::{Character, CharacterRules, Statistic, StatisticId, StatisticsSeed};
+use crate::actor::{ActivationRecord, Actor, ActorRules, TrackedAbility};
+use crate::aura::update_auras;
+use crate::battle::{Battle, BattleRules, Checkpoint, Watcher};
+use crate::character::{
+    notify_threshold_crossings, threshold_levels, Character, CharacterRules, Statistic,
+    StatisticId, StatisticsSeed,
+};
 use crate::entity::{Entity, EntityId, Transmutation};
 use crate::error::{WeaselError, WeaselResult};
 use crate::event::{Event, EventKind, EventProcessor, EventQueue, EventTrigger};
 use crate::metric::system::*;
-use crate::round::TurnState;
+use crate::player::PlayerId;
+use crate::round::{TurnState, TurnsCount};
 use crate::space::{Position, PositionClaim};
-use crate::status::{AppliedStatus, StatusId};
+use crate::status::{clear_status, AppliedStatus, StatusId};
 use crate::team::{EntityAddition, TeamId, TeamRules};
 use crate::util::{collect_from_iter, Id};
 use indexmap::IndexMap;
 #[cfg(feature = "serialization")]
 use serde::{Deserialize, Serialize};
 use std::any::Any;
+use std::collections::VecDeque;
 use std::fmt::{Debug, Formatter, Result};
 
 /// Type to represent the id of creatures.
@@ -30,28 +36,142 @@ type Statistics<R> = IndexMap<
 type Statuses<R> =
     IndexMap<<<<R as BattleRules>::CR as CharacterRules<R>>::Status as Id>::Id, AppliedStatus<R>>;
 
-type Abilities<R> = IndexMap<
-    <<<R as BattleRules>::AR as ActorRules<R>>::Ability as Id>::Id,
-    <<R as BattleRules>::AR as ActorRules<R>>::Ability,
->;
+type Abilities<R> =
+    IndexMap<<<<R as BattleRules>::AR as ActorRules<R>>::Ability as Id>::Id, TrackedAbility<R>>;
 
 /// A creature is the main acting entity of a battle.
 ///
 /// Creatures can activate abilities during their turn, occupy a spatial position,
 /// suffer status effects and are characterized by their statistics.
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub struct Creature<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "EntityId<R>: Serialize",
+            deserialize = "EntityId<R>: Deserialize<'de>"
+        ))
+    )]
     id: EntityId<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "TeamId<R>: Serialize",
+            deserialize = "TeamId<R>: Deserialize<'de>"
+        ))
+    )]
     team_id: TeamId<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Position<R>: Serialize",
+            deserialize = "Position<R>: Deserialize<'de>"
+        ))
+    )]
     position: Position<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(
+            bound(
+                serialize = "Statistic<R>: Serialize",
+                deserialize = "Statistic<R>: Deserialize<'de>"
+            ),
+            serialize_with = "crate::serde::serialize_pairs",
+            deserialize_with = "crate::serde::deserialize_pairs"
+        )
+    )]
     statistics: Statistics<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(
+            bound(
+                serialize = "AppliedStatus<R>: Serialize",
+                deserialize = "AppliedStatus<R>: Deserialize<'de>"
+            ),
+            serialize_with = "crate::serde::serialize_pairs",
+            deserialize_with = "crate::serde::deserialize_pairs"
+        )
+    )]
     statuses: Statuses<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(
+            bound(
+                serialize = "TrackedAbility<R>: Serialize",
+                deserialize = "TrackedAbility<R>: Deserialize<'de>"
+            ),
+            serialize_with = "crate::serde::serialize_pairs",
+            deserialize_with = "crate::serde::deserialize_pairs"
+        )
+    )]
     abilities: Abilities<R>,
+
+    owner: Option<PlayerId>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "VecDeque<ActivationRecord<R>>: Serialize",
+            deserialize = "VecDeque<ActivationRecord<R>>: Deserialize<'de>"
+        ))
+    )]
+    recent_activations: VecDeque<ActivationRecord<R>>,
 }
 
 impl<R: BattleRules> Creature<R> {
+    pub(crate) fn set_id(&mut self, id: CreatureId<R>) {
+        self.id = EntityId::Creature(id);
+    }
+
     pub(crate) fn set_team_id(&mut self, id: TeamId<R>) {
         self.team_id = id;
     }
+
+    /// Returns the player who has exclusive control over this creature, if any.
+    ///
+    /// Creatures without an owner are controlled by the players with rights to their team.
+    pub fn owner(&self) -> Option<PlayerId> {
+        self.owner
+    }
+
+    pub(crate) fn set_owner(&mut self, owner: Option<PlayerId>) {
+        self.owner = owner;
+    }
+
+    /// Remembers an activation, forgetting the oldest ones in excess of `capacity`.
+    pub(crate) fn mark_ability_used(&mut self, id: &AbilityId<R>, turn: TurnsCount) {
+        if let Some(ability) = self.abilities.get_mut(id) {
+            ability.set_last_used_turn(turn);
+        }
+    }
+
+    pub(crate) fn recharge_abilities(&mut self, ids: Option<&[AbilityId<R>]>) {
+        match ids {
+            Some(ids) => {
+                for id in ids {
+                    if let Some(ability) = self.abilities.get_mut(id) {
+                        ability.recharge();
+                    }
+                }
+            }
+            None => self
+                .abilities
+                .values_mut()
+                .for_each(TrackedAbility::recharge),
+        }
+    }
+
+    pub(crate) fn record_activation(&mut self, record: ActivationRecord<R>, capacity: usize) {
+        self.recent_activations.push_back(record);
+        while self.recent_activations.len() > capacity {
+            self.recent_activations.pop_front();
+        }
+    }
 }
 
 impl<R: BattleRules> Id for Creature<R> {
@@ -132,41 +252,55 @@ impl<R: BattleRules> Character<R> for Creature<R> {
 
 impl<R: BattleRules> Actor<R> for Creature<R> {
     fn abilities<'a>(&'a self) -> Box<dyn Iterator<Item = &'a Ability<R>> + 'a> {
-        Box::new(self.abilities.values())
+        Box::new(self.abilities.values().map(TrackedAbility::ability))
     }
 
     fn abilities_mut<'a>(&'a mut self) -> Box<dyn Iterator<Item = &'a mut Ability<R>> + 'a> {
-        Box::new(self.abilities.values_mut())
+        Box::new(self.abilities.values_mut().map(TrackedAbility::ability_mut))
     }
 
     fn ability(&self, id: &AbilityId<R>) -> Option<&Ability<R>> {
-        self.abilities.get(id)
+        self.abilities.get(id).map(TrackedAbility::ability)
     }
 
     fn ability_mut(&mut self, id: &AbilityId<R>) -> Option<&mut Ability<R>> {
-        self.abilities.get_mut(id)
+        self.abilities.get_mut(id).map(TrackedAbility::ability_mut)
     }
 
     fn add_ability(&mut self, ability: Ability<R>) -> Option<Ability<R>> {
-        self.abilities.insert(ability.id().clone(), ability)
+        self.abilities
+            .insert(ability.id().clone(), TrackedAbility::new(ability))
+            .map(TrackedAbility::into_ability)
     }
 
     fn remove_ability(&mut self, id: &AbilityId<R>) -> Option<Ability<R>> {
-        self.abilities.remove(id)
+        self.abilities.remove(id).map(TrackedAbility::into_ability)
+    }
+
+    fn tracked_ability(&self, id: &AbilityId<R>) -> Option<&TrackedAbility<R>> {
+        self.abilities.get(id)
     }
 
     fn team_id(&self) -> &TeamId<R> {
         &self.team_id
     }
+
+    fn recent_activations<'a>(&'a self) -> Box<dyn Iterator<Item = &'a ActivationRecord<R>> + 'a> {
+        Box::new(self.recent_activations.iter())
+    }
 }
 
 /// Event to create a new creature.
 ///
+/// The creature can be linked to the creature who summoned it, see `Entities::summoner`.
+/// When a summoner is removed, `CharacterRules::on_summoner_removed` decides whether
+/// its summons are removed as well.
+///
 /// # Examples
 /// ```
 /// use weasel::{
 ///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateCreature,
-///     CreateTeam, EventTrigger, Server,
+///     CreateTeam, EntityId, EventTrigger, Server,
 /// };
 ///
 /// battle_rules! {}
@@ -183,6 +317,16 @@ impl<R: BattleRules> Actor<R> for Creature<R> {
 ///     .fire()
 ///     .unwrap();
 /// assert_eq!(server.battle().entities().creatures().count(), 1);
+///
+/// let summon_id = 2;
+/// CreateCreature::trigger(&mut server, summon_id, team_id, position)
+///     .summoner(EntityId::Creature(creature_id))
+///     .fire()
+///     .unwrap();
+/// assert_eq!(
+///     server.battle().entities().summoner(&EntityId::Creature(summon_id)),
+///     Some(&EntityId::Creature(creature_id))
+/// );
 /// ```
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub struct CreateCreature<R: BattleRules> {
@@ -230,6 +374,15 @@ pub struct CreateCreature<R: BattleRules> {
         ))
     )]
     abilities_seed: Option<AbilitiesSeed<R>>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Option<EntityId<R>>: Serialize",
+            deserialize = "Option<EntityId<R>>: Deserialize<'de>"
+        ))
+    )]
+    summoner: Option<EntityId<R>>,
 }
 
 impl<R: BattleRules> Debug for CreateCreature<R> {
@@ -237,8 +390,13 @@ impl<R: BattleRules> Debug for CreateCreature<R> {
         write!(
             f,
             "CreateCreature {{ id: {:?}, team_id: {:?}, position: {:?}, \
-             statistics_seed: {:?}, abilities_seed: {:?} }}",
-            self.id, self.team_id, self.position, self.statistics_seed, self.abilities_seed
+             statistics_seed: {:?}, abilities_seed: {:?}, summoner: {:?} }}",
+            self.id,
+            self.team_id,
+            self.position,
+            self.statistics_seed,
+            self.abilities_seed,
+            self.summoner
         )
     }
 }
@@ -251,6 +409,7 @@ impl<R: BattleRules> Clone for CreateCreature<R> {
             position: self.position.clone(),
             statistics_seed: self.statistics_seed.clone(),
             abilities_seed: self.abilities_seed.clone(),
+            summoner: self.summoner.clone(),
         }
     }
 }
@@ -270,6 +429,7 @@ impl<R: BattleRules> CreateCreature<R> {
             position,
             statistics_seed: None,
             abilities_seed: None,
+            summoner: None,
         }
     }
 
@@ -297,51 +457,67 @@ impl<R: BattleRules> CreateCreature<R> {
     pub fn abilities_seed(&self) -> &Option<AbilitiesSeed<R>> {
         &self.abilities_seed
     }
+
+    /// Returns the id of the creature who summoned the creature to be created, if any.
+    pub fn summoner(&self) -> &Option<EntityId<R>> {
+        &self.summoner
+    }
 }
 
-impl<R: BattleRules + 'static> Event<R> for CreateCreature<R> {
-    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
-        let team = battle
-            .entities()
-            .team(&self.team_id)
-            .ok_or_else(|| WeaselError::TeamNotFound(self.team_id.clone()))?;
-        // Check if the team accepts a new creature.
-        battle
-            .rules()
-            .team_rules()
-            .allow_new_entity(&battle.state, &team, EntityAddition::CreatureSpawn)
-            .map_err(|err| {
-                WeaselError::NewCreatureUnaccepted(self.team_id.clone(), Box::new(err))
-            })?;
+impl<R: BattleRules + 'static> CreateCreature<R> {
+    /// Verifies that the creature's id is unique and that its position is valid.
+    fn verify_id_and_position(&self, battle: &Battle<R>, watcher: Watcher) -> WeaselResult<(), R> {
         // Check id duplication.
         if battle.entities().creature(&self.id).is_some() {
             return Err(WeaselError::DuplicatedCreature(self.id.clone()));
         }
+        // Check that the summoner is an existing creature.
+        if let Some(summoner) = &self.summoner {
+            if !matches!(summoner, EntityId::Creature(_)) {
+                return Err(WeaselError::NotACreature(summoner.clone()));
+            }
+            if battle.entities().entity(summoner).is_none() {
+                return Err(WeaselError::EntityNotFound(summoner.clone()));
+            }
+        }
         // Check position.
-        battle
-            .space()
-            .check_move(
+        watch!(
+            watcher,
+            "SpaceRules::check_move",
+            battle.space().check_move(
                 PositionClaim::Spawn(&EntityId::Creature(self.id.clone())),
                 &self.position,
             )
-            .map_err(|err| WeaselError::PositionError(None, self.position.clone(), Box::new(err)))
+        )
+        .map_err(|err| WeaselError::PositionError(None, self.position.clone(), Box::new(err)))
     }
 
-    fn apply(&self, battle: &mut Battle<R>, event_queue: &mut Option<EventQueue<R>>) {
+    /// Creates the creature and adds it to the battle.
+    fn spawn(&self, battle: &mut Battle<R>, event_queue: &mut Option<EventQueue<R>>) {
+        let watcher = battle.apply_watcher();
         // Statistics' generation is influenced by the given statistics_seed, if present.
-        let it = battle.rules.character_rules().generate_statistics(
-            &self.statistics_seed,
-            &mut battle.entropy,
-            &mut battle.metrics.write_handle(),
+        let statistics = watch!(
+            watcher,
+            "CharacterRules::generate_statistics",
+            collect_from_iter(battle.rules.character_rules().generate_statistics(
+                &self.statistics_seed,
+                &mut battle.entropy,
+                &mut battle.metrics.write_handle(),
+            ))
         );
-        let statistics = collect_from_iter(it);
         // Abilities' generation is influenced by the given abilities_seed, if present.
-        let it = battle.rules.actor_rules().generate_abilities(
-            &self.abilities_seed,
-            &mut battle.entropy,
-            &mut battle.metrics.write_handle(),
-        );
-        let abilities = collect_from_iter(it);
+        let abilities = watch!(
+            watcher,
+            "ActorRules::generate_abilities",
+            collect_from_iter(battle.rules.actor_rules().generate_abilities(
+                &self.abilities_seed,
+                &mut battle.entropy,
+                &mut battle.metrics.write_handle(),
+            ))
+        )
+        .into_iter()
+        .map(|(id, ability)| (id, TrackedAbility::new(ability)))
+        .collect();
         // Create the creature.
         let creature = Creature {
             id: EntityId::Creature(self.id.clone()),
@@ -350,26 +526,40 @@ impl<R: BattleRules + 'static> Event<R> for CreateCreature<R> {
             statistics,
             statuses: IndexMap::new(),
             abilities,
+            owner: None,
+            recent_activations: VecDeque::new(),
         };
         // Take the position.
-        battle.state.space.move_entity(
-            PositionClaim::Spawn(&EntityId::Creature(self.id.clone())),
-            Some(&self.position),
-            &mut battle.metrics.write_handle(),
+        watch!(
+            watcher,
+            "SpaceRules::move_entity",
+            battle.state.space.move_entity(
+                PositionClaim::Spawn(&EntityId::Creature(self.id.clone())),
+                Some(&self.position),
+                &mut battle.metrics.write_handle(),
+            )
         );
         // Notify the rounds module.
-        battle.state.rounds.on_actor_added(
-            &creature,
-            &mut battle.entropy,
-            &mut battle.metrics.write_handle(),
+        watch!(
+            watcher,
+            "RoundsRules::on_actor_added",
+            battle.state.rounds.on_actor_added(
+                &creature,
+                &mut battle.entropy,
+                &mut battle.metrics.write_handle(),
+            )
         );
         // Invoke the character's rules callback.
-        battle.rules.character_rules().on_character_added(
-            &battle.state,
-            &creature,
-            event_queue,
-            &mut battle.entropy,
-            &mut battle.metrics.write_handle(),
+        watch!(
+            watcher,
+            "CharacterRules::on_character_added",
+            battle.rules.character_rules().on_character_added(
+                &battle.state,
+                &creature,
+                event_queue,
+                &mut battle.entropy,
+                &mut battle.metrics.write_handle(),
+            )
         );
         // Add the creature to the entities.
         battle
@@ -377,6 +567,38 @@ impl<R: BattleRules + 'static> Event<R> for CreateCreature<R> {
             .entities
             .add_creature(creature)
             .unwrap_or_else(|err| panic!("constraint violated: {:?}", err));
+        // Link the creature to its summoner.
+        if let Some(summoner) = &self.summoner {
+            battle
+                .state
+                .entities
+                .link_summon(EntityId::Creature(self.id.clone()), summoner.clone());
+        }
+    }
+}
+
+impl<R: BattleRules + 'static> Event<R> for CreateCreature<R> {
+    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
+        let team = battle
+            .entities()
+            .team(&self.team_id)
+            .ok_or_else(|| WeaselError::TeamNotFound(self.team_id.clone()))?;
+        // Check if the team accepts a new creature.
+        watch!(
+            battle.verify_watcher(self.kind()),
+            "TeamRules::allow_new_entity",
+            battle.rules().team_rules().allow_new_entity(
+                &battle.state,
+                &team,
+                EntityAddition::CreatureSpawn
+            )
+        )
+        .map_err(|err| WeaselError::NewCreatureUnaccepted(self.team_id.clone(), Box::new(err)))?;
+        self.verify_id_and_position(battle, battle.verify_watcher(self.kind()))
+    }
+
+    fn apply(&self, battle: &mut Battle<R>, event_queue: &mut Option<EventQueue<R>>) {
+        self.spawn(battle, event_queue);
         // Update metrics.
         battle
             .metrics
@@ -408,8 +630,9 @@ where
     id: CreatureId<R>,
     team_id: TeamId<R>,
     position: Position<R>,
-    statistics_seed: Option<StatisticsSeed<R>>,
-    abilities_seed: Option<AbilitiesSeed<R>>,
+    pub(crate) statistics_seed: Option<StatisticsSeed<R>>,
+    pub(crate) abilities_seed: Option<AbilitiesSeed<R>>,
+    pub(crate) summoner: Option<EntityId<R>>,
 }
 
 impl<'a, R, P> CreateCreatureTrigger<'a, R, P>
@@ -428,6 +651,12 @@ where
         self.abilities_seed = Some(seed);
         self
     }
+
+    /// Links this creature to the creature who summoned it.
+    pub fn summoner(&'a mut self, summoner: EntityId<R>) -> &'a mut Self {
+        self.summoner = Some(summoner);
+        self
+    }
 }
 
 impl<'a, R, P> EventTrigger<'a, R, P> for CreateCreatureTrigger<'a, R, P>
@@ -447,12 +676,202 @@ where
             position: self.position.clone(),
             statistics_seed: self.statistics_seed.clone(),
             abilities_seed: self.abilities_seed.clone(),
+            summoner: self.summoner.clone(),
+        })
+    }
+}
+
+/// Event to create many creatures at once.
+///
+/// All creatures in the wave are verified together, before any of them is created.
+/// Each team is asked a single time whether it accepts the new creatures, with
+/// `EntityAddition::CreatureWaveSpawn`. Ids must be unique within the wave, while positions
+/// are checked against the space as it is before the wave is created.\
+/// This event is meant for procedurally generated encounters, which would otherwise
+/// require one `CreateCreature` for each creature.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateTeam,
+///     EventTrigger, Server, SpawnWave,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// let team_id = 1;
+/// CreateTeam::trigger(&mut server, team_id).fire().unwrap();
+///
+/// let mut trigger = SpawnWave::trigger(&mut server);
+/// for creature_id in 0..10 {
+///     trigger.creature(creature_id, team_id, (), None, None);
+/// }
+/// trigger.fire().unwrap();
+/// assert_eq!(server.battle().entities().creatures().count(), 10);
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct SpawnWave<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Vec<CreateCreature<R>>: Serialize",
+            deserialize = "Vec<CreateCreature<R>>: Deserialize<'de>"
+        ))
+    )]
+    creatures: Vec<CreateCreature<R>>,
+}
+
+impl<R: BattleRules> Debug for SpawnWave<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "SpawnWave {{ creatures: {:?} }}", self.creatures)
+    }
+}
+
+impl<R: BattleRules> Clone for SpawnWave<R> {
+    fn clone(&self) -> Self {
+        Self {
+            creatures: self.creatures.clone(),
+        }
+    }
+}
+
+impl<R: BattleRules> SpawnWave<R> {
+    /// Returns a trigger for this event.
+    pub fn trigger<P: EventProcessor<R>>(processor: &mut P) -> SpawnWaveTrigger<'_, R, P> {
+        SpawnWaveTrigger {
+            processor,
+            creatures: Vec::new(),
+        }
+    }
+
+    /// Returns the creatures to be created, in order of creation.
+    pub fn creatures(&self) -> &[CreateCreature<R>] {
+        &self.creatures
+    }
+}
+
+impl<R: BattleRules + 'static> Event<R> for SpawnWave<R> {
+    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
+        // Count how many creatures join each team.
+        let mut teams: IndexMap<&TeamId<R>, usize> = IndexMap::new();
+        for creature in &self.creatures {
+            *teams.entry(&creature.team_id).or_insert(0) += 1;
+        }
+        // Check if the teams accept the new creatures.
+        for (team_id, count) in teams {
+            let team = battle
+                .entities()
+                .team(team_id)
+                .ok_or_else(|| WeaselError::TeamNotFound(team_id.clone()))?;
+            watch!(
+                battle.verify_watcher(self.kind()),
+                "TeamRules::allow_new_entity",
+                battle.rules().team_rules().allow_new_entity(
+                    &battle.state,
+                    team,
+                    EntityAddition::CreatureWaveSpawn(count),
+                )
+            )
+            .map_err(|err| WeaselError::NewCreatureUnaccepted(team_id.clone(), Box::new(err)))?;
+        }
+        for (i, creature) in self.creatures.iter().enumerate() {
+            creature.verify_id_and_position(battle, battle.verify_watcher(self.kind()))?;
+            // Check id duplication inside the wave.
+            if self.creatures[..i]
+                .iter()
+                .any(|other| other.id == creature.id)
+            {
+                return Err(WeaselError::DuplicatedCreature(creature.id.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    fn apply(&self, battle: &mut Battle<R>, event_queue: &mut Option<EventQueue<R>>) {
+        for creature in &self.creatures {
+            creature.spawn(battle, event_queue);
+        }
+        // Update metrics.
+        battle
+            .metrics
+            .write_handle()
+            .add_system_u64(CREATURES_CREATED, self.creatures.len() as u64)
+            .unwrap_or_else(|err| panic!("constraint violated: {:?}", err));
+    }
+
+    fn kind(&self) -> EventKind {
+        EventKind::SpawnWave
+    }
+
+    fn box_clone(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Trigger to build and fire a `SpawnWave` event.
+pub struct SpawnWaveTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    processor: &'a mut P,
+    creatures: Vec<CreateCreature<R>>,
+}
+
+impl<'a, R, P> SpawnWaveTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    /// Adds a creature to the wave.
+    pub fn creature(
+        &mut self,
+        id: CreatureId<R>,
+        team_id: TeamId<R>,
+        position: Position<R>,
+        statistics_seed: Option<StatisticsSeed<R>>,
+        abilities_seed: Option<AbilitiesSeed<R>>,
+    ) -> &mut Self {
+        self.creatures.push(CreateCreature {
+            id,
+            team_id,
+            position,
+            statistics_seed,
+            abilities_seed,
+            summoner: None,
+        });
+        self
+    }
+}
+
+impl<'a, R, P> EventTrigger<'a, R, P> for SpawnWaveTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    fn processor(&'a mut self) -> &'a mut P {
+        self.processor
+    }
+
+    /// Returns a `SpawnWave` event.
+    fn event(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(SpawnWave {
+            creatures: self.creatures.clone(),
         })
     }
 }
 
 /// Event to move a creature from its current team to another one.
 ///
+/// The creature loses its owner, if it had one.
+///
 /// # Examples
 /// ```
 /// use weasel::{
@@ -569,21 +988,22 @@ impl<R: BattleRules + 'static> Event<R> for ConvertCreature<R> {
                 self.creature_id.clone(),
             ));
         }
-        battle
-            .rules()
-            .team_rules()
-            .allow_new_entity(
+        watch!(
+            battle.verify_watcher(self.kind()),
+            "TeamRules::allow_new_entity",
+            battle.rules().team_rules().allow_new_entity(
                 &battle.state,
                 &team,
                 EntityAddition::CreatureConversion(&creature),
             )
-            .map_err(|err| {
-                WeaselError::ConvertedCreatureUnaccepted(
-                    self.team_id.clone(),
-                    self.creature_id.clone(),
-                    Box::new(err),
-                )
-            })
+        )
+        .map_err(|err| {
+            WeaselError::ConvertedCreatureUnaccepted(
+                self.team_id.clone(),
+                self.creature_id.clone(),
+                Box::new(err),
+            )
+        })
     }
 
     fn apply(&self, battle: &mut Battle<R>, _event_queue: &mut Option<EventQueue<R>>) {
@@ -636,16 +1056,18 @@ where
     }
 }
 
-/// Event to remove a creature from the battle.
+/// Event to change the id of a creature.
 ///
-/// If the creature is the current actor, its turn will be terminated.\
-/// The creature will be removed from the corresponding team and its position will be freed.
+/// All references to the creature are updated: its team's creature list,
+/// its position in the space model and the rounds model, and the current turn state.\
+/// Space and rounds rules are notified as if the creature was removed and then added again
+/// with the new id.
 ///
 /// # Examples
 /// ```
 /// use weasel::{
 ///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateCreature,
-///     CreateTeam, EventTrigger, RemoveCreature, Server,
+///     CreateTeam, EventTrigger, ReidentifyCreature, Server,
 /// };
 ///
 /// battle_rules! {}
@@ -661,11 +1083,15 @@ where
 ///     .fire()
 ///     .unwrap();
 ///
-/// RemoveCreature::trigger(&mut server, creature_id).fire().unwrap();
-/// assert_eq!(server.battle().entities().creatures().count(), 0);
+/// let new_creature_id = 2;
+/// ReidentifyCreature::trigger(&mut server, creature_id, new_creature_id)
+///     .fire()
+///     .unwrap();
+/// assert!(server.battle().entities().creature(&creature_id).is_none());
+/// assert!(server.battle().entities().creature(&new_creature_id).is_some());
 /// ```
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
-pub struct RemoveCreature<R: BattleRules> {
+pub struct ReidentifyCreature<R: BattleRules> {
     #[cfg_attr(
         feature = "serialization",
         serde(bound(
@@ -674,13 +1100,704 @@ pub struct RemoveCreature<R: BattleRules> {
         ))
     )]
     id: CreatureId<R>,
-}
 
-impl<R: BattleRules> RemoveCreature<R> {
-    /// Returns a trigger for this event.
-    pub fn trigger<P: EventProcessor<R>>(
-        processor: &mut P,
-        id: CreatureId<R>,
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "CreatureId<R>: Serialize",
+            deserialize = "CreatureId<R>: Deserialize<'de>"
+        ))
+    )]
+    new_id: CreatureId<R>,
+}
+
+impl<R: BattleRules> ReidentifyCreature<R> {
+    /// Returns a trigger for this event.
+    pub fn trigger<P: EventProcessor<R>>(
+        processor: &mut P,
+        id: CreatureId<R>,
+        new_id: CreatureId<R>,
+    ) -> ReidentifyCreatureTrigger<R, P> {
+        ReidentifyCreatureTrigger {
+            processor,
+            id,
+            new_id,
+        }
+    }
+
+    /// Returns the current id of the creature.
+    pub fn id(&self) -> &CreatureId<R> {
+        &self.id
+    }
+
+    /// Returns the new id of the creature.
+    pub fn new_id(&self) -> &CreatureId<R> {
+        &self.new_id
+    }
+}
+
+impl<R: BattleRules> Debug for ReidentifyCreature<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "ReidentifyCreature {{ id: {:?}, new_id: {:?} }}",
+            self.id, self.new_id
+        )
+    }
+}
+
+impl<R: BattleRules> Clone for ReidentifyCreature<R> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+            new_id: self.new_id.clone(),
+        }
+    }
+}
+
+impl<R: BattleRules + 'static> Event<R> for ReidentifyCreature<R> {
+    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
+        // Verify if the creature exists.
+        if battle.entities().creature(&self.id).is_none() {
+            return Err(WeaselError::CreatureNotFound(self.id.clone()));
+        }
+        // Check id duplication.
+        if battle.entities().creature(&self.new_id).is_some() {
+            return Err(WeaselError::DuplicatedCreature(self.new_id.clone()));
+        }
+        Ok(())
+    }
+
+    fn apply(&self, battle: &mut Battle<R>, _event_queue: &mut Option<EventQueue<R>>) {
+        let watcher = battle.apply_watcher();
+        let creature = battle
+            .state
+            .entities
+            .creature(&self.id)
+            .unwrap_or_else(|| panic!("constraint violated: creature {:?} not found", self.id));
+        let position = creature.position().clone();
+        // Free the position and notify the rounds module, under the old id.
+        watch!(
+            watcher,
+            "SpaceRules::move_entity",
+            battle.state.space.move_entity(
+                PositionClaim::Movement(creature as &dyn Entity<R>),
+                None,
+                &mut battle.metrics.write_handle(),
+            )
+        );
+        watch!(
+            watcher,
+            "RoundsRules::on_actor_removed",
+            battle.state.rounds.on_actor_removed(
+                creature,
+                &mut battle.entropy,
+                &mut battle.metrics.write_handle(),
+            )
+        );
+        let pending = battle
+            .state
+            .rounds
+            .remove_pending_activation(creature.entity_id());
+        // Change the creature's id.
+        battle
+            .state
+            .entities
+            .reidentify_creature(&self.id, &self.new_id)
+            .unwrap_or_else(|err| panic!("constraint violated: {:?}", err));
+        battle.state.visibility.reidentify_entity(
+            &EntityId::Creature(self.id.clone()),
+            &EntityId::Creature(self.new_id.clone()),
+        );
+        battle.rights_mut().reidentify_entity(
+            &EntityId::Creature(self.id.clone()),
+            &EntityId::Creature(self.new_id.clone()),
+        );
+        let creature = battle
+            .state
+            .entities
+            .creature(&self.new_id)
+            .unwrap_or_else(|| panic!("constraint violated: creature {:?} not found", self.new_id));
+        // Take the position and notify the rounds module again, under the new id.
+        watch!(
+            watcher,
+            "SpaceRules::move_entity",
+            battle.state.space.move_entity(
+                PositionClaim::Spawn(creature.entity_id()),
+                Some(&position),
+                &mut battle.metrics.write_handle(),
+            )
+        );
+        watch!(
+            watcher,
+            "RoundsRules::on_actor_added",
+            battle.state.rounds.on_actor_added(
+                creature,
+                &mut battle.entropy,
+                &mut battle.metrics.write_handle(),
+            )
+        );
+        // Move the pending ability activation under the new id.
+        if let Some(mut pending) = pending {
+            pending.set_entity_id(creature.entity_id().clone());
+            battle.state.rounds.add_pending_activation(pending);
+        }
+        // Replace the creature's id among the current actors.
+        let old_entity_id = EntityId::Creature(self.id.clone());
+        battle
+            .state
+            .rounds
+            .reidentify_activations(&old_entity_id, creature.entity_id());
+        if let TurnState::Started(actors) = battle.state.rounds.state() {
+            if actors.contains(&old_entity_id) {
+                let actors = actors
+                    .iter()
+                    .map(|id| {
+                        if *id == old_entity_id {
+                            EntityId::Creature(self.new_id.clone())
+                        } else {
+                            id.clone()
+                        }
+                    })
+                    .collect();
+                battle.state.rounds.set_state(TurnState::Started(actors));
+            }
+        }
+    }
+
+    fn kind(&self) -> EventKind {
+        EventKind::ReidentifyCreature
+    }
+
+    fn box_clone(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Trigger to build and fire a `ReidentifyCreature` event.
+pub struct ReidentifyCreatureTrigger<'a, R, P>
+where
+    R: BattleRules,
+    P: EventProcessor<R>,
+{
+    processor: &'a mut P,
+    id: CreatureId<R>,
+    new_id: CreatureId<R>,
+}
+
+impl<'a, R, P> EventTrigger<'a, R, P> for ReidentifyCreatureTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    fn processor(&'a mut self) -> &'a mut P {
+        self.processor
+    }
+
+    /// Returns a `ReidentifyCreature` event.
+    fn event(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(ReidentifyCreature {
+            id: self.id.clone(),
+            new_id: self.new_id.clone(),
+        })
+    }
+}
+
+/// Event to transform a creature into a new form.
+///
+/// The creature's statistics and abilities are generated again from the given seeds,
+/// replacing the current ones, while its id, team, position and owner are preserved.
+/// Statuses are cleared, unless they are explicitly kept. A pending ability activation
+/// is interrupted.\
+/// Once the creature has been transformed, `CharacterRules::on_transform` is invoked.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateCreature,
+///     CreateTeam, EventTrigger, Server, TransformCreature,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// let team_id = 1;
+/// CreateTeam::trigger(&mut server, team_id).fire().unwrap();
+/// let creature_id = 1;
+/// CreateCreature::trigger(&mut server, creature_id, team_id, ())
+///     .fire()
+///     .unwrap();
+///
+/// TransformCreature::trigger(&mut server, creature_id)
+///     .keep_statuses()
+///     .fire()
+///     .unwrap();
+/// assert!(server.battle().entities().creature(&creature_id).is_some());
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct TransformCreature<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "CreatureId<R>: Serialize",
+            deserialize = "CreatureId<R>: Deserialize<'de>"
+        ))
+    )]
+    id: CreatureId<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Option<StatisticsSeed<R>>: Serialize",
+            deserialize = "Option<StatisticsSeed<R>>: Deserialize<'de>"
+        ))
+    )]
+    statistics_seed: Option<StatisticsSeed<R>>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Option<AbilitiesSeed<R>>: Serialize",
+            deserialize = "Option<AbilitiesSeed<R>>: Deserialize<'de>"
+        ))
+    )]
+    abilities_seed: Option<AbilitiesSeed<R>>,
+
+    keep_statuses: bool,
+}
+
+impl<R: BattleRules> TransformCreature<R> {
+    /// Returns a trigger for this event.
+    pub fn trigger<P: EventProcessor<R>>(
+        processor: &mut P,
+        id: CreatureId<R>,
+    ) -> TransformCreatureTrigger<'_, R, P> {
+        TransformCreatureTrigger {
+            processor,
+            id,
+            statistics_seed: None,
+            abilities_seed: None,
+            keep_statuses: false,
+        }
+    }
+
+    /// Returns the id of the creature to be transformed.
+    pub fn id(&self) -> &CreatureId<R> {
+        &self.id
+    }
+
+    /// Returns the seed to generate the creature's new statistics.
+    pub fn statistics_seed(&self) -> &Option<StatisticsSeed<R>> {
+        &self.statistics_seed
+    }
+
+    /// Returns the seed to generate the creature's new abilities.
+    pub fn abilities_seed(&self) -> &Option<AbilitiesSeed<R>> {
+        &self.abilities_seed
+    }
+
+    /// Returns true if the creature keeps its statuses.
+    pub fn keep_statuses(&self) -> bool {
+        self.keep_statuses
+    }
+}
+
+impl<R: BattleRules> Debug for TransformCreature<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "TransformCreature {{ id: {:?}, statistics_seed: {:?}, abilities_seed: {:?}, \
+             keep_statuses: {:?} }}",
+            self.id, self.statistics_seed, self.abilities_seed, self.keep_statuses
+        )
+    }
+}
+
+impl<R: BattleRules> Clone for TransformCreature<R> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+            statistics_seed: self.statistics_seed.clone(),
+            abilities_seed: self.abilities_seed.clone(),
+            keep_statuses: self.keep_statuses,
+        }
+    }
+}
+
+impl<R: BattleRules + 'static> Event<R> for TransformCreature<R> {
+    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
+        // Verify if the creature exists.
+        if battle.entities().creature(&self.id).is_none() {
+            return Err(WeaselError::CreatureNotFound(self.id.clone()));
+        }
+        Ok(())
+    }
+
+    fn apply(&self, battle: &mut Battle<R>, event_queue: &mut Option<EventQueue<R>>) {
+        let watcher = battle.apply_watcher();
+        let entity_id = EntityId::Creature(self.id.clone());
+        // Clear the creature's statuses, unless they must be kept.
+        let mut cleared = false;
+        if !self.keep_statuses {
+            let statuses: Vec<_> = battle
+                .state
+                .entities
+                .creature(&self.id)
+                .unwrap_or_else(|| panic!("constraint violated: creature {:?} not found", self.id))
+                .statuses()
+                .map(|status| status.id().clone())
+                .collect();
+            for status_id in &statuses {
+                clear_status(&entity_id, status_id, battle, event_queue);
+            }
+            cleared = !statuses.is_empty();
+        }
+        let creature = battle
+            .state
+            .entities
+            .creature_mut(&self.id)
+            .unwrap_or_else(|| panic!("constraint violated: creature {:?} not found", self.id));
+        // Remember the statistics' threshold levels before the transformation.
+        let levels = threshold_levels(battle.rules.character_rules(), creature);
+        // Generate the new statistics and abilities.
+        creature.statistics = watch!(
+            watcher,
+            "CharacterRules::generate_statistics",
+            collect_from_iter(battle.rules.character_rules().generate_statistics(
+                &self.statistics_seed,
+                &mut battle.entropy,
+                &mut battle.metrics.write_handle(),
+            ))
+        );
+        creature.abilities = watch!(
+            watcher,
+            "ActorRules::generate_abilities",
+            collect_from_iter(battle.rules.actor_rules().generate_abilities(
+                &self.abilities_seed,
+                &mut battle.entropy,
+                &mut battle.metrics.write_handle(),
+            ))
+        )
+        .into_iter()
+        .map(|(id, ability)| (id, TrackedAbility::new(ability)))
+        .collect();
+        // Interrupt the pending ability activation.
+        battle.state.rounds.remove_pending_activation(&entity_id);
+        // Notify the rules about any threshold crossing.
+        notify_threshold_crossings(battle, &entity_id, levels, event_queue);
+        // Invoke the character's rules callback.
+        let creature = battle
+            .state
+            .entities
+            .creature(&self.id)
+            .unwrap_or_else(|| panic!("constraint violated: creature {:?} not found", self.id));
+        watch!(
+            watcher,
+            "CharacterRules::on_transform",
+            battle.rules.character_rules().on_transform(
+                &battle.state,
+                creature,
+                event_queue,
+                &mut battle.entropy,
+                &mut battle.metrics.write_handle(),
+            )
+        );
+        // Auras projected by the cleared statuses must be removed.
+        if cleared {
+            update_auras(battle, event_queue);
+        }
+    }
+
+    fn kind(&self) -> EventKind {
+        EventKind::TransformCreature
+    }
+
+    fn box_clone(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Trigger to build and fire a `TransformCreature` event.
+pub struct TransformCreatureTrigger<'a, R, P>
+where
+    R: BattleRules,
+    P: EventProcessor<R>,
+{
+    processor: &'a mut P,
+    id: CreatureId<R>,
+    statistics_seed: Option<StatisticsSeed<R>>,
+    abilities_seed: Option<AbilitiesSeed<R>>,
+    keep_statuses: bool,
+}
+
+impl<'a, R, P> TransformCreatureTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    /// Adds a seed to drive the generation of the creature's new statistics.
+    pub fn statistics_seed(&'a mut self, seed: StatisticsSeed<R>) -> &'a mut Self {
+        self.statistics_seed = Some(seed);
+        self
+    }
+
+    /// Adds a seed to drive the generation of the creature's new abilities.
+    pub fn abilities_seed(&'a mut self, seed: AbilitiesSeed<R>) -> &'a mut Self {
+        self.abilities_seed = Some(seed);
+        self
+    }
+
+    /// Lets the creature keep its statuses through the transformation.
+    pub fn keep_statuses(&'a mut self) -> &'a mut Self {
+        self.keep_statuses = true;
+        self
+    }
+}
+
+impl<'a, R, P> EventTrigger<'a, R, P> for TransformCreatureTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    fn processor(&'a mut self) -> &'a mut P {
+        self.processor
+    }
+
+    /// Returns a `TransformCreature` event.
+    fn event(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(TransformCreature {
+            id: self.id.clone(),
+            statistics_seed: self.statistics_seed.clone(),
+            abilities_seed: self.abilities_seed.clone(),
+            keep_statuses: self.keep_statuses,
+        })
+    }
+}
+
+/// Event to give a player exclusive control over a creature, without changing its team.
+///
+/// Once a creature has an owner, only the owner can fire events on behalf of the creature;
+/// players' rights to the creature's team are not enough.\
+/// If no owner is set, the creature goes back under the control of the players with rights
+/// to its team.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateCreature,
+///     CreateTeam, EventTrigger, Server, TransferOwnership,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// let team_id = 1;
+/// CreateTeam::trigger(&mut server, team_id).fire().unwrap();
+/// let creature_id = 1;
+/// let position = ();
+/// CreateCreature::trigger(&mut server, creature_id, team_id, position)
+///     .fire()
+///     .unwrap();
+///
+/// let player_id = 5;
+/// TransferOwnership::trigger(&mut server, creature_id)
+///     .owner(player_id)
+///     .fire()
+///     .unwrap();
+/// let creature = server.battle().entities().creature(&creature_id).unwrap();
+/// assert_eq!(creature.owner(), Some(player_id));
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct TransferOwnership<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "CreatureId<R>: Serialize",
+            deserialize = "CreatureId<R>: Deserialize<'de>"
+        ))
+    )]
+    creature_id: CreatureId<R>,
+
+    owner: Option<PlayerId>,
+}
+
+impl<R: BattleRules> TransferOwnership<R> {
+    /// Returns a trigger for this event.
+    pub fn trigger<P: EventProcessor<R>>(
+        processor: &mut P,
+        creature_id: CreatureId<R>,
+    ) -> TransferOwnershipTrigger<R, P> {
+        TransferOwnershipTrigger {
+            processor,
+            creature_id,
+            owner: None,
+        }
+    }
+
+    /// Returns the id of the creature to be transferred.
+    pub fn creature_id(&self) -> &CreatureId<R> {
+        &self.creature_id
+    }
+
+    /// Returns the new owner of the creature.
+    pub fn owner(&self) -> Option<PlayerId> {
+        self.owner
+    }
+}
+
+impl<R: BattleRules> Debug for TransferOwnership<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "TransferOwnership {{ creature_id: {:?}, owner: {:?} }}",
+            self.creature_id, self.owner
+        )
+    }
+}
+
+impl<R: BattleRules> Clone for TransferOwnership<R> {
+    fn clone(&self) -> Self {
+        Self {
+            creature_id: self.creature_id.clone(),
+            owner: self.owner,
+        }
+    }
+}
+
+impl<R: BattleRules + 'static> Event<R> for TransferOwnership<R> {
+    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
+        // Verify if the creature exists.
+        if battle.entities().creature(&self.creature_id).is_none() {
+            return Err(WeaselError::CreatureNotFound(self.creature_id.clone()));
+        }
+        Ok(())
+    }
+
+    fn apply(&self, battle: &mut Battle<R>, _event_queue: &mut Option<EventQueue<R>>) {
+        let creature = battle
+            .state
+            .entities
+            .creature_mut(&self.creature_id)
+            .unwrap_or_else(|| {
+                panic!(
+                    "constraint violated: creature {:?} not found",
+                    self.creature_id
+                )
+            });
+        creature.set_owner(self.owner);
+    }
+
+    fn kind(&self) -> EventKind {
+        EventKind::TransferOwnership
+    }
+
+    fn box_clone(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Trigger to build and fire a `TransferOwnership` event.
+pub struct TransferOwnershipTrigger<'a, R, P>
+where
+    R: BattleRules,
+    P: EventProcessor<R>,
+{
+    processor: &'a mut P,
+    creature_id: CreatureId<R>,
+    owner: Option<PlayerId>,
+}
+
+impl<'a, R, P> TransferOwnershipTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    /// Sets the player who will own the creature.
+    pub fn owner(&'a mut self, owner: PlayerId) -> &'a mut Self {
+        self.owner = Some(owner);
+        self
+    }
+}
+
+impl<'a, R, P> EventTrigger<'a, R, P> for TransferOwnershipTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    fn processor(&'a mut self) -> &'a mut P {
+        self.processor
+    }
+
+    /// Returns a `TransferOwnership` event.
+    fn event(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(TransferOwnership {
+            creature_id: self.creature_id.clone(),
+            owner: self.owner,
+        })
+    }
+}
+
+/// Event to remove a creature from the battle.
+///
+/// If the creature is the current actor, its turn will be terminated.\
+/// The creature will be removed from the corresponding team and its position will be freed.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateCreature,
+///     CreateTeam, EventTrigger, RemoveCreature, Server,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// let team_id = 1;
+/// CreateTeam::trigger(&mut server, team_id).fire().unwrap();
+/// let creature_id = 1;
+/// let position = ();
+/// CreateCreature::trigger(&mut server, creature_id, team_id, position)
+///     .fire()
+///     .unwrap();
+///
+/// RemoveCreature::trigger(&mut server, creature_id).fire().unwrap();
+/// assert_eq!(server.battle().entities().creatures().count(), 0);
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct RemoveCreature<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "CreatureId<R>: Serialize",
+            deserialize = "CreatureId<R>: Deserialize<'de>"
+        ))
+    )]
+    id: CreatureId<R>,
+}
+
+impl<R: BattleRules> RemoveCreature<R> {
+    /// Returns a trigger for this event.
+    pub fn trigger<P: EventProcessor<R>>(
+        processor: &mut P,
+        id: CreatureId<R>,
     ) -> RemoveCreatureTrigger<R, P> {
         RemoveCreatureTrigger { processor, id }
     }
@@ -715,6 +1832,7 @@ impl<R: BattleRules + 'static> Event<R> for RemoveCreature<R> {
     }
 
     fn apply(&self, battle: &mut Battle<R>, event_queue: &mut Option<EventQueue<R>>) {
+        let watcher = battle.apply_watcher();
         let creature = battle
             .state
             .entities
@@ -724,52 +1842,102 @@ impl<R: BattleRules + 'static> Event<R> for RemoveCreature<R> {
         if let TurnState::Started(actors) = battle.state.rounds.state() {
             if actors.contains(creature.entity_id()) {
                 // Invoke `RoundRules` callback.
-                battle.state.rounds.on_end(
-                    &battle.state.entities,
-                    &battle.state.space,
-                    creature as &dyn Actor<_>,
-                    &mut battle.entropy,
-                    &mut battle.metrics.write_handle(),
+                watch!(
+                    watcher,
+                    "RoundsRules::on_end",
+                    battle.state.rounds.on_end(
+                        &battle.state.entities,
+                        &battle.state.space,
+                        creature as &dyn Actor<_>,
+                        &mut battle.entropy,
+                        &mut battle.metrics.write_handle(),
+                    )
                 );
                 // Check teams' objectives.
                 Battle::check_objectives(
-                    &battle.state,
+                    &mut battle.state,
                     &battle.rules.team_rules(),
                     &battle.metrics.read_handle(),
                     event_queue,
                     Checkpoint::TurnEnd,
+                    watcher,
                 );
                 // Set the turn state.
                 battle.state.rounds.set_state(TurnState::Ready);
+                battle.state.rounds.clear_activations();
             }
         }
+        // Remember the creature's summons, before their links are forgotten.
+        let summons: Vec<_> = battle
+            .state
+            .entities
+            .summons(&EntityId::Creature(self.id.clone()))
+            .cloned()
+            .collect();
         // Remove the creature.
         let creature = battle
             .state
             .entities
             .remove_creature(&self.id)
             .unwrap_or_else(|err| panic!("constraint violated: {:?}", err));
+        battle.state.visibility.remove_entity(creature.entity_id());
+        battle.rights_mut().forget_entity(creature.entity_id());
         // Invoke the character's rules callback.
-        battle.rules.character_rules().on_character_transmuted(
-            &battle.state,
-            &creature,
-            Transmutation::REMOVAL,
-            event_queue,
-            &mut battle.entropy,
-            &mut battle.metrics.write_handle(),
+        watch!(
+            watcher,
+            "CharacterRules::on_character_transmuted",
+            battle.rules.character_rules().on_character_transmuted(
+                &battle.state,
+                &creature,
+                Transmutation::REMOVAL,
+                event_queue,
+                &mut battle.entropy,
+                &mut battle.metrics.write_handle(),
+            )
         );
-        // Notify the rounds module.
-        battle.state.rounds.on_actor_removed(
-            &creature,
-            &mut battle.entropy,
-            &mut battle.metrics.write_handle(),
+        // Drop the creature's pending ability activation and notify the rounds module.
+        battle
+            .state
+            .rounds
+            .remove_pending_activation(creature.entity_id());
+        battle.state.rounds.remove_activations(creature.entity_id());
+        watch!(
+            watcher,
+            "RoundsRules::on_actor_removed",
+            battle.state.rounds.on_actor_removed(
+                &creature,
+                &mut battle.entropy,
+                &mut battle.metrics.write_handle(),
+            )
         );
         // Free the position.
-        battle.state.space.move_entity(
-            PositionClaim::Movement(&creature as &dyn Entity<R>),
-            None,
-            &mut battle.metrics.write_handle(),
+        watch!(
+            watcher,
+            "SpaceRules::move_entity",
+            battle.state.space.move_entity(
+                PositionClaim::Movement(&creature as &dyn Entity<R>),
+                None,
+                &mut battle.metrics.write_handle(),
+            )
         );
+        // Ask the rules whether the summons should follow their summoner.
+        for summon_id in summons {
+            if let Some(summon) = battle.state.entities.character(&summon_id) {
+                if watch!(
+                    watcher,
+                    "CharacterRules::on_summoner_removed",
+                    battle.rules.character_rules().on_summoner_removed(
+                        &battle.state,
+                        &creature,
+                        summon,
+                    )
+                ) {
+                    if let EntityId::Creature(id) = summon_id {
+                        RemoveCreature::trigger(event_queue, id).fire();
+                    }
+                }
+            }
+        }
     }
 
     fn kind(&self) -> EventKind {
@@ -1,22 +1,35 @@
 //! Module for entities and their storage.
 
 use crate::actor::Actor;
-use crate::battle::BattleRules;
-use crate::character::Character;
+#[cfg(feature = "arena")]
+use crate::arena::{Arena, Handle};
+use crate::battle::{Battle, BattleRules};
+use crate::character::{Character, Statistic, StatisticId};
 use crate::creature::{Creature, CreatureId, RemoveCreature};
 use crate::error::{WeaselError, WeaselResult};
-use crate::event::{Event, EventProcessor, EventTrigger};
-use crate::object::{Object, ObjectId, RemoveObject};
+use crate::event::{Event, EventKind, EventProcessor, EventQueue, EventTrigger};
+use crate::object::{Object, ObjectId, ObjectOwner, RemoveObject};
+use crate::round::RoundsCount;
 use crate::space::Position;
-use crate::team::{Conclusion, Relation, RelationshipPair, Team, TeamId};
+use crate::team::{
+    Conclusion, ObjectiveProgress, Relation, RelationshipPair, TargetRelation, Team, TeamId,
+};
 use crate::util::Id;
-use indexmap::IndexMap;
+use indexmap::{IndexMap, IndexSet};
 #[cfg(feature = "serialization")]
 use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::borrow::Borrow;
+use std::cmp::{Ordering, Reverse};
 use std::fmt::{Debug, Display, Formatter, Result};
 use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
 
+/// Type to represent a tag, used to group entities under an arbitrary trait.
+///
+/// Tags are meaningful only to the rules that define them, e.g. *flying* or *undead*.
+pub type Tag = u16;
+
 /// An entity represents any being existing in the game world.
 pub trait Entity<R: BattleRules> {
     /// Returns the id of this entity.
@@ -147,6 +160,32 @@ impl<R: BattleRules> PartialEq<Self> for EntityId<R> {
 
 impl<R: BattleRules> Eq for EntityId<R> {}
 
+/// Creatures come before objects. Ids of the same kind are compared with each other.
+impl<R: BattleRules> PartialOrd<Self> for EntityId<R>
+where
+    CreatureId<R>: Ord,
+    ObjectId<R>: Ord,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<R: BattleRules> Ord for EntityId<R>
+where
+    CreatureId<R>: Ord,
+    ObjectId<R>: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Creature(id), Self::Creature(other_id)) => id.cmp(other_id),
+            (Self::Object(id), Self::Object(other_id)) => id.cmp(other_id),
+            (Self::Creature(_), Self::Object(_)) => Ordering::Less,
+            (Self::Object(_), Self::Creature(_)) => Ordering::Greater,
+        }
+    }
+}
+
 impl<R: BattleRules> Hash for EntityId<R> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         match self {
@@ -183,24 +222,212 @@ pub(crate) fn transmute_entity<R, P>(
     }
 }
 
+/// Storage for creatures and objects.
+#[cfg(not(feature = "arena"))]
+type Storage<K, V> = IndexMap<K, V>;
+
+/// Storage for creatures and objects.
+#[cfg(feature = "arena")]
+type Storage<K, V> = Arena<K, V>;
+
+/// Changes the key of an element in a storage, preserving the elements' order.
+#[cfg(not(feature = "arena"))]
+fn rekey<K: Hash + Eq + Clone, V>(storage: &mut Storage<K, V>, key: &K, new_key: &K) {
+    *storage = storage
+        .drain(..)
+        .map(|(k, v)| {
+            if k == *key {
+                (new_key.clone(), v)
+            } else {
+                (k, v)
+            }
+        })
+        .collect();
+}
+
+/// Changes the key of an element in a storage, preserving the elements' order.
+#[cfg(feature = "arena")]
+fn rekey<K: Hash + Eq + Clone, V>(storage: &mut Storage<K, V>, key: &K, new_key: &K) {
+    storage.rekey(key, new_key.clone());
+}
+
 /// Data structure to manage ownership of teams and entities.
+///
+/// With the `arena` feature creatures and objects are kept in generational arenas, which
+/// offer denser iterations and stable handles.
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub struct Entities<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(
+            bound(
+                serialize = "TeamId<R>: Serialize, Team<R>: Serialize",
+                deserialize = "TeamId<R>: Deserialize<'de>, Team<R>: Deserialize<'de>"
+            ),
+            serialize_with = "crate::serde::serialize_pairs",
+            deserialize_with = "crate::serde::deserialize_pairs"
+        )
+    )]
     teams: IndexMap<TeamId<R>, Team<R>>,
-    creatures: IndexMap<CreatureId<R>, Creature<R>>,
-    objects: IndexMap<ObjectId<R>, Object<R>>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(
+            bound(
+                serialize = "CreatureId<R>: Serialize, Creature<R>: Serialize",
+                deserialize = "CreatureId<R>: Deserialize<'de>, Creature<R>: Deserialize<'de>"
+            ),
+            serialize_with = "crate::serde::serialize_pairs",
+            deserialize_with = "crate::serde::deserialize_pairs"
+        )
+    )]
+    creatures: Storage<CreatureId<R>, Creature<R>>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(
+            bound(
+                serialize = "ObjectId<R>: Serialize, Object<R>: Serialize",
+                deserialize = "ObjectId<R>: Deserialize<'de>, Object<R>: Deserialize<'de>"
+            ),
+            serialize_with = "crate::serde::serialize_pairs",
+            deserialize_with = "crate::serde::deserialize_pairs"
+        )
+    )]
+    objects: Storage<ObjectId<R>, Object<R>>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(
+            bound(
+                serialize = "TeamId<R>: Serialize",
+                deserialize = "TeamId<R>: Deserialize<'de>"
+            ),
+            serialize_with = "crate::serde::serialize_pairs",
+            deserialize_with = "crate::serde::deserialize_pairs"
+        )
+    )]
     relations: IndexMap<RelationshipPair<R>, Relation>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(
+            bound(
+                serialize = "EntityId<R>: Serialize",
+                deserialize = "EntityId<R>: Deserialize<'de>"
+            ),
+            serialize_with = "crate::serde::serialize_pairs",
+            deserialize_with = "crate::serde::deserialize_pairs"
+        )
+    )]
+    time_to_live: IndexMap<EntityId<R>, RoundsCount>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(
+            bound(
+                serialize = "EntityId<R>: Serialize",
+                deserialize = "EntityId<R>: Deserialize<'de>"
+            ),
+            serialize_with = "crate::serde::serialize_pairs",
+            deserialize_with = "crate::serde::deserialize_pairs"
+        )
+    )]
+    summoners: IndexMap<EntityId<R>, EntityId<R>>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(
+            bound(
+                serialize = "EntityId<R>: Serialize",
+                deserialize = "EntityId<R>: Deserialize<'de>"
+            ),
+            serialize_with = "crate::serde::serialize_pairs",
+            deserialize_with = "crate::serde::deserialize_pairs"
+        )
+    )]
+    tags: IndexMap<EntityId<R>, IndexSet<Tag>>,
+
+    #[cfg_attr(feature = "serialization", serde(skip, default = "IndexSet::new"))]
+    changed_entities: IndexSet<EntityId<R>>,
+
+    #[cfg_attr(feature = "serialization", serde(skip, default = "IndexSet::new"))]
+    changed_teams: IndexSet<TeamId<R>>,
 }
 
 impl<R: BattleRules> Entities<R> {
-    pub(crate) fn new() -> Self {
+    /// Creates a new storage with room for at least `creatures` creatures and
+    /// `objects` objects without reallocating.
+    pub(crate) fn with_capacity(creatures: usize, objects: usize) -> Self {
         Self {
             teams: IndexMap::new(),
-            creatures: IndexMap::new(),
-            objects: IndexMap::new(),
+            creatures: Storage::with_capacity(creatures),
+            objects: Storage::with_capacity(objects),
             relations: IndexMap::new(),
+            time_to_live: IndexMap::new(),
+            summoners: IndexMap::new(),
+            tags: IndexMap::new(),
+            changed_entities: IndexSet::new(),
+            changed_teams: IndexSet::new(),
         }
     }
 
+    /// Reserves room for at least `creatures` more creatures and `objects` more objects.
+    ///
+    /// Useful to avoid repeated reallocations before spawning a large amount of entities.
+    pub fn reserve(&mut self, creatures: usize, objects: usize) {
+        self.creatures.reserve(creatures);
+        self.objects.reserve(objects);
+    }
+
+    /// Returns the number of creatures.
+    pub fn creatures_len(&self) -> usize {
+        self.creatures.len()
+    }
+
+    /// Returns the number of objects.
+    pub fn objects_len(&self) -> usize {
+        self.objects.len()
+    }
+
+    /// Returns the number of creatures that can be stored without reallocating.
+    pub fn creatures_capacity(&self) -> usize {
+        self.creatures.capacity()
+    }
+
+    /// Returns the number of objects that can be stored without reallocating.
+    pub fn objects_capacity(&self) -> usize {
+        self.objects.capacity()
+    }
+
+    /// Returns an iterator over the ids of all entities touched since the last event began.
+    pub(crate) fn changed_entities(&self) -> impl Iterator<Item = &EntityId<R>> {
+        self.changed_entities.iter()
+    }
+
+    /// Returns an iterator over the ids of all teams touched since the last event began.
+    pub(crate) fn changed_teams(&self) -> impl Iterator<Item = &TeamId<R>> {
+        self.changed_teams.iter()
+    }
+
+    /// Forgets all tracked changes.
+    pub(crate) fn clear_changes(&mut self) {
+        self.changed_entities.clear();
+        self.changed_teams.clear();
+    }
+
+    /// Marks all creatures as changed.
+    fn touch_creatures(&mut self) {
+        let creatures = self.creatures.keys().cloned().map(EntityId::Creature);
+        self.changed_entities.extend(creatures);
+    }
+
+    /// Marks all objects as changed.
+    fn touch_objects(&mut self) {
+        let objects = self.objects.keys().cloned().map(EntityId::Object);
+        self.changed_entities.extend(objects);
+    }
+
     /// Returns an iterator over creatures.
     pub fn creatures(&self) -> impl Iterator<Item = &Creature<R>> {
         self.creatures.values()
@@ -208,17 +435,59 @@ impl<R: BattleRules> Entities<R> {
 
     /// Returns a mutable iterator over creatures.
     pub fn creatures_mut(&mut self) -> impl Iterator<Item = &mut Creature<R>> {
+        self.touch_creatures();
         self.creatures.values_mut()
     }
 
     /// Returns the creature with the given id.
-    pub fn creature(&self, id: &CreatureId<R>) -> Option<&Creature<R>> {
+    ///
+    /// The id can be any borrowed form of `CreatureId`, so that lookups don't require
+    /// to own or clone an id.
+    pub fn creature<Q>(&self, id: &Q) -> Option<&Creature<R>>
+    where
+        CreatureId<R>: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
         self.creatures.get(id)
     }
 
     /// Returns a mutable reference to the creature with the given id.
-    pub fn creature_mut(&mut self, id: &CreatureId<R>) -> Option<&mut Creature<R>> {
-        self.creatures.get_mut(id)
+    ///
+    /// The id can be any borrowed form of `CreatureId`.
+    pub fn creature_mut<Q>(&mut self, id: &Q) -> Option<&mut Creature<R>>
+    where
+        CreatureId<R>: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (_, id, creature) = self.creatures.get_full_mut(id)?;
+        self.changed_entities.insert(EntityId::Creature(id.clone()));
+        Some(creature)
+    }
+
+    /// Returns a stable handle to the creature with the given id.
+    ///
+    /// The handle stays valid until the creature is removed, even if its id changes.
+    #[cfg(feature = "arena")]
+    pub fn creature_handle<Q>(&self, id: &Q) -> Option<Handle>
+    where
+        CreatureId<R>: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.creatures.handle(id)
+    }
+
+    /// Returns the creature with the given handle.
+    #[cfg(feature = "arena")]
+    pub fn creature_by_handle(&self, handle: Handle) -> Option<&Creature<R>> {
+        self.creatures.get_by_handle(handle)
+    }
+
+    /// Returns a mutable reference to the creature with the given handle.
+    #[cfg(feature = "arena")]
+    pub fn creature_by_handle_mut(&mut self, handle: Handle) -> Option<&mut Creature<R>> {
+        let (id, creature) = self.creatures.get_by_handle_mut(handle)?;
+        self.changed_entities.insert(EntityId::Creature(id.clone()));
+        Some(creature)
     }
 
     /// Returns an iterator over objects.
@@ -228,17 +497,59 @@ impl<R: BattleRules> Entities<R> {
 
     /// Returns a mutable iterator over objects.
     pub fn objects_mut(&mut self) -> impl Iterator<Item = &mut Object<R>> {
+        self.touch_objects();
         self.objects.values_mut()
     }
 
     /// Returns the object with the given id.
-    pub fn object(&self, id: &ObjectId<R>) -> Option<&Object<R>> {
+    ///
+    /// The id can be any borrowed form of `ObjectId`, so that lookups don't require
+    /// to own or clone an id.
+    pub fn object<Q>(&self, id: &Q) -> Option<&Object<R>>
+    where
+        ObjectId<R>: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
         self.objects.get(id)
     }
 
     /// Returns a mutable reference to the object with the given id.
-    pub fn object_mut(&mut self, id: &ObjectId<R>) -> Option<&mut Object<R>> {
-        self.objects.get_mut(id)
+    ///
+    /// The id can be any borrowed form of `ObjectId`.
+    pub fn object_mut<Q>(&mut self, id: &Q) -> Option<&mut Object<R>>
+    where
+        ObjectId<R>: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (_, id, object) = self.objects.get_full_mut(id)?;
+        self.changed_entities.insert(EntityId::Object(id.clone()));
+        Some(object)
+    }
+
+    /// Returns a stable handle to the object with the given id.
+    ///
+    /// The handle stays valid until the object is removed.
+    #[cfg(feature = "arena")]
+    pub fn object_handle<Q>(&self, id: &Q) -> Option<Handle>
+    where
+        ObjectId<R>: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.objects.handle(id)
+    }
+
+    /// Returns the object with the given handle.
+    #[cfg(feature = "arena")]
+    pub fn object_by_handle(&self, handle: Handle) -> Option<&Object<R>> {
+        self.objects.get_by_handle(handle)
+    }
+
+    /// Returns a mutable reference to the object with the given handle.
+    #[cfg(feature = "arena")]
+    pub fn object_by_handle_mut(&mut self, handle: Handle) -> Option<&mut Object<R>> {
+        let (id, object) = self.objects.get_by_handle_mut(handle)?;
+        self.changed_entities.insert(EntityId::Object(id.clone()));
+        Some(object)
     }
 
     /// Returns an iterator over teams.
@@ -248,20 +559,157 @@ impl<R: BattleRules> Entities<R> {
 
     /// Returns a mutable iterator over teams.
     pub fn teams_mut(&mut self) -> impl Iterator<Item = &mut Team<R>> {
+        self.changed_teams.extend(self.teams.keys().cloned());
         self.teams.values_mut()
     }
 
+    /// Returns the number of rounds left before the entity with the given id expires.
+    ///
+    /// Entities without a time to live never expire.
+    pub fn time_to_live(&self, id: &EntityId<R>) -> Option<RoundsCount> {
+        self.time_to_live.get(id).copied()
+    }
+
+    /// Returns an iterator over all entities with a time to live, together with
+    /// the number of rounds they have left.
+    pub fn expiring(&self) -> impl Iterator<Item = (&EntityId<R>, RoundsCount)> {
+        self.time_to_live.iter().map(|(id, rounds)| (id, *rounds))
+    }
+
+    /// Sets or clears the time to live of an entity.
+    pub(crate) fn set_time_to_live(&mut self, id: &EntityId<R>, rounds: Option<RoundsCount>) {
+        match rounds {
+            Some(rounds) => {
+                self.time_to_live.insert(id.clone(), rounds);
+            }
+            None => {
+                self.time_to_live.shift_remove(id);
+            }
+        }
+    }
+
+    /// Decreases by one round the time to live of all entities.
+    ///
+    /// Returns the ids of the entities whose time to live expired.
+    pub(crate) fn decrease_time_to_live(&mut self) -> Vec<EntityId<R>> {
+        let mut expired = Vec::new();
+        self.time_to_live.retain(|id, rounds| {
+            *rounds = rounds.saturating_sub(1);
+            if *rounds == 0 {
+                expired.push(id.clone());
+                false
+            } else {
+                true
+            }
+        });
+        expired
+    }
+
+    /// Returns the id of the entity that summoned the entity with the given id, if any.
+    pub fn summoner(&self, id: &EntityId<R>) -> Option<&EntityId<R>> {
+        self.summoners.get(id)
+    }
+
+    /// Returns an iterator over the ids of all entities summoned by the entity with
+    /// the given id.
+    pub fn summons<'a>(
+        &'a self,
+        id: &'a EntityId<R>,
+    ) -> impl Iterator<Item = &'a EntityId<R>> + 'a {
+        self.summoners
+            .iter()
+            .filter(move |(_, summoner)| *summoner == id)
+            .map(|(summon, _)| summon)
+    }
+
+    /// Links a summon to the entity that summoned it.
+    pub(crate) fn link_summon(&mut self, summon: EntityId<R>, summoner: EntityId<R>) {
+        self.summoners.insert(summon, summoner);
+    }
+
+    /// Returns an iterator over the tags of the entity with the given id.
+    pub fn tags(&self, id: &EntityId<R>) -> impl Iterator<Item = Tag> + '_ {
+        self.tags.get(id).into_iter().flatten().copied()
+    }
+
+    /// Returns true if the entity with the given id has `tag`.
+    pub fn has_tag(&self, id: &EntityId<R>, tag: Tag) -> bool {
+        match self.tags.get(id) {
+            Some(tags) => tags.contains(&tag),
+            None => false,
+        }
+    }
+
+    /// Returns an iterator over the ids of all entities having `tag`.
+    pub fn entities_with_tag(&self, tag: Tag) -> impl Iterator<Item = &EntityId<R>> {
+        self.tags
+            .iter()
+            .filter(move |(_, tags)| tags.contains(&tag))
+            .map(|(id, _)| id)
+    }
+
+    /// Adds a tag to an entity.
+    pub(crate) fn tag(&mut self, id: &EntityId<R>, tag: Tag) {
+        self.tags.entry(id.clone()).or_default().insert(tag);
+        self.changed_entities.insert(id.clone());
+    }
+
+    /// Removes a tag from an entity.
+    pub(crate) fn untag(&mut self, id: &EntityId<R>, tag: Tag) {
+        if let Some(tags) = self.tags.get_mut(id) {
+            tags.shift_remove(&tag);
+            if tags.is_empty() {
+                self.tags.shift_remove(id);
+            }
+        }
+        self.changed_entities.insert(id.clone());
+    }
+
+    /// Returns all teams sorted by score, in descending order.
+    /// Teams with the same score keep their creation order.
+    pub fn leaderboard(&self) -> Vec<&Team<R>> {
+        let mut teams: Vec<_> = self.teams.values().collect();
+        teams.sort_by_key(|team| Reverse(team.score()));
+        teams
+    }
+
     /// Returns the team with the given id.
-    pub fn team(&self, id: &TeamId<R>) -> Option<&Team<R>> {
+    ///
+    /// The id can be any borrowed form of `TeamId`.
+    pub fn team<Q>(&self, id: &Q) -> Option<&Team<R>>
+    where
+        TeamId<R>: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
         self.teams.get(id)
     }
 
     /// Returns a mutable reference to the team with the given id.
-    pub fn team_mut(&mut self, id: &TeamId<R>) -> Option<&mut Team<R>> {
-        self.teams.get_mut(id)
+    ///
+    /// The id can be any borrowed form of `TeamId`.
+    pub fn team_mut<Q>(&mut self, id: &Q) -> Option<&mut Team<R>>
+    where
+        TeamId<R>: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (_, id, team) = self.teams.get_full_mut(id)?;
+        self.changed_teams.insert(id.clone());
+        Some(team)
+    }
+
+    /// Updates the objectives progress of a team, without flagging it as changed.
+    pub(crate) fn set_team_progress(
+        &mut self,
+        id: &TeamId<R>,
+        progress: Option<Box<dyn ObjectiveProgress>>,
+    ) {
+        if let Some(team) = self.teams.get_mut(id) {
+            team.set_progress(progress);
+        }
     }
 
     pub(crate) fn add_team(&mut self, team: Team<R>) {
+        self.changed_teams.insert(team.id().clone());
         self.teams.insert(team.id().clone(), team);
     }
 
@@ -272,6 +720,8 @@ impl<R: BattleRules> Entities<R> {
             .get_mut(creature.team_id())
             .ok_or_else(|| WeaselError::TeamNotFound(creature.team_id().clone()))?;
         team.creatures_mut().push(creature.id().clone());
+        self.changed_teams.insert(creature.team_id().clone());
+        self.changed_entities.insert(creature.entity_id().clone());
         // Insert the creature.
         self.creatures.insert(creature.id().clone(), creature);
         Ok(())
@@ -279,6 +729,7 @@ impl<R: BattleRules> Entities<R> {
 
     pub(crate) fn add_object(&mut self, object: Object<R>) {
         // Insert the object.
+        self.changed_entities.insert(object.entity_id().clone());
         self.objects.insert(object.id().clone(), object);
     }
 
@@ -292,6 +743,8 @@ impl<R: BattleRules> Entities<R> {
 
     /// Returns a mutable iterator over entities.
     pub fn entities_mut(&mut self) -> impl Iterator<Item = &mut dyn Entity<R>> {
+        self.touch_creatures();
+        self.touch_objects();
         self.creatures
             .values_mut()
             .map(|e| e as &mut dyn Entity<R>)
@@ -321,8 +774,45 @@ impl<R: BattleRules> Entities<R> {
             .chain(self.objects().map(|e| e as &dyn Character<R>))
     }
 
+    /// Returns all characters having the statistic with the given id, ordered by
+    /// the value of such statistic.
+    ///
+    /// `compare` defines the ordering between two statistics. If `descending` is true
+    /// the order is reversed. Characters with equivalent statistics are ordered by entity id.
+    pub fn sorted_by_statistic<F>(
+        &self,
+        id: &StatisticId<R>,
+        descending: bool,
+        mut compare: F,
+    ) -> Vec<&dyn Character<R>>
+    where
+        F: FnMut(&Statistic<R>, &Statistic<R>) -> Ordering,
+        CreatureId<R>: Ord,
+        ObjectId<R>: Ord,
+    {
+        let mut characters: Vec<_> = self
+            .characters()
+            .filter_map(|character| character.statistic(id).map(|stat| (character, stat)))
+            .collect();
+        characters.sort_by(|(a, a_stat), (b, b_stat)| {
+            let ordering = compare(a_stat, b_stat);
+            let ordering = if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            };
+            ordering.then_with(|| a.entity_id().cmp(b.entity_id()))
+        });
+        characters
+            .into_iter()
+            .map(|(character, _)| character)
+            .collect()
+    }
+
     /// Returns a mutable iterator over characters.
     pub fn characters_mut(&mut self) -> impl Iterator<Item = &mut dyn Character<R>> {
+        self.touch_creatures();
+        self.touch_objects();
         self.creatures
             .values_mut()
             .map(|e| e as &mut dyn Character<R>)
@@ -379,6 +869,8 @@ impl<R: BattleRules> Entities<R> {
     /// Existing relations are overridden.
     pub(crate) fn update_relations(&mut self, relations: Vec<(RelationshipPair<R>, Relation)>) {
         for (pair, relation) in relations {
+            self.changed_teams.insert(pair.first.clone());
+            self.changed_teams.insert(pair.second.clone());
             self.relations.insert(pair, relation);
         }
     }
@@ -396,6 +888,52 @@ impl<R: BattleRules> Entities<R> {
         }
     }
 
+    /// Returns the team of an entity.
+    ///
+    /// Creatures belong to their team, while objects belong to the team that owns them, if any.
+    pub fn entity_team(&self, id: &EntityId<R>) -> Option<&TeamId<R>> {
+        match id {
+            EntityId::Creature(id) => self.creature(id).map(|creature| creature.team_id()),
+            EntityId::Object(id) => match self.object(id).and_then(|object| object.owner()) {
+                Some(ObjectOwner::Team(team_id)) => Some(team_id),
+                _ => None,
+            },
+        }
+    }
+
+    /// Returns the `Relation` between the teams of two entities.
+    ///
+    /// Entities not belonging to any team have no relation with the others.
+    pub fn entity_relation(&self, first: &EntityId<R>, second: &EntityId<R>) -> Option<Relation> {
+        self.relation(self.entity_team(first)?, self.entity_team(second)?)
+    }
+
+    /// Verifies that `target` satisfies the relation constraint with respect to `actor`.
+    ///
+    /// Use this helper in the rules' verification hooks to enforce, for instance, that
+    /// an offensive ability can only target enemies.
+    pub fn verify_target(
+        &self,
+        actor: &EntityId<R>,
+        target: &EntityId<R>,
+        constraint: TargetRelation,
+    ) -> WeaselResult<(), R> {
+        for id in &[actor, target] {
+            if self.entity(id).is_none() {
+                return Err(WeaselError::EntityNotFound((*id).clone()));
+            }
+        }
+        if constraint.allows(self.entity_relation(actor, target)) {
+            Ok(())
+        } else {
+            Err(WeaselError::InvalidTargetRelation(
+                actor.clone(),
+                target.clone(),
+                constraint,
+            ))
+        }
+    }
+
     /// Returns all allied teams' id of a team.
     pub fn allies_id<'a>(&'a self, id: &'a TeamId<R>) -> impl Iterator<Item = TeamId<R>> + 'a {
         self.relations
@@ -467,6 +1005,14 @@ impl<R: BattleRules> Entities<R> {
             .get_mut(creature.team_id())
             .ok_or_else(|| WeaselError::TeamNotFound(creature.team_id().clone()))?;
         team.remove_creature(id);
+        self.time_to_live.shift_remove(creature.entity_id());
+        // Forget the links with summoner and summons.
+        let entity_id = creature.entity_id();
+        self.summoners
+            .retain(|summon, summoner| summon != entity_id && summoner != entity_id);
+        self.tags.shift_remove(entity_id);
+        self.changed_teams.insert(creature.team_id().clone());
+        self.changed_entities.insert(creature.entity_id().clone());
         Ok(creature)
     }
 
@@ -485,7 +1031,7 @@ impl<R: BattleRules> Entities<R> {
         let current_team = self
             .teams
             .get_mut(&current_team_id)
-            .ok_or(WeaselError::TeamNotFound(current_team_id))?;
+            .ok_or_else(|| WeaselError::TeamNotFound(current_team_id.clone()))?;
         current_team.remove_creature(creature_id);
         // Change the new team's creature lists.
         let new_team = self
@@ -495,6 +1041,137 @@ impl<R: BattleRules> Entities<R> {
         new_team.creatures_mut().push(creature_id.clone());
         // Change the creature's team.
         creature.set_team_id(team_id.clone());
+        creature.set_owner(None);
+        self.changed_entities
+            .insert(EntityId::Creature(creature_id.clone()));
+        self.changed_teams.insert(current_team_id);
+        self.changed_teams.insert(team_id.clone());
+        Ok(())
+    }
+
+    /// Changes the id of a creature, updating the creature list of its team.
+    /// The creature must exist and the new id must be free.
+    pub(crate) fn reidentify_creature(
+        &mut self,
+        id: &CreatureId<R>,
+        new_id: &CreatureId<R>,
+    ) -> WeaselResult<(), R> {
+        if self.creatures.contains_key(new_id) {
+            return Err(WeaselError::DuplicatedCreature(new_id.clone()));
+        }
+        let creature = self
+            .creatures
+            .get_mut(id)
+            .ok_or_else(|| WeaselError::CreatureNotFound(id.clone()))?;
+        creature.set_id(new_id.clone());
+        let team_id = creature.team_id().clone();
+        rekey(&mut self.creatures, id, new_id);
+        // Change the id inside the team's creature list.
+        let team = self
+            .teams
+            .get_mut(&team_id)
+            .ok_or_else(|| WeaselError::TeamNotFound(team_id.clone()))?;
+        for creature_id in team.creatures_mut() {
+            if creature_id == id {
+                *creature_id = new_id.clone();
+            }
+        }
+        // Carry over the time to live.
+        if let Some(rounds) = self
+            .time_to_live
+            .shift_remove(&EntityId::Creature(id.clone()))
+        {
+            self.time_to_live
+                .insert(EntityId::Creature(new_id.clone()), rounds);
+        }
+        // Carry over the tags.
+        if let Some(tags) = self.tags.shift_remove(&EntityId::Creature(id.clone())) {
+            self.tags.insert(EntityId::Creature(new_id.clone()), tags);
+        }
+        // Carry over the links with summoner and summons.
+        let (entity_id, new_entity_id) = (
+            EntityId::Creature(id.clone()),
+            EntityId::Creature(new_id.clone()),
+        );
+        self.summoners = self
+            .summoners
+            .drain(..)
+            .map(|(summon, summoner)| {
+                let rename = |id: EntityId<R>| {
+                    if id == entity_id {
+                        new_entity_id.clone()
+                    } else {
+                        id
+                    }
+                };
+                (rename(summon), rename(summoner))
+            })
+            .collect();
+        self.changed_entities.insert(EntityId::Creature(id.clone()));
+        self.changed_entities
+            .insert(EntityId::Creature(new_id.clone()));
+        self.changed_teams.insert(team_id);
+        Ok(())
+    }
+
+    /// Changes the id of a team, updating its creatures and its relations.
+    /// The team must exist and the new id must be free.
+    pub(crate) fn rename_team(
+        &mut self,
+        id: &TeamId<R>,
+        new_id: &TeamId<R>,
+    ) -> WeaselResult<(), R> {
+        if self.teams.contains_key(new_id) {
+            return Err(WeaselError::DuplicatedTeam(new_id.clone()));
+        }
+        let team = self
+            .teams
+            .get_mut(id)
+            .ok_or_else(|| WeaselError::TeamNotFound(id.clone()))?;
+        team.set_id(new_id.clone());
+        // Change the team id of all the team's creatures.
+        for creature_id in team.creatures() {
+            let creature = self
+                .creatures
+                .get_mut(creature_id)
+                .ok_or_else(|| WeaselError::CreatureNotFound(creature_id.clone()))?;
+            creature.set_team_id(new_id.clone());
+            self.changed_entities
+                .insert(EntityId::Creature(creature_id.clone()));
+        }
+        self.changed_teams.insert(id.clone());
+        self.changed_teams.insert(new_id.clone());
+        // Rebuild the maps to preserve the order of teams and relations.
+        self.teams = self
+            .teams
+            .drain(..)
+            .map(|(k, v)| {
+                if k == *id {
+                    (new_id.clone(), v)
+                } else {
+                    (k, v)
+                }
+            })
+            .collect();
+        let rename = |team_id: TeamId<R>| {
+            if team_id == *id {
+                new_id.clone()
+            } else {
+                team_id
+            }
+        };
+        self.relations = self
+            .relations
+            .drain(..)
+            .map(|(k, v)| (RelationshipPair::new(rename(k.first), rename(k.second)), v))
+            .collect();
+        // Update the owner of the team's objects.
+        for object in self.objects.values_mut() {
+            if object.owner() == Some(&ObjectOwner::Team(id.clone())) {
+                object.set_owner(Some(ObjectOwner::Team(new_id.clone())));
+                self.changed_entities.insert(object.entity_id().clone());
+            }
+        }
         Ok(())
     }
 
@@ -507,6 +1184,9 @@ impl<R: BattleRules> Entities<R> {
             .objects
             .remove(id)
             .ok_or_else(|| WeaselError::ObjectNotFound(id.clone()))?;
+        self.time_to_live.shift_remove(object.entity_id());
+        self.tags.shift_remove(object.entity_id());
+        self.changed_entities.insert(EntityId::Object(id.clone()));
         Ok(object)
     }
 
@@ -527,6 +1207,14 @@ impl<R: BattleRules> Entities<R> {
             .teams
             .remove(id)
             .ok_or_else(|| WeaselError::TeamNotFound(id.clone()))?;
+        // The team's objects lose their owner.
+        for object in self.objects.values_mut() {
+            if object.owner() == Some(&ObjectOwner::Team(id.clone())) {
+                object.set_owner(None);
+                self.changed_entities.insert(object.entity_id().clone());
+            }
+        }
+        self.changed_teams.insert(id.clone());
         Ok(team)
     }
 }
@@ -600,6 +1288,348 @@ where
     }
 }
 
+/// Event to set or clear the time to live of an entity.
+///
+/// An entity with a time to live is removed automatically once the given number of rounds
+/// has ended, counting the current one. Useful for temporary walls, decoys or conjured items.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateCreature,
+///     CreateTeam, EndRound, EntityId, EventTrigger, Server, SetTimeToLive,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// let team_id = 1;
+/// CreateTeam::trigger(&mut server, team_id).fire().unwrap();
+/// let creature_id = 1;
+/// CreateCreature::trigger(&mut server, creature_id, team_id, ())
+///     .fire()
+///     .unwrap();
+///
+/// SetTimeToLive::trigger(&mut server, EntityId::Creature(creature_id))
+///     .rounds(1)
+///     .fire()
+///     .unwrap();
+/// EndRound::trigger(&mut server).fire().unwrap();
+/// assert_eq!(server.battle().entities().creatures().count(), 0);
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct SetTimeToLive<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "EntityId<R>: Serialize",
+            deserialize = "EntityId<R>: Deserialize<'de>"
+        ))
+    )]
+    id: EntityId<R>,
+
+    rounds: Option<RoundsCount>,
+}
+
+impl<R: BattleRules> SetTimeToLive<R> {
+    /// Returns a trigger for this event.
+    pub fn trigger<P: EventProcessor<R>>(
+        processor: &mut P,
+        id: EntityId<R>,
+    ) -> SetTimeToLiveTrigger<'_, R, P> {
+        SetTimeToLiveTrigger {
+            processor,
+            id,
+            rounds: None,
+        }
+    }
+
+    /// Returns the id of the entity.
+    pub fn id(&self) -> &EntityId<R> {
+        &self.id
+    }
+
+    /// Returns the number of rounds the entity will live.
+    /// `None` means that the entity never expires.
+    pub fn rounds(&self) -> Option<RoundsCount> {
+        self.rounds
+    }
+}
+
+impl<R: BattleRules> Debug for SetTimeToLive<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "SetTimeToLive {{ id: {:?}, rounds: {:?} }}",
+            self.id, self.rounds
+        )
+    }
+}
+
+impl<R: BattleRules> Clone for SetTimeToLive<R> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+            rounds: self.rounds,
+        }
+    }
+}
+
+impl<R: BattleRules + 'static> Event<R> for SetTimeToLive<R> {
+    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
+        // Verify that the entity exists.
+        if battle.entities().entity(&self.id).is_none() {
+            return Err(WeaselError::EntityNotFound(self.id.clone()));
+        }
+        Ok(())
+    }
+
+    fn apply(&self, battle: &mut Battle<R>, _: &mut Option<EventQueue<R>>) {
+        battle
+            .state
+            .entities
+            .set_time_to_live(&self.id, self.rounds);
+    }
+
+    fn kind(&self) -> EventKind {
+        EventKind::SetTimeToLive
+    }
+
+    fn box_clone(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Trigger to build and fire a `SetTimeToLive` event.
+pub struct SetTimeToLiveTrigger<'a, R, P>
+where
+    R: BattleRules,
+    P: EventProcessor<R>,
+{
+    processor: &'a mut P,
+    id: EntityId<R>,
+    rounds: Option<RoundsCount>,
+}
+
+impl<'a, R, P> SetTimeToLiveTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    /// Sets the number of rounds the entity will live.
+    /// If not set, the entity's time to live is cleared.
+    pub fn rounds(&'a mut self, rounds: RoundsCount) -> &'a mut Self {
+        self.rounds = Some(rounds);
+        self
+    }
+}
+
+impl<'a, R, P> EventTrigger<'a, R, P> for SetTimeToLiveTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    fn processor(&'a mut self) -> &'a mut P {
+        self.processor
+    }
+
+    /// Returns a `SetTimeToLive` event.
+    fn event(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(SetTimeToLive {
+            id: self.id.clone(),
+            rounds: self.rounds,
+        })
+    }
+}
+
+/// Event to add or remove tags from an entity.
+///
+/// Tags are removed before new ones are added.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, rules::empty::*, AlterTags, Battle, BattleController, BattleRules,
+///     CreateCreature, CreateTeam, EntityId, EventTrigger, Server,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// let team_id = 1;
+/// CreateTeam::trigger(&mut server, team_id).fire().unwrap();
+/// let creature_id = 1;
+/// CreateCreature::trigger(&mut server, creature_id, team_id, ())
+///     .fire()
+///     .unwrap();
+///
+/// const FLYING: u16 = 1;
+/// let entity_id = EntityId::Creature(creature_id);
+/// AlterTags::trigger(&mut server, entity_id)
+///     .add(FLYING)
+///     .fire()
+///     .unwrap();
+/// assert!(server.battle().entities().has_tag(&entity_id, FLYING));
+/// assert_eq!(
+///     server.battle().entities().entities_with_tag(FLYING).count(),
+///     1
+/// );
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct AlterTags<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "EntityId<R>: Serialize",
+            deserialize = "EntityId<R>: Deserialize<'de>"
+        ))
+    )]
+    id: EntityId<R>,
+
+    added: Vec<Tag>,
+
+    removed: Vec<Tag>,
+}
+
+impl<R: BattleRules> AlterTags<R> {
+    /// Returns a trigger for this event.
+    pub fn trigger<P: EventProcessor<R>>(
+        processor: &mut P,
+        id: EntityId<R>,
+    ) -> AlterTagsTrigger<'_, R, P> {
+        AlterTagsTrigger {
+            processor,
+            id,
+            added: Vec::new(),
+            removed: Vec::new(),
+        }
+    }
+
+    /// Returns the id of the entity.
+    pub fn id(&self) -> &EntityId<R> {
+        &self.id
+    }
+
+    /// Returns the tags to be added to the entity.
+    pub fn added(&self) -> &Vec<Tag> {
+        &self.added
+    }
+
+    /// Returns the tags to be removed from the entity.
+    pub fn removed(&self) -> &Vec<Tag> {
+        &self.removed
+    }
+}
+
+impl<R: BattleRules> Debug for AlterTags<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "AlterTags {{ id: {:?}, added: {:?}, removed: {:?} }}",
+            self.id, self.added, self.removed
+        )
+    }
+}
+
+impl<R: BattleRules> Clone for AlterTags<R> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+            added: self.added.clone(),
+            removed: self.removed.clone(),
+        }
+    }
+}
+
+impl<R: BattleRules + 'static> Event<R> for AlterTags<R> {
+    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
+        // Verify that the entity exists.
+        if battle.entities().entity(&self.id).is_none() {
+            return Err(WeaselError::EntityNotFound(self.id.clone()));
+        }
+        Ok(())
+    }
+
+    fn apply(&self, battle: &mut Battle<R>, _: &mut Option<EventQueue<R>>) {
+        let entities = &mut battle.state.entities;
+        for tag in &self.removed {
+            entities.untag(&self.id, *tag);
+        }
+        for tag in &self.added {
+            entities.tag(&self.id, *tag);
+        }
+    }
+
+    fn kind(&self) -> EventKind {
+        EventKind::AlterTags
+    }
+
+    fn box_clone(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Trigger to build and fire an `AlterTags` event.
+pub struct AlterTagsTrigger<'a, R, P>
+where
+    R: BattleRules,
+    P: EventProcessor<R>,
+{
+    processor: &'a mut P,
+    id: EntityId<R>,
+    pub(crate) added: Vec<Tag>,
+    removed: Vec<Tag>,
+}
+
+impl<'a, R, P> AlterTagsTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    /// Adds a tag to the entity.
+    pub fn add(&'a mut self, tag: Tag) -> &'a mut Self {
+        self.added.push(tag);
+        self
+    }
+
+    /// Removes a tag from the entity.
+    pub fn remove(&'a mut self, tag: Tag) -> &'a mut Self {
+        self.removed.push(tag);
+        self
+    }
+}
+
+impl<'a, R, P> EventTrigger<'a, R, P> for AlterTagsTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    fn processor(&'a mut self) -> &'a mut P {
+        self.processor
+    }
+
+    /// Returns an `AlterTags` event.
+    fn event(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(AlterTags {
+            id: self.id.clone(),
+            added: self.added.clone(),
+            removed: self.removed.clone(),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::battle::BattleRules;
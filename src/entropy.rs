@@ -2,7 +2,7 @@
 
 use crate::battle::{Battle, BattleRules};
 use crate::error::WeaselResult;
-use crate::event::{Event, EventKind, EventProcessor, EventQueue, EventTrigger};
+use crate::event::{Event, EventId, EventKind, EventProcessor, EventQueue, EventTrigger};
 use num_traits::Num;
 #[cfg(feature = "serialization")]
 use serde::{Deserialize, Serialize};
@@ -14,6 +14,7 @@ use std::fmt::Debug;
 pub struct Entropy<R: BattleRules> {
     model: EntropyModel<R>,
     rules: R::ER,
+    checkpoints: Vec<(EventId, EntropyModel<R>)>,
 }
 
 impl<R: BattleRules> Entropy<R> {
@@ -22,6 +23,7 @@ impl<R: BattleRules> Entropy<R> {
         Self {
             model: rules.generate_model(&seed),
             rules,
+            checkpoints: Vec::new(),
         }
     }
 
@@ -60,6 +62,54 @@ impl<R: BattleRules> Entropy<R> {
     pub(crate) fn regenerate_model(&mut self, seed: &Option<EntropySeed<R>>) {
         self.model = self.rules.generate_model(seed)
     }
+
+    /// Saves a checkpoint of the model before the event with id `position` is applied,
+    /// if the rules support checkpoints.
+    pub(crate) fn record_checkpoint(&mut self, position: EventId) {
+        if let Some(model) = self.rules.checkpoint(&self.model) {
+            self.checkpoints.push((position, model));
+        }
+    }
+
+    /// Returns the entropy model as it was right before the event with id `position`
+    /// was applied, or `None` if there's no checkpoint for such position.
+    ///
+    /// Checkpoints are taken only if the entropy rules support them.
+    pub fn checkpoint(&self, position: EventId) -> Option<&EntropyModel<R>> {
+        self.checkpoints
+            .binary_search_by_key(&position, |(id, _)| *id)
+            .ok()
+            .map(|index| &self.checkpoints[index].1)
+    }
+
+    /// Returns the number of checkpoints taken so far.
+    pub fn checkpoints_len(&self) -> usize {
+        self.checkpoints.len()
+    }
+
+    /// Forgets all checkpoints, to free memory.
+    pub fn clear_checkpoints(&mut self) {
+        self.checkpoints.clear();
+    }
+
+    /// Forgets the checkpoints taken before the event with id `floor`.
+    pub fn prune_checkpoints(&mut self, floor: EventId) {
+        let index = self.checkpoints.partition_point(|(id, _)| *id < floor);
+        self.checkpoints.drain(..index);
+    }
+
+    /// Replaces the current model with a copy of `model`.
+    ///
+    /// Returns false if the rules don't support checkpoints.
+    pub(crate) fn restore(&mut self, model: &EntropyModel<R>) -> bool {
+        match self.rules.checkpoint(model) {
+            Some(model) => {
+                self.model = model;
+                true
+            }
+            None => false,
+        }
+    }
 }
 
 /// Defines how casuality works inside the battle system.
@@ -74,8 +124,12 @@ pub trait EntropyRules {
     /// See [EntropySeed](type.EntropySeed.html).
     type EntropySeed: Clone + Debug + Send + Serialize + for<'a> Deserialize<'a>;
 
+    #[cfg(not(feature = "serialization"))]
     /// See [EntropyModel](type.EntropyModel.html).
     type EntropyModel;
+    #[cfg(feature = "serialization")]
+    /// See [EntropyModel](type.EntropyModel.html).
+    type EntropyModel: Serialize + for<'a> Deserialize<'a>;
     /// See [EntropyOutput](type.EntropyOutput.html).
     type EntropyOutput: PartialOrd + Copy + Num + Debug;
 
@@ -91,6 +145,25 @@ pub trait EntropyRules {
         low: Self::EntropyOutput,
         high: Self::EntropyOutput,
     ) -> Self::EntropyOutput;
+
+    /// Returns a copy of `model`, to be used as a checkpoint for rewinding the entropy.
+    ///
+    /// Checkpoints are taken before each event is applied and they are used to keep
+    /// the entropy in lockstep with the history, for instance when undoing events.
+    /// Return `None` if your model can't be copied or if it has no state.
+    ///
+    /// The provided implementation returns `None`.
+    fn checkpoint(&self, _model: &Self::EntropyModel) -> Option<Self::EntropyModel> {
+        None
+    }
+
+    /// Returns the names of the optional features enabled in these entropy rules.
+    /// They are reported by `Battle::describe`.
+    ///
+    /// The provided implementation returns no features.
+    fn features(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
 }
 
 /// Type to represent an entropy seed.
@@ -173,7 +246,11 @@ impl<R: BattleRules + 'static> Event<R> for ResetEntropy<R> {
     }
 
     fn apply(&self, battle: &mut Battle<R>, _: &mut Option<EventQueue<R>>) {
-        battle.entropy.regenerate_model(&self.seed);
+        watch!(
+            battle.apply_watcher(),
+            "EntropyRules::generate_model",
+            battle.entropy.regenerate_model(&self.seed)
+        );
     }
 
     fn kind(&self) -> EventKind {
@@ -1,17 +1,19 @@
 //! Error and Result module.
 
 use crate::ability::AbilityId;
-use crate::battle::{BattleRules, Version};
+use crate::battle::{BattlePhase, BattleRules, EndReason, Version};
 use crate::creature::CreatureId;
 use crate::entity::EntityId;
-use crate::event::{DefaultOutput, Event, EventId, EventSinkId};
+use crate::event::{DefaultOutput, Event, EventId, EventKind, EventSinkId};
 use crate::metric::MetricIdType;
 use crate::object::ObjectId;
 use crate::player::PlayerId;
 use crate::power::PowerId;
+use crate::round::TurnsCount;
 use crate::space::Position;
 use crate::status::StatusId;
-use crate::team::TeamId;
+use crate::team::{ResourceId, TargetRelation, TeamId};
+use crate::user::UserEventId;
 use std::ops::Range;
 use std::result::Result;
 use std::{fmt, fmt::Debug};
@@ -45,6 +47,10 @@ pub enum WeaselError<V, TI, EI, CI, OI, PI, AI, WI, SI, MI, E> {
     DuplicatedObject(OI),
     /// Duplicated team id.
     DuplicatedTeam(TI),
+    /// Duplicated player id.
+    DuplicatedPlayer(PlayerId),
+    /// The player doesn't exist.
+    PlayerNotFound(PlayerId),
     /// The team doesn't exist.
     TeamNotFound(TI),
     /// The creature doesn't exist.
@@ -59,6 +65,10 @@ pub enum WeaselError<V, TI, EI, CI, OI, PI, AI, WI, SI, MI, E> {
     InvalidCreatureConversion(TI, CI),
     /// The team is not empty.
     TeamNotEmpty(TI),
+    /// The team doesn't own enough of a resource.
+    InsufficientResources(TI, ResourceId),
+    /// A team can't trade with itself.
+    SelfTrade(TI),
     /// Position is invalid.
     PositionError(Option<PI>, PI, Box<Self>),
     /// The entity doesn't exist.
@@ -73,10 +83,22 @@ pub enum WeaselError<V, TI, EI, CI, OI, PI, AI, WI, SI, MI, E> {
     ActorNotEligible(EI),
     /// The actor can't act at the moment.
     ActorNotReady(EI),
+    /// The actor is already acting in the current turn.
+    ActorAlreadyInTurn(EI),
     /// The actor doesn't know such ability.
     AbilityNotKnown(EI, AI),
+    /// The ability is still recharging. Contains the number of turns left.
+    AbilityOnCooldown(EI, AI, TurnsCount),
     /// The ability can't be activated.
     AbilityNotActivable(EI, AI, Box<Self>),
+    /// The actor is already preparing the activation of an ability.
+    ActivationPending(EI),
+    /// The actor isn't preparing the activation of any ability.
+    NoActivationPending(EI),
+    /// The actor already activated the maximum number of abilities in this turn.
+    ActivationsExhausted(EI),
+    /// The ability is blocked by a status afflicting the actor.
+    AbilityBlocked(EI, AI, SI),
     /// The team can't act at the moment.
     TeamNotReady(TI),
     /// The team doesn't possess such power.
@@ -85,6 +107,9 @@ pub enum WeaselError<V, TI, EI, CI, OI, PI, AI, WI, SI, MI, E> {
     PowerNotInvocable(TI, WI, Box<Self>),
     /// Status not present on a character.
     StatusNotPresent(EI, SI),
+    /// The client event is bigger than the maximum size accepted by the server.
+    /// Contains the event's size and the maximum size, both in bytes.
+    EventTooLarge(usize, usize),
     /// The event processor is not valid.
     EmptyEventProcessor,
     /// The entity is not a character.
@@ -95,30 +120,57 @@ pub enum WeaselError<V, TI, EI, CI, OI, PI, AI, WI, SI, MI, E> {
     NotACreature(EI),
     /// The entity is not an object.
     NotAnObject(EI),
+    /// The path to move the entity along is empty.
+    EmptyPath(EI),
     /// Attempt to set `Relation::Kin`.
     KinshipRelation,
     /// Attempt to set relation towards oneself.
     SelfRelation,
+    /// The target doesn't satisfy the relation constraint with respect to the actor.
+    InvalidTargetRelation(EI, EI, TargetRelation),
     /// Two versions of the battle rules are incompatible.
     IncompatibleVersions(V, V),
-    /// The battle has already ended.
-    BattleEnded,
+    /// Client and server rules data have different hashes.
+    IncompatibleRulesData(u64, u64),
+    /// The state of the battle doesn't match the server's one.
+    /// Contains the digest of the server's state and the one of the local state.
+    StateMismatch(u64, u64),
+    /// The battle has already ended. Contains the reason why it ended.
+    BattleEnded(EndReason),
+    /// The battle is still in progress.
+    BattleInProgress,
+    /// Events of the given kind can't be fired during the current phase of the battle.
+    WrongBattlePhase(EventKind, BattlePhase),
+    /// A tick's number is not greater than the number of the last tick.
+    InvalidTick(u64, u64),
     /// The metric's type is not correct.
     WrongMetricType(MI),
     /// The `EventPrototype`'s condition is not satisfied.
     ConditionUnsatisfied,
     /// Duplicated event sink id.
     DuplicatedEventSink(EventSinkId),
+    /// Duplicated user event id. Contains the name of the event already registered.
+    DuplicatedUserEvent(UserEventId, String),
     /// The event range is invalid.
     InvalidEventRange(Range<EventId>, EventId),
     /// The event sink doesn't exist.
     EventSinkNotFound(EventSinkId),
     /// The player can't fire the event.
     AuthenticationError(Option<PlayerId>, TI),
+    /// The player can't fire the event because the entity is owned by another player.
+    OwnershipError(Option<PlayerId>, EI),
+    /// The player isn't allowed to fire events of this kind.
+    EventKindNotAllowed(PlayerId, EventKind),
     /// No authentication in the event.
     MissingAuthentication,
     /// Event can be fired only be the server.
     ServerOnlyEvent,
+    /// Only reaction events are accepted while a response window is open.
+    ResponseWindowOpen,
+    /// The server has been shut down and it doesn't accept client events anymore.
+    ServerShutDown,
+    /// The session token doesn't belong to any session.
+    InvalidSessionToken,
     /// Failure while packing an user event into a `UserEventPacker`.
     UserEventPackingError(E, String),
     /// Failure while unpacking a `UserEventPacker` into an user event.
@@ -131,6 +183,10 @@ pub enum WeaselError<V, TI, EI, CI, OI, PI, AI, WI, SI, MI, E> {
     UserError(String),
     /// A generic event sink error.
     EventSinkError(String),
+    /// Failure while writing an event into a stream.
+    EventWriteError(EventId, String),
+    /// Failure while serializing an event.
+    EventSerializationError(String),
 }
 
 impl<V, TI, EI, CI, OI, PI, AI, WI, SI, MI, E> fmt::Display
@@ -155,6 +211,8 @@ where
             DuplicatedCreature(id) => write!(f, "duplicated creature with id {:?}", id),
             DuplicatedObject(id) => write!(f, "duplicated object with id {:?}", id),
             DuplicatedTeam(id) => write!(f, "duplicated team with id {:?}", id),
+            DuplicatedPlayer(id) => write!(f, "duplicated player with id {:?}", id),
+            PlayerNotFound(id) => write!(f, "player {:?} not found", id),
             TeamNotFound(id) => write!(f, "team {:?} not found", id),
             CreatureNotFound(id) => write!(f, "creature {:?} not found", id),
             ObjectNotFound(id) => write!(f, "object {:?} not found", id),
@@ -174,6 +232,12 @@ where
                 creature_id, team_id
             ),
             TeamNotEmpty(id) => write!(f, "team {:?} has at least one creature", id),
+            InsufficientResources(id, resource) => write!(
+                f,
+                "team {:?} doesn't own enough of resource {:?}",
+                id, resource
+            ),
+            SelfTrade(id) => write!(f, "team {:?} can't trade with itself", id),
             PositionError(source, destination, error) => write!(
                 f,
                 "can't move entity from position {:?} to position {:?} due to {:?}",
@@ -187,16 +251,34 @@ where
             NoTurnInProgress => write!(f, "no turn is in progress"),
             ActorNotEligible(id) => write!(f, "actor {:?} is not eligible to start a new turn", id),
             ActorNotReady(id) => write!(f, "actor {:?} can't act outside of his turn", id),
+            ActorAlreadyInTurn(id) => write!(f, "actor {:?} is already acting in this turn", id),
             AbilityNotKnown(actor_id, ability_id) => write!(
                 f,
                 "actor {:?} doesn't know ability {:?}",
                 actor_id, ability_id
             ),
+            AbilityOnCooldown(actor_id, ability_id, turns) => write!(
+                f,
+                "ability {:?} of actor {:?} is recharging for {} more turns",
+                ability_id, actor_id, turns
+            ),
             AbilityNotActivable(actor_id, ability_id, error) => write!(
                 f,
                 "actor {:?} can't activate ability {:?} due to {:?}",
                 actor_id, ability_id, error
             ),
+            ActivationPending(id) => write!(f, "actor {:?} is already preparing an ability", id),
+            NoActivationPending(id) => write!(f, "actor {:?} is not preparing any ability", id),
+            ActivationsExhausted(id) => write!(
+                f,
+                "actor {:?} can't activate more abilities in this turn",
+                id
+            ),
+            AbilityBlocked(actor_id, ability_id, status_id) => write!(
+                f,
+                "actor {:?} can't activate ability {:?} because of status {:?}",
+                actor_id, ability_id, status_id
+            ),
             TeamNotReady(id) => write!(f, "team {:?} can't act in this moment", id),
             PowerNotKnown(team_id, power_id) => {
                 write!(f, "team {:?} doesn't know power {:?}", team_id, power_id)
@@ -211,19 +293,51 @@ where
                 "character {:?} is not afflicted by status {:?}",
                 character_id, status_id
             ),
+            EventTooLarge(size, max) => write!(
+                f,
+                "event of {} bytes exceeds the maximum size of {} bytes",
+                size, max
+            ),
             NotACharacter(id) => write!(f, "entity {:?} is not a character", id),
             NotAnActor(id) => write!(f, "entity {:?} is not an actor", id),
             NotACreature(id) => write!(f, "entity {:?} is not a creature", id),
             NotAnObject(id) => write!(f, "entity {:?} is not an object", id),
             EmptyEventProcessor => write!(f, "() is not a valid event processor to process events"),
             KinshipRelation => write!(f, "kinship relation can't be explicitly set"),
+            EmptyPath(id) => write!(f, "entity {:?} can't move along an empty path", id),
             SelfRelation => write!(f, "a team can't explicitly set a relation towards itself"),
+            InvalidTargetRelation(actor, target, constraint) => write!(
+                f,
+                "target {:?} of {:?} doesn't satisfy the relation {:?}",
+                target, actor, constraint
+            ),
             IncompatibleVersions(client, server) => write!(
                 f,
                 "client version {:?} is different from server version {:?}",
                 client, server
             ),
-            BattleEnded => write!(f, "the battle has ended"),
+            IncompatibleRulesData(client, server) => write!(
+                f,
+                "client rules data hash {:?} is different from server rules data hash {:?}",
+                client, server
+            ),
+            StateMismatch(server, local) => write!(
+                f,
+                "battle state digest {:?} is different from server state digest {:?}",
+                local, server
+            ),
+            BattleEnded(reason) => write!(f, "the battle has ended ({:?})", reason),
+            BattleInProgress => write!(f, "the battle is still in progress"),
+            WrongBattlePhase(kind, phase) => write!(
+                f,
+                "events of kind {:?} can't be fired during the {:?} phase",
+                kind, phase
+            ),
+            InvalidTick(last, tick) => write!(
+                f,
+                "tick {} must be greater than the last tick {}",
+                tick, last
+            ),
             WrongMetricType(id) => write!(
                 f,
                 "metric {:?} exists already with a different counter type",
@@ -234,6 +348,11 @@ where
                 "the condition to apply this event prototype is not satisfied"
             ),
             DuplicatedEventSink(id) => write!(f, "duplicated event sink with id {:?}", id),
+            DuplicatedUserEvent(id, name) => write!(
+                f,
+                "duplicated user event with id {:?}, already registered as {:?}",
+                id, name
+            ),
             InvalidEventRange(range, history_len) => write!(
                 f,
                 "event history (0..{}) doesn't contain the event range {:?}",
@@ -245,8 +364,24 @@ where
                 "player {:?} doesn't have control over team {:?}",
                 player, team
             ),
+            OwnershipError(player, entity) => write!(
+                f,
+                "player {:?} doesn't have control over entity {:?}",
+                player, entity
+            ),
+            EventKindNotAllowed(player, kind) => write!(
+                f,
+                "player {:?} isn't allowed to fire events of kind {:?}",
+                player, kind
+            ),
             MissingAuthentication => write!(f, "event is not linked to any player"),
             ServerOnlyEvent => write!(f, "event can be fired only by the server"),
+            ServerShutDown => write!(f, "the server has been shut down"),
+            InvalidSessionToken => write!(f, "invalid session token"),
+            ResponseWindowOpen => write!(
+                f,
+                "only reaction events are accepted while a response window is open"
+            ),
             UserEventPackingError(event, error) => {
                 write!(f, "failed to pack user event {:?}: {}", event, error)
             }
@@ -261,6 +396,8 @@ where
             }
             UserError(msg) => write!(f, "user error: {}", msg),
             EventSinkError(msg) => write!(f, "sink error: {}", msg),
+            EventWriteError(id, error) => write!(f, "failed to write event {}: {}", id, error),
+            EventSerializationError(error) => write!(f, "failed to serialize event: {}", error),
         }
     }
 }
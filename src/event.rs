@@ -1,17 +1,50 @@
 //! Event module.
 
-use crate::battle::{Battle, BattleRules, BattleState, Version};
-use crate::error::{WeaselError, WeaselResult};
-use crate::player::PlayerId;
-use crate::team::TeamId;
+use crate::ability::{ActivateAbility, InterruptAbility};
+use crate::actor::{AlterAbilities, RechargeAbilities, RegenerateAbilities};
+#[cfg(feature = "async")]
+use crate::asynchronous::{AsyncEventProcessor, BoxFuture};
+use crate::battle::{
+    Battle, BattleRules, BattleState, EndBattle, StartBattle, VerifyState, Version,
+};
+use crate::character::{AlterStatistics, RegenerateStatistics};
+use crate::creature::{
+    ConvertCreature, CreateCreature, ReidentifyCreature, RemoveCreature, SpawnWave,
+    TransferOwnership, TransformCreature,
+};
+use crate::entity::{AlterTags, EntityId, SetTimeToLive};
+use crate::entropy::ResetEntropy;
+use crate::error::{WeaselError, WeaselErrorType, WeaselResult};
+use crate::fight::ApplyImpact;
+use crate::history::History;
+use crate::metric::MetricChanged;
+use crate::object::{CreateObject, RemoveObject, TransferObjectOwnership};
+use crate::player::{PlayerId, RegisterPlayer, RemovePlayer};
+use crate::power::InvokePower;
+use crate::round::{
+    AddTurnActor, EndRound, EndTurn, EnvironmentTurn, RemoveTurnActor, ResetRounds, RestoreRounds,
+    ScheduleEvent, StartTurn, Tick, TurnTimedOut,
+};
+use crate::server::{pre_broadcast, PreBroadcastCallback, ShutdownReason};
+use crate::space::{AlterSpace, MoveEntity, MoveEntityAlongPath, ResetSpace};
+use crate::status::{AlterStatusDuration, AlterStatuses, ClearStatus, InflictStatus};
+use crate::team::{
+    AddScore, AlterPowers, ConcludeObjectives, CreateTeam, RegeneratePowers, RemoveTeam,
+    RenameTeam, ResetObjectives, SetRelations, TeamId, TradeProposal, TransferResource,
+};
 use crate::user::UserEventId;
-use log::error;
+use crate::visibility::{
+    filter, Audience, HideEntity, RedactionPolicy, RevealEntity, VisibilityRules,
+};
+use log::{error, warn};
 #[cfg(feature = "serialization")]
 use serde::{Deserialize, Serialize};
 use std::any::Any;
+use std::collections::{HashMap, VecDeque};
 use std::fmt::{Debug, Formatter, Result};
 use std::marker::PhantomData;
 use std::ops::{Deref, Range};
+use std::time::{Duration, Instant};
 
 /// Type for the id of events.
 pub type EventId = u32;
@@ -20,6 +53,7 @@ pub type EventId = u32;
 // Internal note: remember to update the event debug and serialization tests in tests/event.rs
 // each time a new event is added to weasel.
 #[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub enum EventKind {
     /// Dummy event doing nothing.
     DummyEvent,
@@ -31,16 +65,32 @@ pub enum EventKind {
     CreateObject,
     /// Move an entity from one position to another.
     MoveEntity,
+    /// Set or clear the time to live of an entity.
+    SetTimeToLive,
+    /// Add or remove tags from an entity.
+    AlterTags,
     /// Start a new turn.
     StartTurn,
     /// End the current turn.
     EndTurn,
+    /// End the current turn because its actors exceeded their time.
+    TurnTimedOut,
+    /// Add an actor to the current turn.
+    AddTurnActor,
+    /// Remove an actor from the current turn.
+    RemoveTurnActor,
     /// End the current round.
     EndRound,
     /// Perform a turn for the environment.
     EnvironmentTurn,
+    /// Advance the simulation by one tick.
+    Tick,
+    /// Restore the progress of rounds and turns.
+    RestoreRounds,
     /// Activate an actor's ability.
     ActivateAbility,
+    /// Cancel the pending ability activation of an actor.
+    InterruptAbility,
     /// Invoke a team's power.
     InvokePower,
     /// Apply the consequences of an impact on the world.
@@ -63,20 +113,42 @@ pub enum EventKind {
     InflictStatus,
     /// Frees a character from a status effect.
     ClearStatus,
+    /// Change the duration of a status effect.
+    AlterStatusDuration,
     /// Convert a creature from one team to another.
     ConvertCreature,
+    /// Change the id of a creature.
+    ReidentifyCreature,
+    /// Regenerate the statistics and abilities of a creature, preserving its identity.
+    TransformCreature,
+    /// Give a player exclusive control over a creature.
+    TransferOwnership,
+    /// Transfer the ownership of an object to a team or a player.
+    TransferObjectOwnership,
     /// Set new relations between teams.
     SetRelations,
     /// An event to set a team's objectives outcome.
     ConcludeObjectives,
+    /// Add points to the score of a team.
+    AddScore,
+    /// Transfer a resource from a team to another.
+    TransferResource,
+    /// Propose an exchange of resources to another team.
+    TradeProposal,
     /// Remove a creature from the battle.
     RemoveCreature,
     /// Remove an object from the battle.
     RemoveObject,
     /// Remove a team from the battle.
     RemoveTeam,
+    /// Change the id of a team.
+    RenameTeam,
     /// Modify the spatial model.
     AlterSpace,
+    /// Reveal an entity to a team.
+    RevealEntity,
+    /// Hide an entity from one or all teams.
+    HideEntity,
     /// Reset the entropy model.
     ResetEntropy,
     /// Reset the objectives of a team.
@@ -85,8 +157,24 @@ pub enum EventKind {
     ResetRounds,
     /// Reset the space model.
     ResetSpace,
+    /// Move the battle from the setup phase to the fighting one.
+    StartBattle,
     /// End the battle.
     EndBattle,
+    /// Schedule an event to be fired in the future.
+    ScheduleEvent,
+    /// Create many creatures at once.
+    SpawnWave,
+    /// Move an entity along a path.
+    MoveEntityAlongPath,
+    /// Recharge the abilities of an actor.
+    RechargeAbilities,
+    /// Verify that the battle state matches the server's one.
+    VerifyState,
+    /// Register a new player.
+    RegisterPlayer,
+    /// Remove a player.
+    RemovePlayer,
     /// A user defined event with an unique id.
     UserEvent(UserEventId),
 }
@@ -101,6 +189,18 @@ pub enum EventRights<'a, R: BattleRules> {
     Team(&'a TeamId<R>),
     /// Only the server or a player with rights to all of these teams can fire the event.
     Teams(Vec<&'a TeamId<R>>),
+    /// Only the server or a player in control of this entity can fire the event.
+    ///
+    /// An entity is controlled by its owner, if it has one.
+    /// Otherwise, it is controlled by the players with rights to its team.
+    Entity(&'a EntityId<R>),
+    /// Only the server or a player in control of all of these entities can fire the event.
+    Entities(Vec<&'a EntityId<R>>),
+    /// Only the server or a player satisfying all of these requirements can fire the event.
+    All(Vec<EventRights<'a, R>>),
+    /// Only the server or a player satisfying at least one of these requirements
+    /// can fire the event. An empty list can be satisfied only by the server.
+    Any(Vec<EventRights<'a, R>>),
 }
 
 impl<'a, R: BattleRules> Debug for EventRights<'a, R> {
@@ -111,6 +211,10 @@ impl<'a, R: BattleRules> Debug for EventRights<'a, R> {
             Server => write!(f, "EventRights::Server"),
             Team(id) => write!(f, "EventRights::Team {{ {:?} }}", id),
             Teams(ids) => write!(f, "EventRights::Teams {{ {:?} }}", ids),
+            Entity(id) => write!(f, "EventRights::Entity {{ {:?} }}", id),
+            Entities(ids) => write!(f, "EventRights::Entities {{ {:?} }}", ids),
+            All(rights) => write!(f, "EventRights::All {{ {:?} }}", rights),
+            Any(rights) => write!(f, "EventRights::Any {{ {:?} }}", rights),
         }
     }
 }
@@ -123,6 +227,11 @@ impl<'a, 'b, R: BattleRules> PartialEq<EventRights<'b, R>> for EventRights<'a, R
             (Server, Server) => true,
             (Team(a), Team(b)) => a == b,
             (Teams(a), Teams(b)) => a == b,
+            (Entity(a), Entity(b)) => a == b,
+            (Entities(a), Entities(b)) => a == b,
+            (All(a), All(b)) | (Any(a), Any(b)) => {
+                a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| a == b)
+            }
             _ => false,
         }
     }
@@ -173,19 +282,278 @@ impl<R: BattleRules> PartialEq<Box<dyn Event<R> + Send>> for Box<dyn Event<R> +
     }
 }
 
+/// Downcasts an event to its concrete type.
+///
+/// `event` can be anything that can be viewed as an event, such as an `EventWrapper`
+/// or a boxed event. Returns `None` if the event is not of type `E`.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, event::downcast_event, rules::empty::*, BattleRules, CreateTeam, EndBattle,
+///     EventTrigger,
+/// };
+///
+/// battle_rules! {}
+///
+/// let event = CreateTeam::<CustomRules>::trigger(&mut (), 1).event();
+/// let create_team: Option<&CreateTeam<_>> = downcast_event(&event);
+/// assert_eq!(create_team.map(|e| *e.id()), Some(1));
+/// assert!(downcast_event::<_, EndBattle<_>, _>(&event).is_none());
+/// ```
+pub fn downcast_event<R, E, T>(event: &T) -> Option<&E>
+where
+    R: BattleRules + 'static,
+    E: Event<R> + 'static,
+    T: AsRef<dyn Event<R> + Send> + ?Sized,
+{
+    event.as_ref().as_any().downcast_ref::<E>()
+}
+
+/// Macro to run different code depending on the concrete type of an event.
+///
+/// Each arm contains the name of an event type and a pattern to bind the downcast event.
+/// Arms are tried in order. An optional last arm `_` is taken when no other arm matches;
+/// if it's missing, the macro evaluates to `()`.
+///
+/// Generic arguments are inferred for event types generic over the battle rules, as all
+/// built-in events are. Other events must specify their generic arguments explicitly,
+/// for instance `MyEvent<>` for an event type without generics.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, match_event, rules::empty::*, Battle, BattleController, BattleRules,
+///     CreateCreature, CreateTeam, EventTrigger, Server,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// CreateTeam::trigger(&mut server, 1).fire().unwrap();
+/// CreateCreature::trigger(&mut server, 2, 1, ()).fire().unwrap();
+///
+/// let descriptions: Vec<_> = server
+///     .battle()
+///     .history()
+///     .events()
+///     .iter()
+///     .map(|event| {
+///         match_event!(event, {
+///             CreateTeam(e) => format!("team {}", e.id()),
+///             CreateCreature(e) => format!("creature {}", e.id()),
+///             _ => "other".to_string(),
+///         })
+///     })
+///     .collect();
+/// assert_eq!(descriptions, vec!["team 1", "creature 2"]);
+/// ```
+#[macro_export]
+macro_rules! match_event {
+    (@type $name: ident) => {
+        $name<_>
+    };
+    (@type $name: ident < $($generic: ty),* >) => {
+        $name<$($generic),*>
+    };
+    ($event: expr, {
+        $($name: ident $(< $($generic: ty),* >)? ($binding: pat) => $body: expr,)*
+        _ => $default: expr $(,)?
+    }) => {{
+        let event = $event;
+        $(
+            if let Some($binding) = $crate::event::downcast_event::<
+                _,
+                $crate::match_event!(@type $name $(< $($generic),* >)?),
+                _,
+            >(event) {
+                $body
+            } else
+        )*
+        {
+            $default
+        }
+    }};
+    ($event: expr, {
+        $($name: ident $(< $($generic: ty),* >)? ($binding: pat) => $body: expr),* $(,)?
+    }) => {
+        $crate::match_event!($event, {
+            $($name $(< $($generic),* >)? ($binding) => $body,)*
+            _ => (),
+        })
+    };
+}
+
+/// Generates the `EventVisitor` trait, with a method for every built-in event.
+macro_rules! event_visitor {
+    ($( $event: ident => $method: ident ),* $(,)?) => {
+        /// A visitor over the concrete types of events.
+        ///
+        /// Each built-in event has its own method, which is invoked by `visit` with the event
+        /// already downcast to its concrete type. Implement only the methods of the events
+        /// you are interested in.
+        ///
+        /// # Examples
+        /// ```
+        /// use weasel::event::EventVisitor;
+        /// use weasel::{
+        ///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules,
+        ///     CreateCreature, CreateTeam, EventTrigger, Server,
+        /// };
+        ///
+        /// battle_rules! {}
+        ///
+        /// #[derive(Default)]
+        /// struct Counter {
+        ///     teams: u32,
+        ///     creatures: u32,
+        /// }
+        ///
+        /// impl EventVisitor<CustomRules> for Counter {
+        ///     fn visit_create_team(&mut self, _: &CreateTeam<CustomRules>) {
+        ///         self.teams += 1;
+        ///     }
+        ///
+        ///     fn visit_create_creature(&mut self, _: &CreateCreature<CustomRules>) {
+        ///         self.creatures += 1;
+        ///     }
+        /// }
+        ///
+        /// let battle = Battle::builder(CustomRules::new()).build();
+        /// let mut server = Server::builder(battle).build();
+        /// CreateTeam::trigger(&mut server, 1).fire().unwrap();
+        /// CreateCreature::trigger(&mut server, 1, 1, ()).fire().unwrap();
+        /// CreateCreature::trigger(&mut server, 2, 1, ()).fire().unwrap();
+        ///
+        /// let mut counter = Counter::default();
+        /// for event in server.battle().history().events() {
+        ///     counter.visit(event);
+        /// }
+        /// assert_eq!((counter.teams, counter.creatures), (1, 2));
+        /// ```
+        pub trait EventVisitor<R: BattleRules + 'static> {
+            $(
+                #[doc = concat!("Visits a `", stringify!($event), "` event.")]
+                ///
+                /// The provided implementation does nothing.
+                fn $method(&mut self, _event: &$event<R>) {}
+            )*
+
+            /// Visits an user event.
+            ///
+            /// The provided implementation does nothing.
+            fn visit_user_event(&mut self, _event: &(dyn Event<R> + Send)) {}
+
+            /// Invokes the method of this visitor for the concrete type of `event`.
+            fn visit<T>(&mut self, event: &T)
+            where
+                Self: Sized,
+                T: AsRef<dyn Event<R> + Send> + ?Sized,
+            {
+                let event = event.as_ref();
+                match event.kind() {
+                    $(EventKind::$event => {
+                        if let Some(event) = event.as_any().downcast_ref::<$event<R>>() {
+                            self.$method(event);
+                        }
+                    })*
+                    EventKind::UserEvent(_) => self.visit_user_event(event),
+                }
+            }
+        }
+    };
+}
+
+event_visitor! {
+    DummyEvent => visit_dummy_event,
+    CreateTeam => visit_create_team,
+    CreateCreature => visit_create_creature,
+    CreateObject => visit_create_object,
+    MoveEntity => visit_move_entity,
+    SetTimeToLive => visit_set_time_to_live,
+    AlterTags => visit_alter_tags,
+    StartTurn => visit_start_turn,
+    EndTurn => visit_end_turn,
+    TurnTimedOut => visit_turn_timed_out,
+    AddTurnActor => visit_add_turn_actor,
+    RemoveTurnActor => visit_remove_turn_actor,
+    EndRound => visit_end_round,
+    EnvironmentTurn => visit_environment_turn,
+    Tick => visit_tick,
+    RestoreRounds => visit_restore_rounds,
+    ActivateAbility => visit_activate_ability,
+    InterruptAbility => visit_interrupt_ability,
+    InvokePower => visit_invoke_power,
+    ApplyImpact => visit_apply_impact,
+    AlterStatistics => visit_alter_statistics,
+    AlterStatuses => visit_alter_statuses,
+    AlterAbilities => visit_alter_abilities,
+    AlterPowers => visit_alter_powers,
+    RegenerateStatistics => visit_regenerate_statistics,
+    RegenerateAbilities => visit_regenerate_abilities,
+    RegeneratePowers => visit_regenerate_powers,
+    InflictStatus => visit_inflict_status,
+    ClearStatus => visit_clear_status,
+    AlterStatusDuration => visit_alter_status_duration,
+    ConvertCreature => visit_convert_creature,
+    ReidentifyCreature => visit_reidentify_creature,
+    TransformCreature => visit_transform_creature,
+    TransferOwnership => visit_transfer_ownership,
+    TransferObjectOwnership => visit_transfer_object_ownership,
+    SetRelations => visit_set_relations,
+    ConcludeObjectives => visit_conclude_objectives,
+    AddScore => visit_add_score,
+    TransferResource => visit_transfer_resource,
+    TradeProposal => visit_trade_proposal,
+    RemoveCreature => visit_remove_creature,
+    RemoveObject => visit_remove_object,
+    RemoveTeam => visit_remove_team,
+    RenameTeam => visit_rename_team,
+    AlterSpace => visit_alter_space,
+    RevealEntity => visit_reveal_entity,
+    HideEntity => visit_hide_entity,
+    ResetEntropy => visit_reset_entropy,
+    ResetObjectives => visit_reset_objectives,
+    ResetRounds => visit_reset_rounds,
+    ResetSpace => visit_reset_space,
+    StartBattle => visit_start_battle,
+    EndBattle => visit_end_battle,
+    ScheduleEvent => visit_schedule_event,
+    SpawnWave => visit_spawn_wave,
+    MoveEntityAlongPath => visit_move_entity_along_path,
+    RechargeAbilities => visit_recharge_abilities,
+    VerifyState => visit_verify_state,
+    RegisterPlayer => visit_register_player,
+    RemovePlayer => visit_remove_player,
+}
+
 /// A wrapper to decorate verified events with additional data.
 pub struct EventWrapper<R: BattleRules> {
     /// Event Id is assigned only after events has been verified for consistency.
     id: EventId,
     /// Id of the event that generated this one.
     origin: Option<EventId>,
+    /// Ids of the other events that contributed to generate this one.
+    secondary_origins: Vec<EventId>,
     /// The actual event wrapped inside this struct.
     pub(crate) event: Box<dyn Event<R> + Send>,
+    /// Whether this event was synthesized to bootstrap a timeline.
+    synthetic: bool,
+    /// Whether this event was applied despite failing verification.
+    unverified: bool,
+    /// User defined data attached to this event.
+    metadata: Option<EventMetadata>,
 }
 
 impl<R: BattleRules> Clone for EventWrapper<R> {
     fn clone(&self) -> Self {
         Self::new(self.id, self.origin, self.event.clone())
+            .with_secondary_origins(self.secondary_origins.clone())
+            .synthesized(self.synthetic)
+            .forced(self.unverified)
+            .with_metadata(self.metadata.clone())
     }
 }
 
@@ -196,7 +564,39 @@ impl<R: BattleRules> EventWrapper<R> {
         origin: Option<EventId>,
         event: Box<dyn Event<R> + Send>,
     ) -> Self {
-        Self { id, origin, event }
+        Self {
+            id,
+            origin,
+            secondary_origins: Vec::new(),
+            event,
+            synthetic: false,
+            unverified: false,
+            metadata: None,
+        }
+    }
+
+    /// Sets the ids of the other events that contributed to generate this one.
+    pub(crate) fn with_secondary_origins(mut self, origins: Vec<EventId>) -> Self {
+        self.secondary_origins = origins;
+        self
+    }
+
+    /// Marks this event as synthetic or not.
+    pub(crate) fn synthesized(mut self, synthetic: bool) -> Self {
+        self.synthetic = synthetic;
+        self
+    }
+
+    /// Marks this event as forced past a failed verification or not.
+    pub(crate) fn forced(mut self, unverified: bool) -> Self {
+        self.unverified = unverified;
+        self
+    }
+
+    /// Sets the user defined data attached to this event.
+    pub(crate) fn with_metadata(mut self, metadata: Option<EventMetadata>) -> Self {
+        self.metadata = metadata;
+        self
     }
 
     /// Returns this event's id.
@@ -205,22 +605,90 @@ impl<R: BattleRules> EventWrapper<R> {
     }
 
     /// Returns the id of the event that caused this one.
+    ///
+    /// If the event has multiple causes, this is the primary one.
     pub fn origin(&self) -> Option<EventId> {
         self.origin
     }
 
+    /// Returns the ids of the events, besides the primary origin, that caused this one.
+    pub fn secondary_origins(&self) -> &[EventId] {
+        &self.secondary_origins
+    }
+
+    /// Returns the ids of all events that caused this one, starting with the primary origin.
+    pub fn origins(&self) -> impl Iterator<Item = EventId> + '_ {
+        self.origin
+            .iter()
+            .chain(self.secondary_origins.iter())
+            .copied()
+    }
+
     /// Returns the event.
     #[allow(clippy::borrowed_box)]
     pub fn event(&self) -> &Box<dyn Event<R> + Send> {
         &self.event
     }
 
+    /// Returns `true` if this event doesn't belong to the original timeline, but it was
+    /// synthesized to reproduce the state of a battle.
+    ///
+    /// See [bootstrap_events_for_new_client](../server/struct.Server.html#method.bootstrap_events_for_new_client).
+    pub fn synthetic(&self) -> bool {
+        self.synthetic
+    }
+
+    /// Returns `true` if this event failed verification, but it was nonetheless applied
+    /// by a server in sandbox mode.
+    ///
+    /// See [sandbox](../server/struct.ServerBuilder.html#method.sandbox).
+    pub fn unverified(&self) -> bool {
+        self.unverified
+    }
+
+    /// Returns the user defined data attached to this event, if any.
+    pub fn metadata(&self) -> Option<&[u8]> {
+        self.metadata.as_deref()
+    }
+
     /// Consume this event wrapper and returns a versioned instance of it.
     pub fn version(self, version: Version<R>) -> VersionedEventWrapper<R> {
         VersionedEventWrapper::new(self, version)
     }
 }
 
+impl<R: BattleRules + 'static> EventWrapper<R> {
+    /// Downcasts the event to its concrete type `E`.
+    /// Returns `None` if the event is not of type `E`.
+    ///
+    /// # Examples
+    /// ```
+    /// use weasel::{
+    ///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateTeam,
+    ///     EndBattle, EventTrigger, Server,
+    /// };
+    ///
+    /// battle_rules! {}
+    ///
+    /// let battle = Battle::builder(CustomRules::new()).build();
+    /// let mut server = Server::builder(battle).build();
+    /// CreateTeam::trigger(&mut server, 1).fire().unwrap();
+    ///
+    /// let event = &server.battle().history().events()[0];
+    /// assert_eq!(event.downcast::<CreateTeam<_>>().map(|e| *e.id()), Some(1));
+    /// assert!(event.downcast::<EndBattle<_>>().is_none());
+    /// ```
+    pub fn downcast<E: Event<R> + 'static>(&self) -> Option<&E> {
+        downcast_event(self)
+    }
+}
+
+impl<R: BattleRules> AsRef<dyn Event<R> + Send> for EventWrapper<R> {
+    fn as_ref(&self) -> &(dyn Event<R> + Send + 'static) {
+        &*self.event
+    }
+}
+
 impl<R: BattleRules> Deref for EventWrapper<R> {
     type Target = Box<dyn Event<R> + Send>;
 
@@ -270,14 +738,27 @@ impl<R: BattleRules> Deref for VersionedEventWrapper<R> {
 /// in order to be applied.
 pub type Condition<R> = std::rc::Rc<dyn Fn(&BattleState<R>) -> bool>;
 
+/// Opaque user defined data that can be attached to any event.
+///
+/// Weasel never inspects metadata. It is stored in the history together with the event
+/// and forwarded to all sinks. Use it to put UI hints, chat messages or analytics tags
+/// on the same timeline of the battle.
+pub type EventMetadata = Vec<u8>;
+
 /// A prototype for tentative events that are not yet verified.
 pub struct EventPrototype<R: BattleRules> {
     /// Id of the event that generated this one.
     origin: Option<EventId>,
+    /// Ids of the other events that contributed to generate this one.
+    secondary_origins: Vec<EventId>,
     /// The actual event wrapped inside this struct.
     event: Box<dyn Event<R> + Send>,
     /// Condition that must be satisfied for this prototype to be valid.
     condition: Option<Condition<R>>,
+    /// Priority of this prototype among the other events in the same queue.
+    priority: Priority,
+    /// User defined data attached to this prototype.
+    metadata: Option<EventMetadata>,
 }
 
 impl<R: BattleRules> EventPrototype<R> {
@@ -285,13 +766,18 @@ impl<R: BattleRules> EventPrototype<R> {
     pub(crate) fn new(event: Box<dyn Event<R> + Send>) -> Self {
         Self {
             origin: None,
+            secondary_origins: Vec::new(),
             event,
             condition: None,
+            priority: Priority::Normal,
+            metadata: None,
         }
     }
 
     pub(crate) fn promote(self, id: EventId) -> EventWrapper<R> {
         EventWrapper::new(id, self.origin, self.event)
+            .with_secondary_origins(self.secondary_origins)
+            .with_metadata(self.metadata)
     }
 
     /// Returns the id of the event that caused this one.
@@ -304,6 +790,16 @@ impl<R: BattleRules> EventPrototype<R> {
         self.origin = origin;
     }
 
+    /// Returns the ids of the events, besides the primary origin, that caused this one.
+    pub fn secondary_origins(&self) -> &[EventId] {
+        &self.secondary_origins
+    }
+
+    /// Sets the ids of the events, besides the primary origin, that caused this one.
+    pub fn set_secondary_origins(&mut self, origins: Vec<EventId>) {
+        self.secondary_origins = origins;
+    }
+
     /// Returns the event.
     #[allow(clippy::borrowed_box)]
     pub fn event(&self) -> &Box<dyn Event<R> + Send> {
@@ -320,6 +816,26 @@ impl<R: BattleRules> EventPrototype<R> {
         self.condition = condition;
     }
 
+    /// Returns the priority of this prototype.
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+
+    /// Sets the priority of this prototype.
+    pub fn set_priority(&mut self, priority: Priority) {
+        self.priority = priority;
+    }
+
+    /// Returns the user defined data attached to this prototype, if any.
+    pub fn metadata(&self) -> Option<&[u8]> {
+        self.metadata.as_deref()
+    }
+
+    /// Sets the user defined data attached to this prototype.
+    pub fn set_metadata(&mut self, metadata: Option<EventMetadata>) {
+        self.metadata = metadata;
+    }
+
     /// Consume this event prototype and returns a `ClientEventPrototype` instance of it.
     pub fn client_prototype(
         self,
@@ -327,6 +843,8 @@ impl<R: BattleRules> EventPrototype<R> {
         player: Option<PlayerId>,
     ) -> ClientEventPrototype<R> {
         ClientEventPrototype::new(self.origin, self.event, version, player)
+            .with_secondary_origins(self.secondary_origins)
+            .with_metadata(self.metadata)
     }
 }
 
@@ -342,8 +860,11 @@ impl<R: BattleRules> Clone for EventPrototype<R> {
     fn clone(&self) -> Self {
         Self {
             origin: self.origin,
+            secondary_origins: self.secondary_origins.clone(),
             event: self.event.clone(),
             condition: self.condition.clone(),
+            priority: self.priority,
+            metadata: self.metadata.clone(),
         }
     }
 }
@@ -353,12 +874,16 @@ impl<R: BattleRules> Clone for EventPrototype<R> {
 pub struct ClientEventPrototype<R: BattleRules> {
     /// Id of the event that generated this one.
     origin: Option<EventId>,
+    /// Ids of the other events that contributed to generate this one.
+    secondary_origins: Vec<EventId>,
     /// The actual event wrapped inside this struct.
     pub(crate) event: Box<dyn Event<R> + Send>,
     /// Version of `BattleRules` that generated this event.
     pub(crate) version: Version<R>,
     /// Id of the player who fired this event.
     player: Option<PlayerId>,
+    /// User defined data attached to this event.
+    metadata: Option<EventMetadata>,
 }
 
 impl<R: BattleRules> ClientEventPrototype<R> {
@@ -371,12 +896,26 @@ impl<R: BattleRules> ClientEventPrototype<R> {
     ) -> Self {
         Self {
             origin,
+            secondary_origins: Vec::new(),
             event,
             version,
             player,
+            metadata: None,
         }
     }
 
+    /// Sets the ids of the other events that contributed to generate this one.
+    pub(crate) fn with_secondary_origins(mut self, origins: Vec<EventId>) -> Self {
+        self.secondary_origins = origins;
+        self
+    }
+
+    /// Sets the user defined data attached to this event.
+    pub(crate) fn with_metadata(mut self, metadata: Option<EventMetadata>) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
     /// Returns the `BattleRules`'s version of the event.
     pub fn version(&self) -> &Version<R> {
         &self.version
@@ -387,6 +926,11 @@ impl<R: BattleRules> ClientEventPrototype<R> {
         self.origin
     }
 
+    /// Returns the ids of the events, besides the primary origin, that caused this one.
+    pub fn secondary_origins(&self) -> &[EventId] {
+        &self.secondary_origins
+    }
+
     /// Returns the event.
     #[allow(clippy::borrowed_box)]
     pub fn event(&self) -> &Box<dyn Event<R> + Send> {
@@ -397,8 +941,11 @@ impl<R: BattleRules> ClientEventPrototype<R> {
     pub(crate) fn prototype(self) -> EventPrototype<R> {
         EventPrototype {
             origin: self.origin,
+            secondary_origins: self.secondary_origins,
             event: self.event,
             condition: None,
+            priority: Priority::Normal,
+            metadata: self.metadata,
         }
     }
 
@@ -414,6 +961,11 @@ impl<R: BattleRules> ClientEventPrototype<R> {
     pub fn player(&self) -> Option<PlayerId> {
         self.player
     }
+
+    /// Returns the user defined data attached to this event, if any.
+    pub fn metadata(&self) -> Option<&[u8]> {
+        self.metadata.as_deref()
+    }
 }
 
 impl<R: BattleRules> Deref for ClientEventPrototype<R> {
@@ -428,9 +980,11 @@ impl<R: BattleRules> Clone for ClientEventPrototype<R> {
     fn clone(&self) -> Self {
         Self {
             origin: self.origin,
+            secondary_origins: self.secondary_origins.clone(),
             event: self.event.clone(),
             version: self.version.clone(),
             player: self.player,
+            metadata: self.metadata.clone(),
         }
     }
 }
@@ -487,6 +1041,27 @@ pub trait EventTrigger<'a, R: BattleRules, P: 'a + EventProcessor<R>> {
     fn prototype(&self) -> EventPrototype<R> {
         EventPrototype::new(self.event())
     }
+
+    /// Attaches user defined data to the event constructed by this builder.
+    ///
+    /// See [Annotated](struct.Annotated.html).
+    fn metadata<M: Into<EventMetadata>>(self, metadata: M) -> Annotated<'a, R, Self, P>
+    where
+        Self: Sized,
+    {
+        Annotated::new(self, metadata.into())
+    }
+
+    /// Fires the event constructed by this builder, returning a future that resolves
+    /// once the processor is done with it.
+    #[cfg(feature = "async")]
+    fn fire_async(&'a mut self) -> BoxFuture<'a, P::AsyncOutput>
+    where
+        P: AsyncEventProcessor<R>,
+    {
+        let prototype = self.prototype();
+        self.processor().process_async(prototype)
+    }
 }
 
 /// Collection to queue events prototypes, in order of insertion.
@@ -703,6 +1278,69 @@ where
     }
 }
 
+/// Priority of an event prototype among the other events derived from the same event.
+///
+/// Derived events are processed from the highest to the lowest priority.
+/// Events with the same priority are processed in the order they were inserted into the queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// Processed before all other events.
+    High,
+    /// The priority of all events, unless specified otherwise.
+    Normal,
+    /// Processed after all other events.
+    Low,
+}
+
+/// Decorator for `EventQueue` processor. It sets the priority of all events inserted
+/// into the queue.
+///
+/// Derived events are sorted by priority just before being processed, thus it's possible
+/// to order reactions, such as counterattacks, deterministically before or after
+/// the other derived events.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, event::DummyEvent, event::Priority, event::WithPriority, rules::empty::*,
+///     BattleRules, EndBattle, EventQueue, EventTrigger,
+/// };
+///
+/// battle_rules! {}
+///
+/// let mut queue = EventQueue::<CustomRules>::new();
+/// EndBattle::trigger(&mut WithPriority::new(&mut queue, Priority::Low)).fire();
+/// DummyEvent::trigger(&mut queue).fire();
+/// assert_eq!(queue[0].priority(), Priority::Low);
+/// assert_eq!(queue[1].priority(), Priority::Normal);
+/// ```
+pub struct WithPriority<'a, R: BattleRules> {
+    event_queue: &'a mut EventQueue<R>,
+    priority: Priority,
+}
+
+impl<'a, R: BattleRules> WithPriority<'a, R> {
+    /// Creates a new WithPriority decorator for the given `event_queue`.
+    pub fn new(event_queue: &'a mut EventQueue<R>, priority: Priority) -> Self {
+        Self {
+            event_queue,
+            priority,
+        }
+    }
+}
+
+impl<R> EventProcessor<R> for WithPriority<'_, R>
+where
+    R: BattleRules,
+{
+    type ProcessOutput = ();
+
+    fn process(&mut self, mut event: EventPrototype<R>) -> Self::ProcessOutput {
+        event.set_priority(self.priority);
+        self.event_queue.push(event);
+    }
+}
+
 /// Decorator for `EventQueue` processor. It sets the origin of all events inserted into the queue
 /// to the `EventId` specified during instantiation, unless origin has been manually specified.
 ///
@@ -840,56 +1478,292 @@ pub trait EventSink {
     ///
     /// The provided implementation does nothing.
     fn on_disconnect(&mut self) {}
+
+    /// Invoked when this sink is disconnected because the server is shutting down.
+    ///
+    /// The provided implementation calls `on_disconnect`.
+    fn on_shutdown(&mut self, _reason: ShutdownReason) {
+        self.on_disconnect();
+    }
+}
+
+/// Policy to handle a failure of a client sink while sending an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SinkErrorPolicy {
+    /// Disconnect the sink immediately.
+    Disconnect,
+    /// Send the same event again, up to the given number of times.
+    /// The sink is disconnected if all attempts fail.
+    Retry(u32),
+    /// Store the event in a buffer holding at most the given number of events.
+    /// Buffered events are sent again, in order, before the next event is delivered to the sink.
+    /// The sink is disconnected if the buffer overflows.
+    Buffer(usize),
+    /// Skip the event and keep the sink connected.
+    /// The event is passed to `ClientSink::on_quarantine` and won't be sent again.\
+    /// Use this policy only when clients can tolerate missing events, as in spectator feeds.
+    Quarantine,
 }
 
 /// An output sink to dump versioned and verified events to a client.
 pub trait ClientSink<R: BattleRules>: EventSink {
     /// Sends an already accepted event to a remote or local client.
     fn send(&mut self, event: &VersionedEventWrapper<R>) -> WeaselResult<(), R>;
+
+    /// Invoked when sending the event with id `event_id` failed because of `error`.
+    /// Returns the policy to apply for this failure.
+    ///
+    /// The provided implementation disconnects the sink.
+    fn on_error(&mut self, _event_id: EventId, _error: &WeaselErrorType<R>) -> SinkErrorPolicy {
+        SinkErrorPolicy::Disconnect
+    }
+
+    /// Invoked when `event` is skipped because `on_error` returned
+    /// `SinkErrorPolicy::Quarantine`.
+    ///
+    /// The provided implementation does nothing.
+    fn on_quarantine(&mut self, _event: &VersionedEventWrapper<R>, _error: &WeaselErrorType<R>) {}
+
+    /// Sends the notification of a modification to an user metric.
+    /// Invoked only if the server forwards metrics, right after the event that caused `change`.
+    ///
+    /// Notifications are not part of the history: failures are logged and otherwise ignored.\
+    /// The provided implementation does nothing.
+    fn send_metric(&mut self, _change: &MetricChanged<R>) -> WeaselResult<(), R> {
+        Ok(())
+    }
+
+    /// Invoked when the server shuts down, to persist or send any data still held by the sink.
+    ///
+    /// The provided implementation does nothing.
+    fn flush(&mut self) -> WeaselResult<(), R> {
+        Ok(())
+    }
 }
 
 /// An output sink to dump tentative events to a server.
 pub trait ServerSink<R: BattleRules>: EventSink {
     /// Sends a client event prototype to a remote or local server.
     fn send(&mut self, event: &ClientEventPrototype<R>) -> WeaselResult<(), R>;
+
+    /// Asks the server to send again all events starting from the one with id `from`.
+    ///
+    /// The server is expected to answer with `Server::sync_events_for`.
+    /// The provided implementation returns an error, because resynchronization
+    /// is not supported.
+    fn request_resync(&mut self, _from: EventId) -> WeaselResult<(), R> {
+        Err(WeaselError::EventSinkError(format!(
+            "sink {} doesn't support resynchronization",
+            self.id()
+        )))
+    }
+}
+
+/// A client sink together with the events it failed to send.
+struct ClientSinkSlot<R: BattleRules> {
+    sink: Box<dyn ClientSink<R> + Send>,
+    buffer: VecDeque<VersionedEventWrapper<R>>,
+    last_event: Option<EventId>,
+    last_activity: Instant,
+    audience: Audience,
+}
+
+impl<R: BattleRules> ClientSinkSlot<R> {
+    fn new(sink: Box<dyn ClientSink<R> + Send>) -> Self {
+        Self {
+            sink,
+            buffer: VecDeque::new(),
+            last_event: None,
+            last_activity: Instant::now(),
+            audience: Audience::Everyone,
+        }
+    }
+
+    /// Returns the status of this sink.
+    fn status(&self) -> SinkStatus {
+        SinkStatus {
+            id: self.sink.id(),
+            last_event: self.last_event,
+            idle: self.last_activity.elapsed(),
+            buffered_events: self.buffer.len(),
+        }
+    }
+
+    /// Delivers `event` to the sink, after all previously buffered events.
+    /// Returns an error if the sink must be disconnected.
+    fn deliver(&mut self, event: &VersionedEventWrapper<R>) -> WeaselResult<(), R> {
+        self.buffer.push_back(event.clone());
+        self.drain()
+    }
+
+    /// Sends all buffered events to the sink.
+    /// Returns an error if the sink must be disconnected.
+    fn drain(&mut self) -> WeaselResult<(), R> {
+        while let Some(event) = self.buffer.front() {
+            match Self::try_send(&mut self.sink, event) {
+                Ok(()) => {
+                    self.last_event = Some(event.id());
+                    self.last_activity = Instant::now();
+                    self.buffer.pop_front();
+                }
+                Err((err, SinkErrorPolicy::Buffer(capacity))) => {
+                    if self.buffer.len() > capacity {
+                        return Err(err);
+                    }
+                    return Ok(());
+                }
+                Err((err, SinkErrorPolicy::Quarantine)) => {
+                    let event = self.buffer.pop_front().unwrap();
+                    warn!(
+                        "quarantined event {} for sink {}: {:?}",
+                        event.id(),
+                        self.sink.id(),
+                        err
+                    );
+                    self.sink.on_quarantine(&event, &err);
+                }
+                Err((err, _)) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends an event to the sink, retrying as long as the sink's policy allows it.
+    /// On failure, returns the last error together with the policy chosen by the sink.
+    fn try_send(
+        sink: &mut Box<dyn ClientSink<R> + Send>,
+        event: &VersionedEventWrapper<R>,
+    ) -> std::result::Result<(), (WeaselErrorType<R>, SinkErrorPolicy)> {
+        let mut attempts = 0;
+        loop {
+            let err = match sink.send(event) {
+                Ok(()) => return Ok(()),
+                Err(err) => err,
+            };
+            error!("{:?}", err);
+            match sink.on_error(event.id(), &err) {
+                SinkErrorPolicy::Retry(retries) if attempts < retries => attempts += 1,
+                policy => return Err((err, policy)),
+            }
+        }
+    }
+}
+
+/// Health information about a client sink connected to a server.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct SinkStatus {
+    id: EventSinkId,
+    last_event: Option<EventId>,
+    idle: Duration,
+    buffered_events: usize,
+}
+
+impl SinkStatus {
+    /// Returns the id of the sink.
+    pub fn id(&self) -> EventSinkId {
+        self.id
+    }
+
+    /// Returns the id of the last event delivered to the sink.
+    pub fn last_event(&self) -> Option<EventId> {
+        self.last_event
+    }
+
+    /// Returns how much time passed since the sink received its last event,
+    /// or since it was connected if it didn't receive any event.
+    pub fn idle(&self) -> Duration {
+        self.idle
+    }
+
+    /// Returns the number of events waiting to be delivered to the sink.
+    pub fn buffered_events(&self) -> usize {
+        self.buffered_events
+    }
 }
 
 /// A data structure to contain multiple client sinks.
 pub(crate) struct MultiClientSink<R: BattleRules> {
-    sinks: Vec<Box<dyn ClientSink<R> + Send>>,
+    sinks: Vec<ClientSinkSlot<R>>,
+    /// First undelivered event of each sink disconnected because of a failure.
+    detached: HashMap<EventSinkId, EventId>,
 }
 
 impl<R: BattleRules> MultiClientSink<R> {
     pub(crate) fn new() -> Self {
-        Self { sinks: Vec::new() }
+        Self {
+            sinks: Vec::new(),
+            detached: HashMap::new(),
+        }
     }
 
     /// Adds a new sink.
     /// Returns an error if another sink with the same id already exists.
     fn add(&mut self, sink: Box<dyn ClientSink<R> + Send>) -> WeaselResult<(), R> {
-        if self.sinks.iter().any(|e| e.id() == sink.id()) {
-            Err(WeaselError::DuplicatedEventSink(sink.id()))
+        self.insert(ClientSinkSlot::new(sink), Audience::Everyone)
+    }
+
+    /// Adds a new sink for `player`.
+    /// Returns an error if another sink with the same id already exists.
+    fn add_player(
+        &mut self,
+        sink: Box<dyn ClientSink<R> + Send>,
+        player: PlayerId,
+    ) -> WeaselResult<(), R> {
+        self.insert(ClientSinkSlot::new(sink), Audience::Player(player))
+    }
+
+    /// Adds a new sink for a spectator.
+    /// Returns an error if another sink with the same id already exists.
+    fn add_spectator(
+        &mut self,
+        sink: Box<dyn ClientSink<R> + Send>,
+        policy: RedactionPolicy,
+    ) -> WeaselResult<(), R> {
+        let audience = match policy {
+            RedactionPolicy::Full => Audience::Everyone,
+            RedactionPolicy::Redacted => Audience::Spectator,
+        };
+        self.insert(ClientSinkSlot::new(sink), audience)
+    }
+
+    fn insert(&mut self, mut slot: ClientSinkSlot<R>, audience: Audience) -> WeaselResult<(), R> {
+        slot.audience = audience;
+        let id = slot.sink.id();
+        if self.sinks.iter().any(|e| e.sink.id() == id) {
+            Err(WeaselError::DuplicatedEventSink(id))
         } else {
-            self.sinks.push(sink);
+            self.detached.remove(&id);
+            self.sinks.push(slot);
             Ok(())
         }
     }
 
+    /// Returns the audience of the sink with the given `id`.
+    fn audience(&self, id: EventSinkId) -> Audience {
+        self.sinks
+            .iter()
+            .find(|e| e.sink.id() == id)
+            .map_or(Audience::Everyone, |e| e.audience)
+    }
+
     /// Sends all `events` to an existing sink.
     /// Returns an error if sending the events failed or the sink doesn't exist.
     fn send<I>(&mut self, id: EventSinkId, events: I) -> WeaselResult<(), R>
     where
         I: Iterator<Item = VersionedEventWrapper<R>>,
     {
-        let index = self.sinks.iter().position(|e| e.id() == id);
+        let index = self.sinks.iter().position(|e| e.sink.id() == id);
         if let Some(index) = index {
             // Send events.
             for event in events {
-                let sink = &mut self.sinks[index];
-                let result = sink.send(&event);
+                #[cfg(feature = "tracing")]
+                let _span =
+                    tracing::debug_span!("dispatch", id = event.id(), kind = ?event.kind(), sink = id)
+                        .entered();
+                let result = self.sinks[index].deliver(&event);
                 if result.is_err() {
-                    sink.on_disconnect();
-                    self.sinks.remove(index);
+                    self.disconnect(index);
                 }
                 result?;
             }
@@ -900,32 +1774,119 @@ impl<R: BattleRules> MultiClientSink<R> {
     }
 
     /// Removes the sink with the given `id`, if it exists.
-    fn remove(&mut self, id: EventSinkId) {
-        let index = self.sinks.iter().position(|e| e.id() == id);
+    /// `next_id` is the id of the next event that would have been sent to the sink.
+    fn remove(&mut self, id: EventSinkId, next_id: EventId) {
+        let index = self.sinks.iter().position(|e| e.sink.id() == id);
         if let Some(index) = index {
-            self.sinks.remove(index);
+            let slot = self.sinks.remove(index);
+            let first_undelivered = slot.buffer.front().map_or(next_id, |event| event.id());
+            self.detached.insert(id, first_undelivered);
         }
     }
 
     /// Sends an event to all sinks.
-    /// If a sink returns an error, its on_disconnect() fn will be invoked
-    /// and the sink is disconnected from the server.
+    /// If a sink fails, its error policy is applied. Sinks that must be disconnected
+    /// have their on_disconnect() fn invoked and are removed from the server.
     pub(crate) fn send_all(&mut self, event: &VersionedEventWrapper<R>) {
+        self.send_all_filtered(event, |_| Some(event.clone()));
+    }
+
+    /// Sends an event to all sinks, as `send_all` does.
+    /// Sinks of players and of spectators receiving redacted events get the result of
+    /// `filter` instead, if any.
+    pub(crate) fn send_all_filtered<F>(&mut self, event: &VersionedEventWrapper<R>, filter: F)
+    where
+        F: Fn(Audience) -> Option<VersionedEventWrapper<R>>,
+    {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!(
+            "dispatch",
+            id = event.id(),
+            kind = ?event.kind(),
+            sinks = self.sinks.len()
+        )
+        .entered();
         let mut failed_sinks_index = Vec::new();
-        for (i, sink) in self.sinks.iter_mut().enumerate() {
-            sink.send(event).unwrap_or_else(|err| {
-                error!("{:?}", err);
-                failed_sinks_index.push(i)
-            });
+        for (i, slot) in self.sinks.iter_mut().enumerate() {
+            let result = if slot.audience == Audience::Everyone {
+                slot.deliver(event)
+            } else {
+                match filter(slot.audience) {
+                    Some(event) => slot.deliver(&event),
+                    None => continue,
+                }
+            };
+            if result.is_err() {
+                failed_sinks_index.push(i);
+            }
+        }
+        for i in failed_sinks_index.into_iter().rev() {
+            self.disconnect(i);
+        }
+    }
+
+    /// Sends the notification `change` to all sinks.
+    pub(crate) fn send_metric_all(&mut self, change: &MetricChanged<R>) {
+        for slot in &mut self.sinks {
+            if let Err(err) = slot.sink.send_metric(change) {
+                warn!(
+                    "failed to send {:?} to sink {}: {:?}",
+                    change,
+                    slot.sink.id(),
+                    err
+                );
+            }
         }
-        for i in failed_sinks_index {
-            self.sinks[i].on_disconnect();
-            self.sinks.remove(i);
+    }
+
+    /// Disconnects the sink at `index`, remembering the first event it didn't receive.
+    fn disconnect(&mut self, index: usize) {
+        let mut slot = self.sinks.remove(index);
+        slot.sink.on_disconnect();
+        if let Some(event) = slot.buffer.front() {
+            self.detached.insert(slot.sink.id(), event.id());
         }
     }
 
+    /// Removes the sink with the given `id`, even if it was already disconnected.
+    /// `next_id` is the id of the next event that would have been sent to the sink.
+    ///
+    /// Returns the id of the first event not delivered to the sink,
+    /// or `None` if the sink is unknown.
+    pub(crate) fn detach(&mut self, id: EventSinkId, next_id: EventId) -> Option<EventId> {
+        self.remove(id, next_id);
+        self.detached.remove(&id)
+    }
+
     fn sinks(&self) -> impl Iterator<Item = &Box<dyn ClientSink<R> + Send>> {
-        self.sinks.iter()
+        self.sinks.iter().map(|slot| &slot.sink)
+    }
+
+    /// Returns the status of all connected sinks.
+    pub(crate) fn statuses(&self) -> Vec<SinkStatus> {
+        self.sinks.iter().map(ClientSinkSlot::status).collect()
+    }
+
+    /// Sends all buffered events, flushes and disconnects all sinks.
+    /// Returns the errors that occurred while doing so.
+    pub(crate) fn shutdown(&mut self, reason: ShutdownReason) -> Vec<WeaselErrorType<R>> {
+        let mut errors = Vec::new();
+        for mut slot in self.sinks.drain(..) {
+            if let Err(err) = slot.drain() {
+                errors.push(err);
+            } else if !slot.buffer.is_empty() {
+                errors.push(WeaselError::EventSinkError(format!(
+                    "sink {} shut down with {} undelivered events",
+                    slot.sink.id(),
+                    slot.buffer.len()
+                )));
+            }
+            if let Err(err) = slot.sink.flush() {
+                errors.push(err);
+            }
+            slot.sink.on_shutdown(reason);
+        }
+        errors
     }
 }
 
@@ -958,6 +1919,8 @@ where
 {
     sinks: &'a mut MultiClientSink<R>,
     battle: &'a Battle<R>,
+    pre_broadcast: Option<&'a PreBroadcastCallback<R>>,
+    visibility_rules: Option<&'a (dyn VisibilityRules<R> + Send)>,
 }
 
 impl<'a, R> MultiClientSinkHandleMut<'a, R>
@@ -965,7 +1928,46 @@ where
     R: BattleRules + 'static,
 {
     pub(crate) fn new(sinks: &'a mut MultiClientSink<R>, battle: &'a Battle<R>) -> Self {
-        Self { sinks, battle }
+        Self {
+            sinks,
+            battle,
+            pre_broadcast: None,
+            visibility_rules: None,
+        }
+    }
+
+    /// Sets the callback applied to events taken from the history.
+    pub(crate) fn pre_broadcast(mut self, callback: Option<&'a PreBroadcastCallback<R>>) -> Self {
+        self.pre_broadcast = callback;
+        self
+    }
+
+    /// Sets the rules used to filter the events sent to players and spectators.
+    pub(crate) fn visibility_rules(
+        mut self,
+        rules: Option<&'a (dyn VisibilityRules<R> + Send)>,
+    ) -> Self {
+        self.visibility_rules = rules;
+        self
+    }
+
+    /// Returns the versioned events inside `range`, filtered by the pre broadcast callback.
+    /// The events are also filtered by the visibility rules, for the given `audience`.
+    fn history_events(
+        &self,
+        range: Range<usize>,
+        audience: Audience,
+    ) -> impl Iterator<Item = VersionedEventWrapper<R>> + 'a {
+        let battle = self.battle;
+        let callback = self.pre_broadcast;
+        let rules = self.visibility_rules;
+        battle
+            .versioned_events(range)
+            .filter_map(move |event| pre_broadcast(callback, battle, event))
+            .filter_map(move |event| match rules {
+                Some(rules) => filter(rules, battle, &event, audience),
+                None => Some(event),
+            })
     }
 
     /// Adds a new sink.
@@ -975,6 +1977,66 @@ where
         self.sinks.add(sink)
     }
 
+    /// Adds a new sink for `player`.
+    ///
+    /// Each event is filtered for the player by the server's `VisibilityRules`
+    /// before being sent to the sink; clients, lacking visibility rules, forward events
+    /// as they are.\
+    /// Sinks must have unique ids.
+    pub fn add_player_sink(
+        &mut self,
+        sink: Box<dyn ClientSink<R> + Send>,
+        player: PlayerId,
+    ) -> WeaselResult<(), R> {
+        self.sinks.add_player(sink, player)
+    }
+
+    /// Adds a new sink for a spectator, an observer who doesn't control any team.
+    ///
+    /// Events are sent to the sink according to `policy`. With `RedactionPolicy::Redacted`,
+    /// each event is first redacted by the server's `VisibilityRules`; clients,
+    /// lacking visibility rules, forward events as they are.\
+    /// Sinks must have unique ids.
+    ///
+    /// # Examples
+    /// ```
+    /// use weasel::visibility::{RedactionPolicy, VisibilityRules};
+    /// use weasel::{battle_rules, rules::empty::*, Battle, BattleRules, Server};
+    /// # use weasel::event::{ClientSink, EventSink, EventSinkId, VersionedEventWrapper};
+    /// # use weasel::WeaselResult;
+    /// # struct Spectator;
+    /// # impl EventSink for Spectator {
+    /// #     fn id(&self) -> EventSinkId { 1 }
+    /// # }
+    /// # impl ClientSink<CustomRules> for Spectator {
+    /// #     fn send(&mut self, _: &VersionedEventWrapper<CustomRules>)
+    /// #         -> WeaselResult<(), CustomRules> { Ok(()) }
+    /// # }
+    ///
+    /// battle_rules! {}
+    ///
+    /// struct Rules;
+    ///
+    /// impl VisibilityRules<CustomRules> for Rules {}
+    ///
+    /// let battle = Battle::builder(CustomRules::new()).build();
+    /// let mut server = Server::builder(battle)
+    ///     .visibility_rules(Box::new(Rules))
+    ///     .build();
+    /// server
+    ///     .client_sinks_mut()
+    ///     .add_spectator_sink(Box::new(Spectator), RedactionPolicy::Redacted)
+    ///     .unwrap();
+    /// assert_eq!(server.client_sinks().sinks().count(), 1);
+    /// ```
+    pub fn add_spectator_sink(
+        &mut self,
+        sink: Box<dyn ClientSink<R> + Send>,
+        policy: RedactionPolicy,
+    ) -> WeaselResult<(), R> {
+        self.sinks.add_spectator(sink, policy)
+    }
+
     /// Adds a new sink and shares the battle history with it,
     /// starting from the event having `event_id` up to the most recent event.
     ///
@@ -988,7 +2050,7 @@ where
             sink,
             Range {
                 start: event_id,
-                end: self.battle.history().len(),
+                end: self.battle.history().next_id(),
             },
         )
     }
@@ -1002,25 +2064,26 @@ where
         sink: Box<dyn ClientSink<R> + Send>,
         range: Range<EventId>,
     ) -> WeaselResult<(), R> {
-        let range = normalize_range(range, self.battle.history().len())?;
+        let range = normalize_range(range, self.battle.history())?;
         // Add the new sink.
         let sink_id = sink.id();
         self.sinks.add(sink)?;
         // Get all versioned events from history and send them.
-        self.sinks
-            .send(sink_id, self.battle.versioned_events(range))
+        let events = self.history_events(range, Audience::Everyone);
+        self.sinks.send(sink_id, events)
     }
 
     /// Sends a range of events from the battle history to the sink with the given id.
     pub fn send_range(&mut self, id: EventSinkId, range: Range<EventId>) -> WeaselResult<(), R> {
-        let range = normalize_range(range, self.battle.history().len())?;
+        let range = normalize_range(range, self.battle.history())?;
         // Get all versioned events from history and send them.
-        self.sinks.send(id, self.battle.versioned_events(range))
+        let events = self.history_events(range, self.sinks.audience(id));
+        self.sinks.send(id, events)
     }
 
     /// Removes the sink with the given id.
     pub fn remove_sink(&mut self, id: EventSinkId) {
-        self.sinks.remove(id);
+        self.sinks.remove(id, self.battle.history().next_id());
     }
 
     /// Returns an iterator over all sinks.
@@ -1030,12 +2093,13 @@ where
 }
 
 /// Converts a range of `EventId` into a range of `usize`.
-fn normalize_range<R: BattleRules>(
+pub(crate) fn normalize_range<R: BattleRules>(
     range: Range<EventId>,
-    history_len: EventId,
+    history: &History<R>,
 ) -> WeaselResult<Range<usize>, R> {
-    if range.start > range.end || range.end > history_len {
-        return Err(WeaselError::InvalidEventRange(range, history_len));
+    if range.start > range.end || range.start < history.first_id() || range.end > history.next_id()
+    {
+        return Err(WeaselError::InvalidEventRange(range, history.next_id()));
     }
     let range: Range<usize> = Range {
         start: range.start as usize,
@@ -1071,6 +2135,7 @@ where
 {
     trigger: T,
     origin: EventId,
+    secondary_origins: Vec<EventId>,
     _phantom: PhantomData<&'a P>,
     _phantom_: PhantomData<R>,
 }
@@ -1086,10 +2151,17 @@ where
         Self {
             trigger,
             origin,
+            secondary_origins: Vec::new(),
             _phantom: PhantomData,
             _phantom_: PhantomData,
         }
     }
+
+    /// Adds other events that, together with the primary origin, caused this event.
+    pub fn secondary_origins(mut self, origins: &[EventId]) -> Self {
+        self.secondary_origins.extend_from_slice(origins);
+        self
+    }
 }
 
 impl<'a, R, T, P> EventTrigger<'a, R, P> for Originated<'a, R, T, P>
@@ -1109,6 +2181,84 @@ where
     fn prototype(&self) -> EventPrototype<R> {
         let mut prototype = self.trigger.prototype();
         prototype.set_origin(Some(self.origin));
+        if !self.secondary_origins.is_empty() {
+            prototype.set_secondary_origins(self.secondary_origins.clone());
+        }
+        prototype
+    }
+}
+
+/// Decorator for event triggers to attach user defined metadata to an event.
+///
+/// The metadata is stored in the history and forwarded to sinks, together with the event.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, event::DummyEvent, rules::empty::*, Battle, BattleController, BattleRules,
+///     EventTrigger, Server,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// DummyEvent::trigger(&mut server)
+///     .metadata("hello")
+///     .fire()
+///     .unwrap();
+/// assert_eq!(
+///     server.battle().history().events()[0].metadata(),
+///     Some(&b"hello"[..])
+/// );
+/// ```
+pub struct Annotated<'a, R, T, P>
+where
+    R: BattleRules,
+    T: EventTrigger<'a, R, P>,
+    P: 'a + EventProcessor<R>,
+{
+    trigger: T,
+    metadata: EventMetadata,
+    _phantom: PhantomData<&'a P>,
+    _phantom_: PhantomData<R>,
+}
+
+impl<'a, R, T, P> Annotated<'a, R, T, P>
+where
+    R: BattleRules,
+    T: EventTrigger<'a, R, P>,
+    P: 'a + EventProcessor<R>,
+{
+    /// Creates a new decorator to attach `metadata` to an event.
+    pub fn new(trigger: T, metadata: EventMetadata) -> Self {
+        Self {
+            trigger,
+            metadata,
+            _phantom: PhantomData,
+            _phantom_: PhantomData,
+        }
+    }
+}
+
+impl<'a, R, T, P> EventTrigger<'a, R, P> for Annotated<'a, R, T, P>
+where
+    R: BattleRules,
+    T: EventTrigger<'a, R, P>,
+    P: 'a + EventProcessor<R>,
+{
+    fn processor(&'a mut self) -> &'a mut P {
+        self.trigger.processor()
+    }
+
+    fn event(&self) -> Box<dyn Event<R> + Send> {
+        self.trigger.event()
+    }
+
+    fn prototype(&self) -> EventPrototype<R> {
+        let mut prototype = self.trigger.prototype();
+        prototype.set_metadata(Some(self.metadata.clone()));
         prototype
     }
 }
@@ -1167,9 +2317,9 @@ mod tests {
         );
         assert_eq!(multi.sinks.len(), 1);
         // Check remove.
-        multi.remove(2);
+        multi.remove(2, 0);
         assert_eq!(multi.sinks.len(), 1);
-        multi.remove(0);
+        multi.remove(0, 0);
         assert_eq!(multi.sinks.len(), 0);
         // Check send_all.
         assert_eq!(multi.add(Box::new(Sink { id: 0, ok: true })).err(), None);
@@ -1190,10 +2340,194 @@ mod tests {
         assert_eq!(multi.add(Box::new(Sink { id: 1, ok: false })).err(), None);
         assert_eq!(multi.sinks.len(), 2);
         assert_eq!(
-            multi.send(1, once(event)).err(),
+            multi.send(1, once(event.clone())).err(),
+            Some(WeaselError::EventSinkError("broken".to_string()))
+        );
+        assert_eq!(multi.sinks.len(), 1);
+        // Check that send_all removes exactly the failed sinks, when they are more than one.
+        assert_eq!(multi.add(Box::new(Sink { id: 1, ok: false })).err(), None);
+        assert_eq!(multi.add(Box::new(Sink { id: 2, ok: true })).err(), None);
+        assert_eq!(multi.add(Box::new(Sink { id: 3, ok: false })).err(), None);
+        multi.send_all(&event);
+        let ids: Vec<_> = multi.sinks().map(|sink| sink.id()).collect();
+        assert_eq!(ids, vec![0, 2]);
+    }
+
+    #[test]
+    fn multi_client_sink_error_policy() {
+        struct Sink {
+            id: EventSinkId,
+            // Number of upcoming sends that will fail.
+            failures: u32,
+            policy: SinkErrorPolicy,
+        }
+
+        impl Sink {
+            fn new(id: EventSinkId, failures: u32, policy: SinkErrorPolicy) -> Self {
+                Self {
+                    id,
+                    failures,
+                    policy,
+                }
+            }
+        }
+
+        impl EventSink for Sink {
+            fn id(&self) -> EventSinkId {
+                self.id
+            }
+        }
+
+        impl ClientSink<CustomRules> for Sink {
+            fn send(
+                &mut self,
+                _: &VersionedEventWrapper<CustomRules>,
+            ) -> WeaselResult<(), CustomRules> {
+                if self.failures > 0 {
+                    self.failures -= 1;
+                    Err(WeaselError::EventSinkError("broken".to_string()))
+                } else {
+                    Ok(())
+                }
+            }
+
+            fn on_error(
+                &mut self,
+                _: EventId,
+                _: &WeaselErrorType<CustomRules>,
+            ) -> SinkErrorPolicy {
+                self.policy
+            }
+        }
+
+        let event = |id| {
+            DummyEvent::<CustomRules>::trigger(&mut ())
+                .prototype()
+                .promote(id)
+                .version(0)
+        };
+        // Retry succeeds within the allowed number of attempts.
+        let mut slot = ClientSinkSlot::new(Box::new(Sink::new(0, 2, SinkErrorPolicy::Retry(2))));
+        assert_eq!(slot.deliver(&event(0)).err(), None);
+        // Retry fails when all attempts are exhausted.
+        let mut slot = ClientSinkSlot::new(Box::new(Sink::new(0, 3, SinkErrorPolicy::Retry(2))));
+        assert_eq!(
+            slot.deliver(&event(0)).err(),
             Some(WeaselError::EventSinkError("broken".to_string()))
         );
+        // Buffered events are sent again in order.
+        let mut slot = ClientSinkSlot::new(Box::new(Sink::new(0, 2, SinkErrorPolicy::Buffer(2))));
+        assert_eq!(slot.deliver(&event(0)).err(), None);
+        assert_eq!(slot.deliver(&event(1)).err(), None);
+        assert_eq!(slot.buffer.len(), 2);
+        assert_eq!(slot.deliver(&event(2)).err(), None);
+        assert!(slot.buffer.is_empty());
+        // Buffer overflow disconnects the sink.
+        let mut slot = ClientSinkSlot::new(Box::new(Sink::new(0, 5, SinkErrorPolicy::Buffer(1))));
+        assert_eq!(slot.deliver(&event(0)).err(), None);
+        assert_eq!(
+            slot.deliver(&event(1)).err(),
+            Some(WeaselError::EventSinkError("broken".to_string()))
+        );
+        // Check the policies through send_all.
+        let mut multi = MultiClientSink::new();
+        assert_eq!(
+            multi
+                .add(Box::new(Sink::new(0, 1, SinkErrorPolicy::Disconnect)))
+                .err(),
+            None
+        );
+        assert_eq!(
+            multi
+                .add(Box::new(Sink::new(1, 1, SinkErrorPolicy::Disconnect)))
+                .err(),
+            None
+        );
+        assert_eq!(
+            multi
+                .add(Box::new(Sink::new(2, 1, SinkErrorPolicy::Buffer(1))))
+                .err(),
+            None
+        );
+        multi.send_all(&event(0));
+        assert_eq!(multi.sinks.len(), 1);
+        assert_eq!(multi.sinks().next().unwrap().id(), 2);
+        multi.send_all(&event(1));
+        assert!(multi.sinks[0].buffer.is_empty());
+    }
+
+    #[test]
+    fn multi_client_sink_quarantine() {
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Default)]
+        struct Log {
+            sent: Vec<EventId>,
+            quarantined: Vec<EventId>,
+        }
+
+        struct Sink {
+            poison: EventId,
+            log: Arc<Mutex<Log>>,
+        }
+
+        impl EventSink for Sink {
+            fn id(&self) -> EventSinkId {
+                0
+            }
+        }
+
+        impl ClientSink<CustomRules> for Sink {
+            fn send(
+                &mut self,
+                event: &VersionedEventWrapper<CustomRules>,
+            ) -> WeaselResult<(), CustomRules> {
+                if event.id() == self.poison {
+                    Err(WeaselError::EventSinkError("poison".to_string()))
+                } else {
+                    self.log.lock().unwrap().sent.push(event.id());
+                    Ok(())
+                }
+            }
+
+            fn on_error(
+                &mut self,
+                _: EventId,
+                _: &WeaselErrorType<CustomRules>,
+            ) -> SinkErrorPolicy {
+                SinkErrorPolicy::Quarantine
+            }
+
+            fn on_quarantine(
+                &mut self,
+                event: &VersionedEventWrapper<CustomRules>,
+                _: &WeaselErrorType<CustomRules>,
+            ) {
+                self.log.lock().unwrap().quarantined.push(event.id());
+            }
+        }
+
+        let event = |id| {
+            DummyEvent::<CustomRules>::trigger(&mut ())
+                .prototype()
+                .promote(id)
+                .version(0)
+        };
+        let log = Arc::new(Mutex::new(Log::default()));
+        let mut multi = MultiClientSink::new();
+        let sink = Sink {
+            poison: 1,
+            log: log.clone(),
+        };
+        assert_eq!(multi.add(Box::new(sink)).err(), None);
+        for id in 0..3 {
+            multi.send_all(&event(id));
+        }
+        // The poison event is skipped while the sink stays connected.
         assert_eq!(multi.sinks.len(), 1);
+        assert!(multi.sinks[0].buffer.is_empty());
+        assert_eq!(log.lock().unwrap().sent, vec![0, 2]);
+        assert_eq!(log.lock().unwrap().quarantined, vec![1]);
     }
 
     #[test]
@@ -1234,5 +2568,11 @@ mod tests {
         assert_eq!(EventRights::<R>::Teams(vec![&1, &2]), Teams(vec![&1, &2]));
         assert_ne!(EventRights::<R>::Teams(vec![&1, &2]), Teams(vec![&1, &3]));
         assert_ne!(EventRights::<R>::Team(&1), Teams(vec![&1]));
+        assert_eq!(
+            EventRights::<R>::All(vec![Team(&1), Server]),
+            All(vec![Team(&1), Server])
+        );
+        assert_ne!(EventRights::<R>::All(vec![Team(&1)]), Any(vec![Team(&1)]));
+        assert_ne!(EventRights::<R>::Any(vec![Team(&1)]), Any(vec![Team(&2)]));
     }
 }
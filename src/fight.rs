@@ -1,12 +1,14 @@
 //! Module to handle combat.
 
+use crate::ability::Ability;
+use crate::aura::Aura;
 use crate::battle::{Battle, BattleRules, BattleState};
 use crate::character::Character;
 use crate::entropy::Entropy;
 use crate::error::WeaselResult;
 use crate::event::{Event, EventKind, EventProcessor, EventQueue, EventTrigger, LinkedQueue};
 use crate::metric::WriteMetrics;
-use crate::status::{Application, AppliedStatus};
+use crate::status::{Application, AppliedStatus, Status, StatusCombination, StatusId};
 #[cfg(feature = "serialization")]
 use serde::{Deserialize, Serialize};
 use std::any::Any;
@@ -60,6 +62,39 @@ pub trait FightRules<R: BattleRules> {
     ) {
     }
 
+    /// Returns the order in which statuses inflicted together are applied.
+    ///
+    /// When an event derives more than one `InflictStatus` event, the latter are applied
+    /// in increasing order, so that statuses depending on others are resolved after them.
+    /// Statuses with the same order keep the order in which they were inflicted.\
+    /// Event priorities are respected.
+    ///
+    /// The provided implementation returns zero for every status.
+    fn status_order(&self, _status_id: &StatusId<R>) -> i32 {
+        0
+    }
+
+    /// Combines a status about to be inflicted upon a character with the statuses
+    /// already affecting it.
+    ///
+    /// Use this hook to resolve interactions between statuses, for instance to turn
+    /// a wet character into a frozen one. The returned combination tells whether the status
+    /// is inflicted, possibly altered, and which existing statuses are consumed by it.\
+    /// Consumed statuses are cleared, together with their side effects, before the
+    /// new status is added.
+    ///
+    /// The provided implementation inflicts the status as it is.
+    fn combine_statuses(
+        &self,
+        _state: &BattleState<R>,
+        _character: &dyn Character<R>,
+        status: Status<R>,
+        _entropy: &mut Entropy<R>,
+        _metrics: &mut WriteMetrics<R>,
+    ) -> StatusCombination<R> {
+        StatusCombination::Inflict(status, Vec::new())
+    }
+
     /// Applies the periodic side effects of a status.
     /// Returns `true` if the status should end after this update.
     ///
@@ -95,6 +130,41 @@ pub trait FightRules<R: BattleRules> {
         _metrics: &mut WriteMetrics<R>,
     ) {
     }
+
+    /// Returns `true` if `status` prevents the character it's inflicted upon from
+    /// activating `ability`. Use this hook to implement effects such as silence or disarm.
+    ///
+    /// It's invoked during the verification of `ActivateAbility`, once for each status
+    /// of the actor.
+    ///
+    /// The provided implementation never blocks any ability.
+    fn ability_blocked_by(&self, _status: &AppliedStatus<R>, _ability: &Ability<R>) -> bool {
+        false
+    }
+
+    /// Returns the aura projected by `status` of the character `source`,
+    /// or `None` if `status` is not an aura.
+    ///
+    /// Each aura inflicts a derived status on the characters inside its area.
+    /// See the [aura module](../aura/index.html).
+    ///
+    /// The provided implementation returns `None`.
+    fn aura(
+        &self,
+        _state: &BattleState<R>,
+        _source: &dyn Character<R>,
+        _status: &AppliedStatus<R>,
+    ) -> Option<Aura<R>> {
+        None
+    }
+
+    /// Returns the names of the optional features enabled in these fight rules.
+    /// They are reported by `Battle::describe`.
+    ///
+    /// The provided implementation returns no features.
+    fn features(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
 }
 
 /// Impacts encapsulate information about which creatures or areas are affected
@@ -179,12 +249,16 @@ impl<R: BattleRules + 'static> Event<R> for ApplyImpact<R> {
     }
 
     fn apply(&self, battle: &mut Battle<R>, event_queue: &mut Option<EventQueue<R>>) {
-        battle.rules.fight_rules().apply_impact(
-            &battle.state,
-            &self.impact,
-            event_queue,
-            &mut battle.entropy,
-            &mut battle.metrics.write_handle(),
+        watch!(
+            battle.apply_watcher(),
+            "FightRules::apply_impact",
+            battle.rules.fight_rules().apply_impact(
+                &battle.state,
+                &self.impact,
+                event_queue,
+                &mut battle.entropy,
+                &mut battle.metrics.write_handle(),
+            )
         );
     }
 
@@ -1,21 +1,35 @@
 //! History of events.
 
-use crate::battle::BattleRules;
+use crate::battle::{BattleRules, Version};
 use crate::error::{WeaselError, WeaselResult};
-use crate::event::EventId;
-use crate::event::EventWrapper;
+use crate::event::{Event, EventId, EventWrapper, VersionedEventWrapper};
+use std::collections::HashMap;
 use std::convert::TryInto;
+#[cfg(feature = "serialization")]
+use std::io::Write;
 
 /// History is the place where all events are kept, in a way such that they
 /// construct a single, consistent timeline.
+///
+/// A timeline normally starts from the event with id zero. The only exceptions are timelines
+/// bootstrapped from synthetic events, which start from the id of the first synthetic event,
+/// and timelines of battles restored from a snapshot, which start from the id of the first
+/// event fired after the restoration.
 pub struct History<R: BattleRules> {
     events: Vec<EventWrapper<R>>,
+    first_id: EventId,
+    /// Ids of the events caused by each event, either as primary or secondary origin.
+    derived: HashMap<EventId, Vec<EventId>>,
 }
 
 impl<R: BattleRules> History<R> {
     /// Creates a new History.
     pub(crate) fn new() -> Self {
-        Self { events: Vec::new() }
+        Self {
+            events: Vec::new(),
+            first_id: 0,
+            derived: HashMap::new(),
+        }
     }
 
     /// Returns all events inside this timeline.
@@ -23,19 +37,219 @@ impl<R: BattleRules> History<R> {
         &self.events
     }
 
+    /// Returns an iterator over all events of type `E` inside this timeline,
+    /// already downcast to their concrete type.
+    ///
+    /// # Examples
+    /// ```
+    /// use weasel::{
+    ///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateCreature,
+    ///     CreateTeam, EventTrigger, Server,
+    /// };
+    ///
+    /// battle_rules! {}
+    ///
+    /// let battle = Battle::builder(CustomRules::new()).build();
+    /// let mut server = Server::builder(battle).build();
+    ///
+    /// CreateTeam::trigger(&mut server, 1).fire().unwrap();
+    /// CreateCreature::trigger(&mut server, 1, 1, ()).fire().unwrap();
+    /// CreateCreature::trigger(&mut server, 2, 1, ()).fire().unwrap();
+    ///
+    /// let history = server.battle().history();
+    /// let ids: Vec<_> = history.iter_as::<CreateCreature<_>>().map(|e| *e.id()).collect();
+    /// assert_eq!(ids, vec![1, 2]);
+    /// ```
+    pub fn iter_as<E: Event<R> + 'static>(&self) -> impl Iterator<Item = &E> {
+        self.events
+            .iter()
+            .filter_map(|event| event.as_any().downcast_ref::<E>())
+    }
+
+    /// Returns the event with the given id.
+    ///
+    /// Returns `None` if the event doesn't exist or if it was pruned from this timeline
+    /// because the history has been bootstrapped from synthetic events.
+    pub fn event(&self, id: EventId) -> Option<&EventWrapper<R>> {
+        if id < self.first_id {
+            None
+        } else {
+            self.events.get((id - self.first_id) as usize)
+        }
+    }
+
+    /// Returns the id of the first event in this timeline.
+    pub fn first_id(&self) -> EventId {
+        self.first_id
+    }
+
+    /// Returns an iterator over the events directly caused by the event with the given id,
+    /// either as their primary or as one of their secondary origins.
+    pub fn children(&self, id: EventId) -> impl Iterator<Item = &EventWrapper<R>> {
+        self.derived
+            .get(&id)
+            .into_iter()
+            .flatten()
+            .filter_map(move |child| self.event(*child))
+    }
+
+    /// Returns an iterator over the events whose primary origin is the event with id `origin`,
+    /// in ascending order.
+    ///
+    /// Unlike `children`, events having `origin` only as secondary origin are excluded.
+    ///
+    /// # Examples
+    /// ```
+    /// use weasel::event::{DummyEvent, Originated};
+    /// use weasel::{
+    ///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, EventTrigger,
+    ///     Server,
+    /// };
+    ///
+    /// battle_rules! {}
+    ///
+    /// let battle = Battle::builder(CustomRules::new()).build();
+    /// let mut server = Server::builder(battle).build();
+    ///
+    /// DummyEvent::trigger(&mut server).fire().unwrap();
+    /// Originated::new(DummyEvent::trigger(&mut server), 0)
+    ///     .fire()
+    ///     .unwrap();
+    /// Originated::new(DummyEvent::trigger(&mut server), 1)
+    ///     .fire()
+    ///     .unwrap();
+    ///
+    /// let history = server.battle().history();
+    /// let derived: Vec<_> = history.derived_events(0).map(|event| event.id()).collect();
+    /// assert_eq!(derived, vec![1]);
+    /// assert_eq!(history.origin_chain(2), vec![1, 0]);
+    /// ```
+    pub fn derived_events(&self, origin: EventId) -> impl Iterator<Item = &EventWrapper<R>> {
+        self.children(origin)
+            .filter(move |event| event.origin() == Some(origin))
+    }
+
+    /// Returns the ids of the chain of primary origins of the event with the given id,
+    /// starting from its direct origin and ending with the root cause.
+    ///
+    /// The chain stops at the first origin pruned from this timeline.
+    pub fn origin_chain(&self, id: EventId) -> Vec<EventId> {
+        let mut chain = Vec::new();
+        let mut current = self.event(id).and_then(|event| event.origin());
+        while let Some(origin) = current {
+            chain.push(origin);
+            current = self.event(origin).and_then(|event| event.origin());
+        }
+        chain
+    }
+
+    /// Returns the ids of all events that directly or indirectly caused the event
+    /// with the given id, in ascending order.
+    ///
+    /// Origins pruned from this timeline are included, but their causes can't be followed.
+    ///
+    /// # Examples
+    /// ```
+    /// use weasel::event::{DummyEvent, Originated};
+    /// use weasel::{
+    ///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, EventTrigger,
+    ///     Server,
+    /// };
+    ///
+    /// battle_rules! {}
+    ///
+    /// let battle = Battle::builder(CustomRules::new()).build();
+    /// let mut server = Server::builder(battle).build();
+    ///
+    /// DummyEvent::trigger(&mut server).fire().unwrap();
+    /// DummyEvent::trigger(&mut server).fire().unwrap();
+    /// // A combo caused by both previous events.
+    /// Originated::new(DummyEvent::trigger(&mut server), 0)
+    ///     .secondary_origins(&[1])
+    ///     .fire()
+    ///     .unwrap();
+    /// Originated::new(DummyEvent::trigger(&mut server), 2)
+    ///     .fire()
+    ///     .unwrap();
+    ///
+    /// let history = server.battle().history();
+    /// assert_eq!(history.ancestors(3), vec![0, 1, 2]);
+    /// assert_eq!(history.descendants(1), vec![2, 3]);
+    /// ```
+    pub fn ancestors(&self, id: EventId) -> Vec<EventId> {
+        let mut ancestors = Vec::new();
+        let mut pending = vec![id];
+        while let Some(current) = pending.pop() {
+            if let Some(event) = self.event(current) {
+                for origin in event.origins() {
+                    if !ancestors.contains(&origin) {
+                        ancestors.push(origin);
+                        pending.push(origin);
+                    }
+                }
+            }
+        }
+        ancestors.sort_unstable();
+        ancestors
+    }
+
+    /// Returns the ids of all events directly or indirectly caused by the event
+    /// with the given id, in ascending order.
+    pub fn descendants(&self, id: EventId) -> Vec<EventId> {
+        let mut descendants = Vec::new();
+        let mut pending = vec![id];
+        while let Some(current) = pending.pop() {
+            for child in self.derived.get(&current).into_iter().flatten() {
+                if !descendants.contains(child) {
+                    descendants.push(*child);
+                    pending.push(*child);
+                }
+            }
+        }
+        descendants.sort_unstable();
+        descendants
+    }
+
     /// Stores a new event in the history logs.
     pub(crate) fn archive(&mut self, event: &EventWrapper<R>) {
-        assert_eq!(event.id() as usize, self.events.len());
+        if self.events.is_empty() && event.synthetic() {
+            self.first_id = event.id();
+        }
+        assert_eq!(event.id(), self.next_id());
+        for origin in event.origins() {
+            let derived = self.derived.entry(origin).or_default();
+            if derived.last() != Some(&event.id()) {
+                derived.push(event.id());
+            }
+        }
         self.events.push(event.clone());
     }
 
+    /// Makes this empty timeline start from `next_id`.
+    #[cfg(feature = "serialization")]
+    pub(crate) fn restore_next_id(&mut self, next_id: EventId) {
+        assert!(self.events.is_empty());
+        self.first_id = next_id;
+    }
+
+    /// Returns true if the events of this timeline reproduce the whole state of the battle,
+    /// that is, if the timeline is complete or it starts with synthetic events.
+    pub(crate) fn is_reproducible(&self) -> bool {
+        self.first_id == 0 || self.events.first().is_some_and(EventWrapper::synthetic)
+    }
+
     /// Verifies if an event has an id compatible with the current timeline.
     /// Timeline only accepts monotonically increasing ids with no gaps.
+    ///
+    /// An empty timeline also accepts a synthetic event with any id.
     pub(crate) fn verify_event(&self, event: &EventWrapper<R>) -> WeaselResult<(), R> {
-        if event.id() as usize != self.events.len() {
+        if self.events.is_empty() && event.synthetic() {
+            return Ok(());
+        }
+        if event.id() != self.next_id() {
             return Err(WeaselError::NonContiguousEventId(
                 event.id(),
-                self.events.len().try_into().unwrap(),
+                self.next_id(),
             ));
         }
         Ok(())
@@ -43,7 +257,7 @@ impl<R: BattleRules> History<R> {
 
     /// Returns the id for the next event.
     pub(crate) fn next_id(&self) -> EventId {
-        self.events.len().try_into().unwrap()
+        self.first_id + self.len()
     }
 
     /// Returns the number of events in this history.
@@ -55,6 +269,230 @@ impl<R: BattleRules> History<R> {
     pub fn is_empty(&self) -> bool {
         self.events.is_empty()
     }
+
+    /// Writes the whole timeline into `writer`, encoded with `format`.
+    ///
+    /// Each event is exported with its id, kind, origin and a summary equal to
+    /// the event's debug representation.
+    /// Use `Battle::export_history` to get summaries from `BattleRules::summarize`.
+    ///
+    /// # Examples
+    /// ```
+    /// use weasel::history::ExportFormat;
+    /// use weasel::{
+    ///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateTeam,
+    ///     EventTrigger, Server,
+    /// };
+    ///
+    /// battle_rules! {}
+    ///
+    /// let battle = Battle::builder(CustomRules::new()).build();
+    /// let mut server = Server::builder(battle).build();
+    /// CreateTeam::trigger(&mut server, 1).fire().unwrap();
+    ///
+    /// let mut buffer = Vec::new();
+    /// server.battle().history().export(&mut buffer, ExportFormat::Csv).unwrap();
+    /// let csv = String::from_utf8(buffer).unwrap();
+    /// assert_eq!(csv.lines().count(), 2);
+    /// assert!(csv.starts_with("id,kind,origin,summary\n0,CreateTeam,,"));
+    /// ```
+    #[cfg(feature = "serialization")]
+    pub fn export<W: Write>(&self, writer: &mut W, format: ExportFormat) -> WeaselResult<(), R> {
+        self.export_with_summary(writer, format, |event| format!("{:?}", event.event()))
+    }
+
+    /// Same as `export`, but the summary of each event is computed by `summary`.
+    #[cfg(feature = "serialization")]
+    pub fn export_with_summary<W, F>(
+        &self,
+        writer: &mut W,
+        format: ExportFormat,
+        mut summary: F,
+    ) -> WeaselResult<(), R>
+    where
+        W: Write,
+        F: FnMut(&EventWrapper<R>) -> String,
+    {
+        let error = |id, err: std::io::Error| WeaselError::EventWriteError(id, err.to_string());
+        if let ExportFormat::Csv = format {
+            writeln!(writer, "id,kind,origin,summary").map_err(|err| error(self.first_id, err))?;
+        }
+        for event in &self.events {
+            let kind = format!("{:?}", event.kind());
+            let summary = summary(event);
+            let result = match format {
+                ExportFormat::JsonLines => writeln!(
+                    writer,
+                    "{{\"id\":{},\"kind\":{},\"origin\":{},\"summary\":{}}}",
+                    event.id(),
+                    json_string(&kind),
+                    event
+                        .origin()
+                        .map_or_else(|| "null".to_string(), |origin| origin.to_string()),
+                    json_string(&summary)
+                ),
+                ExportFormat::Csv => writeln!(
+                    writer,
+                    "{},{},{},{}",
+                    event.id(),
+                    csv_field(&kind),
+                    event
+                        .origin()
+                        .map_or_else(String::new, |origin| origin.to_string()),
+                    csv_field(&summary)
+                ),
+            };
+            result.map_err(|err| error(event.id(), err))?;
+        }
+        writer.flush().map_err(|err| error(self.next_id(), err))
+    }
+}
+
+/// Formats in which a history can be exported with `History::export`.
+#[cfg(feature = "serialization")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExportFormat {
+    /// One JSON object per line, with the fields `id`, `kind`, `origin` and `summary`.
+    JsonLines,
+    /// Comma separated values, with a header row followed by one row per event.
+    /// The columns are `id`, `kind`, `origin` and `summary`.
+    Csv,
+}
+
+/// Encodes `value` as a JSON string literal.
+#[cfg(feature = "serialization")]
+fn json_string(value: &str) -> String {
+    let mut result = String::with_capacity(value.len() + 2);
+    result.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+/// Encodes `value` as a CSV field, quoting it if necessary.
+#[cfg(feature = "serialization")]
+fn csv_field(value: &str) -> String {
+    if value.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Builder to create synthetic timelines of events, without running a server.
+///
+/// Events get contiguous ids and the rules version given at construction.
+/// The resulting sequence is a valid timeline, thus it can be fed to anything that
+/// processes verified events, such as a client, an event sink or a replay tool.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, rules::empty::*, BattleRules, CreateCreature, CreateTeam, EventTrigger,
+///     HistoryBuilder,
+/// };
+///
+/// battle_rules! {}
+///
+/// let events = HistoryBuilder::<CustomRules>::new(0)
+///     .event(CreateTeam::trigger(&mut (), 1).event())
+///     .derived(CreateCreature::trigger(&mut (), 1, 1, ()).event())
+///     .build();
+/// assert_eq!(events[0].id(), 0);
+/// assert_eq!(events[1].id(), 1);
+/// assert_eq!(events[1].origin(), Some(0));
+/// ```
+pub struct HistoryBuilder<R: BattleRules> {
+    version: Version<R>,
+    events: Vec<EventWrapper<R>>,
+    first_id: EventId,
+    root: Option<EventId>,
+}
+
+impl<R: BattleRules> HistoryBuilder<R> {
+    /// Creates a new builder for a timeline whose events have the given rules' version.
+    pub fn new(version: Version<R>) -> Self {
+        Self {
+            version,
+            events: Vec::new(),
+            first_id: 0,
+            root: None,
+        }
+    }
+
+    /// Makes the timeline start from `first_id`, as if it were bootstrapped from
+    /// synthetic events. If `first_id` is not zero, the first event is marked as synthetic.
+    ///
+    /// It has no effect once events have been added.
+    pub fn first_id(mut self, first_id: EventId) -> Self {
+        if self.events.is_empty() {
+            self.first_id = first_id;
+        }
+        self
+    }
+
+    /// Appends an event without origin. Following derived events will originate from it.
+    pub fn event(mut self, event: Box<dyn Event<R> + Send>) -> Self {
+        let id = self.next_id();
+        self.push(id, None, Vec::new(), event);
+        self.root = Some(id);
+        self
+    }
+
+    /// Appends an event originated from the last event added with `event`.
+    pub fn derived(mut self, event: Box<dyn Event<R> + Send>) -> Self {
+        let id = self.next_id();
+        self.push(id, self.root, Vec::new(), event);
+        self
+    }
+
+    /// Appends an event caused by all the given events.
+    /// The first id is the primary origin, the others are secondary origins.
+    pub fn caused_by(mut self, event: Box<dyn Event<R> + Send>, origins: &[EventId]) -> Self {
+        let id = self.next_id();
+        let secondary_origins = origins.iter().skip(1).copied().collect();
+        self.push(id, origins.first().copied(), secondary_origins, event);
+        self
+    }
+
+    /// Returns the id that will be assigned to the next event.
+    pub fn next_id(&self) -> EventId {
+        self.first_id + self.events.len() as EventId
+    }
+
+    /// Consumes this builder and returns the timeline of versioned events.
+    pub fn build(self) -> Vec<VersionedEventWrapper<R>> {
+        let version = self.version;
+        self.events
+            .into_iter()
+            .map(|event| event.version(version.clone()))
+            .collect()
+    }
+
+    fn push(
+        &mut self,
+        id: EventId,
+        origin: Option<EventId>,
+        secondary_origins: Vec<EventId>,
+        event: Box<dyn Event<R> + Send>,
+    ) {
+        let synthetic = self.events.is_empty() && self.first_id != 0;
+        self.events.push(
+            EventWrapper::new(id, origin, event)
+                .with_secondary_origins(secondary_origins)
+                .synthesized(synthetic),
+        );
+    }
 }
 
 #[cfg(test)]
@@ -80,4 +518,35 @@ mod tests {
         assert!(try_archive(1).is_err());
         assert!(try_archive(0).is_err());
     }
+
+    #[test]
+    fn synthetic_start() {
+        battle_rules! {}
+        let mut history = History::<CustomRules>::new();
+        let mut try_archive = |id, synthetic| -> WeaselResult<(), _> {
+            let event = EventWrapper::new(id, None, DummyEvent::trigger(&mut ()).event())
+                .synthesized(synthetic);
+            history.verify_event(&event)?;
+            history.archive(&event);
+            Ok(())
+        };
+        assert!(try_archive(5, false).is_err());
+        assert!(try_archive(5, true).is_ok());
+        assert!(try_archive(7, true).is_err());
+        assert!(try_archive(6, false).is_ok());
+        assert_eq!(history.first_id(), 5);
+        assert_eq!(history.next_id(), 7);
+        assert!(history.event(4).is_none());
+        assert_eq!(history.event(6).map(|event| event.id()), Some(6));
+    }
+
+    #[cfg(feature = "serialization")]
+    #[test]
+    fn export_escaping() {
+        assert_eq!(json_string("plain"), "\"plain\"");
+        assert_eq!(json_string("a\"b\\c\nd\u{1}"), "\"a\\\"b\\\\c\\nd\\u0001\"");
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
 }
@@ -0,0 +1,115 @@
+//! Middleware for the processing of events.
+
+use crate::battle::{Battle, BattleRules};
+use crate::error::WeaselResult;
+use crate::event::{EventPrototype, EventWrapper};
+use crate::player::PlayerId;
+
+/// A middleware that observes, vetoes or annotates the events processed by
+/// a server or a client.
+///
+/// Interceptors are registered with `add_interceptor` and they are invoked in order of
+/// registration. They are useful to add logging, rate limiting or debugging layers.
+///
+/// # Examples
+/// ```
+/// use weasel::event::{DummyEvent, EventPrototype};
+/// use weasel::interceptor::EventInterceptor;
+/// use weasel::player::PlayerId;
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, EventTrigger,
+///     Server, WeaselError, WeaselResult,
+/// };
+///
+/// battle_rules! {}
+///
+/// /// Accepts at most a fixed number of events.
+/// struct RateLimiter(usize);
+///
+/// impl EventInterceptor<CustomRules> for RateLimiter {
+///     fn before_verification(
+///         &mut self,
+///         _: &Battle<CustomRules>,
+///         _: &mut EventPrototype<CustomRules>,
+///         _: Option<PlayerId>,
+///     ) -> WeaselResult<(), CustomRules> {
+///         if self.0 == 0 {
+///             return Err(WeaselError::UserError("rate limit exceeded".to_string()));
+///         }
+///         self.0 -= 1;
+///         Ok(())
+///     }
+/// }
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+/// server.add_interceptor(Box::new(RateLimiter(1)));
+///
+/// assert!(DummyEvent::trigger(&mut server).fire().is_ok());
+/// assert!(DummyEvent::trigger(&mut server).fire().is_err());
+/// assert_eq!(server.battle().history().len(), 1);
+/// ```
+pub trait EventInterceptor<R: BattleRules> {
+    /// Invoked before `event` is verified. `player` is the player who fired the event,
+    /// if known.
+    ///
+    /// The prototype can be annotated, for instance by changing its origins or condition.
+    /// Condition and priority are ignored for events fired by clients.\
+    /// Returning an error vetoes the event.
+    ///
+    /// The provided implementation accepts all events.
+    fn before_verification(
+        &mut self,
+        _battle: &Battle<R>,
+        _event: &mut EventPrototype<R>,
+        _player: Option<PlayerId>,
+    ) -> WeaselResult<(), R> {
+        Ok(())
+    }
+
+    /// Invoked after `event` has been applied to `battle`.
+    ///
+    /// The provided implementation does nothing.
+    fn after_application(&mut self, _battle: &Battle<R>, _event: &EventWrapper<R>) {}
+}
+
+/// A chain of interceptors, invoked in order of insertion.
+pub(crate) struct Interceptors<R: BattleRules> {
+    interceptors: Vec<Box<dyn EventInterceptor<R> + Send>>,
+}
+
+impl<R: BattleRules> Interceptors<R> {
+    pub(crate) fn new() -> Self {
+        Self {
+            interceptors: Vec::new(),
+        }
+    }
+
+    pub(crate) fn add(&mut self, interceptor: Box<dyn EventInterceptor<R> + Send>) {
+        self.interceptors.push(interceptor);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.interceptors.clear();
+    }
+
+    /// Runs all interceptors on `event`, stopping at the first veto.
+    pub(crate) fn before_verification(
+        &mut self,
+        battle: &Battle<R>,
+        event: &mut EventPrototype<R>,
+        player: Option<PlayerId>,
+    ) -> WeaselResult<(), R> {
+        for interceptor in &mut self.interceptors {
+            interceptor.before_verification(battle, event, player)?;
+        }
+        Ok(())
+    }
+
+    /// Notifies all interceptors that `event` has been applied.
+    pub(crate) fn after_application(&mut self, battle: &Battle<R>, event: &EventWrapper<R>) {
+        for interceptor in &mut self.interceptors {
+            interceptor.after_application(battle, event);
+        }
+    }
+}
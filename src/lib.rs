@@ -55,6 +55,7 @@
 //! - Long lasting status effects.
 //! - Player managed teams.
 //! - Team objectives and diplomacy.
+//! - Per team visibility of entities.
 //! - Division of the battle into turns and rounds.
 //! - Rules to govern the game subdivided into orthogonal traits.
 //! - Fully serializable battle history.
@@ -118,20 +119,58 @@
 //!
 //! The following optional features are available:
 //!
+//! - `arena`: stores creatures and objects in generational arenas, for faster iterations and
+//!   stable handles to entities.
+//! - `async`: enables asynchronous counterparts of event processors and sinks, built on top
+//!   of the standard library's futures.
+//! - `campaign`: enables a campaign layer to persist a roster of teams and creatures across
+//!   multiple battles.
 //! - `random`: enables built-in entropy rules that use a pseudorandom number generator.
 //! - `serialization`: enables serialization and deserialization of events.
+//! - `tcp`: enables ready to use sinks to exchange events over tcp. Implies `serialization`.
+//! - `tracing`: emits `tracing` spans for the verification, application and dispatch to sinks
+//!   of each event.
+
+/// Evaluates `$call`, the rules hook named `$hook`, and reports it to the `Watcher`
+/// of the event being processed if it exceeds the watchdog's time budget.
+macro_rules! watch {
+    ($watcher: expr, $hook: expr, $call: expr) => {{
+        let watcher: $crate::battle::Watcher = $watcher;
+        let start = watcher.start();
+        let result = $call;
+        watcher.finish($hook, start);
+        result
+    }};
+}
 
 pub mod ability;
-pub use crate::ability::ActivateAbility;
+pub use crate::ability::{ActivateAbility, InterruptAbility};
 
 pub mod actor;
-pub use crate::actor::{Action, Actor, ActorRules, AlterAbilities, RegenerateAbilities};
+pub use crate::actor::{
+    Action, ActionSuggestion, ActivationRecord, Actor, ActorRules, AlterAbilities,
+    RechargeAbilities, RegenerateAbilities, TrackedAbility,
+};
+
+#[cfg(feature = "arena")]
+pub mod arena;
+
+#[cfg(feature = "async")]
+pub mod asynchronous;
+
+pub mod aura;
+pub use crate::aura::Aura;
 
 pub mod battle;
 pub use crate::battle::{
-    Battle, BattleController, BattleRules, BattleState, EndBattle, EventCallback, Version,
+    Battle, BattleController, BattleOutcome, BattleRules, BattleState, Changes, EndBattle,
+    EndReason, EventCallback, Extensions, Handshake, RulesData, RulesDescription, RulesDescriptor,
+    StartBattle, StaticData, VerifyState, Version, Watchdog,
 };
 
+#[cfg(feature = "campaign")]
+pub mod campaign;
+
 pub mod character;
 pub use crate::character::{AlterStatistics, Character, CharacterRules, RegenerateStatistics};
 
@@ -139,10 +178,15 @@ pub mod client;
 pub use crate::client::Client;
 
 pub mod creature;
-pub use crate::creature::{ConvertCreature, CreateCreature, Creature, RemoveCreature};
+pub use crate::creature::{
+    ConvertCreature, CreateCreature, Creature, ReidentifyCreature, RemoveCreature, SpawnWave,
+    TransferOwnership, TransformCreature,
+};
 
 pub mod entity;
-pub use crate::entity::{Entities, Entity, EntityId, RemoveEntity, Transmutation};
+pub use crate::entity::{
+    AlterTags, Entities, Entity, EntityId, RemoveEntity, SetTimeToLive, Tag, Transmutation,
+};
 
 pub mod entropy;
 pub use crate::entropy::{Entropy, EntropyRules, ResetEntropy};
@@ -152,33 +196,50 @@ pub use crate::error::{WeaselError, WeaselResult};
 
 pub mod event;
 pub use crate::event::{
-    ClientEventPrototype, Event, EventId, EventKind, EventProcessor, EventPrototype, EventQueue,
-    EventReceiver, EventRights, EventServer, EventTrigger, EventWrapper, LinkedQueue,
-    VersionedEventWrapper,
+    ClientEventPrototype, Event, EventId, EventKind, EventMetadata, EventProcessor, EventPrototype,
+    EventQueue, EventReceiver, EventRights, EventServer, EventTrigger, EventVisitor, EventWrapper,
+    LinkedQueue, Priority, VersionedEventWrapper, WithPriority,
 };
 
 pub mod fight;
 pub use crate::fight::{ApplyImpact, FightRules};
 
 pub mod history;
-pub use crate::history::History;
+#[cfg(feature = "serialization")]
+pub use crate::history::ExportFormat;
+pub use crate::history::{History, HistoryBuilder};
+
+pub mod interceptor;
+pub use crate::interceptor::EventInterceptor;
 
 pub mod metric;
-pub use crate::metric::{Metric, MetricId, ReadMetrics, SystemMetricId, WriteMetrics};
+pub use crate::metric::{
+    Histogram, Metric, MetricChanged, MetricId, MetricsDelta, ReadMetrics, SystemMetricId,
+    WriteMetrics,
+};
 
 pub mod object;
-pub use crate::object::{CreateObject, Object, RemoveObject};
+pub use crate::object::{CreateObject, Object, ObjectOwner, RemoveObject, TransferObjectOwnership};
 
 pub mod player;
-pub use crate::player::PlayerId;
+pub use crate::player::{Player, PlayerId, PlayerRules, Players, RegisterPlayer, RemovePlayer};
 
 pub mod power;
 pub use crate::power::InvokePower;
 
 pub mod round;
 pub use crate::round::{
-    EndRound, EndTurn, EnvironmentTurn, ResetRounds, Rounds, RoundsRules, StartTurn,
+    AddTurnActor, Delay, EndRound, EndTurn, EnvironmentTurn, RemoveTurnActor, ResetRounds,
+    RestoreRounds, Rounds, RoundsRules, ScheduleEvent, StartTurn, Tick, TurnTimedOut,
 };
+#[allow(deprecated)]
+pub use crate::round::{EnvironmentRound, StartRound};
+
+pub mod reaction;
+pub use crate::reaction::ReactionRules;
+
+pub mod replay;
+pub use crate::replay::Replay;
 
 pub mod rules;
 
@@ -190,22 +251,44 @@ pub use crate::serde::{FlatClientEvent, FlatEvent, FlatVersionedEvent};
 pub mod server;
 pub use crate::server::Server;
 
+pub mod sinks;
+
 pub mod space;
-pub use crate::space::{AlterSpace, MoveEntity, PositionClaim, ResetSpace, Space, SpaceRules};
+pub use crate::space::{
+    AlterSpace, Area, Distance, MoveEntity, MoveEntityAlongPath, PositionClaim, ResetSpace, Space,
+    SpaceRules,
+};
 
 pub mod status;
-pub use crate::status::{AlterStatuses, Application, AppliedStatus, ClearStatus, InflictStatus};
+pub use crate::status::{
+    AlterStatusDuration, AlterStatuses, Application, AppliedStatus, ClearStatus, InflictStatus,
+    StatusCombination,
+};
 
 pub mod team;
 pub use crate::team::{
-    AlterPowers, Call, ConcludeObjectives, Conclusion, CreateTeam, EntityAddition,
-    RegeneratePowers, Relation, RemoveTeam, ResetObjectives, SetRelations, Team, TeamRules,
+    AddScore, AlterPowers, Call, ConcludeObjectives, Conclusion, CreateTeam, EntityAddition,
+    ObjectiveProgress, RegeneratePowers, Relation, RemoveTeam, RenameTeam, ResetObjectives,
+    ResourceId, SetRelations, TargetRelation, Team, TeamRules, TradeProposal, TransferResource,
 };
 
+pub mod testing;
+
+pub mod transaction;
+pub use crate::transaction::Transaction;
+
+pub mod undo;
+pub use crate::undo::UndoManager;
+
 pub mod user;
 #[cfg(feature = "serialization")]
 pub use crate::user::UserEventPacker;
-pub use crate::user::{UserEventId, UserRules};
+pub use crate::user::{UserEventId, UserEventRegistry, UserRules};
 
 pub mod util;
 pub use crate::util::Id;
+
+pub mod visibility;
+pub use crate::visibility::{
+    HideEntity, RedactionPolicy, RevealEntity, Visibility, VisibilityRules,
+};
@@ -2,31 +2,188 @@
 
 use crate::battle::BattleRules;
 use crate::error::{WeaselError, WeaselResult};
+use crate::event::EventId;
+use crate::round::{RoundsCount, TurnsCount};
 use crate::user::{UserMetricId, UserRules};
-use std::collections::HashMap;
+#[cfg(feature = "serialization")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Debug, Formatter, Result};
 use std::hash::Hash;
 
+/// Map containing the values of all metrics.
+type MetricsMap<R> = HashMap<MetricIdType<R>, Metric>;
+
+/// Map containing the values of user metrics, bucketed by turn.
+type SeriesMap<R> = HashMap<UserMetricId<R>, BTreeMap<TurnsCount, u64>>;
+
+/// Map containing all user histograms.
+type HistogramsMap<R> = HashMap<UserMetricId<R>, Histogram>;
+
 /// Manages all metrics in a battle.
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub(crate) struct Metrics<R: BattleRules> {
-    map: HashMap<MetricIdType<R>, Metric>,
+    #[cfg_attr(
+        feature = "serialization",
+        serde(
+            bound(
+                serialize = "MetricIdType<R>: Serialize",
+                deserialize = "MetricIdType<R>: Deserialize<'de>"
+            ),
+            serialize_with = "crate::serde::serialize_pairs",
+            deserialize_with = "crate::serde::deserialize_pairs"
+        )
+    )]
+    map: MetricsMap<R>,
+    /// Values of all metrics at the end of each round.
+    #[cfg_attr(
+        feature = "serialization",
+        serde(
+            bound(
+                serialize = "MetricIdType<R>: Serialize",
+                deserialize = "MetricIdType<R>: Deserialize<'de>"
+            ),
+            serialize_with = "serialize_snapshots",
+            deserialize_with = "deserialize_snapshots"
+        )
+    )]
+    snapshots: Vec<MetricsMap<R>>,
+    /// Values of user metrics, bucketed by turn.
+    #[cfg_attr(
+        feature = "serialization",
+        serde(
+            bound(
+                serialize = "UserMetricId<R>: Serialize",
+                deserialize = "UserMetricId<R>: Deserialize<'de>"
+            ),
+            serialize_with = "crate::serde::serialize_pairs",
+            deserialize_with = "crate::serde::deserialize_pairs"
+        )
+    )]
+    series: SeriesMap<R>,
+    /// Distributions of values recorded by the rules.
+    #[cfg_attr(
+        feature = "serialization",
+        serde(
+            bound(
+                serialize = "UserMetricId<R>: Serialize",
+                deserialize = "UserMetricId<R>: Deserialize<'de>"
+            ),
+            serialize_with = "crate::serde::serialize_pairs",
+            deserialize_with = "crate::serde::deserialize_pairs"
+        )
+    )]
+    histograms: HistogramsMap<R>,
+    /// Number of turns completed so far.
+    turn: TurnsCount,
+    /// Modifications of user metrics not yet collected, if tracking is enabled.
+    #[cfg_attr(feature = "serialization", serde(skip, default = "Option::default"))]
+    changes: Option<Vec<(UserMetricId<R>, Option<Metric>)>>,
 }
 
 impl<R: BattleRules> Metrics<R> {
     pub(crate) fn new() -> Self {
         Self {
             map: HashMap::new(),
+            snapshots: Vec::new(),
+            series: HashMap::new(),
+            histograms: HashMap::new(),
+            turn: 0,
+            changes: None,
         }
     }
 
     /// Returns a handle to read metrics.
     pub(crate) fn read_handle(&self) -> ReadMetrics<R> {
-        ReadMetrics { metrics: self }
+        ReadMetrics {
+            map: &self.map,
+            snapshots: &self.snapshots,
+            series: Some(&self.series),
+            histograms: Some(&self.histograms),
+        }
+    }
+
+    /// Sets the number of turns completed so far, used to bucket the values of series.
+    pub(crate) fn set_turn(&mut self, turn: TurnsCount) {
+        self.turn = turn;
+    }
+
+    /// Enables or disables the tracking of modifications to user metrics.
+    pub(crate) fn track_changes(&mut self, enabled: bool) {
+        self.changes = if enabled { Some(Vec::new()) } else { None };
+    }
+
+    /// Returns true if modifications to user metrics are tracked.
+    pub(crate) fn tracking_changes(&self) -> bool {
+        self.changes.is_some()
+    }
+
+    /// Removes and returns all tracked modifications, in order, tagging them with `event_id`.
+    pub(crate) fn take_changes(&mut self, event_id: EventId) -> Vec<MetricChanged<R>> {
+        match &mut self.changes {
+            Some(changes) => changes
+                .drain(..)
+                .map(|(id, value)| MetricChanged {
+                    event_id,
+                    id,
+                    value,
+                })
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Saves the current value of all metrics as the snapshot of the round just completed.
+    pub(crate) fn take_snapshot(&mut self) {
+        self.snapshots.push(self.map.clone());
     }
 
     /// Returns a handle to write metrics.
     pub(crate) fn write_handle(&mut self) -> WriteMetrics<R> {
         WriteMetrics { metrics: self }
     }
+
+    /// Replaces all metrics with those in `metrics`.
+    ///
+    /// The tracking of modifications to user metrics is left untouched.
+    #[cfg(feature = "serialization")]
+    pub(crate) fn restore(&mut self, metrics: Metrics<R>) {
+        self.map = metrics.map;
+        self.snapshots = metrics.snapshots;
+        self.series = metrics.series;
+        self.histograms = metrics.histograms;
+        self.turn = metrics.turn;
+    }
+}
+
+/// Serializes the snapshots of all metrics as sequences of key-value pairs.
+#[cfg(feature = "serialization")]
+fn serialize_snapshots<K, S>(
+    snapshots: &[HashMap<K, Metric>],
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error>
+where
+    K: Serialize,
+    S: Serializer,
+{
+    serializer.collect_seq(snapshots.iter().map(|map| map.iter().collect::<Vec<_>>()))
+}
+
+/// Deserializes the snapshots of all metrics from sequences of key-value pairs.
+#[cfg(feature = "serialization")]
+fn deserialize_snapshots<'de, K, D>(
+    deserializer: D,
+) -> std::result::Result<Vec<HashMap<K, Metric>>, D::Error>
+where
+    K: Deserialize<'de> + Eq + Hash,
+    D: Deserializer<'de>,
+{
+    let snapshots = Vec::<Vec<(K, Metric)>>::deserialize(deserializer)?;
+    Ok(snapshots
+        .into_iter()
+        .map(|entries| entries.into_iter().collect())
+        .collect())
 }
 
 /// Alias for system metrics id.
@@ -37,6 +194,7 @@ pub type MetricIdType<R> = MetricId<<<R as BattleRules>::UR as UserRules<R>>::Us
 
 /// An id to uniquely identify metrics.
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub enum MetricId<T> {
     /// System metric.
     System(SystemMetricId),
@@ -45,7 +203,8 @@ pub enum MetricId<T> {
 }
 
 /// A metric is a compact measurement of some quantity.
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub enum Metric {
     /// A 64 bit unsigned counter.
     CounterU64(u64),
@@ -53,11 +212,47 @@ pub enum Metric {
     CounterI64(i64),
     /// A 64 bit floating point counter.
     CounterF64(f64),
+    /// A 64 bit floating point gauge. Unlike counters, gauges are overwritten
+    /// with each new value.
+    GaugeF64(f64),
+}
+
+impl Metric {
+    /// Returns the difference between this metric and `other`,
+    /// or `None` if the two metrics have different types.
+    ///
+    /// The difference of unsigned counters saturates at zero.
+    fn difference(&self, other: &Metric) -> Option<Metric> {
+        match (self, other) {
+            (Metric::CounterU64(a), Metric::CounterU64(b)) => {
+                Some(Metric::CounterU64(a.saturating_sub(*b)))
+            }
+            (Metric::CounterI64(a), Metric::CounterI64(b)) => {
+                Some(Metric::CounterI64(a.wrapping_sub(*b)))
+            }
+            (Metric::CounterF64(a), Metric::CounterF64(b)) => Some(Metric::CounterF64(a - b)),
+            (Metric::GaugeF64(a), Metric::GaugeF64(b)) => Some(Metric::GaugeF64(a - b)),
+            _ => None,
+        }
+    }
+
+    /// Returns a metric of the same type, with value zero.
+    fn zero(&self) -> Metric {
+        match self {
+            Metric::CounterU64(_) => Metric::CounterU64(0),
+            Metric::CounterI64(_) => Metric::CounterI64(0),
+            Metric::CounterF64(_) => Metric::CounterF64(0.),
+            Metric::GaugeF64(_) => Metric::GaugeF64(0.),
+        }
+    }
 }
 
 /// Handle to read metrics.
 pub struct ReadMetrics<'a, R: BattleRules> {
-    metrics: &'a Metrics<R>,
+    map: &'a MetricsMap<R>,
+    snapshots: &'a [MetricsMap<R>],
+    series: Option<&'a SeriesMap<R>>,
+    histograms: Option<&'a HistogramsMap<R>>,
 }
 
 macro_rules! get_metric {
@@ -93,42 +288,206 @@ impl<'a, R: BattleRules> ReadMetrics<'a, R> {
     ///
     /// Returns `None` if there's no such system counter or if it has another type.
     pub fn system_u64(&self, id: SystemMetricId) -> Option<u64> {
-        get_metric!(self.metrics.map, id, System, CounterU64)
+        get_metric!(self.map, id, System, CounterU64)
     }
 
     /// Returns the value of a `i64` system counter.
     ///
     /// Returns `None` if there's no such system counter or if it has another type.
     pub fn system_i64(&self, id: SystemMetricId) -> Option<i64> {
-        get_metric!(self.metrics.map, id, System, CounterI64)
+        get_metric!(self.map, id, System, CounterI64)
     }
 
     /// Returns the value of a `f64` system counter.
     ///
     /// Returns `None` if there's no such system counter or if it has another type.
     pub fn system_f64(&self, id: SystemMetricId) -> Option<f64> {
-        get_metric!(self.metrics.map, id, System, CounterF64)
+        get_metric!(self.map, id, System, CounterF64)
     }
 
     /// Returns the value of a `u64` user counter.
     ///
     /// Returns `None` if there's no such user counter or if it has another type.
     pub fn user_u64(&self, id: UserMetricId<R>) -> Option<u64> {
-        get_metric!(self.metrics.map, id, User, CounterU64)
+        get_metric!(self.map, id, User, CounterU64)
     }
 
     /// Returns the value of a `i64` user counter.
     ///
     /// Returns `None` if there's no such user counter or if it has another type.
     pub fn user_i64(&self, id: UserMetricId<R>) -> Option<i64> {
-        get_metric!(self.metrics.map, id, User, CounterI64)
+        get_metric!(self.map, id, User, CounterI64)
     }
 
     /// Returns the value of a `f64` user counter.
     ///
     /// Returns `None` if there's no such user counter or if it has another type.
     pub fn user_f64(&self, id: UserMetricId<R>) -> Option<f64> {
-        get_metric!(self.metrics.map, id, User, CounterF64)
+        get_metric!(self.map, id, User, CounterF64)
+    }
+
+    /// Returns the value of a `f64` user gauge.
+    ///
+    /// Returns `None` if there's no such user gauge or if it has another type.
+    pub fn user_gauge_f64(&self, id: UserMetricId<R>) -> Option<f64> {
+        get_metric!(self.map, id, User, GaugeF64)
+    }
+
+    /// Returns the user histogram with the given `id`.
+    ///
+    /// Returns `None` if there's no such histogram or if this handle
+    /// comes from a snapshot or a delta.
+    pub fn user_histogram(&self, id: UserMetricId<R>) -> Option<&'a Histogram> {
+        self.histograms.and_then(|histograms| histograms.get(&id))
+    }
+
+    /// Returns an iterator over all metrics.
+    pub fn iter(&self) -> impl Iterator<Item = (&'a MetricIdType<R>, &'a Metric)> {
+        self.map.iter()
+    }
+
+    /// Returns the number of round snapshots available.
+    ///
+    /// A snapshot of all metrics is taken automatically at the end of each round.
+    pub fn snapshots(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    /// Returns the value of all metrics at the end of the given round.
+    ///
+    /// Rounds are counted from one. Returns `None` if the round is not completed yet.
+    pub fn snapshot(&self, round: RoundsCount) -> Option<ReadMetrics<'a, R>> {
+        self.snapshot_map(round).map(|map| ReadMetrics {
+            map,
+            snapshots: &[],
+            series: None,
+            histograms: None,
+        })
+    }
+
+    /// Returns the values added to a `u64` user counter with `add_user_u64_for_turn`,
+    /// summed by turn and sorted by turn.
+    ///
+    /// Each value is paired with the number of turns completed when it was added.
+    /// Values added during the first turn are thus paired with zero.\
+    /// The iterator is empty if the counter has no series or if this handle
+    /// comes from a snapshot or a delta.
+    ///
+    /// # Examples
+    /// ```
+    /// use weasel::{battle_rules, rules::empty::*, Battle, BattleRules};
+    ///
+    /// battle_rules! {}
+    ///
+    /// let mut battle = Battle::builder(CustomRules::new()).build();
+    /// battle.metrics_mut().add_user_u64_for_turn(0, 5).unwrap();
+    /// battle.metrics_mut().add_user_u64_for_turn(0, 3).unwrap();
+    ///
+    /// let series: Vec<_> = battle.metrics().user_u64_series(0).collect();
+    /// assert_eq!(series, vec![(0, 8)]);
+    /// ```
+    pub fn user_u64_series(
+        &self,
+        id: UserMetricId<R>,
+    ) -> impl Iterator<Item = (TurnsCount, u64)> + 'a {
+        self.series
+            .and_then(|series| series.get(&id))
+            .into_iter()
+            .flat_map(|buckets| buckets.iter().map(|(turn, value)| (*turn, *value)))
+    }
+
+    /// Returns how much a `u64` user counter increased during each completed round,
+    /// as `(round, increase)` pairs. Rounds are counted from one.
+    ///
+    /// Increases are computed from the snapshots taken at the end of each round.
+    /// Rounds in which the counter didn't exist or had another type are skipped.
+    pub fn user_u64_round_series(
+        &self,
+        id: UserMetricId<R>,
+    ) -> impl Iterator<Item = (RoundsCount, u64)> + 'a {
+        let snapshots = self.snapshots;
+        (0..snapshots.len()).filter_map(move |index| {
+            let value = get_metric!(snapshots[index], id.clone(), User, CounterU64)?;
+            let previous = if index == 0 {
+                0
+            } else {
+                get_metric!(snapshots[index - 1], id.clone(), User, CounterU64).unwrap_or(0)
+            };
+            Some((index as RoundsCount + 1, value.saturating_sub(previous)))
+        })
+    }
+
+    /// Returns how much each metric changed from the end of `round_a`
+    /// to the end of `round_b`.
+    ///
+    /// A metric missing in one of the two snapshots counts as zero. Metrics whose type
+    /// changed between the two snapshots are not included.\
+    /// Returns `None` if any of the two rounds is not completed yet.
+    ///
+    /// # Examples
+    /// ```
+    /// use weasel::metric::system::TEAMS_CREATED;
+    /// use weasel::{
+    ///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateTeam,
+    ///     EndRound, EventTrigger, Server,
+    /// };
+    ///
+    /// battle_rules! {}
+    ///
+    /// let battle = Battle::builder(CustomRules::new()).build();
+    /// let mut server = Server::builder(battle).build();
+    ///
+    /// CreateTeam::trigger(&mut server, 1).fire().unwrap();
+    /// EndRound::trigger(&mut server).fire().unwrap();
+    /// CreateTeam::trigger(&mut server, 2).fire().unwrap();
+    /// CreateTeam::trigger(&mut server, 3).fire().unwrap();
+    /// EndRound::trigger(&mut server).fire().unwrap();
+    ///
+    /// let delta = server.battle().metrics().delta(1, 2).unwrap();
+    /// assert_eq!(delta.read().system_u64(TEAMS_CREATED), Some(2));
+    /// ```
+    pub fn delta(&self, round_a: RoundsCount, round_b: RoundsCount) -> Option<MetricsDelta<R>> {
+        let a = self.snapshot_map(round_a)?;
+        let b = self.snapshot_map(round_b)?;
+        let mut map = HashMap::new();
+        for (id, metric) in b {
+            let base = a.get(id).copied().unwrap_or_else(|| metric.zero());
+            if let Some(difference) = metric.difference(&base) {
+                map.insert(id.clone(), difference);
+            }
+        }
+        for (id, metric) in a.iter().filter(|(id, _)| !b.contains_key(id)) {
+            if let Some(difference) = metric.zero().difference(metric) {
+                map.insert(id.clone(), difference);
+            }
+        }
+        Some(MetricsDelta { map })
+    }
+
+    fn snapshot_map(&self, round: RoundsCount) -> Option<&'a MetricsMap<R>> {
+        if round == 0 {
+            return None;
+        }
+        self.snapshots.get(round as usize - 1)
+    }
+}
+
+/// Changes in the value of metrics between two rounds.
+///
+/// See [delta](struct.ReadMetrics.html#method.delta).
+pub struct MetricsDelta<R: BattleRules> {
+    map: MetricsMap<R>,
+}
+
+impl<R: BattleRules> MetricsDelta<R> {
+    /// Returns a handle to read the change of each metric.
+    pub fn read(&self) -> ReadMetrics<'_, R> {
+        ReadMetrics {
+            map: &self.map,
+            snapshots: &[],
+            series: None,
+            histograms: None,
+        }
     }
 }
 
@@ -144,9 +503,22 @@ impl<'a, R: BattleRules> WriteMetrics<'a, R> {
         self.metrics.map.remove(&MetricIdType::<R>::System(id));
     }
 
-    /// Removes an user metric.
+    /// Removes an user metric, together with its series and its histogram.
     pub fn remove_user(&mut self, id: UserMetricId<R>) {
-        self.metrics.map.remove(&MetricIdType::<R>::User(id));
+        self.metrics.series.remove(&id);
+        self.metrics.histograms.remove(&id);
+        self.metrics
+            .map
+            .remove(&MetricIdType::<R>::User(id.clone()));
+        self.changed(id);
+    }
+
+    /// Tracks the modification of the user metric with the given `id`, if tracking is enabled.
+    fn changed(&mut self, id: UserMetricId<R>) {
+        if let Some(changes) = &mut self.metrics.changes {
+            let value = self.metrics.map.get(&MetricIdType::<R>::User(id.clone()));
+            changes.push((id, value.copied()));
+        }
     }
 
     /// Adds `value` to the system metric with the given `id`.\
@@ -181,7 +553,56 @@ impl<'a, R: BattleRules> WriteMetrics<'a, R> {
     /// Creates the metric (initialized with `value`) if it doesn't exist.
     /// Returns an error if the metric exists, but its type is different.
     pub fn add_user_u64(&mut self, id: UserMetricId<R>, value: u64) -> WeaselResult<(), R> {
-        add_metric!(self.metrics.map, id, value, User, CounterU64)
+        add_metric!(self.metrics.map, id.clone(), value, User, CounterU64)?;
+        self.changed(id);
+        Ok(())
+    }
+
+    /// Sets the user gauge with the given `id` to `value`.
+    ///
+    /// Creates the gauge if it doesn't exist.
+    /// Returns an error if a metric with the same id exists, but its type is different.
+    pub fn set_user_gauge_f64(&mut self, id: UserMetricId<R>, value: f64) -> WeaselResult<(), R> {
+        let full_id = MetricIdType::<R>::User(id.clone());
+        match self.metrics.map.get_mut(&full_id) {
+            Some(Metric::GaugeF64(v)) => *v = value,
+            Some(_) => return Err(WeaselError::WrongMetricType(full_id)),
+            None => {
+                self.metrics.map.insert(full_id, Metric::GaugeF64(value));
+            }
+        }
+        self.changed(id);
+        Ok(())
+    }
+
+    /// Records `value` in the user histogram with the given `id`.
+    /// Creates the histogram if it doesn't exist.
+    ///
+    /// Histograms have their own ids, separated from those of counters and gauges.
+    /// They are not included in round snapshots.
+    pub fn add_user_histogram(&mut self, id: UserMetricId<R>, value: f64) {
+        self.metrics.histograms.entry(id).or_default().record(value);
+    }
+
+    /// Same as `add_user_u64`, but `value` is also added to the bucket of the current turn
+    /// in the counter's series.
+    ///
+    /// See [user_u64_series](struct.ReadMetrics.html#method.user_u64_series).
+    pub fn add_user_u64_for_turn(
+        &mut self,
+        id: UserMetricId<R>,
+        value: u64,
+    ) -> WeaselResult<(), R> {
+        add_metric!(self.metrics.map, id.clone(), value, User, CounterU64)?;
+        *self
+            .metrics
+            .series
+            .entry(id.clone())
+            .or_default()
+            .entry(self.metrics.turn)
+            .or_insert(0) += value;
+        self.changed(id);
+        Ok(())
     }
 
     /// Adds `value` to the user metric with the given `id`.\
@@ -189,7 +610,9 @@ impl<'a, R: BattleRules> WriteMetrics<'a, R> {
     /// Creates the metric (initialized with `value`) if it doesn't exist.
     /// Returns an error if the metric exists, but its type is different.
     pub fn add_user_i64(&mut self, id: UserMetricId<R>, value: i64) -> WeaselResult<(), R> {
-        add_metric!(self.metrics.map, id, value, User, CounterI64)
+        add_metric!(self.metrics.map, id.clone(), value, User, CounterI64)?;
+        self.changed(id);
+        Ok(())
     }
 
     /// Adds `value` to the user metric with the given `id`.\
@@ -197,7 +620,157 @@ impl<'a, R: BattleRules> WriteMetrics<'a, R> {
     /// Creates the metric (initialized with `value`) if it doesn't exist.
     /// Returns an error if the metric exists, but its type is different.
     pub fn add_user_f64(&mut self, id: UserMetricId<R>, value: f64) -> WeaselResult<(), R> {
-        add_metric!(self.metrics.map, id, value, User, CounterF64)
+        add_metric!(self.metrics.map, id.clone(), value, User, CounterF64)?;
+        self.changed(id);
+        Ok(())
+    }
+}
+
+/// Notification of a modification to an user metric, caused by the application of an event.
+///
+/// Servers forward these notifications to their client sinks when metric forwarding is enabled.
+/// See [forward_metrics](../server/struct.ServerBuilder.html#method.forward_metrics).
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct MetricChanged<R: BattleRules> {
+    event_id: EventId,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "UserMetricId<R>: Serialize",
+            deserialize = "UserMetricId<R>: Deserialize<'de>"
+        ))
+    )]
+    id: UserMetricId<R>,
+
+    value: Option<Metric>,
+}
+
+impl<R: BattleRules> MetricChanged<R> {
+    /// Returns the id of the event whose application modified the metric.
+    pub fn event_id(&self) -> EventId {
+        self.event_id
+    }
+
+    /// Returns the id of the modified user metric.
+    pub fn id(&self) -> &UserMetricId<R> {
+        &self.id
+    }
+
+    /// Returns the new value of the metric, or `None` if the metric was removed.
+    pub fn value(&self) -> Option<Metric> {
+        self.value
+    }
+}
+
+impl<R: BattleRules> Debug for MetricChanged<R> {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(
+            f,
+            "MetricChanged {{ event_id: {:?}, id: {:?}, value: {:?} }}",
+            self.event_id, self.id, self.value
+        )
+    }
+}
+
+impl<R: BattleRules> Clone for MetricChanged<R> {
+    fn clone(&self) -> Self {
+        Self {
+            event_id: self.event_id,
+            id: self.id.clone(),
+            value: self.value,
+        }
+    }
+}
+
+/// A distribution of values, such as the results of dice rolls.
+///
+/// All recorded values are kept, thus quantiles are exact.
+///
+/// # Examples
+/// ```
+/// use weasel::{battle_rules, rules::empty::*, Battle, BattleRules};
+///
+/// battle_rules! {}
+///
+/// let mut battle = Battle::builder(CustomRules::new()).build();
+/// for roll in &[4., 1., 3., 2.] {
+///     battle.metrics_mut().add_user_histogram(0, *roll);
+/// }
+///
+/// let metrics = battle.metrics();
+/// let histogram = metrics.user_histogram(0).unwrap();
+/// assert_eq!(histogram.count(), 4);
+/// assert_eq!(histogram.mean(), Some(2.5));
+/// assert_eq!(histogram.quantile(0.5), Some(2.5));
+/// assert_eq!(histogram.max(), Some(4.));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Histogram {
+    /// Recorded values, in ascending order.
+    values: Vec<f64>,
+    sum: f64,
+}
+
+impl Histogram {
+    /// Records a new value.
+    fn record(&mut self, value: f64) {
+        let index = self
+            .values
+            .partition_point(|v| v.total_cmp(&value) != Ordering::Greater);
+        self.values.insert(index, value);
+        self.sum += value;
+    }
+
+    /// Returns the number of recorded values.
+    pub fn count(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns the sum of all recorded values.
+    pub fn sum(&self) -> f64 {
+        self.sum
+    }
+
+    /// Returns the smallest recorded value.
+    pub fn min(&self) -> Option<f64> {
+        self.values.first().copied()
+    }
+
+    /// Returns the largest recorded value.
+    pub fn max(&self) -> Option<f64> {
+        self.values.last().copied()
+    }
+
+    /// Returns the arithmetic mean of all recorded values.
+    pub fn mean(&self) -> Option<f64> {
+        if self.values.is_empty() {
+            None
+        } else {
+            Some(self.sum / self.values.len() as f64)
+        }
+    }
+
+    /// Returns the value below which a fraction `q` of the recorded values fall,
+    /// interpolating linearly between the two nearest values.
+    ///
+    /// `q` is clamped between 0 and 1. Returns `None` if the histogram is empty.
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        if self.values.is_empty() {
+            return None;
+        }
+        let q = if q.is_nan() { 0. } else { q.clamp(0., 1.) };
+        let position = q * (self.values.len() - 1) as f64;
+        let lower = position.floor() as usize;
+        let upper = position.ceil() as usize;
+        let weight = position - lower as f64;
+        Some(self.values[lower] + (self.values[upper] - self.values[lower]) * weight)
+    }
+
+    /// Returns all recorded values, in ascending order.
+    pub fn values(&self) -> &[f64] {
+        &self.values
     }
 }
 
@@ -211,6 +784,8 @@ pub mod system {
     pub const OBJECTS_CREATED: SystemMetricId = 1;
     /// Number of teams created.
     pub const TEAMS_CREATED: SystemMetricId = 2;
+    /// Number of client events rejected by a server because they exceeded the maximum size.
+    pub const OVERSIZED_EVENTS_REJECTED: SystemMetricId = 3;
 }
 
 #[cfg(test)]
@@ -268,4 +843,129 @@ mod tests {
             Some(WeaselError::WrongMetricType(MetricId::User(0)))
         );
     }
+
+    #[test]
+    fn snapshots_and_delta() {
+        let mut server = server(CustomRules::new());
+        let mut writer = server.battle.metrics.write_handle();
+        assert_eq!(writer.add_user_u64(0, 10).err(), None);
+        assert_eq!(writer.add_user_i64(1, 3).err(), None);
+        server.battle.metrics.take_snapshot();
+        let mut writer = server.battle.metrics.write_handle();
+        assert_eq!(writer.add_user_u64(0, 5).err(), None);
+        writer.remove_user(1);
+        assert_eq!(writer.add_user_f64(2, 1.5).err(), None);
+        server.battle.metrics.take_snapshot();
+        let reader = server.battle.metrics.read_handle();
+        assert_eq!(reader.snapshots(), 2);
+        // Check snapshots.
+        assert!(reader.snapshot(0).is_none());
+        assert!(reader.snapshot(3).is_none());
+        assert_eq!(reader.snapshot(1).unwrap().user_u64(0), Some(10));
+        assert_eq!(reader.snapshot(2).unwrap().user_u64(0), Some(15));
+        // Check delta.
+        assert!(reader.delta(1, 3).is_none());
+        let delta = reader.delta(1, 2).unwrap();
+        let delta = delta.read();
+        assert_eq!(delta.user_u64(0), Some(5));
+        assert_eq!(delta.user_i64(1), Some(-3));
+        assert_eq!(delta.user_f64(2), Some(1.5));
+        // Unsigned counters saturate at zero.
+        let delta = reader.delta(2, 1).unwrap();
+        assert_eq!(delta.read().user_u64(0), Some(0));
+    }
+
+    #[test]
+    fn series() {
+        let mut server = server(CustomRules::new());
+        let mut writer = server.battle.metrics.write_handle();
+        assert_eq!(writer.add_user_u64_for_turn(0, 2).err(), None);
+        assert_eq!(writer.add_user_u64(0, 10).err(), None);
+        server.battle.metrics.set_turn(2);
+        let mut writer = server.battle.metrics.write_handle();
+        assert_eq!(writer.add_user_u64_for_turn(0, 3).err(), None);
+        assert_eq!(writer.add_user_u64_for_turn(0, 4).err(), None);
+        server.battle.metrics.take_snapshot();
+        server.battle.metrics.set_turn(3);
+        let mut writer = server.battle.metrics.write_handle();
+        assert_eq!(writer.add_user_u64_for_turn(0, 1).err(), None);
+        // Wrong types are not added to the series.
+        assert_eq!(writer.add_user_u64_for_turn(1, 1).err(), None);
+        assert!(writer.add_user_f64(1, 1.).is_err());
+        assert_eq!(writer.add_user_f64(2, 1.).err(), None);
+        assert!(writer.add_user_u64_for_turn(2, 1).is_err());
+        server.battle.metrics.take_snapshot();
+        // Check the series.
+        let reader = server.battle.metrics.read_handle();
+        assert_eq!(reader.user_u64(0), Some(20));
+        let series: Vec<_> = reader.user_u64_series(0).collect();
+        assert_eq!(series, vec![(0, 2), (2, 7), (3, 1)]);
+        assert_eq!(reader.user_u64_series(2).count(), 0);
+        assert_eq!(reader.snapshot(1).unwrap().user_u64_series(0).count(), 0);
+        // Check the rounds.
+        let rounds: Vec<_> = reader.user_u64_round_series(0).collect();
+        assert_eq!(rounds, vec![(1, 19), (2, 1)]);
+        let rounds: Vec<_> = reader.user_u64_round_series(1).collect();
+        assert_eq!(rounds, vec![(2, 1)]);
+        // Removing a metric removes its series.
+        server.battle.metrics.write_handle().remove_user(0);
+        let reader = server.battle.metrics.read_handle();
+        assert_eq!(reader.user_u64_series(0).count(), 0);
+    }
+
+    #[test]
+    fn gauges() {
+        let mut server = server(CustomRules::new());
+        let mut writer = server.battle.metrics.write_handle();
+        assert_eq!(writer.set_user_gauge_f64(0, 2.5).err(), None);
+        assert_eq!(writer.set_user_gauge_f64(0, 1.5).err(), None);
+        assert_eq!(writer.add_user_u64(1, 1).err(), None);
+        assert_eq!(
+            writer.set_user_gauge_f64(1, 1.).err(),
+            Some(WeaselError::WrongMetricType(MetricId::User(1)))
+        );
+        server.battle.metrics.take_snapshot();
+        let mut writer = server.battle.metrics.write_handle();
+        assert_eq!(writer.set_user_gauge_f64(0, 4.).err(), None);
+        server.battle.metrics.take_snapshot();
+        let reader = server.battle.metrics.read_handle();
+        assert_eq!(reader.user_gauge_f64(0), Some(4.));
+        assert_eq!(reader.user_f64(0), None);
+        assert_eq!(reader.user_gauge_f64(1), None);
+        assert_eq!(reader.snapshot(1).unwrap().user_gauge_f64(0), Some(1.5));
+        let delta = reader.delta(1, 2).unwrap();
+        assert_eq!(delta.read().user_gauge_f64(0), Some(2.5));
+    }
+
+    #[test]
+    fn histograms() {
+        let mut server = server(CustomRules::new());
+        let mut writer = server.battle.metrics.write_handle();
+        for value in &[5., 1., 4., 2., 3.] {
+            writer.add_user_histogram(0, *value);
+        }
+        // Histograms don't clash with counters.
+        assert_eq!(writer.add_user_u64(0, 1).err(), None);
+        server.battle.metrics.take_snapshot();
+        let reader = server.battle.metrics.read_handle();
+        assert!(reader.user_histogram(1).is_none());
+        assert!(reader.snapshot(1).unwrap().user_histogram(0).is_none());
+        let histogram = reader.user_histogram(0).unwrap();
+        assert_eq!(histogram.values(), &[1., 2., 3., 4., 5.]);
+        assert_eq!(histogram.count(), 5);
+        assert_eq!(histogram.sum(), 15.);
+        assert_eq!(histogram.mean(), Some(3.));
+        assert_eq!(histogram.min(), Some(1.));
+        assert_eq!(histogram.max(), Some(5.));
+        assert_eq!(histogram.quantile(0.), Some(1.));
+        assert_eq!(histogram.quantile(0.5), Some(3.));
+        assert_eq!(histogram.quantile(0.875), Some(4.5));
+        assert_eq!(histogram.quantile(2.), Some(5.));
+        assert_eq!(Histogram::default().quantile(0.5), None);
+        assert_eq!(Histogram::default().mean(), None);
+        // Removing a metric removes its histogram.
+        server.battle.metrics.write_handle().remove_user(0);
+        let reader = server.battle.metrics.read_handle();
+        assert!(reader.user_histogram(0).is_none());
+    }
 }
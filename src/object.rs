@@ -6,8 +6,10 @@ use crate::entity::{Entity, EntityId, Transmutation};
 use crate::error::{WeaselError, WeaselResult};
 use crate::event::{Event, EventKind, EventProcessor, EventQueue, EventTrigger};
 use crate::metric::system::OBJECTS_CREATED;
+use crate::player::PlayerId;
 use crate::space::{Position, PositionClaim};
 use crate::status::{AppliedStatus, StatusId};
+use crate::team::TeamId;
 use crate::util::{collect_from_iter, Id};
 use indexmap::IndexMap;
 #[cfg(feature = "serialization")]
@@ -30,12 +32,120 @@ type Statuses<R> =
 ///
 /// Objects possess a position and a set of statistics, but they can't start a turn
 /// nor activate abilities. They can be target of status effects.\
-/// Objects aren't part of any team.
+/// Objects aren't part of any team, but they can be owned by a team or by a player.
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub struct Object<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "EntityId<R>: Serialize",
+            deserialize = "EntityId<R>: Deserialize<'de>"
+        ))
+    )]
     id: EntityId<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Position<R>: Serialize",
+            deserialize = "Position<R>: Deserialize<'de>"
+        ))
+    )]
     position: Position<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(
+            bound(
+                serialize = "Statistic<R>: Serialize",
+                deserialize = "Statistic<R>: Deserialize<'de>"
+            ),
+            serialize_with = "crate::serde::serialize_pairs",
+            deserialize_with = "crate::serde::deserialize_pairs"
+        )
+    )]
     statistics: Statistics<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(
+            bound(
+                serialize = "AppliedStatus<R>: Serialize",
+                deserialize = "AppliedStatus<R>: Deserialize<'de>"
+            ),
+            serialize_with = "crate::serde::serialize_pairs",
+            deserialize_with = "crate::serde::deserialize_pairs"
+        )
+    )]
     statuses: Statuses<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Option<ObjectOwner<R>>: Serialize",
+            deserialize = "Option<ObjectOwner<R>>: Deserialize<'de>"
+        ))
+    )]
+    owner: Option<ObjectOwner<R>>,
+}
+
+impl<R: BattleRules> Object<R> {
+    /// Returns the owner of this object.
+    ///
+    /// Players can fire events on behalf of an object only if they own it, directly or
+    /// through their rights to the owning team. Objects without an owner are controlled
+    /// only by the server.
+    pub fn owner(&self) -> Option<&ObjectOwner<R>> {
+        self.owner.as_ref()
+    }
+
+    pub(crate) fn set_owner(&mut self, owner: Option<ObjectOwner<R>>) {
+        self.owner = owner;
+    }
+}
+
+/// The owner of an object.
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum ObjectOwner<R: BattleRules> {
+    /// The object is controlled by the players with rights to this team.
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "TeamId<R>: Serialize",
+            deserialize = "TeamId<R>: Deserialize<'de>"
+        ))
+    )]
+    Team(TeamId<R>),
+    /// The object is controlled exclusively by this player.
+    Player(PlayerId),
+}
+
+impl<R: BattleRules> Debug for ObjectOwner<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Team(id) => write!(f, "ObjectOwner::Team {{ {:?} }}", id),
+            Self::Player(id) => write!(f, "ObjectOwner::Player {{ {:?} }}", id),
+        }
+    }
+}
+
+impl<R: BattleRules> Clone for ObjectOwner<R> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Team(id) => Self::Team(id.clone()),
+            Self::Player(id) => Self::Player(*id),
+        }
+    }
+}
+
+impl<R: BattleRules> PartialEq for ObjectOwner<R> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Team(a), Self::Team(b)) => a == b,
+            (Self::Player(a), Self::Player(b)) => a == b,
+            _ => false,
+        }
+    }
 }
 
 impl<R: BattleRules> Id for Object<R> {
@@ -224,43 +334,58 @@ impl<R: BattleRules + 'static> Event<R> for CreateObject<R> {
             return Err(WeaselError::DuplicatedObject(self.id.clone()));
         }
         // Check position.
-        battle
-            .space()
-            .check_move(
+        watch!(
+            battle.verify_watcher(self.kind()),
+            "SpaceRules::check_move",
+            battle.space().check_move(
                 PositionClaim::Spawn(&EntityId::Object(self.id.clone())),
                 &self.position,
             )
-            .map_err(|err| WeaselError::PositionError(None, self.position.clone(), Box::new(err)))
+        )
+        .map_err(|err| WeaselError::PositionError(None, self.position.clone(), Box::new(err)))
     }
 
     fn apply(&self, battle: &mut Battle<R>, event_queue: &mut Option<EventQueue<R>>) {
+        let watcher = battle.apply_watcher();
         // Statistics' generation is influenced by the given statistics_seed, if present.
-        let it = battle.rules.character_rules().generate_statistics(
-            &self.statistics_seed,
-            &mut battle.entropy,
-            &mut battle.metrics.write_handle(),
+        let statistics = watch!(
+            watcher,
+            "CharacterRules::generate_statistics",
+            collect_from_iter(battle.rules.character_rules().generate_statistics(
+                &self.statistics_seed,
+                &mut battle.entropy,
+                &mut battle.metrics.write_handle(),
+            ))
         );
-        let statistics = collect_from_iter(it);
         // Create the object.
         let object = Object {
             id: EntityId::Object(self.id.clone()),
             position: self.position.clone(),
             statistics,
             statuses: IndexMap::new(),
+            owner: None,
         };
         // Take the position.
-        battle.state.space.move_entity(
-            PositionClaim::Spawn(&EntityId::Object(self.id.clone())),
-            Some(&self.position),
-            &mut battle.metrics.write_handle(),
+        watch!(
+            watcher,
+            "SpaceRules::move_entity",
+            battle.state.space.move_entity(
+                PositionClaim::Spawn(&EntityId::Object(self.id.clone())),
+                Some(&self.position),
+                &mut battle.metrics.write_handle(),
+            )
         );
         // Invoke the character's rules callback.
-        battle.rules.character_rules().on_character_added(
-            &battle.state,
-            &object,
-            event_queue,
-            &mut battle.entropy,
-            &mut battle.metrics.write_handle(),
+        watch!(
+            watcher,
+            "CharacterRules::on_character_added",
+            battle.rules.character_rules().on_character_added(
+                &battle.state,
+                &object,
+                event_queue,
+                &mut battle.entropy,
+                &mut battle.metrics.write_handle(),
+            )
         );
         // Add the object to the entities.
         battle.state.entities.add_object(object);
@@ -294,7 +419,7 @@ where
     processor: &'a mut P,
     id: ObjectId<R>,
     position: Position<R>,
-    statistics_seed: Option<StatisticsSeed<R>>,
+    pub(crate) statistics_seed: Option<StatisticsSeed<R>>,
 }
 
 impl<'a, R, P> CreateObjectTrigger<'a, R, P>
@@ -404,26 +529,37 @@ impl<R: BattleRules + 'static> Event<R> for RemoveObject<R> {
     }
 
     fn apply(&self, battle: &mut Battle<R>, event_queue: &mut Option<EventQueue<R>>) {
+        let watcher = battle.apply_watcher();
         // Remove the object.
         let object = battle
             .state
             .entities
             .remove_object(&self.id)
             .unwrap_or_else(|err| panic!("constraint violated: {:?}", err));
+        battle.state.visibility.remove_entity(object.entity_id());
+        battle.rights_mut().forget_entity(object.entity_id());
         // Invoke the character's rules callback.
-        battle.rules.character_rules().on_character_transmuted(
-            &battle.state,
-            &object,
-            Transmutation::REMOVAL,
-            event_queue,
-            &mut battle.entropy,
-            &mut battle.metrics.write_handle(),
+        watch!(
+            watcher,
+            "CharacterRules::on_character_transmuted",
+            battle.rules.character_rules().on_character_transmuted(
+                &battle.state,
+                &object,
+                Transmutation::REMOVAL,
+                event_queue,
+                &mut battle.entropy,
+                &mut battle.metrics.write_handle(),
+            )
         );
         // Free the position.
-        battle.state.space.move_entity(
-            PositionClaim::Movement(&object as &dyn Entity<R>),
-            None,
-            &mut battle.metrics.write_handle(),
+        watch!(
+            watcher,
+            "SpaceRules::move_entity",
+            battle.state.space.move_entity(
+                PositionClaim::Movement(&object as &dyn Entity<R>),
+                None,
+                &mut battle.metrics.write_handle(),
+            )
         );
     }
 
@@ -467,6 +603,186 @@ where
     }
 }
 
+/// Event to give a team or a player control over an object.
+///
+/// Once an object has an owner, clients can fire events on its behalf as long as they
+/// own the object. Without an owner, only the server can act on the object.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateObject,
+///     EventTrigger, ObjectOwner, Server, TransferObjectOwnership,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// let object_id = 1;
+/// CreateObject::trigger(&mut server, object_id, ())
+///     .fire()
+///     .unwrap();
+///
+/// let player_id = 5;
+/// TransferObjectOwnership::trigger(&mut server, object_id)
+///     .player(player_id)
+///     .fire()
+///     .unwrap();
+/// let object = server.battle().entities().object(&object_id).unwrap();
+/// assert_eq!(object.owner(), Some(&ObjectOwner::Player(player_id)));
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct TransferObjectOwnership<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "ObjectId<R>: Serialize",
+            deserialize = "ObjectId<R>: Deserialize<'de>"
+        ))
+    )]
+    object_id: ObjectId<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Option<ObjectOwner<R>>: Serialize",
+            deserialize = "Option<ObjectOwner<R>>: Deserialize<'de>"
+        ))
+    )]
+    owner: Option<ObjectOwner<R>>,
+}
+
+impl<R: BattleRules> TransferObjectOwnership<R> {
+    /// Returns a trigger for this event.
+    pub fn trigger<P: EventProcessor<R>>(
+        processor: &mut P,
+        object_id: ObjectId<R>,
+    ) -> TransferObjectOwnershipTrigger<'_, R, P> {
+        TransferObjectOwnershipTrigger {
+            processor,
+            object_id,
+            owner: None,
+        }
+    }
+
+    /// Returns the id of the object to be transferred.
+    pub fn object_id(&self) -> &ObjectId<R> {
+        &self.object_id
+    }
+
+    /// Returns the new owner of the object.
+    pub fn owner(&self) -> Option<&ObjectOwner<R>> {
+        self.owner.as_ref()
+    }
+}
+
+impl<R: BattleRules> Debug for TransferObjectOwnership<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "TransferObjectOwnership {{ object_id: {:?}, owner: {:?} }}",
+            self.object_id, self.owner
+        )
+    }
+}
+
+impl<R: BattleRules> Clone for TransferObjectOwnership<R> {
+    fn clone(&self) -> Self {
+        Self {
+            object_id: self.object_id.clone(),
+            owner: self.owner.clone(),
+        }
+    }
+}
+
+impl<R: BattleRules + 'static> Event<R> for TransferObjectOwnership<R> {
+    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
+        // Verify if the object exists.
+        if battle.entities().object(&self.object_id).is_none() {
+            return Err(WeaselError::ObjectNotFound(self.object_id.clone()));
+        }
+        // Verify if the owning team exists.
+        if let Some(ObjectOwner::Team(team_id)) = &self.owner {
+            if battle.entities().team(team_id).is_none() {
+                return Err(WeaselError::TeamNotFound(team_id.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    fn apply(&self, battle: &mut Battle<R>, _event_queue: &mut Option<EventQueue<R>>) {
+        let object = battle
+            .state
+            .entities
+            .object_mut(&self.object_id)
+            .unwrap_or_else(|| {
+                panic!("constraint violated: object {:?} not found", self.object_id)
+            });
+        object.set_owner(self.owner.clone());
+    }
+
+    fn kind(&self) -> EventKind {
+        EventKind::TransferObjectOwnership
+    }
+
+    fn box_clone(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Trigger to build and fire a `TransferObjectOwnership` event.
+pub struct TransferObjectOwnershipTrigger<'a, R, P>
+where
+    R: BattleRules,
+    P: EventProcessor<R>,
+{
+    processor: &'a mut P,
+    object_id: ObjectId<R>,
+    owner: Option<ObjectOwner<R>>,
+}
+
+impl<'a, R, P> TransferObjectOwnershipTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    /// Sets the team that will own the object.
+    pub fn team(&'a mut self, team_id: TeamId<R>) -> &'a mut Self {
+        self.owner = Some(ObjectOwner::Team(team_id));
+        self
+    }
+
+    /// Sets the player who will own the object.
+    pub fn player(&'a mut self, player_id: PlayerId) -> &'a mut Self {
+        self.owner = Some(ObjectOwner::Player(player_id));
+        self
+    }
+}
+
+impl<'a, R, P> EventTrigger<'a, R, P> for TransferObjectOwnershipTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    fn processor(&'a mut self) -> &'a mut P {
+        self.processor
+    }
+
+    /// Returns a `TransferObjectOwnership` event.
+    fn event(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(TransferObjectOwnership {
+            object_id: self.object_id.clone(),
+            owner: self.owner.clone(),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,8 +1,16 @@
 //! Player-oriented features.
 
-use crate::battle::BattleRules;
+use crate::battle::{Battle, BattleRules, BattleState};
+use crate::entity::{Entities, EntityId};
 use crate::error::{WeaselError, WeaselResult};
+use crate::event::{Event, EventKind, EventProcessor, EventQueue, EventTrigger};
 use crate::team::TeamId;
+use indexmap::IndexMap;
+#[cfg(feature = "serialization")]
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::fmt::{Debug, Formatter, Result};
+use std::marker::PhantomData;
 
 /// Type to uniquely identify players.
 ///
@@ -14,13 +22,39 @@ use crate::team::TeamId;
 pub type PlayerId = u64;
 
 /// Manages players' rights to initiate events on behalf of a given team.
+///
+/// Players can also be granted rights to control single entities and be restricted
+/// to fire only some kinds of events.
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub(crate) struct Rights<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Vec<(PlayerId, Vec<TeamId<R>>)>: Serialize",
+            deserialize = "Vec<(PlayerId, Vec<TeamId<R>>)>: Deserialize<'de>"
+        ))
+    )]
     data: Vec<(PlayerId, Vec<TeamId<R>>)>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Vec<(PlayerId, Vec<EntityId<R>>)>: Serialize",
+            deserialize = "Vec<(PlayerId, Vec<EntityId<R>>)>: Deserialize<'de>"
+        ))
+    )]
+    entities: Vec<(PlayerId, Vec<EntityId<R>>)>,
+
+    kinds: Vec<(PlayerId, Vec<EventKind>)>,
 }
 
 impl<R: BattleRules> Rights<R> {
     pub(crate) fn new() -> Self {
-        Self { data: Vec::new() }
+        Self {
+            data: Vec::new(),
+            entities: Vec::new(),
+            kinds: Vec::new(),
+        }
     }
 
     /// Removes all players without any rights.
@@ -53,6 +87,8 @@ impl<R: BattleRules> Rights<R> {
     /// Removes all stored rights.
     fn clear(&mut self) {
         self.data.clear();
+        self.entities.clear();
+        self.kinds.clear();
     }
 
     /// Returns an iterator over all players' rights.
@@ -79,15 +115,123 @@ impl<R: BattleRules> Rights<R> {
         self.cleanup_players();
     }
 
+    /// Replace all occurrences of a team in all players' rights with `new_team`.
+    fn rename_team(&mut self, team: &TeamId<R>, new_team: &TeamId<R>) {
+        for (_, rights) in &mut self.data {
+            for e in rights.iter_mut().filter(|e| *e == team) {
+                *e = new_team.clone();
+            }
+        }
+    }
+
     /// Remove all rights of a player.
     fn remove_player(&mut self, player: PlayerId) {
         let index = self.data.iter().position(|(e, _)| *e == player);
         if let Some(index) = index {
             self.data.remove(index);
         }
+        self.entities.retain(|(e, _)| *e != player);
+        self.kinds.retain(|(e, _)| *e != player);
+    }
+
+    /// Add rights for `entity` to `player`.
+    fn add_entity(&mut self, player: PlayerId, entity: &EntityId<R>) {
+        grant(&mut self.entities, player, entity);
+    }
+
+    /// Remove rights for `entity` to `player`.
+    fn remove_entity(&mut self, player: PlayerId, entity: &EntityId<R>) {
+        revoke(&mut self.entities, player, entity);
+    }
+
+    /// Returns `true` if `player` has been granted rights for `entity`.
+    fn check_entity(&self, player: PlayerId, entity: &EntityId<R>) -> bool {
+        matches!(granted(&self.entities, player), Some(entities) if entities.contains(entity))
+    }
+
+    /// Returns an iterator over all players' rights to single entities.
+    fn get_entities(&self) -> impl Iterator<Item = (PlayerId, &[EntityId<R>])> {
+        self.entities
+            .iter()
+            .map(|(player, vec)| (*player, &vec[..]))
+    }
+
+    /// Remove all occurrences of an entity from all players' rights.
+    fn forget_entity(&mut self, entity: &EntityId<R>) {
+        for (_, entities) in &mut self.entities {
+            entities.retain(|e| e != entity);
+        }
+        self.entities.retain(|(_, entities)| !entities.is_empty());
+    }
+
+    /// Replace all occurrences of an entity in all players' rights with `new_entity`.
+    fn reidentify_entity(&mut self, entity: &EntityId<R>, new_entity: &EntityId<R>) {
+        for (_, entities) in &mut self.entities {
+            for e in entities.iter_mut().filter(|e| *e == entity) {
+                *e = new_entity.clone();
+            }
+        }
+    }
+
+    /// Allow `player` to fire events of the given kind.
+    fn add_kind(&mut self, player: PlayerId, kind: EventKind) {
+        grant(&mut self.kinds, player, &kind);
+    }
+
+    /// Disallow `player` to fire events of the given kind.
+    /// The player stays restricted even if no allowed kind is left.
+    fn remove_kind(&mut self, player: PlayerId, kind: EventKind) {
+        if let Some((_, kinds)) = self.kinds.iter_mut().find(|(e, _)| *e == player) {
+            kinds.retain(|e| *e != kind);
+        }
+    }
+
+    /// Lifts all restrictions on the kinds of events that `player` can fire.
+    fn clear_kinds(&mut self, player: PlayerId) {
+        self.kinds.retain(|(e, _)| *e != player);
+    }
+
+    /// Returns `true` if `player` is allowed to fire events of the given kind.
+    /// Players that were never restricted can fire events of all kinds.
+    fn check_kind(&self, player: PlayerId, kind: EventKind) -> bool {
+        match granted(&self.kinds, player) {
+            Some(kinds) => kinds.contains(&kind),
+            None => true,
+        }
+    }
+
+    /// Returns an iterator over the kinds of events that each player is allowed to fire.
+    fn get_kinds(&self) -> impl Iterator<Item = (PlayerId, &[EventKind])> {
+        self.kinds.iter().map(|(player, vec)| (*player, &vec[..]))
+    }
+}
+
+/// Adds `item` to the rights of `player`.
+fn grant<T: PartialEq + Clone>(data: &mut Vec<(PlayerId, Vec<T>)>, player: PlayerId, item: &T) {
+    if let Some((_, rights)) = data.iter_mut().find(|(e, _)| *e == player) {
+        if !rights.contains(item) {
+            rights.push(item.clone());
+        }
+    } else {
+        data.push((player, vec![item.clone()]));
     }
 }
 
+/// Removes `item` from the rights of `player`, forgetting the player if it has no rights left.
+fn revoke<T: PartialEq>(data: &mut Vec<(PlayerId, Vec<T>)>, player: PlayerId, item: &T) {
+    if let Some((_, rights)) = data.iter_mut().find(|(e, _)| *e == player) {
+        rights.retain(|e| e != item);
+    }
+    data.retain(|(_, rights)| !rights.is_empty());
+}
+
+/// Returns the rights of `player`, if any.
+fn granted<T>(data: &[(PlayerId, Vec<T>)], player: PlayerId) -> Option<&[T]> {
+    data.iter()
+        .find(|(e, _)| *e == player)
+        .map(|(_, rights)| &rights[..])
+}
+
 /// A structure to access player's rights.
 /// Rights are used to control which players can act on behalf of what teams.
 pub struct RightsHandle<'a, R>
@@ -114,10 +258,37 @@ where
     pub fn check(&self, player: PlayerId, team: &TeamId<R>) -> bool {
         self.rights.check(player, team)
     }
+
+    /// Returns an iterator over all players' rights to control single entities.
+    pub fn get_entities(&self) -> impl Iterator<Item = (PlayerId, &[EntityId<R>])> {
+        self.rights.get_entities()
+    }
+
+    /// Returns `true` if `player` has been granted rights to control the single
+    /// entity `entity`.
+    ///
+    /// Rights to control the entity's team are not taken into account.
+    pub fn check_entity(&self, player: PlayerId, entity: &EntityId<R>) -> bool {
+        self.rights.check_entity(player, entity)
+    }
+
+    /// Returns an iterator over the kinds of events that each player is restricted to.
+    pub fn get_kinds(&self) -> impl Iterator<Item = (PlayerId, &[EventKind])> {
+        self.rights.get_kinds()
+    }
+
+    /// Returns `true` if `player` is allowed to fire events of the given kind.
+    pub fn check_kind(&self, player: PlayerId, kind: EventKind) -> bool {
+        self.rights.check_kind(player, kind)
+    }
 }
 
 /// A structure to access and manipulate player's rights.
 /// Rights are used to control which players can act on behalf of what teams.
+///
+/// Rights can also be granted for single entities. Moreover, players can be restricted
+/// to fire only some kinds of events: once a player is allowed at least one kind of event,
+/// all other kinds are forbidden to them.
 pub struct RightsHandleMut<'a, R, I>
 where
     R: BattleRules,
@@ -125,6 +296,7 @@ where
 {
     rights: &'a mut Rights<R>,
     teams: I,
+    entities: &'a Entities<R>,
 }
 
 impl<'a, R, I> RightsHandleMut<'a, R, I>
@@ -132,8 +304,12 @@ where
     R: BattleRules,
     I: Iterator<Item = &'a TeamId<R>>,
 {
-    pub(crate) fn new(rights: &'a mut Rights<R>, teams: I) -> Self {
-        Self { rights, teams }
+    pub(crate) fn new(rights: &'a mut Rights<R>, teams: I, entities: &'a Entities<R>) -> Self {
+        Self {
+            rights,
+            teams,
+            entities,
+        }
     }
 
     /// Add rights to control the team with the given id to `player`. The team must exist.
@@ -160,6 +336,11 @@ where
         self.rights.remove_team(team);
     }
 
+    /// Transfer all players' rights towards a team to `new_team`.
+    pub fn rename_team(&mut self, team: &TeamId<R>, new_team: &TeamId<R>) {
+        self.rights.rename_team(team, new_team);
+    }
+
     /// Remove all rights of a player.
     pub fn remove_player(&mut self, player: PlayerId) {
         self.rights.remove_player(player);
@@ -174,6 +355,558 @@ where
     pub fn check(&self, player: PlayerId, team: &TeamId<R>) -> bool {
         self.rights.check(player, team)
     }
+
+    /// Add rights to control the entity with the given id to `player`. The entity must exist.
+    ///
+    /// The entity can be controlled by the player even if it's owned by someone else.
+    pub fn add_entity(&mut self, player: PlayerId, entity: &EntityId<R>) -> WeaselResult<(), R> {
+        if self.entities.entity(entity).is_none() {
+            return Err(WeaselError::EntityNotFound(entity.clone()));
+        }
+        self.rights.add_entity(player, entity);
+        Ok(())
+    }
+
+    /// Remove player rights to control the entity with the given id.
+    pub fn remove_entity(&mut self, player: PlayerId, entity: &EntityId<R>) {
+        self.rights.remove_entity(player, entity);
+    }
+
+    /// Remove all occurrences of an entity from all players' rights.
+    pub fn forget_entity(&mut self, entity: &EntityId<R>) {
+        self.rights.forget_entity(entity);
+    }
+
+    /// Transfer all players' rights towards an entity to `new_entity`.
+    pub fn reidentify_entity(&mut self, entity: &EntityId<R>, new_entity: &EntityId<R>) {
+        self.rights.reidentify_entity(entity, new_entity);
+    }
+
+    /// Allow `player` to fire events of the given kind.
+    ///
+    /// From now on, the player can fire only events of the allowed kinds.
+    pub fn add_kind(&mut self, player: PlayerId, kind: EventKind) {
+        self.rights.add_kind(player, kind);
+    }
+
+    /// Disallow `player` to fire events of the given kind.
+    ///
+    /// The player can still fire only events of the remaining allowed kinds.
+    /// Once all kinds are removed, the player can't fire any event.
+    /// It has no effect on players not restricted to some kinds of events.
+    pub fn remove_kind(&mut self, player: PlayerId, kind: EventKind) {
+        self.rights.remove_kind(player, kind);
+    }
+
+    /// Lifts all restrictions on the kinds of events that `player` can fire.
+    pub fn clear_kinds(&mut self, player: PlayerId) {
+        self.rights.clear_kinds(player);
+    }
+
+    /// Returns an iterator over all players' rights to control single entities.
+    pub fn get_entities(&self) -> impl Iterator<Item = (PlayerId, &[EntityId<R>])> {
+        self.rights.get_entities()
+    }
+
+    /// Returns `true` if `player` has been granted rights to control the single
+    /// entity `entity`.
+    ///
+    /// Rights to control the entity's team are not taken into account.
+    pub fn check_entity(&self, player: PlayerId, entity: &EntityId<R>) -> bool {
+        self.rights.check_entity(player, entity)
+    }
+
+    /// Returns an iterator over the kinds of events that each player is restricted to.
+    pub fn get_kinds(&self) -> impl Iterator<Item = (PlayerId, &[EventKind])> {
+        self.rights.get_kinds()
+    }
+
+    /// Returns `true` if `player` is allowed to fire events of the given kind.
+    pub fn check_kind(&self, player: PlayerId, kind: EventKind) -> bool {
+        self.rights.check_kind(player, kind)
+    }
+}
+
+/// Type to represent the rating of a player, for instance an Elo score.
+pub type Rating<R> = <<R as BattleRules>::PR as PlayerRules<R>>::Rating;
+
+/// Type to store additional rules defined data for each player.
+pub type PlayerData<R> = <<R as BattleRules>::PR as PlayerRules<R>>::PlayerData;
+
+/// Rules to define the data attached to the players registered in a battle.
+///
+/// Players are registered with `RegisterPlayer` and removed with `RemovePlayer`.
+/// Their data is part of the verified state of the battle, thus it is replicated to
+/// all clients and it is recorded in the history.
+pub trait PlayerRules<R: BattleRules> {
+    #[cfg(not(feature = "serialization"))]
+    /// See [Rating](type.Rating.html).
+    type Rating: Clone + Debug + Send + Default;
+    #[cfg(feature = "serialization")]
+    /// See [Rating](type.Rating.html).
+    type Rating: Clone + Debug + Send + Default + Serialize + for<'a> Deserialize<'a>;
+
+    #[cfg(not(feature = "serialization"))]
+    /// See [PlayerData](type.PlayerData.html).
+    type PlayerData: Clone + Debug + Send + Default;
+    #[cfg(feature = "serialization")]
+    /// See [PlayerData](type.PlayerData.html).
+    type PlayerData: Clone + Debug + Send + Default + Serialize + for<'a> Deserialize<'a>;
+
+    /// Returns the names of the optional features enabled in these player rules.
+    /// They are reported by `Battle::describe`.
+    ///
+    /// The provided implementation returns no features.
+    fn features(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// Checks if the registration of a new player is allowed.
+    ///
+    /// The provided implementation accepts any new player.
+    fn allow_new_player(
+        &self,
+        _state: &BattleState<R>,
+        _player: &Player<R>,
+    ) -> WeaselResult<(), R> {
+        Ok(())
+    }
+}
+
+/// A player registered in a battle.
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Player<R: BattleRules> {
+    id: PlayerId,
+
+    name: String,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Rating<R>: Serialize",
+            deserialize = "Rating<R>: Deserialize<'de>"
+        ))
+    )]
+    rating: Rating<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "PlayerData<R>: Serialize",
+            deserialize = "PlayerData<R>: Deserialize<'de>"
+        ))
+    )]
+    data: PlayerData<R>,
+}
+
+impl<R: BattleRules> Player<R> {
+    /// Returns the id of this player.
+    pub fn id(&self) -> PlayerId {
+        self.id
+    }
+
+    /// Returns the name of this player.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the rating of this player.
+    pub fn rating(&self) -> &Rating<R> {
+        &self.rating
+    }
+
+    /// Returns the rules defined data of this player.
+    pub fn data(&self) -> &PlayerData<R> {
+        &self.data
+    }
+}
+
+impl<R: BattleRules> Debug for Player<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "Player {{ id: {:?}, name: {:?}, rating: {:?}, data: {:?} }}",
+            self.id, self.name, self.rating, self.data
+        )
+    }
+}
+
+impl<R: BattleRules> Clone for Player<R> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            name: self.name.clone(),
+            rating: self.rating.clone(),
+            data: self.data.clone(),
+        }
+    }
+}
+
+/// Registry of all players taking part in a battle.
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Players<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "IndexMap<PlayerId, Player<R>>: Serialize",
+            deserialize = "IndexMap<PlayerId, Player<R>>: Deserialize<'de>"
+        ))
+    )]
+    players: IndexMap<PlayerId, Player<R>>,
+}
+
+impl<R: BattleRules> Players<R> {
+    pub(crate) fn new() -> Self {
+        Self {
+            players: IndexMap::new(),
+        }
+    }
+
+    /// Returns the player with the given id.
+    pub fn player(&self, id: PlayerId) -> Option<&Player<R>> {
+        self.players.get(&id)
+    }
+
+    /// Returns an iterator over all players, in order of registration.
+    pub fn players(&self) -> impl Iterator<Item = &Player<R>> {
+        self.players.values()
+    }
+
+    /// Returns the number of registered players.
+    pub fn len(&self) -> usize {
+        self.players.len()
+    }
+
+    /// Returns `true` if there are no registered players.
+    pub fn is_empty(&self) -> bool {
+        self.players.is_empty()
+    }
+
+    fn add(&mut self, player: Player<R>) {
+        self.players.insert(player.id, player);
+    }
+
+    fn remove(&mut self, id: PlayerId) -> Option<Player<R>> {
+        self.players.shift_remove(&id)
+    }
+}
+
+/// Event to register a new player in the battle.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, EventTrigger,
+///     RegisterPlayer, Server,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// let player_id = 1;
+/// RegisterPlayer::trigger(&mut server, player_id, "Alice".to_string())
+///     .fire()
+///     .unwrap();
+/// let players = server.battle().players();
+/// assert_eq!(players.player(player_id).unwrap().name(), "Alice");
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct RegisterPlayer<R: BattleRules> {
+    id: PlayerId,
+
+    name: String,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Rating<R>: Serialize",
+            deserialize = "Rating<R>: Deserialize<'de>"
+        ))
+    )]
+    rating: Rating<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "PlayerData<R>: Serialize",
+            deserialize = "PlayerData<R>: Deserialize<'de>"
+        ))
+    )]
+    data: PlayerData<R>,
+}
+
+impl<R: BattleRules> RegisterPlayer<R> {
+    /// Returns a trigger for this event.
+    pub fn trigger<'a, P: EventProcessor<R>>(
+        processor: &'a mut P,
+        id: PlayerId,
+        name: String,
+    ) -> RegisterPlayerTrigger<'a, R, P> {
+        RegisterPlayerTrigger {
+            processor,
+            id,
+            name,
+            rating: Rating::<R>::default(),
+            data: PlayerData::<R>::default(),
+        }
+    }
+
+    /// Returns the id of the player to be registered.
+    pub fn id(&self) -> PlayerId {
+        self.id
+    }
+
+    /// Returns the name of the player.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the rating of the player.
+    pub fn rating(&self) -> &Rating<R> {
+        &self.rating
+    }
+
+    /// Returns the rules defined data of the player.
+    pub fn data(&self) -> &PlayerData<R> {
+        &self.data
+    }
+
+    fn player(&self) -> Player<R> {
+        Player {
+            id: self.id,
+            name: self.name.clone(),
+            rating: self.rating.clone(),
+            data: self.data.clone(),
+        }
+    }
+}
+
+impl<R: BattleRules> Debug for RegisterPlayer<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "RegisterPlayer {{ id: {:?}, name: {:?}, rating: {:?}, data: {:?} }}",
+            self.id, self.name, self.rating, self.data
+        )
+    }
+}
+
+impl<R: BattleRules> Clone for RegisterPlayer<R> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            name: self.name.clone(),
+            rating: self.rating.clone(),
+            data: self.data.clone(),
+        }
+    }
+}
+
+impl<R: BattleRules + 'static> Event<R> for RegisterPlayer<R> {
+    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
+        // Verify if the player already exists.
+        if battle.players().player(self.id).is_some() {
+            return Err(WeaselError::DuplicatedPlayer(self.id));
+        }
+        // Check if the rules allow the new player.
+        watch!(
+            battle.verify_watcher(self.kind()),
+            "PlayerRules::allow_new_player",
+            battle
+                .rules()
+                .player_rules()
+                .allow_new_player(&battle.state, &self.player())
+        )
+    }
+
+    fn apply(&self, battle: &mut Battle<R>, _event_queue: &mut Option<EventQueue<R>>) {
+        battle.state.players.add(self.player());
+    }
+
+    fn kind(&self) -> EventKind {
+        EventKind::RegisterPlayer
+    }
+
+    fn box_clone(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Trigger to build and fire a `RegisterPlayer` event.
+pub struct RegisterPlayerTrigger<'a, R, P>
+where
+    R: BattleRules,
+    P: EventProcessor<R>,
+{
+    processor: &'a mut P,
+    id: PlayerId,
+    name: String,
+    rating: Rating<R>,
+    data: PlayerData<R>,
+}
+
+impl<'a, R, P> RegisterPlayerTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    /// Sets the rating of the player.
+    pub fn rating(&'a mut self, rating: Rating<R>) -> &'a mut Self {
+        self.rating = rating;
+        self
+    }
+
+    /// Sets the rules defined data of the player.
+    pub fn data(&'a mut self, data: PlayerData<R>) -> &'a mut Self {
+        self.data = data;
+        self
+    }
+}
+
+impl<'a, R, P> EventTrigger<'a, R, P> for RegisterPlayerTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    fn processor(&'a mut self) -> &'a mut P {
+        self.processor
+    }
+
+    /// Returns a `RegisterPlayer` event.
+    fn event(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(RegisterPlayer {
+            id: self.id,
+            name: self.name.clone(),
+            rating: self.rating.clone(),
+            data: self.data.clone(),
+        })
+    }
+}
+
+/// Event to remove a player from the battle.
+///
+/// The rights and the entities owned by the player are not affected.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, EventTrigger,
+///     RegisterPlayer, RemovePlayer, Server,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// let player_id = 1;
+/// RegisterPlayer::trigger(&mut server, player_id, "Alice".to_string())
+///     .fire()
+///     .unwrap();
+/// RemovePlayer::trigger(&mut server, player_id).fire().unwrap();
+/// assert!(server.battle().players().is_empty());
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct RemovePlayer<R> {
+    id: PlayerId,
+
+    #[cfg_attr(feature = "serialization", serde(skip))]
+    _phantom: PhantomData<R>,
+}
+
+impl<R: BattleRules> RemovePlayer<R> {
+    /// Returns a trigger for this event.
+    pub fn trigger<'a, P: EventProcessor<R>>(
+        processor: &'a mut P,
+        id: PlayerId,
+    ) -> RemovePlayerTrigger<'a, R, P> {
+        RemovePlayerTrigger {
+            processor,
+            id,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the id of the player to be removed.
+    pub fn id(&self) -> PlayerId {
+        self.id
+    }
+}
+
+impl<R> Debug for RemovePlayer<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "RemovePlayer {{ id: {:?} }}", self.id)
+    }
+}
+
+impl<R> Clone for RemovePlayer<R> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<R: BattleRules + 'static> Event<R> for RemovePlayer<R> {
+    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
+        // Verify if the player exists.
+        if battle.players().player(self.id).is_none() {
+            return Err(WeaselError::PlayerNotFound(self.id));
+        }
+        Ok(())
+    }
+
+    fn apply(&self, battle: &mut Battle<R>, _event_queue: &mut Option<EventQueue<R>>) {
+        battle.state.players.remove(self.id);
+    }
+
+    fn kind(&self) -> EventKind {
+        EventKind::RemovePlayer
+    }
+
+    fn box_clone(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Trigger to build and fire a `RemovePlayer` event.
+pub struct RemovePlayerTrigger<'a, R, P>
+where
+    R: BattleRules,
+    P: EventProcessor<R>,
+{
+    processor: &'a mut P,
+    id: PlayerId,
+    _phantom: PhantomData<R>,
+}
+
+impl<'a, R, P> EventTrigger<'a, R, P> for RemovePlayerTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    fn processor(&'a mut self) -> &'a mut P {
+        self.processor
+    }
+
+    /// Returns a `RemovePlayer` event.
+    fn event(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(RemovePlayer {
+            id: self.id,
+            _phantom: PhantomData,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -238,6 +971,20 @@ mod tests {
         assert_eq!(rights.data.len(), 1);
     }
 
+    #[test]
+    fn rename_team() {
+        let mut rights: Rights<CustomRules> = Rights::new();
+        // Add rights for team 1 to player 1 and player 2.
+        rights.add(PLAYER_1_ID, &TEAM_1_ID);
+        rights.add(PLAYER_2_ID, &TEAM_1_ID);
+        // Rename team 1.
+        rights.rename_team(&TEAM_1_ID, &TEAM_2_ID);
+        assert_eq!(rights.check(PLAYER_1_ID, &TEAM_1_ID), false);
+        assert_eq!(rights.check(PLAYER_2_ID, &TEAM_1_ID), false);
+        assert_eq!(rights.check(PLAYER_1_ID, &TEAM_2_ID), true);
+        assert_eq!(rights.check(PLAYER_2_ID, &TEAM_2_ID), true);
+    }
+
     #[test]
     fn remove_player() {
         let mut rights: Rights<CustomRules> = Rights::new();
@@ -253,6 +1000,45 @@ mod tests {
         assert_eq!(rights.data.len(), 1);
     }
 
+    #[test]
+    fn entities_and_kinds() {
+        let mut rights: Rights<CustomRules> = Rights::new();
+        let entity_1 = EntityId::Creature(1);
+        let entity_2 = EntityId::Creature(2);
+        // Add rights for single entities.
+        rights.add_entity(PLAYER_1_ID, &entity_1);
+        rights.add_entity(PLAYER_1_ID, &entity_1);
+        rights.add_entity(PLAYER_2_ID, &entity_1);
+        assert_eq!(rights.entities.len(), 2);
+        assert!(rights.check_entity(PLAYER_1_ID, &entity_1));
+        assert!(!rights.check_entity(PLAYER_1_ID, &entity_2));
+        // Reidentify and forget an entity.
+        rights.reidentify_entity(&entity_1, &entity_2);
+        assert!(rights.check_entity(PLAYER_2_ID, &entity_2));
+        rights.remove_entity(PLAYER_2_ID, &entity_2);
+        assert_eq!(rights.entities.len(), 1);
+        rights.forget_entity(&entity_2);
+        assert_eq!(rights.entities.len(), 0);
+        // Restrict a player to some event kinds.
+        assert!(rights.check_kind(PLAYER_1_ID, EventKind::StartTurn));
+        rights.add_kind(PLAYER_1_ID, EventKind::EndTurn);
+        assert!(!rights.check_kind(PLAYER_1_ID, EventKind::StartTurn));
+        assert!(rights.check_kind(PLAYER_1_ID, EventKind::EndTurn));
+        assert!(rights.check_kind(PLAYER_2_ID, EventKind::StartTurn));
+        // Removing the last allowed kind keeps the player restricted.
+        rights.remove_kind(PLAYER_1_ID, EventKind::EndTurn);
+        assert!(!rights.check_kind(PLAYER_1_ID, EventKind::EndTurn));
+        assert!(!rights.check_kind(PLAYER_1_ID, EventKind::StartTurn));
+        rights.clear_kinds(PLAYER_1_ID);
+        assert!(rights.check_kind(PLAYER_1_ID, EventKind::StartTurn));
+        rights.add_kind(PLAYER_1_ID, EventKind::EndTurn);
+        // Remove all rights of a player.
+        rights.add_entity(PLAYER_1_ID, &entity_1);
+        rights.remove_player(PLAYER_1_ID);
+        assert_eq!(rights.entities.len(), 0);
+        assert_eq!(rights.kinds.len(), 0);
+    }
+
     #[test]
     fn handle() {
         let mut battle = Battle::builder(CustomRules::new()).build();
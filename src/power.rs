@@ -157,17 +157,21 @@ impl<R: BattleRules + 'static> Event<R> for InvokePower<R> {
             // Verify that the team possesses this power.
             if let Some(power) = team.power(&self.power_id) {
                 // Verify if this power can be activated.
-                battle
-                    .rules
-                    .team_rules()
-                    .invocable(&battle.state, Call::new(team, power, &self.invocation))
-                    .map_err(|err| {
-                        WeaselError::PowerNotInvocable(
-                            self.team_id.clone(),
-                            self.power_id.clone(),
-                            Box::new(err),
-                        )
-                    })
+                watch!(
+                    battle.verify_watcher(self.kind()),
+                    "TeamRules::invocable",
+                    battle
+                        .rules
+                        .team_rules()
+                        .invocable(&battle.state, Call::new(team, power, &self.invocation))
+                )
+                .map_err(|err| {
+                    WeaselError::PowerNotInvocable(
+                        self.team_id.clone(),
+                        self.power_id.clone(),
+                        Box::new(err),
+                    )
+                })
             } else {
                 Err(WeaselError::PowerNotKnown(
                     self.team_id.clone(),
@@ -191,12 +195,16 @@ impl<R: BattleRules + 'static> Event<R> for InvokePower<R> {
                 self.power_id, self.team_id
             )
         });
-        battle.rules.team_rules().invoke(
-            &battle.state,
-            Call::new(team, power, &self.invocation),
-            event_queue,
-            &mut battle.entropy,
-            &mut battle.metrics.write_handle(),
+        watch!(
+            battle.apply_watcher(),
+            "TeamRules::invoke",
+            battle.rules.team_rules().invoke(
+                &battle.state,
+                Call::new(team, power, &self.invocation),
+                event_queue,
+                &mut battle.entropy,
+                &mut battle.metrics.write_handle(),
+            )
         );
     }
 
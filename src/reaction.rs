@@ -0,0 +1,77 @@
+//! Module to react automatically to events.
+
+use crate::battle::{BattleRules, BattleState};
+use crate::entropy::Entropy;
+use crate::event::{EventQueue, EventWrapper};
+use crate::metric::WriteMetrics;
+
+/// Rules to declare automatic responses to events.
+///
+/// Reactions are invoked by the battle after each event has been applied. They can fire
+/// new events by pushing them into the event queue. Such events are derived from the one
+/// that caused the reaction, thus they go through the same verification and replication
+/// pipeline of every other event.\
+/// The event queue is only available on the server, so reactions are computed once and
+/// then sent to clients as normal events.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, battle_rules_with_reaction, rules::empty::*, Battle, BattleController,
+///     BattleRules, BattleState, CreateTeam, Entropy, EventKind, EventQueue, EventTrigger,
+///     EventWrapper, ReactionRules, Server, WriteMetrics,
+/// };
+/// use weasel::event::DummyEvent;
+///
+/// #[derive(Default)]
+/// pub struct CustomReactionRules {}
+///
+/// impl<R: BattleRules + 'static> ReactionRules<R> for CustomReactionRules {
+///     fn react(
+///         &self,
+///         _state: &BattleState<R>,
+///         event: &EventWrapper<R>,
+///         event_queue: &mut Option<EventQueue<R>>,
+///         _entropy: &mut Entropy<R>,
+///         _metrics: &mut WriteMetrics<R>,
+///     ) {
+///         // Welcome every new team with a dummy event.
+///         if event.kind() == EventKind::CreateTeam {
+///             DummyEvent::trigger(event_queue).fire();
+///         }
+///     }
+/// }
+///
+/// battle_rules_with_reaction! { CustomReactionRules }
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+/// CreateTeam::trigger(&mut server, 1).fire().unwrap();
+/// assert_eq!(server.battle().history().len(), 2);
+/// ```
+pub trait ReactionRules<R: BattleRules> {
+    /// Returns the names of the optional features enabled in these reaction rules.
+    /// They are reported by `Battle::describe`.
+    ///
+    /// The provided implementation returns no features.
+    fn features(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// Reacts to an event that has just been applied to the battle, by inserting the
+    /// response events into `event_queue`.
+    ///
+    /// Events derived by the reactions are themselves subject to reactions. Make sure that
+    /// your rules don't react forever to their own events.
+    ///
+    /// The provided implementation does nothing.
+    fn react(
+        &self,
+        _state: &BattleState<R>,
+        _event: &EventWrapper<R>,
+        _event_queue: &mut Option<EventQueue<R>>,
+        _entropy: &mut Entropy<R>,
+        _metrics: &mut WriteMetrics<R>,
+    ) {
+    }
+}
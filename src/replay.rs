@@ -0,0 +1,164 @@
+//! Deterministic playback of a recorded battle.
+
+use crate::battle::{Battle, BattleRules};
+use crate::error::{WeaselError, WeaselResult};
+use crate::event::{EventId, VersionedEventWrapper};
+#[cfg(feature = "serialization")]
+use crate::serde::FlatVersionedEvent;
+
+/// Type of the closure used by a replay to create the rules of each new battle.
+pub type RulesFactory<R> = Box<dyn Fn() -> R>;
+
+/// A replay plays back a recorded history, one event at a time.
+///
+/// The replay owns a battle, initially empty, to which events are applied in order.
+/// It's possible to step forward, to jump to any event and to materialize independent
+/// battles at intermediate points of the timeline.\
+/// Jumping backward rebuilds the battle from the start, with new rules created by
+/// the replay's rules factory.
+///
+/// # Examples
+/// ```
+/// use weasel::replay::Replay;
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateTeam,
+///     EventTrigger, Server,
+/// };
+///
+/// battle_rules! {}
+///
+/// // Record a short battle.
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+/// CreateTeam::trigger(&mut server, 1).fire().unwrap();
+/// CreateTeam::trigger(&mut server, 2).fire().unwrap();
+/// let events = server.battle().versioned_events(0..2).collect();
+///
+/// // Play it back.
+/// let mut replay = Replay::new(Box::new(CustomRules::new), events);
+/// assert_eq!(replay.battle().entities().teams().count(), 0);
+/// assert!(replay.step().unwrap());
+/// assert_eq!(replay.battle().entities().teams().count(), 1);
+/// replay.jump_to(1).unwrap();
+/// assert!(replay.is_finished());
+/// assert_eq!(replay.battle().entities().teams().count(), 2);
+/// ```
+pub struct Replay<R: BattleRules> {
+    rules_factory: RulesFactory<R>,
+    events: Vec<VersionedEventWrapper<R>>,
+    battle: Battle<R>,
+}
+
+impl<R: BattleRules + 'static> Replay<R> {
+    /// Creates a new replay of `events`, which must be a history starting from the first event.
+    ///
+    /// `rules_factory` is invoked each time the replay needs a new battle.
+    pub fn new(rules_factory: RulesFactory<R>, events: Vec<VersionedEventWrapper<R>>) -> Self {
+        let battle = Battle::builder(rules_factory()).build();
+        Self {
+            rules_factory,
+            events,
+            battle,
+        }
+    }
+
+    /// Creates a new replay from a serialized history.
+    #[cfg(feature = "serialization")]
+    pub fn from_flat_events(
+        rules_factory: RulesFactory<R>,
+        events: Vec<FlatVersionedEvent<R>>,
+    ) -> Self {
+        Self::new(rules_factory, events.into_iter().map(Into::into).collect())
+    }
+
+    /// Returns the battle in its current state of playback.
+    pub fn battle(&self) -> &Battle<R> {
+        &self.battle
+    }
+
+    /// Returns all events in this replay.
+    pub fn events(&self) -> &[VersionedEventWrapper<R>] {
+        &self.events
+    }
+
+    /// Returns the number of events in this replay.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns true if this replay doesn't contain any event.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Returns the number of events applied so far.
+    pub fn position(&self) -> usize {
+        self.battle.history().len() as usize
+    }
+
+    /// Returns true if all events have been applied.
+    pub fn is_finished(&self) -> bool {
+        self.position() >= self.events.len()
+    }
+
+    /// Applies the next event.
+    ///
+    /// Returns false if there are no more events to apply.
+    pub fn step(&mut self) -> WeaselResult<bool, R> {
+        match self.events.get(self.position()) {
+            Some(event) => {
+                Self::apply(&mut self.battle, event)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Brings the battle to the state it had right after the event with the given id.
+    ///
+    /// Returns an error if the replay doesn't contain such event.
+    pub fn jump_to(&mut self, id: EventId) -> WeaselResult<(), R> {
+        let target = self.target(id)?;
+        if target < self.position() {
+            self.rewind();
+        }
+        while self.position() < target {
+            self.step()?;
+        }
+        Ok(())
+    }
+
+    /// Brings the battle back to its initial state, before any event.
+    pub fn rewind(&mut self) {
+        self.battle = Battle::builder((self.rules_factory)()).build();
+    }
+
+    /// Materializes a new battle with the state right after the event with the given id.
+    ///
+    /// The battle of this replay is left untouched.
+    pub fn checkpoint(&self, id: EventId) -> WeaselResult<Battle<R>, R> {
+        let target = self.target(id)?;
+        let mut battle = Battle::builder((self.rules_factory)()).build();
+        for event in &self.events[..target] {
+            Self::apply(&mut battle, event)?;
+        }
+        Ok(battle)
+    }
+
+    /// Returns the number of events to apply in order to include the event with the given id.
+    fn target(&self, id: EventId) -> WeaselResult<usize, R> {
+        let len = self.events.len() as EventId;
+        self.events
+            .iter()
+            .position(|event| event.id() == id)
+            .map(|index| index + 1)
+            .ok_or_else(|| WeaselError::InvalidEventRange(id..id + 1, len))
+    }
+
+    /// Verifies and applies an event to a battle.
+    fn apply(battle: &mut Battle<R>, event: &VersionedEventWrapper<R>) -> WeaselResult<(), R> {
+        battle.verify_wrapper(event, false)?;
+        battle.apply(event.wrapper(), &mut None);
+        Ok(())
+    }
+}
@@ -1,15 +1,24 @@
 //! Everything related to the battle's turns and rounds.
 
+use crate::ability::{update_pending_activation, AbilityId, PendingActivation};
 use crate::actor::{Actor, ActorRules};
-use crate::battle::{Battle, BattleRules, Checkpoint};
-use crate::entity::{Entities, Entity, EntityId};
+use crate::battle::{Battle, BattleRules, Checkpoint, EndBattle, EndReason};
+use crate::creature::CreatureId;
+use crate::entity::{Entities, Entity, EntityId, RemoveEntity};
 use crate::entropy::Entropy;
 use crate::error::{WeaselError, WeaselResult};
-use crate::event::{Event, EventKind, EventProcessor, EventQueue, EventRights, EventTrigger};
+use crate::event::{
+    Event, EventId, EventKind, EventProcessor, EventPrototype, EventQueue, EventRights,
+    EventTrigger, LinkedQueue,
+};
 use crate::metric::WriteMetrics;
-use crate::space::Space;
+#[cfg(feature = "serialization")]
+use crate::serde::FlatEvent;
+use crate::space::{Space, SpaceRules};
 use crate::status::update_statuses;
-use indexmap::IndexSet;
+use crate::team::{ConcludeObjectives, TeamRules};
+use crate::util::Id;
+use indexmap::{IndexMap, IndexSet};
 #[cfg(feature = "serialization")]
 use serde::{Deserialize, Serialize};
 use std::any::Any;
@@ -23,13 +32,142 @@ pub type RoundsCount = u32;
 /// Type for counting the number of turns.
 pub type TurnsCount = u32;
 
+/// Type for counting the number of simulation ticks.
+pub type TicksCount = u64;
+
 /// Manages the battle's rounds. The main purpose is to tell which actor(s) will act next.
+#[cfg_attr(feature = "serialization", derive(Serialize))]
 pub struct Rounds<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "TurnStateType<R>: Serialize",
+            deserialize = "TurnStateType<R>: Deserialize<'de>"
+        ))
+    )]
     state: TurnStateType<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "RoundsModel<R>: Serialize",
+            deserialize = "RoundsModel<R>: Deserialize<'de>"
+        ))
+    )]
     model: RoundsModel<R>,
+
+    #[cfg_attr(feature = "serialization", serde(skip))]
     rules: R::RR,
+
+    rounds: RoundsCount,
+
+    turns: TurnsCount,
+
+    ticks: TicksCount,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(
+            bound(
+                serialize = "PendingActivation<R>: Serialize",
+                deserialize = "PendingActivation<R>: Deserialize<'de>"
+            ),
+            serialize_with = "crate::serde::serialize_pairs",
+            deserialize_with = "crate::serde::deserialize_pairs"
+        )
+    )]
+    pending: IndexMap<EntityId<R>, PendingActivation<R>>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(
+            bound(
+                serialize = "EntityId<R>: Serialize",
+                deserialize = "EntityId<R>: Deserialize<'de>"
+            ),
+            serialize_with = "crate::serde::serialize_pairs",
+            deserialize_with = "crate::serde::deserialize_pairs"
+        )
+    )]
+    activations: IndexMap<EntityId<R>, u32>,
+
+    #[cfg_attr(feature = "serialization", serde(skip))]
+    round_limit: Option<RoundsCount>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Vec<ScheduledEvent<R>>: Serialize",
+            deserialize = "Vec<ScheduledEvent<R>>: Deserialize<'de>"
+        ))
+    )]
+    scheduled: Vec<ScheduledEvent<R>>,
+}
+
+/// The progress of rounds and turns stored in `Rounds`, without its rules.
+///
+/// It's used to deserialize the rounds of a `BattleSnapshot`.
+#[cfg(feature = "serialization")]
+#[derive(Deserialize)]
+pub(crate) struct RoundsState<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "TurnStateType<R>: Serialize",
+            deserialize = "TurnStateType<R>: Deserialize<'de>"
+        ))
+    )]
+    state: TurnStateType<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "RoundsModel<R>: Serialize",
+            deserialize = "RoundsModel<R>: Deserialize<'de>"
+        ))
+    )]
+    model: RoundsModel<R>,
+
     rounds: RoundsCount,
+
     turns: TurnsCount,
+
+    ticks: TicksCount,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(
+            bound(
+                serialize = "PendingActivation<R>: Serialize",
+                deserialize = "PendingActivation<R>: Deserialize<'de>"
+            ),
+            serialize_with = "crate::serde::serialize_pairs",
+            deserialize_with = "crate::serde::deserialize_pairs"
+        )
+    )]
+    pending: IndexMap<EntityId<R>, PendingActivation<R>>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(
+            bound(
+                serialize = "EntityId<R>: Serialize",
+                deserialize = "EntityId<R>: Deserialize<'de>"
+            ),
+            serialize_with = "crate::serde::serialize_pairs",
+            deserialize_with = "crate::serde::deserialize_pairs"
+        )
+    )]
+    activations: IndexMap<EntityId<R>, u32>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Vec<ScheduledEvent<R>>: Serialize",
+            deserialize = "Vec<ScheduledEvent<R>>: Deserialize<'de>"
+        ))
+    )]
+    scheduled: Vec<ScheduledEvent<R>>,
 }
 
 impl<R: BattleRules> Rounds<R> {
@@ -40,9 +178,27 @@ impl<R: BattleRules> Rounds<R> {
             rules,
             rounds: 0,
             turns: 0,
+            ticks: 0,
+            pending: IndexMap::new(),
+            activations: IndexMap::new(),
+            round_limit: None,
+            scheduled: Vec::new(),
         }
     }
 
+    /// Replaces the progress of rounds and turns with `state`.
+    #[cfg(feature = "serialization")]
+    pub(crate) fn restore(&mut self, state: RoundsState<R>) {
+        self.state = state.state;
+        self.model = state.model;
+        self.rounds = state.rounds;
+        self.turns = state.turns;
+        self.ticks = state.ticks;
+        self.pending = state.pending;
+        self.activations = state.activations;
+        self.scheduled = state.scheduled;
+    }
+
     /// Returns the rounds model. It contains all data starting from which `RoundsRules`
     /// can compute the order of acting in this battle.
     pub fn model(&self) -> &RoundsModel<R> {
@@ -85,11 +241,30 @@ impl<R: BattleRules> Rounds<R> {
         &mut self.rules
     }
 
+    /// Returns the actors in the order in which they are expected to act.
+    ///
+    /// See [order](trait.RoundsRules.html#method.order).
+    pub fn order(&self, entities: &Entities<R>) -> Vec<EntityId<R>> {
+        self.rules.order(&self.model, entities)
+    }
+
     /// Returns the number of completed rounds.
     pub fn completed_rounds(&self) -> RoundsCount {
         self.rounds
     }
 
+    /// Returns the number of rounds after which the battle automatically ends, if any.
+    ///
+    /// See [round_limit](../battle/struct.BattleBuilder.html#method.round_limit).
+    pub fn round_limit(&self) -> Option<RoundsCount> {
+        self.round_limit
+    }
+
+    /// Sets the number of rounds after which the battle automatically ends.
+    pub(crate) fn set_round_limit(&mut self, limit: Option<RoundsCount>) {
+        self.round_limit = limit;
+    }
+
     /// Increases the rounds counter.
     pub(crate) fn increase_completed_rounds(&mut self) {
         self.rounds += 1;
@@ -100,11 +275,128 @@ impl<R: BattleRules> Rounds<R> {
         self.turns
     }
 
+    /// Sets the number of completed rounds and turns.
+    pub(crate) fn restore_counters(&mut self, rounds: RoundsCount, turns: TurnsCount) {
+        self.rounds = rounds;
+        self.turns = turns;
+    }
+
     /// Increases the turns counter.
     pub(crate) fn increase_completed_turns(&mut self) {
         self.turns += 1;
     }
 
+    /// Returns the number of the last simulation tick.
+    pub fn ticks(&self) -> TicksCount {
+        self.ticks
+    }
+
+    /// Returns the pending ability activation of the actor with the given id, if any.
+    pub fn pending_activation(&self, entity_id: &EntityId<R>) -> Option<&PendingActivation<R>> {
+        self.pending.get(entity_id)
+    }
+
+    /// Returns an iterator over all pending ability activations.
+    pub fn pending_activations(&self) -> impl Iterator<Item = &PendingActivation<R>> {
+        self.pending.values()
+    }
+
+    /// Returns a mutable reference to the pending ability activation of an actor.
+    pub(crate) fn pending_activation_mut(
+        &mut self,
+        entity_id: &EntityId<R>,
+    ) -> Option<&mut PendingActivation<R>> {
+        self.pending.get_mut(entity_id)
+    }
+
+    /// Stores a pending ability activation.
+    pub(crate) fn add_pending_activation(&mut self, pending: PendingActivation<R>) {
+        self.pending.insert(pending.entity_id().clone(), pending);
+    }
+
+    /// Removes the pending ability activation of an actor, if any.
+    pub(crate) fn remove_pending_activation(
+        &mut self,
+        entity_id: &EntityId<R>,
+    ) -> Option<PendingActivation<R>> {
+        self.pending.shift_remove(entity_id)
+    }
+
+    /// Removes all pending ability activations.
+    pub(crate) fn clear_pending_activations(&mut self) {
+        self.pending.clear();
+    }
+
+    /// Returns the number of abilities activated by the actor with the given id
+    /// in the current turn.
+    pub fn activations(&self, entity_id: &EntityId<R>) -> u32 {
+        self.activations.get(entity_id).copied().unwrap_or_default()
+    }
+
+    /// Increases the number of abilities activated by an actor in the current turn.
+    pub(crate) fn increase_activations(&mut self, entity_id: &EntityId<R>) {
+        *self.activations.entry(entity_id.clone()).or_insert(0) += 1;
+    }
+
+    /// Sets the number of abilities activated by an actor in the current turn.
+    pub(crate) fn set_activations(&mut self, entity_id: EntityId<R>, count: u32) {
+        self.activations.insert(entity_id, count);
+    }
+
+    /// Returns an iterator over the number of abilities activated by each actor
+    /// in the current turn.
+    pub(crate) fn activations_iter(&self) -> impl Iterator<Item = (&EntityId<R>, &u32)> {
+        self.activations.iter()
+    }
+
+    /// Moves the activations counter of an actor under a new id.
+    pub(crate) fn reidentify_activations(&mut self, old_id: &EntityId<R>, new_id: &EntityId<R>) {
+        if let Some(count) = self.activations.shift_remove(old_id) {
+            self.activations.insert(new_id.clone(), count);
+        }
+    }
+
+    /// Removes the activations counter of an actor.
+    pub(crate) fn remove_activations(&mut self, entity_id: &EntityId<R>) {
+        self.activations.shift_remove(entity_id);
+    }
+
+    /// Resets the activations counters of all actors.
+    pub(crate) fn clear_activations(&mut self) {
+        self.activations.clear();
+    }
+
+    /// Returns an iterator over all events scheduled to be fired in the future.
+    pub fn scheduled_events(&self) -> impl Iterator<Item = &ScheduledEvent<R>> {
+        self.scheduled.iter()
+    }
+
+    /// Stores an event to be fired in the future.
+    pub(crate) fn add_scheduled_event(&mut self, scheduled: ScheduledEvent<R>) {
+        self.scheduled.push(scheduled);
+    }
+
+    /// Advances the delay of all scheduled events by one turn or round.
+    /// Returns the events whose delay expired, in order of scheduling.
+    pub(crate) fn advance_scheduled_events(&mut self, round: bool) -> Vec<ScheduledEvent<R>> {
+        let mut expired = Vec::new();
+        let mut pending = Vec::new();
+        for mut scheduled in self.scheduled.drain(..) {
+            match &mut scheduled.delay {
+                Delay::Turns(count) if !round => *count = count.saturating_sub(1),
+                Delay::Rounds(count) if round => *count = count.saturating_sub(1),
+                _ => {}
+            }
+            if scheduled.delay.is_expired() {
+                expired.push(scheduled);
+            } else {
+                pending.push(scheduled);
+            }
+        }
+        self.scheduled = pending;
+        expired
+    }
+
     /// Called when a new actor is added to the battle.
     pub(crate) fn on_actor_added(
         &mut self,
@@ -140,6 +432,26 @@ impl<R: BattleRules> Rounds<R> {
             .on_end(entities, space, &mut self.model, actor, entropy, metrics);
     }
 
+    /// Invoked when a simulation tick happens.
+    pub(crate) fn on_tick(
+        &mut self,
+        entities: &Entities<R>,
+        tick: TicksCount,
+        event_queue: &mut Option<EventQueue<R>>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.ticks = tick;
+        self.rules.on_tick(
+            entities,
+            &mut self.model,
+            tick,
+            event_queue,
+            entropy,
+            metrics,
+        );
+    }
+
     /// Regenerates this rounds' model starting from the given seed.
     pub(crate) fn regenerate_model(&mut self, seed: &Option<RoundsSeed<R>>) {
         self.model = self.rules.generate_model(seed)
@@ -151,6 +463,7 @@ pub type TurnStateType<R> = TurnState<EntityId<R>>;
 
 /// State machine to manage the turns' state.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub enum TurnState<EI>
 where
     EI: Debug + Hash + Eq,
@@ -188,8 +501,12 @@ pub trait RoundsRules<R: BattleRules> {
     /// See [RoundsSeed](type.RoundsSeed.html).
     type RoundsSeed: Debug + Clone + Send + Serialize + for<'a> Deserialize<'a>;
 
+    #[cfg(not(feature = "serialization"))]
     /// See [RoundsModel](type.RoundsModel.html).
     type RoundsModel;
+    #[cfg(feature = "serialization")]
+    /// See [RoundsModel](type.RoundsModel.html).
+    type RoundsModel: Serialize + for<'a> Deserialize<'a>;
 
     /// Generates a `RoundsModel` starting from a `RoundsSeed`.
     fn generate_model(&self, seed: &Option<Self::RoundsSeed>) -> Self::RoundsModel;
@@ -201,6 +518,21 @@ pub trait RoundsRules<R: BattleRules> {
         true
     }
 
+    /// Returns the actors in the order in which they are expected to act,
+    /// starting from the one who should act next.
+    ///
+    /// The order is only informative, for instance to display the initiative in a user
+    /// interface. Turns are still validated with `eligible`.
+    ///
+    /// The provided implementation returns the eligible actors, in order of insertion.
+    fn order(&self, model: &Self::RoundsModel, entities: &Entities<R>) -> Vec<EntityId<R>> {
+        entities
+            .actors()
+            .filter(|actor| self.eligible(model, *actor))
+            .map(|actor| actor.entity_id().clone())
+            .collect()
+    }
+
     /// Invoked when a new turn begins.
     ///
     /// The provided implementation does nothing.
@@ -229,6 +561,24 @@ pub trait RoundsRules<R: BattleRules> {
     ) {
     }
 
+    /// Invoked on each simulation tick, with the number of the new tick.
+    ///
+    /// Ticks let hybrid real-time games evolve the rounds model, for instance by filling
+    /// the actors' charge bars, through events recorded in the history.
+    /// Turns can be started by inserting events in `event_queue`.
+    ///
+    /// The provided implementation does nothing.
+    fn on_tick(
+        &self,
+        _entities: &Entities<R>,
+        _model: &mut Self::RoundsModel,
+        _tick: TicksCount,
+        _event_queue: &mut Option<EventQueue<R>>,
+        _entropy: &mut Entropy<R>,
+        _metrics: &mut WriteMetrics<R>,
+    ) {
+    }
+
     /// Invoked when a new actor is added to the battle.
     ///
     /// The provided implementation does nothing.
@@ -252,6 +602,14 @@ pub trait RoundsRules<R: BattleRules> {
         _metrics: &mut WriteMetrics<R>,
     ) {
     }
+
+    /// Returns the names of the optional features enabled in these rounds rules.
+    /// They are reported by `Battle::describe`.
+    ///
+    /// The provided implementation returns no features.
+    fn features(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
 }
 
 /// Type to represent a rounds seed.
@@ -369,7 +727,12 @@ impl<R: BattleRules + 'static> Event<R> for StartTurn<R> {
             // Verify if entity exists.
             if let Some(actor) = battle.entities().actor(&id) {
                 // Verify if actor is eligible.
-                if !battle.rounds().eligible(actor) {
+                let eligible = watch!(
+                    battle.verify_watcher(self.kind()),
+                    "RoundsRules::eligible",
+                    battle.rounds().eligible(actor)
+                );
+                if !eligible {
                     return Err(WeaselError::ActorNotEligible(id.clone()));
                 }
             } else {
@@ -386,35 +749,10 @@ impl<R: BattleRules + 'static> Event<R> for StartTurn<R> {
             .state
             .rounds
             .set_state(TurnState::Started(actors_ids.clone()));
+        battle.state.rounds.clear_activations();
         // Perform some operations on every actor.
         for id in &actors_ids {
-            let metrics = &mut battle.metrics.write_handle();
-            // Get the actor.
-            let actor = battle
-                .state
-                .entities
-                .actor(id)
-                .unwrap_or_else(|| panic!("constraint violated: actor {:?} not found", id));
-            // Invoke `RoundRules` callback.
-            battle.state.rounds.rules.on_start(
-                &battle.state.entities,
-                &battle.state.space,
-                &mut battle.state.rounds.model,
-                actor,
-                &mut battle.entropy,
-                metrics,
-            );
-            // Invoke `CharacterRules` callback.
-            battle.rules.actor_rules().on_turn_start(
-                &battle.state,
-                actor,
-                event_queue,
-                &mut battle.entropy,
-                metrics,
-            );
-            // Update all statuses afflicting the actor.
-            update_statuses(id, battle, event_queue)
-                .unwrap_or_else(|err| panic!("constraint violated: {:?}", err));
+            start_actor_turn(id, battle, event_queue);
         }
     }
 
@@ -430,17 +768,8 @@ impl<R: BattleRules + 'static> Event<R> for StartTurn<R> {
         self
     }
 
-    fn rights<'a>(&'a self, battle: &'a Battle<R>) -> EventRights<'a, R> {
-        // Collect all teams involved out of the list of actors.
-        let mut teams = Vec::new();
-        for id in &self.ids {
-            let actor =
-                battle.state.entities.actor(id).unwrap_or_else(|| {
-                    panic!("constraint violated: actor {:?} not found", id.clone())
-                });
-            teams.push(actor.team_id());
-        }
-        EventRights::Teams(teams)
+    fn rights<'a>(&'a self, _battle: &'a Battle<R>) -> EventRights<'a, R> {
+        EventRights::Entities(self.ids.iter().collect())
     }
 }
 
@@ -539,49 +868,7 @@ impl<R: BattleRules + 'static> Event<R> for EndTurn<R> {
     }
 
     fn apply(&self, battle: &mut Battle<R>, event_queue: &mut Option<EventQueue<R>>) {
-        let actors_ids = if let TurnState::Started(actors) = battle.state.rounds.state() {
-            actors.clone()
-        } else {
-            panic!("constraint violated: end turn called when state is not started");
-        };
-        // End the turn for each actor.
-        for actor_id in actors_ids {
-            let actor = battle.state.entities.actor(&actor_id).unwrap_or_else(|| {
-                panic!(
-                    "constraint violated: actor {:?} not found",
-                    actor_id.clone()
-                )
-            });
-            let metrics = &mut battle.metrics.write_handle();
-            // Invoke `CharacterRules` callback.
-            battle.rules.actor_rules().on_turn_end(
-                &battle.state,
-                actor,
-                event_queue,
-                &mut battle.entropy,
-                metrics,
-            );
-            // Invoke `RoundRules` callback.
-            battle.state.rounds.on_end(
-                &battle.state.entities,
-                &battle.state.space,
-                actor,
-                &mut battle.entropy,
-                metrics,
-            );
-            // Check teams' objectives.
-            Battle::check_objectives(
-                &battle.state,
-                &battle.rules.team_rules(),
-                &battle.metrics.read_handle(),
-                event_queue,
-                Checkpoint::TurnEnd,
-            );
-        }
-        // Set the turn state.
-        battle.state.rounds.set_state(TurnState::Ready);
-        // Increase the turns counter.
-        battle.rounds_mut().increase_completed_turns();
+        end_turn(battle, event_queue);
     }
 
     fn kind(&self) -> EventKind {
@@ -602,18 +889,7 @@ impl<R: BattleRules + 'static> Event<R> for EndTurn<R> {
         } else {
             panic!("constraint violated: end turn called when state is not started");
         };
-        // Collect the rights to all teams involved.
-        let mut teams = Vec::new();
-        for actor_id in actors {
-            let actor = battle.state.entities.actor(actor_id).unwrap_or_else(|| {
-                panic!(
-                    "constraint violated: actor {:?} not found",
-                    actor_id.clone()
-                )
-            });
-            teams.push(actor.team_id());
-        }
-        EventRights::Teams(teams)
+        EventRights::Entities(actors.iter().collect())
     }
 }
 
@@ -644,15 +920,16 @@ where
     }
 }
 
-/// Event to reset the rounds model.
+/// Event to end the current turn because its actors exceeded the time at their disposal.
 ///
-/// This event can be fired only if no turn is in progress.
+/// This event is fired by servers with a turn timeout, through `Server::check_timeouts`.
+/// It has the same effects of `EndTurn`, but it keeps a record of the timeout in the history.
 ///
 /// # Examples
 /// ```
 /// use weasel::{
-///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, EventKind,
-///     EventTrigger, ResetRounds, Server,
+///     battle_rules, round::TurnState, rules::empty::*, Battle, BattleController, BattleRules,
+///     CreateCreature, CreateTeam, EntityId, EventTrigger, Server, StartTurn, TurnTimedOut,
 /// };
 ///
 /// battle_rules! {}
@@ -660,68 +937,89 @@ where
 /// let battle = Battle::builder(CustomRules::new()).build();
 /// let mut server = Server::builder(battle).build();
 ///
-/// ResetRounds::trigger(&mut server).fire().unwrap();
-/// assert_eq!(
-///     server.battle().history().events()[0].kind(),
-///     EventKind::ResetRounds
-/// );
+/// let team_id = 1;
+/// CreateTeam::trigger(&mut server, team_id).fire().unwrap();
+/// let creature_id = 1;
+/// let position = ();
+/// CreateCreature::trigger(&mut server, creature_id, team_id, position)
+///     .fire()
+///     .unwrap();
+/// StartTurn::trigger(&mut server, EntityId::Creature(creature_id))
+///     .fire()
+///     .unwrap();
+///
+/// TurnTimedOut::trigger(&mut server, vec![EntityId::Creature(creature_id)])
+///     .fire()
+///     .unwrap();
+/// assert_eq!(*server.battle().rounds().state(), TurnState::Ready);
 /// ```
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
-pub struct ResetRounds<R: BattleRules> {
+pub struct TurnTimedOut<R: BattleRules> {
     #[cfg_attr(
         feature = "serialization",
         serde(bound(
-            serialize = "Option<RoundsSeed<R>>: Serialize",
-            deserialize = "Option<RoundsSeed<R>>: Deserialize<'de>"
+            serialize = "Vec<EntityId<R>>: Serialize",
+            deserialize = "Vec<EntityId<R>>: Deserialize<'de>"
         ))
     )]
-    seed: Option<RoundsSeed<R>>,
+    ids: Vec<EntityId<R>>,
 }
 
-impl<R: BattleRules> ResetRounds<R> {
-    /// Returns a trigger for this event.
-    pub fn trigger<P: EventProcessor<R>>(processor: &mut P) -> ResetRoundsTrigger<R, P> {
-        ResetRoundsTrigger {
+impl<R: BattleRules> TurnTimedOut<R> {
+    /// Returns a trigger for this event.\
+    /// `ids` are the actors who exceeded their time. All of them must be acting.
+    pub fn trigger<'a, P, I>(processor: &'a mut P, ids: I) -> TurnTimedOutTrigger<'a, R, P>
+    where
+        P: EventProcessor<R>,
+        I: IntoIterator<Item = EntityId<R>>,
+    {
+        TurnTimedOutTrigger {
             processor,
-            seed: None,
+            ids: ids.into_iter().collect(),
         }
     }
 
-    /// Returns the new seed.
-    pub fn seed(&self) -> &Option<RoundsSeed<R>> {
-        &self.seed
+    /// Returns the ids of the actors who exceeded their time.
+    pub fn ids(&self) -> &Vec<EntityId<R>> {
+        &self.ids
     }
 }
 
-impl<R: BattleRules> Debug for ResetRounds<R> {
+impl<R: BattleRules> Debug for TurnTimedOut<R> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        write!(f, "ResetRounds {{ seed: {:?} }}", self.seed)
+        write!(f, "TurnTimedOut {{ ids: {:?} }}", self.ids)
     }
 }
 
-impl<R: BattleRules> Clone for ResetRounds<R> {
+impl<R: BattleRules> Clone for TurnTimedOut<R> {
     fn clone(&self) -> Self {
         Self {
-            seed: self.seed.clone(),
+            ids: self.ids.clone(),
         }
     }
 }
 
-impl<R: BattleRules + 'static> Event<R> for ResetRounds<R> {
+impl<R: BattleRules + 'static> Event<R> for TurnTimedOut<R> {
     fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
-        // Verify that no turn is in progress.
-        if let TurnState::Started(_) = battle.rounds().state() {
-            return Err(WeaselError::TurnInProgress);
+        // Verify if the turn can end.
+        if let TurnState::Ready = battle.rounds().state() {
+            return Err(WeaselError::NoTurnInProgress);
+        }
+        // Verify that all actors are acting.
+        for id in &self.ids {
+            if !battle.rounds().is_acting(id) {
+                return Err(WeaselError::ActorNotReady(id.clone()));
+            }
         }
         Ok(())
     }
 
-    fn apply(&self, battle: &mut Battle<R>, _: &mut Option<EventQueue<R>>) {
-        battle.state.rounds.regenerate_model(&self.seed);
+    fn apply(&self, battle: &mut Battle<R>, event_queue: &mut Option<EventQueue<R>>) {
+        end_turn(battle, event_queue);
     }
 
     fn kind(&self) -> EventKind {
-        EventKind::ResetRounds
+        EventKind::TurnTimedOut
     }
 
     fn box_clone(&self) -> Box<dyn Event<R> + Send> {
@@ -733,29 +1031,1009 @@ impl<R: BattleRules + 'static> Event<R> for ResetRounds<R> {
     }
 }
 
-/// Trigger to build and fire a `ResetRounds` event.
-pub struct ResetRoundsTrigger<'a, R, P>
+/// Trigger to build and fire a `TurnTimedOut` event.
+pub struct TurnTimedOutTrigger<'a, R, P>
 where
     R: BattleRules,
     P: EventProcessor<R>,
 {
     processor: &'a mut P,
-    seed: Option<RoundsSeed<R>>,
+    ids: Vec<EntityId<R>>,
 }
 
-impl<'a, R, P> ResetRoundsTrigger<'a, R, P>
+impl<'a, R, P> EventTrigger<'a, R, P> for TurnTimedOutTrigger<'a, R, P>
 where
     R: BattleRules + 'static,
     P: EventProcessor<R>,
 {
-    /// Adds a seed to drive the generation of the new rounds model.
-    pub fn seed(&'a mut self, seed: RoundsSeed<R>) -> &'a mut Self {
-        self.seed = Some(seed);
-        self
-    }
+    fn processor(&'a mut self) -> &'a mut P {
+        self.processor
+    }
+
+    /// Returns a `TurnTimedOut` event.
+    fn event(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(TurnTimedOut {
+            ids: self.ids.clone(),
+        })
+    }
+}
+
+/// Ends the turn in progress.
+fn end_turn<R: BattleRules + 'static>(
+    battle: &mut Battle<R>,
+    event_queue: &mut Option<EventQueue<R>>,
+) {
+    let actors_ids = if let TurnState::Started(actors) = battle.state.rounds.state() {
+        actors.clone()
+    } else {
+        panic!("constraint violated: end turn called when state is not started");
+    };
+    // End the turn for each actor.
+    for actor_id in actors_ids {
+        end_actor_turn(&actor_id, battle, event_queue);
+    }
+    // Set the turn state.
+    battle.state.rounds.set_state(TurnState::Ready);
+    battle.state.rounds.clear_activations();
+    // Increase the turns counter.
+    battle.rounds_mut().increase_completed_turns();
+    battle.metrics.set_turn(battle.rounds().completed_turns());
+    fire_scheduled_events(battle, event_queue, false);
+}
+
+/// Starts the turn of a single actor.
+fn start_actor_turn<R: BattleRules + 'static>(
+    id: &EntityId<R>,
+    battle: &mut Battle<R>,
+    event_queue: &mut Option<EventQueue<R>>,
+) {
+    let watcher = battle.apply_watcher();
+    let metrics = &mut battle.metrics.write_handle();
+    // Get the actor.
+    let actor = battle
+        .state
+        .entities
+        .actor(id)
+        .unwrap_or_else(|| panic!("constraint violated: actor {:?} not found", id));
+    // Invoke `RoundRules` callback.
+    watch!(
+        watcher,
+        "RoundsRules::on_start",
+        battle.state.rounds.rules.on_start(
+            &battle.state.entities,
+            &battle.state.space,
+            &mut battle.state.rounds.model,
+            actor,
+            &mut battle.entropy,
+            metrics,
+        )
+    );
+    // Invoke `CharacterRules` callback.
+    watch!(
+        watcher,
+        "ActorRules::on_turn_start",
+        battle.rules.actor_rules().on_turn_start(
+            &battle.state,
+            actor,
+            event_queue,
+            &mut battle.entropy,
+            metrics,
+        )
+    );
+    // Update all statuses afflicting the actor.
+    update_statuses(id, battle, event_queue)
+        .unwrap_or_else(|err| panic!("constraint violated: {:?}", err));
+    // Advance the actor's pending ability activation.
+    update_pending_activation(id, battle, event_queue);
+}
+
+/// Ends the turn of a single actor.
+fn end_actor_turn<R: BattleRules + 'static>(
+    id: &EntityId<R>,
+    battle: &mut Battle<R>,
+    event_queue: &mut Option<EventQueue<R>>,
+) {
+    let watcher = battle.apply_watcher();
+    let actor = battle
+        .state
+        .entities
+        .actor(id)
+        .unwrap_or_else(|| panic!("constraint violated: actor {:?} not found", id));
+    let metrics = &mut battle.metrics.write_handle();
+    // Invoke `CharacterRules` callback.
+    watch!(
+        watcher,
+        "ActorRules::on_turn_end",
+        battle.rules.actor_rules().on_turn_end(
+            &battle.state,
+            actor,
+            event_queue,
+            &mut battle.entropy,
+            metrics,
+        )
+    );
+    // Invoke `RoundRules` callback.
+    watch!(
+        watcher,
+        "RoundsRules::on_end",
+        battle.state.rounds.on_end(
+            &battle.state.entities,
+            &battle.state.space,
+            actor,
+            &mut battle.entropy,
+            metrics,
+        )
+    );
+    // Check teams' objectives.
+    Battle::check_objectives(
+        &mut battle.state,
+        &battle.rules.team_rules(),
+        &battle.metrics.read_handle(),
+        event_queue,
+        Checkpoint::TurnEnd,
+        watcher,
+    );
+}
+
+/// Event to add an actor to the turn in progress.
+///
+/// The actor starts its turn right away, as if it were part of the original `StartTurn`.
+/// Use this event, for instance, for summoned creatures that act immediately.\
+/// Players can fire it only if they control both the new actor and at least
+/// one of the actors of the current turn.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, rules::empty::*, AddTurnActor, Battle, BattleController, BattleRules,
+///     CreateCreature, CreateTeam, EntityId, EventTrigger, Server, StartTurn,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// let team_id = 1;
+/// CreateTeam::trigger(&mut server, team_id).fire().unwrap();
+/// CreateCreature::trigger(&mut server, 1, team_id, ()).fire().unwrap();
+/// CreateCreature::trigger(&mut server, 2, team_id, ()).fire().unwrap();
+/// StartTurn::trigger(&mut server, EntityId::Creature(1))
+///     .fire()
+///     .unwrap();
+///
+/// AddTurnActor::trigger(&mut server, EntityId::Creature(2))
+///     .fire()
+///     .unwrap();
+/// assert!(server
+///     .battle()
+///     .rounds()
+///     .state()
+///     .has_actor(&EntityId::Creature(2)));
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct AddTurnActor<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "EntityId<R>: Serialize",
+            deserialize = "EntityId<R>: Deserialize<'de>"
+        ))
+    )]
+    id: EntityId<R>,
+}
+
+impl<R: BattleRules> AddTurnActor<R> {
+    /// Returns a trigger for this event.
+    pub fn trigger<P: EventProcessor<R>>(
+        processor: &mut P,
+        id: EntityId<R>,
+    ) -> AddTurnActorTrigger<'_, R, P> {
+        AddTurnActorTrigger { processor, id }
+    }
+
+    /// Returns the id of the actor to add to the turn.
+    pub fn id(&self) -> &EntityId<R> {
+        &self.id
+    }
+}
+
+impl<R: BattleRules> Debug for AddTurnActor<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "AddTurnActor {{ id: {:?} }}", self.id)
+    }
+}
+
+impl<R: BattleRules> Clone for AddTurnActor<R> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+        }
+    }
+}
+
+impl<R: BattleRules + 'static> Event<R> for AddTurnActor<R> {
+    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
+        // Verify that a turn is in progress.
+        let actors = if let TurnState::Started(actors) = battle.rounds().state() {
+            actors
+        } else {
+            return Err(WeaselError::NoTurnInProgress);
+        };
+        // Verify if entity is an actor.
+        if !self.id.is_actor() {
+            return Err(WeaselError::NotAnActor(self.id.clone()));
+        }
+        // Verify that the actor isn't already acting.
+        if actors.contains(&self.id) {
+            return Err(WeaselError::ActorAlreadyInTurn(self.id.clone()));
+        }
+        // Verify if entity exists.
+        if let Some(actor) = battle.entities().actor(&self.id) {
+            // Verify if actor is eligible.
+            let eligible = watch!(
+                battle.verify_watcher(self.kind()),
+                "RoundsRules::eligible",
+                battle.rounds().eligible(actor)
+            );
+            if !eligible {
+                return Err(WeaselError::ActorNotEligible(self.id.clone()));
+            }
+        } else {
+            return Err(WeaselError::EntityNotFound(self.id.clone()));
+        }
+        Ok(())
+    }
+
+    fn apply(&self, battle: &mut Battle<R>, event_queue: &mut Option<EventQueue<R>>) {
+        // Add the actor to the turn state.
+        if let TurnState::Started(actors) = &mut battle.state.rounds.state {
+            actors.insert(self.id.clone());
+        } else {
+            panic!("constraint violated: actor added when state is not started");
+        }
+        start_actor_turn(&self.id, battle, event_queue);
+    }
+
+    fn kind(&self) -> EventKind {
+        EventKind::AddTurnActor
+    }
+
+    fn box_clone(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn rights<'a>(&'a self, battle: &'a Battle<R>) -> EventRights<'a, R> {
+        let actors = match battle.rounds().state() {
+            TurnState::Started(actors) => actors.iter().map(EventRights::Entity).collect(),
+            _ => Vec::new(),
+        };
+        EventRights::All(vec![
+            EventRights::Entity(&self.id),
+            EventRights::Any(actors),
+        ])
+    }
+}
+
+/// Trigger to build and fire an `AddTurnActor` event.
+pub struct AddTurnActorTrigger<'a, R, P>
+where
+    R: BattleRules,
+    P: EventProcessor<R>,
+{
+    processor: &'a mut P,
+    id: EntityId<R>,
+}
+
+impl<'a, R, P> EventTrigger<'a, R, P> for AddTurnActorTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    fn processor(&'a mut self) -> &'a mut P {
+        self.processor
+    }
+
+    /// Returns an `AddTurnActor` event.
+    fn event(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(AddTurnActor {
+            id: self.id.clone(),
+        })
+    }
+}
+
+/// Event to remove an actor from the turn in progress.
+///
+/// The actor ends its turn right away, while the other actors keep acting.
+/// Removing the last actor ends the turn, as `EndTurn` would.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, round::TurnState, rules::empty::*, Battle, BattleController, BattleRules,
+///     CreateCreature, CreateTeam, EntityId, EventTrigger, RemoveTurnActor, Server, StartTurn,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// let team_id = 1;
+/// CreateTeam::trigger(&mut server, team_id).fire().unwrap();
+/// CreateCreature::trigger(&mut server, 1, team_id, ()).fire().unwrap();
+/// StartTurn::trigger(&mut server, EntityId::Creature(1))
+///     .fire()
+///     .unwrap();
+///
+/// RemoveTurnActor::trigger(&mut server, EntityId::Creature(1))
+///     .fire()
+///     .unwrap();
+/// assert_eq!(*server.battle().rounds().state(), TurnState::Ready);
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct RemoveTurnActor<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "EntityId<R>: Serialize",
+            deserialize = "EntityId<R>: Deserialize<'de>"
+        ))
+    )]
+    id: EntityId<R>,
+}
+
+impl<R: BattleRules> RemoveTurnActor<R> {
+    /// Returns a trigger for this event.
+    pub fn trigger<P: EventProcessor<R>>(
+        processor: &mut P,
+        id: EntityId<R>,
+    ) -> RemoveTurnActorTrigger<'_, R, P> {
+        RemoveTurnActorTrigger { processor, id }
+    }
+
+    /// Returns the id of the actor to remove from the turn.
+    pub fn id(&self) -> &EntityId<R> {
+        &self.id
+    }
+}
+
+impl<R: BattleRules> Debug for RemoveTurnActor<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "RemoveTurnActor {{ id: {:?} }}", self.id)
+    }
+}
+
+impl<R: BattleRules> Clone for RemoveTurnActor<R> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+        }
+    }
+}
+
+impl<R: BattleRules + 'static> Event<R> for RemoveTurnActor<R> {
+    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
+        // Verify that a turn is in progress.
+        if let TurnState::Ready = battle.rounds().state() {
+            return Err(WeaselError::NoTurnInProgress);
+        }
+        // Verify that the actor is acting.
+        if !battle.rounds().is_acting(&self.id) {
+            return Err(WeaselError::ActorNotReady(self.id.clone()));
+        }
+        Ok(())
+    }
+
+    fn apply(&self, battle: &mut Battle<R>, event_queue: &mut Option<EventQueue<R>>) {
+        end_actor_turn(&self.id, battle, event_queue);
+        // Remove the actor from the turn state.
+        let last = if let TurnState::Started(actors) = &mut battle.state.rounds.state {
+            actors.shift_remove(&self.id);
+            actors.is_empty()
+        } else {
+            panic!("constraint violated: actor removed when state is not started");
+        };
+        battle.state.rounds.remove_activations(&self.id);
+        // End the turn if no actor is left.
+        if last {
+            battle.state.rounds.set_state(TurnState::Ready);
+            battle.state.rounds.clear_activations();
+            battle.rounds_mut().increase_completed_turns();
+            battle.metrics.set_turn(battle.rounds().completed_turns());
+        }
+    }
+
+    fn kind(&self) -> EventKind {
+        EventKind::RemoveTurnActor
+    }
+
+    fn box_clone(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn rights<'a>(&'a self, _battle: &'a Battle<R>) -> EventRights<'a, R> {
+        EventRights::Entity(&self.id)
+    }
+}
+
+/// Trigger to build and fire a `RemoveTurnActor` event.
+pub struct RemoveTurnActorTrigger<'a, R, P>
+where
+    R: BattleRules,
+    P: EventProcessor<R>,
+{
+    processor: &'a mut P,
+    id: EntityId<R>,
+}
+
+impl<'a, R, P> EventTrigger<'a, R, P> for RemoveTurnActorTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    fn processor(&'a mut self) -> &'a mut P {
+        self.processor
+    }
+
+    /// Returns a `RemoveTurnActor` event.
+    fn event(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(RemoveTurnActor {
+            id: self.id.clone(),
+        })
+    }
+}
+
+/// Event to reset the rounds model.
+///
+/// This event can be fired only if no turn is in progress.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, EventKind,
+///     EventTrigger, ResetRounds, Server,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// ResetRounds::trigger(&mut server).fire().unwrap();
+/// assert_eq!(
+///     server.battle().history().events()[0].kind(),
+///     EventKind::ResetRounds
+/// );
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct ResetRounds<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Option<RoundsSeed<R>>: Serialize",
+            deserialize = "Option<RoundsSeed<R>>: Deserialize<'de>"
+        ))
+    )]
+    seed: Option<RoundsSeed<R>>,
+}
+
+impl<R: BattleRules> ResetRounds<R> {
+    /// Returns a trigger for this event.
+    pub fn trigger<P: EventProcessor<R>>(processor: &mut P) -> ResetRoundsTrigger<R, P> {
+        ResetRoundsTrigger {
+            processor,
+            seed: None,
+        }
+    }
+
+    /// Returns the new seed.
+    pub fn seed(&self) -> &Option<RoundsSeed<R>> {
+        &self.seed
+    }
+}
+
+impl<R: BattleRules> Debug for ResetRounds<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "ResetRounds {{ seed: {:?} }}", self.seed)
+    }
+}
+
+impl<R: BattleRules> Clone for ResetRounds<R> {
+    fn clone(&self) -> Self {
+        Self {
+            seed: self.seed.clone(),
+        }
+    }
+}
+
+impl<R: BattleRules + 'static> Event<R> for ResetRounds<R> {
+    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
+        // Verify that no turn is in progress.
+        if let TurnState::Started(_) = battle.rounds().state() {
+            return Err(WeaselError::TurnInProgress);
+        }
+        Ok(())
+    }
+
+    fn apply(&self, battle: &mut Battle<R>, _: &mut Option<EventQueue<R>>) {
+        watch!(
+            battle.apply_watcher(),
+            "RoundsRules::generate_model",
+            battle.state.rounds.regenerate_model(&self.seed)
+        );
+    }
+
+    fn kind(&self) -> EventKind {
+        EventKind::ResetRounds
+    }
+
+    fn box_clone(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Trigger to build and fire a `ResetRounds` event.
+pub struct ResetRoundsTrigger<'a, R, P>
+where
+    R: BattleRules,
+    P: EventProcessor<R>,
+{
+    processor: &'a mut P,
+    seed: Option<RoundsSeed<R>>,
+}
+
+impl<'a, R, P> ResetRoundsTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    /// Adds a seed to drive the generation of the new rounds model.
+    pub fn seed(&'a mut self, seed: RoundsSeed<R>) -> &'a mut Self {
+        self.seed = Some(seed);
+        self
+    }
+}
+
+impl<'a, R, P> EventTrigger<'a, R, P> for ResetRoundsTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    fn processor(&'a mut self) -> &'a mut P {
+        self.processor
+    }
+
+    /// Returns a `ResetRounds` event.
+    fn event(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(ResetRounds {
+            seed: self.seed.clone(),
+        })
+    }
+}
+
+/// Event to perform a collective turn for the environment's inanimate entities.\
+/// The purpose of this event is to update the statuses of all objects and to let
+/// the space rules move them, through `SpaceRules::on_environment_turn`.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, EnvironmentTurn,
+///     EventTrigger, Server,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// EnvironmentTurn::trigger(&mut server).fire().unwrap();
+/// assert_eq!(
+///     server.battle().rounds().completed_turns(),
+///     1
+/// );
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct EnvironmentTurn<R> {
+    #[cfg_attr(feature = "serialization", serde(skip))]
+    _phantom: PhantomData<R>,
+}
+
+impl<R: BattleRules> EnvironmentTurn<R> {
+    /// Returns a trigger for this event.
+    pub fn trigger<P: EventProcessor<R>>(processor: &mut P) -> EnvironmentTurnTrigger<R, P> {
+        EnvironmentTurnTrigger {
+            processor,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<R> Debug for EnvironmentTurn<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "EnvironmentTurn {{ }}")
+    }
+}
+
+impl<R> Clone for EnvironmentTurn<R> {
+    fn clone(&self) -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<R: BattleRules + 'static> Event<R> for EnvironmentTurn<R> {
+    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
+        // Verify that no other turn is in progress.
+        if let TurnState::Started(_) = battle.rounds().state() {
+            return Err(WeaselError::TurnInProgress);
+        }
+        Ok(())
+    }
+
+    fn apply(&self, battle: &mut Battle<R>, event_queue: &mut Option<EventQueue<R>>) {
+        // Update the statuses of all objects.
+        let objects_ids: Vec<_> = battle
+            .entities()
+            .objects()
+            .map(|object| object.entity_id())
+            .cloned()
+            .collect();
+        for object_id in objects_ids {
+            update_statuses(&object_id, battle, event_queue)
+                .unwrap_or_else(|err| panic!("constraint violated: {:?}", err));
+        }
+        // Let the space rules move the objects.
+        for object in battle.state.entities.objects() {
+            watch!(
+                battle.apply_watcher(),
+                "SpaceRules::on_environment_turn",
+                battle.state.space.rules().on_environment_turn(
+                    battle.state.space.model(),
+                    object,
+                    event_queue,
+                    &mut battle.entropy,
+                    &mut battle.metrics.write_handle(),
+                )
+            );
+        }
+        // The turn started and ended, atomically.
+        battle.rounds_mut().increase_completed_turns();
+        battle.metrics.set_turn(battle.rounds().completed_turns());
+        fire_scheduled_events(battle, event_queue, false);
+    }
+
+    fn kind(&self) -> EventKind {
+        EventKind::EnvironmentTurn
+    }
+
+    fn box_clone(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Trigger to build and fire an `EnvironmentTurn` event.
+pub struct EnvironmentTurnTrigger<'a, R, P>
+where
+    R: BattleRules,
+    P: EventProcessor<R>,
+{
+    processor: &'a mut P,
+    _phantom: PhantomData<R>,
+}
+
+impl<'a, R, P> EventTrigger<'a, R, P> for EnvironmentTurnTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    fn processor(&'a mut self) -> &'a mut P {
+        self.processor
+    }
+
+    /// Returns an `EnvironmentTurn` event.
+    fn event(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(EnvironmentTurn {
+            _phantom: self._phantom,
+        })
+    }
+}
+
+/// Event to trigger the end of the current round.
+///
+/// A round is a logical separator between turns and it can contain any
+/// number of these.\
+/// Managing rounds is optional and not required in order to start or end turns.
+///
+/// The first round is implicitly started at the beginning of the battle.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, EndRound,
+///     EventTrigger, Server,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// EndRound::trigger(&mut server).fire().unwrap();
+/// assert_eq!(
+///     server.battle().rounds().completed_rounds(),
+///     1
+/// );
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct EndRound<R> {
+    #[cfg_attr(feature = "serialization", serde(skip))]
+    _phantom: PhantomData<R>,
+}
+
+impl<R: BattleRules> EndRound<R> {
+    /// Returns a trigger for this event.
+    pub fn trigger<P: EventProcessor<R>>(processor: &mut P) -> EndRoundTrigger<R, P> {
+        EndRoundTrigger {
+            processor,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<R> Debug for EndRound<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "EndRound {{ }}")
+    }
+}
+
+impl<R> Clone for EndRound<R> {
+    fn clone(&self) -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<R: BattleRules + 'static> Event<R> for EndRound<R> {
+    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
+        // Verify that no turn is in progress.
+        if let TurnState::Started(_) = battle.rounds().state() {
+            return Err(WeaselError::TurnInProgress);
+        }
+        Ok(())
+    }
+
+    fn apply(&self, battle: &mut Battle<R>, event_queue: &mut Option<EventQueue<R>>) {
+        let watcher = battle.apply_watcher();
+        // Let the teams' rules handle the end of the round.
+        for team in battle.state.entities.teams_mut() {
+            watch!(
+                watcher,
+                "TeamRules::on_round_end",
+                battle.rules.team_rules().on_round_end(
+                    team,
+                    event_queue,
+                    &mut battle.entropy,
+                    &mut battle.metrics.write_handle(),
+                )
+            );
+        }
+        battle.rounds_mut().increase_completed_rounds();
+        battle.metrics.take_snapshot();
+        // Then let them handle the start of the new round.
+        for team in battle.state.entities.teams_mut() {
+            watch!(
+                watcher,
+                "TeamRules::on_round_start",
+                battle.rules.team_rules().on_round_start(
+                    team,
+                    event_queue,
+                    &mut battle.entropy,
+                    &mut battle.metrics.write_handle(),
+                )
+            );
+        }
+        fire_scheduled_events(battle, event_queue, true);
+        // Remove the entities whose time to live expired.
+        for id in battle.state.entities.decrease_time_to_live() {
+            RemoveEntity::trigger(event_queue, id).fire();
+        }
+        // Conclude the battle once the round limit is reached.
+        if battle.rounds().round_limit() == Some(battle.rounds().completed_rounds()) {
+            let metrics = battle.metrics.read_handle();
+            for team in battle
+                .entities()
+                .teams()
+                .filter(|team| team.conclusion().is_none())
+            {
+                let conclusion = watch!(
+                    watcher,
+                    "TeamRules::conclusion_on_round_limit",
+                    battle.rules.team_rules().conclusion_on_round_limit(
+                        &battle.state,
+                        team,
+                        &metrics,
+                    )
+                );
+                if let Some(conclusion) = conclusion {
+                    ConcludeObjectives::trigger(event_queue, team.id().clone(), conclusion).fire();
+                }
+            }
+            EndBattle::trigger(event_queue)
+                .reason(EndReason::Timeout)
+                .fire();
+        }
+    }
+
+    fn kind(&self) -> EventKind {
+        EventKind::EndRound
+    }
+
+    fn box_clone(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Trigger to build and fire an `EndRound` event.
+pub struct EndRoundTrigger<'a, R, P>
+where
+    R: BattleRules,
+    P: EventProcessor<R>,
+{
+    processor: &'a mut P,
+    _phantom: PhantomData<R>,
+}
+
+impl<'a, R, P> EventTrigger<'a, R, P> for EndRoundTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    fn processor(&'a mut self) -> &'a mut P {
+        self.processor
+    }
+
+    /// Returns an `EndRound` event.
+    fn event(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(EndRound {
+            _phantom: self._phantom,
+        })
+    }
+}
+
+/// Event to advance the simulation by one tick.
+///
+/// Ticks are meant for hybrid real-time and turn-based games, such as those with
+/// active time battle systems. Each tick invokes `RoundsRules::on_tick`, so that the
+/// passing of time is recorded in the history and can be replayed deterministically.\
+/// The number of each tick must be greater than the number of the previous one.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, EventTrigger,
+///     Server, Tick,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// Tick::trigger(&mut server, 1).fire().unwrap();
+/// assert_eq!(server.battle().rounds().ticks(), 1);
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Tick<R> {
+    tick: TicksCount,
+
+    #[cfg_attr(feature = "serialization", serde(skip))]
+    _phantom: PhantomData<R>,
+}
+
+impl<R: BattleRules> Tick<R> {
+    /// Returns a trigger for this event.
+    pub fn trigger<P: EventProcessor<R>>(processor: &mut P, tick: TicksCount) -> TickTrigger<R, P> {
+        TickTrigger {
+            processor,
+            tick,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the number of this tick.
+    pub fn tick(&self) -> TicksCount {
+        self.tick
+    }
+}
+
+impl<R> Debug for Tick<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "Tick {{ tick: {:?} }}", self.tick)
+    }
+}
+
+impl<R> Clone for Tick<R> {
+    fn clone(&self) -> Self {
+        Self {
+            tick: self.tick,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<R: BattleRules + 'static> Event<R> for Tick<R> {
+    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
+        // Verify that the ticks' counter increases.
+        let ticks = battle.rounds().ticks();
+        if self.tick <= ticks {
+            return Err(WeaselError::InvalidTick(ticks, self.tick));
+        }
+        Ok(())
+    }
+
+    fn apply(&self, battle: &mut Battle<R>, event_queue: &mut Option<EventQueue<R>>) {
+        watch!(
+            battle.apply_watcher(),
+            "RoundsRules::on_tick",
+            battle.state.rounds.on_tick(
+                &battle.state.entities,
+                self.tick,
+                event_queue,
+                &mut battle.entropy,
+                &mut battle.metrics.write_handle(),
+            )
+        );
+    }
+
+    fn kind(&self) -> EventKind {
+        EventKind::Tick
+    }
+
+    fn box_clone(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
 }
 
-impl<'a, R, P> EventTrigger<'a, R, P> for ResetRoundsTrigger<'a, R, P>
+/// Trigger to build and fire a `Tick` event.
+pub struct TickTrigger<'a, R, P>
+where
+    R: BattleRules,
+    P: EventProcessor<R>,
+{
+    processor: &'a mut P,
+    tick: TicksCount,
+    _phantom: PhantomData<R>,
+}
+
+impl<'a, R, P> EventTrigger<'a, R, P> for TickTrigger<'a, R, P>
 where
     R: BattleRules + 'static,
     P: EventProcessor<R>,
@@ -764,22 +2042,28 @@ where
         self.processor
     }
 
-    /// Returns a `ResetRounds` event.
+    /// Returns a `Tick` event.
     fn event(&self) -> Box<dyn Event<R> + Send> {
-        Box::new(ResetRounds {
-            seed: self.seed.clone(),
+        Box::new(Tick {
+            tick: self.tick,
+            _phantom: self._phantom,
         })
     }
 }
 
-/// Event to perform a collective turn for the environment's inanimate entities.\
-/// The purpose of this event is to update the statuses of all objects.
+/// Event to restore the progress of rounds and turns, without invoking any rules' callback.
+///
+/// It sets the number of completed rounds and turns, the actors of the current turn
+/// together with the number of abilities they have activated, the turn in which
+/// each ability of a creature was last activated and the pending ability activations.\
+/// The server synthesizes this event to bootstrap new clients, in place of the events
+/// that lead to the current turn. See `Server::bootstrap_events_for_new_client`.
 ///
 /// # Examples
 /// ```
 /// use weasel::{
-///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, EnvironmentTurn,
-///     EventTrigger, Server,
+///     battle_rules, round::TurnState, rules::empty::*, Battle, BattleController, BattleRules,
+///     CreateCreature, CreateTeam, EntityId, EventTrigger, RestoreRounds, Server,
 /// };
 ///
 /// battle_rules! {}
@@ -787,69 +2071,206 @@ where
 /// let battle = Battle::builder(CustomRules::new()).build();
 /// let mut server = Server::builder(battle).build();
 ///
-/// EnvironmentTurn::trigger(&mut server).fire().unwrap();
-/// assert_eq!(
-///     server.battle().rounds().completed_turns(),
-///     1
-/// );
+/// let team_id = 1;
+/// CreateTeam::trigger(&mut server, team_id).fire().unwrap();
+/// let creature_id = 1;
+/// CreateCreature::trigger(&mut server, creature_id, team_id, ())
+///     .fire()
+///     .unwrap();
+///
+/// RestoreRounds::trigger(&mut server, 2, 7)
+///     .actors(vec![EntityId::Creature(creature_id)])
+///     .fire()
+///     .unwrap();
+/// assert_eq!(server.battle().rounds().completed_rounds(), 2);
+/// assert_eq!(server.battle().rounds().completed_turns(), 7);
+/// assert!(server.battle().rounds().is_acting(&EntityId::Creature(creature_id)));
 /// ```
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
-pub struct EnvironmentTurn<R> {
-    #[cfg_attr(feature = "serialization", serde(skip))]
-    _phantom: PhantomData<R>,
+pub struct RestoreRounds<R: BattleRules> {
+    rounds: RoundsCount,
+    turns: TurnsCount,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Option<Vec<EntityId<R>>>: Serialize",
+            deserialize = "Option<Vec<EntityId<R>>>: Deserialize<'de>"
+        ))
+    )]
+    actors: Option<Vec<EntityId<R>>>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Vec<(EntityId<R>, u32)>: Serialize",
+            deserialize = "Vec<(EntityId<R>, u32)>: Deserialize<'de>"
+        ))
+    )]
+    activations: Vec<(EntityId<R>, u32)>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Vec<(CreatureId<R>, AbilityId<R>, TurnsCount)>: Serialize",
+            deserialize = "Vec<(CreatureId<R>, AbilityId<R>, TurnsCount)>: Deserialize<'de>"
+        ))
+    )]
+    cooldowns: Vec<(CreatureId<R>, AbilityId<R>, TurnsCount)>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Vec<PendingActivation<R>>: Serialize",
+            deserialize = "Vec<PendingActivation<R>>: Deserialize<'de>"
+        ))
+    )]
+    pending: Vec<PendingActivation<R>>,
 }
 
-impl<R: BattleRules> EnvironmentTurn<R> {
+impl<R: BattleRules> RestoreRounds<R> {
     /// Returns a trigger for this event.
-    pub fn trigger<P: EventProcessor<R>>(processor: &mut P) -> EnvironmentTurnTrigger<R, P> {
-        EnvironmentTurnTrigger {
+    pub fn trigger<P: EventProcessor<R>>(
+        processor: &mut P,
+        rounds: RoundsCount,
+        turns: TurnsCount,
+    ) -> RestoreRoundsTrigger<'_, R, P> {
+        RestoreRoundsTrigger {
             processor,
-            _phantom: PhantomData,
+            rounds,
+            turns,
+            actors: None,
+            activations: Vec::new(),
+            cooldowns: Vec::new(),
+            pending: Vec::new(),
         }
     }
+
+    /// Returns the number of completed rounds.
+    pub fn rounds(&self) -> RoundsCount {
+        self.rounds
+    }
+
+    /// Returns the number of completed turns.
+    pub fn turns(&self) -> TurnsCount {
+        self.turns
+    }
+
+    /// Returns the actors of the current turn, or `None` if no turn is in progress.
+    pub fn actors(&self) -> &Option<Vec<EntityId<R>>> {
+        &self.actors
+    }
+
+    /// Returns the number of abilities activated by each actor in the current turn.
+    pub fn activations(&self) -> &[(EntityId<R>, u32)] {
+        &self.activations
+    }
+
+    /// Returns the number of turns completed when each ability of a creature
+    /// was last activated.
+    pub fn cooldowns(&self) -> &[(CreatureId<R>, AbilityId<R>, TurnsCount)] {
+        &self.cooldowns
+    }
+
+    /// Returns the pending ability activations.
+    pub fn pending(&self) -> &[PendingActivation<R>] {
+        &self.pending
+    }
 }
 
-impl<R> Debug for EnvironmentTurn<R> {
+impl<R: BattleRules> Debug for RestoreRounds<R> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        write!(f, "EnvironmentTurn {{ }}")
+        write!(
+            f,
+            "RestoreRounds {{ rounds: {:?}, turns: {:?}, actors: {:?}, activations: {:?}, \
+             cooldowns: {:?}, pending: {:?} }}",
+            self.rounds, self.turns, self.actors, self.activations, self.cooldowns, self.pending
+        )
     }
 }
 
-impl<R> Clone for EnvironmentTurn<R> {
+impl<R: BattleRules> Clone for RestoreRounds<R> {
     fn clone(&self) -> Self {
         Self {
-            _phantom: PhantomData,
+            rounds: self.rounds,
+            turns: self.turns,
+            actors: self.actors.clone(),
+            activations: self.activations.clone(),
+            cooldowns: self.cooldowns.clone(),
+            pending: self.pending.clone(),
         }
     }
 }
 
-impl<R: BattleRules + 'static> Event<R> for EnvironmentTurn<R> {
+impl<R: BattleRules + 'static> Event<R> for RestoreRounds<R> {
     fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
-        // Verify that no other turn is in progress.
-        if let TurnState::Started(_) = battle.rounds().state() {
-            return Err(WeaselError::TurnInProgress);
+        // Verify if all actors exist.
+        let actors = self.actors.iter().flatten();
+        let activations = self.activations.iter().map(|(id, _)| id);
+        let pending = self.pending.iter().map(PendingActivation::entity_id);
+        for id in actors.chain(activations).chain(pending) {
+            if !id.is_actor() {
+                return Err(WeaselError::NotAnActor(id.clone()));
+            }
+            if battle.entities().actor(id).is_none() {
+                return Err(WeaselError::EntityNotFound(id.clone()));
+            }
+        }
+        // Verify if all abilities exist.
+        for (creature_id, ability_id, _) in &self.cooldowns {
+            let creature = battle
+                .entities()
+                .creature(creature_id)
+                .ok_or_else(|| WeaselError::CreatureNotFound(creature_id.clone()))?;
+            if creature.ability(ability_id).is_none() {
+                return Err(WeaselError::AbilityNotKnown(
+                    EntityId::Creature(creature_id.clone()),
+                    ability_id.clone(),
+                ));
+            }
+        }
+        // Verify if all pending abilities are known by their actors.
+        for pending in &self.pending {
+            let actor = battle.entities().actor(pending.entity_id()).unwrap();
+            if actor.ability(pending.ability_id()).is_none() {
+                return Err(WeaselError::AbilityNotKnown(
+                    pending.entity_id().clone(),
+                    pending.ability_id().clone(),
+                ));
+            }
         }
         Ok(())
     }
 
-    fn apply(&self, battle: &mut Battle<R>, event_queue: &mut Option<EventQueue<R>>) {
-        // Update the statuses of all objects.
-        let objects_ids: Vec<_> = battle
-            .entities()
-            .objects()
-            .map(|object| object.entity_id())
-            .cloned()
-            .collect();
-        for object_id in objects_ids {
-            update_statuses(&object_id, battle, event_queue)
-                .unwrap_or_else(|err| panic!("constraint violated: {:?}", err));
+    fn apply(&self, battle: &mut Battle<R>, _: &mut Option<EventQueue<R>>) {
+        let rounds = &mut battle.state.rounds;
+        rounds.restore_counters(self.rounds, self.turns);
+        match &self.actors {
+            Some(actors) => rounds.set_state(TurnState::Started(actors.iter().cloned().collect())),
+            None => rounds.set_state(TurnState::Ready),
+        }
+        rounds.clear_activations();
+        for (id, count) in &self.activations {
+            rounds.set_activations(id.clone(), *count);
+        }
+        rounds.clear_pending_activations();
+        for pending in &self.pending {
+            rounds.add_pending_activation(pending.clone());
+        }
+        for (creature_id, ability_id, turn) in &self.cooldowns {
+            battle
+                .state
+                .entities
+                .creature_mut(creature_id)
+                .unwrap_or_else(|| {
+                    panic!("constraint violated: creature {:?} not found", creature_id)
+                })
+                .mark_ability_used(ability_id, *turn);
         }
-        // The turn started and ended, atomically.
-        battle.rounds_mut().increase_completed_turns();
     }
 
     fn kind(&self) -> EventKind {
-        EventKind::EnvironmentTurn
+        EventKind::RestoreRounds
     }
 
     fn box_clone(&self) -> Box<dyn Event<R> + Send> {
@@ -861,17 +2282,56 @@ impl<R: BattleRules + 'static> Event<R> for EnvironmentTurn<R> {
     }
 }
 
-/// Trigger to build and fire an `EnvironmentTurn` event.
-pub struct EnvironmentTurnTrigger<'a, R, P>
+/// Trigger to build and fire a `RestoreRounds` event.
+pub struct RestoreRoundsTrigger<'a, R, P>
 where
     R: BattleRules,
     P: EventProcessor<R>,
 {
     processor: &'a mut P,
-    _phantom: PhantomData<R>,
+    pub(crate) rounds: RoundsCount,
+    pub(crate) turns: TurnsCount,
+    pub(crate) actors: Option<Vec<EntityId<R>>>,
+    pub(crate) activations: Vec<(EntityId<R>, u32)>,
+    pub(crate) cooldowns: Vec<(CreatureId<R>, AbilityId<R>, TurnsCount)>,
+    pub(crate) pending: Vec<PendingActivation<R>>,
 }
 
-impl<'a, R, P> EventTrigger<'a, R, P> for EnvironmentTurnTrigger<'a, R, P>
+impl<'a, R, P> RestoreRoundsTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    /// Sets the actors of the turn in progress.
+    pub fn actors(&'a mut self, actors: Vec<EntityId<R>>) -> &'a mut Self {
+        self.actors = Some(actors);
+        self
+    }
+
+    /// Sets the number of abilities activated by each actor in the current turn.
+    pub fn activations(&'a mut self, activations: Vec<(EntityId<R>, u32)>) -> &'a mut Self {
+        self.activations = activations;
+        self
+    }
+
+    /// Sets the number of turns completed when each ability of a creature
+    /// was last activated.
+    pub fn cooldowns(
+        &'a mut self,
+        cooldowns: Vec<(CreatureId<R>, AbilityId<R>, TurnsCount)>,
+    ) -> &'a mut Self {
+        self.cooldowns = cooldowns;
+        self
+    }
+
+    /// Sets the pending ability activations.
+    pub fn pending(&'a mut self, pending: Vec<PendingActivation<R>>) -> &'a mut Self {
+        self.pending = pending;
+        self
+    }
+}
+
+impl<'a, R, P> EventTrigger<'a, R, P> for RestoreRoundsTrigger<'a, R, P>
 where
     R: BattleRules + 'static,
     P: EventProcessor<R>,
@@ -880,27 +2340,116 @@ where
         self.processor
     }
 
-    /// Returns an `EnvironmentTurn` event.
+    /// Returns a `RestoreRounds` event.
     fn event(&self) -> Box<dyn Event<R> + Send> {
-        Box::new(EnvironmentTurn {
-            _phantom: self._phantom,
+        Box::new(RestoreRounds {
+            rounds: self.rounds,
+            turns: self.turns,
+            actors: self.actors.clone(),
+            activations: self.activations.clone(),
+            cooldowns: self.cooldowns.clone(),
+            pending: self.pending.clone(),
         })
     }
 }
 
-/// Event to trigger the end of the current round.
+/// Delay after which a scheduled event is fired.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum Delay {
+    /// The event is fired at the end of the given number of turns.
+    Turns(TurnsCount),
+    /// The event is fired at the end of the given number of rounds.
+    Rounds(RoundsCount),
+}
+
+impl Delay {
+    /// Returns true if the delay is expired.
+    pub fn is_expired(self) -> bool {
+        match self {
+            Delay::Turns(count) => count == 0,
+            Delay::Rounds(count) => count == 0,
+        }
+    }
+}
+
+/// An event waiting to be fired once its delay expires.
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct ScheduledEvent<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(
+            bound(
+                serialize = "R: 'static, FlatEvent<R>: Serialize",
+                deserialize = "R: 'static, FlatEvent<R>: Deserialize<'de>"
+            ),
+            serialize_with = "crate::serde::serialize_boxed_event",
+            deserialize_with = "crate::serde::deserialize_boxed_event"
+        )
+    )]
+    event: Box<dyn Event<R> + Send>,
+    delay: Delay,
+    origin: EventId,
+}
+
+impl<R: BattleRules> ScheduledEvent<R> {
+    /// Returns the event that will be fired.
+    pub fn event(&self) -> &(dyn Event<R> + Send) {
+        &*self.event
+    }
+
+    /// Returns the remaining delay.
+    pub fn delay(&self) -> Delay {
+        self.delay
+    }
+
+    /// Returns the id of the `ScheduleEvent` event that scheduled this event.
+    pub fn origin(&self) -> EventId {
+        self.origin
+    }
+}
+
+impl<R: BattleRules> Debug for ScheduledEvent<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "ScheduledEvent {{ event: {:?}, delay: {:?}, origin: {:?} }}",
+            self.event, self.delay, self.origin
+        )
+    }
+}
+
+/// Fires all scheduled events whose delay expired after the end of a turn or round.
 ///
-/// A round is a logical separator between turns and it can contain any
-/// number of these.\
-/// Managing rounds is optional and not required in order to start or end turns.
+/// Events are inserted into `event_queue` with the id of the event that scheduled them
+/// as origin. They'll be verified once processed, as any other derived event.
+fn fire_scheduled_events<R: BattleRules + 'static>(
+    battle: &mut Battle<R>,
+    event_queue: &mut Option<EventQueue<R>>,
+    round: bool,
+) {
+    for scheduled in battle.state.rounds.advance_scheduled_events(round) {
+        if let Some(queue) = event_queue {
+            LinkedQueue::new(queue, Some(scheduled.origin))
+                .process(EventPrototype::new(scheduled.event));
+        }
+    }
+}
+
+/// Event to schedule another event to be fired after a number of turns or rounds.
 ///
-/// The first round is implicitly started at the beginning of the battle.
+/// The scheduled event is kept by the rounds module until its delay expires.
+/// Then, it's fired as a derived event of this one and verified against the
+/// state of the battle at that time.\
+/// Turns are counted by `EndTurn` and `EnvironmentTurn`, rounds by `EndRound`.
+///
+/// Only the server can schedule events.
 ///
 /// # Examples
 /// ```
 /// use weasel::{
-///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, EndRound,
-///     EventTrigger, Server,
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateTeam,
+///     EndRound, EventTrigger, ScheduleEvent, Server,
 /// };
 ///
 /// battle_rules! {}
@@ -908,57 +2457,98 @@ where
 /// let battle = Battle::builder(CustomRules::new()).build();
 /// let mut server = Server::builder(battle).build();
 ///
+/// let team_id = 1;
+/// let event = CreateTeam::trigger(&mut (), team_id).event();
+/// ScheduleEvent::trigger(&mut server, event)
+///     .after_rounds(2)
+///     .fire()
+///     .unwrap();
+/// assert_eq!(server.battle().rounds().scheduled_events().count(), 1);
+///
 /// EndRound::trigger(&mut server).fire().unwrap();
-/// assert_eq!(
-///     server.battle().rounds().completed_rounds(),
-///     1
-/// );
+/// assert!(server.battle().entities().team(&team_id).is_none());
+/// EndRound::trigger(&mut server).fire().unwrap();
+/// assert!(server.battle().entities().team(&team_id).is_some());
 /// ```
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
-pub struct EndRound<R> {
-    #[cfg_attr(feature = "serialization", serde(skip))]
-    _phantom: PhantomData<R>,
+pub struct ScheduleEvent<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(
+            bound(
+                serialize = "R: 'static, FlatEvent<R>: Serialize",
+                deserialize = "R: 'static, FlatEvent<R>: Deserialize<'de>"
+            ),
+            serialize_with = "crate::serde::serialize_boxed_event",
+            deserialize_with = "crate::serde::deserialize_boxed_event"
+        )
+    )]
+    event: Box<dyn Event<R> + Send>,
+    delay: Delay,
 }
 
-impl<R: BattleRules> EndRound<R> {
+impl<R: BattleRules> ScheduleEvent<R> {
     /// Returns a trigger for this event.
-    pub fn trigger<P: EventProcessor<R>>(processor: &mut P) -> EndRoundTrigger<R, P> {
-        EndRoundTrigger {
+    ///
+    /// `event` is fired after one turn, unless a different delay is specified.
+    pub fn trigger<P: EventProcessor<R>>(
+        processor: &mut P,
+        event: Box<dyn Event<R> + Send>,
+    ) -> ScheduleEventTrigger<'_, R, P> {
+        ScheduleEventTrigger {
             processor,
-            _phantom: PhantomData,
+            event,
+            delay: Delay::Turns(1),
         }
     }
+
+    /// Returns the event to be scheduled.
+    pub fn event(&self) -> &(dyn Event<R> + Send) {
+        &*self.event
+    }
+
+    /// Returns the delay after which the event is fired.
+    pub fn delay(&self) -> Delay {
+        self.delay
+    }
 }
 
-impl<R> Debug for EndRound<R> {
+impl<R: BattleRules> Debug for ScheduleEvent<R> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        write!(f, "EndRound {{ }}")
+        write!(
+            f,
+            "ScheduleEvent {{ event: {:?}, delay: {:?} }}",
+            self.event, self.delay
+        )
     }
 }
 
-impl<R> Clone for EndRound<R> {
+impl<R: BattleRules> Clone for ScheduleEvent<R> {
     fn clone(&self) -> Self {
         Self {
-            _phantom: PhantomData,
+            event: self.event.box_clone(),
+            delay: self.delay,
         }
     }
 }
 
-impl<R: BattleRules + 'static> Event<R> for EndRound<R> {
-    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
-        // Verify that no turn is in progress.
-        if let TurnState::Started(_) = battle.rounds().state() {
-            return Err(WeaselError::TurnInProgress);
-        }
+impl<R: BattleRules + 'static> Event<R> for ScheduleEvent<R> {
+    fn verify(&self, _: &Battle<R>) -> WeaselResult<(), R> {
+        // The scheduled event is verified only when fired.
         Ok(())
     }
 
     fn apply(&self, battle: &mut Battle<R>, _: &mut Option<EventQueue<R>>) {
-        battle.rounds_mut().increase_completed_rounds();
+        let origin = battle.history.next_id();
+        battle.state.rounds.add_scheduled_event(ScheduledEvent {
+            event: self.event.box_clone(),
+            delay: self.delay,
+            origin,
+        });
     }
 
     fn kind(&self) -> EventKind {
-        EventKind::EndRound
+        EventKind::ScheduleEvent
     }
 
     fn box_clone(&self) -> Box<dyn Event<R> + Send> {
@@ -968,19 +2558,42 @@ impl<R: BattleRules + 'static> Event<R> for EndRound<R> {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn rights<'a>(&'a self, _: &'a Battle<R>) -> EventRights<'a, R> {
+        EventRights::Server
+    }
 }
 
-/// Trigger to build and fire an `EndRound` event.
-pub struct EndRoundTrigger<'a, R, P>
+/// Trigger to build and fire a `ScheduleEvent` event.
+pub struct ScheduleEventTrigger<'a, R, P>
 where
     R: BattleRules,
     P: EventProcessor<R>,
 {
     processor: &'a mut P,
-    _phantom: PhantomData<R>,
+    event: Box<dyn Event<R> + Send>,
+    pub(crate) delay: Delay,
 }
 
-impl<'a, R, P> EventTrigger<'a, R, P> for EndRoundTrigger<'a, R, P>
+impl<'a, R, P> ScheduleEventTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    /// Fires the scheduled event at the end of the given number of turns.
+    pub fn after_turns(&'a mut self, turns: TurnsCount) -> &'a mut Self {
+        self.delay = Delay::Turns(turns);
+        self
+    }
+
+    /// Fires the scheduled event at the end of the given number of rounds.
+    pub fn after_rounds(&'a mut self, rounds: RoundsCount) -> &'a mut Self {
+        self.delay = Delay::Rounds(rounds);
+        self
+    }
+}
+
+impl<'a, R, P> EventTrigger<'a, R, P> for ScheduleEventTrigger<'a, R, P>
 where
     R: BattleRules + 'static,
     P: EventProcessor<R>,
@@ -989,10 +2602,23 @@ where
         self.processor
     }
 
-    /// Returns an `EndRound` event.
+    /// Returns a `ScheduleEvent` event.
     fn event(&self) -> Box<dyn Event<R> + Send> {
-        Box::new(EndRound {
-            _phantom: self._phantom,
+        Box::new(ScheduleEvent {
+            event: self.event.box_clone(),
+            delay: self.delay,
         })
     }
 }
+
+/// Former name of `StartTurn`, before version 0.9.0.
+#[deprecated(since = "0.11.0", note = "renamed into `StartTurn`")]
+pub type StartRound<R> = StartTurn<R>;
+
+/// Former name of `EnvironmentTurn`, before version 0.9.0.
+#[deprecated(since = "0.11.0", note = "renamed into `EnvironmentTurn`")]
+pub type EnvironmentRound<R> = EnvironmentTurn<R>;
+
+/// Former name of `TurnState`, before version 0.9.0.
+#[deprecated(since = "0.11.0", note = "renamed into `TurnState`")]
+pub type RoundState<EI> = TurnState<EI>;
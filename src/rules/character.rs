@@ -0,0 +1,182 @@
+//! Predefined rules for characters.
+
+use crate::battle::BattleRules;
+use crate::character::{Character, CharacterRules};
+use crate::entity::Transmutation;
+use crate::entropy::Entropy;
+use crate::metric::WriteMetrics;
+use crate::rules::fight::Damage;
+use crate::rules::statistic::SimpleStatistic;
+use crate::rules::status::{SimplePotency, SimpleStatus};
+use crate::status::{Potency, Status, StatusDuration, StatusId};
+use indexmap::IndexMap;
+#[cfg(feature = "serialization")]
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::convert::TryFrom;
+use std::hash::{Hash, Hasher};
+
+/// Id of the health statistic of characters managed by `SimpleCharacter`.
+pub const HEALTH: &str = "health";
+
+/// Default health of characters created without a seed.
+pub const DEFAULT_HEALTH: i32 = 100;
+
+/// Seed to generate the statistics of a character managed by `SimpleCharacter`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct SimpleCharacterSeed {
+    health: i32,
+    statistics: Vec<(String, i32)>,
+}
+
+impl SimpleCharacterSeed {
+    /// Creates a new seed for a character with the given maximum health.
+    pub fn new(health: i32) -> Self {
+        Self {
+            health,
+            statistics: Vec::new(),
+        }
+    }
+
+    /// Adds another statistic, for instance the attack used by `SimpleFight`.
+    pub fn statistic(mut self, id: &str, value: i32) -> Self {
+        self.statistics.push((id.to_string(), value));
+        self
+    }
+
+    /// Returns the maximum health of the character.
+    pub fn health(&self) -> i32 {
+        self.health
+    }
+
+    /// Returns an iterator over the ids and values of all other statistics.
+    pub fn statistics(&self) -> impl Iterator<Item = (&str, i32)> {
+        self.statistics
+            .iter()
+            .map(|(id, value)| (id.as_str(), *value))
+    }
+}
+
+/// Simple character rules with a health statistic and death handling.
+///
+/// Characters have a `HEALTH` statistic, plus any statistic defined in their seed.
+/// Statistics are altered through `Damage`, which makes these rules work out of the box
+/// with `SimpleFight`. A character whose health drops to zero is removed from the battle.\
+/// Statuses are `SimpleStatus` generated from the templates registered with `status`.
+/// A status inflicted with a `SimplePotency<i32>`, such as the potency of `SimpleFight`,
+/// takes its effect and duration from the potency.
+pub struct SimpleCharacter {
+    default_health: i32,
+    statuses: IndexMap<u32, (i32, Option<StatusDuration>)>,
+}
+
+impl SimpleCharacter {
+    /// Creates new simple character rules.
+    /// Characters created without a seed will have `default_health`.
+    pub fn new(default_health: i32) -> Self {
+        Self {
+            default_health,
+            statuses: IndexMap::new(),
+        }
+    }
+
+    /// Registers the template of a common status, such as poison or regeneration.\
+    /// Inflicting the status with the given id creates a `SimpleStatus` having `effect`
+    /// and `max_duration`. Statuses without a template can't be inflicted.
+    pub fn status(mut self, id: u32, effect: i32, max_duration: Option<StatusDuration>) -> Self {
+        self.statuses.insert(id, (effect, max_duration));
+        self
+    }
+
+    /// Returns the health of characters created without a seed.
+    pub fn default_health(&self) -> i32 {
+        self.default_health
+    }
+}
+
+impl Default for SimpleCharacter {
+    fn default() -> Self {
+        Self::new(DEFAULT_HEALTH)
+    }
+}
+
+impl<R> CharacterRules<R> for SimpleCharacter
+where
+    R: BattleRules<CR = Self> + 'static,
+{
+    type CreatureId = u32;
+    type ObjectId = u32;
+    type Statistic = SimpleStatistic<String, i32>;
+    type StatisticsSeed = SimpleCharacterSeed;
+    type StatisticsAlteration = Damage<R>;
+    type Status = SimpleStatus<u32, i32>;
+    type StatusesAlteration = ();
+
+    fn generate_statistics(
+        &self,
+        seed: &Option<Self::StatisticsSeed>,
+        _entropy: &mut Entropy<R>,
+        _metrics: &mut WriteMetrics<R>,
+    ) -> Box<dyn Iterator<Item = Self::Statistic>> {
+        let seed = seed
+            .clone()
+            .unwrap_or_else(|| SimpleCharacterSeed::new(self.default_health));
+        let mut v = vec![SimpleStatistic::new(HEALTH.to_string(), seed.health)];
+        v.extend(
+            seed.statistics()
+                .map(|(id, value)| SimpleStatistic::new(id.to_string(), value)),
+        );
+        Box::new(v.into_iter())
+    }
+
+    /// Subtracts the damage from the statistic. Characters without health left are removed.
+    fn alter_statistics(
+        &self,
+        character: &mut dyn Character<R>,
+        alteration: &Self::StatisticsAlteration,
+        _entropy: &mut Entropy<R>,
+        _metrics: &mut WriteMetrics<R>,
+    ) -> Option<Transmutation> {
+        let statistic = character.statistic_mut(alteration.statistic())?;
+        let value = i64::from(statistic.value()).saturating_sub(alteration.amount());
+        let value = i32::try_from(value).unwrap_or(if value < 0 { i32::MIN } else { i32::MAX });
+        statistic.set_value(value);
+        match character.statistic(&HEALTH.to_string()) {
+            Some(health) if health.value() <= 0 => Some(Transmutation::REMOVAL),
+            _ => None,
+        }
+    }
+
+    /// Writes the statistic's id and its current, minimum and maximum values.
+    fn digest_statistic(&self, statistic: &Self::Statistic, mut hasher: &mut dyn Hasher) {
+        statistic.hash(&mut hasher);
+    }
+
+    /// Writes the status' id, effect and maximum duration.
+    fn digest_status(&self, status: &Self::Status, mut hasher: &mut dyn Hasher) {
+        status.hash(&mut hasher);
+    }
+
+    fn generate_status(
+        &self,
+        _character: &dyn Character<R>,
+        status_id: &StatusId<R>,
+        potency: &Option<Potency<R>>,
+        _entropy: &mut Entropy<R>,
+        _metrics: &mut WriteMetrics<R>,
+    ) -> Option<Status<R>> {
+        let template = self.statuses.get(status_id);
+        let potency = potency
+            .as_ref()
+            .and_then(|potency| (potency as &dyn Any).downcast_ref::<SimplePotency<i32>>());
+        match potency {
+            Some(potency) if template.is_some() => {
+                Some(SimpleStatus::from_potency(*status_id, *potency))
+            }
+            _ => template.map(|(effect, max_duration)| {
+                SimpleStatus::new(*status_id, *effect, *max_duration)
+            }),
+        }
+    }
+}
@@ -5,6 +5,8 @@ use crate::actor::ActorRules;
 use crate::battle::BattleRules;
 use crate::character::CharacterRules;
 use crate::fight::FightRules;
+use crate::player::PlayerRules;
+use crate::reaction::ReactionRules;
 use crate::round::RoundsRules;
 use crate::rules::entropy::FixedAverage;
 use crate::space::SpaceRules;
@@ -50,6 +52,7 @@ impl<R: BattleRules> TeamRules<R> for EmptyTeamRules {
     type PowersAlteration = ();
     type ObjectivesSeed = ();
     type Objectives = ();
+    type TeamData = ();
 }
 
 /// Minimalistic implementation of character rules, doing no-op for everything.
@@ -84,6 +87,7 @@ pub struct EmptySpaceRules {}
 impl<R: BattleRules> SpaceRules<R> for EmptySpaceRules {
     type Position = ();
     type SpaceSeed = ();
+    type Area = ();
     type SpaceModel = ();
     type SpaceAlteration = ();
 
@@ -120,5 +124,20 @@ impl<R: BattleRules> UserRules<R> for EmptyUserRules {
     type UserEventPackage = ();
 }
 
+/// Minimalistic implementation of reaction rules, never reacting to anything.
+#[derive(Default)]
+pub struct EmptyReactionRules {}
+
+impl<R: BattleRules> ReactionRules<R> for EmptyReactionRules {}
+
+/// Minimalistic implementation of player rules, without any data attached to players.
+#[derive(Default)]
+pub struct EmptyPlayerRules {}
+
+impl<R: BattleRules> PlayerRules<R> for EmptyPlayerRules {
+    type Rating = ();
+    type PlayerData = ();
+}
+
 /// Entropy rules that do not have randomness. They just return the average value.
 pub type EmptyEntropyRules = FixedAverage<i32>;
@@ -63,12 +63,26 @@ impl<T: PartialOrd + Copy + Num + Debug> EntropyRules for FixedAverage<T> {
 /// It uses a seedable pseudo random number generator with deterministic output.
 ///
 /// A seed is required to ensure a good level of entropy.
+///
+/// Checkpoints of the generator are disabled by default, because each of them is a copy
+/// of the generator kept for every event. Enable them with `checkpointing` to rewind
+/// the entropy together with the history, for instance in an `UndoManager`.
 #[cfg(feature = "random")]
 #[derive(Debug, Default, Clone, Copy)]
 pub struct UniformDistribution<T> {
+    checkpointing: bool,
     _phantom: PhantomData<T>,
 }
 
+#[cfg(feature = "random")]
+impl<T> UniformDistribution<T> {
+    /// Enables checkpoints of the generator.
+    pub fn checkpointing(mut self) -> Self {
+        self.checkpointing = true;
+        self
+    }
+}
+
 #[cfg(feature = "random")]
 impl<T> EntropyRules for UniformDistribution<T>
 where
@@ -90,6 +104,15 @@ where
     ) -> Self::EntropyOutput {
         model.gen_range(low, high)
     }
+
+    /// Returns a copy of the generator, only if checkpoints are enabled.
+    fn checkpoint(&self, model: &Self::EntropyModel) -> Option<Self::EntropyModel> {
+        if self.checkpointing {
+            Some(model.clone())
+        } else {
+            None
+        }
+    }
 }
 
 #[cfg(test)]
@@ -119,4 +142,14 @@ mod tests {
             assert_eq!(rule.generate(&mut model, 0, 10), 8);
         }
     }
+
+    #[cfg(feature = "random")]
+    #[test]
+    fn uniform_distribution_checkpoints() {
+        let rule = UniformDistribution::<i32>::default();
+        let model = rule.generate_model(&Some(1));
+        assert!(rule.checkpoint(&model).is_none());
+        let rule = rule.checkpointing();
+        assert!(rule.checkpoint(&model).is_some());
+    }
 }
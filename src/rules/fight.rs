@@ -0,0 +1,324 @@
+//! Predefined rules for combat.
+
+use crate::battle::{BattleRules, BattleState};
+use crate::character::{AlterStatistics, Character, Statistic, StatisticId, StatisticsAlteration};
+use crate::entity::EntityId;
+use crate::entropy::Entropy;
+use crate::event::{EventQueue, EventTrigger, LinkedQueue};
+use crate::fight::FightRules;
+use crate::metric::WriteMetrics;
+use crate::rules::statistic::SimpleStatistic;
+use crate::rules::status::{expired, SimplePotency, SimpleStatus};
+use crate::status::{AppliedStatus, Status, StatusDuration};
+#[cfg(feature = "serialization")]
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter, Result};
+use std::ops::Add;
+
+/// Trait for statistics that can be used in the damage formula of `SimpleFight`.
+pub trait FightStatistic {
+    /// Returns the value of this statistic.
+    fn fight_value(&self) -> i64;
+}
+
+impl<I, V> FightStatistic for SimpleStatistic<I, V>
+where
+    I: Send,
+    V: Copy + PartialOrd + Add<Output = V> + Into<i64>,
+{
+    fn fight_value(&self) -> i64 {
+        self.value().into()
+    }
+}
+
+/// Trait for statuses that periodically damage or heal the character they are inflicted upon.
+pub trait StatusEffect {
+    /// Returns the damage dealt on each status update. Negative values heal the character.
+    fn effect(&self) -> i64;
+
+    /// Returns the maximum duration of the status. `None` means infinite duration.
+    fn max_duration(&self) -> Option<StatusDuration>;
+
+    /// Returns true if a status active for `duration` reached its maximum duration.
+    fn is_expired(&self, duration: StatusDuration) -> bool {
+        expired(self.max_duration(), duration)
+    }
+}
+
+impl<I, V> StatusEffect for SimpleStatus<I, V>
+where
+    I: Send,
+    V: Copy + Into<i64>,
+{
+    fn effect(&self) -> i64 {
+        SimpleStatus::effect(self).into()
+    }
+
+    fn max_duration(&self) -> Option<StatusDuration> {
+        SimpleStatus::max_duration(self)
+    }
+}
+
+/// Impact used by `SimpleFight`. It hits a single target with the given power.
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct SimpleImpact<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "EntityId<R>: Serialize",
+            deserialize = "EntityId<R>: Deserialize<'de>"
+        ))
+    )]
+    source: Option<EntityId<R>>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "EntityId<R>: Serialize",
+            deserialize = "EntityId<R>: Deserialize<'de>"
+        ))
+    )]
+    target: EntityId<R>,
+
+    power: i64,
+}
+
+impl<R: BattleRules> SimpleImpact<R> {
+    /// Creates a new impact against `target`.
+    pub fn new(target: EntityId<R>, power: i64) -> Self {
+        Self {
+            source: None,
+            target,
+            power,
+        }
+    }
+
+    /// Creates a new impact generated by `source` against `target`.
+    /// The attack statistic of `source` is added to the impact's power.
+    pub fn with_source(source: EntityId<R>, target: EntityId<R>, power: i64) -> Self {
+        Self {
+            source: Some(source),
+            target,
+            power,
+        }
+    }
+
+    /// Returns the entity who generated this impact, if any.
+    pub fn source(&self) -> &Option<EntityId<R>> {
+        &self.source
+    }
+
+    /// Returns the entity hit by this impact.
+    pub fn target(&self) -> &EntityId<R> {
+        &self.target
+    }
+
+    /// Returns the power of this impact.
+    pub fn power(&self) -> i64 {
+        self.power
+    }
+}
+
+impl<R: BattleRules> Debug for SimpleImpact<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "SimpleImpact {{ source: {:?}, target: {:?}, power: {:?} }}",
+            self.source, self.target, self.power
+        )
+    }
+}
+
+impl<R: BattleRules> Clone for SimpleImpact<R> {
+    fn clone(&self) -> Self {
+        Self {
+            source: self.source.clone(),
+            target: self.target.clone(),
+            power: self.power,
+        }
+    }
+}
+
+/// Damage dealt to a statistic by `SimpleFight`.
+///
+/// `SimpleFight` alters statistics through `AlterStatistics` events, thus the
+/// `StatisticsAlteration` of the character rules must be constructible from a `Damage`.
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Damage<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "StatisticId<R>: Serialize",
+            deserialize = "StatisticId<R>: Deserialize<'de>"
+        ))
+    )]
+    statistic: StatisticId<R>,
+
+    amount: i64,
+}
+
+impl<R: BattleRules> Damage<R> {
+    /// Creates a new damage of `amount` to the given statistic.
+    pub fn new(statistic: StatisticId<R>, amount: i64) -> Self {
+        Self { statistic, amount }
+    }
+
+    /// Returns the id of the damaged statistic.
+    pub fn statistic(&self) -> &StatisticId<R> {
+        &self.statistic
+    }
+
+    /// Returns the amount of damage. Negative values represent healing.
+    pub fn amount(&self) -> i64 {
+        self.amount
+    }
+}
+
+impl<R: BattleRules> Debug for Damage<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "Damage {{ statistic: {:?}, amount: {:?} }}",
+            self.statistic, self.amount
+        )
+    }
+}
+
+impl<R: BattleRules> Clone for Damage<R> {
+    fn clone(&self) -> Self {
+        Self {
+            statistic: self.statistic.clone(),
+            amount: self.amount,
+        }
+    }
+}
+
+/// Simple fight rules with a basic damage formula.
+///
+/// An impact deals `power + attack - defense` damage to the health of its target,
+/// where `attack` is a statistic of the impact's source and `defense` is a statistic
+/// of the target. Missing statistics count as zero and the damage is never negative.\
+/// Statuses implementing `StatusEffect` deal their effect as damage on each update
+/// and end once their maximum duration is reached.\
+/// The potency is a `SimplePotency`, which character rules such as `SimpleCharacter`
+/// use to customize the effect and duration of inflicted statuses.
+pub struct SimpleFight<R: BattleRules> {
+    attack: StatisticId<R>,
+    defense: StatisticId<R>,
+    health: StatisticId<R>,
+}
+
+impl<R: BattleRules> SimpleFight<R> {
+    /// Creates new simple fight rules from the ids of the attack, defense and health statistics.
+    pub fn new(attack: StatisticId<R>, defense: StatisticId<R>, health: StatisticId<R>) -> Self {
+        Self {
+            attack,
+            defense,
+            health,
+        }
+    }
+
+    /// Returns the id of the attack statistic.
+    pub fn attack(&self) -> &StatisticId<R> {
+        &self.attack
+    }
+
+    /// Returns the id of the defense statistic.
+    pub fn defense(&self) -> &StatisticId<R> {
+        &self.defense
+    }
+
+    /// Returns the id of the health statistic.
+    pub fn health(&self) -> &StatisticId<R> {
+        &self.health
+    }
+
+    /// Computes the damage dealt by an impact.
+    pub fn damage(&self, power: i64, attack: i64, defense: i64) -> i64 {
+        power.saturating_add(attack).saturating_sub(defense).max(0)
+    }
+}
+
+impl<R: BattleRules> Default for SimpleFight<R>
+where
+    StatisticId<R>: Default,
+{
+    fn default() -> Self {
+        Self::new(
+            StatisticId::<R>::default(),
+            StatisticId::<R>::default(),
+            StatisticId::<R>::default(),
+        )
+    }
+}
+
+/// Returns the value of a character's statistic, or zero if the statistic doesn't exist.
+fn statistic_value<R>(character: Option<&dyn Character<R>>, id: &StatisticId<R>) -> i64
+where
+    R: BattleRules,
+    Statistic<R>: FightStatistic,
+{
+    character
+        .and_then(|character| character.statistic(id))
+        .map_or(0, |statistic| statistic.fight_value())
+}
+
+impl<R: BattleRules + 'static> FightRules<R> for SimpleFight<R>
+where
+    Statistic<R>: FightStatistic,
+    Status<R>: StatusEffect,
+    StatisticsAlteration<R>: From<Damage<R>>,
+{
+    type Impact = SimpleImpact<R>;
+    type Potency = SimplePotency<i32>;
+
+    fn apply_impact(
+        &self,
+        state: &BattleState<R>,
+        impact: &Self::Impact,
+        mut event_queue: &mut Option<EventQueue<R>>,
+        _entropy: &mut Entropy<R>,
+        _metrics: &mut WriteMetrics<R>,
+    ) {
+        let target = match state.entities().character(&impact.target) {
+            Some(target) => target,
+            None => return,
+        };
+        let source = impact
+            .source
+            .as_ref()
+            .and_then(|source| state.entities().character(source));
+        let damage = self.damage(
+            impact.power,
+            statistic_value(source, &self.attack),
+            statistic_value(Some(target), &self.defense),
+        );
+        AlterStatistics::trigger(
+            &mut event_queue,
+            impact.target.clone(),
+            Damage::new(self.health.clone(), damage).into(),
+        )
+        .fire();
+    }
+
+    fn update_status(
+        &self,
+        _state: &BattleState<R>,
+        character: &dyn Character<R>,
+        status: &AppliedStatus<R>,
+        linked_queue: &mut Option<LinkedQueue<R>>,
+        _entropy: &mut Entropy<R>,
+        _metrics: &mut WriteMetrics<R>,
+    ) -> bool {
+        let effect = status.effect();
+        if effect != 0 {
+            AlterStatistics::trigger(
+                linked_queue,
+                character.entity_id().clone(),
+                Damage::new(self.health.clone(), effect).into(),
+            )
+            .fire();
+        }
+        status.is_expired(status.duration())
+    }
+}
@@ -16,6 +16,44 @@ macro_rules! battle_rules {
         }
     };
     ($ty: ty, $cy: ty, $ay: ty, $fy: ty, $uy: ty, $sy: ty, $ry: ty, $ey: ty) => {
+        battle_rules! {
+            $ty,
+            $cy,
+            $ay,
+            $fy,
+            $uy,
+            $sy,
+            $ry,
+            $ey,
+            $crate::rules::empty::EmptyReactionRules
+        }
+    };
+    ($ty: ty, $cy: ty, $ay: ty, $fy: ty, $uy: ty, $sy: ty, $ry: ty, $ey: ty, $xy: ty) => {
+        battle_rules! {
+            $ty,
+            $cy,
+            $ay,
+            $fy,
+            $uy,
+            $sy,
+            $ry,
+            $ey,
+            $xy,
+            $crate::rules::empty::EmptyPlayerRules
+        }
+    };
+    (
+        $ty: ty,
+        $cy: ty,
+        $ay: ty,
+        $fy: ty,
+        $uy: ty,
+        $sy: ty,
+        $ry: ty,
+        $ey: ty,
+        $xy: ty,
+        $py: ty
+    ) => {
         pub(crate) struct CustomRules {
             pub(crate) team_rules: $ty,
             pub(crate) character_rules: $cy,
@@ -25,7 +63,10 @@ macro_rules! battle_rules {
             pub(crate) space_rules: Option<$sy>,
             pub(crate) rounds_rules: Option<$ry>,
             pub(crate) entropy_rules: Option<$ey>,
+            pub(crate) reaction_rules: $xy,
+            pub(crate) player_rules: $py,
             pub(crate) version: u32,
+            pub(crate) rules_data: std::sync::Arc<()>,
         }
 
         impl CustomRules {
@@ -40,7 +81,10 @@ macro_rules! battle_rules {
                     space_rules: Some(<$sy>::default()),
                     rounds_rules: Some(<$ry>::default()),
                     entropy_rules: Some(<$ey>::default()),
+                    reaction_rules: <$xy>::default(),
+                    player_rules: <$py>::default(),
                     version: 0,
+                    rules_data: std::sync::Arc::new(()),
                 }
             }
         }
@@ -54,7 +98,10 @@ macro_rules! battle_rules {
             type SR = $sy;
             type RR = $ry;
             type ER = $ey;
+            type XR = $xy;
+            type PR = $py;
             type Version = u32;
+            type RulesData = ();
 
             fn team_rules(&self) -> &Self::TR {
                 &self.team_rules
@@ -71,6 +118,12 @@ macro_rules! battle_rules {
             fn user_rules(&self) -> &Self::UR {
                 &self.user_rules
             }
+            fn reaction_rules(&self) -> &Self::XR {
+                &self.reaction_rules
+            }
+            fn player_rules(&self) -> &Self::PR {
+                &self.player_rules
+            }
             fn space_rules(&mut self) -> Self::SR {
                 self.space_rules.take().expect("space_rules is None!")
             }
@@ -83,6 +136,9 @@ macro_rules! battle_rules {
             fn version(&self) -> &Self::Version {
                 &self.version
             }
+            fn rules_data(&self) -> &std::sync::Arc<Self::RulesData> {
+                &self.rules_data
+            }
         }
     };
 }
@@ -222,3 +278,40 @@ macro_rules! battle_rules_with_user {
         }
     };
 }
+
+/// Empty battle rules with user defined `ReactionRules`.
+#[macro_export]
+macro_rules! battle_rules_with_reaction {
+    ($ty: ty) => {
+        battle_rules! {
+            EmptyTeamRules,
+            EmptyCharacterRules,
+            EmptyActorRules,
+            EmptyFightRules,
+            EmptyUserRules,
+            EmptySpaceRules,
+            EmptyRoundsRules,
+            EmptyEntropyRules,
+            $ty
+        }
+    };
+}
+
+/// Empty battle rules with user defined `PlayerRules`.
+#[macro_export]
+macro_rules! battle_rules_with_player {
+    ($ty: ty) => {
+        battle_rules! {
+            EmptyTeamRules,
+            EmptyCharacterRules,
+            EmptyActorRules,
+            EmptyFightRules,
+            EmptyUserRules,
+            EmptySpaceRules,
+            EmptyRoundsRules,
+            EmptyEntropyRules,
+            EmptyReactionRules,
+            $ty
+        }
+    };
+}
@@ -0,0 +1,432 @@
+//! Combinators to compose multiple rules implementations for the same module.
+
+use crate::ability::{Ability, Activation};
+use crate::actor::{Action, ActionSuggestion, Actor, ActorRules, TrackedAbility};
+use crate::aura::Aura;
+use crate::battle::{BattleRules, BattleState};
+use crate::character::Character;
+use crate::entropy::Entropy;
+use crate::error::WeaselResult;
+use crate::event::{EventQueue, LinkedQueue};
+use crate::fight::FightRules;
+use crate::metric::WriteMetrics;
+use crate::round::TurnsCount;
+use crate::status::{Application, AppliedStatus, Status, StatusCombination, StatusId};
+use std::hash::Hasher;
+
+/// Rules combinator that chains two rules implementations of the same module.
+///
+/// Every hook is invoked first on `first` and then on `second`, so that independent
+/// pieces of rules logic (for instance, elemental damage and critical hits) can be packaged
+/// separately and reused across games.\
+/// Both rules must agree on the module's associated types.
+/// Chains can be nested to compose more than two rules.
+///
+/// # Examples
+/// ```
+/// use weasel::rules::mixin::Chain;
+/// use weasel::{
+///     battle_rules, battle_rules_with_fight, rules::empty::*, BattleRules, BattleState, Entropy,
+///     EventQueue, FightRules, WriteMetrics,
+/// };
+///
+/// #[derive(Default)]
+/// pub struct ElementalDamage {}
+///
+/// impl<R: BattleRules> FightRules<R> for ElementalDamage {
+///     type Impact = u32;
+///     type Potency = ();
+///
+///     fn apply_impact(
+///         &self,
+///         _state: &BattleState<R>,
+///         _impact: &Self::Impact,
+///         _event_queue: &mut Option<EventQueue<R>>,
+///         _entropy: &mut Entropy<R>,
+///         _metrics: &mut WriteMetrics<R>,
+///     ) {
+///         // Deal elemental damage.
+///     }
+/// }
+///
+/// #[derive(Default)]
+/// pub struct CriticalHits {}
+///
+/// impl<R: BattleRules> FightRules<R> for CriticalHits {
+///     type Impact = u32;
+///     type Potency = ();
+///
+///     fn apply_impact(
+///         &self,
+///         _state: &BattleState<R>,
+///         _impact: &Self::Impact,
+///         _event_queue: &mut Option<EventQueue<R>>,
+///         _entropy: &mut Entropy<R>,
+///         _metrics: &mut WriteMetrics<R>,
+///     ) {
+///         // Roll for a critical hit.
+///     }
+/// }
+///
+/// battle_rules_with_fight! { Chain<ElementalDamage, CriticalHits> }
+/// ```
+#[derive(Default)]
+pub struct Chain<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> Chain<A, B> {
+    /// Creates a new chain. Hooks of `first` are invoked before the ones of `second`.
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+
+    /// Returns the rules invoked first.
+    pub fn first(&self) -> &A {
+        &self.first
+    }
+
+    /// Returns a mutable reference to the rules invoked first.
+    pub fn first_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Returns the rules invoked second.
+    pub fn second(&self) -> &B {
+        &self.second
+    }
+
+    /// Returns a mutable reference to the rules invoked second.
+    pub fn second_mut(&mut self) -> &mut B {
+        &mut self.second
+    }
+}
+
+/// Concatenates the features of two chained rules.
+fn chain_features(mut first: Vec<&'static str>, second: Vec<&'static str>) -> Vec<&'static str> {
+    first.extend(second);
+    first
+}
+
+/// Appends to `consumed` the statuses in `other` not already present.
+fn merge_consumed<R: BattleRules>(consumed: &mut Vec<StatusId<R>>, other: Vec<StatusId<R>>) {
+    for id in other {
+        if !consumed.contains(&id) {
+            consumed.push(id);
+        }
+    }
+}
+
+impl<R, A, B> ActorRules<R> for Chain<A, B>
+where
+    R: BattleRules,
+    A: ActorRules<R>,
+    B: ActorRules<
+        R,
+        Ability = A::Ability,
+        AbilitiesSeed = A::AbilitiesSeed,
+        Activation = A::Activation,
+        AbilitiesAlteration = A::AbilitiesAlteration,
+    >,
+{
+    type Ability = A::Ability;
+    type AbilitiesSeed = A::AbilitiesSeed;
+    type Activation = A::Activation;
+    type AbilitiesAlteration = A::AbilitiesAlteration;
+
+    /// Returns the abilities generated by `first` followed by those generated by `second`.
+    fn generate_abilities(
+        &self,
+        seed: &Option<Self::AbilitiesSeed>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) -> Box<dyn Iterator<Item = Self::Ability>> {
+        let first = self.first.generate_abilities(seed, entropy, metrics);
+        let second = self.second.generate_abilities(seed, entropy, metrics);
+        Box::new(first.chain(second))
+    }
+
+    fn clamp_ability(&self, current: &mut Self::Ability, new: &Self::Ability) {
+        self.first.clamp_ability(current, new);
+        self.second.clamp_ability(current, new);
+    }
+
+    fn digest_ability(&self, ability: &Self::Ability, hasher: &mut dyn Hasher) {
+        self.first.digest_ability(ability, hasher);
+        self.second.digest_ability(ability, hasher);
+    }
+
+    /// Returns the first error among the ones returned by the chained rules.
+    fn activable(&self, state: &BattleState<R>, action: Action<R>) -> WeaselResult<(), R> {
+        self.first.activable(
+            state,
+            Action::new(action.actor, action.ability, action.activation),
+        )?;
+        self.second.activable(state, action)
+    }
+
+    /// Returns the strictest limit among the ones set by the chained rules.
+    fn max_activations(&self, state: &BattleState<R>, actor: &dyn Actor<R>) -> Option<u32> {
+        match (
+            self.first.max_activations(state, actor),
+            self.second.max_activations(state, actor),
+        ) {
+            (Some(first), Some(second)) => Some(first.min(second)),
+            (first, second) => first.or(second),
+        }
+    }
+
+    /// Returns the largest capacity among the ones requested by the chained rules.
+    fn max_recent_activations(&self) -> usize {
+        self.first
+            .max_recent_activations()
+            .max(self.second.max_recent_activations())
+    }
+
+    fn activate(
+        &self,
+        state: &BattleState<R>,
+        action: Action<R>,
+        event_queue: &mut Option<EventQueue<R>>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.first.activate(
+            state,
+            Action::new(action.actor, action.ability, action.activation),
+            event_queue,
+            entropy,
+            metrics,
+        );
+        self.second
+            .activate(state, action, event_queue, entropy, metrics);
+    }
+
+    fn alter_abilities(
+        &self,
+        actor: &mut dyn Actor<R>,
+        alteration: &Self::AbilitiesAlteration,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.first
+            .alter_abilities(actor, alteration, entropy, metrics);
+        self.second
+            .alter_abilities(actor, alteration, entropy, metrics);
+    }
+
+    fn on_turn_start(
+        &self,
+        state: &BattleState<R>,
+        actor: &dyn Actor<R>,
+        event_queue: &mut Option<EventQueue<R>>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.first
+            .on_turn_start(state, actor, event_queue, entropy, metrics);
+        self.second
+            .on_turn_start(state, actor, event_queue, entropy, metrics);
+    }
+
+    fn on_turn_end(
+        &self,
+        state: &BattleState<R>,
+        actor: &dyn Actor<R>,
+        event_queue: &mut Option<EventQueue<R>>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.first
+            .on_turn_end(state, actor, event_queue, entropy, metrics);
+        self.second
+            .on_turn_end(state, actor, event_queue, entropy, metrics);
+    }
+
+    /// Returns the longest recharge among the ones required by the chained rules.
+    fn recharge(
+        &self,
+        state: &BattleState<R>,
+        actor: &dyn Actor<R>,
+        ability: &TrackedAbility<R>,
+    ) -> TurnsCount {
+        self.first
+            .recharge(state, actor, ability)
+            .max(self.second.recharge(state, actor, ability))
+    }
+
+    /// Returns the activation profiles of `first` followed by those of `second`.
+    /// An activation without profile is returned at most once.
+    fn valid_activations(
+        &self,
+        state: &BattleState<R>,
+        actor: &dyn Actor<R>,
+        ability: &Ability<R>,
+    ) -> Vec<Option<Activation<R>>> {
+        let mut activations = self.first.valid_activations(state, actor, ability);
+        for activation in self.second.valid_activations(state, actor, ability) {
+            if activation.is_some() || activations.iter().all(Option::is_some) {
+                activations.push(activation);
+            }
+        }
+        activations
+    }
+
+    /// Returns the suggestion of `first`, or the one of `second` if the former suggests nothing.
+    fn suggest_action(
+        &self,
+        state: &BattleState<R>,
+        actor: &dyn Actor<R>,
+    ) -> Option<ActionSuggestion<R>> {
+        self.first
+            .suggest_action(state, actor)
+            .or_else(|| self.second.suggest_action(state, actor))
+    }
+
+    fn features(&self) -> Vec<&'static str> {
+        chain_features(
+            ActorRules::features(&self.first),
+            ActorRules::features(&self.second),
+        )
+    }
+}
+
+impl<R, A, B> FightRules<R> for Chain<A, B>
+where
+    R: BattleRules,
+    A: FightRules<R>,
+    B: FightRules<R, Impact = A::Impact, Potency = A::Potency>,
+{
+    type Impact = A::Impact;
+    type Potency = A::Potency;
+
+    fn apply_impact(
+        &self,
+        state: &BattleState<R>,
+        impact: &Self::Impact,
+        event_queue: &mut Option<EventQueue<R>>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.first
+            .apply_impact(state, impact, event_queue, entropy, metrics);
+        self.second
+            .apply_impact(state, impact, event_queue, entropy, metrics);
+    }
+
+    fn apply_status(
+        &self,
+        state: &BattleState<R>,
+        character: &dyn Character<R>,
+        application: Application<R>,
+        event_queue: &mut Option<EventQueue<R>>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.first
+            .apply_status(state, character, application, event_queue, entropy, metrics);
+        self.second
+            .apply_status(state, character, application, event_queue, entropy, metrics);
+    }
+
+    /// Returns the order given by `first`, or the one given by `second` if the former is zero.
+    fn status_order(&self, status_id: &StatusId<R>) -> i32 {
+        match self.first.status_order(status_id) {
+            0 => self.second.status_order(status_id),
+            order => order,
+        }
+    }
+
+    /// Combines the status with `first` and then, if it's still inflicted, with `second`.
+    /// Statuses consumed by any of the chained rules are cleared.
+    fn combine_statuses(
+        &self,
+        state: &BattleState<R>,
+        character: &dyn Character<R>,
+        status: Status<R>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) -> StatusCombination<R> {
+        match self
+            .first
+            .combine_statuses(state, character, status, entropy, metrics)
+        {
+            StatusCombination::Inflict(status, mut consumed) => {
+                match self
+                    .second
+                    .combine_statuses(state, character, status, entropy, metrics)
+                {
+                    StatusCombination::Inflict(status, other) => {
+                        merge_consumed::<R>(&mut consumed, other);
+                        StatusCombination::Inflict(status, consumed)
+                    }
+                    StatusCombination::Discard(other) => {
+                        merge_consumed::<R>(&mut consumed, other);
+                        StatusCombination::Discard(consumed)
+                    }
+                }
+            }
+            discard => discard,
+        }
+    }
+
+    /// Updates the status with both rules. The status ends if any of them asks so.
+    fn update_status(
+        &self,
+        state: &BattleState<R>,
+        character: &dyn Character<R>,
+        status: &AppliedStatus<R>,
+        linked_queue: &mut Option<LinkedQueue<R>>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) -> bool {
+        let first =
+            self.first
+                .update_status(state, character, status, linked_queue, entropy, metrics);
+        let second =
+            self.second
+                .update_status(state, character, status, linked_queue, entropy, metrics);
+        first || second
+    }
+
+    fn delete_status(
+        &self,
+        state: &BattleState<R>,
+        character: &dyn Character<R>,
+        status: &AppliedStatus<R>,
+        event_queue: &mut Option<EventQueue<R>>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.first
+            .delete_status(state, character, status, event_queue, entropy, metrics);
+        self.second
+            .delete_status(state, character, status, event_queue, entropy, metrics);
+    }
+
+    /// Returns `true` if any of the chained rules blocks the ability.
+    fn ability_blocked_by(&self, status: &AppliedStatus<R>, ability: &Ability<R>) -> bool {
+        self.first.ability_blocked_by(status, ability)
+            || self.second.ability_blocked_by(status, ability)
+    }
+
+    /// Returns the aura given by `first`, or the one given by `second` if the former is `None`.
+    fn aura(
+        &self,
+        state: &BattleState<R>,
+        source: &dyn Character<R>,
+        status: &AppliedStatus<R>,
+    ) -> Option<Aura<R>> {
+        self.first
+            .aura(state, source, status)
+            .or_else(|| self.second.aura(state, source, status))
+    }
+
+    fn features(&self) -> Vec<&'static str> {
+        chain_features(
+            FightRules::features(&self.first),
+            FightRules::features(&self.second),
+        )
+    }
+}
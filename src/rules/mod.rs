@@ -1,8 +1,12 @@
 //! Collection of generic rules.
 
 pub mod ability;
+pub mod character;
 pub mod empty;
 pub mod entropy;
+pub mod fight;
 mod generic;
+pub mod mixin;
+pub mod rounds;
 pub mod statistic;
 pub mod status;
@@ -0,0 +1,219 @@
+//! Predefined rules for rounds.
+
+use crate::actor::Actor;
+use crate::battle::BattleRules;
+use crate::character::{Statistic, StatisticId};
+use crate::entity::{Entities, EntityId};
+use crate::entropy::Entropy;
+use crate::event::EventQueue;
+use crate::metric::WriteMetrics;
+use crate::round::{RoundsRules, TicksCount};
+use crate::rules::statistic::SimpleStatistic;
+use crate::space::Space;
+use indexmap::IndexMap;
+#[cfg(feature = "serialization")]
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::ops::Add;
+
+/// Trait for statistics that can tell how fast an actor charges its action bar.
+pub trait ChargeRate {
+    /// Returns the amount of charge accumulated in one tick.
+    fn charge_rate(&self) -> u64;
+}
+
+impl<I, V> ChargeRate for SimpleStatistic<I, V>
+where
+    I: Send,
+    V: Copy + PartialOrd + Add<Output = V> + Into<u64>,
+{
+    fn charge_rate(&self) -> u64 {
+        self.value().into()
+    }
+}
+
+/// Default amount of charge needed by an actor to act.
+pub const DEFAULT_CHARGE_THRESHOLD: u64 = 100;
+
+/// Active time battle rules.
+///
+/// Each actor accumulates charge on every `Tick`, at a rate given by its speed statistic.
+/// Once the charge reaches the threshold, the actor becomes eligible to start a turn.
+/// The charge is spent when the actor's turn ends.\
+/// Actors without the speed statistic never charge.
+pub struct AtbRounds<R: BattleRules> {
+    speed: StatisticId<R>,
+    threshold: u64,
+}
+
+impl<R: BattleRules> AtbRounds<R> {
+    /// Creates new active time battle rules.
+    ///
+    /// `speed` is the id of the statistic used as charge rate and `threshold` is the charge
+    /// needed by an actor to act.
+    pub fn new(speed: StatisticId<R>, threshold: u64) -> Self {
+        Self { speed, threshold }
+    }
+
+    /// Returns the id of the statistic used as charge rate.
+    pub fn speed(&self) -> &StatisticId<R> {
+        &self.speed
+    }
+
+    /// Returns the charge needed by an actor to act.
+    pub fn threshold(&self) -> u64 {
+        self.threshold
+    }
+}
+
+impl<R: BattleRules> Default for AtbRounds<R>
+where
+    StatisticId<R>: Default,
+{
+    fn default() -> Self {
+        Self::new(StatisticId::<R>::default(), DEFAULT_CHARGE_THRESHOLD)
+    }
+}
+
+impl<R: BattleRules> RoundsRules<R> for AtbRounds<R>
+where
+    Statistic<R>: ChargeRate,
+{
+    type RoundsSeed = ();
+    type RoundsModel = AtbModel<R>;
+
+    fn generate_model(&self, _seed: &Option<Self::RoundsSeed>) -> Self::RoundsModel {
+        AtbModel {
+            charges: IndexMap::new(),
+            threshold: self.threshold,
+        }
+    }
+
+    /// Returns whether the actor's charge reached the threshold.
+    fn eligible(&self, model: &Self::RoundsModel, actor: &dyn Actor<R>) -> bool {
+        model.is_ready(actor.entity_id())
+    }
+
+    /// Returns all actors sorted by charge, highest first.
+    fn order(&self, model: &Self::RoundsModel, _entities: &Entities<R>) -> Vec<EntityId<R>> {
+        let mut charges: Vec<_> = model.charges().collect();
+        charges.sort_by_key(|(_, charge)| Reverse(*charge));
+        charges.into_iter().map(|(id, _)| id.clone()).collect()
+    }
+
+    fn on_end(
+        &self,
+        _entities: &Entities<R>,
+        _space: &Space<R>,
+        model: &mut Self::RoundsModel,
+        actor: &dyn Actor<R>,
+        _entropy: &mut Entropy<R>,
+        _metrics: &mut WriteMetrics<R>,
+    ) {
+        if let Some(charge) = model.charges.get_mut(actor.entity_id()) {
+            *charge = 0;
+        }
+    }
+
+    fn on_tick(
+        &self,
+        entities: &Entities<R>,
+        model: &mut Self::RoundsModel,
+        _tick: TicksCount,
+        _event_queue: &mut Option<EventQueue<R>>,
+        _entropy: &mut Entropy<R>,
+        _metrics: &mut WriteMetrics<R>,
+    ) {
+        for actor in entities.actors() {
+            let rate = actor
+                .statistic(&self.speed)
+                .map_or(0, |statistic| statistic.charge_rate());
+            if let Some(charge) = model.charges.get_mut(actor.entity_id()) {
+                *charge = charge.saturating_add(rate).min(model.threshold);
+            }
+        }
+    }
+
+    fn on_actor_added(
+        &self,
+        model: &mut Self::RoundsModel,
+        actor: &dyn Actor<R>,
+        _entropy: &mut Entropy<R>,
+        _metrics: &mut WriteMetrics<R>,
+    ) {
+        model.charges.insert(actor.entity_id().clone(), 0);
+    }
+
+    fn on_actor_removed(
+        &self,
+        model: &mut Self::RoundsModel,
+        actor: &dyn Actor<R>,
+        _entropy: &mut Entropy<R>,
+        _metrics: &mut WriteMetrics<R>,
+    ) {
+        model.charges.shift_remove(actor.entity_id());
+    }
+}
+
+/// Rounds model of `AtbRounds`, storing the charge of every actor.
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct AtbModel<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(
+            bound(
+                serialize = "EntityId<R>: Serialize",
+                deserialize = "EntityId<R>: Deserialize<'de>"
+            ),
+            serialize_with = "crate::serde::serialize_pairs",
+            deserialize_with = "crate::serde::deserialize_pairs"
+        )
+    )]
+    charges: IndexMap<EntityId<R>, u64>,
+
+    threshold: u64,
+}
+
+impl<R: BattleRules> AtbModel<R> {
+    /// Returns the current charge of an actor.
+    pub fn charge(&self, entity_id: &EntityId<R>) -> Option<u64> {
+        self.charges.get(entity_id).copied()
+    }
+
+    /// Returns the charge of an actor as a fraction of the threshold, between 0 and 1.
+    pub fn progress(&self, entity_id: &EntityId<R>) -> Option<f32> {
+        self.charge(entity_id).map(|charge| {
+            if self.threshold == 0 {
+                1.0
+            } else {
+                charge as f32 / self.threshold as f32
+            }
+        })
+    }
+
+    /// Returns `true` if the actor's charge reached the threshold.
+    pub fn is_ready(&self, entity_id: &EntityId<R>) -> bool {
+        match self.charge(entity_id) {
+            Some(charge) => charge >= self.threshold,
+            None => false,
+        }
+    }
+
+    /// Returns an iterator over all actors and their charge.
+    pub fn charges(&self) -> impl Iterator<Item = (&EntityId<R>, u64)> {
+        self.charges.iter().map(|(id, charge)| (id, *charge))
+    }
+
+    /// Returns an iterator over the ids of all actors whose charge reached the threshold.
+    pub fn ready(&self) -> impl Iterator<Item = &EntityId<R>> {
+        self.charges
+            .iter()
+            .filter(move |(_, charge)| **charge >= self.threshold)
+            .map(|(id, _)| id)
+    }
+
+    /// Returns the charge needed by an actor to act.
+    pub fn threshold(&self) -> u64 {
+        self.threshold
+    }
+}
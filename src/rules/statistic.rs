@@ -9,7 +9,7 @@ use std::hash::Hash;
 use std::ops::Add;
 
 /// A simple generic statistic storing current value, minimum and maximum value.
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub struct SimpleStatistic<I, V> {
     id: I,
@@ -69,6 +69,13 @@ where
     pub fn add(&mut self, inc: V) {
         self.set_value(self.value + inc);
     }
+
+    /// Takes the min/max bounds of `other` and clamps the current value within them.
+    pub fn clamp_to(&mut self, other: &Self) {
+        self.min = other.min;
+        self.max = other.max;
+        self.set_value(self.value);
+    }
 }
 
 #[cfg(not(feature = "serialization"))]
@@ -105,4 +112,13 @@ mod tests {
         stat.add(-100);
         assert_eq!(stat.value(), stat.min());
     }
+
+    #[test]
+    fn simple_statistic_clamp_to() {
+        let mut stat = SimpleStatistic::with_value(1, 10, 20, 15);
+        stat.clamp_to(&SimpleStatistic::new(1, 12));
+        assert_eq!(stat.value(), 12);
+        assert_eq!(stat.min(), 0);
+        assert_eq!(stat.max(), 12);
+    }
 }
@@ -8,7 +8,7 @@ use std::fmt::Debug;
 use std::hash::Hash;
 
 /// A simple generic status.
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub struct SimpleStatus<I, V> {
     id: I,
@@ -41,6 +41,82 @@ impl<I: Send, V: Copy> SimpleStatus<I, V> {
     pub fn max_duration(&self) -> Option<StatusDuration> {
         self.max_duration
     }
+
+    /// Creates a new `SimpleStatus` with the effect and duration of the given potency.
+    pub fn from_potency(id: I, potency: SimplePotency<V>) -> Self {
+        Self::new(id, potency.value, potency.max_duration)
+    }
+
+    /// Returns the potency of this status.
+    pub fn potency(&self) -> SimplePotency<V> {
+        SimplePotency::new(self.effect, self.max_duration)
+    }
+
+    /// Returns true if a status active for `duration` reached its maximum duration.
+    pub fn is_expired(&self, duration: StatusDuration) -> bool {
+        expired(self.max_duration, duration)
+    }
+}
+
+/// Returns true if `duration` reached `max_duration`. `None` means infinite duration.
+pub(crate) fn expired(max_duration: Option<StatusDuration>, duration: StatusDuration) -> bool {
+    match max_duration {
+        Some(max_duration) => duration >= max_duration,
+        None => false,
+    }
+}
+
+/// A simple potency for statuses, made of an effect's value and an optional maximum duration.
+///
+/// It covers common cases such as damage over time or temporary buffs, without the need
+/// of a custom `Potency` type.
+///
+/// # Examples
+/// ```
+/// use weasel::rules::status::{SimplePotency, SimpleStatus};
+///
+/// let poison = SimplePotency::lasting(5, 3);
+/// let status = SimpleStatus::from_potency(1, poison);
+/// assert_eq!(status.effect(), 5);
+/// assert!(!status.is_expired(2));
+/// assert!(status.is_expired(3));
+/// assert_eq!(status.potency(), poison);
+/// ```
+#[derive(PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct SimplePotency<V> {
+    value: V,
+    max_duration: Option<StatusDuration>,
+}
+
+impl<V: Copy> SimplePotency<V> {
+    /// Creates a new `SimplePotency`. A `max_duration` equal to `None` means infinite duration.
+    pub fn new(value: V, max_duration: Option<StatusDuration>) -> Self {
+        Self {
+            value,
+            max_duration,
+        }
+    }
+
+    /// Creates a potency for a status lasting forever.
+    pub fn permanent(value: V) -> Self {
+        Self::new(value, None)
+    }
+
+    /// Creates a potency for a status lasting `max_duration` turns.
+    pub fn lasting(value: V, max_duration: StatusDuration) -> Self {
+        Self::new(value, Some(max_duration))
+    }
+
+    /// Returns the value of the effect.
+    pub fn value(&self) -> V {
+        self.value
+    }
+
+    /// Returns the maximum duration. `None` means infinite duration.
+    pub fn max_duration(&self) -> Option<StatusDuration> {
+        self.max_duration
+    }
 }
 
 #[cfg(not(feature = "serialization"))]
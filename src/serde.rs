@@ -1,28 +1,45 @@
 //! Module to handle serialization and deserialization.
 
-use crate::ability::ActivateAbility;
-use crate::actor::{AlterAbilities, RegenerateAbilities};
-use crate::battle::{BattleRules, EndBattle, Version};
+use crate::ability::{ActivateAbility, InterruptAbility};
+use crate::actor::{AlterAbilities, RechargeAbilities, RegenerateAbilities};
+use crate::battle::{
+    Battle, BattleOutcome, BattlePhase, BattleRules, EndBattle, EndReason, StartBattle,
+    VerifyState, Version,
+};
 use crate::character::{AlterStatistics, RegenerateStatistics};
-use crate::creature::{ConvertCreature, CreateCreature, RemoveCreature};
-use crate::entropy::ResetEntropy;
+use crate::creature::{
+    ConvertCreature, CreateCreature, ReidentifyCreature, RemoveCreature, SpawnWave,
+    TransferOwnership, TransformCreature,
+};
+use crate::entity::{AlterTags, Entities, EntityId, SetTimeToLive};
+use crate::entropy::{EntropyModel, ResetEntropy};
+use crate::error::WeaselResult;
 use crate::event::{
-    ClientEventPrototype, DummyEvent, Event, EventId, EventKind, EventWrapper,
-    VersionedEventWrapper,
+    ClientEventPrototype, DummyEvent, Event, EventId, EventKind, EventMetadata, EventTrigger,
+    EventWrapper, VersionedEventWrapper,
 };
 use crate::fight::ApplyImpact;
-use crate::object::{CreateObject, RemoveObject};
-use crate::player::PlayerId;
+use crate::metric::{MetricIdType, Metrics};
+use crate::object::{CreateObject, RemoveObject, TransferObjectOwnership};
+use crate::player::{PlayerId, Players, RegisterPlayer, RemovePlayer, Rights};
 use crate::power::InvokePower;
-use crate::round::{EndRound, EndTurn, EnvironmentTurn, ResetRounds, StartTurn};
-use crate::space::{AlterSpace, MoveEntity, ResetSpace};
-use crate::status::{AlterStatuses, ClearStatus, InflictStatus};
+use crate::round::{
+    AddTurnActor, EndRound, EndTurn, EnvironmentTurn, RemoveTurnActor, ResetRounds, RestoreRounds,
+    Rounds, RoundsModel, RoundsState, ScheduleEvent, StartTurn, Tick, TurnTimedOut,
+};
+use crate::space::{AlterSpace, MoveEntity, MoveEntityAlongPath, ResetSpace, SpaceModel};
+use crate::status::{AlterStatusDuration, AlterStatuses, ClearStatus, InflictStatus};
 use crate::team::{
-    AlterPowers, ConcludeObjectives, CreateTeam, RegeneratePowers, RemoveTeam, ResetObjectives,
-    SetRelations,
+    AddScore, AlterPowers, ConcludeObjectives, CreateTeam, RegeneratePowers, RemoveTeam,
+    RenameTeam, ResetObjectives, SetRelations, TeamId, TradeProposal, TransferResource,
 };
-use crate::user::{UserEventPackage, UserEventPacker};
-use serde::{Deserialize, Serialize};
+use crate::user::{UserEventPackage, UserEventPacker, UserMetricId};
+use crate::visibility::{HideEntity, RevealEntity, Visibility};
+use serde::de::value::{self, MapAccessDeserializer, MapDeserializer, SeqDeserializer};
+use serde::de::{self, IntoDeserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::io::{self, Write};
 
 /// Macro to panic on incorrect cast.
 macro_rules! bad_cast {
@@ -77,9 +94,33 @@ macro_rules! flat_event_flattened {
     }
 }
 
+/// Generates the borrowed() method for `FlatEventRef`.
+macro_rules! flat_event_borrowed {
+    ($( $x:ident ),* $(,)?) => {
+        /// Borrows an event trait object as a flattened event.
+        ///
+        /// User events are cloned, because they must be packed into a `UserEventPacker`.
+        pub(crate) fn borrowed(event: &'a (dyn Event<R> + Send)) -> WeaselResult<Self, R> {
+            // Generate a match with an arm for every concrete event type.
+            match event.kind() {
+                $(EventKind::$x => {
+                    match event.as_any().downcast_ref::<$x<R>>() {
+                        Some(event) => Ok(FlatEventRef::$x(event)),
+                        None => bad_cast!(),
+                    }
+                })*
+                EventKind::UserEvent(_) => Ok(FlatEventRef::UserEventPackage(
+                    UserEventPackage::<R>::flattened(event.box_clone())?,
+                )),
+            }
+        }
+    }
+}
+
 /// Generates the FlatEvent enum starting from a list of event identifiers.
+/// Each identifier can be followed by the legacy names accepted during deserialization.
 macro_rules! flat_event {
-    ($( $x:ident, $ser:expr, $de:expr ),* $(,)?) => {
+    ($( $x:ident $(| $alias:literal)*, $ser:expr, $de:expr ),* $(,)?) => {
         /// An enum representation of event trait objects.
         #[derive(Serialize, Deserialize)]
         pub enum FlatEvent<R: BattleRules> {
@@ -88,6 +129,7 @@ macro_rules! flat_event {
                 serialize = $ser,
                 deserialize = $de
             ))]
+            $(#[serde(alias = $alias)])*
             $x($x<R>),)*
             #[allow(missing_docs)]
             #[serde(bound(
@@ -102,6 +144,21 @@ macro_rules! flat_event {
 
             flat_event_flattened! { $($x),* }
         }
+
+        /// A borrowed representation of event trait objects.
+        /// It's serialized exactly as the equivalent `FlatEvent`.
+        #[derive(Serialize)]
+        #[serde(rename = "FlatEvent")]
+        pub(crate) enum FlatEventRef<'a, R: BattleRules> {
+            $(#[serde(bound(serialize = $ser))]
+            $x(&'a $x<R>),)*
+            #[serde(bound(serialize = "UserEventPackage<R>: Serialize"))]
+            UserEventPackage(UserEventPackage<R>),
+        }
+
+        impl<'a, R: BattleRules + 'static> FlatEventRef<'a, R> {
+            flat_event_borrowed! { $($x),* }
+        }
     };
 }
 
@@ -111,11 +168,19 @@ flat_event! {
     CreateCreature, "CreateCreature<R>: Serialize", "CreateCreature<R>: Deserialize<'de>",
     CreateObject, "CreateObject<R>: Serialize", "CreateObject<R>: Deserialize<'de>",
     MoveEntity, "MoveEntity<R>: Serialize", "MoveEntity<R>: Deserialize<'de>",
-    StartTurn, "StartTurn<R>: Serialize", "StartTurn<R>: Deserialize<'de>",
+    SetTimeToLive, "SetTimeToLive<R>: Serialize", "SetTimeToLive<R>: Deserialize<'de>",
+    AlterTags, "AlterTags<R>: Serialize", "AlterTags<R>: Deserialize<'de>",
+    StartTurn | "StartRound", "StartTurn<R>: Serialize", "StartTurn<R>: Deserialize<'de>",
     EndTurn, "EndTurn<R>: Serialize", "EndTurn<R>: Deserialize<'de>",
+    TurnTimedOut, "TurnTimedOut<R>: Serialize", "TurnTimedOut<R>: Deserialize<'de>",
+    AddTurnActor, "AddTurnActor<R>: Serialize", "AddTurnActor<R>: Deserialize<'de>",
+    RemoveTurnActor, "RemoveTurnActor<R>: Serialize", "RemoveTurnActor<R>: Deserialize<'de>",
     EndRound, "EndRound<R>: Serialize", "EndRound<R>: Deserialize<'de>",
-    EnvironmentTurn, "EnvironmentTurn<R>: Serialize", "EnvironmentTurn<R>: Deserialize<'de>",
+    EnvironmentTurn | "EnvironmentRound", "EnvironmentTurn<R>: Serialize", "EnvironmentTurn<R>: Deserialize<'de>",
+    Tick, "Tick<R>: Serialize", "Tick<R>: Deserialize<'de>",
+    RestoreRounds, "RestoreRounds<R>: Serialize", "RestoreRounds<R>: Deserialize<'de>",
     ActivateAbility, "ActivateAbility<R>: Serialize", "ActivateAbility<R>: Deserialize<'de>",
+    InterruptAbility, "InterruptAbility<R>: Serialize", "InterruptAbility<R>: Deserialize<'de>",
     InvokePower, "InvokePower<R>: Serialize", "InvokePower<R>: Deserialize<'de>",
     ApplyImpact, "ApplyImpact<R>: Serialize", "ApplyImpact<R>: Deserialize<'de>",
     AlterStatistics, "AlterStatistics<R>: Serialize", "AlterStatistics<R>: Deserialize<'de>",
@@ -127,18 +192,106 @@ flat_event! {
     RegeneratePowers, "RegeneratePowers<R>: Serialize", "RegeneratePowers<R>: Deserialize<'de>",
     InflictStatus, "InflictStatus<R>: Serialize", "InflictStatus<R>: Deserialize<'de>",
     ClearStatus, "ClearStatus<R>: Serialize", "ClearStatus<R>: Deserialize<'de>",
+    AlterStatusDuration, "AlterStatusDuration<R>: Serialize", "AlterStatusDuration<R>: Deserialize<'de>",
     ConvertCreature, "ConvertCreature<R>: Serialize", "ConvertCreature<R>: Deserialize<'de>",
+    ReidentifyCreature, "ReidentifyCreature<R>: Serialize", "ReidentifyCreature<R>: Deserialize<'de>",
+    TransformCreature, "TransformCreature<R>: Serialize", "TransformCreature<R>: Deserialize<'de>",
+    TransferOwnership, "TransferOwnership<R>: Serialize", "TransferOwnership<R>: Deserialize<'de>",
+    TransferObjectOwnership, "TransferObjectOwnership<R>: Serialize", "TransferObjectOwnership<R>: Deserialize<'de>",
     SetRelations, "SetRelations<R>: Serialize", "SetRelations<R>: Deserialize<'de>",
     ConcludeObjectives, "ConcludeObjectives<R>: Serialize", "ConcludeObjectives<R>: Deserialize<'de>",
+    AddScore, "AddScore<R>: Serialize", "AddScore<R>: Deserialize<'de>",
+    TransferResource, "TransferResource<R>: Serialize", "TransferResource<R>: Deserialize<'de>",
+    TradeProposal, "TradeProposal<R>: Serialize", "TradeProposal<R>: Deserialize<'de>",
     RemoveCreature, "RemoveCreature<R>: Serialize", "RemoveCreature<R>: Deserialize<'de>",
     RemoveObject, "RemoveObject<R>: Serialize", "RemoveObject<R>: Deserialize<'de>",
     RemoveTeam, "RemoveTeam<R>: Serialize", "RemoveTeam<R>: Deserialize<'de>",
+    RenameTeam, "RenameTeam<R>: Serialize", "RenameTeam<R>: Deserialize<'de>",
     AlterSpace, "AlterSpace<R>: Serialize", "AlterSpace<R>: Deserialize<'de>",
+    RevealEntity, "RevealEntity<R>: Serialize", "RevealEntity<R>: Deserialize<'de>",
+    HideEntity, "HideEntity<R>: Serialize", "HideEntity<R>: Deserialize<'de>",
     ResetEntropy, "ResetEntropy<R>: Serialize", "ResetEntropy<R>: Deserialize<'de>",
     ResetObjectives, "ResetObjectives<R>: Serialize", "ResetObjectives<R>: Deserialize<'de>",
     ResetRounds, "ResetRounds<R>: Serialize", "ResetRounds<R>: Deserialize<'de>",
     ResetSpace, "ResetSpace<R>: Serialize", "ResetSpace<R>: Deserialize<'de>",
+    StartBattle, "StartBattle<R>: Serialize", "StartBattle<R>: Deserialize<'de>",
     EndBattle, "EndBattle<R>: Serialize", "EndBattle<R>: Deserialize<'de>",
+    // The bounds of `ScheduleEvent` would be recursive, because it contains a `FlatEvent`.
+    ScheduleEvent, "R: 'static", "R: 'static",
+    SpawnWave, "SpawnWave<R>: Serialize", "SpawnWave<R>: Deserialize<'de>",
+    MoveEntityAlongPath, "MoveEntityAlongPath<R>: Serialize", "MoveEntityAlongPath<R>: Deserialize<'de>",
+    RechargeAbilities, "RechargeAbilities<R>: Serialize", "RechargeAbilities<R>: Deserialize<'de>",
+    VerifyState, "VerifyState<R>: Serialize", "VerifyState<R>: Deserialize<'de>",
+    RegisterPlayer, "RegisterPlayer<R>: Serialize", "RegisterPlayer<R>: Deserialize<'de>",
+    RemovePlayer, "RemovePlayer<R>: Serialize", "RemovePlayer<R>: Deserialize<'de>",
+}
+
+impl<R: BattleRules + 'static> FlatEvent<R> {
+    /// Converts an event serialized before version 0.9.0 into its current form.
+    ///
+    /// Back then the meaning of `EndRound` and `EndTurn` was swapped, thus they are exchanged.
+    /// `StartRound` and `EnvironmentRound` are already deserialized as `StartTurn` and
+    /// `EnvironmentTurn`.
+    pub fn migrate_legacy_rounds(self) -> Self {
+        match self {
+            FlatEvent::EndRound(_) => FlatEvent::flattened(EndTurn::trigger(&mut ()).event()),
+            FlatEvent::EndTurn(_) => FlatEvent::flattened(EndRound::trigger(&mut ()).event()),
+            event => event,
+        }
+    }
+}
+
+/// Serializes a boxed event trait object as a `FlatEvent`.
+#[allow(clippy::borrowed_box)]
+pub(crate) fn serialize_boxed_event<R, S>(
+    event: &Box<dyn Event<R> + Send>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    R: BattleRules + 'static,
+    S: Serializer,
+    FlatEvent<R>: Serialize,
+{
+    FlatEvent::flattened(event.box_clone()).serialize(serializer)
+}
+
+/// Deserializes a boxed event trait object from a `FlatEvent`.
+pub(crate) fn deserialize_boxed_event<'de, R, D>(
+    deserializer: D,
+) -> Result<Box<dyn Event<R> + Send>, D::Error>
+where
+    R: BattleRules + 'static,
+    D: Deserializer<'de>,
+    FlatEvent<R>: Deserialize<'de>,
+{
+    Ok(FlatEvent::deserialize(deserializer)?.boxed())
+}
+
+/// Serializes a map as a sequence of key-value pairs.
+///
+/// Unlike a serialized map, a sequence of pairs can be encoded in formats that
+/// only support string keys, such as JSON, regardless of the type of the keys.
+pub(crate) fn serialize_pairs<'a, M, K, V, S>(map: &'a M, serializer: S) -> Result<S::Ok, S::Error>
+where
+    &'a M: IntoIterator<Item = (&'a K, &'a V)>,
+    K: Serialize + 'a,
+    V: Serialize + 'a,
+    S: Serializer,
+{
+    serializer.collect_seq(map)
+}
+
+/// Deserializes a map from a sequence of key-value pairs.
+pub(crate) fn deserialize_pairs<'de, M, K, V, D>(deserializer: D) -> Result<M, D::Error>
+where
+    M: std::iter::FromIterator<(K, V)>,
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Ok(Vec::<(K, V)>::deserialize(deserializer)?
+        .into_iter()
+        .collect())
 }
 
 /// A versioned event wrapper containing a flattened event.
@@ -148,6 +301,9 @@ pub struct FlatVersionedEvent<R: BattleRules> {
     id: EventId,
     origin: Option<EventId>,
 
+    #[serde(default)]
+    secondary_origins: Vec<EventId>,
+
     #[serde(bound(
         serialize = "FlatEvent<R>: Serialize",
         deserialize = "FlatEvent<R>: Deserialize<'de>"
@@ -159,19 +315,46 @@ pub struct FlatVersionedEvent<R: BattleRules> {
         deserialize = "Version<R>: Deserialize<'de>"
     ))]
     version: Version<R>,
+
+    #[serde(default)]
+    synthetic: bool,
+
+    #[serde(default)]
+    unverified: bool,
+
+    #[serde(default)]
+    metadata: Option<EventMetadata>,
+
+    #[serde(default)]
+    schema: u32,
 }
 
+/// Current version of the serialization schema of `FlatVersionedEvent`.
+///
+/// Events serialized before schemas were introduced have schema zero.
+pub const FLAT_EVENT_SCHEMA: u32 = 1;
+
 impl<R: BattleRules> FlatVersionedEvent<R> {
     /// Returns the id of this event.
     pub fn id(&self) -> EventId {
         self.id
     }
 
+    /// Returns the version of the serialization schema used by this event.
+    pub fn schema(&self) -> u32 {
+        self.schema
+    }
+
     /// Returns the origin of this event.
     pub fn origin(&self) -> Option<EventId> {
         self.origin
     }
 
+    /// Returns the ids of the events, besides the primary origin, that caused this one.
+    pub fn secondary_origins(&self) -> &[EventId] {
+        &self.secondary_origins
+    }
+
     /// Returns the inner `FlatEvent`.
     pub fn event(&self) -> &FlatEvent<R> {
         &self.event
@@ -181,6 +364,33 @@ impl<R: BattleRules> FlatVersionedEvent<R> {
     pub fn version(&self) -> &Version<R> {
         &self.version
     }
+
+    /// Returns whether this event was synthesized to bootstrap a timeline.
+    pub fn synthetic(&self) -> bool {
+        self.synthetic
+    }
+
+    /// Returns whether this event was applied despite failing verification.
+    pub fn unverified(&self) -> bool {
+        self.unverified
+    }
+
+    /// Returns the user defined data attached to this event, if any.
+    pub fn metadata(&self) -> Option<&[u8]> {
+        self.metadata.as_deref()
+    }
+}
+
+impl<R: BattleRules + 'static> FlatVersionedEvent<R> {
+    /// Converts an event serialized before version 0.9.0 into its current form.
+    ///
+    /// See [migrate_legacy_rounds](enum.FlatEvent.html#method.migrate_legacy_rounds).
+    pub fn migrate_legacy_rounds(self) -> Self {
+        Self {
+            event: self.event.migrate_legacy_rounds(),
+            ..self
+        }
+    }
 }
 
 impl<R: BattleRules + 'static> From<VersionedEventWrapper<R>> for FlatVersionedEvent<R> {
@@ -188,8 +398,13 @@ impl<R: BattleRules + 'static> From<VersionedEventWrapper<R>> for FlatVersionedE
         Self {
             id: event.wrapper().id(),
             origin: event.wrapper().origin(),
+            secondary_origins: event.wrapper().secondary_origins().to_vec(),
+            synthetic: event.wrapper().synthetic(),
+            unverified: event.wrapper().unverified(),
+            metadata: event.wrapper().metadata().map(<[u8]>::to_vec),
             event: FlatEvent::flattened(event.wrapper.event),
             version: event.version,
+            schema: FLAT_EVENT_SCHEMA,
         }
     }
 }
@@ -197,18 +412,656 @@ impl<R: BattleRules + 'static> From<VersionedEventWrapper<R>> for FlatVersionedE
 impl<R: BattleRules + 'static> From<FlatVersionedEvent<R>> for VersionedEventWrapper<R> {
     fn from(event: FlatVersionedEvent<R>) -> Self {
         Self::new(
-            EventWrapper::new(event.id, event.origin, event.event.boxed()),
+            EventWrapper::new(event.id, event.origin, event.event.boxed())
+                .with_secondary_origins(event.secondary_origins)
+                .synthesized(event.synthetic)
+                .forced(event.unverified)
+                .with_metadata(event.metadata),
             event.version,
         )
     }
 }
 
+/// A snapshot of the state of a battle, borrowed from the battle itself.
+///
+/// Restoring a battle from a snapshot takes time proportional to the size of its state,
+/// rather than to the length of its history. A snapshot contains the entities, the space,
+/// rounds and entropy models, the progress of rounds and turns, the battle phase,
+/// players, their rights, the visibility of entities, all metrics and the id of
+/// the next event.\
+/// The rules, the rules' data and the battle's extensions are not part of the snapshot.
+/// Neither is the last known progress of each team towards its objectives, which is
+/// evaluated again by the next objectives check.
+///
+/// Take snapshots with `Battle::snapshot` and serialize them. Deserialize them into an
+/// `OwnedBattleSnapshot` and restore them with `Battle::from_snapshot`.
+#[derive(Serialize)]
+pub struct BattleSnapshot<'a, R: BattleRules> {
+    #[serde(bound(serialize = "Version<R>: Serialize"))]
+    version: &'a Version<R>,
+
+    next_id: EventId,
+
+    #[serde(bound(serialize = "Entities<R>: Serialize"))]
+    entities: &'a Entities<R>,
+
+    #[serde(bound(serialize = "SpaceModel<R>: Serialize"))]
+    space: &'a SpaceModel<R>,
+
+    #[serde(bound(serialize = "Rounds<R>: Serialize"))]
+    rounds: &'a Rounds<R>,
+
+    #[serde(bound(serialize = "EntropyModel<R>: Serialize"))]
+    entropy: &'a EntropyModel<R>,
+
+    phase: BattlePhase,
+
+    end_reason: Option<EndReason>,
+
+    #[serde(bound(serialize = "Option<BattleOutcome<R>>: Serialize"))]
+    outcome: &'a Option<BattleOutcome<R>>,
+
+    #[serde(bound(serialize = "Visibility<R>: Serialize"))]
+    visibility: &'a Visibility<R>,
+
+    #[serde(bound(serialize = "Players<R>: Serialize"))]
+    players: &'a Players<R>,
+
+    #[serde(bound(serialize = "TeamId<R>: Serialize, EntityId<R>: Serialize"))]
+    rights: &'a Rights<R>,
+
+    #[serde(bound(serialize = "MetricIdType<R>: Serialize, UserMetricId<R>: Serialize"))]
+    metrics: &'a Metrics<R>,
+}
+
+impl<'a, R: BattleRules + 'static> BattleSnapshot<'a, R> {
+    /// Creates a snapshot of `battle`.
+    pub(crate) fn new(battle: &'a Battle<R>) -> Self {
+        Self {
+            version: battle.rules().version(),
+            next_id: battle.history().next_id(),
+            entities: &battle.state.entities,
+            space: battle.state.space.model(),
+            rounds: &battle.state.rounds,
+            entropy: battle.entropy.model(),
+            phase: battle.state.phase,
+            end_reason: battle.state.end_reason,
+            outcome: &battle.state.outcome,
+            visibility: &battle.state.visibility,
+            players: &battle.state.players,
+            rights: &battle.rights,
+            metrics: &battle.metrics,
+        }
+    }
+
+    /// Returns the id of the next event of the battle.
+    pub fn next_id(&self) -> EventId {
+        self.next_id
+    }
+}
+
+/// An owned snapshot of the state of a battle, obtained by deserializing a `BattleSnapshot`.
+///
+/// Restore it with `Battle::from_snapshot`.
+#[derive(Deserialize)]
+pub struct OwnedBattleSnapshot<R: BattleRules> {
+    #[serde(bound(deserialize = "Version<R>: Deserialize<'de>"))]
+    version: Version<R>,
+
+    next_id: EventId,
+
+    #[serde(bound(deserialize = "Entities<R>: Deserialize<'de>"))]
+    entities: Entities<R>,
+
+    #[serde(bound(deserialize = "SpaceModel<R>: Deserialize<'de>"))]
+    space: SpaceModel<R>,
+
+    #[serde(bound(
+        deserialize = "R: 'static, RoundsModel<R>: Deserialize<'de>, FlatEvent<R>: Deserialize<'de>"
+    ))]
+    rounds: RoundsState<R>,
+
+    #[serde(bound(deserialize = "EntropyModel<R>: Deserialize<'de>"))]
+    entropy: EntropyModel<R>,
+
+    phase: BattlePhase,
+
+    end_reason: Option<EndReason>,
+
+    #[serde(bound(deserialize = "Option<BattleOutcome<R>>: Deserialize<'de>"))]
+    outcome: Option<BattleOutcome<R>>,
+
+    #[serde(bound(deserialize = "Visibility<R>: Deserialize<'de>"))]
+    visibility: Visibility<R>,
+
+    #[serde(bound(deserialize = "Players<R>: Deserialize<'de>"))]
+    players: Players<R>,
+
+    #[serde(bound(deserialize = "TeamId<R>: Deserialize<'de>, EntityId<R>: Deserialize<'de>"))]
+    rights: Rights<R>,
+
+    #[serde(bound(
+        deserialize = "MetricIdType<R>: Deserialize<'de>, UserMetricId<R>: Deserialize<'de>"
+    ))]
+    metrics: Metrics<R>,
+}
+
+impl<R: BattleRules + 'static> OwnedBattleSnapshot<R> {
+    /// Returns the version of the rules of the battle.
+    pub fn version(&self) -> &Version<R> {
+        &self.version
+    }
+
+    /// Returns the id of the next event of the battle.
+    pub fn next_id(&self) -> EventId {
+        self.next_id
+    }
+
+    /// Returns the entities of the battle.
+    pub fn entities(&self) -> &Entities<R> {
+        &self.entities
+    }
+
+    /// Restores the state contained in this snapshot into `battle`.
+    pub(crate) fn restore(self, battle: &mut Battle<R>) {
+        let state = &mut battle.state;
+        state.entities = self.entities;
+        state.space.restore(self.space);
+        state.rounds.restore(self.rounds);
+        state.phase = self.phase;
+        state.end_reason = self.end_reason;
+        state.outcome = self.outcome;
+        state.visibility = self.visibility;
+        state.players = self.players;
+        *battle.entropy.model_mut() = self.entropy;
+        battle.rights = self.rights;
+        battle.metrics.restore(self.metrics);
+        battle.history.restore_next_id(self.next_id);
+    }
+}
+
+/// A flattened versioned event which may contain an event unknown to this program.
+///
+/// Deserializing a `FlatVersionedEvent` fails if its event kind doesn't exist, for example
+/// when the server runs a newer version with additional user events. Tolerant clients
+/// such as spectators can instead deserialize a `TolerantEvent`, which preserves
+/// unknown events as an [UnknownEvent](struct.UnknownEvent.html).
+///
+/// Tolerant deserialization requires a self-describing format, such as JSON.
+///
+/// # Examples
+/// ```
+/// use weasel::serde::TolerantEvent;
+/// use weasel::{battle_rules, rules::empty::*, BattleRules};
+///
+/// battle_rules! {}
+///
+/// let json = r#"{"id":0,"origin":null,"event":{"Teleport":{"to":3}},"version":0}"#;
+/// let event: TolerantEvent<CustomRules> = serde_json::from_str(json).unwrap();
+/// match event {
+///     TolerantEvent::Unknown(unknown) => assert_eq!(unknown.id(), 0),
+///     TolerantEvent::Known(_) => panic!("Teleport is not an event"),
+/// }
+/// ```
+pub enum TolerantEvent<R: BattleRules> {
+    /// An event that was deserialized correctly.
+    Known(FlatVersionedEvent<R>),
+    /// An event that couldn't be deserialized.
+    Unknown(UnknownEvent<R>),
+}
+
+impl<R: BattleRules> TolerantEvent<R> {
+    /// Returns the id of this event.
+    pub fn id(&self) -> EventId {
+        match self {
+            TolerantEvent::Known(event) => event.id(),
+            TolerantEvent::Unknown(event) => event.id(),
+        }
+    }
+}
+
+impl<'de, R: BattleRules> Deserialize<'de> for TolerantEvent<R>
+where
+    FlatVersionedEvent<R>: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = RawValue::deserialize(deserializer)?;
+        match FlatVersionedEvent::deserialize(raw.clone()) {
+            Ok(event) => Ok(TolerantEvent::Known(event)),
+            Err(err) => {
+                // The event is unknown, but its header must still be valid.
+                let header =
+                    UnknownEventHeader::<R>::deserialize(raw.clone()).map_err(de::Error::custom)?;
+                Ok(TolerantEvent::Unknown(UnknownEvent {
+                    id: header.id,
+                    origin: header.origin,
+                    secondary_origins: header.secondary_origins,
+                    version: header.version,
+                    schema: header.schema,
+                    payload: raw.field("event").cloned().unwrap_or(RawValue::Unit),
+                    reason: err.to_string(),
+                }))
+            }
+        }
+    }
+}
+
+impl<R: BattleRules + 'static> From<TolerantEvent<R>> for VersionedEventWrapper<R> {
+    fn from(event: TolerantEvent<R>) -> Self {
+        match event {
+            TolerantEvent::Known(event) => event.into(),
+            TolerantEvent::Unknown(event) => event.placeholder(),
+        }
+    }
+}
+
+/// The fields of a `FlatVersionedEvent` which are independent from the event kind.
+#[derive(Deserialize)]
+struct UnknownEventHeader<R: BattleRules> {
+    id: EventId,
+    origin: Option<EventId>,
+    #[serde(default)]
+    secondary_origins: Vec<EventId>,
+    #[serde(bound(deserialize = "Version<R>: Deserialize<'de>"))]
+    version: Version<R>,
+    #[serde(default)]
+    schema: u32,
+}
+
+/// An event whose kind is unknown to this program.
+///
+/// It preserves the id and the raw payload of the original event.
+pub struct UnknownEvent<R: BattleRules> {
+    id: EventId,
+    origin: Option<EventId>,
+    secondary_origins: Vec<EventId>,
+    version: Version<R>,
+    schema: u32,
+    payload: RawValue,
+    reason: String,
+}
+
+impl<R: BattleRules> UnknownEvent<R> {
+    /// Returns the id of this event.
+    pub fn id(&self) -> EventId {
+        self.id
+    }
+
+    /// Returns the origin of this event.
+    pub fn origin(&self) -> Option<EventId> {
+        self.origin
+    }
+
+    /// Returns the ids of the events, besides the primary origin, that caused this one.
+    pub fn secondary_origins(&self) -> &[EventId] {
+        &self.secondary_origins
+    }
+
+    /// Returns the rules' version under which this event was created.
+    pub fn version(&self) -> &Version<R> {
+        &self.version
+    }
+
+    /// Returns the version of the serialization schema used by this event.
+    pub fn schema(&self) -> u32 {
+        self.schema
+    }
+
+    /// Returns the raw payload of the event.
+    pub fn payload(&self) -> &RawValue {
+        &self.payload
+    }
+
+    /// Returns a description of why the event couldn't be deserialized.
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+impl<R: BattleRules + 'static> UnknownEvent<R> {
+    /// Returns a `DummyEvent` with the same id, origins and version of this event.
+    ///
+    /// Processing the placeholder in place of the unknown event keeps the ids
+    /// in the history contiguous.
+    pub fn placeholder(self) -> VersionedEventWrapper<R> {
+        VersionedEventWrapper::new(
+            EventWrapper::new(self.id, self.origin, DummyEvent::trigger(&mut ()).event())
+                .with_secondary_origins(self.secondary_origins),
+            self.version,
+        )
+    }
+}
+
+impl<R: BattleRules> std::fmt::Debug for UnknownEvent<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "UnknownEvent {{ id: {:?}, origin: {:?}, secondary_origins: {:?}, version: {:?}, \
+             schema: {:?}, payload: {:?}, reason: {:?} }}",
+            self.id,
+            self.origin,
+            self.secondary_origins,
+            self.version,
+            self.schema,
+            self.payload,
+            self.reason
+        )
+    }
+}
+
+/// A self-describing representation of any serialized value.
+///
+/// It can be serialized again or deserialized into a concrete type.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RawValue {
+    /// A unit value.
+    Unit,
+    /// A boolean.
+    Bool(bool),
+    /// A signed integer.
+    I64(i64),
+    /// An unsigned integer.
+    U64(u64),
+    /// A floating point number.
+    F64(f64),
+    /// A character.
+    Char(char),
+    /// A string.
+    String(String),
+    /// A sequence of bytes.
+    Bytes(Vec<u8>),
+    /// An optional value.
+    Option(Option<Box<RawValue>>),
+    /// A sequence of values.
+    Seq(Vec<RawValue>),
+    /// A map of values.
+    Map(Vec<(RawValue, RawValue)>),
+}
+
+impl RawValue {
+    /// Returns the value associated to the key `name`, if this value is a map.
+    pub fn field(&self, name: &str) -> Option<&RawValue> {
+        match self {
+            RawValue::Map(entries) => entries.iter().find_map(|(key, value)| match key {
+                RawValue::String(key) if key == name => Some(value),
+                _ => None,
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for RawValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            RawValue::Unit => serializer.serialize_unit(),
+            RawValue::Bool(v) => serializer.serialize_bool(*v),
+            RawValue::I64(v) => serializer.serialize_i64(*v),
+            RawValue::U64(v) => serializer.serialize_u64(*v),
+            RawValue::F64(v) => serializer.serialize_f64(*v),
+            RawValue::Char(v) => serializer.serialize_char(*v),
+            RawValue::String(v) => serializer.serialize_str(v),
+            RawValue::Bytes(v) => serializer.serialize_bytes(v),
+            RawValue::Option(None) => serializer.serialize_none(),
+            RawValue::Option(Some(v)) => serializer.serialize_some(v),
+            RawValue::Seq(v) => v.serialize(serializer),
+            RawValue::Map(v) => {
+                let mut map = serializer.serialize_map(Some(v.len()))?;
+                for (key, value) in v {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RawValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(RawValueVisitor)
+    }
+}
+
+/// Visitor to deserialize a `RawValue`.
+struct RawValueVisitor;
+
+impl<'de> Visitor<'de> for RawValueVisitor {
+    type Value = RawValue;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "any value")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<RawValue, E> {
+        Ok(RawValue::Bool(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<RawValue, E> {
+        Ok(RawValue::I64(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<RawValue, E> {
+        Ok(RawValue::U64(v))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<RawValue, E> {
+        Ok(RawValue::F64(v))
+    }
+
+    fn visit_char<E: de::Error>(self, v: char) -> Result<RawValue, E> {
+        Ok(RawValue::Char(v))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<RawValue, E> {
+        Ok(RawValue::String(v.to_owned()))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<RawValue, E> {
+        Ok(RawValue::String(v))
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<RawValue, E> {
+        Ok(RawValue::Bytes(v.to_owned()))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<RawValue, E> {
+        Ok(RawValue::Bytes(v))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<RawValue, E> {
+        Ok(RawValue::Unit)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<RawValue, E> {
+        Ok(RawValue::Option(None))
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<RawValue, D::Error> {
+        let value = RawValue::deserialize(deserializer)?;
+        Ok(RawValue::Option(Some(Box::new(value))))
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<RawValue, D::Error> {
+        RawValue::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<RawValue, A::Error> {
+        let mut values = Vec::new();
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(RawValue::Seq(values))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<RawValue, A::Error> {
+        let mut entries = Vec::new();
+        while let Some(entry) = map.next_entry()? {
+            entries.push(entry);
+        }
+        Ok(RawValue::Map(entries))
+    }
+}
+
+impl<'de> Deserializer<'de> for RawValue {
+    type Error = value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, value::Error> {
+        match self {
+            RawValue::Unit => visitor.visit_unit(),
+            RawValue::Bool(v) => visitor.visit_bool(v),
+            RawValue::I64(v) => visitor.visit_i64(v),
+            RawValue::U64(v) => visitor.visit_u64(v),
+            RawValue::F64(v) => visitor.visit_f64(v),
+            RawValue::Char(v) => visitor.visit_char(v),
+            RawValue::String(v) => visitor.visit_string(v),
+            RawValue::Bytes(v) => visitor.visit_byte_buf(v),
+            RawValue::Option(None) => visitor.visit_none(),
+            RawValue::Option(Some(v)) => visitor.visit_some(*v),
+            RawValue::Seq(v) => {
+                let mut seq = SeqDeserializer::new(v.into_iter());
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            RawValue::Map(v) => {
+                let mut map = MapDeserializer::new(v.into_iter());
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, value::Error> {
+        match self {
+            RawValue::Unit | RawValue::Option(None) => visitor.visit_none(),
+            RawValue::Option(Some(v)) => visitor.visit_some(*v),
+            v => visitor.visit_some(v),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, value::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, value::Error> {
+        match self {
+            RawValue::String(variant) => visitor.visit_enum(variant.into_deserializer()),
+            RawValue::Map(entries) if entries.len() == 1 => visitor.visit_enum(
+                MapAccessDeserializer::new(MapDeserializer::new(entries.into_iter())),
+            ),
+            _ => Err(de::Error::custom("expected an enum")),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, value::Error> for RawValue {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+/// An encoding used to write a stream of events with
+/// [write_events](../battle/struct.Battle.html#method.write_events).
+///
+/// Any `FnMut(&mut dyn Write, &FlatVersionedEvent<R>) -> io::Result<()>` is a format that
+/// writes each event on its own, without any header or footer.
+///
+/// # Examples
+/// ```
+/// use std::io::{self, Write};
+/// use weasel::serde::{EventFormat, FlatVersionedEvent};
+/// use weasel::BattleRules;
+///
+/// /// Writes events as a comma separated list of ids, surrounded by brackets.
+/// struct Ids;
+///
+/// impl<R: BattleRules> EventFormat<R> for Ids {
+///     fn begin(&mut self, writer: &mut dyn Write) -> io::Result<()> {
+///         write!(writer, "[")
+///     }
+///
+///     fn write_event(
+///         &mut self,
+///         writer: &mut dyn Write,
+///         event: &FlatVersionedEvent<R>,
+///         index: usize,
+///     ) -> io::Result<()> {
+///         if index > 0 {
+///             write!(writer, ",")?;
+///         }
+///         write!(writer, "{}", event.id())
+///     }
+///
+///     fn end(&mut self, writer: &mut dyn Write) -> io::Result<()> {
+///         write!(writer, "]")
+///     }
+/// }
+/// ```
+pub trait EventFormat<R: BattleRules> {
+    /// Writes the data preceding all events.
+    fn begin(&mut self, _writer: &mut dyn Write) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Writes a single event. `index` is the position of the event in the stream.
+    fn write_event(
+        &mut self,
+        writer: &mut dyn Write,
+        event: &FlatVersionedEvent<R>,
+        index: usize,
+    ) -> io::Result<()>;
+
+    /// Writes the data following all events.
+    fn end(&mut self, _writer: &mut dyn Write) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<R, F> EventFormat<R> for F
+where
+    R: BattleRules,
+    F: FnMut(&mut dyn Write, &FlatVersionedEvent<R>) -> io::Result<()>,
+{
+    fn write_event(
+        &mut self,
+        writer: &mut dyn Write,
+        event: &FlatVersionedEvent<R>,
+        _index: usize,
+    ) -> io::Result<()> {
+        self(writer, event)
+    }
+}
+
 /// A versioned client event containing a flattened event.
 /// Use this struct to serialize/deserialize a `ClientEventPrototype`.
 #[derive(Serialize, Deserialize)]
 pub struct FlatClientEvent<R: BattleRules> {
     origin: Option<EventId>,
 
+    #[serde(default)]
+    secondary_origins: Vec<EventId>,
+
     #[serde(bound(
         serialize = "FlatEvent<R>: Serialize",
         deserialize = "FlatEvent<R>: Deserialize<'de>"
@@ -222,6 +1075,9 @@ pub struct FlatClientEvent<R: BattleRules> {
     version: Version<R>,
 
     player: Option<PlayerId>,
+
+    #[serde(default)]
+    metadata: Option<EventMetadata>,
 }
 
 impl<R: BattleRules> FlatClientEvent<R> {
@@ -230,6 +1086,11 @@ impl<R: BattleRules> FlatClientEvent<R> {
         self.origin
     }
 
+    /// Returns the ids of the events, besides the primary origin, that caused this one.
+    pub fn secondary_origins(&self) -> &[EventId] {
+        &self.secondary_origins
+    }
+
     /// Returns the inner `FlatEvent`.
     pub fn event(&self) -> &FlatEvent<R> {
         &self.event
@@ -244,6 +1105,11 @@ impl<R: BattleRules> FlatClientEvent<R> {
     pub fn player(&self) -> Option<PlayerId> {
         self.player
     }
+
+    /// Returns the user defined data attached to this event, if any.
+    pub fn metadata(&self) -> Option<&[u8]> {
+        self.metadata.as_deref()
+    }
 }
 
 impl<R: BattleRules + 'static> From<ClientEventPrototype<R>> for FlatClientEvent<R> {
@@ -251,6 +1117,8 @@ impl<R: BattleRules + 'static> From<ClientEventPrototype<R>> for FlatClientEvent
         let player = event.player();
         Self {
             origin: event.origin(),
+            secondary_origins: event.secondary_origins().to_vec(),
+            metadata: event.metadata().map(<[u8]>::to_vec),
             event: FlatEvent::flattened(event.event),
             version: event.version,
             player,
@@ -266,5 +1134,311 @@ impl<R: BattleRules + 'static> From<FlatClientEvent<R>> for ClientEventPrototype
             event.version,
             event.player,
         )
+        .with_secondary_origins(event.secondary_origins)
+        .with_metadata(event.metadata)
+    }
+}
+
+/// A borrowed version of `FlatClientEvent`, used to measure client events without
+/// cloning them. It's serialized exactly as the equivalent `FlatClientEvent`.
+#[derive(Serialize)]
+#[serde(rename = "FlatClientEvent")]
+pub(crate) struct FlatClientEventRef<'a, R: BattleRules> {
+    origin: Option<EventId>,
+
+    secondary_origins: &'a [EventId],
+
+    #[serde(bound(serialize = "FlatEventRef<'a, R>: Serialize"))]
+    event: FlatEventRef<'a, R>,
+
+    #[serde(bound(serialize = "Version<R>: Serialize"))]
+    version: &'a Version<R>,
+
+    player: Option<PlayerId>,
+
+    metadata: Option<&'a [u8]>,
+}
+
+impl<'a, R: BattleRules + 'static> FlatClientEventRef<'a, R> {
+    /// Borrows `event` as a flattened client event.
+    ///
+    /// Returns an error if `event` is an user event that can't be packed.
+    pub(crate) fn new(event: &'a ClientEventPrototype<R>) -> WeaselResult<Self, R> {
+        Ok(Self {
+            origin: event.origin(),
+            secondary_origins: event.secondary_origins(),
+            event: FlatEventRef::borrowed(&*event.event)?,
+            version: &event.version,
+            player: event.player(),
+            metadata: event.metadata(),
+        })
+    }
+}
+
+/// Returns the size in bytes of `value`, once serialized in a compact binary format.
+///
+/// Integers and floats take their native size, while strings, byte arrays, sequences
+/// and maps are prefixed by an eight bytes length. Enum variants take four bytes.\
+/// The result is a transport independent measure of how big a value is, for instance
+/// to limit the payload of events coming from clients.
+///
+/// Returns an error if `value` fails to serialize itself.
+///
+/// # Examples
+/// ```
+/// use weasel::serde::serialized_size;
+///
+/// assert_eq!(serialized_size(&5u32).unwrap(), 4);
+/// assert_eq!(serialized_size(&"weasel").unwrap(), 14);
+/// assert_eq!(serialized_size(&Some(vec![1u8, 2, 3])).unwrap(), 12);
+/// ```
+pub fn serialized_size<T: Serialize + ?Sized>(value: &T) -> Result<usize, SizeError> {
+    let mut counter = SizeCounter { size: 0 };
+    value.serialize(&mut counter)?;
+    Ok(counter.size)
+}
+
+/// Size in bytes of the length prefix of strings and collections.
+const LENGTH_SIZE: usize = 8;
+
+/// Size in bytes of an enum variant's index.
+const VARIANT_SIZE: usize = 4;
+
+/// Serializer that counts bytes instead of producing any output.
+struct SizeCounter {
+    size: usize,
+}
+
+impl SizeCounter {
+    fn add(&mut self, size: usize) -> std::result::Result<(), SizeError> {
+        self.size = self.size.saturating_add(size);
+        Ok(())
+    }
+}
+
+/// Error produced by a failed size computation.
+#[derive(Debug)]
+pub struct SizeError(String);
+
+impl std::fmt::Display for SizeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for SizeError {}
+
+impl serde::ser::Error for SizeError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        SizeError(msg.to_string())
+    }
+}
+
+/// Generates the serializer methods for primitive types.
+macro_rules! size_of_primitive {
+    ($( $method:ident, $t:ty ),* $(,)?) => {
+        $(fn $method(self, _: $t) -> std::result::Result<(), SizeError> {
+            self.add(std::mem::size_of::<$t>())
+        })*
+    };
+}
+
+impl serde::Serializer for &mut SizeCounter {
+    type Ok = ();
+    type Error = SizeError;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    size_of_primitive! {
+        serialize_bool, bool,
+        serialize_i8, i8,
+        serialize_i16, i16,
+        serialize_i32, i32,
+        serialize_i64, i64,
+        serialize_u8, u8,
+        serialize_u16, u16,
+        serialize_u32, u32,
+        serialize_u64, u64,
+        serialize_f32, f32,
+        serialize_f64, f64,
+        serialize_char, char,
+    }
+
+    fn serialize_str(self, v: &str) -> std::result::Result<(), SizeError> {
+        self.add(LENGTH_SIZE + v.len())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> std::result::Result<(), SizeError> {
+        self.add(LENGTH_SIZE + v.len())
+    }
+
+    fn serialize_none(self) -> std::result::Result<(), SizeError> {
+        self.add(1)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(
+        self,
+        value: &T,
+    ) -> std::result::Result<(), SizeError> {
+        self.add(1)?;
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> std::result::Result<(), SizeError> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> std::result::Result<(), SizeError> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+    ) -> std::result::Result<(), SizeError> {
+        self.add(VARIANT_SIZE)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> std::result::Result<(), SizeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        value: &T,
+    ) -> std::result::Result<(), SizeError> {
+        self.add(VARIANT_SIZE)?;
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> std::result::Result<Self, SizeError> {
+        self.add(LENGTH_SIZE)?;
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _: usize) -> std::result::Result<Self, SizeError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> std::result::Result<Self, SizeError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> std::result::Result<Self, SizeError> {
+        self.add(VARIANT_SIZE)?;
+        Ok(self)
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> std::result::Result<Self, SizeError> {
+        self.add(LENGTH_SIZE)?;
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> std::result::Result<Self, SizeError> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> std::result::Result<Self, SizeError> {
+        self.add(VARIANT_SIZE)?;
+        Ok(self)
+    }
+}
+
+/// Generates the implementation of a compound serializer trait for `SizeCounter`.
+macro_rules! size_of_compound {
+    ($trait:ident, $method:ident) => {
+        impl serde::ser::$trait for &mut SizeCounter {
+            type Ok = ();
+            type Error = SizeError;
+
+            fn $method<T: Serialize + ?Sized>(
+                &mut self,
+                value: &T,
+            ) -> std::result::Result<(), SizeError> {
+                value.serialize(&mut **self)
+            }
+
+            fn end(self) -> std::result::Result<(), SizeError> {
+                Ok(())
+            }
+        }
+    };
+    ($trait:ident) => {
+        impl serde::ser::$trait for &mut SizeCounter {
+            type Ok = ();
+            type Error = SizeError;
+
+            fn serialize_field<T: Serialize + ?Sized>(
+                &mut self,
+                _: &'static str,
+                value: &T,
+            ) -> std::result::Result<(), SizeError> {
+                value.serialize(&mut **self)
+            }
+
+            fn end(self) -> std::result::Result<(), SizeError> {
+                Ok(())
+            }
+        }
+    };
+}
+
+size_of_compound!(SerializeSeq, serialize_element);
+size_of_compound!(SerializeTuple, serialize_element);
+size_of_compound!(SerializeTupleStruct, serialize_field);
+size_of_compound!(SerializeTupleVariant, serialize_field);
+size_of_compound!(SerializeStruct);
+size_of_compound!(SerializeStructVariant);
+
+impl serde::ser::SerializeMap for &mut SizeCounter {
+    type Ok = ();
+    type Error = SizeError;
+
+    fn serialize_key<T: Serialize + ?Sized>(
+        &mut self,
+        key: &T,
+    ) -> std::result::Result<(), SizeError> {
+        key.serialize(&mut **self)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> std::result::Result<(), SizeError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> std::result::Result<(), SizeError> {
+        Ok(())
     }
 }
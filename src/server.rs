@@ -1,14 +1,56 @@
 //! A battle server.
 
-use crate::battle::{Battle, BattleController, BattleRules, EventCallback};
-use crate::error::{WeaselError, WeaselResult};
+use crate::ability::AbilitiesSeed;
+use crate::actor::{ActionSuggestion, Actor, ActorRules, RegenerateAbilities, TrackedAbility};
+use crate::aura::projected_statuses;
+use crate::battle::{
+    Battle, BattleController, BattlePhase, BattleRules, EndBattle, EndReason, EventCallback,
+    Handshake, StartBattle, VerifyState,
+};
+use crate::character::{RegenerateStatistics, StatisticsSeed};
+use crate::creature::{
+    CreateCreature, CreatureId, ReidentifyCreature, SpawnWave, TransferOwnership, TransformCreature,
+};
+use crate::entity::{AlterTags, Entity, EntityId, SetTimeToLive};
+use crate::entropy::ResetEntropy;
+use crate::error::{WeaselError, WeaselErrorType, WeaselResult};
 use crate::event::{
-    ClientEventPrototype, EventProcessor, EventPrototype, EventQueue, EventReceiver, EventRights,
-    EventServer, EventWrapper, MultiClientSink, MultiClientSinkHandle, MultiClientSinkHandleMut,
+    ClientEventPrototype, ClientSink, Event, EventId, EventKind, EventProcessor, EventPrototype,
+    EventQueue, EventReceiver, EventRights, EventServer, EventSinkId, EventTrigger, EventWrapper,
+    MultiClientSink, MultiClientSinkHandle, MultiClientSinkHandleMut, SinkStatus,
     VersionedEventWrapper,
 };
-use crate::player::{PlayerId, RightsHandle, RightsHandleMut};
-use crate::team::TeamId;
+use crate::fight::FightRules;
+use crate::interceptor::{EventInterceptor, Interceptors};
+#[cfg(feature = "serialization")]
+use crate::metric::system::OVERSIZED_EVENTS_REJECTED;
+use crate::object::{CreateObject, ObjectId, ObjectOwner, TransferObjectOwnership};
+use crate::player::{PlayerId, RegisterPlayer, RightsHandle, RightsHandleMut};
+use crate::power::PowersSeed;
+use crate::round::{
+    EndTurn, ResetRounds, RestoreRounds, RoundsCount, ScheduleEvent, StartTurn, Tick, TicksCount,
+    TurnState, TurnTimedOut, TurnsCount,
+};
+#[cfg(feature = "serialization")]
+use crate::serde::{serialized_size, FlatClientEventRef};
+use crate::space::{AlterSpace, ResetSpace};
+use crate::status::InflictStatus;
+use crate::team::{
+    AddScore, ConcludeObjectives, CreateTeam, ObjectivesSeed, RegeneratePowers, RenameTeam,
+    ResetObjectives, SetRelations, TeamId,
+};
+use crate::transaction::Transaction;
+use crate::util::Id;
+use crate::visibility::{filter, RevealEntity, VisibilityRules};
+use indexmap::IndexMap;
+use log::warn;
+#[cfg(feature = "serialization")]
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{BuildHasher, Hasher};
+use std::ops::Range;
+use std::time::{Duration, Instant};
 
 /// The server is the main object used to orchestrate a battle.
 ///
@@ -21,6 +63,28 @@ pub struct Server<R: BattleRules> {
     pub(crate) battle: Battle<R>,
     client_sinks: MultiClientSink<R>,
     authentication: bool,
+    response_window: Option<ResponseWindow>,
+    held_resolution: Option<HeldResolution<R>>,
+    bootstrap_callback: Option<BootstrapCallback<R>>,
+    pre_broadcast: Option<PreBroadcastCallback<R>>,
+    visibility_rules: Option<Box<dyn VisibilityRules<R> + Send>>,
+    sandbox: bool,
+    sandbox_report: Vec<SandboxWarning<R>>,
+    tick_interval: Option<Duration>,
+    last_tick: Instant,
+    turn_timeout: Option<Duration>,
+    turn_started: Instant,
+    verification_interval: Option<EventId>,
+    next_verification: EventId,
+    #[cfg(feature = "serialization")]
+    max_event_size: Option<usize>,
+    shut_down: bool,
+    pending: IndexMap<Option<PlayerId>, VecDeque<ClientEventPrototype<R>>>,
+    schedule_cursor: usize,
+    sessions: HashMap<SessionToken, Session>,
+    token_state: RandomState,
+    interceptors: Interceptors<R>,
+    issued_tokens: u64,
 }
 
 impl<R: BattleRules + 'static> Server<R> {
@@ -29,6 +93,17 @@ impl<R: BattleRules + 'static> Server<R> {
         ServerBuilder {
             battle,
             authentication: false,
+            response_window: None,
+            bootstrap_callback: None,
+            pre_broadcast: None,
+            visibility_rules: None,
+            sandbox: false,
+            tick_interval: None,
+            turn_timeout: None,
+            verification_interval: None,
+            forward_metrics: false,
+            #[cfg(feature = "serialization")]
+            max_event_size: None,
         }
     }
 
@@ -55,6 +130,662 @@ impl<R: BattleRules + 'static> Server<R> {
     /// Returns a mutable handle to manage the client sinks of this server.
     pub fn client_sinks_mut(&mut self) -> MultiClientSinkHandleMut<'_, R> {
         MultiClientSinkHandleMut::new(&mut self.client_sinks, &self.battle)
+            .pre_broadcast(self.pre_broadcast.as_ref())
+            .visibility_rules(self.visibility_rules.as_deref())
+    }
+
+    /// Sends to the client sink with id `sink_id` all events starting from the one with
+    /// id `from` up to the most recent event.
+    ///
+    /// Use this method to answer a client that requested a resynchronization through
+    /// `ServerSink::request_resync`.\
+    /// Returns the id of the next event, to be passed to the client's `Client::sync_to`.
+    pub fn sync_events_for(
+        &mut self,
+        sink_id: EventSinkId,
+        from: EventId,
+    ) -> WeaselResult<EventId, R> {
+        let next_id = self.battle.history().next_id();
+        self.client_sinks_mut().send_range(sink_id, from..next_id)?;
+        Ok(next_id)
+    }
+
+    /// Attaches a new client sink on behalf of `player` and opens a session for it.
+    ///
+    /// The sink is added as with `MultiClientSinkHandleMut::add_player_sink`, or `add_sink`
+    /// if there's no player: sharing the battle history with the client is up to the caller.\
+    /// Returns a token that the client can present to `resume_session` after a reconnection.
+    /// Tokens are hard to guess, but they are not cryptographically secure.
+    ///
+    /// # Examples
+    /// ```
+    /// use weasel::event::{ClientSink, EventSink, EventSinkId, VersionedEventWrapper};
+    /// use weasel::{
+    ///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateTeam,
+    ///     EventTrigger, Server, WeaselResult,
+    /// };
+    ///
+    /// battle_rules! {}
+    ///
+    /// struct Sink(EventSinkId);
+    ///
+    /// impl EventSink for Sink {
+    ///     fn id(&self) -> EventSinkId {
+    ///         self.0
+    ///     }
+    /// }
+    ///
+    /// impl ClientSink<CustomRules> for Sink {
+    ///     fn send(
+    ///         &mut self,
+    ///         _: &VersionedEventWrapper<CustomRules>,
+    ///     ) -> WeaselResult<(), CustomRules> {
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let battle = Battle::builder(CustomRules::new()).build();
+    /// let mut server = Server::builder(battle).build();
+    ///
+    /// let token = server.open_session(Box::new(Sink(1)), Some(7)).unwrap();
+    /// server.client_sinks_mut().remove_sink(1);
+    /// CreateTeam::trigger(&mut server, 1).fire().unwrap();
+    ///
+    /// // The client reconnects and receives the events it missed.
+    /// let player = server.resume_session(token, Box::new(Sink(2))).unwrap();
+    /// assert_eq!(player, Some(7));
+    /// assert_eq!(server.session(token).unwrap().sink_id(), 2);
+    /// ```
+    pub fn open_session(
+        &mut self,
+        sink: Box<dyn ClientSink<R> + Send>,
+        player: Option<PlayerId>,
+    ) -> WeaselResult<SessionToken, R> {
+        if self.shut_down {
+            return Err(WeaselError::ServerShutDown);
+        }
+        let sink_id = sink.id();
+        match player {
+            Some(player) => self.client_sinks_mut().add_player_sink(sink, player)?,
+            None => self.client_sinks_mut().add_sink(sink)?,
+        }
+        let token = self.issue_token();
+        self.sessions.insert(token, Session { sink_id, player });
+        Ok(token)
+    }
+
+    /// Resumes the session identified by `token` with a new sink.
+    ///
+    /// The previous sink of the session is removed, if still attached. The new sink
+    /// receives all events the previous one didn't receive, while the player's rights and
+    /// its queued client events are kept.\
+    /// Returns the player of the session.
+    pub fn resume_session(
+        &mut self,
+        token: SessionToken,
+        sink: Box<dyn ClientSink<R> + Send>,
+    ) -> WeaselResult<Option<PlayerId>, R> {
+        if self.shut_down {
+            return Err(WeaselError::ServerShutDown);
+        }
+        let session = *self
+            .sessions
+            .get(&token)
+            .ok_or(WeaselError::InvalidSessionToken)?;
+        let next_id = self.battle.history().next_id();
+        let resume_from = self
+            .client_sinks
+            .detach(session.sink_id, next_id)
+            .unwrap_or(next_id);
+        let sink_id = sink.id();
+        let mut sinks = self.client_sinks_mut();
+        match session.player {
+            Some(player) => sinks.add_player_sink(sink, player)?,
+            None => sinks.add_sink(sink)?,
+        }
+        sinks.send_range(sink_id, resume_from..next_id)?;
+        self.sessions.insert(
+            token,
+            Session {
+                sink_id,
+                player: session.player,
+            },
+        );
+        Ok(session.player)
+    }
+
+    /// Adds an interceptor at the end of this server's chain of interceptors.
+    ///
+    /// Interceptors are invoked for all events processed by the server, including
+    /// derived events and those fired by clients.
+    pub fn add_interceptor(&mut self, interceptor: Box<dyn EventInterceptor<R> + Send>) {
+        self.interceptors.add(interceptor);
+    }
+
+    /// Removes all interceptors from this server.
+    pub fn clear_interceptors(&mut self) {
+        self.interceptors.clear();
+    }
+
+    /// Closes the session identified by `token` and removes its sink.
+    ///
+    /// Returns false if there was no such session.
+    pub fn close_session(&mut self, token: SessionToken) -> bool {
+        match self.sessions.remove(&token) {
+            Some(session) => {
+                let next_id = self.battle.history().next_id();
+                self.client_sinks.detach(session.sink_id, next_id);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the session identified by `token`, if any.
+    pub fn session(&self, token: SessionToken) -> Option<&Session> {
+        self.sessions.get(&token)
+    }
+
+    /// Returns a new unique session token.
+    fn issue_token(&mut self) -> SessionToken {
+        loop {
+            let mut hasher = self.token_state.build_hasher();
+            hasher.write_u64(self.issued_tokens);
+            self.issued_tokens += 1;
+            let token = hasher.finish();
+            if !self.sessions.contains_key(&token) {
+                return token;
+            }
+        }
+    }
+
+    /// Verifies that a client's handshake is compatible with the rules of this server.
+    ///
+    /// Clients with a different rules' version or with different rules data
+    /// should not be connected to the server.
+    pub fn verify_handshake(&self, handshake: &Handshake<R>) -> WeaselResult<(), R> {
+        self.battle.verify_handshake(handshake)
+    }
+
+    /// Returns true if this server is in sandbox mode.
+    pub fn sandbox(&self) -> bool {
+        self.sandbox
+    }
+
+    /// Enables or disables the sandbox mode.
+    ///
+    /// See [sandbox](struct.ServerBuilder.html#method.sandbox).
+    pub fn set_sandbox(&mut self, sandbox: bool) {
+        self.sandbox = sandbox;
+    }
+
+    /// Returns true if this server forwards modifications of user metrics to its client sinks.
+    pub fn forwards_metrics(&self) -> bool {
+        self.battle.metrics.tracking_changes()
+    }
+
+    /// Enables or disables the forwarding of user metrics modifications.
+    ///
+    /// See [forward_metrics](struct.ServerBuilder.html#method.forward_metrics).
+    pub fn set_forward_metrics(&mut self, forward: bool) {
+        if forward != self.forwards_metrics() {
+            self.battle.metrics.track_changes(forward);
+        }
+    }
+
+    /// Returns all verification failures downgraded to warnings while in sandbox mode.
+    pub fn sandbox_report(&self) -> &[SandboxWarning<R>] {
+        &self.sandbox_report
+    }
+
+    /// Removes and returns all warnings in the sandbox report.
+    pub fn take_sandbox_report(&mut self) -> Vec<SandboxWarning<R>> {
+        std::mem::take(&mut self.sandbox_report)
+    }
+
+    /// Returns the maximum serialized size of client events accepted by this server, if any.
+    ///
+    /// See [max_event_size](struct.ServerBuilder.html#method.max_event_size).
+    #[cfg(feature = "serialization")]
+    pub fn max_event_size(&self) -> Option<usize> {
+        self.max_event_size
+    }
+
+    /// Rejects the client event if it's bigger than the maximum size.
+    #[cfg(feature = "serialization")]
+    fn check_event_size(&mut self, event: &ClientEventPrototype<R>) -> WeaselResult<(), R> {
+        if let Some(max) = self.max_event_size {
+            let event = FlatClientEventRef::new(event)?;
+            let size = serialized_size(&event)
+                .map_err(|err| WeaselError::EventSerializationError(err.to_string()))?;
+            if size > max {
+                self.battle
+                    .metrics
+                    .write_handle()
+                    .add_system_u64(OVERSIZED_EVENTS_REJECTED, 1)
+                    .unwrap_or_else(|err| panic!("constraint violated: {:?}", err));
+                return Err(WeaselError::EventTooLarge(size, max));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the response window configuration of this server, if any.
+    pub fn response_window(&self) -> Option<&ResponseWindow> {
+        self.response_window.as_ref()
+    }
+
+    /// Returns the id of the event that opened the current response window.
+    /// Returns `None` if no response window is open.
+    pub fn open_response_window(&self) -> Option<EventId> {
+        self.held_resolution.as_ref().map(|held| held.origin)
+    }
+
+    /// Closes the current response window, if any, and resumes the resolution
+    /// of the events derived from the event that opened it.
+    pub fn close_response_window(&mut self) -> WeaselResult<(), R> {
+        if let Some(held) = self.held_resolution.take() {
+            let event_queue = held
+                .events
+                .into_iter()
+                .map(|held| {
+                    let mut prototype = EventPrototype::new(held.event);
+                    prototype.set_origin(held.origin);
+                    prototype.set_secondary_origins(held.secondary_origins);
+                    prototype
+                })
+                .collect();
+            self.process_derived(event_queue, held.origin)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Closes the current response window if its timeout has expired.
+    ///
+    /// This method is invoked automatically each time a client event is processed.
+    pub fn update_response_window(&mut self) -> WeaselResult<(), R> {
+        let expired = match (&self.held_resolution, &self.response_window) {
+            (
+                Some(held),
+                Some(ResponseWindow {
+                    timeout: Some(timeout),
+                    ..
+                }),
+            ) => held.opened.elapsed() >= *timeout,
+            _ => false,
+        };
+        if expired {
+            self.close_response_window()
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Terminates the battle cleanly, before the server is dropped.
+    ///
+    /// The server stops accepting client events and resolves the events held by an open
+    /// response window. Then, if requested by `policy`, the battle is ended.
+    /// Finally all client sinks receive their buffered events, are flushed and
+    /// disconnected with the reason given in `policy`.\
+    /// Errors don't interrupt the shutdown: they are collected and returned together
+    /// at the end. Calling this method again does nothing.
+    ///
+    /// # Examples
+    /// ```
+    /// use weasel::battle::BattlePhase;
+    /// use weasel::server::{ShutdownPolicy, ShutdownReason};
+    /// use weasel::{
+    ///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, EventServer,
+    ///     EventTrigger, ResetEntropy, Server, WeaselError,
+    /// };
+    ///
+    /// battle_rules! {}
+    ///
+    /// let battle = Battle::builder(CustomRules::new()).build();
+    /// let mut server = Server::builder(battle).build();
+    ///
+    /// let policy = ShutdownPolicy::new(ShutdownReason::Completed).end_battle();
+    /// assert_eq!(server.shutdown(policy).err(), None);
+    /// assert_eq!(server.battle().phase(), BattlePhase::Ended);
+    ///
+    /// let event = ResetEntropy::trigger(&mut ())
+    ///     .prototype()
+    ///     .client_prototype(0, None);
+    /// assert_eq!(
+    ///     server.process_client(event).err(),
+    ///     Some(WeaselError::ServerShutDown)
+    /// );
+    /// ```
+    pub fn shutdown(&mut self, policy: ShutdownPolicy) -> WeaselResult<(), R> {
+        if self.shut_down {
+            return Ok(());
+        }
+        self.shut_down = true;
+        let mut errors = Vec::new();
+        // Don't leave derived events unresolved.
+        if let Err(err) = self.close_response_window() {
+            errors.push(err);
+        }
+        if policy.end_battle && self.battle.phase() != BattlePhase::Ended {
+            if let Err(err) = EndBattle::trigger(self).reason(EndReason::Aborted).fire() {
+                errors.push(err);
+            }
+        }
+        errors.extend(self.client_sinks.shutdown(policy.reason));
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.remove(0)),
+            _ => Err(WeaselError::MultiError(errors)),
+        }
+    }
+
+    /// Returns true if the server has been shut down.
+    pub fn is_shut_down(&self) -> bool {
+        self.shut_down
+    }
+
+    /// Queues a client event, to be processed at the next `process_pending`.
+    ///
+    /// Queued events are grouped by the player who sent them. Use this method instead of
+    /// `process_client` when many clients submit events at the same time, so that a single
+    /// fast connection can't starve the others.
+    pub fn enqueue_client(&mut self, event: ClientEventPrototype<R>) {
+        self.pending
+            .entry(event.player())
+            .or_default()
+            .push_back(event);
+    }
+
+    /// Returns the number of queued client events.
+    pub fn pending_client_events(&self) -> usize {
+        self.pending.values().map(VecDeque::len).sum()
+    }
+
+    /// Processes all queued client events, interleaving them round-robin between players.
+    ///
+    /// Each pass processes at most one event per player, in the order players queued
+    /// their first pending event. The player served first rotates at each invocation.\
+    /// Returns the player and the outcome of each processed event, in processing order.
+    ///
+    /// # Examples
+    /// ```
+    /// use weasel::event::DummyEvent;
+    /// use weasel::{battle_rules, rules::empty::*, Battle, BattleRules, EventTrigger, Server};
+    ///
+    /// battle_rules! {}
+    ///
+    /// let battle = Battle::builder(CustomRules::new()).build();
+    /// let mut server = Server::builder(battle).build();
+    ///
+    /// let event = |player| {
+    ///     DummyEvent::trigger(&mut ())
+    ///         .prototype()
+    ///         .client_prototype(0, Some(player))
+    /// };
+    /// // Player 1 floods the server before player 2 submits its event.
+    /// server.enqueue_client(event(1));
+    /// server.enqueue_client(event(1));
+    /// server.enqueue_client(event(2));
+    /// let players: Vec<_> = server
+    ///     .process_pending()
+    ///     .into_iter()
+    ///     .map(|(player, _)| player.unwrap())
+    ///     .collect();
+    /// assert_eq!(players, vec![1, 2, 1]);
+    /// ```
+    pub fn process_pending(&mut self) -> Vec<(Option<PlayerId>, WeaselResult<(), R>)> {
+        let mut outcomes = Vec::with_capacity(self.pending_client_events());
+        let players = self.pending.len();
+        if players == 0 {
+            return outcomes;
+        }
+        let start = self.schedule_cursor % players;
+        self.schedule_cursor = start + 1;
+        loop {
+            let mut progressed = false;
+            for i in 0..players {
+                let index = (start + i) % players;
+                let (player, queue) = self.pending.get_index_mut(index).unwrap();
+                let player = *player;
+                if let Some(event) = queue.pop_front() {
+                    progressed = true;
+                    let result = self.process_client(event);
+                    outcomes.push((player, result));
+                }
+            }
+            if !progressed {
+                break;
+            }
+        }
+        // Forget the players whose queue is drained.
+        self.pending.retain(|_, queue| !queue.is_empty());
+        outcomes
+    }
+
+    /// Returns the interval between two simulation ticks, if ticks are emitted at a fixed cadence.
+    pub fn tick_interval(&self) -> Option<Duration> {
+        self.tick_interval
+    }
+
+    /// Fires a `Tick` event advancing the simulation by one tick.
+    pub fn fire_tick(&mut self) -> WeaselResult<(), R> {
+        let tick = self.battle.rounds().ticks() + 1;
+        Tick::trigger(self, tick).fire()
+    }
+
+    /// Fires a `VerifyState` event carrying the digest of the current battle state.
+    pub fn verify_state(&mut self) -> WeaselResult<(), R> {
+        let digest = self.battle.state_digest();
+        VerifyState::trigger(self, digest).fire()
+    }
+
+    /// Fires a `VerifyState` event if it's due, according to the state verification interval.
+    fn verify_state_if_due(&mut self) -> WeaselResult<(), R> {
+        let interval = match self.verification_interval {
+            Some(interval) => interval,
+            None => return Ok(()),
+        };
+        let next_id = self.battle.history().next_id();
+        if next_id < self.next_verification || self.battle.phase() == BattlePhase::Ended {
+            return Ok(());
+        }
+        self.next_verification = next_id + interval;
+        self.verify_state()
+    }
+
+    /// Fires all `Tick` events due since the last update, according to the tick interval.
+    ///
+    /// This method should be invoked periodically, for instance once per frame.
+    /// Returns the number of ticks fired. Without a tick interval, no tick is ever fired.
+    pub fn update_ticks(&mut self) -> WeaselResult<TicksCount, R> {
+        let interval = match self.tick_interval {
+            Some(interval) => interval,
+            None => return Ok(0),
+        };
+        let due = (self.last_tick.elapsed().as_nanos() / interval.as_nanos()) as TicksCount;
+        for _ in 0..due {
+            self.last_tick += interval;
+            self.fire_tick()?;
+        }
+        Ok(due)
+    }
+
+    /// Returns the time at disposal of the actors to complete their turn, if turns have a timeout.
+    pub fn turn_timeout(&self) -> Option<Duration> {
+        self.turn_timeout
+    }
+
+    /// Fires a `TurnTimedOut` event if the turn in progress lasted longer than the turn timeout.
+    ///
+    /// This method should be invoked periodically, for instance once per frame.
+    /// Returns true if the turn in progress has been ended. Without a turn timeout,
+    /// turns never time out.
+    pub fn check_timeouts(&mut self) -> WeaselResult<bool, R> {
+        let timeout = match self.turn_timeout {
+            Some(timeout) => timeout,
+            None => return Ok(false),
+        };
+        let actors: Vec<_> = match self.battle.rounds().state() {
+            TurnState::Started(actors) => actors.iter().cloned().collect(),
+            TurnState::Ready => return Ok(false),
+        };
+        if self.turn_started.elapsed() < timeout {
+            return Ok(false);
+        }
+        TurnTimedOut::trigger(self, actors).fire()?;
+        Ok(true)
+    }
+
+    /// Returns the actor who should act next, according to `RoundsRules::order`.
+    pub fn next_actor(&self) -> Option<EntityId<R>> {
+        self.battle
+            .rounds()
+            .order(self.battle.entities())
+            .into_iter()
+            .next()
+    }
+
+    /// Plays the turn of a computer-controlled actor.
+    ///
+    /// Starts a turn for the actor with the given id, fires the events suggested by
+    /// `ActorRules::suggest_action` and then ends the turn, if it's still in progress.
+    /// Returns the number of suggested events.\
+    /// Stops at the first event that fails, leaving the turn in progress.
+    pub fn run_ai_turn(&mut self, entity_id: EntityId<R>) -> WeaselResult<usize, R> {
+        StartTurn::trigger(self, entity_id.clone()).fire()?;
+        let suggestion = self.battle.entities().actor(&entity_id).and_then(|actor| {
+            self.battle
+                .rules
+                .actor_rules()
+                .suggest_action(&self.battle.state, actor)
+        });
+        let events = suggestion.map_or_else(Vec::new, ActionSuggestion::into_events);
+        let count = events.len();
+        for event in events {
+            self.process(event)?;
+        }
+        if self.battle.rounds().is_acting(&entity_id) {
+            EndTurn::trigger(self).fire()?;
+        }
+        Ok(count)
+    }
+
+    /// Returns a minimal set of events reproducing the current state of the battle,
+    /// to be sent to a client joining late in place of the full history.
+    ///
+    /// The events are synthesized out of the battle's current state and of the seeds found in
+    /// the history. They recreate the entropy, space and rounds models, the ticks counter,
+    /// all teams, creatures and objects, relations between teams, objectives conclusions,
+    /// creatures' owners and the battle phase. The counters of completed rounds and turns,
+    /// the current turn, the abilities' cooldowns and the pending ability activations
+    /// are restored directly with a `RestoreRounds` event, without invoking the rules' callbacks.
+    /// The state owned by the rules, such as the current value of statistics, abilities and
+    /// powers or the active status effects, can't be reproduced by the library alone.
+    /// Use `ServerBuilder::bootstrap_callback` to append the events restoring it.
+    ///
+    /// The following state is rebuilt by the client rather than transferred:
+    /// - The entropy model is regenerated from the seed of the latest `ResetEntropy`,
+    ///   thus it doesn't account for the random values drawn since then.
+    ///   Fire a `ResetEntropy` to bring the entropy of all clients back in sync.
+    /// - Teams and entities are recreated by their creation events, which invoke
+    ///   the rules' callbacks once more, e.g. `RoundsRules::on_actor_added`.
+    ///   The rounds model is regenerated from the seed of the latest `ResetRounds`
+    ///   and it is altered only by these callbacks.
+    /// - The data of each team is set to its default value.
+    /// - Actors don't remember their recent activations.
+    /// - In a battle restored with `Battle::from_snapshot`, the seeds that precede the
+    ///   snapshot are unknown and the models are regenerated from the default seeds.
+    ///
+    /// The derived statuses projected by auras are synthesized after the callback's events,
+    /// thus the callback must restore only the auras themselves.
+    ///
+    /// Synthetic events take the ids immediately preceding the next event of this server,
+    /// so that the client's timeline can continue seamlessly. They are marked as `synthetic`
+    /// and they don't have an origin. The client's history will start from the first
+    /// synthetic event; origins of later events pointing to pruned ids can be detected
+    /// with `History::event`.
+    ///
+    /// If the synthesized events are not fewer than the events in the history,
+    /// the full history is returned instead.
+    ///
+    /// # Examples
+    /// ```
+    /// use weasel::{
+    ///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateTeam,
+    ///     EventTrigger, RenameTeam, Server,
+    /// };
+    ///
+    /// battle_rules! {}
+    ///
+    /// let battle = Battle::builder(CustomRules::new()).build();
+    /// let mut server = Server::builder(battle).build();
+    ///
+    /// CreateTeam::trigger(&mut server, 1).fire().unwrap();
+    /// RenameTeam::trigger(&mut server, 1, 2).fire().unwrap();
+    /// RenameTeam::trigger(&mut server, 2, 3).fire().unwrap();
+    ///
+    /// let events = server.bootstrap_events_for_new_client();
+    /// assert_eq!(events.len(), 1);
+    /// assert_eq!(events[0].id(), 2);
+    /// assert!(events[0].synthetic());
+    /// ```
+    pub fn bootstrap_events_for_new_client(&self) -> Vec<VersionedEventWrapper<R>> {
+        compact_history(&self.battle, self.bootstrap_callback.as_ref())
+    }
+
+    /// Starts a new transaction, to verify a sequence of events against the projected
+    /// state of the battle and then apply them atomically.
+    ///
+    /// `factory` must return servers in the same initial state as this one.
+    /// Its history is replayed on a new server, thus it can't be compacted past
+    /// the events already contained in the servers returned by `factory`.
+    pub fn transaction(
+        &mut self,
+        factory: &dyn Fn() -> Server<R>,
+    ) -> WeaselResult<Transaction<'_, R>, R> {
+        Transaction::new(self, factory)
+    }
+
+    /// Returns health information about this server, meant to be polled by hosting services.
+    ///
+    /// # Examples
+    /// ```
+    /// use weasel::{battle_rules, rules::empty::*, Battle, BattleRules, Server};
+    /// use weasel::battle::BattlePhase;
+    ///
+    /// battle_rules! {}
+    ///
+    /// let server = Server::builder(Battle::builder(CustomRules::new()).build()).build();
+    /// let status = server.status();
+    /// assert_eq!(status.phase(), BattlePhase::Started);
+    /// assert_eq!(status.events(), 0);
+    /// assert_eq!(status.sinks().len(), 0);
+    /// ```
+    pub fn status(&self) -> ServerStatus {
+        let rounds = self.battle.rounds();
+        ServerStatus {
+            phase: self.battle.phase(),
+            events: self.battle.history().len(),
+            sinks: self.client_sinks.statuses(),
+            rights: self.rights().get().map(|(_, teams)| teams.len()).sum(),
+            completed_rounds: rounds.completed_rounds(),
+            completed_turns: rounds.completed_turns(),
+            shut_down: self.shut_down,
+        }
+    }
+
+    /// Sends an event to all client sinks, filtering it with the visibility rules.
+    fn broadcast(&mut self, event: &VersionedEventWrapper<R>) {
+        match &self.visibility_rules {
+            Some(rules) => {
+                let battle = &self.battle;
+                self.client_sinks.send_all_filtered(event, |audience| {
+                    filter(rules.as_ref(), battle, event, audience)
+                });
+            }
+            None => self.client_sinks.send_all(event),
+        }
     }
 
     /// Applies an event. The event must be valid.
@@ -62,23 +793,93 @@ impl<R: BattleRules + 'static> Server<R> {
         let mut event_queue = Some(EventQueue::<R>::new());
         // Apply the event on the battle.
         self.battle.apply(&event, &mut event_queue);
+        self.interceptors.after_application(&self.battle, &event);
+        if event.kind() == EventKind::StartTurn {
+            self.turn_started = Instant::now();
+        }
         // Send the event to all client sinks.
-        self.client_sinks
-            .send_all(&event.clone().version(self.battle.rules().version().clone()));
-        // Recursively process derived events.
-        let mut errors = Vec::new();
+        let versioned = event.clone().version(self.battle.rules().version().clone());
+        if let Some(versioned) = pre_broadcast(self.pre_broadcast.as_ref(), &self.battle, versioned)
+        {
+            self.broadcast(&versioned);
+        }
+        for change in self.battle.metrics.take_changes(event.id()) {
+            self.client_sinks.send_metric_all(&change);
+        }
         if let Some(event_queue) = event_queue {
-            for mut prototype in event_queue {
-                // Set origin id in derived event, only if it wasn't set explicitly.
-                if prototype.origin().is_none() {
-                    prototype.set_origin(Some(event.id()));
-                }
-                let result = self.process(prototype);
-                if let Err(error) = result {
-                    errors.push(error);
+            // Hold the derived events if this event opens a response window.
+            let opens_window = match (&self.held_resolution, &self.response_window) {
+                (None, Some(window)) => window.triggers.contains(&event.kind()),
+                _ => false,
+            };
+            if opens_window {
+                return self.hold_derived(event_queue, event.id());
+            }
+            // Recursively process derived events.
+            self.process_derived(event_queue, event.id())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Opens a response window, holding all events derived from the event with id `origin`.
+    fn hold_derived(
+        &mut self,
+        mut event_queue: EventQueue<R>,
+        origin: EventId,
+    ) -> WeaselResult<(), R> {
+        sort_by_priority(&mut event_queue, self.battle.rules().fight_rules());
+        let mut errors = Vec::new();
+        let mut events = Vec::new();
+        for prototype in event_queue {
+            // Conditions can't be held, thus they are evaluated when the window opens.
+            if let Some(condition) = prototype.condition() {
+                if !condition(&self.battle.state) {
+                    errors.push(WeaselError::InvalidEvent(
+                        prototype.event().clone(),
+                        Box::new(WeaselError::ConditionUnsatisfied),
+                    ));
+                    continue;
                 }
             }
+            events.push(HeldEvent {
+                origin: prototype.origin(),
+                secondary_origins: prototype.secondary_origins().to_vec(),
+                event: prototype.event().clone(),
+            });
+        }
+        self.held_resolution = Some(HeldResolution {
+            origin,
+            events,
+            opened: Instant::now(),
+            reactions: 0,
+        });
+        Server::fold_errors(errors)
+    }
+
+    /// Processes all events derived from the event with id `origin`.
+    fn process_derived(
+        &mut self,
+        mut event_queue: EventQueue<R>,
+        origin: EventId,
+    ) -> WeaselResult<(), R> {
+        sort_by_priority(&mut event_queue, self.battle.rules().fight_rules());
+        let mut errors = Vec::new();
+        for mut prototype in event_queue {
+            // Set origin id in derived event, only if it wasn't set explicitly.
+            if prototype.origin().is_none() {
+                prototype.set_origin(Some(origin));
+            }
+            let result = self.process(prototype);
+            if let Err(error) = result {
+                errors.push(error);
+            }
         }
+        Server::fold_errors(errors)
+    }
+
+    /// Turns a list of errors into a result.
+    fn fold_errors(mut errors: Vec<WeaselErrorType<R>>) -> WeaselResult<(), R> {
         // If there is an error, return it.
         // In the case of multiple errors, wrap them into a multi error.
         match errors.len() {
@@ -99,6 +900,99 @@ impl<R: BattleRules + 'static> Server<R> {
             Ok(())
         }
     }
+
+    /// Checks if the given player controls the given entity.
+    /// Owned creatures can be controlled only by their owner, while all other entities
+    /// require rights to their team.\
+    /// Players granted rights to the single entity can always control it.
+    fn check_entity_rights(
+        &self,
+        player: PlayerId,
+        entity_id: &EntityId<R>,
+    ) -> WeaselResult<(), R> {
+        if self.rights().check_entity(player, entity_id) {
+            return Ok(());
+        }
+        if let EntityId::Object(object_id) = entity_id {
+            // Objects are controlled only through their owner.
+            let owner = self
+                .battle
+                .entities()
+                .object(object_id)
+                .and_then(|object| object.owner());
+            return match owner {
+                Some(ObjectOwner::Player(owner)) if *owner == player => Ok(()),
+                Some(ObjectOwner::Team(team_id)) => self.check_rights(player, team_id),
+                _ => Err(WeaselError::OwnershipError(Some(player), entity_id.clone())),
+            };
+        }
+        if let EntityId::Creature(creature_id) = entity_id {
+            let owner = self
+                .battle
+                .entities()
+                .creature(creature_id)
+                .and_then(|creature| creature.owner());
+            if let Some(owner) = owner {
+                if owner == player {
+                    return Ok(());
+                } else {
+                    return Err(WeaselError::OwnershipError(Some(player), entity_id.clone()));
+                }
+            }
+        }
+        let actor = self
+            .battle
+            .entities()
+            .actor(entity_id)
+            .unwrap_or_else(|| panic!("constraint violated: actor {:?} not found", entity_id));
+        self.check_rights(player, actor.team_id())
+    }
+
+    /// Checks if the given player satisfies the rights required by an event.
+    fn check_event_rights(
+        &self,
+        player: Option<PlayerId>,
+        rights: &EventRights<R>,
+    ) -> WeaselResult<(), R> {
+        let authenticated = || player.ok_or(WeaselError::MissingAuthentication);
+        match rights {
+            EventRights::Server => Err(WeaselError::ServerOnlyEvent),
+            EventRights::None => Ok(()),
+            EventRights::All(all) => all
+                .iter()
+                .try_for_each(|rights| self.check_event_rights(player, rights)),
+            EventRights::Any(any) => {
+                let mut errors = Vec::new();
+                for rights in any {
+                    match self.check_event_rights(player, rights) {
+                        Ok(()) => return Ok(()),
+                        Err(error) => errors.push(error),
+                    }
+                }
+                // In the case of multiple errors, wrap them into a multi error.
+                match errors.len() {
+                    0 => Err(WeaselError::ServerOnlyEvent),
+                    1 => Err(errors.swap_remove(0)),
+                    _ => Err(WeaselError::MultiError(errors)),
+                }
+            }
+            _ if !self.authentication => Ok(()),
+            EventRights::Team(team_id) => self.check_rights(authenticated()?, team_id),
+            EventRights::Teams(teams_ids) => {
+                let player = authenticated()?;
+                teams_ids
+                    .iter()
+                    .try_for_each(|team_id| self.check_rights(player, team_id))
+            }
+            EventRights::Entity(entity_id) => self.check_entity_rights(authenticated()?, entity_id),
+            EventRights::Entities(entities_ids) => {
+                let player = authenticated()?;
+                entities_ids
+                    .iter()
+                    .try_for_each(|entity_id| self.check_entity_rights(player, entity_id))
+            }
+        }
+    }
 }
 
 impl<R: BattleRules> BattleController<R> for Server<R> {
@@ -118,68 +1012,107 @@ impl<R: BattleRules> BattleController<R> for Server<R> {
 impl<R: BattleRules + 'static> EventProcessor<R> for Server<R> {
     type ProcessOutput = WeaselResult<(), R>;
 
-    fn process(&mut self, event: EventPrototype<R>) -> Self::ProcessOutput {
+    fn process(&mut self, mut event: EventPrototype<R>) -> Self::ProcessOutput {
+        self.interceptors
+            .before_verification(&self.battle, &mut event, None)?;
         // Verify this event.
-        self.battle
-            .verify_prototype(&event)
-            .map_err(|e| WeaselError::InvalidEvent(event.event().clone(), e.into()))?;
+        let unverified = match self.battle.verify_prototype(&event) {
+            Ok(()) => false,
+            Err(error) if self.sandbox && SandboxWarning::<R>::can_downgrade(&error) => {
+                // Downgrade the failure to a warning and force the event.
+                let warning = SandboxWarning {
+                    event_id: self.battle.history().next_id(),
+                    error,
+                };
+                warn!(
+                    "sandbox: forcing event {:?} (id: {}) that failed verification: {}",
+                    event.kind(),
+                    warning.event_id,
+                    warning.error
+                );
+                self.sandbox_report.push(warning);
+                true
+            }
+            Err(error) => {
+                return Err(WeaselError::InvalidEvent(
+                    event.event().clone(),
+                    error.into(),
+                ))
+            }
+        };
         // Promote verified event.
-        let event = self.battle.promote(event);
+        let event = self.battle.promote(event).forced(unverified);
         // Apply it.
-        self.apply_event(event)
+        self.apply_event(event)?;
+        self.verify_state_if_due()
     }
 }
 
 impl<R: BattleRules + 'static> EventServer<R> for Server<R> {
     fn process_client(&mut self, event: ClientEventPrototype<R>) -> WeaselResult<(), R> {
+        if self.shut_down {
+            return Err(WeaselError::ServerShutDown);
+        }
+        // Reject oversized events.
+        #[cfg(feature = "serialization")]
+        self.check_event_size(&event)?;
+        // Resume the resolution of held events if the response window expired.
+        self.update_response_window()?;
+        // Only reactions are accepted while a response window is open.
+        if let (Some(_), Some(window)) = (&self.held_resolution, &self.response_window) {
+            if !window.reactions.is_empty() && !window.reactions.contains(&event.kind()) {
+                return Err(WeaselError::ResponseWindowOpen);
+            }
+        }
+        // Let the interceptors inspect the event.
+        let (version, player) = (event.version().clone(), event.player());
+        let mut prototype = event.prototype();
+        self.interceptors
+            .before_verification(&self.battle, &mut prototype, player)?;
+        let event = prototype.client_prototype(version, player);
         // Verify this event.
         self.battle.verify_client(&event)?;
         // Verify event's rights.
-        match event.rights(&self.battle) {
-            EventRights::Server => {
-                return Err(WeaselError::ServerOnlyEvent);
-            }
-            EventRights::Team(team_id) => {
-                if self.authentication {
-                    if let Some(player) = event.player() {
-                        // Player id is present. Check if it matches the event's rights.
-                        self.check_rights(player, team_id)?;
-                    } else {
-                        // No player id present.
-                        return Err(WeaselError::MissingAuthentication);
-                    }
-                }
-            }
-            EventRights::Teams(teams_ids) => {
-                if self.authentication {
-                    if let Some(player) = event.player() {
-                        // Player id is present. Check if it matches the event's rights.
-                        for team_id in teams_ids {
-                            self.check_rights(player, team_id)?;
-                        }
-                    } else {
-                        // No player id present.
-                        return Err(WeaselError::MissingAuthentication);
-                    }
-                }
+        self.check_event_rights(event.player(), &event.rights(&self.battle))?;
+        // Verify that the player can fire this kind of event.
+        if let (true, Some(player)) = (self.authentication, event.player()) {
+            if !self.rights().check_kind(player, event.kind()) {
+                return Err(WeaselError::EventKindNotAllowed(player, event.kind()));
             }
-            EventRights::None => {}
         }
         // Promote verified event.
         let event = self.battle.promote(event.prototype());
+        // Check if this event is a reaction inside a response window.
+        let reaction = self.held_resolution.is_some();
         // Apply it.
-        self.apply_event(event)
+        let result = self.apply_event(event);
+        // Close the response window once the maximum number of reactions is reached.
+        if reaction {
+            if let (Some(held), Some(window)) = (&mut self.held_resolution, &self.response_window) {
+                held.reactions += 1;
+                if let Some(max_reactions) = window.max_reactions {
+                    if held.reactions >= max_reactions {
+                        self.close_response_window()?;
+                    }
+                }
+            }
+        }
+        result
     }
 }
 
 impl<R: BattleRules + 'static> EventReceiver<R> for Server<R> {
     fn receive(&mut self, event: VersionedEventWrapper<R>) -> WeaselResult<(), R> {
         // Verify the event.
-        self.battle.verify_wrapper(&event)?;
+        self.battle.verify_wrapper(&event, self.sandbox)?;
         // Apply the event on the battle.
         self.battle.apply(&event.wrapper(), &mut None);
+        self.interceptors
+            .after_application(&self.battle, event.wrapper());
         // Send the event to all client sinks.
-        self.client_sinks.send_all(&event);
+        if let Some(event) = pre_broadcast(self.pre_broadcast.as_ref(), &self.battle, event) {
+            self.broadcast(&event);
+        }
         Ok(())
     }
 }
@@ -188,6 +1121,17 @@ impl<R: BattleRules + 'static> EventReceiver<R> for Server<R> {
 pub struct ServerBuilder<R: BattleRules> {
     battle: Battle<R>,
     authentication: bool,
+    response_window: Option<ResponseWindow>,
+    bootstrap_callback: Option<BootstrapCallback<R>>,
+    pre_broadcast: Option<PreBroadcastCallback<R>>,
+    visibility_rules: Option<Box<dyn VisibilityRules<R> + Send>>,
+    sandbox: bool,
+    tick_interval: Option<Duration>,
+    turn_timeout: Option<Duration>,
+    verification_interval: Option<EventId>,
+    #[cfg(feature = "serialization")]
+    max_event_size: Option<usize>,
+    forward_metrics: bool,
 }
 
 impl<R: BattleRules> ServerBuilder<R> {
@@ -198,12 +1142,974 @@ impl<R: BattleRules> ServerBuilder<R> {
         self
     }
 
+    /// Opens a response window after each event of the kinds designated in `response_window`.
+    pub fn response_window(mut self, response_window: ResponseWindow) -> Self {
+        self.response_window = Some(response_window);
+        self
+    }
+
+    /// Sets a callback to append events to those synthesized by
+    /// `Server::bootstrap_events_for_new_client`.
+    pub fn bootstrap_callback(mut self, callback: BootstrapCallback<R>) -> Self {
+        self.bootstrap_callback = Some(callback);
+        self
+    }
+
+    /// Sets a callback invoked before forwarding a verified event to the client sinks.
+    ///
+    /// The callback decides whether the event is forwarded as it is, replaced by another
+    /// event or withheld. In all cases the original event is applied to the server's battle
+    /// and recorded in its history.\
+    /// The callback is invoked also when the history is shared with a sink,
+    /// for instance through `MultiClientSinkHandleMut::add_sink_from`.
+    ///
+    /// Use it in games with hidden information, to prevent some authoritative events
+    /// from ever leaving the server.
+    ///
+    /// # Examples
+    /// ```
+    /// use weasel::event::DummyEvent;
+    /// use weasel::server::Broadcast;
+    /// use weasel::{
+    ///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateTeam,
+    ///     EventKind, EventTrigger, Server,
+    /// };
+    ///
+    /// battle_rules! {}
+    ///
+    /// let battle = Battle::builder(CustomRules::new()).build();
+    /// let mut server = Server::builder(battle)
+    ///     .pre_broadcast(Box::new(|event, _| {
+    ///         if event.kind() == EventKind::CreateTeam {
+    ///             // Keep the clients' timeline consistent by sending a placeholder.
+    ///             Broadcast::Replace(DummyEvent::trigger(&mut ()).event())
+    ///         } else {
+    ///             Broadcast::Forward
+    ///         }
+    ///     }))
+    ///     .build();
+    ///
+    /// CreateTeam::trigger(&mut server, 1).fire().unwrap();
+    /// assert_eq!(server.battle().entities().teams().count(), 1);
+    /// ```
+    pub fn pre_broadcast(mut self, callback: PreBroadcastCallback<R>) -> Self {
+        self.pre_broadcast = Some(callback);
+        self
+    }
+
+    /// Sets the rules used to filter the events sent to players and spectators.
+    ///
+    /// Visibility rules apply to the sinks added through
+    /// `MultiClientSinkHandleMut::add_player_sink`, to the sessions opened on behalf of
+    /// a player and to the sinks of spectators with `RedactionPolicy::Redacted`.
+    pub fn visibility_rules(mut self, rules: Box<dyn VisibilityRules<R> + Send>) -> Self {
+        self.visibility_rules = Some(rules);
+        self
+    }
+
+    /// Puts the server in sandbox mode, meant for level editors and debugging tools.
+    ///
+    /// In sandbox mode, events processed by the server itself that fail verification are
+    /// applied anyway. Each failure is collected as a warning in the server's sandbox report
+    /// and the event is marked as `unverified` in the history.\
+    /// Unsatisfied conditions and events fired after the end of the battle are still rejected,
+    /// as well as all events sent by clients.\
+    /// Only servers in sandbox mode and clients built with `ClientBuilder::sandbox`
+    /// accept the `unverified` events they receive without verifying them.
+    ///
+    /// Forcing an event that breaks the battle's invariants, for instance by referring
+    /// to an entity that doesn't exist, might still cause a panic.
+    ///
+    /// # Examples
+    /// ```
+    /// use weasel::{
+    ///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateCreature,
+    ///     CreateTeam, EventTrigger, Server,
+    /// };
+    ///
+    /// battle_rules! {}
+    ///
+    /// let battle = Battle::builder(CustomRules::new()).build();
+    /// let mut server = Server::builder(battle).sandbox().build();
+    ///
+    /// CreateTeam::trigger(&mut server, 1).fire().unwrap();
+    /// // Creating a team with a duplicated id is normally rejected.
+    /// assert!(CreateTeam::trigger(&mut server, 1).fire().is_ok());
+    /// assert_eq!(server.sandbox_report().len(), 1);
+    /// assert!(server.battle().history().events()[1].unverified());
+    /// ```
+    pub fn sandbox(mut self) -> Self {
+        self.sandbox = true;
+        self
+    }
+
+    /// Emits a `Tick` event every `interval`, each time `Server::update_ticks` is invoked.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is zero.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use weasel::{battle_rules, rules::empty::*, Battle, BattleController, BattleRules, Server};
+    ///
+    /// battle_rules! {}
+    ///
+    /// let battle = Battle::builder(CustomRules::new()).build();
+    /// let mut server = Server::builder(battle)
+    ///     .tick_interval(Duration::from_millis(100))
+    ///     .build();
+    ///
+    /// // Ticks are emitted only once their interval has elapsed.
+    /// assert_eq!(server.update_ticks().unwrap(), 0);
+    /// server.fire_tick().unwrap();
+    /// assert_eq!(server.battle().rounds().ticks(), 1);
+    /// ```
+    pub fn tick_interval(mut self, interval: Duration) -> Self {
+        assert!(interval > Duration::from_secs(0), "tick interval is zero");
+        self.tick_interval = Some(interval);
+        self
+    }
+
+    /// Gives actors at most `timeout` to complete their turn. Turns lasting longer are ended
+    /// with a `TurnTimedOut` event, each time `Server::check_timeouts` is invoked.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `timeout` is zero.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use weasel::{battle_rules, rules::empty::*, Battle, BattleRules, Server};
+    ///
+    /// battle_rules! {}
+    ///
+    /// let battle = Battle::builder(CustomRules::new()).build();
+    /// let mut server = Server::builder(battle)
+    ///     .turn_timeout(Duration::from_secs(30))
+    ///     .build();
+    ///
+    /// // No turn is in progress, thus nothing can time out.
+    /// assert!(!server.check_timeouts().unwrap());
+    /// ```
+    pub fn turn_timeout(mut self, timeout: Duration) -> Self {
+        assert!(timeout > Duration::from_secs(0), "turn timeout is zero");
+        self.turn_timeout = Some(timeout);
+        self
+    }
+
+    /// Emits a `VerifyState` event each time `interval` events have been processed,
+    /// so that clients can detect early whether their battle diverged from the server's one.
+    ///
+    /// # Panics
+    /// Panics if `interval` is zero.
+    ///
+    /// # Examples
+    /// ```
+    /// use weasel::event::DummyEvent;
+    /// use weasel::{
+    ///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, EventKind,
+    ///     EventTrigger, Server,
+    /// };
+    ///
+    /// battle_rules! {}
+    ///
+    /// let battle = Battle::builder(CustomRules::new()).build();
+    /// let mut server = Server::builder(battle).state_verification(2).build();
+    ///
+    /// DummyEvent::trigger(&mut server).fire().unwrap();
+    /// DummyEvent::trigger(&mut server).fire().unwrap();
+    /// let events = server.battle().history().events();
+    /// assert_eq!(events.len(), 3);
+    /// assert_eq!(events[2].kind(), EventKind::VerifyState);
+    /// ```
+    pub fn state_verification(mut self, interval: EventId) -> Self {
+        assert!(interval > 0, "state verification interval is zero");
+        self.verification_interval = Some(interval);
+        self
+    }
+
+    /// Rejects client events whose serialized size is bigger than `bytes`.
+    ///
+    /// The size is measured with [serialized_size](../serde/fn.serialized_size.html), before
+    /// any other verification. Rejected events are counted in the system metric
+    /// `OVERSIZED_EVENTS_REJECTED`.\
+    /// Use this option to protect servers from clients sending huge payloads,
+    /// for instance inside user events.
+    #[cfg(feature = "serialization")]
+    pub fn max_event_size(mut self, bytes: usize) -> Self {
+        self.max_event_size = Some(bytes);
+        self
+    }
+
+    /// Forwards each modification of user metrics to all client sinks, as a `MetricChanged`
+    /// notification sent through `ClientSink::send_metric`.
+    ///
+    /// Notifications are sent right after the event whose application caused them.
+    /// Use this option to display live counters on clients, without polling the server.\
+    /// Histograms are not forwarded.
+    ///
+    /// # Examples
+    /// ```
+    /// use weasel::event::{ClientSink, DummyEvent, EventSink, EventSinkId};
+    /// use weasel::{
+    ///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, EventTrigger,
+    ///     MetricChanged, Server, VersionedEventWrapper, WeaselResult,
+    /// };
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// battle_rules! {}
+    ///
+    /// struct Sink(Arc<Mutex<Vec<MetricChanged<CustomRules>>>>);
+    ///
+    /// impl EventSink for Sink {
+    ///     fn id(&self) -> EventSinkId {
+    ///         1
+    ///     }
+    /// }
+    ///
+    /// impl ClientSink<CustomRules> for Sink {
+    ///     fn send(&mut self, _: &VersionedEventWrapper<CustomRules>) -> WeaselResult<(), CustomRules> {
+    ///         Ok(())
+    ///     }
+    ///
+    ///     fn send_metric(&mut self, change: &MetricChanged<CustomRules>) -> WeaselResult<(), CustomRules> {
+    ///         self.0.lock().unwrap().push(change.clone());
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let battle = Battle::builder(CustomRules::new()).build();
+    /// let mut server = Server::builder(battle).forward_metrics().build();
+    /// let changes = Arc::new(Mutex::new(Vec::new()));
+    /// server.client_sinks_mut().add_sink(Box::new(Sink(changes.clone()))).unwrap();
+    ///
+    /// // Metrics are only modified by the rules, while applying events.
+    /// DummyEvent::trigger(&mut server).fire().unwrap();
+    /// assert!(changes.lock().unwrap().is_empty());
+    /// ```
+    pub fn forward_metrics(mut self) -> Self {
+        self.forward_metrics = true;
+        self
+    }
+
     /// Creates a new server.
-    pub fn build(self) -> Server<R> {
+    pub fn build(mut self) -> Server<R> {
+        self.battle.metrics.track_changes(self.forward_metrics);
         Server {
             battle: self.battle,
             client_sinks: MultiClientSink::new(),
             authentication: self.authentication,
+            response_window: self.response_window,
+            held_resolution: None,
+            bootstrap_callback: self.bootstrap_callback,
+            pre_broadcast: self.pre_broadcast,
+            visibility_rules: self.visibility_rules,
+            sandbox: self.sandbox,
+            sandbox_report: Vec::new(),
+            tick_interval: self.tick_interval,
+            last_tick: Instant::now(),
+            turn_timeout: self.turn_timeout,
+            turn_started: Instant::now(),
+            verification_interval: self.verification_interval,
+            next_verification: self.verification_interval.unwrap_or_default(),
+            #[cfg(feature = "serialization")]
+            max_event_size: self.max_event_size,
+            shut_down: false,
+            pending: IndexMap::new(),
+            schedule_cursor: 0,
+            sessions: HashMap::new(),
+            token_state: RandomState::new(),
+            interceptors: Interceptors::new(),
+            issued_tokens: 0,
+        }
+    }
+}
+
+/// A verification failure that was downgraded to a warning by a server in sandbox mode.
+pub struct SandboxWarning<R: BattleRules> {
+    event_id: EventId,
+    error: WeaselErrorType<R>,
+}
+
+impl<R: BattleRules> SandboxWarning<R> {
+    /// Returns the id of the event that was applied despite failing verification.
+    pub fn event_id(&self) -> EventId {
+        self.event_id
+    }
+
+    /// Returns the verification error.
+    pub fn error(&self) -> &WeaselErrorType<R> {
+        &self.error
+    }
+
+    /// Returns true if a verification failure can be downgraded to a warning.
+    fn can_downgrade(error: &WeaselErrorType<R>) -> bool {
+        !matches!(
+            error,
+            WeaselError::ConditionUnsatisfied | WeaselError::BattleEnded(_)
+        )
+    }
+}
+
+impl<R: BattleRules> std::fmt::Debug for SandboxWarning<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "SandboxWarning {{ event_id: {:?}, error: {:?} }}",
+            self.event_id, self.error
+        )
+    }
+}
+
+impl<R: BattleRules> Clone for SandboxWarning<R> {
+    fn clone(&self) -> Self {
+        Self {
+            event_id: self.event_id,
+            error: self.error.clone(),
+        }
+    }
+}
+
+/// Type of the callback used to restore the parts of the battle state that can't be
+/// reproduced by the events synthesized in `Server::bootstrap_events_for_new_client`.
+///
+/// The callback receives the server's battle and must push in the event queue the events
+/// (for instance, alterations of statistics or inflicted statuses) that restore such state.
+/// These events are applied after the creation of all teams and entities
+/// and before the current turn is started.
+pub type BootstrapCallback<R> = Box<dyn Fn(&Battle<R>, &mut EventQueue<R>) + Send>;
+
+/// Type of the callback used to annotate or veto the forwarding of verified events
+/// to client sinks.
+///
+/// The callback receives the event about to be sent and the server's battle.
+/// See [pre_broadcast](struct.ServerBuilder.html#method.pre_broadcast).
+pub type PreBroadcastCallback<R> =
+    Box<dyn Fn(&VersionedEventWrapper<R>, &Battle<R>) -> Broadcast<R> + Send>;
+
+/// Tells how a verified event must be forwarded to client sinks.
+pub enum Broadcast<R: BattleRules> {
+    /// Forward the event as it is.
+    Forward,
+    /// Forward the given event in place of the original one.
+    /// The replacement keeps the id, the origin and the version of the original event.
+    ///
+    /// Clients apply the replacement instead of the original event, thus it must be valid
+    /// in their own battle.
+    Replace(Box<dyn Event<R> + Send>),
+    /// Don't forward the event.
+    ///
+    /// Clients will miss an event id in their timeline and reject the events after it.
+    /// Prefer `Replace` unless the client sinks are not meant to be replayed.
+    Withhold,
+}
+
+impl<R: BattleRules> std::fmt::Debug for Broadcast<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Broadcast::Forward => write!(f, "Broadcast::Forward"),
+            Broadcast::Replace(event) => write!(f, "Broadcast::Replace {{ {:?} }}", event),
+            Broadcast::Withhold => write!(f, "Broadcast::Withhold"),
+        }
+    }
+}
+
+/// Applies the pre broadcast `callback` to `event`.
+/// Returns the event to be sent to client sinks, if any.
+pub(crate) fn pre_broadcast<R: BattleRules>(
+    callback: Option<&PreBroadcastCallback<R>>,
+    battle: &Battle<R>,
+    event: VersionedEventWrapper<R>,
+) -> Option<VersionedEventWrapper<R>> {
+    match callback {
+        Some(callback) => apply_broadcast(callback(&event, battle), event),
+        None => Some(event),
+    }
+}
+
+/// Applies `broadcast` to `event`.
+/// Returns the event to be sent to client sinks, if any.
+pub(crate) fn apply_broadcast<R: BattleRules>(
+    broadcast: Broadcast<R>,
+    event: VersionedEventWrapper<R>,
+) -> Option<VersionedEventWrapper<R>> {
+    match broadcast {
+        Broadcast::Forward => Some(event),
+        Broadcast::Replace(replacement) => Some(
+            EventWrapper::new(event.id(), event.origin(), replacement)
+                .with_secondary_origins(event.secondary_origins().to_vec())
+                .synthesized(event.synthetic())
+                .forced(event.unverified())
+                .with_metadata(event.metadata().map(<[u8]>::to_vec))
+                .version(event.version().clone()),
+        ),
+        Broadcast::Withhold => None,
+    }
+}
+
+/// Seeds used to generate the current powers and objectives of a team.
+type TeamSeeds<R> = (Option<PowersSeed<R>>, Option<ObjectivesSeed<R>>);
+
+/// Seeds used to generate the current statistics and abilities of a creature.
+type CreatureSeeds<R> = (Option<StatisticsSeed<R>>, Option<AbilitiesSeed<R>>);
+
+/// Synthesizes the events reproducing the models, the teams and the entities of a battle.
+fn synthesize_events<R: BattleRules + 'static>(
+    battle: &Battle<R>,
+) -> Vec<Box<dyn Event<R> + Send>> {
+    let mut entropy = None;
+    let mut space = Vec::new();
+    let mut rounds = None;
+    let mut teams: IndexMap<TeamId<R>, TeamSeeds<R>> = IndexMap::new();
+    let mut creatures: IndexMap<CreatureId<R>, CreatureSeeds<R>> = IndexMap::new();
+    let mut objects: IndexMap<ObjectId<R>, Option<StatisticsSeed<R>>> = IndexMap::new();
+    // Find in the history the seeds that generated the current state.
+    for event in battle.history().events() {
+        let event = event.event();
+        let any = event.as_any();
+        if any.is::<ResetEntropy<R>>() {
+            entropy = Some(event.clone());
+        } else if any.is::<ResetSpace<R>>() {
+            space = vec![event.clone()];
+        } else if any.is::<AlterSpace<R>>() {
+            space.push(event.clone());
+        } else if any.is::<ResetRounds<R>>() {
+            rounds = Some(event.clone());
+        } else if let Some(event) = any.downcast_ref::<CreateTeam<R>>() {
+            let seeds = (event.powers_seed().clone(), event.objectives_seed().clone());
+            teams.insert(event.id().clone(), seeds);
+        } else if let Some(event) = any.downcast_ref::<ResetObjectives<R>>() {
+            if let Some(seeds) = teams.get_mut(event.id()) {
+                seeds.1 = event.seed().clone();
+            }
+        } else if let Some(event) = any.downcast_ref::<RegeneratePowers<R>>() {
+            if let Some(seeds) = teams.get_mut(event.id()) {
+                seeds.0 = event.seed().clone();
+            }
+        } else if let Some(event) = any.downcast_ref::<RenameTeam<R>>() {
+            if let Some(seeds) = teams.shift_remove(event.id()) {
+                teams.insert(event.new_id().clone(), seeds);
+            }
+        } else if let Some(event) = any.downcast_ref::<CreateCreature<R>>() {
+            let seeds = (
+                event.statistics_seed().clone(),
+                event.abilities_seed().clone(),
+            );
+            creatures.insert(event.id().clone(), seeds);
+        } else if let Some(event) = any.downcast_ref::<SpawnWave<R>>() {
+            for event in event.creatures() {
+                let seeds = (
+                    event.statistics_seed().clone(),
+                    event.abilities_seed().clone(),
+                );
+                creatures.insert(event.id().clone(), seeds);
+            }
+        } else if let Some(event) = any.downcast_ref::<CreateObject<R>>() {
+            objects.insert(event.id().clone(), event.statistics_seed().clone());
+        } else if let Some(event) = any.downcast_ref::<RegenerateStatistics<R>>() {
+            match event.id() {
+                EntityId::Creature(id) => {
+                    if let Some(seeds) = creatures.get_mut(id) {
+                        seeds.0 = event.seed().clone();
+                    }
+                }
+                EntityId::Object(id) => {
+                    if let Some(seed) = objects.get_mut(id) {
+                        *seed = event.seed().clone();
+                    }
+                }
+            }
+        } else if let Some(event) = any.downcast_ref::<RegenerateAbilities<R>>() {
+            if let EntityId::Creature(id) = event.id() {
+                if let Some(seeds) = creatures.get_mut(id) {
+                    seeds.1 = event.seed().clone();
+                }
+            }
+        } else if let Some(event) = any.downcast_ref::<ReidentifyCreature<R>>() {
+            if let Some(seeds) = creatures.shift_remove(event.id()) {
+                creatures.insert(event.new_id().clone(), seeds);
+            }
+        } else if let Some(event) = any.downcast_ref::<TransformCreature<R>>() {
+            if let Some(seeds) = creatures.get_mut(event.id()) {
+                *seeds = (
+                    event.statistics_seed().clone(),
+                    event.abilities_seed().clone(),
+                );
+            }
+        }
+    }
+    let mut events: Vec<Box<dyn Event<R> + Send>> = Vec::new();
+    let processor: &mut () = &mut ();
+    // Recreate the models.
+    events.extend(entropy);
+    events.extend(space);
+    events.extend(rounds);
+    // Start the battle before any event restricted to the started phase.
+    if battle.setup_phase && battle.phase() != BattlePhase::Setup {
+        events.push(StartBattle::trigger(&mut ()).event());
+    }
+    let ticks = battle.rounds().ticks();
+    if ticks > 0 {
+        events.push(Tick::trigger(processor, ticks).event());
+    }
+    // Register the players.
+    for player in battle.players().players() {
+        events.push(
+            RegisterPlayer::trigger(&mut (), player.id(), player.name().to_string())
+                .rating(player.rating().clone())
+                .data(player.data().clone())
+                .event(),
+        );
+    }
+    // Recreate teams and their relations.
+    let entities = battle.entities();
+    for team in entities.teams() {
+        let mut trigger = CreateTeam::trigger(processor, team.id().clone());
+        if let Some((powers_seed, objectives_seed)) = teams.get(team.id()) {
+            trigger.powers_seed = powers_seed.clone();
+            trigger.objectives_seed = objectives_seed.clone();
+        }
+        let resources: Vec<_> = team.resources().collect();
+        if !resources.is_empty() {
+            trigger.resources = Some(resources);
+        }
+        events.push(trigger.event());
+    }
+    let mut relations = Vec::new();
+    for (i, first) in entities.teams().enumerate() {
+        for second in entities.teams().skip(i + 1) {
+            if let Some(relation) = entities.relation(first.id(), second.id()) {
+                relations.push((first.id().clone(), second.id().clone(), relation));
+            }
         }
     }
+    if !relations.is_empty() {
+        events.push(SetRelations::trigger(&mut (), &relations).event());
+    }
+    // Recreate creatures and objects.
+    for creature in entities.creatures() {
+        let mut trigger = CreateCreature::trigger(
+            processor,
+            creature.id().clone(),
+            creature.team_id().clone(),
+            creature.position().clone(),
+        );
+        if let Some((statistics_seed, abilities_seed)) = creatures.get(creature.id()) {
+            trigger.statistics_seed = statistics_seed.clone();
+            trigger.abilities_seed = abilities_seed.clone();
+        }
+        trigger.summoner = entities.summoner(creature.entity_id()).cloned();
+        events.push(trigger.event());
+        if let Some(owner) = creature.owner() {
+            events.push(
+                TransferOwnership::trigger(&mut (), creature.id().clone())
+                    .owner(owner)
+                    .event(),
+            );
+        }
+    }
+    for object in entities.objects() {
+        let mut trigger =
+            CreateObject::trigger(processor, object.id().clone(), object.position().clone());
+        if let Some(statistics_seed) = objects.get(object.id()) {
+            trigger.statistics_seed = statistics_seed.clone();
+        }
+        events.push(trigger.event());
+        if let Some(owner) = object.owner() {
+            let id = object.id().clone();
+            let event = match owner {
+                ObjectOwner::Team(team_id) => TransferObjectOwnership::trigger(&mut (), id)
+                    .team(team_id.clone())
+                    .event(),
+                ObjectOwner::Player(player_id) => TransferObjectOwnership::trigger(&mut (), id)
+                    .player(*player_id)
+                    .event(),
+            };
+            events.push(event);
+        }
+    }
+    // Restore the entities' time to live.
+    for (entity_id, rounds) in entities.expiring() {
+        events.push(
+            SetTimeToLive::trigger(processor, entity_id.clone())
+                .rounds(rounds)
+                .event(),
+        );
+    }
+    // Restore the entities' tags.
+    for entity in entities.entities() {
+        let entity_id = entity.entity_id();
+        let tags: Vec<_> = entities.tags(entity_id).collect();
+        if !tags.is_empty() {
+            let mut trigger = AlterTags::trigger(processor, entity_id.clone());
+            trigger.added = tags;
+            events.push(trigger.event());
+        }
+    }
+    // Restore the entities revealed to each team.
+    for (team_id, entity_id) in battle.visibility().revealed() {
+        events.push(RevealEntity::trigger(processor, entity_id.clone(), team_id.clone()).event());
+    }
+    // Restore the scheduled events, with their remaining delay.
+    for scheduled in battle.rounds().scheduled_events() {
+        let mut trigger = ScheduleEvent::trigger(processor, scheduled.event().box_clone());
+        trigger.delay = scheduled.delay();
+        events.push(trigger.event());
+    }
+    // Restore the scores and the objectives' outcomes.
+    for team in entities.teams() {
+        if team.score() != 0 {
+            events.push(AddScore::trigger(&mut (), team.id().clone(), team.score()).event());
+        }
+        if let Some(conclusion) = team.conclusion() {
+            events
+                .push(ConcludeObjectives::trigger(&mut (), team.id().clone(), conclusion).event());
+        }
+        // Restore the pending trade proposals.
+        for proposal in team.trade_proposals() {
+            events.push(Box::new(proposal.clone()));
+        }
+    }
+    events
+}
+
+/// Returns the shortest sequence of events reproducing the current state of `battle`,
+/// either synthesized or taken from the history.
+///
+/// See `Server::bootstrap_events_for_new_client`.
+pub(crate) fn compact_history<R: BattleRules + 'static>(
+    battle: &Battle<R>,
+    bootstrap_callback: Option<&BootstrapCallback<R>>,
+) -> Vec<VersionedEventWrapper<R>> {
+    let history = battle.history();
+    let mut events = synthesize_events(battle);
+    if let Some(callback) = bootstrap_callback {
+        let mut event_queue = EventQueue::<R>::new();
+        callback(battle, &mut event_queue);
+        events.extend(
+            event_queue
+                .into_iter()
+                .map(|prototype| prototype.event().clone()),
+        );
+    }
+    events.extend(projected_statuses(battle));
+    append_turn_and_phase(battle, &mut events);
+    let version = battle.rules().version();
+    if history.is_reproducible() && events.len() >= history.len() as usize {
+        return battle
+            .versioned_events(Range {
+                start: history.first_id() as usize,
+                end: history.next_id() as usize,
+            })
+            .collect();
+    }
+    let first_id = history.next_id() - events.len() as EventId;
+    events
+        .into_iter()
+        .enumerate()
+        .map(|(i, event)| {
+            EventWrapper::new(first_id + i as EventId, None, event)
+                .synthesized(true)
+                .version(version.clone())
+        })
+        .collect()
+}
+
+/// Sorts the events in a queue by priority, keeping the insertion order of events
+/// with the same priority.
+///
+/// `InflictStatus` events are then reordered among themselves, following
+/// `FightRules::status_order`, without changing the positions they occupy in the queue.
+fn sort_by_priority<R: BattleRules + 'static>(event_queue: &mut EventQueue<R>, rules: &R::FR) {
+    event_queue.sort_by_key(|prototype| prototype.priority());
+    let status_order = |prototype: &EventPrototype<R>| {
+        prototype
+            .event()
+            .as_any()
+            .downcast_ref::<InflictStatus<R>>()
+            .map(|event| (prototype.priority(), rules.status_order(event.status_id())))
+    };
+    let slots: Vec<_> = (0..event_queue.len())
+        .filter(|i| status_order(&event_queue[*i]).is_some())
+        .collect();
+    if slots.len() < 2 {
+        return;
+    }
+    let mut sorted = slots.clone();
+    sorted.sort_by_key(|i| status_order(&event_queue[*i]));
+    let mut prototypes: Vec<_> = event_queue.drain(..).map(Some).collect();
+    let statuses: Vec<_> = sorted.iter().map(|i| prototypes[*i].take()).collect();
+    for (slot, prototype) in slots.into_iter().zip(statuses) {
+        prototypes[slot] = prototype;
+    }
+    event_queue.extend(prototypes.into_iter().flatten());
+}
+
+/// Appends the events reproducing the progress of rounds and turns and the end of a battle.
+///
+/// The current turn is restored directly, so that the rules' callbacks invoked at the start
+/// of a turn are not run again.
+fn append_turn_and_phase<R: BattleRules + 'static>(
+    battle: &Battle<R>,
+    events: &mut Vec<Box<dyn Event<R> + Send>>,
+) {
+    let rounds = battle.rounds();
+    let processor: &mut () = &mut ();
+    let mut trigger = RestoreRounds::trigger(
+        processor,
+        rounds.completed_rounds(),
+        rounds.completed_turns(),
+    );
+    if let TurnState::Started(actors) = rounds.state() {
+        trigger.actors = Some(actors.iter().cloned().collect());
+    }
+    trigger.activations = rounds
+        .activations_iter()
+        .map(|(id, count)| (id.clone(), *count))
+        .collect();
+    for creature in battle.entities().creatures() {
+        for ability in creature.abilities() {
+            let tracked = creature.tracked_ability(ability.id());
+            if let Some(turn) = tracked.and_then(TrackedAbility::last_used_turn) {
+                trigger
+                    .cooldowns
+                    .push((creature.id().clone(), ability.id().clone(), turn));
+            }
+        }
+    }
+    trigger.pending = rounds.pending_activations().cloned().collect();
+    let restored = trigger.actors.is_some()
+        || trigger.rounds > 0
+        || trigger.turns > 0
+        || !trigger.activations.is_empty()
+        || !trigger.cooldowns.is_empty()
+        || !trigger.pending.is_empty();
+    if restored {
+        events.push(trigger.event());
+    }
+    if let Some(reason) = battle.end_reason() {
+        events.push(match battle.outcome() {
+            Some(outcome) => EndBattle::trigger(&mut ())
+                .outcome(outcome.clone())
+                .reason(reason)
+                .event(),
+            None => EndBattle::trigger(&mut ()).reason(reason).event(),
+        });
+    }
+}
+
+/// Configuration of a window in which clients can respond to an event,
+/// before the events derived from it are resolved.
+///
+/// Each time the server applies an event whose kind is among the window's triggers,
+/// the resolution of the events derived from it is put on hold. Meanwhile, clients can fire
+/// reaction events. The held events are processed, in their original order, once the window
+/// is closed. Conditions attached to the held events are evaluated when the window opens.\
+/// A window closes when `Server::close_response_window` is called, when its timeout expires
+/// or when the maximum number of reactions is reached.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use weasel::{
+///     battle_rules, rules::empty::*, server::ResponseWindow, Battle, BattleRules, EventKind,
+///     Server,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let window = ResponseWindow::new(vec![EventKind::ActivateAbility])
+///     .reactions(vec![EventKind::ActivateAbility, EventKind::InterruptAbility])
+///     .timeout(Duration::from_secs(5));
+/// let server = Server::builder(battle).response_window(window).build();
+/// assert_eq!(server.open_response_window(), None);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResponseWindow {
+    triggers: Vec<EventKind>,
+    reactions: Vec<EventKind>,
+    timeout: Option<Duration>,
+    max_reactions: Option<usize>,
+}
+
+impl ResponseWindow {
+    /// Creates a response window opened by the events of the given kinds.
+    pub fn new(triggers: Vec<EventKind>) -> Self {
+        Self {
+            triggers,
+            reactions: Vec::new(),
+            timeout: None,
+            max_reactions: None,
+        }
+    }
+
+    /// Restricts the client events accepted while the window is open to the given kinds.
+    /// If no kind is set, all client events are accepted.
+    pub fn reactions(mut self, reactions: Vec<EventKind>) -> Self {
+        self.reactions = reactions;
+        self
+    }
+
+    /// Sets the maximum duration of the window.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the maximum number of reactions accepted before the window closes.
+    pub fn max_reactions(mut self, max_reactions: usize) -> Self {
+        self.max_reactions = Some(max_reactions);
+        self
+    }
+
+    /// Returns the kinds of events that open the window.
+    pub fn triggers(&self) -> &[EventKind] {
+        &self.triggers
+    }
+}
+
+/// Health information about a server, returned by `Server::status`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct ServerStatus {
+    phase: BattlePhase,
+    events: EventId,
+    sinks: Vec<SinkStatus>,
+    rights: usize,
+    completed_rounds: RoundsCount,
+    completed_turns: TurnsCount,
+    shut_down: bool,
+}
+
+impl ServerStatus {
+    /// Returns the phase of the battle.
+    pub fn phase(&self) -> BattlePhase {
+        self.phase
+    }
+
+    /// Returns the number of events in the battle's history.
+    pub fn events(&self) -> EventId {
+        self.events
+    }
+
+    /// Returns the status of all connected client sinks.
+    pub fn sinks(&self) -> &[SinkStatus] {
+        &self.sinks
+    }
+
+    /// Returns the number of rights granted to players, one for each pair of player and team.
+    pub fn rights(&self) -> usize {
+        self.rights
+    }
+
+    /// Returns the number of completed rounds.
+    pub fn completed_rounds(&self) -> RoundsCount {
+        self.completed_rounds
+    }
+
+    /// Returns the number of completed turns.
+    pub fn completed_turns(&self) -> TurnsCount {
+        self.completed_turns
+    }
+
+    /// Returns true if the server has been shut down.
+    pub fn shut_down(&self) -> bool {
+        self.shut_down
+    }
+}
+
+/// Token identifying a client session, used to resume it after a reconnection.
+pub type SessionToken = u64;
+
+/// A client session opened with `Server::open_session`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Session {
+    sink_id: EventSinkId,
+    player: Option<PlayerId>,
+}
+
+impl Session {
+    /// Returns the id of the sink currently attached to this session.
+    pub fn sink_id(&self) -> EventSinkId {
+        self.sink_id
+    }
+
+    /// Returns the player who opened this session.
+    pub fn player(&self) -> Option<PlayerId> {
+        self.player
+    }
+}
+
+/// Reason given to sinks disconnected by `Server::shutdown`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShutdownReason {
+    /// The game is over.
+    Completed,
+    /// The server is going offline, for instance for maintenance.
+    Terminated,
+    /// The server stopped because of an error.
+    Error,
+    /// An application specific reason code.
+    Custom(u32),
+}
+
+/// Options to shut down a server with `Server::shutdown`.
+///
+/// # Examples
+/// ```
+/// use weasel::server::{ShutdownPolicy, ShutdownReason};
+///
+/// let policy = ShutdownPolicy::new(ShutdownReason::Terminated).end_battle();
+/// assert!(policy.ends_battle());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShutdownPolicy {
+    reason: ShutdownReason,
+    end_battle: bool,
+}
+
+impl ShutdownPolicy {
+    /// Creates a shutdown policy that disconnects sinks for the given reason.
+    pub fn new(reason: ShutdownReason) -> Self {
+        Self {
+            reason,
+            end_battle: false,
+        }
+    }
+
+    /// Fires an `EndBattle` event before disconnecting the sinks,
+    /// unless the battle has already ended.
+    pub fn end_battle(mut self) -> Self {
+        self.end_battle = true;
+        self
+    }
+
+    /// Returns the reason given to the disconnected sinks.
+    pub fn reason(&self) -> ShutdownReason {
+        self.reason
+    }
+
+    /// Returns true if the battle is ended during the shutdown.
+    pub fn ends_battle(&self) -> bool {
+        self.end_battle
+    }
+}
+
+/// Events whose resolution is held until a response window closes.
+struct HeldResolution<R: BattleRules> {
+    origin: EventId,
+    events: Vec<HeldEvent<R>>,
+    opened: Instant,
+    reactions: usize,
+}
+
+/// A derived event waiting for a response window to close.
+struct HeldEvent<R: BattleRules> {
+    origin: Option<EventId>,
+    secondary_origins: Vec<EventId>,
+    event: Box<dyn Event<R> + Send>,
 }
@@ -0,0 +1,284 @@
+//! Event sinks communicating through channels.
+//!
+//! Channel sinks bridge weasel with an existing event loop, such as the one of a game engine.
+//! Events are moved into a channel as they are, without any serialization.
+//!
+//! `ChannelSender` and `ChannelReceiver` are implemented for the channels in `std::sync::mpsc`.
+//! Implement them for the endpoints of any other channel, for instance a crossbeam's one,
+//! to use it with the sinks in this module.
+
+use crate::battle::BattleRules;
+use crate::error::{WeaselError, WeaselErrorType, WeaselResult};
+use crate::event::{
+    ClientEventPrototype, ClientSink, EventId, EventReceiver, EventServer, EventSink, EventSinkId,
+    ServerSink, SinkErrorPolicy, VersionedEventWrapper,
+};
+use std::marker::PhantomData;
+use std::sync::mpsc::{Receiver, Sender, SyncSender, TryRecvError};
+
+/// The sending endpoint of a channel.
+///
+/// # Examples
+/// ```
+/// use std::collections::VecDeque;
+/// use std::sync::{Arc, Mutex};
+/// use weasel::sinks::channel::ChannelSender;
+///
+/// struct Queue<T>(Arc<Mutex<VecDeque<T>>>);
+///
+/// impl<T> ChannelSender<T> for Queue<T> {
+///     fn send(&self, value: T) -> Result<(), String> {
+///         self.0.lock().map_err(|err| err.to_string())?.push_back(value);
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait ChannelSender<T> {
+    /// Sends `value` into the channel.
+    fn send(&self, value: T) -> Result<(), String>;
+}
+
+impl<T> ChannelSender<T> for Sender<T> {
+    fn send(&self, value: T) -> Result<(), String> {
+        Sender::send(self, value).map_err(|err| err.to_string())
+    }
+}
+
+impl<T> ChannelSender<T> for SyncSender<T> {
+    fn send(&self, value: T) -> Result<(), String> {
+        SyncSender::send(self, value).map_err(|err| err.to_string())
+    }
+}
+
+/// The receiving endpoint of a channel.
+pub trait ChannelReceiver<T> {
+    /// Takes the next value from the channel, without blocking.
+    ///
+    /// Returns `None` if the channel is currently empty.
+    fn try_recv(&self) -> Result<Option<T>, String>;
+}
+
+impl<T> ChannelReceiver<T> for Receiver<T> {
+    fn try_recv(&self) -> Result<Option<T>, String> {
+        match Receiver::try_recv(self) {
+            Ok(value) => Ok(Some(value)),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(err) => Err(err.to_string()),
+        }
+    }
+}
+
+/// A `ServerSink` sending client event prototypes into a channel.
+///
+/// The server side reads the prototypes with `forward_client_events`.
+pub struct ChannelServerSink<R, S> {
+    id: EventSinkId,
+    sender: S,
+    _phantom: PhantomData<R>,
+}
+
+impl<R: BattleRules, S: ChannelSender<ClientEventPrototype<R>>> ChannelServerSink<R, S> {
+    /// Creates a new sink sending events through `sender`.
+    pub fn new(id: EventSinkId, sender: S) -> Self {
+        Self {
+            id,
+            sender,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the sending endpoint of this sink.
+    pub fn sender(&self) -> &S {
+        &self.sender
+    }
+}
+
+impl<R, S> EventSink for ChannelServerSink<R, S> {
+    fn id(&self) -> EventSinkId {
+        self.id
+    }
+}
+
+impl<R, S> ServerSink<R> for ChannelServerSink<R, S>
+where
+    R: BattleRules + 'static,
+    S: ChannelSender<ClientEventPrototype<R>>,
+{
+    fn send(&mut self, event: &ClientEventPrototype<R>) -> WeaselResult<(), R> {
+        self.sender
+            .send(event.clone())
+            .map_err(WeaselError::EventSinkError)
+    }
+}
+
+/// A `ClientSink` sending verified events into a channel.
+///
+/// The client side reads the events with `forward_events`.
+pub struct ChannelClientSink<R, S> {
+    id: EventSinkId,
+    sender: S,
+    error_policy: SinkErrorPolicy,
+    _phantom: PhantomData<R>,
+}
+
+impl<R: BattleRules, S: ChannelSender<VersionedEventWrapper<R>>> ChannelClientSink<R, S> {
+    /// Creates a new sink sending events through `sender`.
+    pub fn new(id: EventSinkId, sender: S) -> Self {
+        Self {
+            id,
+            sender,
+            error_policy: SinkErrorPolicy::Disconnect,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Sets the policy applied when sending an event fails.
+    pub fn error_policy(mut self, policy: SinkErrorPolicy) -> Self {
+        self.error_policy = policy;
+        self
+    }
+
+    /// Returns the sending endpoint of this sink.
+    pub fn sender(&self) -> &S {
+        &self.sender
+    }
+}
+
+impl<R, S> EventSink for ChannelClientSink<R, S> {
+    fn id(&self) -> EventSinkId {
+        self.id
+    }
+}
+
+impl<R, S> ClientSink<R> for ChannelClientSink<R, S>
+where
+    R: BattleRules + 'static,
+    S: ChannelSender<VersionedEventWrapper<R>>,
+{
+    fn send(&mut self, event: &VersionedEventWrapper<R>) -> WeaselResult<(), R> {
+        self.sender
+            .send(event.clone())
+            .map_err(WeaselError::EventSinkError)
+    }
+
+    fn on_error(&mut self, _: EventId, _: &WeaselErrorType<R>) -> SinkErrorPolicy {
+        self.error_policy
+    }
+}
+
+/// Processes with `server` all client event prototypes currently in `receiver`.
+///
+/// Returns the number of processed events.
+/// Stops at the first event that fails, leaving the following ones in the channel.
+pub fn forward_client_events<R, C, S>(receiver: &C, server: &mut S) -> WeaselResult<usize, R>
+where
+    R: BattleRules,
+    C: ChannelReceiver<ClientEventPrototype<R>>,
+    S: EventServer<R>,
+{
+    let mut count = 0;
+    while let Some(event) = receiver.try_recv().map_err(WeaselError::EventSinkError)? {
+        server.process_client(event)?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Delivers to `client` all verified events currently in `receiver`.
+///
+/// Returns the number of received events.
+/// Stops at the first event that fails, leaving the following ones in the channel.
+pub fn forward_events<R, C, E>(receiver: &C, client: &mut E) -> WeaselResult<usize, R>
+where
+    R: BattleRules,
+    C: ChannelReceiver<VersionedEventWrapper<R>>,
+    E: EventReceiver<R>,
+{
+    let mut count = 0;
+    while let Some(event) = receiver.try_recv().map_err(WeaselError::EventSinkError)? {
+        client.receive(event)?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::battle::{Battle, BattleController};
+    use crate::client::Client;
+    use crate::event::{DummyEvent, EventKind, EventTrigger};
+    use crate::server::Server;
+    use crate::team::CreateTeam;
+    use crate::{battle_rules, rules::empty::*};
+    use std::sync::mpsc::{channel, sync_channel};
+
+    battle_rules! {}
+
+    fn battle() -> Battle<CustomRules> {
+        Battle::builder(CustomRules::new()).build()
+    }
+
+    #[test]
+    fn client_server() {
+        let (server_tx, server_rx) = channel();
+        let (client_tx, client_rx) = channel();
+        let mut server = Server::builder(battle()).build();
+        let mut client =
+            Client::builder(battle(), Box::new(ChannelServerSink::new(0, server_tx))).build();
+        server
+            .client_sinks_mut()
+            .add_sink(Box::new(ChannelClientSink::new(1, client_tx)))
+            .unwrap();
+        // Events fired by the client travel to the server and back.
+        DummyEvent::trigger(&mut client).fire().unwrap();
+        assert_eq!(forward_client_events(&server_rx, &mut server), Ok(1));
+        assert_eq!(forward_client_events(&server_rx, &mut server), Ok(0));
+        CreateTeam::trigger(&mut server, 1).fire().unwrap();
+        assert_eq!(server.battle().history().len(), 2);
+        assert_eq!(forward_events(&client_rx, &mut client), Ok(2));
+        let kinds: Vec<_> = client
+            .battle()
+            .history()
+            .events()
+            .iter()
+            .map(|event| event.kind())
+            .collect();
+        assert_eq!(kinds, vec![EventKind::DummyEvent, EventKind::CreateTeam]);
+    }
+
+    #[test]
+    fn errors() {
+        let mut server = Server::builder(battle()).build();
+        // Events after an invalid one are left in the channel.
+        let (sender, receiver) = sync_channel(3);
+        let mut sink = ChannelServerSink::new(0, sender);
+        let event = DummyEvent::trigger(&mut ())
+            .prototype()
+            .client_prototype(0, None);
+        let invalid_event = CreateTeam::trigger(&mut (), 1)
+            .prototype()
+            .client_prototype(0, None);
+        assert_eq!(ServerSink::send(&mut sink, &event).err(), None);
+        assert_eq!(ServerSink::send(&mut sink, &invalid_event).err(), None);
+        assert_eq!(ServerSink::send(&mut sink, &event).err(), None);
+        assert_eq!(
+            forward_client_events(&receiver, &mut server).err(),
+            Some(WeaselError::ServerOnlyEvent)
+        );
+        assert_eq!(forward_client_events(&receiver, &mut server), Ok(1));
+        assert_eq!(server.battle().history().len(), 2);
+        // Sending fails once the receiver is dropped.
+        drop(receiver);
+        assert!(ServerSink::send(&mut sink, &event).is_err());
+        let (sender, receiver) = channel();
+        let mut sink = ChannelClientSink::new(1, sender).error_policy(SinkErrorPolicy::Retry(1));
+        drop(receiver);
+        let event = server.battle().versioned_events(0..1).next().unwrap();
+        assert!(ClientSink::send(&mut sink, &event).is_err());
+        assert_eq!(
+            sink.on_error(0, &WeaselError::EmptyEventProcessor),
+            SinkErrorPolicy::Retry(1)
+        );
+    }
+}
@@ -0,0 +1,6 @@
+//! Collection of ready to use event sinks.
+
+pub mod channel;
+
+#[cfg(feature = "tcp")]
+pub mod tcp;
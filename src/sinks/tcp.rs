@@ -0,0 +1,730 @@
+//! Event sinks communicating over tcp.
+//!
+//! All data is exchanged as frames. A frame starts with a header made of the payload's length,
+//! as a four bytes big endian integer, and of one byte telling the `FrameKind`.
+//! The payload follows.\
+//! Events and handshakes are encoded with a `Codec`, so that any serde data format can be used.
+//!
+//! A connection works as follows:
+//! 1. The client connects to the server and sends the `Handshake` of its battle.
+//! 2. The server verifies the handshake with `Server::verify_handshake`.
+//!    If successful, it registers a `TcpClientSink` for the connection.
+//! 3. The client sends its events through a `TcpServerSink`, while the server sends
+//!    verified events through the `TcpClientSink`. Both sides read the incoming frames
+//!    with a `TcpReader`.
+//! 4. Either side sends a `FrameKind::Bye` frame before closing the connection.
+
+use crate::battle::{BattleRules, Handshake};
+use crate::error::{WeaselError, WeaselErrorType, WeaselResult};
+use crate::event::{
+    ClientEventPrototype, ClientSink, EventId, EventSink, EventSinkId, ServerSink, SinkErrorPolicy,
+    VersionedEventWrapper,
+};
+use crate::serde::{FlatClientEvent, FlatVersionedEvent};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io::{self, Read, Write};
+use std::marker::PhantomData;
+use std::net::{Shutdown, SocketAddr, TcpStream, ToSocketAddrs};
+
+/// Size in bytes of the header of each frame.
+const HEADER_SIZE: usize = 5;
+
+/// Default maximum size in bytes of a frame's payload.
+pub const DEFAULT_MAX_FRAME_SIZE: usize = 1 << 20;
+
+/// A data format used to encode the payload of frames.
+///
+/// # Examples
+/// ```
+/// use serde::{de::DeserializeOwned, Serialize};
+/// use weasel::sinks::tcp::Codec;
+///
+/// struct JsonCodec;
+///
+/// impl Codec for JsonCodec {
+///     fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, String> {
+///         serde_json::to_vec(value).map_err(|err| err.to_string())
+///     }
+///
+///     fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, String> {
+///         serde_json::from_slice(bytes).map_err(|err| err.to_string())
+///     }
+/// }
+/// ```
+pub trait Codec {
+    /// Encodes `value` into a sequence of bytes.
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, String>;
+
+    /// Decodes a value from a sequence of bytes.
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, String>;
+}
+
+/// Kinds of frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameKind {
+    /// The payload is an encoded `Handshake`.
+    Handshake,
+    /// The payload is an encoded `FlatVersionedEvent` or `FlatClientEvent`.
+    Event,
+    /// The sender is closing the connection. The payload is empty.
+    Bye,
+    /// The payload contains application specific data.
+    Custom,
+}
+
+impl FrameKind {
+    fn to_byte(self) -> u8 {
+        match self {
+            FrameKind::Handshake => 0,
+            FrameKind::Event => 1,
+            FrameKind::Bye => 2,
+            FrameKind::Custom => 3,
+        }
+    }
+
+    fn from_byte(byte: u8) -> io::Result<Self> {
+        match byte {
+            0 => Ok(FrameKind::Handshake),
+            1 => Ok(FrameKind::Event),
+            2 => Ok(FrameKind::Bye),
+            3 => Ok(FrameKind::Custom),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unknown frame kind {}", byte),
+            )),
+        }
+    }
+}
+
+/// A frame received from a tcp stream.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    kind: FrameKind,
+    payload: Vec<u8>,
+}
+
+impl Frame {
+    /// Returns the kind of this frame.
+    pub fn kind(&self) -> FrameKind {
+        self.kind
+    }
+
+    /// Returns the payload of this frame.
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// Consumes this frame, returning its payload.
+    pub fn into_payload(self) -> Vec<u8> {
+        self.payload
+    }
+}
+
+/// Writes a frame of the given `kind` into `writer`.
+pub fn write_frame<W: Write>(writer: &mut W, kind: FrameKind, payload: &[u8]) -> io::Result<()> {
+    if payload.len() > u32::MAX as usize {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "frame payload is too big",
+        ));
+    }
+    let mut buffer = Vec::with_capacity(HEADER_SIZE + payload.len());
+    buffer.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    buffer.push(kind.to_byte());
+    buffer.extend_from_slice(payload);
+    writer.write_all(&buffer)?;
+    writer.flush()
+}
+
+/// Encodes `value` and writes it into `writer` as a frame of the given `kind`.
+fn write_encoded<W, C, T>(writer: &mut W, codec: &C, kind: FrameKind, value: &T) -> io::Result<()>
+where
+    W: Write,
+    C: Codec,
+    T: Serialize,
+{
+    let payload = codec
+        .encode(value)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    write_frame(writer, kind, &payload)
+}
+
+/// Converts an io error into a `WeaselError`.
+fn sink_error<R: BattleRules>(err: io::Error) -> WeaselErrorType<R> {
+    WeaselError::EventSinkError(err.to_string())
+}
+
+/// Reads frames from a tcp stream.
+///
+/// Partially received frames are buffered. Thus, the stream can be non-blocking or have
+/// a read timeout: a `WouldBlock` or `TimedOut` error doesn't corrupt the next reads.
+pub struct TcpReader<C> {
+    stream: TcpStream,
+    codec: C,
+    buffer: Vec<u8>,
+    max_frame_size: usize,
+}
+
+impl<C: Codec> TcpReader<C> {
+    /// Creates a new reader for `stream`, decoding payloads with `codec`.
+    pub fn new(stream: TcpStream, codec: C) -> Self {
+        Self {
+            stream,
+            codec,
+            buffer: Vec::new(),
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+        }
+    }
+
+    /// Sets the maximum size of a frame's payload.
+    /// Bigger frames cause an `InvalidData` error.
+    pub fn max_frame_size(mut self, bytes: usize) -> Self {
+        self.max_frame_size = bytes;
+        self
+    }
+
+    /// Returns the stream used by this reader.
+    pub fn stream(&self) -> &TcpStream {
+        &self.stream
+    }
+
+    /// Reads the next frame.
+    ///
+    /// Returns an `UnexpectedEof` error if the connection was closed.
+    pub fn read_frame(&mut self) -> io::Result<Frame> {
+        let mut chunk = [0; 4096];
+        loop {
+            if let Some(frame) = self.take_frame()? {
+                return Ok(frame);
+            }
+            let size = self.stream.read(&mut chunk)?;
+            if size == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "connection closed",
+                ));
+            }
+            self.buffer.extend_from_slice(&chunk[..size]);
+        }
+    }
+
+    /// Reads the handshake sent by a client at the beginning of the connection.
+    pub fn read_handshake<R: BattleRules>(&mut self) -> WeaselResult<Handshake<R>, R> {
+        let frame = self.read_frame().map_err(sink_error)?;
+        match frame.kind {
+            FrameKind::Handshake => self.decode(frame.payload()),
+            kind => Err(WeaselError::EventSinkError(format!(
+                "expected a handshake, received a frame of kind {:?}",
+                kind
+            ))),
+        }
+    }
+
+    /// Reads the next event sent by a server.
+    ///
+    /// Custom frames are skipped. Returns `None` if the server closed the connection.
+    pub fn read_event<R>(&mut self) -> WeaselResult<Option<VersionedEventWrapper<R>>, R>
+    where
+        R: BattleRules + 'static,
+    {
+        self.read_event_frame()?
+            .map(|frame| self.decode_event(&frame))
+            .transpose()
+    }
+
+    /// Reads the next event sent by a client.
+    ///
+    /// Custom frames are skipped. Returns `None` if the client closed the connection.
+    pub fn read_client_event<R>(&mut self) -> WeaselResult<Option<ClientEventPrototype<R>>, R>
+    where
+        R: BattleRules + 'static,
+    {
+        self.read_event_frame()?
+            .map(|frame| self.decode_client_event(&frame))
+            .transpose()
+    }
+
+    /// Decodes the event sent by a server inside `frame`.
+    pub fn decode_event<R>(&self, frame: &Frame) -> WeaselResult<VersionedEventWrapper<R>, R>
+    where
+        R: BattleRules + 'static,
+    {
+        self.decode::<FlatVersionedEvent<R>, R>(frame.payload())
+            .map(|event| event.into())
+    }
+
+    /// Decodes the event sent by a client inside `frame`.
+    pub fn decode_client_event<R>(&self, frame: &Frame) -> WeaselResult<ClientEventPrototype<R>, R>
+    where
+        R: BattleRules + 'static,
+    {
+        self.decode::<FlatClientEvent<R>, R>(frame.payload())
+            .map(|event| event.into())
+    }
+
+    /// Reads frames until an event frame is found.
+    fn read_event_frame<R: BattleRules>(&mut self) -> WeaselResult<Option<Frame>, R> {
+        loop {
+            let frame = match self.read_frame() {
+                Ok(frame) => frame,
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+                Err(err) => return Err(sink_error(err)),
+            };
+            match frame.kind {
+                FrameKind::Event => return Ok(Some(frame)),
+                FrameKind::Bye => return Ok(None),
+                FrameKind::Custom => {}
+                FrameKind::Handshake => {
+                    return Err(WeaselError::EventSinkError(
+                        "unexpected handshake".to_string(),
+                    ))
+                }
+            }
+        }
+    }
+
+    fn decode<T: DeserializeOwned, R: BattleRules>(&self, bytes: &[u8]) -> WeaselResult<T, R> {
+        self.codec
+            .decode(bytes)
+            .map_err(WeaselError::EventSinkError)
+    }
+
+    /// Removes the first complete frame from the buffer, if there's one.
+    fn take_frame(&mut self) -> io::Result<Option<Frame>> {
+        if self.buffer.len() < HEADER_SIZE {
+            return Ok(None);
+        }
+        let mut length = [0; 4];
+        length.copy_from_slice(&self.buffer[..4]);
+        let length = u32::from_be_bytes(length) as usize;
+        if length > self.max_frame_size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("frame of {} bytes exceeds the maximum size", length),
+            ));
+        }
+        if self.buffer.len() < HEADER_SIZE + length {
+            return Ok(None);
+        }
+        let kind = FrameKind::from_byte(self.buffer[4])?;
+        let payload = self.buffer[HEADER_SIZE..HEADER_SIZE + length].to_vec();
+        self.buffer.drain(..HEADER_SIZE + length);
+        Ok(Some(Frame { kind, payload }))
+    }
+}
+
+/// A `ServerSink` sending events to a remote server over tcp.
+///
+/// A sink created with `connect` remembers the server's address and tries to
+/// reconnect, sending the handshake again, when the connection fails.
+pub struct TcpServerSink<R: BattleRules, C> {
+    id: EventSinkId,
+    stream: Option<TcpStream>,
+    address: Option<(SocketAddr, Handshake<R>)>,
+    codec: C,
+    reconnect_attempts: u32,
+    reconnections: u32,
+}
+
+impl<R: BattleRules, C: Codec> TcpServerSink<R, C> {
+    /// Connects to the server at `address` and sends `handshake`.
+    pub fn connect<A: ToSocketAddrs>(
+        address: A,
+        handshake: Handshake<R>,
+        codec: C,
+    ) -> io::Result<Self> {
+        let mut last_error = None;
+        for address in address.to_socket_addrs()? {
+            match Self::open(&address, &handshake, &codec) {
+                Ok(stream) => {
+                    return Ok(Self {
+                        id: 0,
+                        stream: Some(stream),
+                        address: Some((address, handshake)),
+                        codec,
+                        reconnect_attempts: 0,
+                        reconnections: 0,
+                    })
+                }
+                Err(err) => last_error = Some(err),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "no address to connect to")
+        }))
+    }
+
+    /// Creates a sink over an already established connection.
+    ///
+    /// The handshake must be sent separately. The sink won't try to reconnect.
+    pub fn from_stream(stream: TcpStream, codec: C) -> Self {
+        Self {
+            id: 0,
+            stream: Some(stream),
+            address: None,
+            codec,
+            reconnect_attempts: 0,
+            reconnections: 0,
+        }
+    }
+
+    /// Sets the id of this sink.
+    pub fn id(mut self, id: EventSinkId) -> Self {
+        self.id = id;
+        self
+    }
+
+    /// Sets how many times in a row the sink tries to reconnect before giving up on an event.
+    pub fn reconnect_attempts(mut self, attempts: u32) -> Self {
+        self.reconnect_attempts = attempts;
+        self
+    }
+
+    /// Returns the current connection, if any.
+    ///
+    /// Clone it with `try_clone` to read the frames sent by the server.
+    pub fn stream(&self) -> Option<&TcpStream> {
+        self.stream.as_ref()
+    }
+
+    /// Returns how many times the sink reconnected to the server.
+    ///
+    /// After a reconnection, the stream used to read frames must be replaced as well.
+    pub fn reconnections(&self) -> u32 {
+        self.reconnections
+    }
+
+    fn open(address: &SocketAddr, handshake: &Handshake<R>, codec: &C) -> io::Result<TcpStream> {
+        let mut stream = TcpStream::connect(address)?;
+        write_encoded(&mut stream, codec, FrameKind::Handshake, handshake)?;
+        Ok(stream)
+    }
+
+    fn reconnect(&mut self) -> io::Result<()> {
+        if let Some((address, handshake)) = &self.address {
+            let stream = Self::open(address, handshake, &self.codec)?;
+            self.stream = Some(stream);
+            self.reconnections += 1;
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::NotConnected,
+                "connection lost and no address to reconnect to",
+            ))
+        }
+    }
+}
+
+impl<R: BattleRules, C> Drop for TcpServerSink<R, C> {
+    fn drop(&mut self) {
+        if let Some(mut stream) = self.stream.take() {
+            let _ = write_frame(&mut stream, FrameKind::Bye, &[]);
+            let _ = stream.shutdown(Shutdown::Write);
+        }
+    }
+}
+
+impl<R: BattleRules, C> EventSink for TcpServerSink<R, C> {
+    fn id(&self) -> EventSinkId {
+        self.id
+    }
+}
+
+impl<R: BattleRules + 'static, C: Codec> ServerSink<R> for TcpServerSink<R, C> {
+    fn send(&mut self, event: &ClientEventPrototype<R>) -> WeaselResult<(), R> {
+        let event: FlatClientEvent<R> = event.clone().into();
+        let payload = self
+            .codec
+            .encode(&event)
+            .map_err(WeaselError::EventSinkError)?;
+        let mut attempts = 0;
+        loop {
+            let result = match &mut self.stream {
+                Some(stream) => write_frame(stream, FrameKind::Event, &payload),
+                None => Err(io::Error::new(io::ErrorKind::NotConnected, "not connected")),
+            };
+            let err = match result {
+                Ok(()) => return Ok(()),
+                Err(err) => err,
+            };
+            self.stream = None;
+            if attempts >= self.reconnect_attempts {
+                return Err(sink_error(err));
+            }
+            attempts += 1;
+            // Failed reconnections are retried, as long as there are attempts left.
+            let _ = self.reconnect();
+        }
+    }
+}
+
+/// A `ClientSink` sending events to a remote client over tcp.
+///
+/// A failed write might leave part of a frame on the stream, after which the client can't
+/// tell where the next frame starts. Thus, the sink closes the connection as soon as a write
+/// fails and asks to be disconnected, whatever its error policy.
+pub struct TcpClientSink<R, C> {
+    id: EventSinkId,
+    stream: Option<TcpStream>,
+    codec: C,
+    error_policy: SinkErrorPolicy,
+    _phantom: PhantomData<R>,
+}
+
+impl<R: BattleRules, C: Codec> TcpClientSink<R, C> {
+    /// Creates a new sink sending events through `stream`.
+    pub fn new(id: EventSinkId, stream: TcpStream, codec: C) -> Self {
+        Self {
+            id,
+            stream: Some(stream),
+            codec,
+            error_policy: SinkErrorPolicy::Disconnect,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Sets the policy applied when sending an event fails without closing the connection,
+    /// for instance because the event couldn't be encoded.
+    pub fn error_policy(mut self, policy: SinkErrorPolicy) -> Self {
+        self.error_policy = policy;
+        self
+    }
+
+    /// Sends application specific data to the client, as a custom frame.
+    pub fn send_custom(&mut self, payload: &[u8]) -> WeaselResult<(), R> {
+        self.write(FrameKind::Custom, payload)
+    }
+
+    /// Returns whether the connection is still open.
+    pub fn is_connected(&self) -> bool {
+        self.stream.is_some()
+    }
+
+    /// Writes a frame into the stream, closing the connection if the write fails.
+    fn write(&mut self, kind: FrameKind, payload: &[u8]) -> WeaselResult<(), R> {
+        let stream = self.stream.as_mut().ok_or_else(|| {
+            sink_error(io::Error::new(
+                io::ErrorKind::NotConnected,
+                "connection closed after a failed write",
+            ))
+        })?;
+        if let Err(err) = write_frame(stream, kind, payload) {
+            if let Some(stream) = self.stream.take() {
+                let _ = stream.shutdown(Shutdown::Both);
+            }
+            return Err(sink_error(err));
+        }
+        Ok(())
+    }
+}
+
+impl<R, C> Drop for TcpClientSink<R, C> {
+    fn drop(&mut self) {
+        if let Some(mut stream) = self.stream.take() {
+            let _ = write_frame(&mut stream, FrameKind::Bye, &[]);
+            let _ = stream.shutdown(Shutdown::Write);
+        }
+    }
+}
+
+impl<R, C> EventSink for TcpClientSink<R, C> {
+    fn id(&self) -> EventSinkId {
+        self.id
+    }
+}
+
+impl<R: BattleRules + 'static, C: Codec> ClientSink<R> for TcpClientSink<R, C> {
+    fn send(&mut self, event: &VersionedEventWrapper<R>) -> WeaselResult<(), R> {
+        let event: FlatVersionedEvent<R> = event.clone().into();
+        let payload = self
+            .codec
+            .encode(&event)
+            .map_err(WeaselError::EventSinkError)?;
+        self.write(FrameKind::Event, &payload)
+    }
+
+    /// Returns the sink's error policy, or `Disconnect` if the connection was closed.
+    fn on_error(&mut self, _: EventId, _: &WeaselErrorType<R>) -> SinkErrorPolicy {
+        if self.stream.is_some() {
+            self.error_policy
+        } else {
+            SinkErrorPolicy::Disconnect
+        }
+    }
+
+    fn flush(&mut self) -> WeaselResult<(), R> {
+        match &mut self.stream {
+            Some(stream) => stream.flush().map_err(sink_error),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::battle::{Battle, BattleController};
+    use crate::event::{DummyEvent, EventKind, EventServer, EventTrigger};
+    use crate::server::Server;
+    use crate::team::CreateTeam;
+    use crate::{battle_rules, rules::empty::*};
+    use std::net::TcpListener;
+
+    battle_rules! {}
+
+    struct JsonCodec;
+
+    impl Codec for JsonCodec {
+        fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, String> {
+            serde_json::to_vec(value).map_err(|err| err.to_string())
+        }
+
+        fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, String> {
+            serde_json::from_slice(bytes).map_err(|err| err.to_string())
+        }
+    }
+
+    fn battle() -> Battle<CustomRules> {
+        Battle::builder(CustomRules::new()).build()
+    }
+
+    #[test]
+    fn frames() {
+        let mut buffer = Vec::new();
+        write_frame(&mut buffer, FrameKind::Custom, &[7, 8]).unwrap();
+        assert_eq!(buffer, vec![0, 0, 0, 2, 3, 7, 8]);
+        assert_eq!(FrameKind::from_byte(3).unwrap(), FrameKind::Custom);
+        assert!(FrameKind::from_byte(9).is_err());
+    }
+
+    #[test]
+    fn client_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let mut server = Server::builder(battle()).build();
+        // Connect a client.
+        let mut server_sink =
+            TcpServerSink::connect(address, battle().handshake(), JsonCodec).unwrap();
+        let mut client_reader = TcpReader::new(
+            server_sink.stream().unwrap().try_clone().unwrap(),
+            JsonCodec,
+        );
+        let (stream, _) = listener.accept().unwrap();
+        let mut server_reader = TcpReader::new(stream.try_clone().unwrap(), JsonCodec);
+        let handshake = server_reader.read_handshake().unwrap();
+        assert_eq!(server.verify_handshake(&handshake).err(), None);
+        let mut client_sink = TcpClientSink::new(1, stream, JsonCodec);
+        // Send an event from the client to the server.
+        let event = DummyEvent::trigger(&mut ())
+            .prototype()
+            .client_prototype(0, None);
+        assert_eq!(ServerSink::send(&mut server_sink, &event).err(), None);
+        let event = server_reader.read_client_event().unwrap().unwrap();
+        assert_eq!(server.process_client(event).err(), None);
+        assert_eq!(server.battle().history().len(), 1);
+        // Send events from the server to the client. Custom frames are skipped.
+        CreateTeam::trigger(&mut server, 1).fire().unwrap();
+        client_sink.send_custom(&[1]).unwrap();
+        for event in server.battle().versioned_events(0..2) {
+            assert_eq!(ClientSink::send(&mut client_sink, &event).err(), None);
+        }
+        let kinds: Vec<_> = (0..2)
+            .map(|_| {
+                client_reader
+                    .read_event::<CustomRules>()
+                    .unwrap()
+                    .unwrap()
+                    .kind()
+            })
+            .collect();
+        assert_eq!(kinds, vec![EventKind::DummyEvent, EventKind::CreateTeam]);
+        // Closing the client sink says goodbye.
+        drop(client_sink);
+        assert!(client_reader.read_event::<CustomRules>().unwrap().is_none());
+        drop(server_sink);
+        assert!(server_reader
+            .read_client_event::<CustomRules>()
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn reconnection() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let mut sink = TcpServerSink::connect(address, battle().handshake(), JsonCodec)
+            .unwrap()
+            .id(5);
+        assert_eq!(EventSink::id(&sink), 5);
+        let _ = listener.accept().unwrap();
+        let event = DummyEvent::trigger(&mut ())
+            .prototype()
+            .client_prototype(0, None);
+        // Without attempts left, a lost connection makes the send fail.
+        sink.stream = None;
+        assert!(ServerSink::send(&mut sink, &event).is_err());
+        // Reconnect and send the handshake again.
+        let mut sink = sink.reconnect_attempts(1);
+        assert_eq!(ServerSink::send(&mut sink, &event).err(), None);
+        assert_eq!(sink.reconnections(), 1);
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = TcpReader::new(stream, JsonCodec);
+        assert!(reader.read_handshake::<CustomRules>().is_ok());
+        assert!(reader.read_client_event::<CustomRules>().unwrap().is_some());
+        // Sinks over existing streams can't reconnect.
+        let mut sink = TcpServerSink::<CustomRules, _>::from_stream(
+            TcpStream::connect(address).unwrap(),
+            JsonCodec,
+        )
+        .reconnect_attempts(1);
+        sink.stream = None;
+        assert!(ServerSink::send(&mut sink, &event).is_err());
+    }
+
+    #[test]
+    fn partial_write() {
+        use std::time::Duration;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (other, _) = listener.accept().unwrap();
+        stream
+            .set_write_timeout(Some(Duration::from_millis(100)))
+            .unwrap();
+        let mut sink = TcpClientSink::<CustomRules, _>::new(1, stream, JsonCodec)
+            .error_policy(SinkErrorPolicy::Retry(1));
+        // The peer doesn't read, so the frame can't fit in the socket's buffers and
+        // the write times out after sending only a part of it.
+        let payload = vec![0; 64 << 20];
+        assert!(sink.send_custom(&payload).is_err());
+        assert!(!sink.is_connected());
+        // The sink doesn't write anything more and asks to be disconnected.
+        let mut server = Server::builder(battle()).build();
+        CreateTeam::trigger(&mut server, 1).fire().unwrap();
+        let event = server.battle().versioned_events(0..1).next().unwrap();
+        let error = ClientSink::send(&mut sink, &event).err().unwrap();
+        assert_eq!(sink.on_error(0, &error), SinkErrorPolicy::Disconnect);
+        // The peer receives the truncated frame followed by the end of the connection.
+        let mut reader = TcpReader::new(other, JsonCodec).max_frame_size(payload.len());
+        assert_eq!(
+            reader.read_frame().err().map(|err| err.kind()),
+            Some(io::ErrorKind::UnexpectedEof)
+        );
+    }
+
+    #[test]
+    fn max_frame_size() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (other, _) = listener.accept().unwrap();
+        let mut reader = TcpReader::new(other, JsonCodec).max_frame_size(2);
+        write_frame(&mut stream, FrameKind::Custom, &[1, 2]).unwrap();
+        write_frame(&mut stream, FrameKind::Custom, &[1, 2, 3]).unwrap();
+        assert_eq!(reader.read_frame().unwrap().payload(), &[1, 2]);
+        assert_eq!(
+            reader.read_frame().err().map(|err| err.kind()),
+            Some(io::ErrorKind::InvalidData)
+        );
+    }
+}
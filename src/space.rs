@@ -1,21 +1,27 @@
 //! Module for the spatial dimension.
 
+use crate::aura::update_auras;
 use crate::battle::{Battle, BattleRules};
 use crate::entity::{Entities, Entity, EntityId};
+use crate::entropy::Entropy;
 use crate::error::{WeaselError, WeaselResult};
 use crate::event::{Event, EventKind, EventProcessor, EventQueue, EventTrigger};
 use crate::metric::WriteMetrics;
+use crate::object::Object;
 use crate::round::Rounds;
 #[cfg(feature = "serialization")]
 use serde::{Deserialize, Serialize};
 use std::any::Any;
 use std::fmt::{Debug, Formatter, Result};
+use std::hash::Hasher;
 
 /// This object takes care of everything related to space and movement in the battle.\
 /// It verifies the consistency of every entity's position.
 pub struct Space<R: BattleRules> {
     model: SpaceModel<R>,
     rules: R::SR,
+    changed_positions: Vec<Position<R>>,
+    altered: bool,
 }
 
 impl<R: BattleRules> Space<R> {
@@ -24,6 +30,8 @@ impl<R: BattleRules> Space<R> {
         Self {
             model: rules.generate_model(&seed),
             rules,
+            changed_positions: Vec::new(),
+            altered: false,
         }
     }
 
@@ -43,21 +51,106 @@ impl<R: BattleRules> Space<R> {
         position: Option<&Position<R>>,
         metrics: &mut WriteMetrics<R>,
     ) {
+        if let PositionClaim::Movement(entity) = claim {
+            self.touch_position(entity.position());
+        }
+        if let Some(position) = position {
+            self.touch_position(position);
+        }
         self.rules
             .move_entity(&mut self.model, claim, position, metrics);
     }
 
+    /// Marks a position as changed.
+    fn touch_position(&mut self, position: &Position<R>) {
+        if !self.changed_positions.contains(position) {
+            self.changed_positions.push(position.clone());
+        }
+    }
+
+    /// Returns an iterator over all positions touched since the last event began.
+    pub(crate) fn changed_positions(&self) -> impl Iterator<Item = &Position<R>> {
+        self.changed_positions.iter()
+    }
+
+    /// Returns true if the space model was altered as a whole since the last event began.
+    pub(crate) fn altered(&self) -> bool {
+        self.altered
+    }
+
+    /// Forgets all tracked changes.
+    pub(crate) fn clear_changes(&mut self) {
+        self.changed_positions.clear();
+        self.altered = false;
+    }
+
     /// Returns the space model.
     /// It stores all data needed to retrieve and compute the position of entities.
     pub fn model(&self) -> &SpaceModel<R> {
         &self.model
     }
 
+    /// Replaces the space model with `model`.
+    #[cfg(feature = "serialization")]
+    pub(crate) fn restore(&mut self, model: SpaceModel<R>) {
+        self.model = model;
+    }
+
     /// Returns a mutable reference to the space model.
     pub fn model_mut(&mut self) -> &mut SpaceModel<R> {
+        self.altered = true;
         &mut self.model
     }
 
+    /// See [line_of_sight](trait.SpaceRules.html#method.line_of_sight).
+    pub fn line_of_sight(&self, from: &Position<R>, to: &Position<R>) -> bool {
+        self.rules.line_of_sight(&self.model, from, to)
+    }
+
+    /// See [path](trait.SpaceRules.html#method.path).
+    pub fn path(&self, from: &Position<R>, to: &Position<R>) -> Option<Vec<Position<R>>> {
+        self.rules.path(&self.model, from, to)
+    }
+
+    /// See [check_step](trait.SpaceRules.html#method.check_step).
+    pub(crate) fn check_step(
+        &self,
+        entity: &dyn Entity<R>,
+        from: &Position<R>,
+        to: &Position<R>,
+    ) -> WeaselResult<(), R> {
+        self.rules.check_step(&self.model, entity, from, to)
+    }
+
+    /// Returns an iterator over all entities whose position lies inside `area`.
+    pub fn entities_in_area<'a>(
+        &'a self,
+        entities: &'a Entities<R>,
+        area: &'a Area<R>,
+    ) -> impl Iterator<Item = &'a dyn Entity<R>> {
+        entities
+            .entities()
+            .filter(move |entity| self.rules.is_in_area(&self.model, area, entity.position()))
+    }
+
+    /// Returns an iterator over all entities within `radius` from `position`.
+    ///
+    /// Entities standing exactly in `position` are not considered neighbors.
+    pub fn neighbors<'a>(
+        &'a self,
+        entities: &'a Entities<R>,
+        position: &'a Position<R>,
+        radius: Distance,
+    ) -> impl Iterator<Item = &'a dyn Entity<R>> {
+        entities.entities().filter(move |entity| {
+            entity.position() != position
+                && matches!(
+                    self.rules.distance(&self.model, position, entity.position()),
+                    Some(distance) if distance <= radius
+                )
+        })
+    }
+
     /// Returns this space's rules.
     pub fn rules(&self) -> &R::SR {
         &self.rules
@@ -95,8 +188,19 @@ pub trait SpaceRules<R: BattleRules> {
     /// See [SpaceAlteration](type.SpaceAlteration.html).
     type SpaceAlteration: Clone + Debug + Send + Serialize + for<'a> Deserialize<'a>;
 
+    #[cfg(not(feature = "serialization"))]
+    /// See [Area](type.Area.html).
+    type Area: Clone + Debug + Send;
+    #[cfg(feature = "serialization")]
+    /// See [Area](type.Area.html).
+    type Area: Clone + Debug + Send + Serialize + for<'a> Deserialize<'a>;
+
+    #[cfg(not(feature = "serialization"))]
     /// See [SpaceModel](type.SpaceModel.html).
     type SpaceModel;
+    #[cfg(feature = "serialization")]
+    /// See [SpaceModel](type.SpaceModel.html).
+    type SpaceModel: Serialize + for<'a> Deserialize<'a>;
 
     /// Generates a `SpaceModel` starting from a `SpaceSeed`.
     fn generate_model(&self, seed: &Option<Self::SpaceSeed>) -> Self::SpaceModel;
@@ -163,6 +267,124 @@ pub trait SpaceRules<R: BattleRules> {
         _metrics: &mut WriteMetrics<R>,
     ) {
     }
+
+    /// Invoked for every object during an environment turn.
+    ///
+    /// Objects can't move by themselves, but the environment can move them for instance
+    /// to simulate drifting boats or rolling boulders.
+    /// Movements should be applied by registering `MoveEntity` events inside `event_queue`.
+    ///
+    /// The provided implementation does nothing.
+    fn on_environment_turn(
+        &self,
+        _model: &Self::SpaceModel,
+        _object: &Object<R>,
+        _event_queue: &mut Option<EventQueue<R>>,
+        _entropy: &mut Entropy<R>,
+        _metrics: &mut WriteMetrics<R>,
+    ) {
+    }
+
+    /// Writes into `hasher` the data of `position` to be included in `Battle::state_digest`.
+    ///
+    /// The provided implementation writes the position's debug representation.
+    fn digest_position(&self, position: &Self::Position, hasher: &mut dyn Hasher) {
+        hasher.write(format!("{:?}", position).as_bytes());
+    }
+
+    /// Returns true if `position` lies inside `area`.
+    ///
+    /// The provided implementation returns false.
+    fn is_in_area(
+        &self,
+        _model: &Self::SpaceModel,
+        _area: &Self::Area,
+        _position: &Self::Position,
+    ) -> bool {
+        false
+    }
+
+    /// Returns the distance between two positions, or `None` if `to` can't be reached
+    /// from `from`.
+    ///
+    /// The provided implementation returns zero for equal positions and `None` otherwise.
+    fn distance(
+        &self,
+        _model: &Self::SpaceModel,
+        from: &Self::Position,
+        to: &Self::Position,
+    ) -> Option<Distance> {
+        if from == to {
+            Some(0)
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if there's a clear line of sight between two positions.
+    ///
+    /// The provided implementation always returns true.
+    fn line_of_sight(
+        &self,
+        _model: &Self::SpaceModel,
+        _from: &Self::Position,
+        _to: &Self::Position,
+    ) -> bool {
+        true
+    }
+
+    /// Computes a path going from `from` to `to`. The path contains all intermediate steps
+    /// plus the destination, but not the starting position.
+    /// Returns `None` if `to` can't be reached.
+    ///
+    /// The provided implementation returns a path made only of the destination.
+    fn path(
+        &self,
+        _model: &Self::SpaceModel,
+        _from: &Self::Position,
+        to: &Self::Position,
+    ) -> Option<Vec<Self::Position>> {
+        Some(vec![to.clone()])
+    }
+
+    /// Checks if an entity can make a single step from a position to another one,
+    /// while moving along a path.
+    ///
+    /// `check_move` is verified for every step as well.
+    ///
+    /// The provided implementation accepts every step.
+    fn check_step(
+        &self,
+        _model: &Self::SpaceModel,
+        _entity: &dyn Entity<R>,
+        _from: &Self::Position,
+        _to: &Self::Position,
+    ) -> WeaselResult<(), R> {
+        Ok(())
+    }
+
+    /// Returns the positions to consider when enumerating the movements
+    /// an entity can make.
+    ///
+    /// Each candidate is then verified like any other movement.
+    /// See [legal_actions](../battle/struct.Battle.html#method.legal_actions).
+    ///
+    /// The provided implementation returns no positions.
+    fn allowed_moves(
+        &self,
+        _model: &Self::SpaceModel,
+        _entity: &dyn Entity<R>,
+    ) -> Vec<Self::Position> {
+        Vec::new()
+    }
+
+    /// Returns the names of the optional features enabled in these space rules.
+    /// They are reported by `Battle::describe`.
+    ///
+    /// The provided implementation returns no features.
+    fn features(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
 }
 
 /// Type to represent an object's position.
@@ -173,6 +395,14 @@ pub trait SpaceRules<R: BattleRules> {
 /// an area, both the area's location and dimension must be encapsulated in this type.
 pub type Position<R> = <<R as BattleRules>::SR as SpaceRules<R>>::Position;
 
+/// Type to represent a region of space, for instance the area hit by an explosion.
+///
+/// Areas are used to find all entities inside a given portion of the battlefield.
+pub type Area<R> = <<R as BattleRules>::SR as SpaceRules<R>>::Area;
+
+/// Type to represent the distance between two positions.
+pub type Distance = u32;
+
 /// Type to represent a space seed.
 /// It is used to bootstrap the spatial model of a game.
 pub type SpaceSeed<R> = <<R as BattleRules>::SR as SpaceRules<R>>::SpaceSeed;
@@ -312,19 +542,24 @@ impl<R: BattleRules + 'static> Event<R> for MoveEntity<R> {
             .entity(&self.id)
             .ok_or_else(|| WeaselError::EntityNotFound(self.id.clone()))?;
         // Check position.
-        battle
-            .space()
-            .check_move(PositionClaim::Movement(entity), &self.position)
-            .map_err(|err| {
-                WeaselError::PositionError(
-                    Some(entity.position().clone()),
-                    self.position.clone(),
-                    Box::new(err),
-                )
-            })
+        watch!(
+            battle.verify_watcher(self.kind()),
+            "SpaceRules::check_move",
+            battle
+                .space()
+                .check_move(PositionClaim::Movement(entity), &self.position)
+        )
+        .map_err(|err| {
+            WeaselError::PositionError(
+                Some(entity.position().clone()),
+                self.position.clone(),
+                Box::new(err),
+            )
+        })
     }
 
-    fn apply(&self, battle: &mut Battle<R>, _: &mut Option<EventQueue<R>>) {
+    fn apply(&self, battle: &mut Battle<R>, event_queue: &mut Option<EventQueue<R>>) {
+        let watcher = battle.apply_watcher();
         // Find the entity.
         let entity = battle
             .state
@@ -332,13 +567,19 @@ impl<R: BattleRules + 'static> Event<R> for MoveEntity<R> {
             .entity_mut(&self.id)
             .unwrap_or_else(|| panic!("constraint violated: entity {:?} not found", self.id));
         // Take the new position.
-        battle.state.space.move_entity(
-            PositionClaim::Movement(entity),
-            Some(&self.position),
-            &mut battle.metrics.write_handle(),
+        watch!(
+            watcher,
+            "SpaceRules::move_entity",
+            battle.state.space.move_entity(
+                PositionClaim::Movement(entity),
+                Some(&self.position),
+                &mut battle.metrics.write_handle(),
+            )
         );
         // Update the entity.
         entity.set_position(self.position.clone());
+        // Characters might have entered or left the area of an aura.
+        update_auras(battle, event_queue);
     }
 
     fn kind(&self) -> EventKind {
@@ -383,6 +624,208 @@ where
     }
 }
 
+/// An event to move an entity along a path, one step at a time.
+///
+/// Each step is verified with the space rules' `check_step` and `check_move`.
+/// The entity goes through all intermediate positions, so that the rules can apply
+/// the consequences of each step.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateCreature,
+///     CreateTeam, EntityId, EventKind, EventTrigger, MoveEntityAlongPath, Server,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// let team_id = 1;
+/// CreateTeam::trigger(&mut server, team_id).fire().unwrap();
+/// let creature_id = 1;
+/// CreateCreature::trigger(&mut server, creature_id, team_id, ())
+///     .fire()
+///     .unwrap();
+///
+/// let path = server.battle().space().path(&(), &()).unwrap();
+/// MoveEntityAlongPath::trigger(&mut server, EntityId::Creature(creature_id), path)
+///     .fire()
+///     .unwrap();
+/// assert_eq!(
+///     server.battle().history().events().iter().last().unwrap().kind(),
+///     EventKind::MoveEntityAlongPath
+/// );
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct MoveEntityAlongPath<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "EntityId<R>: Serialize",
+            deserialize = "EntityId<R>: Deserialize<'de>"
+        ))
+    )]
+    id: EntityId<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Position<R>: Serialize",
+            deserialize = "Position<R>: Deserialize<'de>"
+        ))
+    )]
+    path: Vec<Position<R>>,
+}
+
+impl<R: BattleRules> MoveEntityAlongPath<R> {
+    /// Returns a trigger for this event.
+    pub fn trigger<P: EventProcessor<R>>(
+        processor: &mut P,
+        id: EntityId<R>,
+        path: Vec<Position<R>>,
+    ) -> MoveEntityAlongPathTrigger<'_, R, P> {
+        MoveEntityAlongPathTrigger {
+            processor,
+            id,
+            path,
+        }
+    }
+
+    /// Returns the entity id.
+    pub fn id(&self) -> &EntityId<R> {
+        &self.id
+    }
+
+    /// Returns the steps of the path, ending with the destination.
+    pub fn path(&self) -> &Vec<Position<R>> {
+        &self.path
+    }
+}
+
+impl<R: BattleRules> Debug for MoveEntityAlongPath<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "MoveEntityAlongPath {{ id: {:?}, path: {:?} }}",
+            self.id, self.path
+        )
+    }
+}
+
+impl<R: BattleRules> Clone for MoveEntityAlongPath<R> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+            path: self.path.clone(),
+        }
+    }
+}
+
+impl<R: BattleRules + 'static> Event<R> for MoveEntityAlongPath<R> {
+    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
+        let watcher = battle.verify_watcher(self.kind());
+        // Find the entity.
+        let entity = battle
+            .entities()
+            .entity(&self.id)
+            .ok_or_else(|| WeaselError::EntityNotFound(self.id.clone()))?;
+        if self.path.is_empty() {
+            return Err(WeaselError::EmptyPath(self.id.clone()));
+        }
+        // Check each step.
+        let mut from = entity.position();
+        for to in &self.path {
+            watch!(
+                watcher,
+                "SpaceRules::check_step",
+                battle.space().check_step(entity, from, to)
+            )
+            .and_then(|_| {
+                watch!(
+                    watcher,
+                    "SpaceRules::check_move",
+                    battle
+                        .space()
+                        .check_move(PositionClaim::Movement(entity), to)
+                )
+            })
+            .map_err(|err| {
+                WeaselError::PositionError(Some(from.clone()), to.clone(), Box::new(err))
+            })?;
+            from = to;
+        }
+        Ok(())
+    }
+
+    fn apply(&self, battle: &mut Battle<R>, event_queue: &mut Option<EventQueue<R>>) {
+        let watcher = battle.apply_watcher();
+        // Find the entity.
+        let entity = battle
+            .state
+            .entities
+            .entity_mut(&self.id)
+            .unwrap_or_else(|| panic!("constraint violated: entity {:?} not found", self.id));
+        // Go through all steps.
+        for position in &self.path {
+            watch!(
+                watcher,
+                "SpaceRules::move_entity",
+                battle.state.space.move_entity(
+                    PositionClaim::Movement(entity),
+                    Some(position),
+                    &mut battle.metrics.write_handle(),
+                )
+            );
+            entity.set_position(position.clone());
+        }
+        // Characters might have entered or left the area of an aura.
+        update_auras(battle, event_queue);
+    }
+
+    fn kind(&self) -> EventKind {
+        EventKind::MoveEntityAlongPath
+    }
+
+    fn box_clone(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Trigger to build and fire a `MoveEntityAlongPath` event.
+pub struct MoveEntityAlongPathTrigger<'a, R, P>
+where
+    R: BattleRules,
+    P: EventProcessor<R>,
+{
+    processor: &'a mut P,
+    id: EntityId<R>,
+    path: Vec<Position<R>>,
+}
+
+impl<'a, R, P> EventTrigger<'a, R, P> for MoveEntityAlongPathTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    fn processor(&'a mut self) -> &'a mut P {
+        self.processor
+    }
+
+    /// Returns a `MoveEntityAlongPath` event.
+    fn event(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(MoveEntityAlongPath {
+            id: self.id.clone(),
+            path: self.path.clone(),
+        })
+    }
+}
+
 /// Event to reset the space model.
 ///
 /// # Examples
@@ -450,21 +893,31 @@ impl<R: BattleRules + 'static> Event<R> for ResetSpace<R> {
     }
 
     fn apply(&self, battle: &mut Battle<R>, event_queue: &mut Option<EventQueue<R>>) {
+        let watcher = battle.apply_watcher();
         let rules = &battle.state.space.rules;
         // Generate a new model.
-        let mut new_model = rules.generate_model(&self.seed);
+        let mut new_model = watch!(
+            watcher,
+            "SpaceRules::generate_model",
+            rules.generate_model(&self.seed)
+        );
         // Translate every entity's position from the old model to the new.
         for entity in battle.state.entities.entities_mut() {
-            rules.translate_entity(
-                &battle.state.space.model,
-                &mut new_model,
-                entity,
-                event_queue,
-                &mut battle.metrics.write_handle(),
+            watch!(
+                watcher,
+                "SpaceRules::translate_entity",
+                rules.translate_entity(
+                    &battle.state.space.model,
+                    &mut new_model,
+                    entity,
+                    event_queue,
+                    &mut battle.metrics.write_handle(),
+                )
             );
         }
         // Set the new model in `Space`.
         battle.state.space.model = new_model;
+        battle.state.space.altered = true;
     }
 
     fn kind(&self) -> EventKind {
@@ -595,14 +1048,19 @@ impl<R: BattleRules + 'static> Event<R> for AlterSpace<R> {
     fn apply(&self, battle: &mut Battle<R>, event_queue: &mut Option<EventQueue<R>>) {
         let rules = &battle.state.space.rules;
         // Apply the alteration.
-        rules.alter_space(
-            &battle.state.entities,
-            &battle.state.rounds,
-            &mut battle.state.space.model,
-            &self.alteration,
-            event_queue,
-            &mut battle.metrics.write_handle(),
+        watch!(
+            battle.apply_watcher(),
+            "SpaceRules::alter_space",
+            rules.alter_space(
+                &battle.state.entities,
+                &battle.state.rounds,
+                &mut battle.state.space.model,
+                &self.alteration,
+                event_queue,
+                &mut battle.metrics.write_handle(),
+            )
         );
+        battle.state.space.altered = true;
     }
 
     fn kind(&self) -> EventKind {
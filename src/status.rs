@@ -1,5 +1,6 @@
 //! Module for long lasting status effects.
 
+use crate::aura::update_auras;
 use crate::battle::{Battle, BattleRules};
 use crate::character::{verify_get_character, CharacterRules};
 use crate::entity::EntityId;
@@ -34,13 +35,30 @@ pub type StatusesAlteration<R> = <<R as BattleRules>::CR as CharacterRules<R>>::
 pub type StatusDuration = EventId;
 
 /// Stores a `Status` and additional information about it.
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub struct AppliedStatus<R: BattleRules> {
     /// The status.
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Status<R>: Serialize",
+            deserialize = "Status<R>: Deserialize<'de>"
+        ))
+    )]
     status: Status<R>,
     /// An optional link to the origin event.
     origin: Option<EventId>,
     /// How long this status have been running.
     duration: StatusDuration,
+    /// The character projecting the aura this status derives from, if any.
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Option<EntityId<R>>: Serialize",
+            deserialize = "Option<EntityId<R>>: Deserialize<'de>"
+        ))
+    )]
+    aura_source: Option<EntityId<R>>,
 }
 
 impl<R: BattleRules> AppliedStatus<R> {
@@ -50,6 +68,7 @@ impl<R: BattleRules> AppliedStatus<R> {
             status,
             origin: None,
             duration: 0,
+            aura_source: None,
         }
     }
 
@@ -59,6 +78,7 @@ impl<R: BattleRules> AppliedStatus<R> {
             status,
             origin: Some(origin),
             duration: 0,
+            aura_source: None,
         }
     }
 
@@ -77,6 +97,12 @@ impl<R: BattleRules> AppliedStatus<R> {
         self.origin
     }
 
+    /// Returns the id of the character projecting the aura this status derives from,
+    /// or `None` if the status is not derived from an aura.
+    pub fn aura_source(&self) -> Option<&EntityId<R>> {
+        self.aura_source.as_ref()
+    }
+
     /// Returns for how many turns the status has been in place.\
     /// Duration is increased at every turn start.
     pub fn duration(&self) -> StatusDuration {
@@ -87,6 +113,16 @@ impl<R: BattleRules> AppliedStatus<R> {
     pub(crate) fn update(&mut self) {
         self.duration += 1;
     }
+
+    /// Applies a change to the duration.
+    pub(crate) fn change_duration(&mut self, change: DurationChange) {
+        self.duration = match change {
+            DurationChange::Reset => 0,
+            DurationChange::Set(duration) => duration,
+            DurationChange::Extend(turns) => self.duration.saturating_sub(turns),
+            DurationChange::Shorten(turns) => self.duration.saturating_add(turns),
+        };
+    }
 }
 
 impl<R: BattleRules> std::ops::Deref for AppliedStatus<R> {
@@ -117,6 +153,96 @@ pub enum Application<'a, R: BattleRules> {
     Replacement(&'a OldStatus<R>, &'a NewStatus<R>),
 }
 
+impl<'a, R: BattleRules> Clone for Application<'a, R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, R: BattleRules> Copy for Application<'a, R> {}
+
+/// Outcome of the combination of a new status with the statuses already affecting a character.
+///
+/// See [combine_statuses](../fight/trait.FightRules.html#method.combine_statuses).
+pub enum StatusCombination<R: BattleRules> {
+    /// Inflicts the given status, after clearing the existing statuses with the given ids.
+    Inflict(Status<R>, Vec<StatusId<R>>),
+    /// The new status is not inflicted. Existing statuses with the given ids are cleared.
+    Discard(Vec<StatusId<R>>),
+}
+
+/// Clears a status from a character, deleting its side effects.
+///
+/// The character must be afflicted by the status.
+pub(crate) fn clear_status<R: BattleRules + 'static>(
+    entity_id: &EntityId<R>,
+    status_id: &StatusId<R>,
+    battle: &mut Battle<R>,
+    event_queue: &mut Option<EventQueue<R>>,
+) {
+    // Retrieve the character.
+    let character = battle
+        .state
+        .entities
+        .character(entity_id)
+        .unwrap_or_else(|| panic!("constraint violated: character {:?} not found", entity_id));
+    // Delete the status' side effects.
+    let status = character.status(status_id).unwrap_or_else(|| {
+        panic!(
+            "constraint violated: status {:?} not found in {:?}",
+            status_id, entity_id
+        )
+    });
+    watch!(
+        battle.apply_watcher(),
+        "FightRules::delete_status",
+        battle.rules.fight_rules().delete_status(
+            &battle.state,
+            character,
+            status,
+            event_queue,
+            &mut battle.entropy,
+            &mut battle.metrics.write_handle(),
+        )
+    );
+    // Retrieve the character, this time through a mutable reference.
+    let character = battle
+        .state
+        .entities
+        .character_mut(entity_id)
+        .unwrap_or_else(|| panic!("constraint violated: character {:?} not found", entity_id));
+    // Remove the status from the character.
+    character.remove_status(status_id);
+}
+
+/// Returns true if the status `status_id` of the character `entity_id` projects an aura.
+fn is_aura<R: BattleRules + 'static>(
+    battle: &Battle<R>,
+    entity_id: &EntityId<R>,
+    status_id: &StatusId<R>,
+) -> bool {
+    battle
+        .state
+        .entities
+        .character(entity_id)
+        .and_then(|character| {
+            character
+                .status(status_id)
+                .filter(|status| status.aura_source().is_none())
+                .and_then(|status| {
+                    watch!(
+                        battle.apply_watcher(),
+                        "FightRules::aura",
+                        battle
+                            .rules
+                            .fight_rules()
+                            .aura(&battle.state, character, status)
+                    )
+                })
+        })
+        .is_some()
+}
+
 /// Updates all statuses of a entity.
 /// Returns an error if the entity doesn't exist or if it isn't a character.
 pub(crate) fn update_statuses<R: BattleRules + 'static>(
@@ -140,16 +266,20 @@ pub(crate) fn update_statuses<R: BattleRules + 'static>(
         .character(id)
         .ok_or_else(|| WeaselError::EntityNotFound(id.clone()))?;
     for status in character.statuses() {
-        let terminated = battle.rules.fight_rules().update_status(
-            &battle.state,
-            character,
-            status,
-            // Set the origin of all events caused by the status' update to the status own origin.
-            &mut event_queue
-                .as_mut()
-                .map(|queue| LinkedQueue::new(queue, status.origin())),
-            &mut battle.entropy,
-            &mut battle.metrics.write_handle(),
+        let terminated = watch!(
+            battle.apply_watcher(),
+            "FightRules::update_status",
+            battle.rules.fight_rules().update_status(
+                &battle.state,
+                character,
+                status,
+                // Set the origin of all events caused by the status' update to the status own origin.
+                &mut event_queue
+                    .as_mut()
+                    .map(|queue| LinkedQueue::new(queue, status.origin())),
+                &mut battle.entropy,
+                &mut battle.metrics.write_handle(),
+            )
         );
         if terminated {
             // Remove the status.
@@ -161,6 +291,8 @@ pub(crate) fn update_statuses<R: BattleRules + 'static>(
             .fire();
         }
     }
+    // Statuses' changes might have altered some aura.
+    update_auras(battle, event_queue);
     Ok(())
 }
 
@@ -225,6 +357,15 @@ pub struct InflictStatus<R: BattleRules> {
         ))
     )]
     potency: Option<Potency<R>>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Option<EntityId<R>>: Serialize",
+            deserialize = "Option<EntityId<R>>: Deserialize<'de>"
+        ))
+    )]
+    aura_source: Option<EntityId<R>>,
 }
 
 impl<R: BattleRules> InflictStatus<R> {
@@ -239,6 +380,24 @@ impl<R: BattleRules> InflictStatus<R> {
             entity_id,
             status_id,
             potency: None,
+            aura_source: None,
+        }
+    }
+
+    /// Returns a trigger for a status projected by the aura of `source`.
+    pub(crate) fn trigger_projection<'a, P: EventProcessor<R>>(
+        processor: &'a mut P,
+        entity_id: EntityId<R>,
+        status_id: StatusId<R>,
+        potency: Option<Potency<R>>,
+        source: EntityId<R>,
+    ) -> InflictStatusTrigger<'a, R, P> {
+        InflictStatusTrigger {
+            processor,
+            entity_id,
+            status_id,
+            potency,
+            aura_source: Some(source),
         }
     }
 
@@ -256,14 +415,23 @@ impl<R: BattleRules> InflictStatus<R> {
     pub fn potency(&self) -> &Option<Potency<R>> {
         &self.potency
     }
+
+    /// Returns the id of the character projecting the aura that inflicts this status,
+    /// if the status is derived from an aura.
+    ///
+    /// See the [aura module](../aura/index.html).
+    pub fn aura_source(&self) -> &Option<EntityId<R>> {
+        &self.aura_source
+    }
 }
 
 impl<R: BattleRules> Debug for InflictStatus<R> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         write!(
             f,
-            "InflictStatus {{ entity_id: {:?}, status_id: {:?}, potency: {:?} }}",
-            self.entity_id, self.status_id, self.potency
+            "InflictStatus {{ entity_id: {:?}, status_id: {:?}, potency: {:?}, \
+             aura_source: {:?} }}",
+            self.entity_id, self.status_id, self.potency, self.aura_source
         )
     }
 }
@@ -274,6 +442,7 @@ impl<R: BattleRules> Clone for InflictStatus<R> {
             entity_id: self.entity_id.clone(),
             status_id: self.status_id.clone(),
             potency: self.potency.clone(),
+            aura_source: self.aura_source.clone(),
         }
     }
 }
@@ -284,11 +453,12 @@ impl<R: BattleRules + 'static> Event<R> for InflictStatus<R> {
     }
 
     fn apply(&self, battle: &mut Battle<R>, event_queue: &mut Option<EventQueue<R>>) {
+        let watcher = battle.apply_watcher();
         // Retrieve the character.
         let character = battle
             .state
             .entities
-            .character_mut(&self.entity_id)
+            .character(&self.entity_id)
             .unwrap_or_else(|| {
                 panic!(
                     "constraint violated: character {:?} not found",
@@ -296,19 +466,68 @@ impl<R: BattleRules + 'static> Event<R> for InflictStatus<R> {
                 )
             });
         // Generate the status.
-        let status = battle.rules.character_rules().generate_status(
-            character,
-            &self.status_id,
-            &self.potency,
-            &mut battle.entropy,
-            &mut battle.metrics.write_handle(),
+        let status = watch!(
+            watcher,
+            "CharacterRules::generate_status",
+            battle.rules.character_rules().generate_status(
+                character,
+                &self.status_id,
+                &self.potency,
+                &mut battle.entropy,
+                &mut battle.metrics.write_handle(),
+            )
         );
+        // Combine the status with the existing ones.
+        let combination = match status {
+            Some(status) => watch!(
+                watcher,
+                "FightRules::combine_statuses",
+                battle.rules.fight_rules().combine_statuses(
+                    &battle.state,
+                    character,
+                    status,
+                    &mut battle.entropy,
+                    &mut battle.metrics.write_handle(),
+                )
+            ),
+            None => StatusCombination::Discard(Vec::new()),
+        };
+        let (status, consumed) = match combination {
+            StatusCombination::Inflict(status, consumed) => (Some(status), consumed),
+            StatusCombination::Discard(consumed) => (None, consumed),
+        };
+        // Clear the consumed statuses.
+        for status_id in consumed {
+            let present = battle
+                .state
+                .entities
+                .character(&self.entity_id)
+                .and_then(|character| character.status(&status_id))
+                .is_some();
+            if present {
+                clear_status(&self.entity_id, &status_id, battle, event_queue);
+            }
+        }
         if let Some(status) = status {
             // We can assume that the id of this event will be equal to history's next_id(),
             // because the id will be assigned just after this function returns.
             let origin = battle.history.next_id();
             // Add the status to the character.
-            let old_status = character.add_status(AppliedStatus::with_origin(status, origin));
+            let character = battle
+                .state
+                .entities
+                .character_mut(&self.entity_id)
+                .unwrap_or_else(|| {
+                    panic!(
+                        "constraint violated: character {:?} not found",
+                        self.entity_id
+                    )
+                });
+            // The combination might have changed the status' id.
+            let status_id = status.id().clone();
+            let mut status = AppliedStatus::with_origin(status, origin);
+            status.aura_source = self.aura_source.clone();
+            let old_status = character.add_status(status);
             // Retrieve the character again, but this time immutably borrowing battle.state.
             let character = battle
                 .state
@@ -321,10 +540,10 @@ impl<R: BattleRules + 'static> Event<R> for InflictStatus<R> {
                     )
                 });
             // Apply the status' side effects.
-            let status = character.status(&self.status_id).unwrap_or_else(|| {
+            let status = character.status(&status_id).unwrap_or_else(|| {
                 panic!(
                     "constraint violated: status {:?} not found in {:?}",
-                    self.status_id, self.entity_id
+                    status_id, self.entity_id
                 )
             });
             let application = if let Some(old) = old_status.as_ref() {
@@ -332,14 +551,22 @@ impl<R: BattleRules + 'static> Event<R> for InflictStatus<R> {
             } else {
                 Application::New(status)
             };
-            battle.rules.fight_rules().apply_status(
-                &battle.state,
-                character,
-                application,
-                event_queue,
-                &mut battle.entropy,
-                &mut battle.metrics.write_handle(),
+            watch!(
+                watcher,
+                "FightRules::apply_status",
+                battle.rules.fight_rules().apply_status(
+                    &battle.state,
+                    character,
+                    application,
+                    event_queue,
+                    &mut battle.entropy,
+                    &mut battle.metrics.write_handle(),
+                )
             );
+            // Project the status if it's an aura.
+            if is_aura(battle, &self.entity_id, &status_id) {
+                update_auras(battle, event_queue);
+            }
         }
     }
 
@@ -366,6 +593,7 @@ where
     entity_id: EntityId<R>,
     status_id: StatusId<R>,
     potency: Option<Potency<R>>,
+    aura_source: Option<EntityId<R>>,
 }
 
 impl<'a, R, P> InflictStatusTrigger<'a, R, P>
@@ -395,6 +623,7 @@ where
             entity_id: self.entity_id.clone(),
             status_id: self.status_id.clone(),
             potency: self.potency.clone(),
+            aura_source: self.aura_source.clone(),
         })
     }
 }
@@ -508,49 +737,222 @@ impl<R: BattleRules + 'static> Event<R> for ClearStatus<R> {
     }
 
     fn apply(&self, battle: &mut Battle<R>, event_queue: &mut Option<EventQueue<R>>) {
+        let aura = is_aura(battle, &self.entity_id, &self.status_id);
+        clear_status(&self.entity_id, &self.status_id, battle, event_queue);
+        // Remove the projections of the aura.
+        if aura {
+            update_auras(battle, event_queue);
+        }
+    }
+
+    fn kind(&self) -> EventKind {
+        EventKind::ClearStatus
+    }
+
+    fn box_clone(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Trigger to build and fire a `ClearStatus` event.
+pub struct ClearStatusTrigger<'a, R, P>
+where
+    R: BattleRules,
+    P: EventProcessor<R>,
+{
+    processor: &'a mut P,
+    entity_id: EntityId<R>,
+    status_id: StatusId<R>,
+}
+
+impl<'a, R, P> EventTrigger<'a, R, P> for ClearStatusTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    fn processor(&'a mut self) -> &'a mut P {
+        self.processor
+    }
+
+    /// Returns a `ClearStatus` event.
+    fn event(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(ClearStatus {
+            entity_id: self.entity_id.clone(),
+            status_id: self.status_id.clone(),
+        })
+    }
+}
+
+/// A change to the duration of an applied status.
+///
+/// The duration counts for how many turns a status has been in place, while its
+/// maximum duration is decided by the rules. Thus extending a status decreases its duration.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum DurationChange {
+    /// Restarts the status, as if it was just inflicted.
+    Reset,
+    /// Sets for how many turns the status has been in place.
+    Set(StatusDuration),
+    /// Makes the status last the given number of turns longer.
+    Extend(StatusDuration),
+    /// Makes the status end the given number of turns earlier.
+    Shorten(StatusDuration),
+}
+
+/// An event to change the duration of a status applied to a character.
+///
+/// Unlike clearing and inflicting again the status, the status keeps its origin and its state.
+///
+/// # Examples
+/// ```
+/// use weasel::status::DurationChange;
+/// use weasel::{
+///     battle_rules, rules::empty::*, AlterStatusDuration, Battle, BattleController,
+///     BattleRules, CreateCreature, CreateTeam, EntityId, EventTrigger, Server,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// let team_id = 1;
+/// CreateTeam::trigger(&mut server, team_id).fire().unwrap();
+/// let creature_id = 1;
+/// let position = ();
+/// CreateCreature::trigger(&mut server, creature_id, team_id, position)
+///     .fire()
+///     .unwrap();
+///
+/// let status_id = 1;
+/// let result = AlterStatusDuration::trigger(
+///     &mut server,
+///     EntityId::Creature(creature_id),
+///     status_id,
+///     DurationChange::Reset,
+/// )
+/// .fire();
+/// // The creature is not afflicted by the status.
+/// assert!(result.is_err());
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct AlterStatusDuration<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "EntityId<R>: Serialize",
+            deserialize = "EntityId<R>: Deserialize<'de>"
+        ))
+    )]
+    entity_id: EntityId<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "StatusId<R>: Serialize",
+            deserialize = "StatusId<R>: Deserialize<'de>"
+        ))
+    )]
+    status_id: StatusId<R>,
+
+    change: DurationChange,
+}
+
+impl<R: BattleRules> AlterStatusDuration<R> {
+    /// Returns a trigger for this event.
+    pub fn trigger<'a, P: EventProcessor<R>>(
+        processor: &'a mut P,
+        entity_id: EntityId<R>,
+        status_id: StatusId<R>,
+        change: DurationChange,
+    ) -> AlterStatusDurationTrigger<'a, R, P> {
+        AlterStatusDurationTrigger {
+            processor,
+            entity_id,
+            status_id,
+            change,
+        }
+    }
+
+    /// Returns the id of the character afflicted by the status.
+    pub fn entity_id(&self) -> &EntityId<R> {
+        &self.entity_id
+    }
+
+    /// Returns the id of the status whose duration will be changed.
+    pub fn status_id(&self) -> &StatusId<R> {
+        &self.status_id
+    }
+
+    /// Returns the change to the status' duration.
+    pub fn change(&self) -> DurationChange {
+        self.change
+    }
+}
+
+impl<R: BattleRules> Debug for AlterStatusDuration<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "AlterStatusDuration {{ entity_id: {:?}, status_id: {:?}, change: {:?} }}",
+            self.entity_id, self.status_id, self.change
+        )
+    }
+}
+
+impl<R: BattleRules> Clone for AlterStatusDuration<R> {
+    fn clone(&self) -> Self {
+        Self {
+            entity_id: self.entity_id.clone(),
+            status_id: self.status_id.clone(),
+            change: self.change,
+        }
+    }
+}
+
+impl<R: BattleRules + 'static> Event<R> for AlterStatusDuration<R> {
+    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
+        let character = verify_get_character(battle.entities(), &self.entity_id)?;
+        // The character must be afflicted by the status.
+        if character.status(&self.status_id).is_none() {
+            Err(WeaselError::StatusNotPresent(
+                self.entity_id.clone(),
+                self.status_id.clone(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn apply(&self, battle: &mut Battle<R>, _: &mut Option<EventQueue<R>>) {
         // Retrieve the character.
         let character = battle
             .state
             .entities
-            .character(&self.entity_id)
+            .character_mut(&self.entity_id)
             .unwrap_or_else(|| {
                 panic!(
                     "constraint violated: character {:?} not found",
                     self.entity_id
                 )
             });
-        // Delete the status' side effects.
-        let status = character.status(&self.status_id).unwrap_or_else(|| {
+        // Change the status' duration.
+        let status = character.status_mut(&self.status_id).unwrap_or_else(|| {
             panic!(
                 "constraint violated: status {:?} not found in {:?}",
                 self.status_id, self.entity_id
             )
         });
-        battle.rules.fight_rules().delete_status(
-            &battle.state,
-            character,
-            status,
-            event_queue,
-            &mut battle.entropy,
-            &mut battle.metrics.write_handle(),
-        );
-        // Retrieve the character, this time through a mutable reference.
-        let character = battle
-            .state
-            .entities
-            .character_mut(&self.entity_id)
-            .unwrap_or_else(|| {
-                panic!(
-                    "constraint violated: character {:?} not found",
-                    self.entity_id
-                )
-            });
-        // Remove the status from the character.
-        character.remove_status(&self.status_id);
+        status.change_duration(self.change);
     }
 
     fn kind(&self) -> EventKind {
-        EventKind::ClearStatus
+        EventKind::AlterStatusDuration
     }
 
     fn box_clone(&self) -> Box<dyn Event<R> + Send> {
@@ -562,8 +964,8 @@ impl<R: BattleRules + 'static> Event<R> for ClearStatus<R> {
     }
 }
 
-/// Trigger to build and fire a `ClearStatus` event.
-pub struct ClearStatusTrigger<'a, R, P>
+/// Trigger to build and fire an `AlterStatusDuration` event.
+pub struct AlterStatusDurationTrigger<'a, R, P>
 where
     R: BattleRules,
     P: EventProcessor<R>,
@@ -571,9 +973,10 @@ where
     processor: &'a mut P,
     entity_id: EntityId<R>,
     status_id: StatusId<R>,
+    change: DurationChange,
 }
 
-impl<'a, R, P> EventTrigger<'a, R, P> for ClearStatusTrigger<'a, R, P>
+impl<'a, R, P> EventTrigger<'a, R, P> for AlterStatusDurationTrigger<'a, R, P>
 where
     R: BattleRules + 'static,
     P: EventProcessor<R>,
@@ -582,11 +985,12 @@ where
         self.processor
     }
 
-    /// Returns a `ClearStatus` event.
+    /// Returns an `AlterStatusDuration` event.
     fn event(&self) -> Box<dyn Event<R> + Send> {
-        Box::new(ClearStatus {
+        Box::new(AlterStatusDuration {
             entity_id: self.entity_id.clone(),
             status_id: self.status_id.clone(),
+            change: self.change,
         })
     }
 }
@@ -693,6 +1097,7 @@ impl<R: BattleRules + 'static> Event<R> for AlterStatuses<R> {
     }
 
     fn apply(&self, battle: &mut Battle<R>, _: &mut Option<EventQueue<R>>) {
+        let watcher = battle.apply_watcher();
         // Retrieve the character.
         let character = battle
             .state
@@ -700,11 +1105,15 @@ impl<R: BattleRules + 'static> Event<R> for AlterStatuses<R> {
             .character_mut(&self.id)
             .unwrap_or_else(|| panic!("constraint violated: character {:?} not found", self.id));
         // Alter the character.
-        battle.rules.character_rules().alter_statuses(
-            character,
-            &self.alteration,
-            &mut battle.entropy,
-            &mut battle.metrics.write_handle(),
+        watch!(
+            watcher,
+            "CharacterRules::alter_statuses",
+            battle.rules.character_rules().alter_statuses(
+                character,
+                &self.alteration,
+                &mut battle.entropy,
+                &mut battle.metrics.write_handle(),
+            )
         );
     }
 
@@ -4,11 +4,11 @@ use crate::battle::{Battle, BattleRules, BattleState};
 use crate::creature::{Creature, CreatureId};
 use crate::entropy::Entropy;
 use crate::error::{WeaselError, WeaselResult};
-use crate::event::{Event, EventKind, EventProcessor, EventQueue, EventTrigger};
+use crate::event::{Event, EventKind, EventProcessor, EventQueue, EventRights, EventTrigger};
 use crate::metric::system::*;
 use crate::metric::{ReadMetrics, WriteMetrics};
 use crate::power::{Invocation, Power, PowerId, PowersAlteration, PowersSeed};
-use crate::util::{collect_from_iter, Id};
+use crate::util::{collect_from_iter, Id, RegenerationPolicy};
 use indexmap::IndexMap;
 #[cfg(feature = "serialization")]
 use serde::{Deserialize, Serialize};
@@ -25,17 +25,79 @@ type Powers<R> = IndexMap<
 ///
 /// A team represents the unit of control of a player. Teams must achieve their objectives in
 /// order to win the battle.
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub struct Team<R: BattleRules> {
     /// The id of this team.
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "TeamId<R>: Serialize",
+            deserialize = "TeamId<R>: Deserialize<'de>"
+        ))
+    )]
     id: TeamId<R>,
     /// Ids of all creatures which are currently part of this team.
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Vec<CreatureId<R>>: Serialize",
+            deserialize = "Vec<CreatureId<R>>: Deserialize<'de>"
+        ))
+    )]
     creatures: Vec<CreatureId<R>>,
     /// All the team's powers.
+    #[cfg_attr(
+        feature = "serialization",
+        serde(
+            bound(
+                serialize = "Power<R>: Serialize",
+                deserialize = "Power<R>: Deserialize<'de>"
+            ),
+            serialize_with = "crate::serde::serialize_pairs",
+            deserialize_with = "crate::serde::deserialize_pairs"
+        )
+    )]
     powers: Powers<R>,
     /// `Conclusion`, if any, reached by this team.
     conclusion: Option<Conclusion>,
     /// Team objectives.
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Objectives<R>: Serialize",
+            deserialize = "Objectives<R>: Deserialize<'de>"
+        ))
+    )]
     objectives: Objectives<R>,
+    /// Last known progress towards the objectives.
+    #[cfg_attr(feature = "serialization", serde(skip))]
+    progress: Option<Box<dyn ObjectiveProgress>>,
+    /// Points scored by this team.
+    score: i64,
+    /// Amount of each resource owned by this team.
+    resources: IndexMap<ResourceId, u64>,
+    /// Pending trade proposals made by this team, indexed by recipient.
+    #[cfg_attr(
+        feature = "serialization",
+        serde(
+            bound(
+                serialize = "TradeProposal<R>: Serialize",
+                deserialize = "TradeProposal<R>: Deserialize<'de>"
+            ),
+            serialize_with = "crate::serde::serialize_pairs",
+            deserialize_with = "crate::serde::deserialize_pairs"
+        )
+    )]
+    trade_proposals: IndexMap<TeamId<R>, TradeProposal<R>>,
+    /// Additional data defined by the team rules.
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "TeamData<R>: Serialize",
+            deserialize = "TeamData<R>: Deserialize<'de>"
+        ))
+    )]
+    data: TeamData<R>,
 }
 
 impl<R: BattleRules> Team<R> {
@@ -90,6 +152,109 @@ impl<R: BattleRules> Team<R> {
         &self.objectives
     }
 
+    /// Returns the additional data defined by the team rules.
+    pub fn data(&self) -> &TeamData<R> {
+        &self.data
+    }
+
+    /// Returns a mutable reference to the additional data defined by the team rules.
+    pub fn data_mut(&mut self) -> &mut TeamData<R> {
+        &mut self.data
+    }
+
+    /// Returns the progress made by this team towards its objectives, if known.
+    ///
+    /// Progress is evaluated by [objectives_progress](trait.TeamRules.html#method.objectives_progress)
+    /// every time the team's objectives are checked.
+    pub fn progress(&self) -> Option<&dyn ObjectiveProgress> {
+        self.progress.as_deref()
+    }
+
+    /// Sets the progress made by this team towards its objectives.
+    pub(crate) fn set_progress(&mut self, progress: Option<Box<dyn ObjectiveProgress>>) {
+        self.progress = progress;
+    }
+
+    /// Returns the points scored by this team.
+    ///
+    /// Scores are changed through [AddScore](struct.AddScore.html) events.
+    pub fn score(&self) -> i64 {
+        self.score
+    }
+
+    /// Returns the amount of the given resource owned by this team.
+    ///
+    /// Resources are exchanged through [TransferResource](struct.TransferResource.html) and
+    /// [TradeProposal](struct.TradeProposal.html) events.
+    pub fn resource(&self, id: ResourceId) -> u64 {
+        self.resources.get(&id).copied().unwrap_or(0)
+    }
+
+    /// Returns an iterator over all resources of this team, together with their amount.
+    pub fn resources(&self) -> impl Iterator<Item = (ResourceId, u64)> + '_ {
+        self.resources.iter().map(|(id, amount)| (*id, *amount))
+    }
+
+    /// Returns the pending trade proposal made by this team to `recipient`, if any.
+    pub fn trade_proposal(&self, recipient: &TeamId<R>) -> Option<&TradeProposal<R>> {
+        self.trade_proposals.get(recipient)
+    }
+
+    /// Returns an iterator over all pending trade proposals made by this team.
+    pub fn trade_proposals(&self) -> impl Iterator<Item = &TradeProposal<R>> {
+        self.trade_proposals.values()
+    }
+
+    /// Checks that this team owns at least the given amounts of resources.
+    fn verify_resources(&self, amounts: &[(ResourceId, u64)]) -> WeaselResult<(), R> {
+        for (id, _) in amounts {
+            // The same resource might appear more than once.
+            let total = amounts
+                .iter()
+                .filter(|(other, _)| other == id)
+                .fold(0u64, |sum, (_, amount)| sum.saturating_add(*amount));
+            if total > self.resource(*id) {
+                return Err(WeaselError::InsufficientResources(self.id.clone(), *id));
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the given amounts of resources to this team.
+    fn add_resources(&mut self, amounts: &[(ResourceId, u64)]) {
+        for (id, amount) in amounts {
+            let current = self.resources.entry(*id).or_insert(0);
+            *current = current.saturating_add(*amount);
+        }
+    }
+
+    /// Removes the given amounts of resources from this team.
+    fn remove_resources(&mut self, amounts: &[(ResourceId, u64)]) {
+        for (id, amount) in amounts {
+            let current = self
+                .resources
+                .get_mut(id)
+                .unwrap_or_else(|| panic!("constraint violated: resource {:?} not found", id));
+            *current -= amount;
+        }
+    }
+
+    /// Sets a new id for this team.
+    pub(crate) fn set_id(&mut self, id: TeamId<R>) {
+        for proposal in self.trade_proposals.values_mut() {
+            proposal.proposer = id.clone();
+        }
+        self.id = id;
+    }
+
+    /// Updates the trade proposals addressed to a team whose id changed.
+    fn rename_trade_partner(&mut self, id: &TeamId<R>, new_id: &TeamId<R>) {
+        if let Some(mut proposal) = self.trade_proposals.remove(id) {
+            proposal.recipient = new_id.clone();
+            self.trade_proposals.insert(new_id.clone(), proposal);
+        }
+    }
+
     /// Removes a creature id from this team.
     ///
     /// # Panics
@@ -113,6 +278,9 @@ impl<R: BattleRules> Id for Team<R> {
     }
 }
 
+/// Type to uniquely identify a team-level resource, such as gold or lumber.
+pub type ResourceId = u16;
+
 /// Collection of rules to manage teams of creatures.
 pub trait TeamRules<R: BattleRules> {
     #[cfg(not(feature = "serialization"))]
@@ -122,8 +290,12 @@ pub trait TeamRules<R: BattleRules> {
     /// See [TeamId](type.TeamId.html).
     type Id: Hash + Eq + PartialOrd + Clone + Debug + Send + Serialize + for<'a> Deserialize<'a>;
 
+    #[cfg(not(feature = "serialization"))]
     /// See [Power](../power/type.Power.html).
     type Power: Id + 'static;
+    #[cfg(feature = "serialization")]
+    /// See [Power](../power/type.Power.html).
+    type Power: Id + Serialize + for<'a> Deserialize<'a> + 'static;
 
     #[cfg(not(feature = "serialization"))]
     /// See [PowersSeed](../power/type.PowersSeed.html).
@@ -146,8 +318,12 @@ pub trait TeamRules<R: BattleRules> {
     /// See [PowersAlteration](../power/type.PowersAlteration.html).
     type PowersAlteration: Clone + Debug + Send + Serialize + for<'a> Deserialize<'a>;
 
+    #[cfg(not(feature = "serialization"))]
     /// See [Objectives](type.Objectives.html).
     type Objectives: Default;
+    #[cfg(feature = "serialization")]
+    /// See [Objectives](type.Objectives.html).
+    type Objectives: Default + Serialize + for<'a> Deserialize<'a>;
 
     #[cfg(not(feature = "serialization"))]
     /// See [ObjectivesSeed](type.ObjectivesSeed.html).
@@ -156,6 +332,13 @@ pub trait TeamRules<R: BattleRules> {
     /// See [ObjectivesSeed](type.ObjectivesSeed.html).
     type ObjectivesSeed: Clone + Debug + Send + Serialize + for<'a> Deserialize<'a>;
 
+    #[cfg(not(feature = "serialization"))]
+    /// See [TeamData](type.TeamData.html).
+    type TeamData: Default;
+    #[cfg(feature = "serialization")]
+    /// See [TeamData](type.TeamData.html).
+    type TeamData: Default + Serialize + for<'a> Deserialize<'a>;
+
     /// Checks if the addition of a new entity in the given team is allowed.
     ///
     /// The provided implementation accepts any new entity.
@@ -181,6 +364,20 @@ pub trait TeamRules<R: BattleRules> {
         Box::new(std::iter::empty())
     }
 
+    /// Adapts the `current` power of a team to its `new` regenerated version.\
+    /// This method is invoked only when powers are regenerated with
+    /// `RegenerationPolicy::ClampToNew`.
+    ///
+    /// The provided implementation leaves `current` untouched.
+    fn clamp_power(&self, _current: &mut Self::Power, _new: &Self::Power) {}
+
+    /// Writes into `hasher` the data of `power` to be included in `Battle::state_digest`.
+    ///
+    /// The provided implementation writes only the power's id.
+    fn digest_power(&self, power: &Self::Power, mut hasher: &mut dyn Hasher) {
+        power.id().hash(&mut hasher);
+    }
+
     /// Returns `Ok` if `call.team` can invoke `call.power` with `call.invocation`,
     /// otherwise returns an error describing the issue preventing the invocation.\
     /// The power is guaranteed to be known by the team.
@@ -242,6 +439,20 @@ pub trait TeamRules<R: BattleRules> {
         None
     }
 
+    /// Evaluates the progress made by the team towards its objectives.
+    /// This method is called together with `check_objectives_on_event` and
+    /// `check_objectives_on_turn`. The result is stored in the team.
+    ///
+    /// The provided implementation does not report any progress.
+    fn objectives_progress(
+        &self,
+        _state: &BattleState<R>,
+        _team: &Team<R>,
+        _metrics: &ReadMetrics<R>,
+    ) -> Option<Box<dyn ObjectiveProgress>> {
+        None
+    }
+
     /// Checks if the team has completed its objectives.
     /// This check is called every time a turn ends.
     ///
@@ -257,6 +468,65 @@ pub trait TeamRules<R: BattleRules> {
     ) -> Option<Conclusion> {
         None
     }
+
+    /// Returns the `Conclusion` of a team when the battle reaches its round limit.
+    /// It's invoked only for teams without a conclusion, right before the battle ends.
+    ///
+    /// The provided implementation declares victory for the teams with the highest score
+    /// among those without a conclusion, and defeat for all the others.
+    ///
+    /// Returns the `Conclusion` for this team, or none to leave it without a conclusion.
+    fn conclusion_on_round_limit(
+        &self,
+        state: &BattleState<R>,
+        team: &Team<R>,
+        _metrics: &ReadMetrics<R>,
+    ) -> Option<Conclusion> {
+        let best = state
+            .entities()
+            .teams()
+            .filter(|team| team.conclusion().is_none())
+            .map(|team| team.score())
+            .max();
+        if Some(team.score()) == best {
+            Some(Conclusion::Victory)
+        } else {
+            Some(Conclusion::Defeat)
+        }
+    }
+
+    /// Invoked for each team when a new round begins, after the previous one
+    /// was ended by `EndRound`. It's not invoked for the first round of the battle.
+    ///
+    /// The provided implementation does nothing.
+    fn on_round_start(
+        &self,
+        _team: &mut Team<R>,
+        _event_queue: &mut Option<EventQueue<R>>,
+        _entropy: &mut Entropy<R>,
+        _metrics: &mut WriteMetrics<R>,
+    ) {
+    }
+
+    /// Invoked for each team when the current round is ended by `EndRound`.
+    ///
+    /// The provided implementation does nothing.
+    fn on_round_end(
+        &self,
+        _team: &mut Team<R>,
+        _event_queue: &mut Option<EventQueue<R>>,
+        _entropy: &mut Entropy<R>,
+        _metrics: &mut WriteMetrics<R>,
+    ) {
+    }
+
+    /// Returns the names of the optional features enabled in these team rules.
+    /// They are reported by `Battle::describe`.
+    ///
+    /// The provided implementation returns no features.
+    fn features(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
 }
 
 /// Type to drive the generation of the objectives for a given team.
@@ -269,12 +539,32 @@ pub type ObjectivesSeed<R> = <<R as BattleRules>::TR as TeamRules<R>>::Objective
 /// The objectives can be checked during the battle to know whether or not a team is victorious.
 pub type Objectives<R> = <<R as BattleRules>::TR as TeamRules<R>>::Objectives;
 
+/// Type to store additional data about a team, such as shared resources or team-wide effects.
+///
+/// The data lives only in the battle's state and it's managed by the team rules,
+/// for instance in `TeamRules::on_round_start` and `TeamRules::on_round_end`.
+pub type TeamData<R> = <<R as BattleRules>::TR as TeamRules<R>>::TeamData;
+
+/// Progress made by a team towards the completion of its objectives.
+///
+/// It lets you display the state of any kind of objectives, without knowing their actual type.
+pub trait ObjectiveProgress: Debug + Send {
+    /// Returns the progress, normalized between 0 (no progress) and 1 (completed).
+    fn value(&self) -> f32;
+
+    /// Returns a human readable description of the progress.
+    fn description(&self) -> String;
+}
+
 /// Describes the different scenarios in which an entity might be added to a team.
 pub enum EntityAddition<'a, R: BattleRules> {
     /// Spawn a new creature.
     CreatureSpawn,
     /// Take a creature from another team.
     CreatureConversion(&'a Creature<R>),
+    /// Spawn many new creatures at once, as part of a wave.
+    /// Contains the number of creatures joining the team.
+    CreatureWaveSpawn(usize),
 }
 
 /// Type to uniquely identify teams.
@@ -363,14 +653,18 @@ pub struct CreateTeam<R: BattleRules> {
         ))
     )]
     objectives_seed: Option<ObjectivesSeed<R>>,
+
+    /// Optional vector containing the initial amount of the team's resources.
+    #[cfg_attr(feature = "serialization", serde(default))]
+    resources: Option<Vec<(ResourceId, u64)>>,
 }
 
 impl<R: BattleRules> Debug for CreateTeam<R> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         write!(
             f,
-            "CreateTeam {{ id: {:?}, relations: {:?}, objectives_seed: {:?} }}",
-            self.id, self.relations, self.objectives_seed
+            "CreateTeam {{ id: {:?}, relations: {:?}, objectives_seed: {:?}, resources: {:?} }}",
+            self.id, self.relations, self.objectives_seed, self.resources
         )
     }
 }
@@ -382,6 +676,7 @@ impl<R: BattleRules> Clone for CreateTeam<R> {
             relations: self.relations.clone(),
             powers_seed: self.powers_seed.clone(),
             objectives_seed: self.objectives_seed.clone(),
+            resources: self.resources.clone(),
         }
     }
 }
@@ -398,6 +693,7 @@ impl<R: BattleRules> CreateTeam<R> {
             relations: None,
             powers_seed: None,
             objectives_seed: None,
+            resources: None,
         }
     }
 
@@ -420,6 +716,11 @@ impl<R: BattleRules> CreateTeam<R> {
     pub fn objectives_seed(&self) -> &Option<ObjectivesSeed<R>> {
         &self.objectives_seed
     }
+
+    /// Returns the initial resources of the new team.
+    pub fn resources(&self) -> &Option<Vec<(ResourceId, u64)>> {
+        &self.resources
+    }
 }
 
 impl<R: BattleRules + 'static> Event<R> for CreateTeam<R> {
@@ -448,23 +749,36 @@ impl<R: BattleRules + 'static> Event<R> for CreateTeam<R> {
     }
 
     fn apply(&self, battle: &mut Battle<R>, _: &mut Option<EventQueue<R>>) {
+        let watcher = battle.apply_watcher();
         // Powers' generation is influenced by the given powers_seed, if present.
-        let it = battle.rules.team_rules().generate_powers(
-            &self.powers_seed,
-            &mut battle.entropy,
-            &mut battle.metrics.write_handle(),
+        let powers = watch!(
+            watcher,
+            "TeamRules::generate_powers",
+            collect_from_iter(battle.rules.team_rules().generate_powers(
+                &self.powers_seed,
+                &mut battle.entropy,
+                &mut battle.metrics.write_handle(),
+            ))
         );
-        let powers = collect_from_iter(it);
         // Insert the new team.
         battle.state.entities.add_team(Team {
             id: self.id.clone(),
             creatures: Vec::new(),
             powers,
             conclusion: None,
-            objectives: battle
-                .rules
-                .team_rules()
-                .generate_objectives(&self.objectives_seed),
+            objectives: watch!(
+                watcher,
+                "TeamRules::generate_objectives",
+                battle
+                    .rules
+                    .team_rules()
+                    .generate_objectives(&self.objectives_seed)
+            ),
+            progress: None,
+            score: 0,
+            resources: self.resources.iter().flatten().copied().collect(),
+            trade_proposals: IndexMap::new(),
+            data: TeamData::<R>::default(),
         });
         // Unpack explicit relations into a vector.
         let mut relations = if let Some(relations) = &self.relations {
@@ -523,8 +837,9 @@ where
     processor: &'a mut P,
     id: TeamId<R>,
     relations: Option<Vec<(TeamId<R>, Relation)>>,
-    powers_seed: Option<PowersSeed<R>>,
-    objectives_seed: Option<ObjectivesSeed<R>>,
+    pub(crate) powers_seed: Option<PowersSeed<R>>,
+    pub(crate) objectives_seed: Option<ObjectivesSeed<R>>,
+    pub(crate) resources: Option<Vec<(ResourceId, u64)>>,
 }
 
 impl<'a, R, P> CreateTeamTrigger<'a, R, P>
@@ -549,6 +864,12 @@ where
         self.objectives_seed = Some(seed);
         self
     }
+
+    /// Sets the initial amount of this team resources.
+    pub fn resources(&'a mut self, resources: &[(ResourceId, u64)]) -> &'a mut Self {
+        self.resources = Some(resources.into());
+        self
+    }
 }
 
 impl<'a, R, P> EventTrigger<'a, R, P> for CreateTeamTrigger<'a, R, P>
@@ -567,6 +888,7 @@ where
             relations: self.relations.clone(),
             powers_seed: self.powers_seed.clone(),
             objectives_seed: self.objectives_seed.clone(),
+            resources: self.resources.clone(),
         })
     }
 }
@@ -583,10 +905,49 @@ pub enum Relation {
     Kin,
 }
 
+/// Constraint on the relation between an entity and the target of its actions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum TargetRelation {
+    /// The target must be an enemy.
+    Enemy,
+    /// The target must be an ally or belong to the same team.
+    Ally,
+    /// Any target is accepted.
+    Any,
+}
+
+impl TargetRelation {
+    /// Returns true if `relation` satisfies this constraint.
+    pub fn allows(self, relation: Option<Relation>) -> bool {
+        match self {
+            Self::Enemy => relation == Some(Relation::Enemy),
+            Self::Ally => matches!(relation, Some(Relation::Ally) | Some(Relation::Kin)),
+            Self::Any => true,
+        }
+    }
+}
+
 /// A pair of two teams that are part of a relationship.
 #[derive(Clone)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub(crate) struct RelationshipPair<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "TeamId<R>: Serialize",
+            deserialize = "TeamId<R>: Deserialize<'de>"
+        ))
+    )]
     pub(crate) first: TeamId<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "TeamId<R>: Serialize",
+            deserialize = "TeamId<R>: Deserialize<'de>"
+        ))
+    )]
     pub(crate) second: TeamId<R>,
 }
 
@@ -780,7 +1141,7 @@ where
 
 /// All possible conclusions for a team's objectives.
 /// In other words, this tells if the team reached its objectives or failed.
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub enum Conclusion {
     /// Team achieved its objectives.
@@ -1024,13 +1385,19 @@ impl<R: BattleRules + 'static> Event<R> for ResetObjectives<R> {
     }
 
     fn apply(&self, battle: &mut Battle<R>, _: &mut Option<EventQueue<R>>) {
+        let watcher = battle.apply_watcher();
         // Regenerate the team's objectives.
         let team = battle
             .state
             .entities
             .team_mut(&self.id)
             .unwrap_or_else(|| panic!("constraint violated: team {:?} not found", self.id));
-        team.objectives = battle.rules.team_rules().generate_objectives(&self.seed);
+        team.objectives = watch!(
+            watcher,
+            "TeamRules::generate_objectives",
+            battle.rules.team_rules().generate_objectives(&self.seed)
+        );
+        team.progress = None;
         // Reset the team's conclusion.
         team.conclusion = None;
     }
@@ -1089,14 +1456,17 @@ where
     }
 }
 
-/// Event to remove a team from a battle.
-/// Teams can be removed only if they are empty.
+/// Event to add points to the score of a team.
+/// Negative amounts subtract points.
+///
+/// Teams' scores can be queried in descending order through
+/// [leaderboard](../entity/struct.Entities.html#method.leaderboard).
 ///
 /// # Examples
 /// ```
 /// use weasel::{
-///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateTeam,
-///     EventTrigger, RemoveTeam, Server,
+///     battle_rules, rules::empty::*, AddScore, Battle, BattleController, BattleRules,
+///     CreateTeam, EventTrigger, Server,
 /// };
 ///
 /// battle_rules! {}
@@ -1107,11 +1477,11 @@ where
 /// let team_id = 1;
 /// CreateTeam::trigger(&mut server, team_id).fire().unwrap();
 ///
-/// RemoveTeam::trigger(&mut server, team_id).fire().unwrap();
-/// assert_eq!(server.battle().entities().teams().count(), 0);
+/// AddScore::trigger(&mut server, team_id, 10).fire().unwrap();
+/// assert_eq!(server.battle().entities().team(&team_id).unwrap().score(), 10);
 /// ```
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
-pub struct RemoveTeam<R: BattleRules> {
+pub struct AddScore<R: BattleRules> {
     #[cfg_attr(
         feature = "serialization",
         serde(bound(
@@ -1120,64 +1490,75 @@ pub struct RemoveTeam<R: BattleRules> {
         ))
     )]
     id: TeamId<R>,
+
+    amount: i64,
 }
 
-impl<R: BattleRules> RemoveTeam<R> {
+impl<R: BattleRules> AddScore<R> {
     /// Returns a trigger for this event.
-    pub fn trigger<P: EventProcessor<R>>(
-        processor: &mut P,
+    pub fn trigger<'a, P: EventProcessor<R>>(
+        processor: &'a mut P,
         id: TeamId<R>,
-    ) -> RemoveTeamTrigger<R, P> {
-        RemoveTeamTrigger { processor, id }
+        amount: i64,
+    ) -> AddScoreTrigger<'a, R, P> {
+        AddScoreTrigger {
+            processor,
+            id,
+            amount,
+        }
     }
 
-    /// Returns the id of the team to be removed.
+    /// Returns the team id.
     pub fn id(&self) -> &TeamId<R> {
         &self.id
     }
+
+    /// Returns the amount of points to add.
+    pub fn amount(&self) -> i64 {
+        self.amount
+    }
 }
 
-impl<R: BattleRules> Debug for RemoveTeam<R> {
+impl<R: BattleRules> Debug for AddScore<R> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        write!(f, "RemoveTeam {{ id: {:?} }}", self.id)
+        write!(
+            f,
+            "AddScore {{ id: {:?}, amount: {:?} }}",
+            self.id, self.amount
+        )
     }
 }
 
-impl<R: BattleRules> Clone for RemoveTeam<R> {
+impl<R: BattleRules> Clone for AddScore<R> {
     fn clone(&self) -> Self {
         Self {
             id: self.id.clone(),
+            amount: self.amount,
         }
     }
 }
 
-impl<R: BattleRules + 'static> Event<R> for RemoveTeam<R> {
+impl<R: BattleRules + 'static> Event<R> for AddScore<R> {
     fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
         // Team must exist.
-        if let Some(team) = battle.entities().team(&self.id) {
-            // Team must not have any creature.
-            if team.creatures().peekable().peek().is_some() {
-                return Err(WeaselError::TeamNotEmpty(self.id.clone()));
-            }
-            Ok(())
-        } else {
-            Err(WeaselError::TeamNotFound(self.id.clone()))
+        if battle.entities().team(&self.id).is_none() {
+            return Err(WeaselError::TeamNotFound(self.id.clone()));
         }
+        Ok(())
     }
 
     fn apply(&self, battle: &mut Battle<R>, _: &mut Option<EventQueue<R>>) {
-        // Remove the team.
-        battle
+        // Update the team's score.
+        let team = battle
             .state
             .entities
-            .remove_team(&self.id)
-            .unwrap_or_else(|err| panic!("constraint violated: {:?}", err));
-        // Remove rights of players towards this team.
-        battle.rights_mut().remove_team(&self.id);
+            .team_mut(&self.id)
+            .unwrap_or_else(|| panic!("constraint violated: team {:?} not found", self.id));
+        team.score = team.score.saturating_add(self.amount);
     }
 
     fn kind(&self) -> EventKind {
-        EventKind::RemoveTeam
+        EventKind::AddScore
     }
 
     fn box_clone(&self) -> Box<dyn Event<R> + Send> {
@@ -1189,17 +1570,18 @@ impl<R: BattleRules + 'static> Event<R> for RemoveTeam<R> {
     }
 }
 
-/// Trigger to build and fire a `RemoveTeam` event.
-pub struct RemoveTeamTrigger<'a, R, P>
+/// Trigger to build and fire an `AddScore` event.
+pub struct AddScoreTrigger<'a, R, P>
 where
     R: BattleRules,
     P: EventProcessor<R>,
 {
     processor: &'a mut P,
     id: TeamId<R>,
+    amount: i64,
 }
 
-impl<'a, R, P> EventTrigger<'a, R, P> for RemoveTeamTrigger<'a, R, P>
+impl<'a, R, P> EventTrigger<'a, R, P> for AddScoreTrigger<'a, R, P>
 where
     R: BattleRules + 'static,
     P: EventProcessor<R>,
@@ -1208,21 +1590,25 @@ where
         self.processor
     }
 
-    /// Returns a `RemoveTeam` event.
+    /// Returns an `AddScore` event.
     fn event(&self) -> Box<dyn Event<R> + Send> {
-        Box::new(RemoveTeam {
+        Box::new(AddScore {
             id: self.id.clone(),
+            amount: self.amount,
         })
     }
 }
 
-/// An event to alter the powers of a team.
+/// Event to transfer some amount of a resource from a team to another.
+///
+/// Only the giving team has to consent to the transfer, thus this event is suited for
+/// gifts and tributes. Use [TradeProposal](struct.TradeProposal.html) for exchanges.
 ///
 /// # Examples
 /// ```
 /// use weasel::{
-///     battle_rules, rules::empty::*, AlterPowers, Battle, BattleController, BattleRules,
-///     CreateTeam, EventKind, EventTrigger, Server,
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateTeam,
+///     EventTrigger, Server, TransferResource,
 /// };
 ///
 /// battle_rules! {}
@@ -1230,20 +1616,21 @@ where
 /// let battle = Battle::builder(CustomRules::new()).build();
 /// let mut server = Server::builder(battle).build();
 ///
-/// let team_id = 1;
-/// CreateTeam::trigger(&mut server, team_id).fire().unwrap();
+/// const GOLD: u16 = 0;
+/// CreateTeam::trigger(&mut server, 1)
+///     .resources(&[(GOLD, 10)])
+///     .fire()
+///     .unwrap();
+/// CreateTeam::trigger(&mut server, 2).fire().unwrap();
 ///
-/// let alteration = ();
-/// AlterPowers::trigger(&mut server, team_id, alteration)
+/// TransferResource::trigger(&mut server, 1, 2, GOLD, 4)
 ///     .fire()
 ///     .unwrap();
-/// assert_eq!(
-///     server.battle().history().events().iter().last().unwrap().kind(),
-///     EventKind::AlterPowers
-/// );
+/// assert_eq!(server.battle().entities().team(&1).unwrap().resource(GOLD), 6);
+/// assert_eq!(server.battle().entities().team(&2).unwrap().resource(GOLD), 4);
 /// ```
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
-pub struct AlterPowers<R: BattleRules> {
+pub struct TransferResource<R: BattleRules> {
     #[cfg_attr(
         feature = "serialization",
         serde(bound(
@@ -1251,29 +1638,805 @@ pub struct AlterPowers<R: BattleRules> {
             deserialize = "TeamId<R>: Deserialize<'de>"
         ))
     )]
-    id: TeamId<R>,
+    from: TeamId<R>,
 
     #[cfg_attr(
         feature = "serialization",
         serde(bound(
-            serialize = "PowersAlteration<R>: Serialize",
-            deserialize = "PowersAlteration<R>: Deserialize<'de>"
+            serialize = "TeamId<R>: Serialize",
+            deserialize = "TeamId<R>: Deserialize<'de>"
         ))
     )]
-    alteration: PowersAlteration<R>,
+    to: TeamId<R>,
+
+    resource: ResourceId,
+
+    amount: u64,
 }
 
-impl<R: BattleRules> AlterPowers<R> {
+impl<R: BattleRules> TransferResource<R> {
     /// Returns a trigger for this event.
     pub fn trigger<'a, P: EventProcessor<R>>(
         processor: &'a mut P,
-        id: TeamId<R>,
-        alteration: PowersAlteration<R>,
-    ) -> AlterPowersTrigger<'a, R, P> {
-        AlterPowersTrigger {
+        from: TeamId<R>,
+        to: TeamId<R>,
+        resource: ResourceId,
+        amount: u64,
+    ) -> TransferResourceTrigger<'a, R, P> {
+        TransferResourceTrigger {
             processor,
-            id,
-            alteration,
+            from,
+            to,
+            resource,
+            amount,
+        }
+    }
+
+    /// Returns the id of the team giving the resource.
+    pub fn from(&self) -> &TeamId<R> {
+        &self.from
+    }
+
+    /// Returns the id of the team receiving the resource.
+    pub fn to(&self) -> &TeamId<R> {
+        &self.to
+    }
+
+    /// Returns the id of the transferred resource.
+    pub fn resource(&self) -> ResourceId {
+        self.resource
+    }
+
+    /// Returns the transferred amount.
+    pub fn amount(&self) -> u64 {
+        self.amount
+    }
+}
+
+impl<R: BattleRules> Debug for TransferResource<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "TransferResource {{ from: {:?}, to: {:?}, resource: {:?}, amount: {:?} }}",
+            self.from, self.to, self.resource, self.amount
+        )
+    }
+}
+
+impl<R: BattleRules> Clone for TransferResource<R> {
+    fn clone(&self) -> Self {
+        Self {
+            from: self.from.clone(),
+            to: self.to.clone(),
+            resource: self.resource,
+            amount: self.amount,
+        }
+    }
+}
+
+impl<R: BattleRules + 'static> Event<R> for TransferResource<R> {
+    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
+        // Receiving team must exist.
+        if battle.entities().team(&self.to).is_none() {
+            return Err(WeaselError::TeamNotFound(self.to.clone()));
+        }
+        // Teams must be different.
+        if self.from == self.to {
+            return Err(WeaselError::SelfTrade(self.from.clone()));
+        }
+        // Giving team must exist and own enough of the resource.
+        battle
+            .entities()
+            .team(&self.from)
+            .ok_or_else(|| WeaselError::TeamNotFound(self.from.clone()))?
+            .verify_resources(&[(self.resource, self.amount)])
+    }
+
+    fn apply(&self, battle: &mut Battle<R>, _: &mut Option<EventQueue<R>>) {
+        let amounts = [(self.resource, self.amount)];
+        move_resources(battle, &self.from, &self.to, &amounts);
+    }
+
+    fn kind(&self) -> EventKind {
+        EventKind::TransferResource
+    }
+
+    fn box_clone(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn rights<'a>(&'a self, _battle: &'a Battle<R>) -> EventRights<'a, R> {
+        EventRights::Team(&self.from)
+    }
+}
+
+/// Trigger to build and fire a `TransferResource` event.
+pub struct TransferResourceTrigger<'a, R, P>
+where
+    R: BattleRules,
+    P: EventProcessor<R>,
+{
+    processor: &'a mut P,
+    from: TeamId<R>,
+    to: TeamId<R>,
+    resource: ResourceId,
+    amount: u64,
+}
+
+impl<'a, R, P> EventTrigger<'a, R, P> for TransferResourceTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    fn processor(&'a mut self) -> &'a mut P {
+        self.processor
+    }
+
+    /// Returns a `TransferResource` event.
+    fn event(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(TransferResource {
+            from: self.from.clone(),
+            to: self.to.clone(),
+            resource: self.resource,
+            amount: self.amount,
+        })
+    }
+}
+
+/// Moves the given amounts of resources from a team to another.
+fn move_resources<R: BattleRules>(
+    battle: &mut Battle<R>,
+    from: &TeamId<R>,
+    to: &TeamId<R>,
+    amounts: &[(ResourceId, u64)],
+) {
+    battle
+        .state
+        .entities
+        .team_mut(from)
+        .unwrap_or_else(|| panic!("constraint violated: team {:?} not found", from))
+        .remove_resources(amounts);
+    battle
+        .state
+        .entities
+        .team_mut(to)
+        .unwrap_or_else(|| panic!("constraint violated: team {:?} not found", to))
+        .add_resources(amounts);
+}
+
+/// Event to propose an exchange of resources to another team.
+///
+/// The proposal is stored in the proposing team until the recipient answers with
+/// a `TradeProposal` mirroring it exactly, that is offering what was requested and
+/// requesting what was offered. At that moment the trade is concluded and the resources
+/// are exchanged. Since each team must have rights to its own proposal, a trade
+/// requires the consent of both sides.
+///
+/// A new proposal replaces the previous one made to the same recipient.
+/// A proposal with no offer and no request withdraws the pending one.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateTeam,
+///     EventTrigger, Server, TradeProposal,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// const GOLD: u16 = 0;
+/// const WOOD: u16 = 1;
+/// CreateTeam::trigger(&mut server, 1)
+///     .resources(&[(GOLD, 10)])
+///     .fire()
+///     .unwrap();
+/// CreateTeam::trigger(&mut server, 2)
+///     .resources(&[(WOOD, 5)])
+///     .fire()
+///     .unwrap();
+///
+/// TradeProposal::trigger(&mut server, 1, 2)
+///     .offer(&[(GOLD, 3)])
+///     .request(&[(WOOD, 5)])
+///     .fire()
+///     .unwrap();
+/// assert!(server.battle().entities().team(&1).unwrap().trade_proposal(&2).is_some());
+///
+/// TradeProposal::trigger(&mut server, 2, 1)
+///     .offer(&[(WOOD, 5)])
+///     .request(&[(GOLD, 3)])
+///     .fire()
+///     .unwrap();
+/// assert_eq!(server.battle().entities().team(&1).unwrap().resource(WOOD), 5);
+/// assert_eq!(server.battle().entities().team(&2).unwrap().resource(GOLD), 3);
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct TradeProposal<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "TeamId<R>: Serialize",
+            deserialize = "TeamId<R>: Deserialize<'de>"
+        ))
+    )]
+    proposer: TeamId<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "TeamId<R>: Serialize",
+            deserialize = "TeamId<R>: Deserialize<'de>"
+        ))
+    )]
+    recipient: TeamId<R>,
+
+    offer: Vec<(ResourceId, u64)>,
+
+    request: Vec<(ResourceId, u64)>,
+}
+
+impl<R: BattleRules> TradeProposal<R> {
+    /// Returns a trigger for this event.
+    pub fn trigger<'a, P: EventProcessor<R>>(
+        processor: &'a mut P,
+        proposer: TeamId<R>,
+        recipient: TeamId<R>,
+    ) -> TradeProposalTrigger<'a, R, P> {
+        TradeProposalTrigger {
+            processor,
+            proposer,
+            recipient,
+            offer: Vec::new(),
+            request: Vec::new(),
+        }
+    }
+
+    /// Returns the id of the team making the proposal.
+    pub fn proposer(&self) -> &TeamId<R> {
+        &self.proposer
+    }
+
+    /// Returns the id of the team receiving the proposal.
+    pub fn recipient(&self) -> &TeamId<R> {
+        &self.recipient
+    }
+
+    /// Returns the resources offered by the proposer.
+    pub fn offer(&self) -> &Vec<(ResourceId, u64)> {
+        &self.offer
+    }
+
+    /// Returns the resources requested to the recipient.
+    pub fn request(&self) -> &Vec<(ResourceId, u64)> {
+        &self.request
+    }
+
+    /// Returns whether this proposal withdraws the pending one.
+    fn is_withdrawal(&self) -> bool {
+        self.offer.is_empty() && self.request.is_empty()
+    }
+
+    /// Returns whether `other` is the exact counterpart of this proposal.
+    fn mirrors(&self, other: &TradeProposal<R>) -> bool {
+        self.proposer == other.recipient
+            && self.recipient == other.proposer
+            && self.offer == other.request
+            && self.request == other.offer
+    }
+}
+
+impl<R: BattleRules + 'static> TradeProposal<R> {
+    /// Returns the pending proposal of the recipient that this proposal concludes, if any.
+    fn counterpart<'a>(&self, battle: &'a Battle<R>) -> Option<&'a TradeProposal<R>> {
+        battle
+            .entities()
+            .team(&self.recipient)
+            .and_then(|team| team.trade_proposal(&self.proposer))
+            .filter(|proposal| proposal.mirrors(self))
+    }
+}
+
+impl<R: BattleRules> Debug for TradeProposal<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "TradeProposal {{ proposer: {:?}, recipient: {:?}, offer: {:?}, request: {:?} }}",
+            self.proposer, self.recipient, self.offer, self.request
+        )
+    }
+}
+
+impl<R: BattleRules> Clone for TradeProposal<R> {
+    fn clone(&self) -> Self {
+        Self {
+            proposer: self.proposer.clone(),
+            recipient: self.recipient.clone(),
+            offer: self.offer.clone(),
+            request: self.request.clone(),
+        }
+    }
+}
+
+impl<R: BattleRules + 'static> Event<R> for TradeProposal<R> {
+    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
+        // Both teams must exist.
+        let proposer = battle
+            .entities()
+            .team(&self.proposer)
+            .ok_or_else(|| WeaselError::TeamNotFound(self.proposer.clone()))?;
+        let recipient = battle
+            .entities()
+            .team(&self.recipient)
+            .ok_or_else(|| WeaselError::TeamNotFound(self.recipient.clone()))?;
+        // Teams must be different.
+        if self.proposer == self.recipient {
+            return Err(WeaselError::SelfTrade(self.proposer.clone()));
+        }
+        // The proposer must own what it offers.
+        proposer.verify_resources(&self.offer)?;
+        // If the trade is concluded, the recipient must own what is requested.
+        if self.counterpart(battle).is_some() {
+            recipient.verify_resources(&self.request)?;
+        }
+        Ok(())
+    }
+
+    fn apply(&self, battle: &mut Battle<R>, _: &mut Option<EventQueue<R>>) {
+        let concluded = self.counterpart(battle).is_some();
+        let proposer = battle
+            .state
+            .entities
+            .team_mut(&self.proposer)
+            .unwrap_or_else(|| panic!("constraint violated: team {:?} not found", self.proposer));
+        proposer.trade_proposals.shift_remove(&self.recipient);
+        if concluded {
+            // Exchange the resources and remove the recipient's proposal.
+            move_resources(battle, &self.proposer, &self.recipient, &self.offer);
+            move_resources(battle, &self.recipient, &self.proposer, &self.request);
+            battle
+                .state
+                .entities
+                .team_mut(&self.recipient)
+                .unwrap_or_else(|| {
+                    panic!("constraint violated: team {:?} not found", self.recipient)
+                })
+                .trade_proposals
+                .shift_remove(&self.proposer);
+        } else if !self.is_withdrawal() {
+            proposer
+                .trade_proposals
+                .insert(self.recipient.clone(), self.clone());
+        }
+    }
+
+    fn kind(&self) -> EventKind {
+        EventKind::TradeProposal
+    }
+
+    fn box_clone(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn rights<'a>(&'a self, _battle: &'a Battle<R>) -> EventRights<'a, R> {
+        EventRights::Team(&self.proposer)
+    }
+}
+
+/// Trigger to build and fire a `TradeProposal` event.
+pub struct TradeProposalTrigger<'a, R, P>
+where
+    R: BattleRules,
+    P: EventProcessor<R>,
+{
+    processor: &'a mut P,
+    proposer: TeamId<R>,
+    recipient: TeamId<R>,
+    offer: Vec<(ResourceId, u64)>,
+    request: Vec<(ResourceId, u64)>,
+}
+
+impl<'a, R, P> TradeProposalTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    /// Sets the resources offered by the proposer.
+    pub fn offer(&'a mut self, offer: &[(ResourceId, u64)]) -> &'a mut Self {
+        self.offer = offer.into();
+        self
+    }
+
+    /// Sets the resources requested to the recipient.
+    pub fn request(&'a mut self, request: &[(ResourceId, u64)]) -> &'a mut Self {
+        self.request = request.into();
+        self
+    }
+}
+
+impl<'a, R, P> EventTrigger<'a, R, P> for TradeProposalTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    fn processor(&'a mut self) -> &'a mut P {
+        self.processor
+    }
+
+    /// Returns a `TradeProposal` event.
+    fn event(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(TradeProposal {
+            proposer: self.proposer.clone(),
+            recipient: self.recipient.clone(),
+            offer: self.offer.clone(),
+            request: self.request.clone(),
+        })
+    }
+}
+
+/// Event to remove a team from a battle.
+/// Teams can be removed only if they are empty.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateTeam,
+///     EventTrigger, RemoveTeam, Server,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// let team_id = 1;
+/// CreateTeam::trigger(&mut server, team_id).fire().unwrap();
+///
+/// RemoveTeam::trigger(&mut server, team_id).fire().unwrap();
+/// assert_eq!(server.battle().entities().teams().count(), 0);
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct RemoveTeam<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "TeamId<R>: Serialize",
+            deserialize = "TeamId<R>: Deserialize<'de>"
+        ))
+    )]
+    id: TeamId<R>,
+}
+
+impl<R: BattleRules> RemoveTeam<R> {
+    /// Returns a trigger for this event.
+    pub fn trigger<P: EventProcessor<R>>(
+        processor: &mut P,
+        id: TeamId<R>,
+    ) -> RemoveTeamTrigger<R, P> {
+        RemoveTeamTrigger { processor, id }
+    }
+
+    /// Returns the id of the team to be removed.
+    pub fn id(&self) -> &TeamId<R> {
+        &self.id
+    }
+}
+
+impl<R: BattleRules> Debug for RemoveTeam<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "RemoveTeam {{ id: {:?} }}", self.id)
+    }
+}
+
+impl<R: BattleRules> Clone for RemoveTeam<R> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+        }
+    }
+}
+
+impl<R: BattleRules + 'static> Event<R> for RemoveTeam<R> {
+    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
+        // Team must exist.
+        if let Some(team) = battle.entities().team(&self.id) {
+            // Team must not have any creature.
+            if team.creatures().peekable().peek().is_some() {
+                return Err(WeaselError::TeamNotEmpty(self.id.clone()));
+            }
+            Ok(())
+        } else {
+            Err(WeaselError::TeamNotFound(self.id.clone()))
+        }
+    }
+
+    fn apply(&self, battle: &mut Battle<R>, _: &mut Option<EventQueue<R>>) {
+        // Remove the team.
+        battle
+            .state
+            .entities
+            .remove_team(&self.id)
+            .unwrap_or_else(|err| panic!("constraint violated: {:?}", err));
+        // Remove rights of players towards this team.
+        battle.rights_mut().remove_team(&self.id);
+        // Forget what the team could see.
+        battle.state.visibility.remove_team(&self.id);
+        // Drop the trade proposals addressed to this team.
+        for team in battle.state.entities.teams_mut() {
+            team.trade_proposals.shift_remove(&self.id);
+        }
+    }
+
+    fn kind(&self) -> EventKind {
+        EventKind::RemoveTeam
+    }
+
+    fn box_clone(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Trigger to build and fire a `RemoveTeam` event.
+pub struct RemoveTeamTrigger<'a, R, P>
+where
+    R: BattleRules,
+    P: EventProcessor<R>,
+{
+    processor: &'a mut P,
+    id: TeamId<R>,
+}
+
+impl<'a, R, P> EventTrigger<'a, R, P> for RemoveTeamTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    fn processor(&'a mut self) -> &'a mut P {
+        self.processor
+    }
+
+    /// Returns a `RemoveTeam` event.
+    fn event(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(RemoveTeam {
+            id: self.id.clone(),
+        })
+    }
+}
+
+/// Event to change the id of a team.
+///
+/// All references to the team are updated: the team id of its creatures,
+/// the relations with other teams and the players' rights.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateTeam,
+///     EventTrigger, RenameTeam, Server,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// let team_id = 1;
+/// CreateTeam::trigger(&mut server, team_id).fire().unwrap();
+///
+/// let new_team_id = 2;
+/// RenameTeam::trigger(&mut server, team_id, new_team_id)
+///     .fire()
+///     .unwrap();
+/// assert!(server.battle().entities().team(&team_id).is_none());
+/// assert!(server.battle().entities().team(&new_team_id).is_some());
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct RenameTeam<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "TeamId<R>: Serialize",
+            deserialize = "TeamId<R>: Deserialize<'de>"
+        ))
+    )]
+    id: TeamId<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "TeamId<R>: Serialize",
+            deserialize = "TeamId<R>: Deserialize<'de>"
+        ))
+    )]
+    new_id: TeamId<R>,
+}
+
+impl<R: BattleRules> RenameTeam<R> {
+    /// Returns a trigger for this event.
+    pub fn trigger<P: EventProcessor<R>>(
+        processor: &mut P,
+        id: TeamId<R>,
+        new_id: TeamId<R>,
+    ) -> RenameTeamTrigger<R, P> {
+        RenameTeamTrigger {
+            processor,
+            id,
+            new_id,
+        }
+    }
+
+    /// Returns the current id of the team.
+    pub fn id(&self) -> &TeamId<R> {
+        &self.id
+    }
+
+    /// Returns the new id of the team.
+    pub fn new_id(&self) -> &TeamId<R> {
+        &self.new_id
+    }
+}
+
+impl<R: BattleRules> Debug for RenameTeam<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "RenameTeam {{ id: {:?}, new_id: {:?} }}",
+            self.id, self.new_id
+        )
+    }
+}
+
+impl<R: BattleRules> Clone for RenameTeam<R> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+            new_id: self.new_id.clone(),
+        }
+    }
+}
+
+impl<R: BattleRules + 'static> Event<R> for RenameTeam<R> {
+    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
+        // Team must exist.
+        if battle.entities().team(&self.id).is_none() {
+            return Err(WeaselError::TeamNotFound(self.id.clone()));
+        }
+        // New id must not be taken by another team.
+        if battle.entities().team(&self.new_id).is_some() {
+            return Err(WeaselError::DuplicatedTeam(self.new_id.clone()));
+        }
+        Ok(())
+    }
+
+    fn apply(&self, battle: &mut Battle<R>, _: &mut Option<EventQueue<R>>) {
+        // Change the team's id in the entities.
+        battle
+            .state
+            .entities
+            .rename_team(&self.id, &self.new_id)
+            .unwrap_or_else(|err| panic!("constraint violated: {:?}", err));
+        // Transfer the rights of players to the new id.
+        battle.rights_mut().rename_team(&self.id, &self.new_id);
+        battle.state.visibility.rename_team(&self.id, &self.new_id);
+        for team in battle.state.entities.teams_mut() {
+            team.rename_trade_partner(&self.id, &self.new_id);
+        }
+    }
+
+    fn kind(&self) -> EventKind {
+        EventKind::RenameTeam
+    }
+
+    fn box_clone(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Trigger to build and fire a `RenameTeam` event.
+pub struct RenameTeamTrigger<'a, R, P>
+where
+    R: BattleRules,
+    P: EventProcessor<R>,
+{
+    processor: &'a mut P,
+    id: TeamId<R>,
+    new_id: TeamId<R>,
+}
+
+impl<'a, R, P> EventTrigger<'a, R, P> for RenameTeamTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    fn processor(&'a mut self) -> &'a mut P {
+        self.processor
+    }
+
+    /// Returns a `RenameTeam` event.
+    fn event(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(RenameTeam {
+            id: self.id.clone(),
+            new_id: self.new_id.clone(),
+        })
+    }
+}
+
+/// An event to alter the powers of a team.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, rules::empty::*, AlterPowers, Battle, BattleController, BattleRules,
+///     CreateTeam, EventKind, EventTrigger, Server,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// let team_id = 1;
+/// CreateTeam::trigger(&mut server, team_id).fire().unwrap();
+///
+/// let alteration = ();
+/// AlterPowers::trigger(&mut server, team_id, alteration)
+///     .fire()
+///     .unwrap();
+/// assert_eq!(
+///     server.battle().history().events().iter().last().unwrap().kind(),
+///     EventKind::AlterPowers
+/// );
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct AlterPowers<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "TeamId<R>: Serialize",
+            deserialize = "TeamId<R>: Deserialize<'de>"
+        ))
+    )]
+    id: TeamId<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "PowersAlteration<R>: Serialize",
+            deserialize = "PowersAlteration<R>: Deserialize<'de>"
+        ))
+    )]
+    alteration: PowersAlteration<R>,
+}
+
+impl<R: BattleRules> AlterPowers<R> {
+    /// Returns a trigger for this event.
+    pub fn trigger<'a, P: EventProcessor<R>>(
+        processor: &'a mut P,
+        id: TeamId<R>,
+        alteration: PowersAlteration<R>,
+    ) -> AlterPowersTrigger<'a, R, P> {
+        AlterPowersTrigger {
+            processor,
+            id,
+            alteration,
         }
     }
 
@@ -1318,6 +2481,7 @@ impl<R: BattleRules + 'static> Event<R> for AlterPowers<R> {
     }
 
     fn apply(&self, battle: &mut Battle<R>, _: &mut Option<EventQueue<R>>) {
+        let watcher = battle.apply_watcher();
         // Retrieve the team.
         let team = battle
             .state
@@ -1325,11 +2489,15 @@ impl<R: BattleRules + 'static> Event<R> for AlterPowers<R> {
             .team_mut(&self.id)
             .unwrap_or_else(|| panic!("constraint violated: team {:?} not found", self.id));
         // Alter the team.
-        battle.rules.team_rules().alter_powers(
-            team,
-            &self.alteration,
-            &mut battle.entropy,
-            &mut battle.metrics.write_handle(),
+        watch!(
+            watcher,
+            "TeamRules::alter_powers",
+            battle.rules.team_rules().alter_powers(
+                team,
+                &self.alteration,
+                &mut battle.entropy,
+                &mut battle.metrics.write_handle(),
+            )
         );
     }
 
@@ -1378,7 +2546,8 @@ where
 /// An event to regenerate the powers of a team.
 ///
 /// A new set of powers is created from a seed.\
-/// - Powers already present in the team won't be modified.
+/// - Powers already present in the team are handled according to the
+///   `RegenerationPolicy` (by default, they won't be modified).
 /// - Powers that the team didn't have before will be added.
 /// - Current team's powers that are not present in the new set will be removed
 ///   from the team.
@@ -1425,6 +2594,9 @@ pub struct RegeneratePowers<R: BattleRules> {
         ))
     )]
     seed: Option<PowersSeed<R>>,
+
+    #[cfg_attr(feature = "serialization", serde(default))]
+    policy: RegenerationPolicy,
 }
 
 impl<R: BattleRules> RegeneratePowers<R> {
@@ -1437,6 +2609,7 @@ impl<R: BattleRules> RegeneratePowers<R> {
             processor,
             id,
             seed: None,
+            policy: RegenerationPolicy::default(),
         }
     }
 
@@ -1449,14 +2622,19 @@ impl<R: BattleRules> RegeneratePowers<R> {
     pub fn seed(&self) -> &Option<PowersSeed<R>> {
         &self.seed
     }
+
+    /// Returns the policy applied to the powers already possessed by the team.
+    pub fn policy(&self) -> RegenerationPolicy {
+        self.policy
+    }
 }
 
 impl<R: BattleRules> Debug for RegeneratePowers<R> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         write!(
             f,
-            "RegeneratePowers {{ id: {:?}, seed: {:?} }}",
-            self.id, self.seed
+            "RegeneratePowers {{ id: {:?}, seed: {:?}, policy: {:?} }}",
+            self.id, self.seed, self.policy
         )
     }
 }
@@ -1466,6 +2644,7 @@ impl<R: BattleRules> Clone for RegeneratePowers<R> {
         Self {
             id: self.id.clone(),
             seed: self.seed.clone(),
+            policy: self.policy,
         }
     }
 }
@@ -1481,6 +2660,7 @@ impl<R: BattleRules + 'static> Event<R> for RegeneratePowers<R> {
     }
 
     fn apply(&self, battle: &mut Battle<R>, _: &mut Option<EventQueue<R>>) {
+        let watcher = battle.apply_watcher();
         // Retrieve the team.
         let team = battle
             .state
@@ -1488,15 +2668,19 @@ impl<R: BattleRules + 'static> Event<R> for RegeneratePowers<R> {
             .team_mut(&self.id)
             .unwrap_or_else(|| panic!("constraint violated: team {:?} not found", self.id));
         // Generate a new set of powers.
-        let powers: Vec<_> = battle
-            .rules
-            .team_rules()
-            .generate_powers(
-                &self.seed,
-                &mut battle.entropy,
-                &mut battle.metrics.write_handle(),
-            )
-            .collect();
+        let powers: Vec<_> = watch!(
+            watcher,
+            "TeamRules::generate_powers",
+            battle
+                .rules
+                .team_rules()
+                .generate_powers(
+                    &self.seed,
+                    &mut battle.entropy,
+                    &mut battle.metrics.write_handle(),
+                )
+                .collect()
+        );
         let mut to_remove = Vec::new();
         // Remove all team's powers not present in the new set.
         for power in team.powers() {
@@ -1508,8 +2692,18 @@ impl<R: BattleRules + 'static> Event<R> for RegeneratePowers<R> {
             team.remove_power(&power_id);
         }
         // Add all powers present in the new set but not in the team.
+        // Existing powers are handled according to the policy.
+        let rules = battle.rules.team_rules();
         for power in powers {
-            if team.power(power.id()).is_none() {
+            if let Some(current) = team.power_mut(power.id()) {
+                match self.policy {
+                    RegenerationPolicy::PreserveCurrent => {}
+                    RegenerationPolicy::ResetToSeed => {
+                        team.add_power(power);
+                    }
+                    RegenerationPolicy::ClampToNew => rules.clamp_power(current, &power),
+                }
+            } else {
                 team.add_power(power);
             }
         }
@@ -1537,6 +2731,7 @@ where
     processor: &'a mut P,
     id: TeamId<R>,
     seed: Option<PowersSeed<R>>,
+    policy: RegenerationPolicy,
 }
 
 impl<'a, R, P> RegeneratePowersTrigger<'a, R, P>
@@ -1549,6 +2744,12 @@ where
         self.seed = Some(seed);
         self
     }
+
+    /// Sets the policy for the powers already possessed by the team.
+    pub fn policy(&'a mut self, policy: RegenerationPolicy) -> &'a mut Self {
+        self.policy = policy;
+        self
+    }
 }
 
 impl<'a, R, P> EventTrigger<'a, R, P> for RegeneratePowersTrigger<'a, R, P>
@@ -1565,6 +2766,7 @@ where
         Box::new(RegeneratePowers {
             id: self.id.clone(),
             seed: self.seed.clone(),
+            policy: self.policy,
         })
     }
 }
@@ -1608,6 +2810,7 @@ mod tests {
         type PowersAlteration = ();
         type ObjectivesSeed = ();
         type Objectives = ();
+        type TeamData = ();
     }
 
     #[test]
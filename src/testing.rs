@@ -0,0 +1,1466 @@
+//! Test doubles to verify how a game reacts to faults, to control randomness and to
+//! record the invocations of rules hooks.
+
+use crate::ability::{Ability, Activation};
+use crate::actor::{Action, ActionSuggestion, Actor, ActorRules, TrackedAbility};
+use crate::aura::Aura;
+use crate::battle::{BattleRules, BattleState};
+use crate::character::{Character, CharacterRules, ThresholdCrossing, ThresholdLevel};
+use crate::entity::{Entities, Entity, EntityId, Transmutation};
+use crate::entropy::{Entropy, EntropyRules};
+use crate::error::{WeaselError, WeaselErrorType, WeaselResult};
+use crate::event::{
+    ClientEventPrototype, ClientSink, EventId, EventQueue, EventSink, EventSinkId, EventWrapper,
+    LinkedQueue, ServerSink, SinkErrorPolicy, VersionedEventWrapper,
+};
+use crate::fight::FightRules;
+use crate::metric::{ReadMetrics, WriteMetrics};
+use crate::object::Object;
+use crate::reaction::ReactionRules;
+use crate::round::{Rounds, RoundsRules, TicksCount, TurnsCount};
+use crate::server::ShutdownReason;
+use crate::space::{Distance, PositionClaim, Space, SpaceRules};
+use crate::status::{Application, AppliedStatus, Potency, Status, StatusCombination, StatusId};
+use crate::team::{Call, Conclusion, EntityAddition, ObjectiveProgress, Team, TeamRules};
+use crate::util::Id;
+use num_traits::Num;
+#[cfg(feature = "serialization")]
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// A wrapper around a client or server sink that fails on command.
+///
+/// Use it to verify that your game handles sending errors gracefully, for instance by
+/// reconnecting or by resynchronizing clients.
+/// Failed sends are not forwarded to the wrapped sink and return a
+/// `WeaselError::EventSinkError`.
+///
+/// # Examples
+/// ```
+/// use weasel::event::{DummyEvent, EventSink, EventSinkId, ServerSink};
+/// use weasel::testing::FlakySink;
+/// use weasel::{
+///     battle_rules, rules::empty::*, BattleRules, ClientEventPrototype, EventTrigger,
+///     WeaselResult,
+/// };
+///
+/// battle_rules! {}
+///
+/// struct Sink;
+///
+/// impl EventSink for Sink {
+///     fn id(&self) -> EventSinkId {
+///         0
+///     }
+/// }
+///
+/// impl ServerSink<CustomRules> for Sink {
+///     fn send(&mut self, _: &ClientEventPrototype<CustomRules>) -> WeaselResult<(), CustomRules> {
+///         Ok(())
+///     }
+/// }
+///
+/// let event = DummyEvent::trigger(&mut ()).prototype().client_prototype(0, None);
+/// let mut sink = FlakySink::new(Sink).fail_every(2);
+/// assert!(ServerSink::send(&mut sink, &event).is_ok());
+/// assert!(ServerSink::send(&mut sink, &event).is_err());
+/// assert_eq!(sink.failures(), 1);
+/// ```
+pub struct FlakySink<S> {
+    sink: S,
+    fail_every: Option<u32>,
+    fail_next: u32,
+    streak: u32,
+    failures: u32,
+}
+
+impl<S> FlakySink<S> {
+    /// Creates a new flaky sink wrapping `sink`. It won't fail until told so.
+    pub fn new(sink: S) -> Self {
+        Self {
+            sink,
+            fail_every: None,
+            fail_next: 0,
+            streak: 0,
+            failures: 0,
+        }
+    }
+
+    /// Makes one out of every `n` sends fail. A value of zero disables periodic failures.
+    pub fn fail_every(mut self, n: u32) -> Self {
+        self.fail_every = if n > 0 { Some(n) } else { None };
+        self
+    }
+
+    /// Makes the next `n` sends fail, regardless of any periodic failure.
+    pub fn fail_next(&mut self, n: u32) {
+        self.fail_next = n;
+    }
+
+    /// Returns the number of sends that failed so far.
+    pub fn failures(&self) -> u32 {
+        self.failures
+    }
+
+    /// Returns the wrapped sink.
+    pub fn sink(&self) -> &S {
+        &self.sink
+    }
+
+    /// Returns a mutable reference to the wrapped sink.
+    pub fn sink_mut(&mut self) -> &mut S {
+        &mut self.sink
+    }
+
+    /// Consumes this flaky sink, returning the wrapped one.
+    pub fn into_inner(self) -> S {
+        self.sink
+    }
+
+    /// Returns an error if the current send must fail.
+    fn inject_fault<R: BattleRules>(&mut self) -> WeaselResult<(), R> {
+        let periodic = match self.fail_every {
+            Some(n) => {
+                self.streak += 1;
+                if self.streak == n {
+                    self.streak = 0;
+                    true
+                } else {
+                    false
+                }
+            }
+            None => false,
+        };
+        if self.fail_next > 0 || periodic {
+            self.fail_next = self.fail_next.saturating_sub(1);
+            self.failures += 1;
+            Err(WeaselError::EventSinkError("injected failure".to_string()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<S: EventSink> EventSink for FlakySink<S> {
+    fn id(&self) -> EventSinkId {
+        self.sink.id()
+    }
+
+    fn on_disconnect(&mut self) {
+        self.sink.on_disconnect();
+    }
+
+    fn on_shutdown(&mut self, reason: ShutdownReason) {
+        self.sink.on_shutdown(reason);
+    }
+}
+
+impl<R: BattleRules, S: ClientSink<R>> ClientSink<R> for FlakySink<S> {
+    fn send(&mut self, event: &VersionedEventWrapper<R>) -> WeaselResult<(), R> {
+        self.inject_fault()?;
+        self.sink.send(event)
+    }
+
+    fn on_error(&mut self, event_id: EventId, error: &WeaselErrorType<R>) -> SinkErrorPolicy {
+        self.sink.on_error(event_id, error)
+    }
+
+    fn on_quarantine(&mut self, event: &VersionedEventWrapper<R>, error: &WeaselErrorType<R>) {
+        self.sink.on_quarantine(event, error)
+    }
+
+    fn flush(&mut self) -> WeaselResult<(), R> {
+        self.sink.flush()
+    }
+}
+
+impl<R: BattleRules, S: ServerSink<R>> ServerSink<R> for FlakySink<S> {
+    fn send(&mut self, event: &ClientEventPrototype<R>) -> WeaselResult<(), R> {
+        self.inject_fault()?;
+        self.sink.send(event)
+    }
+
+    fn request_resync(&mut self, from: EventId) -> WeaselResult<(), R> {
+        self.inject_fault()?;
+        self.sink.request_resync(from)
+    }
+}
+
+/// Values that can be replayed by `RecordingEntropy`.
+#[cfg(not(feature = "serialization"))]
+pub trait ReplayedOutput: PartialOrd + Copy + Num + Debug + Send {}
+
+#[cfg(not(feature = "serialization"))]
+impl<T: PartialOrd + Copy + Num + Debug + Send> ReplayedOutput for T {}
+
+/// Values that can be replayed by `RecordingEntropy`.
+#[cfg(feature = "serialization")]
+pub trait ReplayedOutput:
+    PartialOrd + Copy + Num + Debug + Send + Serialize + for<'a> Deserialize<'a>
+{
+}
+
+#[cfg(feature = "serialization")]
+impl<T> ReplayedOutput for T where
+    T: PartialOrd + Copy + Num + Debug + Send + Serialize + for<'a> Deserialize<'a>
+{
+}
+
+/// Entropy rules replaying a fixed sequence of values, given as seed.
+///
+/// The sequence starts over once exhausted. Without a sequence, `low` is always returned.\
+/// Values are returned as they are, even if they fall outside of the requested range.
+/// Every generated value is recorded in the model, to let you verify that two battles
+/// consumed entropy in the same way.
+///
+/// # Examples
+/// ```
+/// use weasel::entropy::EntropyRules;
+/// use weasel::testing::RecordingEntropy;
+///
+/// let rules = RecordingEntropy::default();
+/// let mut model = rules.generate_model(&Some(vec![3, 1]));
+/// assert_eq!(rules.generate(&mut model, 0, 10), 3);
+/// assert_eq!(rules.generate(&mut model, 0, 10), 1);
+/// assert_eq!(rules.generate(&mut model, 0, 10), 3);
+/// assert_eq!(model.recorded(), &[3, 1, 3]);
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RecordingEntropy<T> {
+    _phantom: PhantomData<T>,
+}
+
+impl<T: ReplayedOutput> EntropyRules for RecordingEntropy<T> {
+    type EntropySeed = Vec<T>;
+    type EntropyModel = RecordingEntropyModel<T>;
+    type EntropyOutput = T;
+
+    fn generate_model(&self, seed: &Option<Self::EntropySeed>) -> Self::EntropyModel {
+        RecordingEntropyModel {
+            sequence: seed.clone().unwrap_or_default(),
+            next: 0,
+            recorded: Vec::new(),
+        }
+    }
+
+    fn generate(
+        &self,
+        model: &mut Self::EntropyModel,
+        low: Self::EntropyOutput,
+        _high: Self::EntropyOutput,
+    ) -> Self::EntropyOutput {
+        let value = if model.sequence.is_empty() {
+            low
+        } else {
+            let value = model.sequence[model.next % model.sequence.len()];
+            model.next += 1;
+            value
+        };
+        model.recorded.push(value);
+        value
+    }
+}
+
+/// Model of `RecordingEntropy`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct RecordingEntropyModel<T> {
+    sequence: Vec<T>,
+    next: usize,
+    recorded: Vec<T>,
+}
+
+impl<T> RecordingEntropyModel<T> {
+    /// Returns the sequence of values being replayed.
+    pub fn sequence(&self) -> &[T] {
+        &self.sequence
+    }
+
+    /// Returns all values generated so far.
+    pub fn recorded(&self) -> &[T] {
+        &self.recorded
+    }
+}
+
+/// A single invocation of a rules hook, recorded by `RecordedRules`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookCall {
+    hook: &'static str,
+    args: String,
+}
+
+impl HookCall {
+    /// Returns the name of the hook.
+    pub fn hook(&self) -> &'static str {
+        self.hook
+    }
+
+    /// Returns the key arguments of the invocation, in debug format.
+    pub fn args(&self) -> &str {
+        &self.args
+    }
+}
+
+/// A shared log of hook invocations.
+///
+/// Clones of a recorder share the same log, so you can keep a handle to it
+/// after moving the rules into a battle.
+#[derive(Debug, Clone, Default)]
+pub struct HookRecorder {
+    calls: Arc<Mutex<Vec<HookCall>>>,
+}
+
+impl HookRecorder {
+    /// Creates a new recorder with an empty log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<HookCall>> {
+        self.calls.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Appends an invocation of `hook` to the log.
+    pub fn record(&self, hook: &'static str, args: String) {
+        self.lock().push(HookCall { hook, args });
+    }
+
+    /// Returns all invocations recorded so far, from the oldest to the newest.
+    pub fn calls(&self) -> Vec<HookCall> {
+        self.lock().clone()
+    }
+
+    /// Returns how many times `hook` has been invoked.
+    pub fn count(&self, hook: &str) -> usize {
+        self.lock().iter().filter(|call| call.hook == hook).count()
+    }
+
+    /// Returns the arguments of every invocation of `hook`.
+    pub fn args(&self, hook: &str) -> Vec<String> {
+        self.lock()
+            .iter()
+            .filter(|call| call.hook == hook)
+            .map(|call| call.args.clone())
+            .collect()
+    }
+
+    /// Clears the log.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+}
+
+/// Asserts how many times a hook has been invoked, according to a `HookRecorder`.
+///
+/// Without `times`, asserts that the hook has been invoked at least once.
+///
+/// # Examples
+/// ```
+/// use weasel::assert_called;
+/// use weasel::testing::HookRecorder;
+///
+/// let recorder = HookRecorder::new();
+/// recorder.record("on_turn_start", "Creature(1)".to_string());
+/// recorder.record("on_turn_start", "Creature(2)".to_string());
+/// assert_called!(recorder, on_turn_start);
+/// assert_called!(recorder, on_turn_start, times = 2);
+/// assert_called!(recorder, on_turn_end, times = 0);
+/// ```
+#[macro_export]
+macro_rules! assert_called {
+    ($recorder: expr, $hook: ident) => {
+        assert!(
+            $recorder.count(stringify!($hook)) > 0,
+            "hook `{}` was never called",
+            stringify!($hook)
+        );
+    };
+    ($recorder: expr, $hook: ident, times = $times: expr) => {
+        assert_eq!(
+            $recorder.count(stringify!($hook)),
+            $times,
+            "hook `{}` was called an unexpected number of times",
+            stringify!($hook)
+        );
+    };
+}
+
+/// A wrapper around rules that records every invocation of their hooks.
+///
+/// Each invocation is logged with the name of the hook and its key arguments,
+/// such as the id of the actor or of the ability involved. All calls are then
+/// forwarded to the wrapped rules.\
+/// `RecordedRules` implements the rules traits for rounds, actors, characters, fights,
+/// teams, space and reactions.
+///
+/// # Examples
+/// ```
+/// use weasel::rules::empty::*;
+/// use weasel::testing::RecordedRules;
+/// use weasel::{
+///     assert_called, battle_rules, BattleRules, CreateCreature, CreateTeam,
+///     EndTurn, EntityId, EventTrigger, Server, StartTurn,
+/// };
+///
+/// battle_rules! {
+///     EmptyTeamRules,
+///     EmptyCharacterRules,
+///     RecordedRules<EmptyActorRules>,
+///     EmptyFightRules,
+///     EmptyUserRules,
+///     EmptySpaceRules,
+///     EmptyRoundsRules,
+///     EmptyEntropyRules
+/// }
+///
+/// let rules = CustomRules::new();
+/// let recorder = rules.actor_rules.recorder().clone();
+/// let battle = weasel::Battle::builder(rules).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// CreateTeam::trigger(&mut server, 1).fire().unwrap();
+/// CreateCreature::trigger(&mut server, 1, 1, ()).fire().unwrap();
+/// StartTurn::trigger(&mut server, EntityId::Creature(1)).fire().unwrap();
+/// EndTurn::trigger(&mut server).fire().unwrap();
+/// assert_called!(recorder, on_turn_start, times = 1);
+/// assert_eq!(recorder.args("on_turn_end"), vec!["EntityId::Creature { 1 }"]);
+/// ```
+#[derive(Default)]
+pub struct RecordedRules<T> {
+    rules: T,
+    recorder: HookRecorder,
+}
+
+impl<T> RecordedRules<T> {
+    /// Wraps `rules` with a new recorder.
+    pub fn new(rules: T) -> Self {
+        Self::with_recorder(rules, HookRecorder::new())
+    }
+
+    /// Wraps `rules`, logging into an existing recorder.
+    pub fn with_recorder(rules: T, recorder: HookRecorder) -> Self {
+        Self { rules, recorder }
+    }
+
+    /// Returns the recorder of this object.
+    pub fn recorder(&self) -> &HookRecorder {
+        &self.recorder
+    }
+
+    /// Returns the wrapped rules.
+    pub fn rules(&self) -> &T {
+        &self.rules
+    }
+
+    fn record(&self, hook: &'static str, args: String) {
+        self.recorder.record(hook, args);
+    }
+}
+
+impl<R: BattleRules, T: RoundsRules<R>> RoundsRules<R> for RecordedRules<T> {
+    type RoundsSeed = T::RoundsSeed;
+    type RoundsModel = T::RoundsModel;
+
+    fn generate_model(&self, seed: &Option<Self::RoundsSeed>) -> Self::RoundsModel {
+        self.record("generate_model", format!("{:?}", seed));
+        self.rules.generate_model(seed)
+    }
+
+    fn eligible(&self, model: &Self::RoundsModel, actor: &dyn Actor<R>) -> bool {
+        self.record("eligible", format!("{:?}", actor.entity_id()));
+        self.rules.eligible(model, actor)
+    }
+
+    fn order(&self, model: &Self::RoundsModel, entities: &Entities<R>) -> Vec<EntityId<R>> {
+        self.record("order", String::new());
+        self.rules.order(model, entities)
+    }
+
+    fn on_start(
+        &self,
+        entities: &Entities<R>,
+        space: &Space<R>,
+        model: &mut Self::RoundsModel,
+        actor: &dyn Actor<R>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.record("on_start", format!("{:?}", actor.entity_id()));
+        self.rules
+            .on_start(entities, space, model, actor, entropy, metrics)
+    }
+
+    fn on_end(
+        &self,
+        entities: &Entities<R>,
+        space: &Space<R>,
+        model: &mut Self::RoundsModel,
+        actor: &dyn Actor<R>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.record("on_end", format!("{:?}", actor.entity_id()));
+        self.rules
+            .on_end(entities, space, model, actor, entropy, metrics)
+    }
+
+    fn on_tick(
+        &self,
+        entities: &Entities<R>,
+        model: &mut Self::RoundsModel,
+        tick: TicksCount,
+        event_queue: &mut Option<EventQueue<R>>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.record("on_tick", format!("{:?}", tick));
+        self.rules
+            .on_tick(entities, model, tick, event_queue, entropy, metrics)
+    }
+
+    fn on_actor_added(
+        &self,
+        model: &mut Self::RoundsModel,
+        actor: &dyn Actor<R>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.record("on_actor_added", format!("{:?}", actor.entity_id()));
+        self.rules.on_actor_added(model, actor, entropy, metrics)
+    }
+
+    fn on_actor_removed(
+        &self,
+        model: &mut Self::RoundsModel,
+        actor: &dyn Actor<R>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.record("on_actor_removed", format!("{:?}", actor.entity_id()));
+        self.rules.on_actor_removed(model, actor, entropy, metrics)
+    }
+
+    fn features(&self) -> Vec<&'static str> {
+        self.rules.features()
+    }
+}
+
+impl<R: BattleRules, T: ActorRules<R>> ActorRules<R> for RecordedRules<T> {
+    type Ability = T::Ability;
+    type AbilitiesSeed = T::AbilitiesSeed;
+    type Activation = T::Activation;
+    type AbilitiesAlteration = T::AbilitiesAlteration;
+
+    fn generate_abilities(
+        &self,
+        seed: &Option<Self::AbilitiesSeed>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) -> Box<dyn Iterator<Item = Self::Ability>> {
+        self.record("generate_abilities", format!("{:?}", seed));
+        self.rules.generate_abilities(seed, entropy, metrics)
+    }
+
+    fn clamp_ability(&self, current: &mut Self::Ability, new: &Self::Ability) {
+        self.record("clamp_ability", format!("{:?}", current.id()));
+        self.rules.clamp_ability(current, new)
+    }
+
+    fn activable(&self, state: &BattleState<R>, action: Action<R>) -> WeaselResult<(), R> {
+        self.record("activable", action_args(&action));
+        self.rules.activable(state, action)
+    }
+
+    fn max_activations(&self, state: &BattleState<R>, actor: &dyn Actor<R>) -> Option<u32> {
+        self.record("max_activations", format!("{:?}", actor.entity_id()));
+        self.rules.max_activations(state, actor)
+    }
+
+    fn max_recent_activations(&self) -> usize {
+        self.record("max_recent_activations", String::new());
+        self.rules.max_recent_activations()
+    }
+
+    fn activate(
+        &self,
+        state: &BattleState<R>,
+        action: Action<R>,
+        event_queue: &mut Option<EventQueue<R>>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.record("activate", action_args(&action));
+        self.rules
+            .activate(state, action, event_queue, entropy, metrics)
+    }
+
+    fn alter_abilities(
+        &self,
+        actor: &mut dyn Actor<R>,
+        alteration: &Self::AbilitiesAlteration,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.record(
+            "alter_abilities",
+            format!("{:?}, {:?}", actor.entity_id(), alteration),
+        );
+        self.rules
+            .alter_abilities(actor, alteration, entropy, metrics)
+    }
+
+    fn on_turn_start(
+        &self,
+        state: &BattleState<R>,
+        actor: &dyn Actor<R>,
+        event_queue: &mut Option<EventQueue<R>>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.record("on_turn_start", format!("{:?}", actor.entity_id()));
+        self.rules
+            .on_turn_start(state, actor, event_queue, entropy, metrics)
+    }
+
+    fn on_turn_end(
+        &self,
+        state: &BattleState<R>,
+        actor: &dyn Actor<R>,
+        event_queue: &mut Option<EventQueue<R>>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.record("on_turn_end", format!("{:?}", actor.entity_id()));
+        self.rules
+            .on_turn_end(state, actor, event_queue, entropy, metrics)
+    }
+
+    fn recharge(
+        &self,
+        state: &BattleState<R>,
+        actor: &dyn Actor<R>,
+        ability: &TrackedAbility<R>,
+    ) -> TurnsCount {
+        self.record(
+            "recharge",
+            format!("{:?}, {:?}", actor.entity_id(), ability.id()),
+        );
+        self.rules.recharge(state, actor, ability)
+    }
+
+    fn valid_activations(
+        &self,
+        state: &BattleState<R>,
+        actor: &dyn Actor<R>,
+        ability: &Ability<R>,
+    ) -> Vec<Option<Activation<R>>> {
+        self.record(
+            "valid_activations",
+            format!("{:?}, {:?}", actor.entity_id(), ability.id()),
+        );
+        self.rules.valid_activations(state, actor, ability)
+    }
+
+    fn suggest_action(
+        &self,
+        state: &BattleState<R>,
+        actor: &dyn Actor<R>,
+    ) -> Option<ActionSuggestion<R>> {
+        self.record("suggest_action", format!("{:?}", actor.entity_id()));
+        self.rules.suggest_action(state, actor)
+    }
+
+    fn features(&self) -> Vec<&'static str> {
+        self.rules.features()
+    }
+}
+
+impl<R: BattleRules, T: CharacterRules<R>> CharacterRules<R> for RecordedRules<T> {
+    type CreatureId = T::CreatureId;
+    type ObjectId = T::ObjectId;
+    type Statistic = T::Statistic;
+    type StatisticsSeed = T::StatisticsSeed;
+    type StatisticsAlteration = T::StatisticsAlteration;
+    type Status = T::Status;
+    type StatusesAlteration = T::StatusesAlteration;
+
+    fn generate_statistics(
+        &self,
+        seed: &Option<Self::StatisticsSeed>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) -> Box<dyn Iterator<Item = Self::Statistic>> {
+        self.record("generate_statistics", format!("{:?}", seed));
+        self.rules.generate_statistics(seed, entropy, metrics)
+    }
+
+    fn alter_statistics(
+        &self,
+        character: &mut dyn Character<R>,
+        alteration: &Self::StatisticsAlteration,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) -> Option<Transmutation> {
+        self.record(
+            "alter_statistics",
+            format!("{:?}, {:?}", character.entity_id(), alteration),
+        );
+        self.rules
+            .alter_statistics(character, alteration, entropy, metrics)
+    }
+
+    fn clamp_statistic(&self, current: &mut Self::Statistic, new: &Self::Statistic) {
+        self.record("clamp_statistic", format!("{:?}", current.id()));
+        self.rules.clamp_statistic(current, new)
+    }
+
+    fn generate_status(
+        &self,
+        character: &dyn Character<R>,
+        status_id: &StatusId<R>,
+        potency: &Option<Potency<R>>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) -> Option<Status<R>> {
+        self.record(
+            "generate_status",
+            format!("{:?}, {:?}", character.entity_id(), status_id),
+        );
+        self.rules
+            .generate_status(character, status_id, potency, entropy, metrics)
+    }
+
+    fn alter_statuses(
+        &self,
+        character: &mut dyn Character<R>,
+        alteration: &Self::StatusesAlteration,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.record(
+            "alter_statuses",
+            format!("{:?}, {:?}", character.entity_id(), alteration),
+        );
+        self.rules
+            .alter_statuses(character, alteration, entropy, metrics)
+    }
+
+    fn on_character_added(
+        &self,
+        state: &BattleState<R>,
+        character: &dyn Character<R>,
+        event_queue: &mut Option<EventQueue<R>>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.record("on_character_added", format!("{:?}", character.entity_id()));
+        self.rules
+            .on_character_added(state, character, event_queue, entropy, metrics)
+    }
+
+    fn on_character_transmuted(
+        &self,
+        state: &BattleState<R>,
+        character: &dyn Character<R>,
+        transmutation: Transmutation,
+        event_queue: &mut Option<EventQueue<R>>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.record(
+            "on_character_transmuted",
+            format!("{:?}", character.entity_id()),
+        );
+        self.rules.on_character_transmuted(
+            state,
+            character,
+            transmutation,
+            event_queue,
+            entropy,
+            metrics,
+        )
+    }
+
+    fn on_transform(
+        &self,
+        state: &BattleState<R>,
+        character: &dyn Character<R>,
+        event_queue: &mut Option<EventQueue<R>>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.record("on_transform", format!("{:?}", character.entity_id()));
+        self.rules
+            .on_transform(state, character, event_queue, entropy, metrics)
+    }
+
+    fn on_summoner_removed(
+        &self,
+        state: &BattleState<R>,
+        summoner: &dyn Character<R>,
+        summon: &dyn Character<R>,
+    ) -> bool {
+        self.record(
+            "on_summoner_removed",
+            format!("{:?}, {:?}", summoner.entity_id(), summon.entity_id()),
+        );
+        self.rules.on_summoner_removed(state, summoner, summon)
+    }
+
+    fn threshold_level(
+        &self,
+        character: &dyn Character<R>,
+        statistic: &Self::Statistic,
+    ) -> Option<ThresholdLevel> {
+        self.record(
+            "threshold_level",
+            format!("{:?}, {:?}", character.entity_id(), statistic.id()),
+        );
+        self.rules.threshold_level(character, statistic)
+    }
+
+    fn on_threshold_crossed(
+        &self,
+        state: &BattleState<R>,
+        character: &dyn Character<R>,
+        crossing: ThresholdCrossing<R>,
+        event_queue: &mut Option<EventQueue<R>>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.record(
+            "on_threshold_crossed",
+            format!(
+                "{:?}, {:?}, {:?} -> {:?}",
+                character.entity_id(),
+                crossing.statistic_id,
+                crossing.previous,
+                crossing.current
+            ),
+        );
+        self.rules
+            .on_threshold_crossed(state, character, crossing, event_queue, entropy, metrics)
+    }
+
+    fn features(&self) -> Vec<&'static str> {
+        self.rules.features()
+    }
+}
+
+impl<R: BattleRules, T: FightRules<R>> FightRules<R> for RecordedRules<T> {
+    type Impact = T::Impact;
+    type Potency = T::Potency;
+
+    fn apply_impact(
+        &self,
+        state: &BattleState<R>,
+        impact: &Self::Impact,
+        event_queue: &mut Option<EventQueue<R>>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.record("apply_impact", format!("{:?}", impact));
+        self.rules
+            .apply_impact(state, impact, event_queue, entropy, metrics)
+    }
+
+    fn apply_status(
+        &self,
+        state: &BattleState<R>,
+        character: &dyn Character<R>,
+        application: Application<R>,
+        event_queue: &mut Option<EventQueue<R>>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        let status_id = match &application {
+            Application::New(status) => status.id(),
+            Application::Replacement(_, status) => status.id(),
+        };
+        self.record(
+            "apply_status",
+            format!("{:?}, {:?}", character.entity_id(), status_id),
+        );
+        self.rules
+            .apply_status(state, character, application, event_queue, entropy, metrics)
+    }
+
+    fn status_order(&self, status_id: &StatusId<R>) -> i32 {
+        self.record("status_order", format!("{:?}", status_id));
+        self.rules.status_order(status_id)
+    }
+
+    fn combine_statuses(
+        &self,
+        state: &BattleState<R>,
+        character: &dyn Character<R>,
+        status: Status<R>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) -> StatusCombination<R> {
+        self.record(
+            "combine_statuses",
+            format!("{:?}, {:?}", character.entity_id(), status.id()),
+        );
+        self.rules
+            .combine_statuses(state, character, status, entropy, metrics)
+    }
+
+    fn update_status(
+        &self,
+        state: &BattleState<R>,
+        character: &dyn Character<R>,
+        status: &AppliedStatus<R>,
+        linked_queue: &mut Option<LinkedQueue<R>>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) -> bool {
+        self.record(
+            "update_status",
+            format!("{:?}, {:?}", character.entity_id(), status.id()),
+        );
+        self.rules
+            .update_status(state, character, status, linked_queue, entropy, metrics)
+    }
+
+    fn delete_status(
+        &self,
+        state: &BattleState<R>,
+        character: &dyn Character<R>,
+        status: &AppliedStatus<R>,
+        event_queue: &mut Option<EventQueue<R>>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.record(
+            "delete_status",
+            format!("{:?}, {:?}", character.entity_id(), status.id()),
+        );
+        self.rules
+            .delete_status(state, character, status, event_queue, entropy, metrics)
+    }
+
+    fn ability_blocked_by(&self, status: &AppliedStatus<R>, ability: &Ability<R>) -> bool {
+        self.record(
+            "ability_blocked_by",
+            format!("{:?}, {:?}", status.id(), ability.id()),
+        );
+        self.rules.ability_blocked_by(status, ability)
+    }
+
+    fn aura(
+        &self,
+        state: &BattleState<R>,
+        source: &dyn Character<R>,
+        status: &AppliedStatus<R>,
+    ) -> Option<Aura<R>> {
+        self.record(
+            "aura",
+            format!("{:?}, {:?}", source.entity_id(), status.id()),
+        );
+        self.rules.aura(state, source, status)
+    }
+
+    fn features(&self) -> Vec<&'static str> {
+        self.rules.features()
+    }
+}
+
+impl<R: BattleRules, T: TeamRules<R>> TeamRules<R> for RecordedRules<T> {
+    type Id = T::Id;
+    type Power = T::Power;
+    type PowersSeed = T::PowersSeed;
+    type Invocation = T::Invocation;
+    type PowersAlteration = T::PowersAlteration;
+    type Objectives = T::Objectives;
+    type ObjectivesSeed = T::ObjectivesSeed;
+    type TeamData = T::TeamData;
+
+    fn allow_new_entity(
+        &self,
+        state: &BattleState<R>,
+        team: &Team<R>,
+        addition: EntityAddition<R>,
+    ) -> WeaselResult<(), R> {
+        self.record("allow_new_entity", format!("{:?}", team.id()));
+        self.rules.allow_new_entity(state, team, addition)
+    }
+
+    fn generate_powers(
+        &self,
+        seed: &Option<Self::PowersSeed>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) -> Box<dyn Iterator<Item = Self::Power>> {
+        self.record("generate_powers", format!("{:?}", seed));
+        self.rules.generate_powers(seed, entropy, metrics)
+    }
+
+    fn clamp_power(&self, current: &mut Self::Power, new: &Self::Power) {
+        self.record("clamp_power", format!("{:?}", current.id()));
+        self.rules.clamp_power(current, new)
+    }
+
+    fn invocable(&self, state: &BattleState<R>, call: Call<R>) -> WeaselResult<(), R> {
+        self.record("invocable", call_args(&call));
+        self.rules.invocable(state, call)
+    }
+
+    fn invoke(
+        &self,
+        state: &BattleState<R>,
+        call: Call<R>,
+        event_queue: &mut Option<EventQueue<R>>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.record("invoke", call_args(&call));
+        self.rules
+            .invoke(state, call, event_queue, entropy, metrics)
+    }
+
+    fn alter_powers(
+        &self,
+        team: &mut Team<R>,
+        alteration: &Self::PowersAlteration,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.record("alter_powers", format!("{:?}, {:?}", team.id(), alteration));
+        self.rules.alter_powers(team, alteration, entropy, metrics)
+    }
+
+    fn generate_objectives(&self, seed: &Option<Self::ObjectivesSeed>) -> Self::Objectives {
+        self.record("generate_objectives", format!("{:?}", seed));
+        self.rules.generate_objectives(seed)
+    }
+
+    fn check_objectives_on_event(
+        &self,
+        state: &BattleState<R>,
+        team: &Team<R>,
+        metrics: &ReadMetrics<R>,
+    ) -> Option<Conclusion> {
+        self.record("check_objectives_on_event", format!("{:?}", team.id()));
+        self.rules.check_objectives_on_event(state, team, metrics)
+    }
+
+    fn objectives_progress(
+        &self,
+        state: &BattleState<R>,
+        team: &Team<R>,
+        metrics: &ReadMetrics<R>,
+    ) -> Option<Box<dyn ObjectiveProgress>> {
+        self.record("objectives_progress", format!("{:?}", team.id()));
+        self.rules.objectives_progress(state, team, metrics)
+    }
+
+    fn check_objectives_on_turn(
+        &self,
+        state: &BattleState<R>,
+        team: &Team<R>,
+        metrics: &ReadMetrics<R>,
+    ) -> Option<Conclusion> {
+        self.record("check_objectives_on_turn", format!("{:?}", team.id()));
+        self.rules.check_objectives_on_turn(state, team, metrics)
+    }
+
+    fn conclusion_on_round_limit(
+        &self,
+        state: &BattleState<R>,
+        team: &Team<R>,
+        metrics: &ReadMetrics<R>,
+    ) -> Option<Conclusion> {
+        self.record("conclusion_on_round_limit", format!("{:?}", team.id()));
+        self.rules.conclusion_on_round_limit(state, team, metrics)
+    }
+
+    fn on_round_start(
+        &self,
+        team: &mut Team<R>,
+        event_queue: &mut Option<EventQueue<R>>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.record("on_round_start", format!("{:?}", team.id()));
+        self.rules
+            .on_round_start(team, event_queue, entropy, metrics)
+    }
+
+    fn on_round_end(
+        &self,
+        team: &mut Team<R>,
+        event_queue: &mut Option<EventQueue<R>>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.record("on_round_end", format!("{:?}", team.id()));
+        self.rules.on_round_end(team, event_queue, entropy, metrics)
+    }
+
+    fn features(&self) -> Vec<&'static str> {
+        self.rules.features()
+    }
+}
+
+impl<R: BattleRules, T: SpaceRules<R>> SpaceRules<R> for RecordedRules<T> {
+    type Position = T::Position;
+    type SpaceSeed = T::SpaceSeed;
+    type SpaceAlteration = T::SpaceAlteration;
+    type Area = T::Area;
+    type SpaceModel = T::SpaceModel;
+
+    fn generate_model(&self, seed: &Option<Self::SpaceSeed>) -> Self::SpaceModel {
+        self.record("generate_model", format!("{:?}", seed));
+        self.rules.generate_model(seed)
+    }
+
+    fn check_move(
+        &self,
+        model: &Self::SpaceModel,
+        claim: PositionClaim<R>,
+        position: &Self::Position,
+    ) -> WeaselResult<(), R> {
+        self.record(
+            "check_move",
+            format!("{:?}, {:?}", claim_entity(&claim), position),
+        );
+        self.rules.check_move(model, claim, position)
+    }
+
+    fn move_entity(
+        &self,
+        model: &mut Self::SpaceModel,
+        claim: PositionClaim<R>,
+        position: Option<&Self::Position>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.record(
+            "move_entity",
+            format!("{:?}, {:?}", claim_entity(&claim), position),
+        );
+        self.rules.move_entity(model, claim, position, metrics)
+    }
+
+    fn translate_entity(
+        &self,
+        model: &Self::SpaceModel,
+        new_model: &mut Self::SpaceModel,
+        entity: &mut dyn Entity<R>,
+        event_queue: &mut Option<EventQueue<R>>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.record("translate_entity", format!("{:?}", entity.entity_id()));
+        self.rules
+            .translate_entity(model, new_model, entity, event_queue, metrics)
+    }
+
+    fn alter_space(
+        &self,
+        entities: &Entities<R>,
+        rounds: &Rounds<R>,
+        model: &mut Self::SpaceModel,
+        alteration: &Self::SpaceAlteration,
+        event_queue: &mut Option<EventQueue<R>>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.record("alter_space", format!("{:?}", alteration));
+        self.rules
+            .alter_space(entities, rounds, model, alteration, event_queue, metrics)
+    }
+
+    fn on_environment_turn(
+        &self,
+        model: &Self::SpaceModel,
+        object: &Object<R>,
+        event_queue: &mut Option<EventQueue<R>>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.record("on_environment_turn", format!("{:?}", object.entity_id()));
+        self.rules
+            .on_environment_turn(model, object, event_queue, entropy, metrics)
+    }
+
+    fn is_in_area(
+        &self,
+        model: &Self::SpaceModel,
+        area: &Self::Area,
+        position: &Self::Position,
+    ) -> bool {
+        self.record("is_in_area", format!("{:?}, {:?}", area, position));
+        self.rules.is_in_area(model, area, position)
+    }
+
+    fn distance(
+        &self,
+        model: &Self::SpaceModel,
+        from: &Self::Position,
+        to: &Self::Position,
+    ) -> Option<Distance> {
+        self.record("distance", format!("{:?}, {:?}", from, to));
+        self.rules.distance(model, from, to)
+    }
+
+    fn line_of_sight(
+        &self,
+        model: &Self::SpaceModel,
+        from: &Self::Position,
+        to: &Self::Position,
+    ) -> bool {
+        self.record("line_of_sight", format!("{:?}, {:?}", from, to));
+        self.rules.line_of_sight(model, from, to)
+    }
+
+    fn path(
+        &self,
+        model: &Self::SpaceModel,
+        from: &Self::Position,
+        to: &Self::Position,
+    ) -> Option<Vec<Self::Position>> {
+        self.record("path", format!("{:?}, {:?}", from, to));
+        self.rules.path(model, from, to)
+    }
+
+    fn check_step(
+        &self,
+        model: &Self::SpaceModel,
+        entity: &dyn Entity<R>,
+        from: &Self::Position,
+        to: &Self::Position,
+    ) -> WeaselResult<(), R> {
+        self.record(
+            "check_step",
+            format!("{:?}, {:?}, {:?}", entity.entity_id(), from, to),
+        );
+        self.rules.check_step(model, entity, from, to)
+    }
+
+    fn allowed_moves(
+        &self,
+        model: &Self::SpaceModel,
+        entity: &dyn Entity<R>,
+    ) -> Vec<Self::Position> {
+        self.record("allowed_moves", format!("{:?}", entity.entity_id()));
+        self.rules.allowed_moves(model, entity)
+    }
+
+    fn features(&self) -> Vec<&'static str> {
+        self.rules.features()
+    }
+}
+
+impl<R: BattleRules, T: ReactionRules<R>> ReactionRules<R> for RecordedRules<T> {
+    fn features(&self) -> Vec<&'static str> {
+        self.rules.features()
+    }
+
+    fn react(
+        &self,
+        state: &BattleState<R>,
+        event: &EventWrapper<R>,
+        event_queue: &mut Option<EventQueue<R>>,
+        entropy: &mut Entropy<R>,
+        metrics: &mut WriteMetrics<R>,
+    ) {
+        self.record("react", format!("{:?}", event.kind()));
+        self.rules
+            .react(state, event, event_queue, entropy, metrics)
+    }
+}
+
+/// Returns the key arguments of an action.
+fn action_args<R: BattleRules>(action: &Action<R>) -> String {
+    format!(
+        "{:?}, {:?}, {:?}",
+        action.actor.entity_id(),
+        action.ability.id(),
+        action.activation
+    )
+}
+
+/// Returns the key arguments of a call.
+fn call_args<R: BattleRules>(call: &Call<R>) -> String {
+    format!(
+        "{:?}, {:?}, {:?}",
+        call.team.id(),
+        call.power.id(),
+        call.invocation
+    )
+}
+
+/// Returns the id of the entity claiming a position.
+fn claim_entity<'a, R: BattleRules>(claim: &'a PositionClaim<R>) -> &'a EntityId<R> {
+    match claim {
+        PositionClaim::Spawn(id) => id,
+        PositionClaim::Movement(entity) => entity.entity_id(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{DummyEvent, EventTrigger};
+    use crate::{battle_rules, rules::empty::*};
+
+    battle_rules! {}
+
+    #[derive(Default)]
+    struct Sink {
+        sent: u32,
+    }
+
+    impl EventSink for Sink {
+        fn id(&self) -> EventSinkId {
+            1
+        }
+    }
+
+    impl ClientSink<CustomRules> for Sink {
+        fn send(
+            &mut self,
+            _: &VersionedEventWrapper<CustomRules>,
+        ) -> WeaselResult<(), CustomRules> {
+            self.sent += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn flaky_sink() {
+        let event = DummyEvent::<CustomRules>::trigger(&mut ())
+            .prototype()
+            .promote(0)
+            .version(0);
+        let mut sink = FlakySink::new(Sink::default()).fail_every(3);
+        let mut results = Vec::new();
+        for _ in 0..6 {
+            results.push(ClientSink::send(&mut sink, &event).is_ok());
+        }
+        assert_eq!(results, vec![true, true, false, true, true, false]);
+        // Force the next sends to fail.
+        sink.fail_next(2);
+        assert!(ClientSink::send(&mut sink, &event).is_err());
+        assert!(ClientSink::send(&mut sink, &event).is_err());
+        // Periodic failures keep going.
+        assert!(ClientSink::send(&mut sink, &event).is_err());
+        assert!(ClientSink::send(&mut sink, &event).is_ok());
+        assert_eq!(sink.failures(), 5);
+        assert_eq!(sink.id(), 1);
+        assert_eq!(sink.into_inner().sent, 5);
+    }
+
+    #[test]
+    fn recording_entropy() {
+        let rules = RecordingEntropy::default();
+        let mut model = rules.generate_model(&None);
+        assert_eq!(rules.generate(&mut model, 2, 5), 2);
+        let mut model = rules.generate_model(&Some(vec![4, 7]));
+        assert_eq!(rules.generate(&mut model, 0, 10), 4);
+        assert_eq!(rules.generate(&mut model, 0, 10), 7);
+        assert_eq!(rules.generate(&mut model, 0, 10), 4);
+        assert_eq!(model.sequence(), &[4, 7]);
+        assert_eq!(model.recorded(), &[4, 7, 4]);
+    }
+
+    #[test]
+    fn recorded_rules() {
+        use crate::creature::{CreateCreature, TransformCreature};
+        use crate::entity::RemoveEntity;
+        use crate::round::{EndTurn, StartTurn};
+        use crate::team::CreateTeam;
+        use crate::{Battle, BattleController, Server};
+
+        mod rules {
+            use super::super::RecordedRules;
+            use crate::battle::BattleRules;
+            use crate::{battle_rules, rules::empty::*};
+
+            battle_rules! {
+                RecordedRules<EmptyTeamRules>,
+                RecordedRules<EmptyCharacterRules>,
+                RecordedRules<EmptyActorRules>,
+                EmptyFightRules,
+                EmptyUserRules,
+                EmptySpaceRules,
+                RecordedRules<EmptyRoundsRules>,
+                EmptyEntropyRules
+            }
+        }
+
+        let mut rules = rules::CustomRules::new();
+        // Share a single log among multiple rules.
+        let recorder = rules.actor_rules.recorder().clone();
+        rules.rounds_rules = Some(RecordedRules::with_recorder(
+            EmptyRoundsRules::default(),
+            recorder.clone(),
+        ));
+        let team_recorder = rules.team_rules.recorder().clone();
+        let battle = Battle::builder(rules).build();
+        let mut server = Server::builder(battle).build();
+        let entity_id = EntityId::Creature(1);
+        CreateTeam::trigger(&mut server, 1).fire().unwrap();
+        CreateCreature::trigger(&mut server, 1, 1, ())
+            .fire()
+            .unwrap();
+        for _ in 0..2 {
+            StartTurn::trigger(&mut server, entity_id).fire().unwrap();
+            EndTurn::trigger(&mut server).fire().unwrap();
+        }
+        CreateCreature::trigger(&mut server, 2, 1, ())
+            .summoner(entity_id)
+            .fire()
+            .unwrap();
+        TransformCreature::trigger(&mut server, 2).fire().unwrap();
+        RemoveEntity::trigger(&mut server, entity_id)
+            .fire()
+            .unwrap();
+        // Verify the invocations.
+        assert_called!(recorder, generate_abilities, times = 3);
+        assert_called!(recorder, on_turn_start, times = 2);
+        assert_called!(recorder, on_start, times = 2);
+        assert_called!(recorder, on_actor_removed);
+        assert_called!(recorder, activate, times = 0);
+        assert_eq!(
+            recorder.args("on_turn_end"),
+            vec!["EntityId::Creature { 1 }"; 2]
+        );
+        let hooks: Vec<_> = recorder
+            .calls()
+            .iter()
+            .map(|call| call.hook())
+            .filter(|hook| hook.starts_with("on_"))
+            .collect();
+        assert_eq!(
+            hooks,
+            vec![
+                "on_actor_added",
+                "on_start",
+                "on_turn_start",
+                "on_turn_end",
+                "on_end",
+                "on_start",
+                "on_turn_start",
+                "on_turn_end",
+                "on_end",
+                "on_actor_added",
+                "on_actor_removed"
+            ]
+        );
+        assert_eq!(team_recorder.args("allow_new_entity"), vec!["1", "1"]);
+        let character_recorder = server.battle().rules().character_rules().recorder();
+        assert_eq!(character_recorder.count("on_character_added"), 2);
+        assert_eq!(
+            character_recorder.args("on_transform"),
+            vec!["EntityId::Creature { 2 }"]
+        );
+        assert_eq!(
+            character_recorder.args("on_summoner_removed"),
+            vec!["EntityId::Creature { 1 }, EntityId::Creature { 2 }"]
+        );
+        recorder.clear();
+        assert!(recorder.calls().is_empty());
+    }
+}
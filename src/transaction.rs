@@ -0,0 +1,122 @@
+//! Atomic application of sequences of events.
+
+use crate::battle::{Battle, BattleController, BattleRules};
+use crate::error::{WeaselError, WeaselResult};
+use crate::event::{EventProcessor, EventPrototype, EventQueue, EventReceiver};
+use crate::server::Server;
+
+/// A sequence of events applied to a server all together, or not at all.
+///
+/// Each event fired on a transaction is verified against a projection of the battle,
+/// in which all previous events of the transaction have already been applied.
+/// The server is left untouched until `commit` is called.
+///
+/// The projection is created by replaying the server's history on a new server,
+/// built with the factory given to `Server::transaction`. For this reason the factory
+/// must always return servers in the same initial state.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateTeam,
+///     EventTrigger, Server,
+/// };
+///
+/// battle_rules! {}
+///
+/// let factory = || Server::builder(Battle::builder(CustomRules::new()).build()).build();
+/// let mut server = factory();
+///
+/// let mut transaction = server.transaction(&factory).unwrap();
+/// CreateTeam::trigger(&mut transaction, 1).fire().unwrap();
+/// // The second team can't be created because its id is already taken.
+/// assert!(CreateTeam::trigger(&mut transaction, 1).fire().is_err());
+/// CreateTeam::trigger(&mut transaction, 2).fire().unwrap();
+/// assert_eq!(transaction.server().battle().entities().teams().count(), 0);
+/// assert_eq!(transaction.projection().entities().teams().count(), 2);
+///
+/// transaction.commit().unwrap();
+/// assert_eq!(server.battle().entities().teams().count(), 2);
+/// ```
+pub struct Transaction<'a, R: BattleRules> {
+    server: &'a mut Server<R>,
+    projection: Server<R>,
+    events: EventQueue<R>,
+}
+
+impl<'a, R: BattleRules + 'static> Transaction<'a, R> {
+    /// Creates a new transaction for `server`, replaying its history on a server
+    /// created with `factory`.
+    pub(crate) fn new(
+        server: &'a mut Server<R>,
+        factory: &dyn Fn() -> Server<R>,
+    ) -> WeaselResult<Self, R> {
+        let mut projection = factory();
+        let start = projection.battle().history().len();
+        let end = server.battle().history().len();
+        if start > end || start < server.battle().history().first_id() {
+            return Err(WeaselError::NonContiguousEventId(start, end));
+        }
+        // Keep the entropy in lockstep with the history, if the rules allow it.
+        if let Some(model) = server.battle().entropy().checkpoint(start) {
+            projection.battle.entropy.restore(model);
+        }
+        let events: Vec<_> = server
+            .battle()
+            .versioned_events(start as usize..end as usize)
+            .collect();
+        for event in events {
+            projection.receive(event)?;
+        }
+        // Continue from the current entropy of the server, if the rules allow it.
+        projection
+            .battle
+            .entropy
+            .restore(server.battle().entropy().model());
+        Ok(Self {
+            server,
+            projection,
+            events: EventQueue::new(),
+        })
+    }
+
+    /// Returns the server on which this transaction will be committed.
+    pub fn server(&self) -> &Server<R> {
+        self.server
+    }
+
+    /// Returns the projected battle, in which all events of this transaction
+    /// have been applied.
+    pub fn projection(&self) -> &Battle<R> {
+        self.projection.battle()
+    }
+
+    /// Returns the events of this transaction, in firing order.
+    pub fn events(&self) -> &[EventPrototype<R>] {
+        &self.events
+    }
+
+    /// Applies all events of this transaction to the server, in firing order.
+    ///
+    /// Events can fail to be applied only if the rules aren't deterministic,
+    /// in which case processing stops at the first error.
+    pub fn commit(self) -> WeaselResult<(), R> {
+        for event in self.events {
+            self.server.process(event)?;
+        }
+        Ok(())
+    }
+
+    /// Discards this transaction, without touching the server.
+    pub fn rollback(self) {}
+}
+
+impl<R: BattleRules + 'static> EventProcessor<R> for Transaction<'_, R> {
+    type ProcessOutput = WeaselResult<(), R>;
+
+    fn process(&mut self, event: EventPrototype<R>) -> Self::ProcessOutput {
+        self.projection.process(event.clone())?;
+        self.events.push(event);
+        Ok(())
+    }
+}
@@ -0,0 +1,186 @@
+//! Undo and redo of battle actions.
+
+use crate::battle::{BattleController, BattleRules};
+use crate::error::WeaselResult;
+use crate::event::{EventId, EventKind, EventReceiver, VersionedEventWrapper};
+use crate::server::Server;
+
+/// Type of the closure used by an undo manager to create a new server.
+pub type ServerFactory<R> = Box<dyn Fn() -> Server<R>>;
+
+/// Manages undo and redo of the actions taken in a battle.
+///
+/// The manager owns a server. Events fired on the server after the manager is created
+/// can be undone, while the previous ones are kept as the initial state of the battle.\
+/// Events are undone in units:
+/// - a turn, from its `StartTurn` up to the matching `EndTurn`, is a single unit.
+/// - any other event is a unit on its own.
+///
+/// Derived events always belong to the same unit of their origin.
+///
+/// Undoing is implemented by replaying the history on a new server, created with
+/// the manager's factory. For this reason the factory must always return
+/// servers in the same initial state. If the entropy rules support checkpoints,
+/// the entropy model is restored from the original timeline before replaying.\
+/// Redo is possible only as long as no new event is fired after an undo.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateTeam,
+///     EventTrigger, Server, UndoManager,
+/// };
+///
+/// battle_rules! {}
+///
+/// let factory = || Server::builder(Battle::builder(CustomRules::new()).build()).build();
+/// let mut manager = UndoManager::new(Box::new(factory));
+/// assert!(!manager.can_undo());
+///
+/// CreateTeam::trigger(manager.server_mut(), 1).fire().unwrap();
+/// assert!(manager.can_undo());
+///
+/// assert!(manager.undo().unwrap());
+/// assert_eq!(manager.server().battle().entities().teams().count(), 0);
+///
+/// assert!(manager.redo().unwrap());
+/// assert_eq!(manager.server().battle().entities().teams().count(), 1);
+/// ```
+pub struct UndoManager<R: BattleRules> {
+    factory: ServerFactory<R>,
+    server: Server<R>,
+    floor: usize,
+    redo_events: Vec<VersionedEventWrapper<R>>,
+    redo_position: usize,
+}
+
+impl<R: BattleRules + 'static> UndoManager<R> {
+    /// Creates a new undo manager, owning a server created with `factory`.
+    ///
+    /// The events already in the server's history can't be undone, thus the entropy
+    /// checkpoints taken before them are dropped.
+    pub fn new(factory: ServerFactory<R>) -> Self {
+        let mut server = factory();
+        let floor = server.battle().history().len() as usize;
+        server.battle.entropy.prune_checkpoints(floor as EventId);
+        Self {
+            factory,
+            server,
+            floor,
+            redo_events: Vec::new(),
+            redo_position: 0,
+        }
+    }
+
+    /// Returns the managed server.
+    pub fn server(&self) -> &Server<R> {
+        &self.server
+    }
+
+    /// Returns a mutable reference to the managed server.
+    pub fn server_mut(&mut self) -> &mut Server<R> {
+        &mut self.server
+    }
+
+    /// Consumes the manager, returning the server.
+    pub fn into_server(self) -> Server<R> {
+        self.server
+    }
+
+    /// Returns true if there is at least one unit of events that can be undone.
+    pub fn can_undo(&self) -> bool {
+        self.history_len() > self.floor
+    }
+
+    /// Returns true if there is at least one unit of events that can be redone.
+    pub fn can_redo(&self) -> bool {
+        self.history_len() == self.redo_position && self.redo_position < self.redo_events.len()
+    }
+
+    /// Undoes the last unit of events.
+    ///
+    /// Returns false if there was nothing to undo.
+    pub fn undo(&mut self) -> WeaselResult<bool, R> {
+        if !self.can_undo() {
+            return Ok(false);
+        }
+        if !self.can_redo() {
+            // Save the current timeline, to be able to redo it later.
+            let battle = self.server.battle();
+            self.redo_events = battle.versioned_events(0..self.history_len()).collect();
+        }
+        let events = &self.redo_events[..self.history_len()];
+        let target = unit_starts(events, self.floor)
+            .last()
+            .copied()
+            .unwrap_or(self.floor);
+        // Replay the history up to the start of the unit.
+        let mut server = (self.factory)();
+        // Keep the entropy in lockstep with the history, if the rules allow it.
+        let start = server.battle().history().len();
+        if let Some(model) = self.server.battle().entropy().checkpoint(start) {
+            server.battle.entropy.restore(model);
+        }
+        server
+            .battle
+            .entropy
+            .prune_checkpoints(self.floor as EventId);
+        for event in &self.redo_events[server.battle().history().len() as usize..target] {
+            server.receive(event.clone())?;
+        }
+        self.server = server;
+        self.redo_position = target;
+        Ok(true)
+    }
+
+    /// Redoes the last undone unit of events.
+    ///
+    /// Returns false if there was nothing to redo.
+    pub fn redo(&mut self) -> WeaselResult<bool, R> {
+        if !self.can_redo() {
+            return Ok(false);
+        }
+        let start = self.redo_position;
+        let end = unit_starts(&self.redo_events, start)
+            .get(1)
+            .copied()
+            .unwrap_or(self.redo_events.len());
+        for event in &self.redo_events[start..end] {
+            self.server.receive(event.clone())?;
+        }
+        self.redo_position = end;
+        Ok(true)
+    }
+
+    /// Returns the length of the current history.
+    fn history_len(&self) -> usize {
+        self.server.battle().history().len() as usize
+    }
+}
+
+/// Returns the indices of the first event of each unit in `events`, starting from `first`.
+fn unit_starts<R: BattleRules>(events: &[VersionedEventWrapper<R>], first: usize) -> Vec<usize> {
+    let mut starts = Vec::new();
+    let mut in_turn = false;
+    for (index, event) in events.iter().enumerate().skip(first) {
+        let derived = event.origin().is_some();
+        match event.kind() {
+            EventKind::StartTurn if !in_turn && !derived => {
+                in_turn = true;
+                starts.push(index);
+            }
+            EventKind::EndTurn => {
+                if !in_turn && !derived {
+                    starts.push(index);
+                }
+                in_turn = false;
+            }
+            _ => {
+                if !in_turn && !derived {
+                    starts.push(index);
+                }
+            }
+        }
+    }
+    starts
+}
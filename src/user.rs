@@ -1,25 +1,132 @@
 //! User defined extension for battle rules functionalities.
 
 use crate::battle::BattleRules;
-#[cfg(feature = "serialization")]
 use crate::error::{WeaselError, WeaselResult};
 #[cfg(feature = "serialization")]
 use crate::event::Event;
+use indexmap::IndexMap;
 #[cfg(feature = "serialization")]
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use std::hash::Hash;
+use std::marker::PhantomData;
 
 /// Numerical identifier to distinguish user events.
 pub type UserEventId = u16;
 
 /// Rules to extend some aspects of the battle with user defined behavior.
 pub trait UserRules<R: BattleRules> {
+    #[cfg(not(feature = "serialization"))]
     /// See [UserMetricId](type.UserMetricId.html).
     type UserMetricId: Eq + Hash + Clone + Debug + Send;
     #[cfg(feature = "serialization")]
+    /// See [UserMetricId](type.UserMetricId.html).
+    type UserMetricId: Eq + Hash + Clone + Debug + Send + Serialize + for<'a> Deserialize<'a>;
+    #[cfg(feature = "serialization")]
     /// See [UserEventPackage](type.UserEventPackage.html).
     type UserEventPackage: UserEventPacker<R>;
+
+    /// Returns the names of the optional features enabled in these user rules.
+    /// They are reported by `Battle::describe`.
+    ///
+    /// The provided implementation returns no features.
+    fn features(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// Registers the id and the name of every user event known by these rules.
+    /// It is invoked once, when the battle is created.\
+    /// Registering the same id twice returns an error, which makes the battle creation panic.
+    ///
+    /// The provided implementation doesn't register any user event.
+    fn register_user_events(&self, _registry: &mut UserEventRegistry<R>) -> WeaselResult<(), R> {
+        Ok(())
+    }
+}
+
+/// Registry of all user events known by a battle.
+///
+/// Registering user events protects games composed of independent plugins from
+/// accidentally assigning the same `UserEventId` to different events.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, battle_rules_with_user, rules::empty::*, Battle, BattleRules,
+///     UserEventRegistry, UserRules, WeaselResult,
+/// };
+///
+/// const FIREBALL: u16 = 0;
+/// const TELEPORT: u16 = 1;
+///
+/// #[derive(Default)]
+/// pub struct CustomUserRules {}
+///
+/// impl UserRules<CustomRules> for CustomUserRules {
+///     type UserMetricId = u32;
+///     #[cfg(feature = "serialization")]
+///     type UserEventPackage = ();
+///
+///     fn register_user_events(
+///         &self,
+///         registry: &mut UserEventRegistry<CustomRules>,
+///     ) -> WeaselResult<(), CustomRules> {
+///         registry.register(FIREBALL, "fireball")?;
+///         registry.register(TELEPORT, "teleport")
+///     }
+/// }
+///
+/// battle_rules_with_user! { CustomUserRules }
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// assert_eq!(battle.user_events().name(TELEPORT), Some("teleport"));
+/// assert_eq!(battle.user_events().events().count(), 2);
+/// ```
+pub struct UserEventRegistry<R> {
+    events: IndexMap<UserEventId, &'static str>,
+    _phantom: PhantomData<R>,
+}
+
+impl<R: BattleRules> UserEventRegistry<R> {
+    pub(crate) fn new() -> Self {
+        Self {
+            events: IndexMap::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Registers a user event with the given id and name.
+    ///
+    /// Returns an error if another user event with the same id is already registered.
+    pub fn register(&mut self, id: UserEventId, name: &'static str) -> WeaselResult<(), R> {
+        if let Some(existing) = self.events.get(&id) {
+            return Err(WeaselError::DuplicatedUserEvent(id, existing.to_string()));
+        }
+        self.events.insert(id, name);
+        Ok(())
+    }
+
+    /// Returns the name of the user event with the given id, if it was registered.
+    pub fn name(&self, id: UserEventId) -> Option<&'static str> {
+        self.events.get(&id).copied()
+    }
+
+    /// Returns true if a user event with the given id was registered.
+    pub fn contains(&self, id: UserEventId) -> bool {
+        self.events.contains_key(&id)
+    }
+
+    /// Returns an iterator over the ids and names of all registered user events,
+    /// in registration order.
+    pub fn events(&self) -> impl Iterator<Item = (UserEventId, &'static str)> + '_ {
+        self.events.iter().map(|(id, name)| (*id, *name))
+    }
+}
+
+impl<R> Debug for UserEventRegistry<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "UserEventRegistry {{ events: {:?} }}", self.events)
+    }
 }
 
 /// Id of user defined metrics.
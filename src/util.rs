@@ -19,6 +19,24 @@ pub trait Id {
     fn id(&self) -> &Self::Id;
 }
 
+/// Policy to decide what happens to the existing elements of a collection
+/// (e.g. the statistics of a character) when the collection is regenerated.
+///
+/// Elements not present in the new set are always removed, while new elements
+/// are always added.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum RegenerationPolicy {
+    /// Existing elements are kept untouched, preserving their current values.
+    #[default]
+    PreserveCurrent,
+    /// Existing elements are replaced by the ones generated from the seed.
+    ResetToSeed,
+    /// Existing elements keep their current values, but they are adapted to the new ones
+    /// by the rules (for example, by clamping the current value within the new bounds).
+    ClampToNew,
+}
+
 /// Collects an iterator into an indexmap.
 /// Subsequent values with same key are ignored.
 pub(crate) fn collect_from_iter<I>(
@@ -0,0 +1,635 @@
+//! Module to manage what teams can see.
+
+use crate::battle::{Battle, BattleRules};
+use crate::entity::EntityId;
+use crate::error::{WeaselError, WeaselResult};
+use crate::event::{
+    Event, EventKind, EventProcessor, EventQueue, EventTrigger, VersionedEventWrapper,
+};
+use crate::player::PlayerId;
+use crate::server::{apply_broadcast, Broadcast};
+use crate::team::TeamId;
+use indexmap::{IndexMap, IndexSet};
+#[cfg(feature = "serialization")]
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::fmt::{Debug, Formatter, Result};
+
+/// Map of the entities revealed to each team.
+///
+/// The map stores only the entities explicitly revealed through `RevealEntity`.
+/// It's up to the rules to decide what a team can see by default,
+/// for instance its own entities or those of its allies.
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Visibility<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(
+            bound(
+                serialize = "TeamId<R>: Serialize, EntityId<R>: Serialize",
+                deserialize = "TeamId<R>: Deserialize<'de>, EntityId<R>: Deserialize<'de>"
+            ),
+            serialize_with = "crate::serde::serialize_pairs",
+            deserialize_with = "crate::serde::deserialize_pairs"
+        )
+    )]
+    revealed: IndexMap<TeamId<R>, IndexSet<EntityId<R>>>,
+}
+
+impl<R: BattleRules> Visibility<R> {
+    pub(crate) fn new() -> Self {
+        Self {
+            revealed: IndexMap::new(),
+        }
+    }
+
+    /// Returns `true` if the entity with id `entity_id` is revealed to the team `team_id`.
+    pub fn is_revealed(&self, team_id: &TeamId<R>, entity_id: &EntityId<R>) -> bool {
+        match self.revealed.get(team_id) {
+            Some(entities) => entities.contains(entity_id),
+            None => false,
+        }
+    }
+
+    /// Returns an iterator over the ids of all entities revealed to the team `team_id`.
+    pub fn revealed_to<'a>(
+        &'a self,
+        team_id: &TeamId<R>,
+    ) -> impl Iterator<Item = &'a EntityId<R>> + 'a {
+        self.revealed.get(team_id).into_iter().flatten()
+    }
+
+    /// Returns an iterator over the ids of all teams to which the entity `entity_id`
+    /// is revealed.
+    pub fn observers<'a>(
+        &'a self,
+        entity_id: &'a EntityId<R>,
+    ) -> impl Iterator<Item = &'a TeamId<R>> + 'a {
+        self.revealed
+            .iter()
+            .filter(move |(_, entities)| entities.contains(entity_id))
+            .map(|(team_id, _)| team_id)
+    }
+
+    /// Returns an iterator over all pairs of team and entity revealed to it.
+    pub fn revealed(&self) -> impl Iterator<Item = (&TeamId<R>, &EntityId<R>)> {
+        self.revealed.iter().flat_map(|(team_id, entities)| {
+            entities.iter().map(move |entity_id| (team_id, entity_id))
+        })
+    }
+
+    /// Reveals an entity to a team.
+    pub(crate) fn reveal(&mut self, team_id: &TeamId<R>, entity_id: &EntityId<R>) {
+        self.revealed
+            .entry(team_id.clone())
+            .or_default()
+            .insert(entity_id.clone());
+    }
+
+    /// Hides an entity from a team.
+    pub(crate) fn hide(&mut self, team_id: &TeamId<R>, entity_id: &EntityId<R>) {
+        if let Some(entities) = self.revealed.get_mut(team_id) {
+            entities.shift_remove(entity_id);
+            if entities.is_empty() {
+                self.revealed.shift_remove(team_id);
+            }
+        }
+    }
+
+    /// Hides an entity from all teams.
+    pub(crate) fn remove_entity(&mut self, entity_id: &EntityId<R>) {
+        for entities in self.revealed.values_mut() {
+            entities.shift_remove(entity_id);
+        }
+        self.revealed.retain(|_, entities| !entities.is_empty());
+    }
+
+    /// Forgets all entities revealed to a team.
+    pub(crate) fn remove_team(&mut self, team_id: &TeamId<R>) {
+        self.revealed.shift_remove(team_id);
+    }
+
+    /// Replaces all occurrences of an entity's id with `new_id`.
+    pub(crate) fn reidentify_entity(&mut self, id: &EntityId<R>, new_id: &EntityId<R>) {
+        for entities in self.revealed.values_mut() {
+            if entities.shift_remove(id) {
+                entities.insert(new_id.clone());
+            }
+        }
+    }
+
+    /// Replaces all occurrences of a team's id with `new_id`.
+    pub(crate) fn rename_team(&mut self, id: &TeamId<R>, new_id: &TeamId<R>) {
+        self.revealed = self
+            .revealed
+            .drain(..)
+            .map(|(k, v)| {
+                if k == *id {
+                    (new_id.clone(), v)
+                } else {
+                    (k, v)
+                }
+            })
+            .collect();
+    }
+}
+
+/// Policy deciding what a spectator's sink receives.
+///
+/// Spectators are observers who don't control any team.
+/// See `MultiClientSinkHandleMut::add_spectator_sink`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum RedactionPolicy {
+    /// The spectator receives all events, exactly as players do.
+    Full,
+    /// Each event is redacted by the server's `VisibilityRules` before being sent.
+    Redacted,
+}
+
+/// Who receives the events sent to a client sink.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Audience {
+    /// Events are sent as they are.
+    Everyone,
+    /// Events are filtered for a player.
+    Player(PlayerId),
+    /// Events are redacted for a spectator.
+    Spectator,
+}
+
+/// Rules to decide which events, or which parts of them, players and spectators may receive.
+///
+/// Visibility rules are set on a server through `ServerBuilder::visibility_rules`.
+/// The server consults them for each client sink, after the pre broadcast callback:
+/// `filter` for the sinks of players and `redact` for the sinks of spectators
+/// with a `Redacted` policy. Other sinks receive events as they are.
+///
+/// Filtered events are still applied to the server's battle and recorded in its history.
+/// Clients apply what they receive, thus a replacement event must be valid
+/// in their own battle.
+///
+/// # Examples
+/// ```
+/// use weasel::event::{EventTrigger, VersionedEventWrapper};
+/// use weasel::player::PlayerId;
+/// use weasel::server::Broadcast;
+/// use weasel::team::TeamId;
+/// use weasel::visibility::VisibilityRules;
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateCreature,
+///     CreateTeam, Server,
+/// };
+///
+/// battle_rules! {}
+///
+/// struct FogOfWar;
+///
+/// impl VisibilityRules<CustomRules> for FogOfWar {
+///     fn filter(
+///         &self,
+///         _: &Battle<CustomRules>,
+///         event: &VersionedEventWrapper<CustomRules>,
+///         _: PlayerId,
+///         teams: &[TeamId<CustomRules>],
+///     ) -> Broadcast<CustomRules> {
+///         if let Some(create) = event.as_any().downcast_ref::<CreateCreature<CustomRules>>() {
+///             if !teams.contains(create.team_id()) {
+///                 // Hide the statistics of enemy creatures.
+///                 return Broadcast::Replace(
+///                     CreateCreature::trigger(
+///                         &mut (),
+///                         create.id().clone(),
+///                         create.team_id().clone(),
+///                         create.position().clone(),
+///                     )
+///                     .event(),
+///                 );
+///             }
+///         }
+///         Broadcast::Forward
+///     }
+/// }
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle)
+///     .visibility_rules(Box::new(FogOfWar))
+///     .build();
+/// CreateTeam::trigger(&mut server, 1).fire().unwrap();
+/// CreateCreature::trigger(&mut server, 1, 1, ()).fire().unwrap();
+/// assert_eq!(server.battle().entities().creatures().count(), 1);
+/// ```
+pub trait VisibilityRules<R: BattleRules> {
+    /// Decides how `event` must be forwarded to `player`.
+    /// `teams` are the teams that `player` has rights to control.
+    ///
+    /// The default implementation forwards all events as they are.
+    fn filter(
+        &self,
+        _battle: &Battle<R>,
+        _event: &VersionedEventWrapper<R>,
+        _player: PlayerId,
+        _teams: &[TeamId<R>],
+    ) -> Broadcast<R> {
+        Broadcast::Forward
+    }
+
+    /// Decides how `event` must be forwarded to a spectator.
+    ///
+    /// The default implementation forwards all events as they are.
+    fn redact(&self, _battle: &Battle<R>, _event: &VersionedEventWrapper<R>) -> Broadcast<R> {
+        Broadcast::Forward
+    }
+}
+
+/// Filters `event` with the visibility `rules`, for the given `audience`.
+/// Returns the event to be sent, if any.
+pub(crate) fn filter<R: BattleRules + 'static>(
+    rules: &(dyn VisibilityRules<R> + Send),
+    battle: &Battle<R>,
+    event: &VersionedEventWrapper<R>,
+    audience: Audience,
+) -> Option<VersionedEventWrapper<R>> {
+    let broadcast = match audience {
+        Audience::Everyone => return Some(event.clone()),
+        Audience::Player(player) => {
+            let rights = battle.rights();
+            let teams = rights
+                .get()
+                .find(|(id, _)| *id == player)
+                .map_or(&[][..], |(_, teams)| teams);
+            rules.filter(battle, event, player, teams)
+        }
+        Audience::Spectator => rules.redact(battle, event),
+    };
+    apply_broadcast(broadcast, event.clone())
+}
+
+/// Event to reveal an entity to a team.
+///
+/// Rules can inspect which entities have been revealed to a team through the
+/// battle's `Visibility`, to implement scouting mechanics.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateCreature,
+///     CreateTeam, EntityId, EventTrigger, RevealEntity, Server,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// CreateTeam::trigger(&mut server, 1).fire().unwrap();
+/// CreateTeam::trigger(&mut server, 2).fire().unwrap();
+/// let creature_id = 1;
+/// CreateCreature::trigger(&mut server, creature_id, 1, ())
+///     .fire()
+///     .unwrap();
+///
+/// let entity_id = EntityId::Creature(creature_id);
+/// RevealEntity::trigger(&mut server, entity_id.clone(), 2)
+///     .fire()
+///     .unwrap();
+/// assert!(server.battle().visibility().is_revealed(&2, &entity_id));
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct RevealEntity<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "EntityId<R>: Serialize",
+            deserialize = "EntityId<R>: Deserialize<'de>"
+        ))
+    )]
+    entity_id: EntityId<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "TeamId<R>: Serialize",
+            deserialize = "TeamId<R>: Deserialize<'de>"
+        ))
+    )]
+    team_id: TeamId<R>,
+}
+
+impl<R: BattleRules> RevealEntity<R> {
+    /// Returns a trigger for this event.
+    pub fn trigger<P: EventProcessor<R>>(
+        processor: &mut P,
+        entity_id: EntityId<R>,
+        team_id: TeamId<R>,
+    ) -> RevealEntityTrigger<R, P> {
+        RevealEntityTrigger {
+            processor,
+            entity_id,
+            team_id,
+        }
+    }
+
+    /// Returns the id of the entity to be revealed.
+    pub fn entity_id(&self) -> &EntityId<R> {
+        &self.entity_id
+    }
+
+    /// Returns the id of the team to which the entity will be revealed.
+    pub fn team_id(&self) -> &TeamId<R> {
+        &self.team_id
+    }
+}
+
+impl<R: BattleRules> Debug for RevealEntity<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "RevealEntity {{ entity_id: {:?}, team_id: {:?} }}",
+            self.entity_id, self.team_id
+        )
+    }
+}
+
+impl<R: BattleRules> Clone for RevealEntity<R> {
+    fn clone(&self) -> Self {
+        Self {
+            entity_id: self.entity_id.clone(),
+            team_id: self.team_id.clone(),
+        }
+    }
+}
+
+impl<R: BattleRules + 'static> Event<R> for RevealEntity<R> {
+    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
+        // Verify if the entity exists.
+        if battle.entities().entity(&self.entity_id).is_none() {
+            return Err(WeaselError::EntityNotFound(self.entity_id.clone()));
+        }
+        // Verify if the team exists.
+        if battle.entities().team(&self.team_id).is_none() {
+            return Err(WeaselError::TeamNotFound(self.team_id.clone()));
+        }
+        Ok(())
+    }
+
+    fn apply(&self, battle: &mut Battle<R>, _event_queue: &mut Option<EventQueue<R>>) {
+        battle
+            .state
+            .visibility
+            .reveal(&self.team_id, &self.entity_id);
+    }
+
+    fn kind(&self) -> EventKind {
+        EventKind::RevealEntity
+    }
+
+    fn box_clone(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Trigger to build and fire a `RevealEntity` event.
+pub struct RevealEntityTrigger<'a, R, P>
+where
+    R: BattleRules,
+    P: EventProcessor<R>,
+{
+    processor: &'a mut P,
+    entity_id: EntityId<R>,
+    team_id: TeamId<R>,
+}
+
+impl<'a, R, P> EventTrigger<'a, R, P> for RevealEntityTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    fn processor(&'a mut self) -> &'a mut P {
+        self.processor
+    }
+
+    /// Returns a `RevealEntity` event.
+    fn event(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(RevealEntity {
+            entity_id: self.entity_id.clone(),
+            team_id: self.team_id.clone(),
+        })
+    }
+}
+
+/// Event to hide an entity from one or all teams.
+///
+/// By default the entity is hidden from all teams.
+/// Hiding an entity that is not revealed has no effect.
+///
+/// # Examples
+/// ```
+/// use weasel::{
+///     battle_rules, rules::empty::*, Battle, BattleController, BattleRules, CreateCreature,
+///     CreateTeam, EntityId, EventTrigger, HideEntity, RevealEntity, Server,
+/// };
+///
+/// battle_rules! {}
+///
+/// let battle = Battle::builder(CustomRules::new()).build();
+/// let mut server = Server::builder(battle).build();
+///
+/// CreateTeam::trigger(&mut server, 1).fire().unwrap();
+/// CreateTeam::trigger(&mut server, 2).fire().unwrap();
+/// let creature_id = 1;
+/// CreateCreature::trigger(&mut server, creature_id, 1, ())
+///     .fire()
+///     .unwrap();
+/// let entity_id = EntityId::Creature(creature_id);
+/// RevealEntity::trigger(&mut server, entity_id.clone(), 2)
+///     .fire()
+///     .unwrap();
+///
+/// HideEntity::trigger(&mut server, entity_id.clone())
+///     .team(2)
+///     .fire()
+///     .unwrap();
+/// assert!(!server.battle().visibility().is_revealed(&2, &entity_id));
+/// ```
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct HideEntity<R: BattleRules> {
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "EntityId<R>: Serialize",
+            deserialize = "EntityId<R>: Deserialize<'de>"
+        ))
+    )]
+    entity_id: EntityId<R>,
+
+    #[cfg_attr(
+        feature = "serialization",
+        serde(bound(
+            serialize = "Option<TeamId<R>>: Serialize",
+            deserialize = "Option<TeamId<R>>: Deserialize<'de>"
+        ))
+    )]
+    team_id: Option<TeamId<R>>,
+}
+
+impl<R: BattleRules> HideEntity<R> {
+    /// Returns a trigger for this event.
+    pub fn trigger<P: EventProcessor<R>>(
+        processor: &mut P,
+        entity_id: EntityId<R>,
+    ) -> HideEntityTrigger<R, P> {
+        HideEntityTrigger {
+            processor,
+            entity_id,
+            team_id: None,
+        }
+    }
+
+    /// Returns the id of the entity to be hidden.
+    pub fn entity_id(&self) -> &EntityId<R> {
+        &self.entity_id
+    }
+
+    /// Returns the id of the team from which the entity will be hidden.
+    /// `None` means all teams.
+    pub fn team_id(&self) -> &Option<TeamId<R>> {
+        &self.team_id
+    }
+}
+
+impl<R: BattleRules> Debug for HideEntity<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "HideEntity {{ entity_id: {:?}, team_id: {:?} }}",
+            self.entity_id, self.team_id
+        )
+    }
+}
+
+impl<R: BattleRules> Clone for HideEntity<R> {
+    fn clone(&self) -> Self {
+        Self {
+            entity_id: self.entity_id.clone(),
+            team_id: self.team_id.clone(),
+        }
+    }
+}
+
+impl<R: BattleRules + 'static> Event<R> for HideEntity<R> {
+    fn verify(&self, battle: &Battle<R>) -> WeaselResult<(), R> {
+        // Verify if the entity exists.
+        if battle.entities().entity(&self.entity_id).is_none() {
+            return Err(WeaselError::EntityNotFound(self.entity_id.clone()));
+        }
+        // Verify if the team exists.
+        if let Some(team_id) = &self.team_id {
+            if battle.entities().team(team_id).is_none() {
+                return Err(WeaselError::TeamNotFound(team_id.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    fn apply(&self, battle: &mut Battle<R>, _event_queue: &mut Option<EventQueue<R>>) {
+        let visibility = &mut battle.state.visibility;
+        if let Some(team_id) = &self.team_id {
+            visibility.hide(team_id, &self.entity_id);
+        } else {
+            visibility.remove_entity(&self.entity_id);
+        }
+    }
+
+    fn kind(&self) -> EventKind {
+        EventKind::HideEntity
+    }
+
+    fn box_clone(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Trigger to build and fire a `HideEntity` event.
+pub struct HideEntityTrigger<'a, R, P>
+where
+    R: BattleRules,
+    P: EventProcessor<R>,
+{
+    processor: &'a mut P,
+    entity_id: EntityId<R>,
+    team_id: Option<TeamId<R>>,
+}
+
+impl<'a, R, P> HideEntityTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    /// Hides the entity only from the team `team_id`.
+    pub fn team(&'a mut self, team_id: TeamId<R>) -> &'a mut Self {
+        self.team_id = Some(team_id);
+        self
+    }
+}
+
+impl<'a, R, P> EventTrigger<'a, R, P> for HideEntityTrigger<'a, R, P>
+where
+    R: BattleRules + 'static,
+    P: EventProcessor<R>,
+{
+    fn processor(&'a mut self) -> &'a mut P {
+        self.processor
+    }
+
+    /// Returns a `HideEntity` event.
+    fn event(&self) -> Box<dyn Event<R> + Send> {
+        Box::new(HideEntity {
+            entity_id: self.entity_id.clone(),
+            team_id: self.team_id.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{battle_rules, rules::empty::*};
+
+    battle_rules! {}
+
+    #[test]
+    fn maintenance() {
+        let mut visibility = Visibility::<CustomRules>::new();
+        let entity_1 = EntityId::Creature(1);
+        let entity_2 = EntityId::Object(1);
+        visibility.reveal(&1, &entity_1);
+        visibility.reveal(&1, &entity_2);
+        visibility.reveal(&2, &entity_1);
+        assert_eq!(visibility.observers(&entity_1).count(), 2);
+        // Rename a team.
+        visibility.rename_team(&2, &3);
+        assert!(visibility.is_revealed(&3, &entity_1));
+        assert!(!visibility.is_revealed(&2, &entity_1));
+        // Reidentify an entity.
+        let entity_3 = EntityId::Creature(5);
+        visibility.reidentify_entity(&entity_1, &entity_3);
+        assert!(visibility.is_revealed(&1, &entity_3));
+        assert!(visibility.is_revealed(&3, &entity_3));
+        assert_eq!(visibility.observers(&entity_1).count(), 0);
+        // Remove an entity.
+        visibility.remove_entity(&entity_3);
+        assert_eq!(visibility.revealed().count(), 1);
+        // Remove a team.
+        visibility.remove_team(&1);
+        assert_eq!(visibility.revealed().count(), 0);
+    }
+}
@@ -1,14 +1,17 @@
-use weasel::ability::ActivateAbility;
-use weasel::actor::{Action, ActorRules};
+use weasel::ability::{Ability, ActivateAbility, InterruptAbility};
+use weasel::actor::{Action, Actor, ActorRules, RechargeAbilities, TrackedAbility};
 use weasel::battle::{Battle, BattleController, BattleRules, BattleState};
 use weasel::entity::EntityId;
 use weasel::entropy::Entropy;
 use weasel::event::{DummyEvent, EventKind, EventQueue, EventRights, EventServer, EventTrigger};
+use weasel::fight::FightRules;
 use weasel::metric::WriteMetrics;
 use weasel::player::PlayerId;
+use weasel::rules::character::SimpleCharacter;
 use weasel::rules::empty::EmptyAbility;
+use weasel::status::{AppliedStatus, ClearStatus, InflictStatus};
 use weasel::{
-    battle_rules, battle_rules_with_actor, rules::empty::*, Server, WeaselError, WeaselResult,
+    battle_rules, battle_rules_with_actor, rules::empty::*, Id, Server, WeaselError, WeaselResult,
 };
 
 const TEAM_1_ID: u32 = 1;
@@ -65,6 +68,10 @@ impl ActorRules<CustomRules> for CustomActorRules {
             DummyEvent::trigger(&mut event_queue).fire();
         }
     }
+
+    fn max_recent_activations(&self) -> usize {
+        2
+    }
 }
 
 battle_rules_with_actor! { CustomActorRules }
@@ -168,7 +175,7 @@ fn ability_rights() {
         .client_prototype(0, Some(PLAYER_1_ID));
     assert_eq!(
         event.event().rights(server.battle()),
-        EventRights::Team(&TEAM_1_ID)
+        EventRights::Entity(&ENTITY_1_ID)
     );
     // Ability activation should be rejected.
     assert_eq!(
@@ -186,3 +193,443 @@ fn ability_rights() {
     // Check that now he can activate the ability.
     assert_eq!(server.process_client(event).err(), None);
 }
+
+#[test]
+fn delayed_activation() {
+    // Count the dummy events in the battle history.
+    let dummies = |server: &Server<CustomRules>| {
+        server
+            .battle()
+            .history()
+            .events()
+            .iter()
+            .filter(|e| e.kind() == EventKind::DummyEvent)
+            .count()
+    };
+    // Create a server with a creature.
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    util::start_turn(&mut server, &ENTITY_1_ID);
+    // Activate an ability with a delay of two turns.
+    assert_eq!(
+        ActivateAbility::trigger(&mut server, ENTITY_1_ID, ABILITY_ID)
+            .activation(1)
+            .delay(2)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(dummies(&server), 0);
+    let pending = server
+        .battle()
+        .rounds()
+        .pending_activation(&ENTITY_1_ID)
+        .unwrap();
+    assert_eq!(*pending.ability_id(), ABILITY_ID);
+    assert_eq!(pending.turns(), 2);
+    // Another delayed activation should fail.
+    assert_eq!(
+        ActivateAbility::trigger(&mut server, ENTITY_1_ID, ABILITY_ID)
+            .activation(1)
+            .delay(1)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::ActivationPending(ENTITY_1_ID))
+    );
+    // The ability is not yet activated in the next turn.
+    util::end_turn(&mut server);
+    util::start_turn(&mut server, &ENTITY_1_ID);
+    assert_eq!(dummies(&server), 0);
+    assert_eq!(
+        server
+            .battle()
+            .rounds()
+            .pending_activation(&ENTITY_1_ID)
+            .map(|pending| pending.turns()),
+        Some(1)
+    );
+    // The ability is activated when the delay expires.
+    util::end_turn(&mut server);
+    util::start_turn(&mut server, &ENTITY_1_ID);
+    assert_eq!(dummies(&server), 1);
+    assert!(server
+        .battle()
+        .rounds()
+        .pending_activation(&ENTITY_1_ID)
+        .is_none());
+}
+
+#[test]
+fn interrupt_ability() {
+    // Create a server with a creature.
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    util::start_turn(&mut server, &ENTITY_1_ID);
+    // Interrupting should fail if the actor doesn't exist or isn't preparing any ability.
+    assert_eq!(
+        InterruptAbility::trigger(&mut server, ENTITY_ERR_ID)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::EntityNotFound(ENTITY_ERR_ID))
+    );
+    assert_eq!(
+        InterruptAbility::trigger(&mut server, ENTITY_1_ID)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::NoActivationPending(ENTITY_1_ID))
+    );
+    // Activate an ability with a delay and then interrupt it.
+    assert_eq!(
+        ActivateAbility::trigger(&mut server, ENTITY_1_ID, ABILITY_ID)
+            .activation(1)
+            .delay(1)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(
+        InterruptAbility::trigger(&mut server, ENTITY_1_ID)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(server.battle().rounds().pending_activations().count(), 0);
+    // The ability is never activated.
+    util::end_turn(&mut server);
+    util::start_turn(&mut server, &ENTITY_1_ID);
+    assert!(server
+        .battle()
+        .history()
+        .events()
+        .iter()
+        .all(|e| e.kind() != EventKind::DummyEvent));
+}
+
+#[test]
+fn max_activations() {
+    #[derive(Default)]
+    pub struct CustomActorRules {}
+
+    impl ActorRules<CustomRules> for CustomActorRules {
+        type Ability = EmptyAbility;
+        type AbilitiesSeed = ();
+        type Activation = ();
+        type AbilitiesAlteration = ();
+
+        fn generate_abilities(
+            &self,
+            _: &Option<Self::AbilitiesSeed>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) -> Box<dyn Iterator<Item = Self::Ability>> {
+            let v = vec![EmptyAbility { id: ABILITY_ID }];
+            Box::new(v.into_iter())
+        }
+
+        fn max_activations(
+            &self,
+            _state: &BattleState<CustomRules>,
+            _actor: &dyn Actor<CustomRules>,
+        ) -> Option<u32> {
+            Some(2)
+        }
+    }
+
+    battle_rules_with_actor! { CustomActorRules }
+
+    let entity_1_id: EntityId<CustomRules> = EntityId::Creature(CREATURE_1_ID);
+    // Create a server with a creature.
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    util::start_turn(&mut server, &entity_1_id);
+    // Activate abilities up to the limit, counting delayed activations as well.
+    assert_eq!(
+        ActivateAbility::trigger(&mut server, entity_1_id, ABILITY_ID)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(
+        ActivateAbility::trigger(&mut server, entity_1_id, ABILITY_ID)
+            .delay(1)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(server.battle().rounds().activations(&entity_1_id), 2);
+    // Further activations in the same turn should fail.
+    assert_eq!(
+        ActivateAbility::trigger(&mut server, entity_1_id, ABILITY_ID)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::ActivationsExhausted(entity_1_id))
+    );
+    // The counter is reset in the next turn.
+    util::end_turn(&mut server);
+    assert_eq!(server.battle().rounds().activations(&entity_1_id), 0);
+    util::start_turn(&mut server, &entity_1_id);
+    assert_eq!(
+        ActivateAbility::trigger(&mut server, entity_1_id, ABILITY_ID)
+            .fire()
+            .err(),
+        None
+    );
+}
+
+#[test]
+fn ability_blocked_by_status() {
+    const SILENCE_ID: u32 = 1;
+    const SPELL_ID: u32 = 2;
+
+    #[derive(Default)]
+    pub struct CustomActorRules {}
+
+    impl ActorRules<CustomRules> for CustomActorRules {
+        type Ability = EmptyAbility;
+        type AbilitiesSeed = ();
+        type Activation = ();
+        type AbilitiesAlteration = ();
+
+        fn generate_abilities(
+            &self,
+            _: &Option<Self::AbilitiesSeed>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) -> Box<dyn Iterator<Item = Self::Ability>> {
+            let v = vec![
+                EmptyAbility { id: ABILITY_ID },
+                EmptyAbility { id: SPELL_ID },
+            ];
+            Box::new(v.into_iter())
+        }
+    }
+
+    #[derive(Default)]
+    pub struct CustomFightRules {}
+
+    impl FightRules<CustomRules> for CustomFightRules {
+        type Impact = ();
+        type Potency = ();
+
+        fn ability_blocked_by(
+            &self,
+            status: &AppliedStatus<CustomRules>,
+            ability: &Ability<CustomRules>,
+        ) -> bool {
+            // Silence blocks spells.
+            *status.id() == SILENCE_ID && *ability.id() == SPELL_ID
+        }
+    }
+
+    battle_rules! {
+        EmptyTeamRules,
+        SimpleCharacter,
+        CustomActorRules,
+        CustomFightRules,
+        EmptyUserRules,
+        EmptySpaceRules,
+        EmptyRoundsRules,
+        EmptyEntropyRules
+    }
+
+    let entity_1_id: EntityId<CustomRules> = EntityId::Creature(CREATURE_1_ID);
+    // Create a server with a creature.
+    let mut rules = CustomRules::new();
+    rules.character_rules = SimpleCharacter::default().status(SILENCE_ID, 0, None);
+    let mut server = util::server(rules);
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    // Silence the creature.
+    assert_eq!(
+        InflictStatus::trigger(&mut server, entity_1_id, SILENCE_ID)
+            .fire()
+            .err(),
+        None
+    );
+    util::start_turn(&mut server, &entity_1_id);
+    // Spells are blocked, while other abilities can still be activated.
+    assert_eq!(
+        ActivateAbility::trigger(&mut server, entity_1_id, SPELL_ID)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::AbilityBlocked(
+            entity_1_id,
+            SPELL_ID,
+            SILENCE_ID
+        ))
+    );
+    assert_eq!(
+        ActivateAbility::trigger(&mut server, entity_1_id, ABILITY_ID)
+            .fire()
+            .err(),
+        None
+    );
+    // Spells can be activated again once the silence is cleared.
+    assert_eq!(
+        ClearStatus::trigger(&mut server, entity_1_id, SILENCE_ID)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(
+        ActivateAbility::trigger(&mut server, entity_1_id, SPELL_ID)
+            .fire()
+            .err(),
+        None
+    );
+}
+
+#[test]
+fn ability_cooldown() {
+    const COOLDOWN: u32 = 2;
+
+    #[derive(Default)]
+    pub struct CustomActorRules {}
+
+    impl ActorRules<CustomRules> for CustomActorRules {
+        type Ability = EmptyAbility;
+        type AbilitiesSeed = ();
+        type Activation = ();
+        type AbilitiesAlteration = ();
+
+        fn generate_abilities(
+            &self,
+            _: &Option<Self::AbilitiesSeed>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) -> Box<dyn Iterator<Item = Self::Ability>> {
+            let v = vec![EmptyAbility { id: ABILITY_ID }];
+            Box::new(v.into_iter())
+        }
+
+        fn recharge(
+            &self,
+            state: &BattleState<CustomRules>,
+            _actor: &dyn Actor<CustomRules>,
+            ability: &TrackedAbility<CustomRules>,
+        ) -> u32 {
+            // Abilities can be used again after a few turns.
+            ability.last_used_turn().map_or(0, |turn| {
+                (turn + COOLDOWN).saturating_sub(state.rounds().completed_turns())
+            })
+        }
+    }
+
+    battle_rules_with_actor! { CustomActorRules }
+
+    let entity_1_id: EntityId<CustomRules> = EntityId::Creature(CREATURE_1_ID);
+    let activate = |server: &mut Server<CustomRules>| {
+        ActivateAbility::trigger(server, entity_1_id, ABILITY_ID)
+            .fire()
+            .err()
+            .map(|e| e.unfold())
+    };
+    // Create a server with a creature.
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    // Activate the ability.
+    util::start_turn(&mut server, &entity_1_id);
+    assert_eq!(activate(&mut server), None);
+    let actor = server.battle().entities().actor(&entity_1_id).unwrap();
+    assert_eq!(
+        actor.tracked_ability(&ABILITY_ID).unwrap().last_used_turn(),
+        Some(0)
+    );
+    // The ability is recharging.
+    assert_eq!(
+        activate(&mut server),
+        Some(WeaselError::AbilityOnCooldown(entity_1_id, ABILITY_ID, 2))
+    );
+    util::end_turn(&mut server);
+    util::start_turn(&mut server, &entity_1_id);
+    assert_eq!(
+        activate(&mut server),
+        Some(WeaselError::AbilityOnCooldown(entity_1_id, ABILITY_ID, 1))
+    );
+    util::end_turn(&mut server);
+    // After the cooldown the ability can be activated again.
+    util::start_turn(&mut server, &entity_1_id);
+    assert_eq!(activate(&mut server), None);
+    // Recharge the ability instantly.
+    assert_eq!(
+        RechargeAbilities::trigger(&mut server, entity_1_id)
+            .abilities(vec![ABILITY_ERR_ID])
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::AbilityNotKnown(entity_1_id, ABILITY_ERR_ID))
+    );
+    assert_eq!(
+        RechargeAbilities::trigger(&mut server, entity_1_id)
+            .abilities(vec![ABILITY_ID])
+            .fire()
+            .err(),
+        None
+    );
+    let actor = server.battle().entities().actor(&entity_1_id).unwrap();
+    assert_eq!(
+        actor.tracked_ability(&ABILITY_ID).unwrap().last_used_turn(),
+        None
+    );
+    assert_eq!(activate(&mut server), None);
+}
+
+#[test]
+fn recent_activations() {
+    // Ids of all ability activations in the battle history.
+    let activations = |server: &Server<CustomRules>| -> Vec<_> {
+        server
+            .battle()
+            .history()
+            .events()
+            .iter()
+            .filter(|e| e.kind() == EventKind::ActivateAbility)
+            .map(|e| e.id())
+            .collect()
+    };
+    // Create a server with a creature.
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    // Activate an ability in the first turn and twice in the second.
+    util::start_turn(&mut server, &ENTITY_1_ID);
+    assert_eq!(
+        ActivateAbility::trigger(&mut server, ENTITY_1_ID, ABILITY_ID)
+            .activation(0)
+            .fire()
+            .err(),
+        None
+    );
+    util::end_turn(&mut server);
+    util::start_turn(&mut server, &ENTITY_1_ID);
+    for _ in 0..2 {
+        assert_eq!(
+            ActivateAbility::trigger(&mut server, ENTITY_1_ID, ABILITY_ID)
+                .activation(1)
+                .fire()
+                .err(),
+            None
+        );
+    }
+    // Only the last two activations are remembered.
+    let ids = activations(&server);
+    assert_eq!(ids.len(), 3);
+    let actor = server.battle().entities().actor(&ENTITY_1_ID).unwrap();
+    let records: Vec<_> = actor
+        .recent_activations()
+        .map(|r| (*r.ability_id(), r.event_id(), r.turn()))
+        .collect();
+    assert_eq!(
+        records,
+        vec![(ABILITY_ID, ids[1], 1), (ABILITY_ID, ids[2], 1)]
+    );
+}
@@ -94,3 +94,239 @@ fn default_works() {
         None
     );
 }
+
+#[test]
+fn legal_actions() {
+    use weasel::ability::{ActivateAbility, Activation};
+    use weasel::actor::Action;
+    use weasel::entity::Entity;
+    use weasel::round::EndTurn;
+    use weasel::rules::ability::SimpleAbility;
+    use weasel::space::{PositionClaim, SpaceRules};
+    use weasel::{Id, WeaselError, WeaselResult};
+
+    #[derive(Default)]
+    pub struct CustomActorRules {}
+
+    impl ActorRules<CustomRules> for CustomActorRules {
+        type Ability = SimpleAbility<u32, ()>;
+        type AbilitiesSeed = ();
+        type Activation = u32;
+        type AbilitiesAlteration = ();
+
+        fn generate_abilities(
+            &self,
+            _: &Option<Self::AbilitiesSeed>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) -> Box<dyn Iterator<Item = Self::Ability>> {
+            Box::new(vec![SimpleAbility::new(1, ()), SimpleAbility::new(2, ())].into_iter())
+        }
+
+        fn activable(
+            &self,
+            _state: &BattleState<CustomRules>,
+            action: Action<CustomRules>,
+        ) -> WeaselResult<(), CustomRules> {
+            // Target 20 is out of reach.
+            if *action.activation == Some(20) {
+                Err(WeaselError::GenericError)
+            } else {
+                Ok(())
+            }
+        }
+
+        fn valid_activations(
+            &self,
+            _state: &BattleState<CustomRules>,
+            _actor: &dyn Actor<CustomRules>,
+            ability: &Self::Ability,
+        ) -> Vec<Option<Activation<CustomRules>>> {
+            // The first ability targets someone, the second one doesn't need a target.
+            if *ability.id() == 1 {
+                vec![Some(10), Some(20)]
+            } else {
+                vec![None]
+            }
+        }
+    }
+
+    #[derive(Default)]
+    pub struct CustomSpaceRules {}
+
+    impl SpaceRules<CustomRules> for CustomSpaceRules {
+        type Position = u32;
+        type SpaceSeed = ();
+        type Area = ();
+        type SpaceModel = ();
+        type SpaceAlteration = ();
+
+        fn generate_model(&self, _: &Option<Self::SpaceSeed>) -> Self::SpaceModel {}
+
+        fn check_move(
+            &self,
+            _model: &Self::SpaceModel,
+            _claim: PositionClaim<CustomRules>,
+            position: &Self::Position,
+        ) -> WeaselResult<(), CustomRules> {
+            // Position 3 is blocked.
+            if *position == 3 {
+                Err(WeaselError::GenericError)
+            } else {
+                Ok(())
+            }
+        }
+
+        fn allowed_moves(
+            &self,
+            _model: &Self::SpaceModel,
+            entity: &dyn Entity<CustomRules>,
+        ) -> Vec<Self::Position> {
+            vec![entity.position() + 1, entity.position() + 2]
+        }
+    }
+
+    battle_rules! {
+        EmptyTeamRules,
+        EmptyCharacterRules,
+        CustomActorRules,
+        EmptyFightRules,
+        EmptyUserRules,
+        CustomSpaceRules,
+        EmptyRoundsRules,
+        EmptyEntropyRules
+    }
+
+    let entity_id = EntityId::Creature(CREATURE_1_ID);
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, 1);
+    // Outside of its turn, the creature can only move.
+    let actions = server.battle().legal_actions(&entity_id);
+    assert_eq!(actions.len(), 1);
+    let movement = actions[0]
+        .as_any()
+        .downcast_ref::<MoveEntity<CustomRules>>()
+        .unwrap();
+    assert_eq!(*movement.position(), 2);
+    // During its turn, the creature can also activate abilities and end the turn.
+    util::start_turn(&mut server, &entity_id);
+    let actions = server.battle().legal_actions(&entity_id);
+    assert_eq!(
+        actions.iter().map(|event| event.kind()).collect::<Vec<_>>(),
+        vec![
+            EventKind::ActivateAbility,
+            EventKind::ActivateAbility,
+            EventKind::MoveEntity,
+            EventKind::EndTurn
+        ]
+    );
+    let activations: Vec<_> = actions
+        .iter()
+        .filter_map(|event| {
+            event
+                .as_any()
+                .downcast_ref::<ActivateAbility<CustomRules>>()
+        })
+        .map(|event| (*event.ability_id(), *event.activation()))
+        .collect();
+    assert_eq!(activations, vec![(1, Some(10)), (2, None)]);
+    assert!(actions[3].as_any().is::<EndTurn<CustomRules>>());
+    // Entities that aren't actors have no legal actions.
+    assert!(server
+        .battle()
+        .legal_actions(&EntityId::Creature(99))
+        .is_empty());
+}
+
+#[test]
+fn run_ai_turn() {
+    use weasel::ability::ActivateAbility;
+    use weasel::actor::ActionSuggestion;
+    use weasel::rules::ability::SimpleAbility;
+    use weasel::WeaselError;
+
+    const CREATURE_2_ID: u32 = 2;
+
+    #[derive(Default)]
+    pub struct CustomActorRules {}
+
+    impl ActorRules<CustomRules> for CustomActorRules {
+        type Ability = SimpleAbility<u32, ()>;
+        type AbilitiesSeed = ();
+        type Activation = ();
+        type AbilitiesAlteration = ();
+
+        fn generate_abilities(
+            &self,
+            _: &Option<Self::AbilitiesSeed>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) -> Box<dyn Iterator<Item = Self::Ability>> {
+            Box::new(std::iter::once(SimpleAbility::new(1, ())))
+        }
+
+        fn suggest_action(
+            &self,
+            _state: &BattleState<CustomRules>,
+            actor: &dyn Actor<CustomRules>,
+        ) -> Option<ActionSuggestion<CustomRules>> {
+            // Only the first creature knows what to do.
+            if *actor.entity_id() == EntityId::Creature(CREATURE_1_ID) {
+                Some(
+                    ActivateAbility::trigger(&mut (), *actor.entity_id(), 1)
+                        .prototype()
+                        .into(),
+                )
+            } else {
+                None
+            }
+        }
+    }
+
+    battle_rules! {
+        EmptyTeamRules,
+        EmptyCharacterRules,
+        CustomActorRules,
+        EmptyFightRules,
+        EmptyUserRules,
+        EmptySpaceRules,
+        EmptyRoundsRules,
+        EmptyEntropyRules
+    }
+
+    let entity_id = EntityId::Creature(CREATURE_1_ID);
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    util::creature(&mut server, CREATURE_2_ID, TEAM_1_ID, ());
+    // The suggested ability is activated during the turn.
+    assert_eq!(server.run_ai_turn(entity_id), Ok(1));
+    let kinds = |server: &weasel::Server<CustomRules>| {
+        server
+            .battle()
+            .history()
+            .events()
+            .iter()
+            .skip(3)
+            .map(|event| event.kind())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        kinds(&server),
+        vec![
+            EventKind::StartTurn,
+            EventKind::ActivateAbility,
+            EventKind::EndTurn
+        ]
+    );
+    assert!(!server.battle().rounds().is_acting(&entity_id));
+    // Without suggestions, the turn is simply ended.
+    assert_eq!(server.run_ai_turn(EntityId::Creature(CREATURE_2_ID)), Ok(0));
+    assert_eq!(kinds(&server).len(), 5);
+    // The turn can't start for a missing actor.
+    assert!(matches!(
+        server.run_ai_turn(EntityId::Creature(99)),
+        Err(WeaselError::InvalidEvent(..))
+    ));
+}
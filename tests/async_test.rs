@@ -0,0 +1,153 @@
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use weasel::asynchronous::{bridge, AsyncClient, AsyncClientSink, AsyncServerSink, BoxFuture};
+use weasel::battle::{BattleController, BattleRules};
+use weasel::event::{
+    ClientEventPrototype, DummyEvent, EventReceiver, EventServer, EventSink, EventSinkId,
+    EventTrigger, ServerSink, VersionedEventWrapper,
+};
+use weasel::team::CreateTeam;
+use weasel::{battle_rules, rules::empty::*};
+use weasel::{WeaselError, WeaselResult};
+
+const TEAM_1_ID: u32 = 1;
+const SERVER_1_ID: EventSinkId = 1;
+const CLIENT_1_ID: EventSinkId = 1;
+
+battle_rules! {}
+
+/// Polls a future until completion. All futures in these tests never wait on anything.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let mut context = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+struct TestServerSink {
+    sent: Arc<Mutex<Vec<ClientEventPrototype<CustomRules>>>>,
+    fail: Arc<Mutex<bool>>,
+}
+
+impl EventSink for TestServerSink {
+    fn id(&self) -> EventSinkId {
+        SERVER_1_ID
+    }
+}
+
+impl ServerSink<CustomRules> for TestServerSink {
+    fn send(&mut self, _: &ClientEventPrototype<CustomRules>) -> WeaselResult<(), CustomRules> {
+        panic!("the synchronous sink shouldn't be used");
+    }
+}
+
+impl AsyncServerSink<CustomRules> for TestServerSink {
+    fn send(
+        &mut self,
+        event: ClientEventPrototype<CustomRules>,
+    ) -> BoxFuture<'_, WeaselResult<(), CustomRules>> {
+        Box::pin(async move {
+            if *self.fail.lock().unwrap() {
+                return Err(WeaselError::EventSinkError("failure".to_string()));
+            }
+            self.sent.lock().unwrap().push(event);
+            Ok(())
+        })
+    }
+}
+
+#[derive(Clone, Default)]
+struct TestClientSink {
+    sent: Arc<Mutex<Vec<VersionedEventWrapper<CustomRules>>>>,
+}
+
+impl EventSink for TestClientSink {
+    fn id(&self) -> EventSinkId {
+        CLIENT_1_ID
+    }
+}
+
+impl AsyncClientSink<CustomRules> for TestClientSink {
+    fn send(
+        &mut self,
+        event: VersionedEventWrapper<CustomRules>,
+    ) -> BoxFuture<'_, WeaselResult<(), CustomRules>> {
+        Box::pin(async move {
+            self.sent.lock().unwrap().push(event);
+            Ok(())
+        })
+    }
+}
+
+#[test]
+fn async_client_server() {
+    // Create a server with a bridged client sink.
+    let mut server = util::server(CustomRules::new());
+    let client_sink = TestClientSink::default();
+    let (sink, mut forwarder) = bridge(client_sink.clone());
+    assert_eq!(
+        server.client_sinks_mut().add_sink(Box::new(sink)).err(),
+        None
+    );
+    // Server processing is immediate.
+    assert_eq!(
+        block_on(CreateTeam::trigger(&mut server, TEAM_1_ID).fire_async()).err(),
+        None
+    );
+    assert_eq!(forwarder.pending(), 1);
+    // Create an asynchronous client and forward the first event to it.
+    let server_sink = TestServerSink::default();
+    let client = util::client(CustomRules::new(), server_sink.clone());
+    let mut client = AsyncClient::new(client, Box::new(server_sink.clone()));
+    assert_eq!(block_on(forwarder.forward()).ok(), Some(1));
+    for event in client_sink.sent.lock().unwrap().drain(..) {
+        assert_eq!(client.receive(event).err(), None);
+    }
+    assert_eq!(client.client().battle().entities().teams().count(), 1);
+    // Events fired synchronously wait in the outbox.
+    util::dummy(&mut client);
+    assert_eq!(client.pending_events().len(), 1);
+    assert!(server_sink.sent.lock().unwrap().is_empty());
+    // Events fired asynchronously flush the outbox.
+    assert_eq!(
+        block_on(DummyEvent::trigger(&mut client).fire_async()).err(),
+        None
+    );
+    assert!(client.pending_events().is_empty());
+    assert_eq!(server_sink.sent.lock().unwrap().len(), 2);
+    // Deliver the events to the server.
+    for event in server_sink.sent.lock().unwrap().drain(..) {
+        assert_eq!(server.process_client(event).err(), None);
+    }
+    assert_eq!(forwarder.pending(), 2);
+    assert!(client_sink.sent.lock().unwrap().is_empty());
+    // Forward the events to the client.
+    assert_eq!(block_on(forwarder.forward()).ok(), Some(2));
+    assert_eq!(forwarder.pending(), 0);
+    for event in client_sink.sent.lock().unwrap().drain(..) {
+        assert_eq!(client.receive(event).err(), None);
+    }
+    assert_eq!(client.client().battle().history().len(), 3);
+}
+
+#[test]
+fn async_flush_failure() {
+    let server_sink = TestServerSink::default();
+    let client = util::client(CustomRules::new(), server_sink.clone());
+    let mut client = AsyncClient::new(client, Box::new(server_sink.clone()));
+    util::dummy(&mut client);
+    *server_sink.fail.lock().unwrap() = true;
+    // The failed event stays in the outbox.
+    assert!(block_on(DummyEvent::trigger(&mut client).fire_async()).is_err());
+    assert_eq!(client.pending_events().len(), 2);
+    // Flushing again sends everything.
+    *server_sink.fail.lock().unwrap() = false;
+    assert_eq!(block_on(client.flush()).err(), None);
+    assert!(client.pending_events().is_empty());
+    assert_eq!(server_sink.sent.lock().unwrap().len(), 2);
+}
@@ -1,17 +1,23 @@
+use std::sync::Arc;
 use weasel::ability::ActivateAbility;
 use weasel::actor::{Action, ActorRules};
-use weasel::battle::{BattleController, BattlePhase, BattleRules, BattleState, EndBattle};
+use weasel::battle::{
+    Battle, BattleController, BattlePhase, BattleRules, BattleState, EndBattle, EndReason,
+    StartBattle, StaticData,
+};
 use weasel::battle_rules_with_actor;
+use weasel::creature::CreateCreature;
 use weasel::entity::EntityId;
 use weasel::entropy::Entropy;
-use weasel::event::{DummyEvent, EventQueue, EventTrigger};
+use weasel::event::{DummyEvent, EventKind, EventQueue, EventReceiver, EventTrigger};
 use weasel::metric::WriteMetrics;
-use weasel::round::{EndTurn, StartTurn};
+use weasel::round::{EndTurn, StartTurn, Tick};
 use weasel::rules::empty::EmptyAbility;
-use weasel::WeaselError;
 use weasel::{battle_rules, rules::empty::*};
+use weasel::{Server, WeaselError, WeaselResult};
 
 const TEAM_1_ID: u32 = 1;
+const TEAM_2_ID: u32 = 2;
 const CREATURE_1_ID: u32 = 1;
 const ENTITY_1_ID: EntityId<CustomRules> = EntityId::Creature(CREATURE_1_ID);
 const ABILITY_ID: u32 = 1;
@@ -47,6 +53,10 @@ impl<R: BattleRules + 'static> ActorRules<R> for CustomActorRules {
         EndBattle::trigger(&mut event_queue).fire();
         EndTurn::trigger(&mut event_queue).fire();
     }
+
+    fn features(&self) -> Vec<&'static str> {
+        vec!["end_battle_on_activation"]
+    }
 }
 
 battle_rules_with_actor! { CustomActorRules }
@@ -58,16 +68,31 @@ fn end_battle() {
     util::team(&mut server, TEAM_1_ID);
     util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
     assert_eq!(server.battle().phase(), BattlePhase::Started);
+    assert_eq!(server.battle().end_reason(), None);
     // End the battle and checks that new events aren't accepted.
-    assert_eq!(EndBattle::trigger(&mut server).fire().err(), None);
+    assert_eq!(
+        EndBattle::trigger(&mut server)
+            .reason(EndReason::Victory)
+            .fire()
+            .err(),
+        None
+    );
     assert_eq!(
         StartTurn::trigger(&mut server, ENTITY_1_ID)
             .fire()
             .err()
             .map(|e| e.unfold()),
-        Some(WeaselError::BattleEnded)
+        Some(WeaselError::BattleEnded(EndReason::Victory))
+    );
+    assert_eq!(
+        EndBattle::trigger(&mut server)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::BattleEnded(EndReason::Victory))
     );
     assert_eq!(server.battle().phase(), BattlePhase::Ended);
+    assert_eq!(server.battle().end_reason(), Some(EndReason::Victory));
 }
 
 #[test]
@@ -85,7 +110,798 @@ fn end_battle_during_events() {
             .fire()
             .err()
             .map(|e| e.unfold()),
-        Some(WeaselError::BattleEnded)
+        Some(WeaselError::BattleEnded(EndReason::Aborted))
+    );
+    assert_eq!(server.battle().phase(), BattlePhase::Ended);
+}
+
+#[test]
+fn last_changes() {
+    const CREATURE_2_ID: u32 = 2;
+    // Create the scenario.
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    let changes = server.battle().last_changes();
+    assert_eq!(changes.teams().collect::<Vec<_>>(), vec![&TEAM_1_ID]);
+    assert!(changes.entities().next().is_none());
+    // Creating a creature touches the creature, its team and its position.
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    let changes = server.battle().last_changes();
+    assert_eq!(changes.entities().collect::<Vec<_>>(), vec![&ENTITY_1_ID]);
+    assert_eq!(changes.teams().collect::<Vec<_>>(), vec![&TEAM_1_ID]);
+    assert_eq!(changes.positions().collect::<Vec<_>>(), vec![&()]);
+    assert!(!changes.space_altered());
+    // Changes are reset after each event.
+    util::creature(&mut server, CREATURE_2_ID, TEAM_1_ID, ());
+    assert_eq!(
+        server
+            .battle()
+            .last_changes()
+            .entities()
+            .collect::<Vec<_>>(),
+        vec![&EntityId::Creature(CREATURE_2_ID)]
+    );
+    util::dummy(&mut server);
+    assert!(server.battle().last_changes().is_empty());
+}
+
+#[test]
+fn rules_data() {
+    struct AbilityTable {
+        names: Vec<&'static str>,
+        hash: u64,
+    }
+
+    impl StaticData for AbilityTable {
+        fn data_hash(&self) -> u64 {
+            self.hash
+        }
+    }
+
+    struct DataRules {
+        data: Arc<AbilityTable>,
+        version: u32,
+    }
+
+    impl BattleRules for DataRules {
+        type TR = EmptyTeamRules;
+        type CR = EmptyCharacterRules;
+        type AR = EmptyActorRules;
+        type FR = EmptyFightRules;
+        type UR = EmptyUserRules;
+        type SR = EmptySpaceRules;
+        type RR = EmptyRoundsRules;
+        type ER = EmptyEntropyRules;
+        type XR = EmptyReactionRules;
+        type PR = EmptyPlayerRules;
+        type Version = u32;
+        type RulesData = AbilityTable;
+
+        fn team_rules(&self) -> &Self::TR {
+            &EmptyTeamRules {}
+        }
+        fn character_rules(&self) -> &Self::CR {
+            &EmptyCharacterRules {}
+        }
+        fn actor_rules(&self) -> &Self::AR {
+            &EmptyActorRules {}
+        }
+        fn fight_rules(&self) -> &Self::FR {
+            &EmptyFightRules {}
+        }
+        fn user_rules(&self) -> &Self::UR {
+            &EmptyUserRules {}
+        }
+        fn reaction_rules(&self) -> &Self::XR {
+            &EmptyReactionRules {}
+        }
+        fn player_rules(&self) -> &Self::PR {
+            &EmptyPlayerRules {}
+        }
+        fn space_rules(&mut self) -> Self::SR {
+            EmptySpaceRules {}
+        }
+        fn rounds_rules(&mut self) -> Self::RR {
+            EmptyRoundsRules {}
+        }
+        fn entropy_rules(&mut self) -> Self::ER {
+            EmptyEntropyRules::default()
+        }
+        fn version(&self) -> &Self::Version {
+            &self.version
+        }
+        fn rules_data(&self) -> &Arc<Self::RulesData> {
+            &self.data
+        }
+    }
+
+    let data = Arc::new(AbilityTable {
+        names: vec!["fireball", "heal"],
+        hash: 42,
+    });
+    let rules = |data: &Arc<AbilityTable>, version| DataRules {
+        data: data.clone(),
+        version,
+    };
+    // Data is shared by all battles.
+    let server = Server::builder(Battle::builder(rules(&data, 0)).build()).build();
+    let client_battle = Battle::builder(rules(&data, 0)).build();
+    assert!(Arc::ptr_eq(server.battle().rules_data(), &data));
+    assert!(Arc::ptr_eq(client_battle.rules_data(), &data));
+    assert_eq!(server.battle().rules_data().names.len(), 2);
+    // Check handshakes.
+    let handshake = client_battle.handshake();
+    assert_eq!(handshake.data_hash(), 42);
+    assert_eq!(server.verify_handshake(&handshake).err(), None);
+    let other_data = Arc::new(AbilityTable {
+        names: vec!["fireball"],
+        hash: 7,
+    });
+    let client_battle = Battle::builder(rules(&other_data, 0)).build();
+    assert_eq!(
+        server.verify_handshake(&client_battle.handshake()).err(),
+        Some(WeaselError::IncompatibleRulesData(7, 42))
+    );
+    let client_battle = Battle::builder(rules(&data, 1)).build();
+    assert_eq!(
+        server.verify_handshake(&client_battle.handshake()).err(),
+        Some(WeaselError::IncompatibleVersions(1, 0))
+    );
+}
+
+#[test]
+fn describe() {
+    let server = util::server(CustomRules::new());
+    let description = server.battle().describe();
+    assert!(description.name().ends_with("CustomRules"));
+    assert_eq!(description.version(), &0);
+    assert_eq!(description.data_hash(), 0);
+    assert_eq!(
+        description
+            .modules()
+            .map(|module| module.role())
+            .collect::<Vec<_>>(),
+        vec![
+            "team",
+            "character",
+            "actor",
+            "fight",
+            "user",
+            "space",
+            "rounds",
+            "entropy",
+            "reaction",
+            "player"
+        ]
+    );
+    let actor = description.module("actor").unwrap();
+    assert!(actor.name().ends_with("CustomActorRules"));
+    assert_eq!(
+        actor.features().collect::<Vec<_>>(),
+        vec!["end_battle_on_activation"]
+    );
+    let team = description.module("team").unwrap();
+    assert!(team.name().ends_with("EmptyTeamRules"));
+    assert_eq!(team.features().count(), 0);
+}
+
+#[test]
+fn extensions() {
+    use weasel::battle_rules_with_team;
+    use weasel::creature::CreateCreature;
+    use weasel::team::{EntityAddition, Team, TeamRules};
+
+    /// A host service limiting the size of teams.
+    struct Config {
+        team_size: usize,
+    }
+
+    #[derive(Default)]
+    pub struct CustomTeamRules {}
+
+    impl<R: BattleRules> TeamRules<R> for CustomTeamRules {
+        type Id = u32;
+        type Power = EmptyPower;
+        type PowersSeed = ();
+        type Invocation = ();
+        type PowersAlteration = ();
+        type ObjectivesSeed = ();
+        type Objectives = ();
+        type TeamData = ();
+
+        fn allow_new_entity(
+            &self,
+            state: &BattleState<R>,
+            team: &Team<R>,
+            _type: EntityAddition<R>,
+        ) -> WeaselResult<(), R> {
+            let config = state.extensions().get::<Config>().unwrap();
+            if team.creatures().count() < config.team_size {
+                Ok(())
+            } else {
+                Err(WeaselError::UserError("team is full".to_string()))
+            }
+        }
+    }
+
+    battle_rules_with_team! { CustomTeamRules }
+    // Create a battle with the extension.
+    let battle = Battle::builder(CustomRules::new())
+        .extension(Config { team_size: 1 })
+        .build();
+    assert_eq!(battle.extensions().len(), 1);
+    assert!(battle.extensions().contains::<Config>());
+    assert!(!battle.extensions().contains::<u32>());
+    let mut server = Server::builder(battle).build();
+    util::team(&mut server, TEAM_1_ID);
+    // The rules read the team size from the extension.
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    assert_eq!(
+        CreateCreature::trigger(&mut server, 2, TEAM_1_ID, ())
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::NewCreatureUnaccepted(
+            TEAM_1_ID,
+            Box::new(WeaselError::UserError("team is full".to_string()))
+        ))
+    );
+}
+
+#[cfg(feature = "serialization")]
+#[test]
+fn snapshot() {
+    use weasel::actor::Actor;
+    use weasel::character::{AlterStatistics, Character};
+    use weasel::entity::Entities;
+    use weasel::entropy::ResetEntropy;
+    use weasel::metric::system::CREATURES_CREATED;
+    use weasel::round::RoundsRules;
+    use weasel::rules::character::{SimpleCharacter, HEALTH};
+    use weasel::rules::fight::Damage;
+    use weasel::serde::OwnedBattleSnapshot;
+    use weasel::space::Space;
+    use weasel::testing::RecordingEntropy;
+
+    #[derive(Default)]
+    pub struct CustomRoundsRules {}
+
+    impl RoundsRules<CustomRules> for CustomRoundsRules {
+        type RoundsSeed = ();
+        // Number of turns started.
+        type RoundsModel = u32;
+
+        fn generate_model(&self, _seed: &Option<Self::RoundsSeed>) -> Self::RoundsModel {
+            0
+        }
+
+        fn on_start(
+            &self,
+            _entities: &Entities<CustomRules>,
+            _space: &Space<CustomRules>,
+            model: &mut Self::RoundsModel,
+            _actor: &dyn Actor<CustomRules>,
+            entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) {
+            *model += 1;
+            entropy.generate(0, 10);
+        }
+    }
+
+    battle_rules! {
+        EmptyTeamRules,
+        SimpleCharacter,
+        EmptyActorRules,
+        EmptyFightRules,
+        EmptyUserRules,
+        EmptySpaceRules,
+        CustomRoundsRules,
+        RecordingEntropy<i32>
+    }
+
+    let entity_id = EntityId::Creature(CREATURE_1_ID);
+    let health = |battle: &Battle<CustomRules>| {
+        let creature = battle.entities().creature(&CREATURE_1_ID).unwrap();
+        creature.statistic(&HEALTH.to_string()).unwrap().value()
+    };
+    // Create a battle with a long history, a damaged creature and consumed entropy.
+    let mut server = util::server(CustomRules::new());
+    assert_eq!(
+        ResetEntropy::trigger(&mut server)
+            .seed(vec![1, 2, 3])
+            .fire()
+            .err(),
+        None
+    );
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    for _ in 0..10 {
+        util::start_turn(&mut server, &entity_id);
+        util::end_turn(&mut server);
+    }
+    assert_eq!(
+        AlterStatistics::trigger(&mut server, entity_id, Damage::new(HEALTH.to_string(), 30))
+            .fire()
+            .err(),
+        None
+    );
+    util::start_turn(&mut server, &entity_id);
+    assert_eq!(health(server.battle()), 70);
+    assert_eq!(server.battle().entropy().model().recorded().len(), 11);
+    // Save and restore the battle.
+    let snapshot = server.battle().snapshot();
+    assert_eq!(snapshot.next_id(), 25);
+    let json = serde_json::to_string(&snapshot).unwrap();
+    let snapshot: OwnedBattleSnapshot<_> = serde_json::from_str(&json).unwrap();
+    assert_eq!(snapshot.next_id(), 25);
+    let battle = Battle::from_snapshot(Battle::builder(CustomRules::new()), snapshot).unwrap();
+    // The state is restored as it was, not replayed.
+    assert!(battle.history().is_empty());
+    assert_eq!(battle.history().first_id(), 25);
+    assert!(battle.entities().team(&TEAM_1_ID).is_some());
+    assert_eq!(health(&battle), 70);
+    assert_eq!(
+        battle.entropy().model().recorded(),
+        server.battle().entropy().model().recorded()
+    );
+    assert_eq!(*battle.rounds().model(), 11);
+    assert_eq!(battle.rounds().completed_turns(), 10);
+    assert!(battle.rounds().is_acting(&entity_id));
+    assert_eq!(battle.metrics().system_u64(CREATURES_CREATED), Some(1));
+    // The restored battle can continue, picking up the entropy where it was left.
+    let mut server = Server::builder(battle).build();
+    util::end_turn(&mut server);
+    util::start_turn(&mut server, &entity_id);
+    assert_eq!(server.battle().rounds().completed_turns(), 11);
+    assert_eq!(server.battle().history().first_id(), 25);
+    assert_eq!(server.battle().history().len(), 2);
+    assert_eq!(&server.battle().entropy().model().recorded()[10..], &[2, 3]);
+}
+
+#[test]
+fn battle_outcome() {
+    use weasel::battle::BattleOutcome;
+    use weasel::event::EventKind;
+    use weasel::team::{ConcludeObjectives, Conclusion};
+    use weasel::Id;
+
+    #[derive(Default)]
+    struct OutcomeRules {
+        team_rules: EmptyTeamRules,
+        character_rules: EmptyCharacterRules,
+        actor_rules: EmptyActorRules,
+        fight_rules: EmptyFightRules,
+        user_rules: EmptyUserRules,
+        reaction_rules: EmptyReactionRules,
+        version: u32,
+        data: Arc<()>,
+    }
+
+    impl BattleRules for OutcomeRules {
+        type TR = EmptyTeamRules;
+        type CR = EmptyCharacterRules;
+        type AR = EmptyActorRules;
+        type FR = EmptyFightRules;
+        type UR = EmptyUserRules;
+        type SR = EmptySpaceRules;
+        type RR = EmptyRoundsRules;
+        type ER = EmptyEntropyRules;
+        type XR = EmptyReactionRules;
+        type PR = EmptyPlayerRules;
+        type Version = u32;
+        type RulesData = ();
+
+        fn team_rules(&self) -> &Self::TR {
+            &self.team_rules
+        }
+        fn character_rules(&self) -> &Self::CR {
+            &self.character_rules
+        }
+        fn actor_rules(&self) -> &Self::AR {
+            &self.actor_rules
+        }
+        fn fight_rules(&self) -> &Self::FR {
+            &self.fight_rules
+        }
+        fn user_rules(&self) -> &Self::UR {
+            &self.user_rules
+        }
+        fn reaction_rules(&self) -> &Self::XR {
+            &self.reaction_rules
+        }
+        fn player_rules(&self) -> &Self::PR {
+            &EmptyPlayerRules {}
+        }
+        fn space_rules(&mut self) -> Self::SR {
+            EmptySpaceRules {}
+        }
+        fn rounds_rules(&mut self) -> Self::RR {
+            EmptyRoundsRules {}
+        }
+        fn entropy_rules(&mut self) -> Self::ER {
+            EmptyEntropyRules::default()
+        }
+        fn version(&self) -> &Self::Version {
+            &self.version
+        }
+        fn rules_data(&self) -> &Arc<Self::RulesData> {
+            &self.data
+        }
+
+        fn check_battle_end(&self, state: &BattleState<Self>) -> Option<BattleOutcome<Self>> {
+            // The battle ends when at most one team is left undefeated.
+            let teams: Vec<_> = state.entities().teams().collect();
+            let survivors: Vec<_> = teams
+                .iter()
+                .filter(|team| team.conclusion() != Some(Conclusion::Defeat))
+                .map(|team| *team.id())
+                .collect();
+            match survivors.len() {
+                _ if teams.len() < 2 => None,
+                0 => Some(BattleOutcome::Draw),
+                1 => Some(BattleOutcome::Victory(survivors)),
+                _ => None,
+            }
+        }
+    }
+
+    let mut server = Server::builder(Battle::builder(OutcomeRules::default()).build()).build();
+    util::team(&mut server, TEAM_1_ID);
+    util::team(&mut server, TEAM_2_ID);
+    assert_eq!(server.battle().outcome(), None);
+    // Defeating one team ends the battle.
+    assert_eq!(
+        ConcludeObjectives::trigger(&mut server, TEAM_2_ID, Conclusion::Defeat)
+            .fire()
+            .err(),
+        None
     );
     assert_eq!(server.battle().phase(), BattlePhase::Ended);
+    assert_eq!(server.battle().end_reason(), Some(EndReason::Victory));
+    assert_eq!(
+        server.battle().outcome(),
+        Some(&BattleOutcome::Victory(vec![TEAM_1_ID]))
+    );
+    let events = server.battle().history().events();
+    assert_eq!(events.len(), 4);
+    assert_eq!(events[3].kind(), EventKind::EndBattle);
+    // Aborted battles have no outcome.
+    let mut server = Server::builder(Battle::builder(OutcomeRules::default()).build()).build();
+    assert_eq!(EndBattle::trigger(&mut server).fire().err(), None);
+    assert_eq!(server.battle().outcome(), None);
+}
+
+#[test]
+fn state_verification() {
+    use std::hash::Hasher;
+    use weasel::battle::VerifyState;
+    use weasel::event::{EventKind, EventReceiver};
+
+    #[derive(Default)]
+    struct DigestRules {
+        team_rules: EmptyTeamRules,
+        character_rules: EmptyCharacterRules,
+        actor_rules: EmptyActorRules,
+        fight_rules: EmptyFightRules,
+        user_rules: EmptyUserRules,
+        reaction_rules: EmptyReactionRules,
+        version: u32,
+        data: Arc<()>,
+        salt: u64,
+    }
+
+    impl BattleRules for DigestRules {
+        type TR = EmptyTeamRules;
+        type CR = EmptyCharacterRules;
+        type AR = EmptyActorRules;
+        type FR = EmptyFightRules;
+        type UR = EmptyUserRules;
+        type SR = EmptySpaceRules;
+        type RR = EmptyRoundsRules;
+        type ER = EmptyEntropyRules;
+        type XR = EmptyReactionRules;
+        type PR = EmptyPlayerRules;
+        type Version = u32;
+        type RulesData = ();
+
+        fn team_rules(&self) -> &Self::TR {
+            &self.team_rules
+        }
+        fn character_rules(&self) -> &Self::CR {
+            &self.character_rules
+        }
+        fn actor_rules(&self) -> &Self::AR {
+            &self.actor_rules
+        }
+        fn fight_rules(&self) -> &Self::FR {
+            &self.fight_rules
+        }
+        fn user_rules(&self) -> &Self::UR {
+            &self.user_rules
+        }
+        fn reaction_rules(&self) -> &Self::XR {
+            &self.reaction_rules
+        }
+        fn player_rules(&self) -> &Self::PR {
+            &EmptyPlayerRules {}
+        }
+        fn space_rules(&mut self) -> Self::SR {
+            EmptySpaceRules {}
+        }
+        fn rounds_rules(&mut self) -> Self::RR {
+            EmptyRoundsRules {}
+        }
+        fn entropy_rules(&mut self) -> Self::ER {
+            EmptyEntropyRules::default()
+        }
+        fn version(&self) -> &Self::Version {
+            &self.version
+        }
+        fn rules_data(&self) -> &Arc<Self::RulesData> {
+            &self.data
+        }
+
+        fn digest(&self, _: &BattleState<Self>, hasher: &mut dyn Hasher) {
+            hasher.write_u64(self.salt);
+        }
+    }
+
+    let server_with_salt = |salt| {
+        let rules = DigestRules {
+            salt,
+            ..Default::default()
+        };
+        Server::builder(Battle::builder(rules).build()).build()
+    };
+    let mut server = Server::builder(Battle::builder(DigestRules::default()).build())
+        .state_verification(2)
+        .build();
+    let mut mirror = server_with_salt(0);
+    let mut diverged = server_with_salt(1);
+    // Battles with the same state have the same digest.
+    assert_eq!(
+        server.battle().state_digest(),
+        mirror.battle().state_digest()
+    );
+    assert_ne!(
+        server.battle().state_digest(),
+        diverged.battle().state_digest()
+    );
+    // The server verifies the state every two events.
+    util::team(&mut server, TEAM_1_ID);
+    assert_eq!(server.battle().history().len(), 1);
+    util::team(&mut server, TEAM_2_ID);
+    let events: Vec<_> = server.battle().versioned_events(0..3).collect();
+    assert_eq!(events[2].kind(), EventKind::VerifyState);
+    // Battles that diverged detect the mismatch.
+    for event in events.iter().take(2) {
+        assert_eq!(mirror.receive(event.clone()).err(), None);
+        assert_eq!(diverged.receive(event.clone()).err(), None);
+    }
+    assert_eq!(
+        server.battle().state_digest(),
+        mirror.battle().state_digest()
+    );
+    assert_eq!(mirror.receive(events[2].clone()).err(), None);
+    assert_eq!(
+        diverged.receive(events[2].clone()).err(),
+        Some(WeaselError::StateMismatch(
+            server.battle().state_digest(),
+            diverged.battle().state_digest()
+        ))
+    );
+    // The server's state can be verified at any time.
+    assert_eq!(server.verify_state().err(), None);
+    assert_eq!(
+        VerifyState::trigger(&mut server, 0)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::StateMismatch(
+            0,
+            mirror.battle().state_digest()
+        ))
+    );
+}
+
+#[test]
+fn state_digest_values() {
+    use weasel::character::AlterStatistics;
+    use weasel::object::TransferObjectOwnership;
+    use weasel::rules::character::{SimpleCharacter, HEALTH};
+    use weasel::rules::fight::Damage;
+
+    const OBJECT_1_ID: u32 = 1;
+
+    battle_rules! {
+        EmptyTeamRules,
+        SimpleCharacter,
+        EmptyActorRules,
+        EmptyFightRules,
+        EmptyUserRules,
+        EmptySpaceRules,
+        EmptyRoundsRules,
+        EmptyEntropyRules
+    }
+
+    let entity_id = EntityId::Creature(CREATURE_1_ID);
+    let damage = |server: &mut Server<CustomRules>| {
+        assert_eq!(
+            AlterStatistics::trigger(server, entity_id, Damage::new(HEALTH.to_string(), 30))
+                .fire()
+                .err(),
+            None
+        );
+    };
+    let mut first = util::server(CustomRules::new());
+    let mut second = util::server(CustomRules::new());
+    for server in [&mut first, &mut second] {
+        util::team(server, TEAM_1_ID);
+        util::creature(server, CREATURE_1_ID, TEAM_1_ID, ());
+        util::object(server, OBJECT_1_ID, ());
+    }
+    // The digest changes when the value of a statistic changes.
+    damage(&mut first);
+    assert_ne!(
+        first.battle().state_digest(),
+        second.battle().state_digest()
+    );
+    damage(&mut second);
+    assert_eq!(
+        first.battle().state_digest(),
+        second.battle().state_digest()
+    );
+    // The digest changes when the owner of an object changes.
+    assert_eq!(
+        TransferObjectOwnership::trigger(&mut first, OBJECT_1_ID)
+            .team(TEAM_1_ID)
+            .fire()
+            .err(),
+        None
+    );
+    assert_ne!(
+        first.battle().state_digest(),
+        second.battle().state_digest()
+    );
+}
+
+#[test]
+fn setup_phase() {
+    #[derive(Default)]
+    struct PhaseRules {
+        version: u32,
+        data: Arc<()>,
+    }
+
+    impl BattleRules for PhaseRules {
+        type TR = EmptyTeamRules;
+        type CR = EmptyCharacterRules;
+        type AR = EmptyActorRules;
+        type FR = EmptyFightRules;
+        type UR = EmptyUserRules;
+        type SR = EmptySpaceRules;
+        type RR = EmptyRoundsRules;
+        type ER = EmptyEntropyRules;
+        type XR = EmptyReactionRules;
+        type PR = EmptyPlayerRules;
+        type Version = u32;
+        type RulesData = ();
+
+        fn team_rules(&self) -> &Self::TR {
+            &EmptyTeamRules {}
+        }
+        fn character_rules(&self) -> &Self::CR {
+            &EmptyCharacterRules {}
+        }
+        fn actor_rules(&self) -> &Self::AR {
+            &EmptyActorRules {}
+        }
+        fn fight_rules(&self) -> &Self::FR {
+            &EmptyFightRules {}
+        }
+        fn user_rules(&self) -> &Self::UR {
+            &EmptyUserRules {}
+        }
+        fn reaction_rules(&self) -> &Self::XR {
+            &EmptyReactionRules {}
+        }
+        fn player_rules(&self) -> &Self::PR {
+            &EmptyPlayerRules {}
+        }
+        fn space_rules(&mut self) -> Self::SR {
+            EmptySpaceRules {}
+        }
+        fn rounds_rules(&mut self) -> Self::RR {
+            EmptyRoundsRules {}
+        }
+        fn entropy_rules(&mut self) -> Self::ER {
+            EmptyEntropyRules::default()
+        }
+        fn version(&self) -> &Self::Version {
+            &self.version
+        }
+        fn rules_data(&self) -> &Arc<Self::RulesData> {
+            &self.data
+        }
+        fn allow_in_phase(
+            &self,
+            _state: &BattleState<Self>,
+            kind: EventKind,
+            phase: BattlePhase,
+        ) -> bool {
+            // No summons once the fighting has started.
+            match phase {
+                BattlePhase::Setup => kind != EventKind::StartTurn,
+                _ => kind != EventKind::CreateCreature,
+            }
+        }
+    }
+
+    let entity_1_id = EntityId::Creature(CREATURE_1_ID);
+    let battle = Battle::builder(PhaseRules::default()).setup_phase().build();
+    let mut server = Server::builder(battle).build();
+    assert_eq!(server.battle().phase(), BattlePhase::Setup);
+    // Prepare the battle.
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    assert_eq!(
+        StartTurn::trigger(&mut server, entity_1_id)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::WrongBattlePhase(
+            EventKind::StartTurn,
+            BattlePhase::Setup
+        ))
+    );
+    // Start the battle.
+    assert_eq!(StartBattle::trigger(&mut server).fire().err(), None);
+    assert_eq!(server.battle().phase(), BattlePhase::Started);
+    assert_eq!(
+        StartBattle::trigger(&mut server)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::WrongBattlePhase(
+            EventKind::StartBattle,
+            BattlePhase::Started
+        ))
+    );
+    assert_eq!(
+        CreateCreature::trigger(&mut server, 2, TEAM_1_ID, ())
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::WrongBattlePhase(
+            EventKind::CreateCreature,
+            BattlePhase::Started
+        ))
+    );
+    util::start_turn(&mut server, &entity_1_id);
+    util::end_turn(&mut server);
+    assert_eq!(Tick::trigger(&mut server, 3).fire().err(), None);
+    util::start_turn(&mut server, &entity_1_id);
+    // Bootstrap events start the battle before any event restricted to the started phase.
+    let events = server.bootstrap_events_for_new_client();
+    let kinds: Vec<_> = events.iter().map(|e| e.kind()).collect();
+    let start = kinds.iter().position(|k| *k == EventKind::StartBattle);
+    let tick = kinds.iter().position(|k| *k == EventKind::Tick);
+    assert!(start.is_some() && start < tick);
+    // Synthetic events are not bound to the phase, thus the summons can be replayed.
+    let battle = Battle::builder(PhaseRules::default()).setup_phase().build();
+    let mut new_server = Server::builder(battle).build();
+    for event in events {
+        assert_eq!(new_server.receive(event).err(), None);
+    }
+    assert_eq!(new_server.battle().phase(), BattlePhase::Started);
+    assert_eq!(new_server.battle().rounds().ticks(), 3);
+    assert!(new_server
+        .battle()
+        .entities()
+        .creature(&CREATURE_1_ID)
+        .is_some());
+    // Battles without a setup phase begin in the started phase.
+    let server = util::server(CustomRules::new());
+    assert_eq!(server.battle().phase(), BattlePhase::Started);
 }
@@ -0,0 +1,139 @@
+use weasel::battle::{BattleController, BattleRules};
+use weasel::campaign::{Campaign, CampaignRules, CreatureTemplate, Roster, TeamTemplate};
+use weasel::character::AlterStatistics;
+use weasel::creature::Creature;
+use weasel::entity::EntityId;
+use weasel::event::EventTrigger;
+use weasel::rules::character::{SimpleCharacter, SimpleCharacterSeed, HEALTH};
+use weasel::rules::fight::Damage;
+use weasel::{
+    battle_rules, battle_rules_with_character, rules::empty::*, Character, EndBattle, WeaselError,
+};
+
+const TEAM_1_ID: u32 = 1;
+const CREATURE_1_ID: u32 = 1;
+const CREATURE_2_ID: u32 = 2;
+const CREATURE_3_ID: u32 = 3;
+const MIN_HEALTH: i32 = 20;
+
+battle_rules_with_character! { SimpleCharacter }
+
+struct CustomCampaignRules {}
+
+impl CampaignRules<CustomRules> for CustomCampaignRules {
+    fn import(
+        &self,
+        template: &CreatureTemplate<CustomRules>,
+    ) -> Option<CreatureTemplate<CustomRules>> {
+        // Creatures too wounded stay at home.
+        match template.statistics_seed() {
+            Some(seed) if seed.health() < MIN_HEALTH => None,
+            _ => Some(template.clone()),
+        }
+    }
+
+    fn export(
+        &self,
+        creature: &Creature<CustomRules>,
+        template: &mut CreatureTemplate<CustomRules>,
+    ) {
+        // Carry the remaining health over to the next battle.
+        let health = creature.statistic(&HEALTH.to_string()).unwrap().value();
+        template.set_statistics_seed(Some(SimpleCharacterSeed::new(health)));
+    }
+}
+
+fn health(roster: &Roster<CustomRules>, id: u32) -> i32 {
+    roster
+        .creature(&id)
+        .unwrap()
+        .statistics_seed()
+        .as_ref()
+        .unwrap()
+        .health()
+}
+
+fn damage(server: &mut weasel::Server<CustomRules>, id: u32, amount: i64) {
+    assert_eq!(
+        AlterStatistics::trigger(
+            server,
+            EntityId::Creature(id),
+            Damage::new(HEALTH.to_string(), amount)
+        )
+        .fire()
+        .err(),
+        None
+    );
+}
+
+#[test]
+fn campaign_lifecycle() {
+    // Create a roster.
+    let mut roster = Roster::new();
+    assert_eq!(
+        roster
+            .add_creature(CreatureTemplate::new(CREATURE_1_ID, TEAM_1_ID, ()))
+            .err(),
+        Some(WeaselError::TeamNotFound(TEAM_1_ID))
+    );
+    assert!(roster.add_team(TeamTemplate::new(TEAM_1_ID)).is_none());
+    for (id, health) in &[
+        (CREATURE_1_ID, 50),
+        (CREATURE_2_ID, 30),
+        (CREATURE_3_ID, 10),
+    ] {
+        let mut template = CreatureTemplate::new(*id, TEAM_1_ID, ());
+        template.set_statistics_seed(Some(SimpleCharacterSeed::new(*health)));
+        assert_eq!(roster.add_creature(template).err(), None);
+    }
+    let mut campaign = Campaign::new(roster, CustomCampaignRules {});
+    // Start a battle. The third creature isn't imported.
+    let mut server = campaign.start_battle(CustomRules::new()).unwrap();
+    let entities = server.battle().entities();
+    assert_eq!(entities.teams().count(), 1);
+    assert_eq!(entities.creatures().count(), 2);
+    assert!(entities.creature(&CREATURE_3_ID).is_none());
+    // Fight. The second creature dies.
+    damage(&mut server, CREATURE_1_ID, 20);
+    damage(&mut server, CREATURE_2_ID, 30);
+    // The battle can't be concluded while in progress.
+    assert_eq!(
+        campaign.end_battle(server.battle()).err(),
+        Some(WeaselError::BattleInProgress)
+    );
+    assert_eq!(EndBattle::trigger(&mut server).fire().err(), None);
+    // Survivors are written back into the roster.
+    assert_eq!(
+        campaign.end_battle(server.battle()).unwrap(),
+        vec![CREATURE_2_ID]
+    );
+    assert_eq!(campaign.battles(), 1);
+    let roster = campaign.roster();
+    assert_eq!(roster.creatures().count(), 2);
+    assert_eq!(health(roster, CREATURE_1_ID), 30);
+    assert_eq!(health(roster, CREATURE_3_ID), 10);
+    // Spawn the next battle into an existing server.
+    let mut server = util::server(CustomRules::new());
+    assert_eq!(campaign.spawn(&mut server).err(), None);
+    let creature = server.battle().entities().creature(&CREATURE_1_ID).unwrap();
+    assert_eq!(creature.statistic(&HEALTH.to_string()).unwrap().value(), 30);
+    // Removing a team removes its creatures too.
+    let mut roster = campaign.into_roster();
+    assert!(roster.remove_team(&TEAM_1_ID).is_some());
+    assert_eq!(roster.creatures().count(), 0);
+}
+
+#[cfg(feature = "serialization")]
+#[test]
+fn roster_serde() {
+    let mut roster: Roster<CustomRules> = Roster::new();
+    roster.add_team(TeamTemplate::new(TEAM_1_ID));
+    let mut template = CreatureTemplate::new(CREATURE_1_ID, TEAM_1_ID, ());
+    template.set_statistics_seed(Some(SimpleCharacterSeed::new(42)));
+    roster.add_creature(template).unwrap();
+    // Save and restore the roster.
+    let json = serde_json::to_string(&roster).unwrap();
+    let roster: Roster<CustomRules> = serde_json::from_str(&json).unwrap();
+    assert_eq!(roster.teams().count(), 1);
+    assert_eq!(health(&roster, CREATURE_1_ID), 42);
+}
@@ -1,9 +1,19 @@
-use weasel::battle::{BattleController, BattleRules};
-use weasel::character::{AlterStatistics, Character};
-use weasel::entity::EntityId;
-use weasel::event::EventTrigger;
+use weasel::battle::{BattleController, BattleRules, BattleState};
+use weasel::character::{
+    AlterStatistics, Character, CharacterRules, ThresholdCrossing, ThresholdLevel,
+};
+use weasel::creature::CreateCreature;
+use weasel::entity::{EntityId, RemoveEntity, Transmutation};
+use weasel::entropy::Entropy;
+use weasel::event::{EventKind, EventQueue, EventTrigger};
+use weasel::fight::ApplyImpact;
+use weasel::metric::WriteMetrics;
+use weasel::rules::character::{SimpleCharacter, SimpleCharacterSeed, DEFAULT_HEALTH, HEALTH};
+use weasel::rules::fight::{SimpleFight, SimpleImpact};
+use weasel::rules::statistic::SimpleStatistic;
+use weasel::rules::status::SimplePotency;
 use weasel::status::InflictStatus;
-use weasel::{battle_rules, rules::empty::*};
+use weasel::{battle_rules, battle_rules_with_character, rules::empty::*, Server};
 
 const TEAM_1_ID: u32 = 1;
 const CREATURE_1_ID: u32 = 1;
@@ -41,3 +51,197 @@ fn default_works() {
         0
     );
 }
+
+#[test]
+fn threshold_crossed() {
+    #[derive(Default)]
+    pub struct CustomCharacterRules {}
+
+    impl CharacterRules<CustomRules> for CustomCharacterRules {
+        type CreatureId = u32;
+        type ObjectId = ();
+        type Statistic = SimpleStatistic<u32, i32>;
+        type StatisticsSeed = ();
+        type StatisticsAlteration = i32;
+        type Status = EmptyStatus;
+        type StatusesAlteration = ();
+
+        fn generate_statistics(
+            &self,
+            _seed: &Option<Self::StatisticsSeed>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) -> Box<dyn Iterator<Item = Self::Statistic>> {
+            let v = vec![SimpleStatistic::new(STAT_ID, 100)];
+            Box::new(v.into_iter())
+        }
+
+        fn alter_statistics(
+            &self,
+            character: &mut dyn Character<CustomRules>,
+            alteration: &Self::StatisticsAlteration,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) -> Option<Transmutation> {
+            character.statistic_mut(&STAT_ID).unwrap().add(*alteration);
+            None
+        }
+
+        fn threshold_level(
+            &self,
+            _character: &dyn Character<CustomRules>,
+            statistic: &Self::Statistic,
+        ) -> Option<ThresholdLevel> {
+            // Three levels: dead, critical and healthy.
+            match statistic.value() {
+                0 => Some(0),
+                x if x < 25 => Some(1),
+                _ => Some(2),
+            }
+        }
+
+        fn on_threshold_crossed(
+            &self,
+            _state: &BattleState<CustomRules>,
+            character: &dyn Character<CustomRules>,
+            crossing: ThresholdCrossing<CustomRules>,
+            event_queue: &mut Option<EventQueue<CustomRules>>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) {
+            assert_eq!(*crossing.statistic_id, STAT_ID);
+            if crossing.current == Some(0) {
+                RemoveEntity::trigger(event_queue, *character.entity_id()).fire();
+            }
+        }
+    }
+
+    const STAT_ID: u32 = 1;
+    const ENTITY_1_ID: EntityId<CustomRules> = EntityId::Creature(CREATURE_1_ID);
+
+    battle_rules_with_character! { CustomCharacterRules }
+    // Create a server with a creature.
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    // Alterations that don't cross any threshold don't produce further events.
+    let events_count = server.battle().history().len();
+    assert_eq!(
+        AlterStatistics::trigger(&mut server, ENTITY_1_ID, -10)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(server.battle().history().len(), events_count + 1);
+    // Reduce the statistic to zero, the creature should be removed.
+    assert_eq!(
+        AlterStatistics::trigger(&mut server, ENTITY_1_ID, -100)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(
+        server
+            .battle()
+            .history()
+            .events()
+            .iter()
+            .last()
+            .unwrap()
+            .kind(),
+        EventKind::RemoveCreature
+    );
+    assert_eq!(server.battle().entities().creatures().count(), 0);
+}
+
+#[test]
+fn simple_character() {
+    const CREATURE_2_ID: u32 = 2;
+    const ATTACK: &str = "attack";
+
+    battle_rules! {
+        EmptyTeamRules,
+        SimpleCharacter,
+        EmptyActorRules,
+        SimpleFight<CustomRules>,
+        EmptyUserRules,
+        EmptySpaceRules,
+        EmptyRoundsRules,
+        EmptyEntropyRules
+    }
+
+    let entity_1_id = EntityId::Creature(CREATURE_1_ID);
+    let entity_2_id = EntityId::Creature(CREATURE_2_ID);
+    let health = |server: &Server<CustomRules>, id| {
+        let creature = server.battle().entities().creature(&id).unwrap();
+        creature.statistic(&HEALTH.to_string()).unwrap().value()
+    };
+    // Create a server with two creatures.
+    let mut rules = CustomRules::new();
+    rules.character_rules = SimpleCharacter::default().status(STATUS_1_ID, 3, Some(1));
+    rules.fight_rules = SimpleFight::new(ATTACK.to_string(), String::new(), HEALTH.to_string());
+    let mut server = util::server(rules);
+    util::team(&mut server, TEAM_1_ID);
+    assert_eq!(
+        CreateCreature::trigger(&mut server, CREATURE_1_ID, TEAM_1_ID, ())
+            .statistics_seed(SimpleCharacterSeed::new(10).statistic(ATTACK, 5))
+            .fire()
+            .err(),
+        None
+    );
+    util::creature(&mut server, CREATURE_2_ID, TEAM_1_ID, ());
+    assert_eq!(health(&server, CREATURE_2_ID), DEFAULT_HEALTH);
+    // Damage the second creature.
+    assert_eq!(
+        ApplyImpact::trigger(
+            &mut server,
+            SimpleImpact::with_source(entity_1_id, entity_2_id, 15)
+        )
+        .fire()
+        .err(),
+        None
+    );
+    assert_eq!(health(&server, CREATURE_2_ID), DEFAULT_HEALTH - 20);
+    // Statuses are generated from their template.
+    assert_eq!(
+        InflictStatus::trigger(&mut server, entity_1_id, STATUS_1_ID)
+            .fire()
+            .err(),
+        None
+    );
+    util::start_turn(&mut server, &entity_1_id);
+    util::end_turn(&mut server);
+    assert_eq!(health(&server, CREATURE_1_ID), 7);
+    let creature = server.battle().entities().creature(&CREATURE_1_ID).unwrap();
+    assert!(creature.status(&STATUS_1_ID).is_none());
+    // A potency overrides the template's effect and duration.
+    assert_eq!(
+        InflictStatus::trigger(&mut server, entity_2_id, STATUS_1_ID)
+            .potency(SimplePotency::lasting(1, 2))
+            .fire()
+            .err(),
+        None
+    );
+    let creature = server.battle().entities().creature(&CREATURE_2_ID).unwrap();
+    let status = creature.status(&STATUS_1_ID).unwrap();
+    assert_eq!(status.potency(), SimplePotency::lasting(1, 2));
+    util::start_turn(&mut server, &entity_2_id);
+    util::end_turn(&mut server);
+    util::start_turn(&mut server, &entity_2_id);
+    util::end_turn(&mut server);
+    assert_eq!(health(&server, CREATURE_2_ID), DEFAULT_HEALTH - 22);
+    let creature = server.battle().entities().creature(&CREATURE_2_ID).unwrap();
+    assert!(creature.status(&STATUS_1_ID).is_none());
+    // Characters without health left are removed.
+    assert_eq!(
+        ApplyImpact::trigger(&mut server, SimpleImpact::new(entity_1_id, 7))
+            .fire()
+            .err(),
+        None
+    );
+    assert!(server
+        .battle()
+        .entities()
+        .creature(&CREATURE_1_ID)
+        .is_none());
+}
@@ -1,14 +1,21 @@
 use std::ops::Range;
 use std::sync::{Arc, Mutex};
-use weasel::battle::{Battle, BattleController, BattleRules};
+use weasel::battle::{Battle, BattleController, BattlePhase, BattleRules, EventCallback};
+use weasel::client::ConnectionState;
+use weasel::creature::{CreateCreature, RemoveCreature, TransferOwnership};
 use weasel::entity::EntityId;
+use weasel::error::WeaselErrorType;
 use weasel::event::{
-    ClientEventPrototype, ClientSink, DummyEvent, EventKind, EventReceiver, EventServer, EventSink,
-    EventSinkId, EventTrigger, ServerSink, VersionedEventWrapper,
+    ClientEventPrototype, ClientSink, DummyEvent, EventId, EventKind, EventPrototype,
+    EventReceiver, EventServer, EventSink, EventSinkId, EventTrigger, EventWrapper, ServerSink,
+    SinkErrorPolicy, VersionedEventWrapper,
 };
+use weasel::interceptor::EventInterceptor;
 use weasel::player::PlayerId;
 use weasel::round::StartTurn;
-use weasel::team::CreateTeam;
+use weasel::server::{Broadcast, ResponseWindow, ShutdownPolicy, ShutdownReason};
+use weasel::team::{CreateTeam, Relation, RenameTeam, SetRelations, TeamId};
+use weasel::visibility::{RedactionPolicy, RevealEntity, VisibilityRules};
 use weasel::{battle_rules, rules::empty::*};
 use weasel::{Client, Server};
 use weasel::{WeaselError, WeaselResult};
@@ -17,7 +24,10 @@ use weasel::{WeaselError, WeaselResult};
 mod helper;
 
 const TEAM_1_ID: u32 = 1;
+const TEAM_2_ID: u32 = 2;
+const TEAM_3_ID: u32 = 3;
 const CREATURE_1_ID: u32 = 1;
+const CREATURE_2_ID: u32 = 2;
 const ENTITY_1_ID: EntityId<CustomRules> = EntityId::Creature(CREATURE_1_ID);
 const SERVER_1_ID: EventSinkId = 1;
 const CLIENT_1_ID: EventSinkId = 1;
@@ -216,6 +226,61 @@ fn send_events() {
     );
 }
 
+#[test]
+fn event_metadata() {
+    // Create a server and a client.
+    let server = Arc::new(Mutex::new(util::server(CustomRules::new())));
+    let server_sink = TestServerSink::new(SERVER_1_ID, server.clone());
+    let client = Arc::new(Mutex::new(util::client(CustomRules::new(), server_sink)));
+    let mut client_sink = TestClientSink::new(CLIENT_1_ID, client.clone());
+    add_sink!(server, client_sink);
+    // Fire events with metadata from the server and from the client.
+    assert_eq!(
+        DummyEvent::trigger(&mut *server.lock().unwrap())
+            .metadata("server")
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(client_sink.receive().err(), None);
+    assert_eq!(
+        DummyEvent::trigger(&mut *client.lock().unwrap())
+            .metadata(vec![1, 2, 3])
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(client_sink.receive().err(), None);
+    util::dummy(&mut *server.lock().unwrap());
+    assert_eq!(client_sink.receive().err(), None);
+    // Metadata is stored in the history of both server and client.
+    let check = |events: &[EventWrapper<CustomRules>]| {
+        assert_eq!(events[0].metadata(), Some(&b"server"[..]));
+        assert_eq!(events[1].metadata(), Some(&[1, 2, 3][..]));
+        assert_eq!(events[2].metadata(), None);
+    };
+    check(events!(server));
+    check(events!(client));
+    // Metadata survives serialization.
+    #[cfg(feature = "serialization")]
+    {
+        use weasel::serde::{FlatClientEvent, FlatVersionedEvent};
+
+        let event = events!(server)[0].clone().version(0);
+        let json = serde_json::to_string(&FlatVersionedEvent::from(event)).unwrap();
+        let event: FlatVersionedEvent<CustomRules> = serde_json::from_str(&json).unwrap();
+        let event: VersionedEventWrapper<CustomRules> = event.into();
+        assert_eq!(event.metadata(), Some(&b"server"[..]));
+        let mut prototype = DummyEvent::<CustomRules>::trigger(&mut ()).prototype();
+        prototype.set_metadata(Some(vec![4]));
+        let prototype = prototype.client_prototype(0, None);
+        let json = serde_json::to_string(&FlatClientEvent::from(prototype)).unwrap();
+        let prototype: FlatClientEvent<CustomRules> = serde_json::from_str(&json).unwrap();
+        let prototype: ClientEventPrototype<CustomRules> = prototype.into();
+        assert_eq!(prototype.metadata(), Some(&[4][..]));
+    }
+}
+
 #[test]
 fn send_errors() {
     // Create a server.
@@ -516,6 +581,92 @@ fn rights() {
     );
 }
 
+#[test]
+fn fine_grained_rights() {
+    const ENTITY_2_ID: EntityId<CustomRules> = EntityId::Creature(CREATURE_2_ID);
+    // Create a server with auth and two creatures.
+    let mut server = Server::builder(Battle::builder(CustomRules::new()).build())
+        .enforce_authentication()
+        .build();
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    util::creature(&mut server, CREATURE_2_ID, TEAM_1_ID, ());
+    let start_turn = |server: &mut Server<CustomRules>, entity_id| {
+        let event = StartTurn::trigger(&mut (), entity_id)
+            .prototype()
+            .client_prototype(0, Some(PLAYER_1_ID));
+        server.process_client(event)
+    };
+    // Check that rights for non existing entities are rejected.
+    assert_eq!(
+        server
+            .rights_mut()
+            .add_entity(PLAYER_1_ID, &EntityId::Creature(99))
+            .err(),
+        Some(WeaselError::EntityNotFound(EntityId::Creature(99)))
+    );
+    // Grant rights to a single creature.
+    assert_eq!(
+        server
+            .rights_mut()
+            .add_entity(PLAYER_1_ID, &ENTITY_1_ID)
+            .err(),
+        None
+    );
+    assert!(server.rights().check_entity(PLAYER_1_ID, &ENTITY_1_ID));
+    assert_eq!(
+        start_turn(&mut server, ENTITY_2_ID).err(),
+        Some(WeaselError::AuthenticationError(
+            Some(PLAYER_1_ID),
+            TEAM_1_ID
+        ))
+    );
+    assert_eq!(start_turn(&mut server, ENTITY_1_ID).err(), None);
+    util::end_turn(&mut server);
+    // Restrict the player to some kinds of events.
+    server
+        .rights_mut()
+        .add_kind(PLAYER_1_ID, EventKind::EndTurn);
+    assert_eq!(
+        start_turn(&mut server, ENTITY_1_ID).err(),
+        Some(WeaselError::EventKindNotAllowed(
+            PLAYER_1_ID,
+            EventKind::StartTurn
+        ))
+    );
+    server
+        .rights_mut()
+        .add_kind(PLAYER_1_ID, EventKind::StartTurn);
+    assert_eq!(start_turn(&mut server, ENTITY_1_ID).err(), None);
+    util::end_turn(&mut server);
+    server
+        .rights_mut()
+        .remove_kind(PLAYER_1_ID, EventKind::StartTurn);
+    assert!(!server
+        .rights()
+        .check_kind(PLAYER_1_ID, EventKind::StartTurn));
+    // Removing the last allowed kind doesn't lift the restriction.
+    server
+        .rights_mut()
+        .remove_kind(PLAYER_1_ID, EventKind::EndTurn);
+    assert!(!server.rights().check_kind(PLAYER_1_ID, EventKind::EndTurn));
+    assert!(!server
+        .rights()
+        .check_kind(PLAYER_1_ID, EventKind::StartTurn));
+    server.rights_mut().clear_kinds(PLAYER_1_ID);
+    assert!(server
+        .rights()
+        .check_kind(PLAYER_1_ID, EventKind::StartTurn));
+    // Rights are forgotten when the entity is removed.
+    assert_eq!(
+        RemoveCreature::trigger(&mut server, CREATURE_1_ID)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(server.rights().get_entities().count(), 0);
+}
+
 #[test]
 fn server_only_events() {
     // Create a client and a server.
@@ -634,3 +785,1424 @@ fn client_server_serde() {
         ]
     );
 }
+
+#[test]
+fn response_window() {
+    // Fire a dummy event each time a team is created.
+    let callback: EventCallback<CustomRules> = Box::new(|event, _, event_queue| {
+        if event.kind() == EventKind::CreateTeam {
+            DummyEvent::trigger(event_queue).fire();
+        }
+    });
+    let reaction = || {
+        DummyEvent::<CustomRules>::trigger(&mut ())
+            .prototype()
+            .client_prototype(0, None)
+    };
+    // Create a server which opens a response window after each team creation.
+    let battle = Battle::builder(CustomRules::new())
+        .event_callback(callback)
+        .build();
+    let window = ResponseWindow::new(vec![EventKind::CreateTeam]).max_reactions(2);
+    let mut server = Server::builder(battle).response_window(window).build();
+    util::team(&mut server, TEAM_1_ID);
+    // The derived event is held.
+    assert_eq!(server.open_response_window(), Some(0));
+    assert_eq!(server.battle().history().len(), 1);
+    // Reactions are processed first.
+    assert_eq!(server.process_client(reaction()).err(), None);
+    assert_eq!(server.battle().history().len(), 2);
+    assert_eq!(server.battle().history().events()[1].origin(), None);
+    // The window closes once the maximum number of reactions is reached.
+    assert_eq!(server.process_client(reaction()).err(), None);
+    assert_eq!(server.open_response_window(), None);
+    let events = server.battle().history().events();
+    assert_eq!(events.len(), 4);
+    assert_eq!(events[3].kind(), EventKind::DummyEvent);
+    assert_eq!(events[3].origin(), Some(0));
+    // Create a server which accepts only a given kind of reactions.
+    let callback: EventCallback<CustomRules> = Box::new(|event, _, event_queue| {
+        if event.kind() == EventKind::CreateTeam {
+            DummyEvent::trigger(event_queue).fire();
+        }
+    });
+    let battle = Battle::builder(CustomRules::new())
+        .event_callback(callback)
+        .build();
+    let window =
+        ResponseWindow::new(vec![EventKind::CreateTeam]).reactions(vec![EventKind::StartTurn]);
+    let mut server = Server::builder(battle).response_window(window).build();
+    util::team(&mut server, TEAM_1_ID);
+    assert_eq!(
+        server.process_client(reaction()).err(),
+        Some(WeaselError::ResponseWindowOpen)
+    );
+    // Close the window manually.
+    assert_eq!(server.close_response_window().err(), None);
+    assert_eq!(server.open_response_window(), None);
+    assert_eq!(server.battle().history().len(), 2);
+    assert_eq!(server.process_client(reaction()).err(), None);
+}
+
+#[test]
+fn bootstrap_new_client() {
+    // Create a server with a long history.
+    let server = Server::builder(Battle::builder(CustomRules::new()).build())
+        .bootstrap_callback(Box::new(|_, queue| {
+            DummyEvent::trigger(queue).fire();
+        }))
+        .build();
+    let server = Arc::new(Mutex::new(server));
+    {
+        let server = &mut *server.lock().unwrap();
+        util::team(server, TEAM_1_ID);
+        util::team(server, TEAM_2_ID);
+        util::creature(server, CREATURE_1_ID, TEAM_1_ID, ());
+        util::creature(server, CREATURE_2_ID, TEAM_2_ID, ());
+        for _ in 0..10 {
+            util::start_turn(server, &ENTITY_1_ID);
+            util::end_turn(server);
+        }
+        assert_eq!(
+            RenameTeam::trigger(server, TEAM_2_ID, TEAM_3_ID)
+                .fire()
+                .err(),
+            None
+        );
+        assert_eq!(
+            SetRelations::trigger(server, &[(TEAM_1_ID, TEAM_3_ID, Relation::Ally)])
+                .fire()
+                .err(),
+            None
+        );
+        assert_eq!(
+            RemoveCreature::trigger(server, CREATURE_2_ID).fire().err(),
+            None
+        );
+        assert_eq!(
+            TransferOwnership::trigger(server, CREATURE_1_ID)
+                .owner(PLAYER_1_ID)
+                .fire()
+                .err(),
+            None
+        );
+        assert_eq!(
+            RevealEntity::trigger(server, ENTITY_1_ID, TEAM_3_ID)
+                .fire()
+                .err(),
+            None
+        );
+        util::start_turn(server, &ENTITY_1_ID);
+    }
+    // Synthesize the bootstrap events.
+    let events = server.lock().unwrap().bootstrap_events_for_new_client();
+    let history_len = server.lock().unwrap().battle().history().len();
+    assert!((events.len() as u32) < history_len);
+    assert!(events.iter().all(|event| event.synthetic()));
+    assert!(events.iter().all(|event| event.origin().is_none()));
+    assert_eq!(events.last().unwrap().id(), history_len - 1);
+    // The bootstrap callback's events come before the turn.
+    assert_eq!(events[events.len() - 2].kind(), EventKind::DummyEvent);
+    // Bootstrap a new client.
+    let server_sink = TestServerSink::new(SERVER_1_ID, server.clone());
+    let client = Arc::new(Mutex::new(util::client(CustomRules::new(), server_sink)));
+    for event in events {
+        assert_eq!(client.lock().unwrap().receive(event).err(), None);
+    }
+    {
+        let client = client.lock().unwrap();
+        let entities = client.battle().entities();
+        assert_eq!(entities.teams().count(), 2);
+        assert!(entities.team(&TEAM_3_ID).is_some());
+        assert_eq!(
+            entities.relation(&TEAM_1_ID, &TEAM_3_ID),
+            Some(Relation::Ally)
+        );
+        assert_eq!(entities.creatures().count(), 1);
+        assert_eq!(
+            entities.creature(&CREATURE_1_ID).unwrap().owner(),
+            Some(PLAYER_1_ID)
+        );
+        assert!(client
+            .battle()
+            .visibility()
+            .is_revealed(&TEAM_3_ID, &ENTITY_1_ID));
+        assert_eq!(
+            client.battle().rounds().state(),
+            server.lock().unwrap().battle().rounds().state()
+        );
+        let history = client.battle().history();
+        assert_eq!(history.len(), history.events().len() as u32);
+        assert!(history.event(0).is_none());
+        assert!(history.event(history.first_id()).is_some());
+    }
+    // Connect the client and continue the battle.
+    let mut client_sink = TestClientSink::new(CLIENT_1_ID, client.clone());
+    add_sink!(server, client_sink);
+    util::end_turn(&mut *server.lock().unwrap());
+    assert_eq!(client_sink.receive().err(), None);
+    assert_eq!(
+        client
+            .lock()
+            .unwrap()
+            .battle()
+            .history()
+            .events()
+            .last()
+            .unwrap()
+            .id(),
+        history_len
+    );
+    // Client can forward only the events in its own timeline.
+    let first_id = client.lock().unwrap().battle().history().first_id();
+    let other_sink = TestClientSink::new(CLIENT_2_ID, client.clone());
+    assert_eq!(
+        client
+            .lock()
+            .unwrap()
+            .client_sinks_mut()
+            .add_sink_from(Box::new(other_sink.clone()), 0)
+            .err(),
+        Some(WeaselError::InvalidEventRange(
+            0..history_len + 1,
+            history_len + 1
+        ))
+    );
+    assert_eq!(
+        client
+            .lock()
+            .unwrap()
+            .client_sinks_mut()
+            .add_sink_from(Box::new(other_sink.clone()), first_id)
+            .err(),
+        None
+    );
+    assert_eq!(
+        other_sink.buffer.lock().unwrap().len() as u32,
+        history_len + 1 - first_id
+    );
+    // Synthetic events survive serialization.
+    #[cfg(feature = "serialization")]
+    {
+        let history_json = helper::history_as_json(client.lock().unwrap().battle());
+        let mut client = util::client(CustomRules::new(), TestServerSink::new(SERVER_1_ID, server));
+        helper::load_json_history(&mut client, history_json);
+        assert_eq!(client.battle().history().first_id(), first_id);
+    }
+    // A server with a short history sends its full history.
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    let events = server.bootstrap_events_for_new_client();
+    assert_eq!(events.len(), 1);
+    assert!(!events[0].synthetic());
+}
+
+#[test]
+fn bootstrap_progress() {
+    use weasel::ability::ActivateAbility;
+    use weasel::actor::{Actor, ActorRules};
+    use weasel::entity::Entities;
+    use weasel::entropy::{Entropy, ResetEntropy};
+    use weasel::event::EventQueue;
+    use weasel::metric::WriteMetrics;
+    use weasel::round::{EndRound, RoundsRules};
+    use weasel::rules::empty::EmptyAbility;
+    use weasel::space::Space;
+    use weasel::team::{Team, TeamRules};
+    use weasel::testing::RecordingEntropy;
+
+    const ABILITY_ID: u32 = 1;
+    const ENTITY_1_ID: EntityId<CustomRules> = EntityId::Creature(CREATURE_1_ID);
+
+    #[derive(Default)]
+    pub struct CustomTeamRules {}
+
+    impl TeamRules<CustomRules> for CustomTeamRules {
+        type Id = u32;
+        type Power = EmptyPower;
+        type PowersSeed = ();
+        type Invocation = ();
+        type PowersAlteration = ();
+        type ObjectivesSeed = ();
+        type Objectives = ();
+        // Number of rounds started.
+        type TeamData = u32;
+
+        fn on_round_start(
+            &self,
+            team: &mut Team<CustomRules>,
+            _event_queue: &mut Option<EventQueue<CustomRules>>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) {
+            *team.data_mut() += 1;
+        }
+    }
+
+    #[derive(Default)]
+    pub struct CustomActorRules {}
+
+    impl ActorRules<CustomRules> for CustomActorRules {
+        type Ability = EmptyAbility;
+        type AbilitiesSeed = ();
+        type Activation = ();
+        type AbilitiesAlteration = ();
+
+        fn generate_abilities(
+            &self,
+            _seed: &Option<Self::AbilitiesSeed>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) -> Box<dyn Iterator<Item = Self::Ability>> {
+            Box::new(std::iter::once(EmptyAbility { id: ABILITY_ID }))
+        }
+    }
+
+    #[derive(Default)]
+    pub struct CustomRoundsRules {}
+
+    impl RoundsRules<CustomRules> for CustomRoundsRules {
+        type RoundsSeed = ();
+        // Number of actors added and of turns started.
+        type RoundsModel = (u32, u32);
+
+        fn generate_model(&self, _seed: &Option<Self::RoundsSeed>) -> Self::RoundsModel {
+            (0, 0)
+        }
+
+        fn on_start(
+            &self,
+            _entities: &Entities<CustomRules>,
+            _space: &Space<CustomRules>,
+            model: &mut Self::RoundsModel,
+            _actor: &dyn Actor<CustomRules>,
+            entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) {
+            model.1 += 1;
+            entropy.generate(0, 10);
+        }
+
+        fn on_actor_added(
+            &self,
+            model: &mut Self::RoundsModel,
+            _actor: &dyn Actor<CustomRules>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) {
+            model.0 += 1;
+        }
+    }
+
+    battle_rules! {
+        CustomTeamRules,
+        EmptyCharacterRules,
+        CustomActorRules,
+        EmptyFightRules,
+        EmptyUserRules,
+        EmptySpaceRules,
+        CustomRoundsRules,
+        RecordingEntropy<i32>
+    }
+
+    // Create a server with a long history.
+    let mut server = util::server(CustomRules::new());
+    assert_eq!(
+        ResetEntropy::trigger(&mut server)
+            .seed(vec![1, 2, 3])
+            .fire()
+            .err(),
+        None
+    );
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    for _ in 0..3 {
+        util::start_turn(&mut server, &ENTITY_1_ID);
+        util::end_turn(&mut server);
+    }
+    assert_eq!(EndRound::trigger(&mut server).fire().err(), None);
+    util::start_turn(&mut server, &ENTITY_1_ID);
+    assert_eq!(
+        ActivateAbility::trigger(&mut server, ENTITY_1_ID, ABILITY_ID)
+            .delay(2)
+            .fire()
+            .err(),
+        None
+    );
+    // Bootstrap a new client.
+    let mut client = util::server(CustomRules::new());
+    for event in server.bootstrap_events_for_new_client() {
+        assert_eq!(client.receive(event).err(), None);
+    }
+    assert!(client.battle().history().events()[0].synthetic());
+    // Rounds, turns, abilities' activations, cooldowns and pending activations are restored.
+    let rounds = client.battle().rounds();
+    assert_eq!(rounds.completed_rounds(), 1);
+    assert_eq!(rounds.completed_turns(), 3);
+    assert_eq!(rounds.state(), server.battle().rounds().state());
+    assert_eq!(rounds.activations(&ENTITY_1_ID), 1);
+    let creature = client.battle().entities().creature(&CREATURE_1_ID).unwrap();
+    assert_eq!(
+        creature
+            .tracked_ability(&ABILITY_ID)
+            .and_then(|ability| ability.last_used_turn()),
+        Some(3)
+    );
+    let pending = rounds.pending_activation(&ENTITY_1_ID).unwrap();
+    assert_eq!(*pending.ability_id(), ABILITY_ID);
+    assert_eq!(pending.turns(), 2);
+    // The rounds model is rebuilt by the creation of the actors, without starting any turn.
+    assert_eq!(*server.battle().rounds().model(), (1, 4));
+    assert_eq!(*rounds.model(), (1, 0));
+    // The entropy model is regenerated from its seed.
+    assert_eq!(server.battle().entropy().model().recorded(), &[1, 2, 3, 1]);
+    assert!(client.battle().entropy().model().recorded().is_empty());
+    // Teams' data is not transferred.
+    assert_eq!(
+        *server.battle().entities().team(&TEAM_1_ID).unwrap().data(),
+        1
+    );
+    assert_eq!(
+        *client.battle().entities().team(&TEAM_1_ID).unwrap().data(),
+        0
+    );
+}
+
+#[test]
+fn sandbox() {
+    // Create a server in sandbox mode, a client in sandbox mode and a regular client.
+    let server = Server::builder(Battle::builder(CustomRules::new()).build())
+        .sandbox()
+        .build();
+    let server = Arc::new(Mutex::new(server));
+    let server_sink = TestServerSink::new(SERVER_1_ID, server.clone());
+    let client = Client::builder(
+        Battle::builder(CustomRules::new()).build(),
+        Box::new(server_sink.clone()),
+    )
+    .sandbox()
+    .build();
+    let client = Arc::new(Mutex::new(client));
+    let mut client_sink = TestClientSink::new(CLIENT_1_ID, client.clone());
+    add_sink!(server, client_sink);
+    let strict_client = Arc::new(Mutex::new(util::client(CustomRules::new(), server_sink)));
+    let mut strict_client_sink = TestClientSink::new(CLIENT_2_ID, strict_client.clone());
+    add_sink!(server, strict_client_sink);
+    assert!(server.lock().unwrap().sandbox());
+    assert!(client.lock().unwrap().sandbox());
+    assert!(!strict_client.lock().unwrap().sandbox());
+    util::team(&mut *server.lock().unwrap(), TEAM_1_ID);
+    // An invalid event is applied and reported.
+    assert_eq!(
+        CreateTeam::trigger(&mut *server.lock().unwrap(), TEAM_1_ID)
+            .fire()
+            .err(),
+        None
+    );
+    {
+        let server = server.lock().unwrap();
+        let report = server.sandbox_report();
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].event_id(), 1);
+        assert_eq!(report[0].error(), &WeaselError::DuplicatedTeam(TEAM_1_ID));
+        let events = server.battle().history().events();
+        assert!(!events[0].unverified());
+        assert!(events[1].unverified());
+    }
+    // The client in sandbox mode accepts the unverified event.
+    assert_eq!(client_sink.receive().err(), None);
+    assert_eq!(events!(client).len(), 2);
+    assert!(events!(client)[1].unverified());
+    // The regular client doesn't trust the flag and verifies the event.
+    assert_eq!(
+        strict_client_sink.receive().err(),
+        Some(WeaselError::DuplicatedTeam(TEAM_1_ID))
+    );
+    assert_eq!(events!(strict_client).len(), 1);
+    // Events sent by clients are still verified.
+    assert_eq!(
+        CreateTeam::trigger(&mut *client.lock().unwrap(), TEAM_1_ID)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::DuplicatedTeam(TEAM_1_ID))
+    );
+    // Outside of sandbox mode verification failures are errors.
+    assert_eq!(server.lock().unwrap().take_sandbox_report().len(), 1);
+    assert!(server.lock().unwrap().sandbox_report().is_empty());
+    server.lock().unwrap().set_sandbox(false);
+    assert_eq!(
+        CreateTeam::trigger(&mut *server.lock().unwrap(), TEAM_1_ID)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::DuplicatedTeam(TEAM_1_ID))
+    );
+    assert!(server.lock().unwrap().sandbox_report().is_empty());
+}
+
+#[test]
+fn client_outbox() {
+    // Create a server and a client with an outbox.
+    let server = Arc::new(Mutex::new(util::server(CustomRules::new())));
+    let server_sink = TestServerSink::new(SERVER_1_ID, server.clone());
+    let client = Client::builder(
+        Battle::builder(CustomRules::new()).build(),
+        Box::new(server_sink.clone()),
+    )
+    .outbox()
+    .build();
+    let client = Arc::new(Mutex::new(client));
+    assert!(client.lock().unwrap().has_outbox());
+    let mut client_sink = TestClientSink::new(CLIENT_1_ID, client.clone());
+    add_sink!(server, client_sink);
+    // Fire some events from the client. They must be held in the outbox.
+    util::dummy(&mut *client.lock().unwrap());
+    util::dummy(&mut *client.lock().unwrap());
+    util::dummy(&mut *client.lock().unwrap());
+    assert_eq!(client.lock().unwrap().pending_events().len(), 3);
+    assert_eq!(events!(server).len(), 0);
+    // Cancel one event.
+    assert_eq!(
+        client
+            .lock()
+            .unwrap()
+            .cancel_pending(1)
+            .map(|event| event.kind()),
+        Some(EventKind::DummyEvent)
+    );
+    assert!(client.lock().unwrap().cancel_pending(2).is_none());
+    // A failed flush keeps the events in the outbox.
+    server_sink.sink.lock().unwrap().broken = true;
+    assert!(client.lock().unwrap().flush().is_err());
+    assert_eq!(client.lock().unwrap().pending_events().len(), 2);
+    server_sink.sink.lock().unwrap().broken = false;
+    // Flush the outbox.
+    assert_eq!(client.lock().unwrap().flush().err(), None);
+    assert!(client.lock().unwrap().pending_events().is_empty());
+    assert_eq!(client_sink.receive().err(), None);
+    assert_eq!(events!(server).len(), 2);
+    assert_eq!(events!(client).len(), 2);
+    // Clear the outbox.
+    util::dummy(&mut *client.lock().unwrap());
+    client.lock().unwrap().clear_pending();
+    assert_eq!(client.lock().unwrap().flush().err(), None);
+    assert_eq!(events!(server).len(), 2);
+}
+
+#[cfg(feature = "serialization")]
+#[test]
+fn max_event_size() {
+    use weasel::metric::system::OVERSIZED_EVENTS_REJECTED;
+    use weasel::serde::{serialized_size, FlatClientEvent};
+    // Measure the size of a client event.
+    let prototype = DummyEvent::<CustomRules>::trigger(&mut ())
+        .prototype()
+        .client_prototype(0, None);
+    let size = serialized_size(&FlatClientEvent::from(prototype)).unwrap();
+    // Create a server accepting events only up to the given size, and a client.
+    let server = Server::builder(Battle::builder(CustomRules::new()).build())
+        .max_event_size(size - 1)
+        .build();
+    assert_eq!(server.max_event_size(), Some(size - 1));
+    let server = Arc::new(Mutex::new(server));
+    let server_sink = TestServerSink::new(SERVER_1_ID, server.clone());
+    let client = Arc::new(Mutex::new(util::client(CustomRules::new(), server_sink)));
+    // Oversized events are rejected and counted.
+    assert_eq!(
+        DummyEvent::trigger(&mut *client.lock().unwrap())
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::EventTooLarge(size, size - 1))
+    );
+    assert_eq!(events!(server).len(), 0);
+    assert_eq!(
+        server
+            .lock()
+            .unwrap()
+            .battle()
+            .metrics()
+            .system_u64(OVERSIZED_EVENTS_REJECTED),
+        Some(1)
+    );
+    // Events within the limit are accepted.
+    let server = Server::builder(Battle::builder(CustomRules::new()).build())
+        .max_event_size(size)
+        .build();
+    let server = Arc::new(Mutex::new(server));
+    let server_sink = TestServerSink::new(SERVER_1_ID, server.clone());
+    let client = Arc::new(Mutex::new(util::client(CustomRules::new(), server_sink)));
+    util::dummy(&mut *client.lock().unwrap());
+    assert_eq!(events!(server).len(), 1);
+}
+
+#[test]
+fn pre_broadcast() {
+    // Create a server hiding the second team and withholding renames.
+    let server = Server::builder(Battle::builder(CustomRules::new()).build())
+        .pre_broadcast(Box::new(|event, battle| {
+            assert!(battle.history().len() > event.id());
+            match event.kind() {
+                EventKind::RenameTeam => Broadcast::Withhold,
+                EventKind::CreateTeam if event.id() == 1 => {
+                    Broadcast::Replace(DummyEvent::trigger(&mut ()).event())
+                }
+                _ => Broadcast::Forward,
+            }
+        }))
+        .build();
+    let server = Arc::new(Mutex::new(server));
+    let server_sink = TestServerSink::new(SERVER_1_ID, server.clone());
+    // Connect a client.
+    let client = Arc::new(Mutex::new(util::client(
+        CustomRules::new(),
+        server_sink.clone(),
+    )));
+    let mut client_sink = TestClientSink::new(CLIENT_1_ID, client.clone());
+    add_sink!(server, client_sink);
+    // Create two teams. The second one never reaches the client.
+    util::team(&mut *server.lock().unwrap(), TEAM_1_ID);
+    util::team(&mut *server.lock().unwrap(), TEAM_2_ID);
+    assert_eq!(client_sink.receive().err(), None);
+    let kinds = |events: &[_]| -> Vec<EventKind> {
+        events
+            .iter()
+            .map(|e: &weasel::event::EventWrapper<CustomRules>| e.kind())
+            .collect()
+    };
+    assert_eq!(
+        kinds(events!(server)),
+        vec![EventKind::CreateTeam, EventKind::CreateTeam]
+    );
+    assert_eq!(
+        kinds(events!(client)),
+        vec![EventKind::CreateTeam, EventKind::DummyEvent]
+    );
+    assert_eq!(events!(client)[1].id(), 1);
+    assert_eq!(
+        client.lock().unwrap().battle().entities().teams().count(),
+        1
+    );
+    // Withheld events are recorded only in the server's history.
+    assert_eq!(
+        RenameTeam::trigger(&mut *server.lock().unwrap(), TEAM_1_ID, TEAM_3_ID)
+            .fire()
+            .err(),
+        None
+    );
+    assert!(client_sink.buffer.lock().unwrap().is_empty());
+    assert_eq!(events!(server).len(), 3);
+    // The callback is applied also when sharing the history.
+    let other_client = Arc::new(Mutex::new(util::client(CustomRules::new(), server_sink)));
+    let mut other_client_sink = TestClientSink::new(CLIENT_2_ID, other_client.clone());
+    add_sink_from!(server, other_client_sink, 0);
+    assert_eq!(other_client_sink.buffer.lock().unwrap().len(), 2);
+    assert_eq!(other_client_sink.receive().err(), None);
+    assert_eq!(
+        kinds(events!(other_client)),
+        vec![EventKind::CreateTeam, EventKind::DummyEvent]
+    );
+}
+
+#[test]
+fn spectator_sinks() {
+    // Spectators see only the first team.
+    struct SpectatorRules;
+
+    impl VisibilityRules<CustomRules> for SpectatorRules {
+        fn redact(
+            &self,
+            _: &Battle<CustomRules>,
+            event: &VersionedEventWrapper<CustomRules>,
+        ) -> Broadcast<CustomRules> {
+            match event.kind() {
+                EventKind::CreateTeam if event.id() == 1 => {
+                    Broadcast::Replace(DummyEvent::trigger(&mut ()).event())
+                }
+                _ => Broadcast::Forward,
+            }
+        }
+    }
+
+    let server = Server::builder(Battle::builder(CustomRules::new()).build())
+        .visibility_rules(Box::new(SpectatorRules))
+        .build();
+    let server = Arc::new(Mutex::new(server));
+    let server_sink = TestServerSink::new(SERVER_1_ID, server.clone());
+    let kinds = |client: &Arc<Mutex<Client<CustomRules>>>| -> Vec<EventKind> {
+        events!(client).iter().map(|e| e.kind()).collect()
+    };
+    // Connect a player, a spectator with full information and one with redacted information.
+    let player = Arc::new(Mutex::new(util::client(
+        CustomRules::new(),
+        server_sink.clone(),
+    )));
+    let mut player_sink = TestClientSink::new(CLIENT_1_ID, player.clone());
+    add_sink!(server, player_sink);
+    let full = Arc::new(Mutex::new(util::client(
+        CustomRules::new(),
+        server_sink.clone(),
+    )));
+    let mut full_sink = TestClientSink::new(CLIENT_2_ID, full.clone());
+    assert_eq!(
+        server
+            .lock()
+            .unwrap()
+            .client_sinks_mut()
+            .add_spectator_sink(Box::new(full_sink.clone()), RedactionPolicy::Full)
+            .err(),
+        None
+    );
+    let redacted = Arc::new(Mutex::new(util::client(
+        CustomRules::new(),
+        server_sink.clone(),
+    )));
+    let mut redacted_sink = TestClientSink::new(CLIENT_ERR_ID, redacted.clone());
+    assert_eq!(
+        server
+            .lock()
+            .unwrap()
+            .client_sinks_mut()
+            .add_spectator_sink(Box::new(redacted_sink.clone()), RedactionPolicy::Redacted)
+            .err(),
+        None
+    );
+    // Ids of spectators' sinks must be unique as well.
+    assert_eq!(
+        server
+            .lock()
+            .unwrap()
+            .client_sinks_mut()
+            .add_spectator_sink(Box::new(player_sink.clone()), RedactionPolicy::Full)
+            .err(),
+        Some(WeaselError::DuplicatedEventSink(CLIENT_1_ID))
+    );
+    // Create two teams.
+    util::team(&mut *server.lock().unwrap(), TEAM_1_ID);
+    util::team(&mut *server.lock().unwrap(), TEAM_2_ID);
+    assert_eq!(player_sink.receive().err(), None);
+    assert_eq!(full_sink.receive().err(), None);
+    assert_eq!(redacted_sink.receive().err(), None);
+    let teams = vec![EventKind::CreateTeam, EventKind::CreateTeam];
+    assert_eq!(kinds(&player), teams);
+    assert_eq!(kinds(&full), teams);
+    assert_eq!(
+        kinds(&redacted),
+        vec![EventKind::CreateTeam, EventKind::DummyEvent]
+    );
+    // The history sent to a spectator is redacted too.
+    let late = Arc::new(Mutex::new(util::client(CustomRules::new(), server_sink)));
+    let mut late_sink = TestClientSink::new(CLIENT_ERR_ID + 1, late.clone());
+    {
+        let mut server = server.lock().unwrap();
+        let mut sinks = server.client_sinks_mut();
+        assert_eq!(
+            sinks
+                .add_spectator_sink(Box::new(late_sink.clone()), RedactionPolicy::Redacted)
+                .err(),
+            None
+        );
+        assert_eq!(sinks.send_range(CLIENT_ERR_ID + 1, 0..2).err(), None);
+    }
+    assert_eq!(late_sink.receive().err(), None);
+    assert_eq!(
+        kinds(&late),
+        vec![EventKind::CreateTeam, EventKind::DummyEvent]
+    );
+}
+
+#[test]
+fn visibility_rules() {
+    // Players don't see the creatures of teams they don't control.
+    struct FogOfWar;
+
+    impl VisibilityRules<CustomRules> for FogOfWar {
+        fn filter(
+            &self,
+            _: &Battle<CustomRules>,
+            event: &VersionedEventWrapper<CustomRules>,
+            _: PlayerId,
+            teams: &[TeamId<CustomRules>],
+        ) -> Broadcast<CustomRules> {
+            match event.as_any().downcast_ref::<CreateCreature<CustomRules>>() {
+                Some(create) if !teams.contains(create.team_id()) => {
+                    Broadcast::Replace(DummyEvent::trigger(&mut ()).event())
+                }
+                _ => Broadcast::Forward,
+            }
+        }
+    }
+
+    let server = Server::builder(Battle::builder(CustomRules::new()).build())
+        .visibility_rules(Box::new(FogOfWar))
+        .build();
+    let server = Arc::new(Mutex::new(server));
+    let server_sink = TestServerSink::new(SERVER_1_ID, server.clone());
+    let kinds = |client: &Arc<Mutex<Client<CustomRules>>>| -> Vec<EventKind> {
+        events!(client).iter().map(|e| e.kind()).collect()
+    };
+    let new_client = || {
+        Arc::new(Mutex::new(util::client(
+            CustomRules::new(),
+            server_sink.clone(),
+        )))
+    };
+    // Create two teams, each one controlled by a player.
+    util::team(&mut *server.lock().unwrap(), TEAM_1_ID);
+    util::team(&mut *server.lock().unwrap(), TEAM_2_ID);
+    {
+        let mut server = server.lock().unwrap();
+        let mut rights = server.rights_mut();
+        assert_eq!(rights.add(PLAYER_1_ID, &TEAM_1_ID).err(), None);
+        assert_eq!(rights.add(PLAYER_2_ID, &TEAM_2_ID).err(), None);
+    }
+    // Connect the two players and a sink without a player.
+    let player_1 = new_client();
+    let mut player_1_sink = TestClientSink::new(CLIENT_1_ID, player_1.clone());
+    assert_eq!(
+        server
+            .lock()
+            .unwrap()
+            .client_sinks_mut()
+            .add_player_sink(Box::new(player_1_sink.clone()), PLAYER_1_ID)
+            .err(),
+        None
+    );
+    let player_2 = new_client();
+    let mut player_2_sink = TestClientSink::new(CLIENT_2_ID, player_2.clone());
+    assert!(server
+        .lock()
+        .unwrap()
+        .open_session(Box::new(player_2_sink.clone()), Some(PLAYER_2_ID))
+        .is_ok());
+    let observer = new_client();
+    let mut observer_sink = TestClientSink::new(CLIENT_ERR_ID, observer.clone());
+    add_sink!(server, observer_sink);
+    let history = |client: &Arc<Mutex<Client<CustomRules>>>, sink: &mut TestClientSink<_>| {
+        assert_eq!(
+            server
+                .lock()
+                .unwrap()
+                .client_sinks_mut()
+                .send_range(sink.id(), 0..2)
+                .err(),
+            None
+        );
+        assert_eq!(sink.receive().err(), None);
+        assert_eq!(kinds(client).len(), 2);
+    };
+    history(&player_1, &mut player_1_sink);
+    history(&player_2, &mut player_2_sink);
+    history(&observer, &mut observer_sink);
+    // Each player sees only its own creature.
+    util::creature(&mut *server.lock().unwrap(), CREATURE_1_ID, TEAM_1_ID, ());
+    util::creature(&mut *server.lock().unwrap(), CREATURE_2_ID, TEAM_2_ID, ());
+    assert_eq!(player_1_sink.receive().err(), None);
+    assert_eq!(player_2_sink.receive().err(), None);
+    assert_eq!(observer_sink.receive().err(), None);
+    let teams = [EventKind::CreateTeam, EventKind::CreateTeam];
+    let creature = EventKind::CreateCreature;
+    let dummy = EventKind::DummyEvent;
+    assert_eq!(kinds(&player_1), [&teams[..], &[creature, dummy]].concat());
+    assert_eq!(kinds(&player_2), [&teams[..], &[dummy, creature]].concat());
+    assert_eq!(
+        kinds(&observer),
+        [&teams[..], &[creature, creature]].concat()
+    );
+    assert_eq!(
+        player_1
+            .lock()
+            .unwrap()
+            .battle()
+            .entities()
+            .creatures()
+            .count(),
+        1
+    );
+    // The history sent to a player is filtered too.
+    let late = new_client();
+    let mut late_sink = TestClientSink::new(CLIENT_ERR_ID + 1, late.clone());
+    {
+        let mut server = server.lock().unwrap();
+        let mut sinks = server.client_sinks_mut();
+        assert_eq!(
+            sinks
+                .add_player_sink(Box::new(late_sink.clone()), PLAYER_2_ID)
+                .err(),
+            None
+        );
+        assert_eq!(sinks.send_range(CLIENT_ERR_ID + 1, 0..4).err(), None);
+    }
+    assert_eq!(late_sink.receive().err(), None);
+    assert_eq!(kinds(&late), kinds(&player_2));
+}
+
+#[test]
+fn shutdown() {
+    /// A sink logging everything happening to it.
+    #[derive(Clone)]
+    struct LogSink {
+        id: EventSinkId,
+        broken: bool,
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl EventSink for LogSink {
+        fn id(&self) -> EventSinkId {
+            self.id
+        }
+
+        fn on_shutdown(&mut self, reason: ShutdownReason) {
+            self.log.lock().unwrap().push(format!("{:?}", reason));
+        }
+    }
+
+    impl ClientSink<CustomRules> for LogSink {
+        fn send(
+            &mut self,
+            event: &VersionedEventWrapper<CustomRules>,
+        ) -> WeaselResult<(), CustomRules> {
+            if self.broken {
+                return Err(WeaselError::EventSinkError("broken".to_string()));
+            }
+            self.log.lock().unwrap().push(format!("{:?}", event.kind()));
+            Ok(())
+        }
+
+        fn on_error(&mut self, _: EventId, _: &WeaselErrorType<CustomRules>) -> SinkErrorPolicy {
+            SinkErrorPolicy::Buffer(10)
+        }
+
+        fn flush(&mut self) -> WeaselResult<(), CustomRules> {
+            self.log.lock().unwrap().push("flush".to_string());
+            Ok(())
+        }
+    }
+
+    // Create a server with a working sink and a broken one.
+    let mut server = util::server(CustomRules::new());
+    let sink = LogSink {
+        id: CLIENT_1_ID,
+        broken: false,
+        log: Arc::new(Mutex::new(Vec::new())),
+    };
+    let broken_sink = LogSink {
+        id: CLIENT_2_ID,
+        broken: true,
+        log: Arc::new(Mutex::new(Vec::new())),
+    };
+    for sink in &[&sink, &broken_sink] {
+        assert_eq!(
+            server
+                .client_sinks_mut()
+                .add_sink(Box::new((*sink).clone()))
+                .err(),
+            None
+        );
+    }
+    util::dummy(&mut server);
+    assert!(!server.is_shut_down());
+    // Shut down the server. Undelivered events are reported.
+    let policy = ShutdownPolicy::new(ShutdownReason::Custom(7)).end_battle();
+    assert_eq!(policy.reason(), ShutdownReason::Custom(7));
+    assert_eq!(
+        server.shutdown(policy.clone()).err(),
+        Some(WeaselError::EventSinkError(format!(
+            "sink {} shut down with 2 undelivered events",
+            CLIENT_2_ID
+        )))
+    );
+    assert!(server.is_shut_down());
+    assert_eq!(server.battle().phase(), BattlePhase::Ended);
+    assert_eq!(server.client_sinks().sinks().count(), 0);
+    assert_eq!(
+        *sink.log.lock().unwrap(),
+        vec!["DummyEvent", "EndBattle", "flush", "Custom(7)"]
+    );
+    assert_eq!(*broken_sink.log.lock().unwrap(), vec!["flush", "Custom(7)"]);
+    // Client events are rejected.
+    let event = DummyEvent::trigger(&mut ())
+        .prototype()
+        .client_prototype(0, None);
+    assert_eq!(
+        server.process_client(event).err(),
+        Some(WeaselError::ServerShutDown)
+    );
+    // Shutting down again does nothing.
+    assert_eq!(server.shutdown(policy).err(), None);
+    assert_eq!(sink.log.lock().unwrap().len(), 4);
+}
+
+#[test]
+fn fair_scheduling() {
+    const PLAYER_3_ID: PlayerId = 3;
+    let prototype = |player| -> ClientEventPrototype<CustomRules> {
+        DummyEvent::trigger(&mut ())
+            .prototype()
+            .client_prototype(0, Some(player))
+    };
+    let mut server = util::server(CustomRules::new());
+    // Nothing to do without pending events.
+    assert!(server.process_pending().is_empty());
+    // Player 1 submits many events before the other players.
+    for player in &[
+        PLAYER_1_ID,
+        PLAYER_1_ID,
+        PLAYER_1_ID,
+        PLAYER_2_ID,
+        PLAYER_3_ID,
+    ] {
+        server.enqueue_client(prototype(*player));
+    }
+    server.enqueue_client(
+        CreateTeam::trigger(&mut (), TEAM_1_ID)
+            .prototype()
+            .client_prototype(0, Some(PLAYER_2_ID)),
+    );
+    assert_eq!(server.pending_client_events(), 6);
+    // Events are interleaved between players.
+    let outcomes = server.process_pending();
+    assert_eq!(server.pending_client_events(), 0);
+    let players: Vec<_> = outcomes.iter().map(|(player, _)| player.unwrap()).collect();
+    assert_eq!(
+        players,
+        vec![
+            PLAYER_1_ID,
+            PLAYER_2_ID,
+            PLAYER_3_ID,
+            PLAYER_1_ID,
+            PLAYER_2_ID,
+            PLAYER_1_ID
+        ]
+    );
+    // Each event has its own outcome.
+    assert_eq!(
+        outcomes[4].1.clone().err().map(|e| e.unfold()),
+        Some(WeaselError::ServerOnlyEvent)
+    );
+    assert_eq!(server.battle().history().len(), 5);
+    // The first player to be served rotates.
+    for player in &[PLAYER_1_ID, PLAYER_2_ID, PLAYER_3_ID] {
+        server.enqueue_client(prototype(*player));
+    }
+    let players: Vec<_> = server
+        .process_pending()
+        .iter()
+        .map(|(player, _)| player.unwrap())
+        .collect();
+    assert_eq!(players, vec![PLAYER_2_ID, PLAYER_3_ID, PLAYER_1_ID]);
+    // Players with a drained queue are forgotten, so the order follows the new queues.
+    for player in &[PLAYER_2_ID, PLAYER_1_ID] {
+        server.enqueue_client(prototype(*player));
+    }
+    let players: Vec<_> = server
+        .process_pending()
+        .iter()
+        .map(|(player, _)| player.unwrap())
+        .collect();
+    assert_eq!(players, vec![PLAYER_2_ID, PLAYER_1_ID]);
+}
+
+#[test]
+fn resumable_sessions() {
+    /// A sink recording the ids of the events it receives.
+    #[derive(Clone)]
+    struct RecordSink {
+        id: EventSinkId,
+        broken: Arc<Mutex<bool>>,
+        received: Arc<Mutex<Vec<EventId>>>,
+    }
+
+    impl RecordSink {
+        fn new(id: EventSinkId) -> Self {
+            Self {
+                id,
+                broken: Arc::new(Mutex::new(false)),
+                received: Arc::new(Mutex::new(Vec::new())),
+            }
+        }
+    }
+
+    impl EventSink for RecordSink {
+        fn id(&self) -> EventSinkId {
+            self.id
+        }
+    }
+
+    impl ClientSink<CustomRules> for RecordSink {
+        fn send(
+            &mut self,
+            event: &VersionedEventWrapper<CustomRules>,
+        ) -> WeaselResult<(), CustomRules> {
+            if *self.broken.lock().unwrap() {
+                return Err(WeaselError::EventSinkError("broken".to_string()));
+            }
+            self.received.lock().unwrap().push(event.id());
+            Ok(())
+        }
+    }
+
+    let mut server = util::server(CustomRules::new());
+    let sink = RecordSink::new(CLIENT_1_ID);
+    let token = server
+        .open_session(Box::new(sink.clone()), Some(PLAYER_1_ID))
+        .unwrap();
+    let session = server.session(token).unwrap();
+    assert_eq!(session.sink_id(), CLIENT_1_ID);
+    assert_eq!(session.player(), Some(PLAYER_1_ID));
+    util::dummy(&mut server);
+    // The connection breaks and the sink is disconnected.
+    *sink.broken.lock().unwrap() = true;
+    util::dummy(&mut server);
+    util::dummy(&mut server);
+    assert_eq!(server.client_sinks().sinks().count(), 0);
+    // Events queued by the player are kept.
+    server.enqueue_client(
+        DummyEvent::trigger(&mut ())
+            .prototype()
+            .client_prototype(0, Some(PLAYER_1_ID)),
+    );
+    // Invalid tokens are rejected.
+    assert_eq!(
+        server
+            .resume_session(
+                token.wrapping_add(1),
+                Box::new(RecordSink::new(CLIENT_2_ID))
+            )
+            .err(),
+        Some(WeaselError::InvalidSessionToken)
+    );
+    // The client reconnects and receives the missed events.
+    let new_sink = RecordSink::new(CLIENT_2_ID);
+    assert_eq!(
+        server
+            .resume_session(token, Box::new(new_sink.clone()))
+            .ok(),
+        Some(Some(PLAYER_1_ID))
+    );
+    assert_eq!(*new_sink.received.lock().unwrap(), vec![1, 2]);
+    assert_eq!(server.session(token).unwrap().sink_id(), CLIENT_2_ID);
+    assert_eq!(server.pending_client_events(), 1);
+    server.process_pending();
+    assert_eq!(*new_sink.received.lock().unwrap(), vec![1, 2, 3]);
+    // Resuming a session whose sink is still attached replaces the sink.
+    let newer_sink = RecordSink::new(CLIENT_1_ID);
+    assert_eq!(
+        server
+            .resume_session(token, Box::new(newer_sink.clone()))
+            .err(),
+        None
+    );
+    util::dummy(&mut server);
+    assert_eq!(server.client_sinks().sinks().count(), 1);
+    assert_eq!(*newer_sink.received.lock().unwrap(), vec![4]);
+    // Closed sessions can't be resumed.
+    assert!(server.close_session(token));
+    assert!(!server.close_session(token));
+    assert_eq!(server.client_sinks().sinks().count(), 0);
+    assert_eq!(
+        server.resume_session(token, Box::new(newer_sink)).err(),
+        Some(WeaselError::InvalidSessionToken)
+    );
+}
+
+#[test]
+fn connection_state() {
+    // Create a server with a few events and a client.
+    let server = Arc::new(Mutex::new(util::server(CustomRules::new())));
+    util::team(&mut *server.lock().unwrap(), TEAM_1_ID);
+    util::dummy(&mut *server.lock().unwrap());
+    util::dummy(&mut *server.lock().unwrap());
+    let server_sink = TestServerSink::new(SERVER_1_ID, server.clone());
+    let transitions = Arc::new(Mutex::new(Vec::new()));
+    let transitions_clone = transitions.clone();
+    let mut client = Client::builder(
+        Battle::builder(CustomRules::new()).build(),
+        Box::new(server_sink.clone()),
+    )
+    .connection_callback(Box::new(move |previous, state| {
+        transitions_clone.lock().unwrap().push((previous, state))
+    }))
+    .build();
+    assert_eq!(client.connection_state(), ConnectionState::Ready);
+    // Sync the client.
+    let events: Vec<_> = server
+        .lock()
+        .unwrap()
+        .battle()
+        .versioned_events(0..3)
+        .collect();
+    client.sync_to(3);
+    assert_eq!(client.connection_state(), ConnectionState::Syncing);
+    assert_eq!(client.receive(events[0].clone()).err(), None);
+    assert_eq!(client.connection_state(), ConnectionState::Syncing);
+    // Missing an event desyncs the client.
+    assert_eq!(
+        client.receive(events[2].clone()).err(),
+        Some(WeaselError::NonContiguousEventId(2, 1))
+    );
+    assert_eq!(client.connection_state(), ConnectionState::Desynced);
+    assert_eq!(client.receive(events[1].clone()).err(), None);
+    assert_eq!(client.connection_state(), ConnectionState::Desynced);
+    // Sync again.
+    client.sync_to(3);
+    assert_eq!(client.receive(events[2].clone()).err(), None);
+    assert_eq!(client.connection_state(), ConnectionState::Ready);
+    // A failure to send an event disconnects the client.
+    server_sink.sink.lock().unwrap().broken = true;
+    assert!(DummyEvent::trigger(&mut client).fire().is_err());
+    assert_eq!(client.connection_state(), ConnectionState::Disconnected);
+    // A new server sink starts a new sync.
+    client.set_server_sink(Box::new(TestServerSink::new(SERVER_1_ID, server)));
+    assert_eq!(client.connection_state(), ConnectionState::Syncing);
+    client.sync_to(3);
+    assert_eq!(
+        *transitions.lock().unwrap(),
+        vec![
+            (ConnectionState::Ready, ConnectionState::Syncing),
+            (ConnectionState::Syncing, ConnectionState::Desynced),
+            (ConnectionState::Desynced, ConnectionState::Syncing),
+            (ConnectionState::Syncing, ConnectionState::Ready),
+            (ConnectionState::Ready, ConnectionState::Disconnected),
+            (ConnectionState::Disconnected, ConnectionState::Syncing),
+            (ConnectionState::Syncing, ConnectionState::Ready),
+        ]
+    );
+}
+
+#[test]
+fn resync() {
+    /// A server sink recording the resynchronization requests.
+    struct ResyncSink {
+        requests: Arc<Mutex<Vec<EventId>>>,
+    }
+
+    impl EventSink for ResyncSink {
+        fn id(&self) -> EventSinkId {
+            SERVER_1_ID
+        }
+    }
+
+    impl ServerSink<CustomRules> for ResyncSink {
+        fn send(&mut self, _: &ClientEventPrototype<CustomRules>) -> WeaselResult<(), CustomRules> {
+            Ok(())
+        }
+
+        fn request_resync(&mut self, from: EventId) -> WeaselResult<(), CustomRules> {
+            self.requests.lock().unwrap().push(from);
+            Ok(())
+        }
+    }
+
+    let server = Arc::new(Mutex::new(util::server(CustomRules::new())));
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let client = Client::builder(
+        Battle::builder(CustomRules::new()).build(),
+        Box::new(ResyncSink {
+            requests: requests.clone(),
+        }),
+    )
+    .auto_resync()
+    .build();
+    let client = Arc::new(Mutex::new(client));
+    let mut client_sink = TestClientSink::new(CLIENT_1_ID, client.clone());
+    add_sink!(server, client_sink);
+    // The client misses an event.
+    util::team(&mut *server.lock().unwrap(), TEAM_1_ID);
+    util::dummy(&mut *server.lock().unwrap());
+    util::dummy(&mut *server.lock().unwrap());
+    client_sink.buffer.lock().unwrap().remove(1);
+    assert_eq!(
+        client_sink.receive().err(),
+        Some(WeaselError::NonContiguousEventId(2, 1))
+    );
+    // The client requests the missing events by itself.
+    assert_eq!(
+        client.lock().unwrap().connection_state(),
+        ConnectionState::Syncing
+    );
+    assert_eq!(*requests.lock().unwrap(), vec![1]);
+    let next_id = server.lock().unwrap().sync_events_for(CLIENT_1_ID, 1);
+    assert_eq!(next_id, Ok(3));
+    client.lock().unwrap().sync_to(next_id.unwrap());
+    assert_eq!(client_sink.receive().err(), None);
+    assert_eq!(events!(client).len(), 3);
+    assert_eq!(
+        client.lock().unwrap().connection_state(),
+        ConnectionState::Ready
+    );
+    // Unknown sinks can't be synced.
+    assert_eq!(
+        server.lock().unwrap().sync_events_for(CLIENT_2_ID, 0).err(),
+        Some(WeaselError::EventSinkNotFound(CLIENT_2_ID))
+    );
+    // Sinks that don't support resynchronization return an error.
+    let server_sink = TestServerSink::new(SERVER_1_ID, server);
+    let mut client = util::client(CustomRules::new(), server_sink);
+    assert!(client.request_resync().is_err());
+    assert_eq!(client.connection_state(), ConnectionState::Ready);
+}
+
+#[test]
+fn server_status() {
+    // Create a server and a client.
+    let server = Arc::new(Mutex::new(util::server(CustomRules::new())));
+    let server_sink = TestServerSink::new(SERVER_1_ID, server.clone());
+    let client = Arc::new(Mutex::new(util::client(CustomRules::new(), server_sink)));
+    let client_sink = TestClientSink::new(CLIENT_1_ID, client);
+    add_sink!(server, client_sink);
+    // Check the initial status.
+    let status = server.lock().unwrap().status();
+    assert_eq!(status.phase(), BattlePhase::Started);
+    assert_eq!(status.events(), 0);
+    assert_eq!(status.sinks().len(), 1);
+    assert_eq!(status.sinks()[0].id(), CLIENT_1_ID);
+    assert_eq!(status.sinks()[0].last_event(), None);
+    assert_eq!(status.rights(), 0);
+    // Fire some events and grant rights.
+    util::team(&mut *server.lock().unwrap(), TEAM_1_ID);
+    util::team(&mut *server.lock().unwrap(), TEAM_2_ID);
+    for team in &[TEAM_1_ID, TEAM_2_ID] {
+        assert_eq!(
+            server
+                .lock()
+                .unwrap()
+                .rights_mut()
+                .add(PLAYER_1_ID, team)
+                .err(),
+            None
+        );
+    }
+    util::creature(&mut *server.lock().unwrap(), CREATURE_1_ID, TEAM_1_ID, ());
+    util::start_turn(&mut *server.lock().unwrap(), &ENTITY_1_ID);
+    util::end_turn(&mut *server.lock().unwrap());
+    let status = server.lock().unwrap().status();
+    assert_eq!(status.events(), 5);
+    assert_eq!(status.sinks()[0].last_event(), Some(4));
+    assert_eq!(status.sinks()[0].buffered_events(), 0);
+    assert_eq!(status.rights(), 2);
+    assert_eq!(status.completed_turns(), 1);
+    assert!(!status.shut_down());
+    // Shut down the server.
+    assert_eq!(
+        server
+            .lock()
+            .unwrap()
+            .shutdown(ShutdownPolicy::new(ShutdownReason::Terminated).end_battle())
+            .err(),
+        None
+    );
+    let status = server.lock().unwrap().status();
+    assert_eq!(status.phase(), BattlePhase::Ended);
+    assert!(status.sinks().is_empty());
+    assert!(status.shut_down());
+}
+
+#[test]
+fn interceptors() {
+    type Calls<T> = Arc<Mutex<Vec<T>>>;
+
+    /// An interceptor recording all events and vetoing those fired by a given player.
+    struct Recorder {
+        banned: PlayerId,
+        before: Calls<(EventKind, Option<PlayerId>)>,
+        after: Calls<(EventId, EventKind)>,
+    }
+
+    impl EventInterceptor<CustomRules> for Recorder {
+        fn before_verification(
+            &mut self,
+            _: &Battle<CustomRules>,
+            event: &mut EventPrototype<CustomRules>,
+            player: Option<PlayerId>,
+        ) -> WeaselResult<(), CustomRules> {
+            self.before.lock().unwrap().push((event.kind(), player));
+            if player == Some(self.banned) {
+                return Err(WeaselError::UserError("banned".to_string()));
+            }
+            // Annotate the event.
+            event.set_secondary_origins(vec![0]);
+            Ok(())
+        }
+
+        fn after_application(
+            &mut self,
+            _: &Battle<CustomRules>,
+            event: &EventWrapper<CustomRules>,
+        ) {
+            self.after.lock().unwrap().push((event.id(), event.kind()));
+        }
+    }
+
+    let server = Arc::new(Mutex::new(util::server(CustomRules::new())));
+    let before = Arc::new(Mutex::new(Vec::new()));
+    let after = Arc::new(Mutex::new(Vec::new()));
+    server.lock().unwrap().add_interceptor(Box::new(Recorder {
+        banned: PLAYER_2_ID,
+        before: before.clone(),
+        after: after.clone(),
+    }));
+    // Events fired by the server go through the interceptors.
+    util::dummy(&mut *server.lock().unwrap());
+    assert_eq!(*before.lock().unwrap(), vec![(EventKind::DummyEvent, None)]);
+    assert_eq!(*after.lock().unwrap(), vec![(0, EventKind::DummyEvent)]);
+    assert_eq!(events!(server)[0].secondary_origins(), &[0]);
+    // Events fired by clients go through the interceptors as well.
+    let server_sink = TestServerSink::new(SERVER_1_ID, server.clone());
+    let mut client = Client::builder(
+        Battle::builder(CustomRules::new()).build(),
+        Box::new(server_sink.clone()),
+    )
+    .enable_authentication(PLAYER_1_ID)
+    .build();
+    util::dummy(&mut client);
+    assert_eq!(
+        before.lock().unwrap()[1],
+        (EventKind::DummyEvent, Some(PLAYER_1_ID))
+    );
+    assert_eq!(after.lock().unwrap().len(), 2);
+    // Interceptors can veto events.
+    let mut client = Client::builder(
+        Battle::builder(CustomRules::new()).build(),
+        Box::new(server_sink),
+    )
+    .enable_authentication(PLAYER_2_ID)
+    .build();
+    assert_eq!(
+        DummyEvent::trigger(&mut client).fire().err(),
+        Some(WeaselError::UserError("banned".to_string()))
+    );
+    assert_eq!(events!(server).len(), 2);
+    assert_eq!(after.lock().unwrap().len(), 2);
+    // Clients have their own interceptors.
+    let client_before = Arc::new(Mutex::new(Vec::new()));
+    let client_after = Arc::new(Mutex::new(Vec::new()));
+    client.add_interceptor(Box::new(Recorder {
+        banned: PLAYER_2_ID,
+        before: client_before.clone(),
+        after: client_after.clone(),
+    }));
+    assert_eq!(
+        DummyEvent::trigger(&mut client).fire().err(),
+        Some(WeaselError::UserError("banned".to_string()))
+    );
+    assert_eq!(client_before.lock().unwrap().len(), 1);
+    let events = server
+        .lock()
+        .unwrap()
+        .battle()
+        .versioned_events(0..2)
+        .collect::<Vec<_>>();
+    for event in events {
+        assert_eq!(client.receive(event).err(), None);
+    }
+    assert_eq!(client_after.lock().unwrap().len(), 2);
+    // Interceptors can be removed.
+    client.clear_interceptors();
+    assert_eq!(
+        DummyEvent::trigger(&mut client).fire().err(),
+        Some(WeaselError::UserError("banned".to_string()))
+    );
+    assert_eq!(client_before.lock().unwrap().len(), 1);
+}
@@ -1,23 +1,30 @@
 use std::cell::RefCell;
 use std::collections::HashSet;
-use weasel::ability::AbilityId;
+use weasel::ability::{AbilityId, ActivateAbility};
 use weasel::actor::{Actor, ActorRules, RegenerateAbilities};
+use weasel::battle::Battle;
 use weasel::battle::{BattleController, BattleRules, BattleState};
 use weasel::character::{
     AlterStatistics, Character, CharacterRules, RegenerateStatistics, StatisticId,
 };
-use weasel::creature::{CreateCreature, RemoveCreature};
-use weasel::entity::{EntityId, RemoveEntity, Transmutation};
+use weasel::creature::{
+    ConvertCreature, CreateCreature, ReidentifyCreature, RemoveCreature, SpawnWave,
+    TransferOwnership, TransformCreature,
+};
+use weasel::entity::{Entity, EntityId, RemoveEntity, Transmutation};
 use weasel::entropy::Entropy;
-use weasel::event::{EventQueue, EventTrigger};
+use weasel::event::{EventQueue, EventReceiver, EventRights, EventServer, EventTrigger};
 use weasel::metric::{system::*, WriteMetrics};
-use weasel::round::{RoundsRules, TurnState};
+use weasel::player::PlayerId;
+use weasel::round::{RoundsRules, StartTurn, TurnState};
 use weasel::rules::empty::{EmptyAbility, EmptyStat};
-use weasel::rules::{ability::SimpleAbility, statistic::SimpleStatistic};
+use weasel::rules::{ability::SimpleAbility, statistic::SimpleStatistic, status::SimpleStatus};
 use weasel::space::{PositionClaim, SpaceRules};
+use weasel::status::{InflictStatus, Potency, Status, StatusId};
 use weasel::user::UserMetricId;
+use weasel::util::RegenerationPolicy;
 use weasel::{
-    battle_rules, battle_rules_with_actor, battle_rules_with_character, rules::empty::*,
+    battle_rules, battle_rules_with_actor, battle_rules_with_character, rules::empty::*, Server,
     WeaselError, WeaselResult,
 };
 
@@ -204,6 +211,109 @@ fn regenerate_statistics() {
     );
 }
 
+#[test]
+fn regenerate_statistics_policy() {
+    #[derive(Default)]
+    pub struct CustomCharacterRules {}
+
+    impl CharacterRules<CustomRules> for CustomCharacterRules {
+        type CreatureId = u32;
+        type ObjectId = ();
+        type Statistic = SimpleStatistic<u32, u32>;
+        // Vec with pair (id, max value).
+        type StatisticsSeed = Vec<(u32, u32)>;
+        type StatisticsAlteration = u32;
+        type Status = EmptyStatus;
+        type StatusesAlteration = ();
+
+        fn generate_statistics(
+            &self,
+            seed: &Option<Self::StatisticsSeed>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) -> Box<dyn Iterator<Item = Self::Statistic>> {
+            let v: Vec<_> = seed
+                .iter()
+                .flatten()
+                .map(|(id, max)| SimpleStatistic::with_value(*id, 0, *max, *max))
+                .collect();
+            Box::new(v.into_iter())
+        }
+
+        fn alter_statistics(
+            &self,
+            character: &mut dyn Character<CustomRules>,
+            alteration: &Self::StatisticsAlteration,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) -> Option<Transmutation> {
+            for statistic in character.statistics_mut() {
+                statistic.set_value(*alteration);
+            }
+            None
+        }
+
+        fn clamp_statistic(&self, current: &mut Self::Statistic, new: &Self::Statistic) {
+            current.clamp_to(new);
+        }
+    }
+
+    battle_rules_with_character! { CustomCharacterRules }
+
+    static STAT_ID: StatisticId<CustomRules> = 1;
+    const ENTITY_1_ID: EntityId<CustomRules> = EntityId::Creature(CREATURE_1_ID);
+    let value = |server: &weasel::Server<CustomRules>| {
+        let creature = server.battle().entities().character(&ENTITY_1_ID).unwrap();
+        let statistic = creature.statistic(&STAT_ID).unwrap();
+        (statistic.value(), statistic.max())
+    };
+    // Create a new creature and damage its statistic.
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    assert_eq!(
+        CreateCreature::trigger(&mut server, CREATURE_1_ID, TEAM_1_ID, ())
+            .statistics_seed(vec![(STAT_ID, 100)])
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(
+        AlterStatistics::trigger(&mut server, ENTITY_1_ID, 60)
+            .fire()
+            .err(),
+        None
+    );
+    // The default policy preserves the current statistic.
+    assert_eq!(
+        RegenerateStatistics::trigger(&mut server, ENTITY_1_ID)
+            .seed(vec![(STAT_ID, 200)])
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(value(&server), (60, 100));
+    // Clamp the current statistic to the new one.
+    assert_eq!(
+        RegenerateStatistics::trigger(&mut server, ENTITY_1_ID)
+            .seed(vec![(STAT_ID, 50)])
+            .policy(RegenerationPolicy::ClampToNew)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(value(&server), (50, 50));
+    // Reset the statistic to its seed.
+    assert_eq!(
+        RegenerateStatistics::trigger(&mut server, ENTITY_1_ID)
+            .seed(vec![(STAT_ID, 80)])
+            .policy(RegenerationPolicy::ResetToSeed)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(value(&server), (80, 80));
+}
+
 #[test]
 fn abilities_generated() {
     #[derive(Default)]
@@ -420,6 +530,7 @@ fn remove_creature() {
     impl SpaceRules<CustomRules> for CustomSpaceRules {
         type Position = u32;
         type SpaceSeed = ();
+        type Area = ();
         type SpaceModel = HashSet<Self::Position>;
         type SpaceAlteration = ();
 
@@ -650,3 +761,581 @@ fn remove_entity() {
     let entities = server.battle().entities();
     assert!(entities.creature(&CREATURE_1_ID).is_none());
 }
+
+#[test]
+fn reidentify_creature() {
+    #[derive(Default)]
+    pub struct CustomRoundsRules {}
+
+    impl RoundsRules<CustomRules> for CustomRoundsRules {
+        type RoundsSeed = ();
+        // Collection of the actors currently in the model.
+        type RoundsModel = Vec<EntityId<CustomRules>>;
+
+        fn generate_model(&self, _: &Option<Self::RoundsSeed>) -> Self::RoundsModel {
+            Vec::new()
+        }
+
+        fn on_actor_added(
+            &self,
+            model: &mut Self::RoundsModel,
+            actor: &dyn Actor<CustomRules>,
+            _: &mut Entropy<CustomRules>,
+            _: &mut WriteMetrics<CustomRules>,
+        ) {
+            model.push(*actor.entity_id());
+        }
+
+        fn on_actor_removed(
+            &self,
+            model: &mut Self::RoundsModel,
+            actor: &dyn Actor<CustomRules>,
+            _: &mut Entropy<CustomRules>,
+            _: &mut WriteMetrics<CustomRules>,
+        ) {
+            model.retain(|id| id != actor.entity_id());
+        }
+    }
+
+    #[derive(Default)]
+    struct CustomSpaceRules {}
+
+    impl SpaceRules<CustomRules> for CustomSpaceRules {
+        type Position = u32;
+        type SpaceSeed = ();
+        // Collection of pairs (position, entity).
+        type Area = ();
+        type SpaceModel = Vec<(Self::Position, EntityId<CustomRules>)>;
+        type SpaceAlteration = ();
+
+        fn generate_model(&self, _: &Option<Self::SpaceSeed>) -> Self::SpaceModel {
+            Vec::new()
+        }
+
+        fn move_entity(
+            &self,
+            model: &mut Self::SpaceModel,
+            claim: PositionClaim<CustomRules>,
+            position: Option<&Self::Position>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) {
+            model.retain(|(_, id)| id != claim.entity_id());
+            if let Some(position) = position {
+                model.push((*position, *claim.entity_id()));
+            }
+        }
+    }
+
+    battle_rules! {
+        EmptyTeamRules,
+        EmptyCharacterRules,
+        EmptyActorRules,
+        EmptyFightRules,
+        EmptyUserRules,
+        CustomSpaceRules,
+        CustomRoundsRules,
+        EmptyEntropyRules
+    }
+    const POSITION_1: u32 = 1;
+    const ENTITY_1_ID: EntityId<CustomRules> = EntityId::Creature(CREATURE_1_ID);
+    const ENTITY_5_ID: EntityId<CustomRules> = EntityId::Creature(CREATURE_5_ID);
+    // Create a battle with one creature and start its turn.
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, POSITION_1);
+    util::start_turn(&mut server, &ENTITY_1_ID);
+    // Reidentify should fail if the creature doesn't exist or the new id is taken.
+    assert_eq!(
+        ReidentifyCreature::trigger(&mut server, CREATURE_ERR_ID, CREATURE_5_ID)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::CreatureNotFound(CREATURE_ERR_ID))
+    );
+    assert_eq!(
+        ReidentifyCreature::trigger(&mut server, CREATURE_1_ID, CREATURE_1_ID)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::DuplicatedCreature(CREATURE_1_ID))
+    );
+    // Change the creature's id.
+    assert_eq!(
+        ReidentifyCreature::trigger(&mut server, CREATURE_1_ID, CREATURE_5_ID)
+            .fire()
+            .err(),
+        None
+    );
+    // Check that all references have been updated.
+    let entities = server.battle().entities();
+    assert!(entities.creature(&CREATURE_1_ID).is_none());
+    let creature = entities.creature(&CREATURE_5_ID).unwrap();
+    assert_eq!(*creature.entity_id(), ENTITY_5_ID);
+    assert_eq!(*creature.position(), POSITION_1);
+    assert_eq!(
+        entities
+            .team(&TEAM_1_ID)
+            .unwrap()
+            .creatures()
+            .collect::<Vec<_>>(),
+        vec![&CREATURE_5_ID]
+    );
+    assert_eq!(
+        server.battle().space().model(),
+        &vec![(POSITION_1, ENTITY_5_ID)]
+    );
+    assert_eq!(server.battle().rounds().model(), &vec![ENTITY_5_ID]);
+    assert!(server.battle().rounds().is_acting(&ENTITY_5_ID));
+    assert!(!server.battle().rounds().is_acting(&ENTITY_1_ID));
+    // The turn can be ended normally.
+    util::end_turn(&mut server);
+}
+
+#[test]
+fn transfer_ownership() {
+    battle_rules! {}
+    const TEAM_2_ID: u32 = 2;
+    const ENTITY_1_ID: EntityId<CustomRules> = EntityId::Creature(CREATURE_1_ID);
+    const PLAYER_1_ID: PlayerId = 1;
+    const PLAYER_2_ID: PlayerId = 2;
+    // Create a server with one creature. Require authentication.
+    let mut server = Server::builder(Battle::builder(CustomRules::new()).build())
+        .enforce_authentication()
+        .build();
+    util::team(&mut server, TEAM_1_ID);
+    util::team(&mut server, TEAM_2_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    assert_eq!(server.rights_mut().add(PLAYER_1_ID, &TEAM_1_ID).err(), None);
+    let creature_owner = |server: &Server<CustomRules>| {
+        server
+            .battle()
+            .entities()
+            .creature(&CREATURE_1_ID)
+            .unwrap()
+            .owner()
+    };
+    assert_eq!(creature_owner(&server), None);
+    // Check that the creature must exist.
+    assert_eq!(
+        TransferOwnership::trigger(&mut server, CREATURE_ERR_ID)
+            .owner(PLAYER_2_ID)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::CreatureNotFound(CREATURE_ERR_ID))
+    );
+    // Events on behalf of the creature require control over it.
+    let event = StartTurn::trigger(&mut server, ENTITY_1_ID)
+        .prototype()
+        .client_prototype(0, Some(PLAYER_2_ID));
+    assert_eq!(
+        event.rights(server.battle()),
+        EventRights::Entities(vec![&ENTITY_1_ID])
+    );
+    assert_eq!(
+        server
+            .process_client(event.clone())
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::AuthenticationError(
+            Some(PLAYER_2_ID),
+            TEAM_1_ID
+        ))
+    );
+    // Give the creature to the second player, who has no rights to the team.
+    assert_eq!(
+        TransferOwnership::trigger(&mut server, CREATURE_1_ID)
+            .owner(PLAYER_2_ID)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(creature_owner(&server), Some(PLAYER_2_ID));
+    assert_eq!(
+        server
+            .battle()
+            .entities()
+            .creature(&CREATURE_1_ID)
+            .unwrap()
+            .team_id(),
+        &TEAM_1_ID
+    );
+    // The team's player lost control over the creature.
+    let other_event = StartTurn::trigger(&mut server, ENTITY_1_ID)
+        .prototype()
+        .client_prototype(0, Some(PLAYER_1_ID));
+    assert_eq!(
+        server.process_client(other_event).err().map(|e| e.unfold()),
+        Some(WeaselError::OwnershipError(Some(PLAYER_1_ID), ENTITY_1_ID))
+    );
+    // The owner can now act on behalf of the creature.
+    assert_eq!(server.process_client(event).err(), None);
+    util::end_turn(&mut server);
+    // Converting the creature clears its owner.
+    assert_eq!(
+        ConvertCreature::trigger(&mut server, CREATURE_1_ID, TEAM_2_ID)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(creature_owner(&server), None);
+    // Releasing the creature gives control back to the team's players.
+    assert_eq!(
+        TransferOwnership::trigger(&mut server, CREATURE_1_ID)
+            .owner(PLAYER_1_ID)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(
+        TransferOwnership::trigger(&mut server, CREATURE_1_ID)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(creature_owner(&server), None);
+}
+
+#[test]
+fn spawn_wave() {
+    battle_rules! {}
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    // Missing team.
+    assert_eq!(
+        SpawnWave::trigger(&mut server)
+            .creature(CREATURE_1_ID, TEAM_1_ID, (), None, None)
+            .creature(CREATURE_5_ID, TEAM_5_ID, (), None, None)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::TeamNotFound(TEAM_5_ID))
+    );
+    // Duplicated id inside the wave.
+    assert_eq!(
+        SpawnWave::trigger(&mut server)
+            .creature(CREATURE_1_ID, TEAM_1_ID, (), None, None)
+            .creature(CREATURE_1_ID, TEAM_1_ID, (), None, None)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::DuplicatedCreature(CREATURE_1_ID))
+    );
+    assert_eq!(server.battle().entities().creatures().count(), 0);
+    // Spawn a wave.
+    let mut trigger = SpawnWave::trigger(&mut server);
+    for id in 0..CREATURE_5_ID {
+        trigger.creature(id, TEAM_1_ID, (), None, None);
+    }
+    assert_eq!(trigger.fire().err(), None);
+    assert_eq!(server.battle().entities().creatures().count(), 5);
+    assert_eq!(server.battle().history().len(), 2);
+    assert_eq!(
+        server.battle().metrics().system_u64(CREATURES_CREATED),
+        Some(5)
+    );
+    // Duplicated id with an existing creature.
+    assert_eq!(
+        SpawnWave::trigger(&mut server)
+            .creature(CREATURE_1_ID, TEAM_1_ID, (), None, None)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::DuplicatedCreature(CREATURE_1_ID))
+    );
+}
+
+#[test]
+fn summons() {
+    const CREATURE_2_ID: u32 = 2;
+    const CREATURE_3_ID: u32 = 3;
+    const PET_ID: u32 = 10;
+
+    #[derive(Default)]
+    struct CustomCharacterRules {}
+
+    impl CharacterRules<CustomRules> for CustomCharacterRules {
+        type CreatureId = u32;
+        type ObjectId = u32;
+        type Statistic = EmptyStat;
+        type StatisticsSeed = ();
+        type StatisticsAlteration = ();
+        type Status = EmptyStatus;
+        type StatusesAlteration = ();
+
+        fn on_summoner_removed(
+            &self,
+            _state: &BattleState<CustomRules>,
+            _summoner: &dyn Character<CustomRules>,
+            summon: &dyn Character<CustomRules>,
+        ) -> bool {
+            // Pets disappear together with their summoner.
+            summon.entity_id().creature().ok() == Some(PET_ID)
+        }
+    }
+
+    battle_rules_with_character! { CustomCharacterRules }
+    let entity_1_id = EntityId::Creature(CREATURE_1_ID);
+    let entity_2_id = EntityId::Creature(CREATURE_2_ID);
+    let entity_3_id = EntityId::Creature(CREATURE_3_ID);
+    let pet_id = EntityId::Creature(PET_ID);
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    util::object(&mut server, CREATURE_1_ID, ());
+    // Summoners must be existing creatures.
+    assert_eq!(
+        CreateCreature::trigger(&mut server, CREATURE_2_ID, TEAM_1_ID, ())
+            .summoner(EntityId::Creature(CREATURE_ERR_ID))
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::EntityNotFound(EntityId::Creature(
+            CREATURE_ERR_ID
+        )))
+    );
+    assert_eq!(
+        CreateCreature::trigger(&mut server, CREATURE_2_ID, TEAM_1_ID, ())
+            .summoner(EntityId::Object(CREATURE_1_ID))
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::NotACreature(EntityId::Object(CREATURE_1_ID)))
+    );
+    // Summon two creatures.
+    for id in &[CREATURE_2_ID, PET_ID] {
+        assert_eq!(
+            CreateCreature::trigger(&mut server, *id, TEAM_1_ID, ())
+                .summoner(entity_1_id)
+                .fire()
+                .err(),
+            None
+        );
+    }
+    let entities = server.battle().entities();
+    assert_eq!(entities.summoner(&entity_2_id), Some(&entity_1_id));
+    assert_eq!(entities.summoner(&entity_1_id), None);
+    assert_eq!(
+        entities.summons(&entity_1_id).collect::<Vec<_>>(),
+        vec![&entity_2_id, &pet_id]
+    );
+    // Links follow a change of id.
+    assert_eq!(
+        ReidentifyCreature::trigger(&mut server, CREATURE_1_ID, CREATURE_3_ID)
+            .fire()
+            .err(),
+        None
+    );
+    let entities = server.battle().entities();
+    assert_eq!(entities.summoner(&entity_2_id), Some(&entity_3_id));
+    assert_eq!(entities.summons(&entity_1_id).count(), 0);
+    // Removing the summoner removes only the pet.
+    assert_eq!(
+        RemoveCreature::trigger(&mut server, CREATURE_3_ID)
+            .fire()
+            .err(),
+        None
+    );
+    let entities = server.battle().entities();
+    assert!(entities.creature(&CREATURE_2_ID).is_some());
+    assert!(entities.creature(&PET_ID).is_none());
+    assert_eq!(entities.summoner(&entity_2_id), None);
+}
+
+#[test]
+fn transform_creature() {
+    #[derive(Default)]
+    pub struct CustomCharacterRules {
+        transforms: RefCell<u32>,
+    }
+
+    impl CharacterRules<CustomRules> for CustomCharacterRules {
+        type CreatureId = u32;
+        type ObjectId = ();
+        type Statistic = SimpleStatistic<u32, u32>;
+        // Vec with pair (id, value).
+        type StatisticsSeed = Vec<(u32, u32)>;
+        type StatisticsAlteration = ();
+        type Status = SimpleStatus<u32, u32>;
+        type StatusesAlteration = ();
+
+        fn generate_statistics(
+            &self,
+            seed: &Option<Self::StatisticsSeed>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) -> Box<dyn Iterator<Item = Self::Statistic>> {
+            let v: Vec<_> = seed
+                .iter()
+                .flatten()
+                .map(|(id, value)| SimpleStatistic::new(*id, *value))
+                .collect();
+            Box::new(v.into_iter())
+        }
+
+        fn generate_status(
+            &self,
+            _character: &dyn Character<CustomRules>,
+            status_id: &StatusId<CustomRules>,
+            _potency: &Option<Potency<CustomRules>>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) -> Option<Status<CustomRules>> {
+            Some(SimpleStatus::new(*status_id, 0, None))
+        }
+
+        fn on_transform(
+            &self,
+            _state: &BattleState<CustomRules>,
+            _character: &dyn Character<CustomRules>,
+            _event_queue: &mut Option<EventQueue<CustomRules>>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) {
+            *self.transforms.borrow_mut() += 1;
+        }
+    }
+
+    #[derive(Default)]
+    pub struct CustomActorRules {}
+
+    impl<R: BattleRules + 'static> ActorRules<R> for CustomActorRules {
+        type Ability = EmptyAbility;
+        // Id of the only ability.
+        type AbilitiesSeed = u32;
+        type Activation = ();
+        type AbilitiesAlteration = ();
+
+        fn generate_abilities(
+            &self,
+            seed: &Option<Self::AbilitiesSeed>,
+            _entropy: &mut Entropy<R>,
+            _metrics: &mut WriteMetrics<R>,
+        ) -> Box<dyn Iterator<Item = Self::Ability>> {
+            let v: Vec<_> = seed.iter().map(|id| EmptyAbility { id: *id }).collect();
+            Box::new(v.into_iter())
+        }
+    }
+
+    battle_rules! {
+        EmptyTeamRules,
+        CustomCharacterRules,
+        CustomActorRules,
+        EmptyFightRules,
+        EmptyUserRules,
+        EmptySpaceRules,
+        EmptyRoundsRules,
+        EmptyEntropyRules
+    }
+
+    static STAT_1_ID: StatisticId<CustomRules> = 1;
+    static STAT_2_ID: StatisticId<CustomRules> = 2;
+    static ABILITY_1_ID: AbilityId<CustomRules> = 1;
+    static ABILITY_2_ID: AbilityId<CustomRules> = 2;
+    static STATUS_ID: StatusId<CustomRules> = 1;
+    const STAT_VALUE: u32 = 10;
+    const ENTITY_1_ID: EntityId<CustomRules> = EntityId::Creature(CREATURE_1_ID);
+    let transforms = |server: &Server<CustomRules>| {
+        *server
+            .battle()
+            .rules()
+            .character_rules()
+            .transforms
+            .borrow()
+    };
+    // Create a new creature with one statistic, one ability and one status.
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    assert_eq!(
+        CreateCreature::trigger(&mut server, CREATURE_1_ID, TEAM_1_ID, ())
+            .statistics_seed(vec![(STAT_1_ID, STAT_VALUE)])
+            .abilities_seed(ABILITY_1_ID)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(
+        InflictStatus::trigger(&mut server, ENTITY_1_ID, STATUS_ID)
+            .fire()
+            .err(),
+        None
+    );
+    // Transform should fail for non existing creatures.
+    assert_eq!(
+        TransformCreature::trigger(&mut server, CREATURE_ERR_ID)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::CreatureNotFound(CREATURE_ERR_ID))
+    );
+    // Start preparing an ability.
+    util::start_turn(&mut server, &ENTITY_1_ID);
+    assert_eq!(
+        ActivateAbility::trigger(&mut server, ENTITY_1_ID, ABILITY_1_ID)
+            .delay(1)
+            .fire()
+            .err(),
+        None
+    );
+    assert!(server
+        .battle()
+        .rounds()
+        .pending_activation(&ENTITY_1_ID)
+        .is_some());
+    // Transform the creature, keeping its statuses.
+    assert_eq!(
+        TransformCreature::trigger(&mut server, CREATURE_1_ID)
+            .statistics_seed(vec![(STAT_2_ID, STAT_VALUE)])
+            .abilities_seed(ABILITY_2_ID)
+            .keep_statuses()
+            .fire()
+            .err(),
+        None
+    );
+    // Verify that statistics and abilities were replaced, while the identity was preserved.
+    let creature = server.battle().entities().creature(&CREATURE_1_ID).unwrap();
+    assert!(creature.statistic(&STAT_1_ID).is_none());
+    assert_eq!(
+        creature.statistic(&STAT_2_ID),
+        Some(&SimpleStatistic::new(STAT_2_ID, STAT_VALUE))
+    );
+    assert!(creature.ability(&ABILITY_1_ID).is_none());
+    assert!(creature.ability(&ABILITY_2_ID).is_some());
+    assert!(creature.status(&STATUS_ID).is_some());
+    assert_eq!(*creature.team_id(), TEAM_1_ID);
+    // Verify that the pending activation was interrupted.
+    assert!(server
+        .battle()
+        .rounds()
+        .pending_activation(&ENTITY_1_ID)
+        .is_none());
+    // Verify that the callback was invoked.
+    assert_eq!(transforms(&server), 1);
+    // Transform the creature again, clearing its statuses.
+    assert_eq!(
+        TransformCreature::trigger(&mut server, CREATURE_1_ID)
+            .statistics_seed(vec![(STAT_2_ID, STAT_VALUE)])
+            .abilities_seed(ABILITY_2_ID)
+            .fire()
+            .err(),
+        None
+    );
+    let creature = server.battle().entities().creature(&CREATURE_1_ID).unwrap();
+    assert!(creature.status(&STATUS_ID).is_none());
+    assert_eq!(transforms(&server), 2);
+    // Late joiners receive the transformed creature.
+    let mut new_server = util::server(CustomRules::new());
+    for event in server.bootstrap_events_for_new_client() {
+        assert_eq!(new_server.receive(event).err(), None);
+    }
+    assert!(new_server.battle().history().events()[0].synthetic());
+    let creature = new_server
+        .battle()
+        .entities()
+        .creature(&CREATURE_1_ID)
+        .unwrap();
+    assert!(creature.statistic(&STAT_1_ID).is_none());
+    assert!(creature.statistic(&STAT_2_ID).is_some());
+    assert!(creature.ability(&ABILITY_1_ID).is_none());
+    assert!(creature.ability(&ABILITY_2_ID).is_some());
+}
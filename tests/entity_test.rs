@@ -1,6 +1,14 @@
-use weasel::battle::{BattleController, BattleRules};
-use weasel::entity::{Entity, EntityId};
-use weasel::{battle_rules, rules::empty::*, WeaselError};
+use weasel::battle::{Battle, BattleController, BattleRules};
+use weasel::character::CharacterRules;
+use weasel::creature::CreateCreature;
+use weasel::entity::{AlterTags, Entity, EntityId, SetTimeToLive, Tag};
+use weasel::entropy::Entropy;
+use weasel::event::EventTrigger;
+use weasel::metric::WriteMetrics;
+use weasel::object::{CreateObject, RemoveObject};
+use weasel::round::EndRound;
+use weasel::rules::statistic::SimpleStatistic;
+use weasel::{battle_rules, battle_rules_with_character, rules::empty::*, Server, WeaselError};
 
 const TEAM_1_ID: u32 = 1;
 const CREATURE_1_ID: u32 = 1;
@@ -64,3 +72,279 @@ fn entity_id_equality() {
     assert_ne!(ENTITY_O1_ID, ENTITY_O2_ID);
     assert_ne!(ENTITY_O1_ID, ENTITY_C1_ID);
 }
+
+#[test]
+fn time_to_live() {
+    // Create the battle.
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    util::creature(&mut server, CREATURE_2_ID, TEAM_1_ID, ());
+    util::object(&mut server, OBJECT_1_ID, ());
+    // Time to live can't be set on missing entities.
+    assert_eq!(
+        SetTimeToLive::trigger(&mut server, ENTITY_O2_ID)
+            .rounds(1)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::EntityNotFound(ENTITY_O2_ID))
+    );
+    // Set a time to live on all entities, then clear it for the second creature.
+    for (id, rounds) in &[(ENTITY_C1_ID, 2), (ENTITY_C2_ID, 1), (ENTITY_O1_ID, 1)] {
+        assert_eq!(
+            SetTimeToLive::trigger(&mut server, *id)
+                .rounds(*rounds)
+                .fire()
+                .err(),
+            None
+        );
+    }
+    assert_eq!(
+        SetTimeToLive::trigger(&mut server, ENTITY_C2_ID)
+            .fire()
+            .err(),
+        None
+    );
+    let entities = server.battle().entities();
+    assert_eq!(entities.time_to_live(&ENTITY_C1_ID), Some(2));
+    assert_eq!(entities.time_to_live(&ENTITY_C2_ID), None);
+    assert_eq!(entities.expiring().count(), 2);
+    // The object expires at the end of the first round.
+    assert_eq!(EndRound::trigger(&mut server).fire().err(), None);
+    let entities = server.battle().entities();
+    assert!(entities.object(&OBJECT_1_ID).is_none());
+    assert!(entities.creature(&CREATURE_1_ID).is_some());
+    assert_eq!(entities.time_to_live(&ENTITY_C1_ID), Some(1));
+    // The first creature expires at the end of the second round.
+    assert_eq!(EndRound::trigger(&mut server).fire().err(), None);
+    let entities = server.battle().entities();
+    assert!(entities.creature(&CREATURE_1_ID).is_none());
+    assert!(entities.creature(&CREATURE_2_ID).is_some());
+    assert_eq!(entities.expiring().count(), 0);
+}
+
+#[test]
+fn tags() {
+    const FLYING: Tag = 1;
+    const UNDEAD: Tag = 2;
+    // Create the battle.
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    util::creature(&mut server, CREATURE_2_ID, TEAM_1_ID, ());
+    util::object(&mut server, OBJECT_1_ID, ());
+    // Tags can't be set on missing entities.
+    assert_eq!(
+        AlterTags::trigger(&mut server, ENTITY_O2_ID)
+            .add(FLYING)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::EntityNotFound(ENTITY_O2_ID))
+    );
+    // Tag all entities.
+    for id in &[ENTITY_C1_ID, ENTITY_C2_ID, ENTITY_O1_ID] {
+        assert_eq!(
+            AlterTags::trigger(&mut server, *id)
+                .add(FLYING)
+                .fire()
+                .err(),
+            None
+        );
+    }
+    assert_eq!(
+        AlterTags::trigger(&mut server, ENTITY_C1_ID)
+            .remove(FLYING)
+            .add(UNDEAD)
+            .fire()
+            .err(),
+        None
+    );
+    let entities = server.battle().entities();
+    assert_eq!(
+        entities.tags(&ENTITY_C1_ID).collect::<Vec<_>>(),
+        vec![UNDEAD]
+    );
+    assert!(entities.has_tag(&ENTITY_C2_ID, FLYING));
+    assert!(!entities.has_tag(&ENTITY_C2_ID, UNDEAD));
+    assert_eq!(
+        entities.entities_with_tag(FLYING).collect::<Vec<_>>(),
+        vec![&ENTITY_C2_ID, &ENTITY_O1_ID]
+    );
+    // Tags are forgotten when an entity is removed.
+    assert_eq!(
+        RemoveObject::trigger(&mut server, OBJECT_1_ID).fire().err(),
+        None
+    );
+    let entities = server.battle().entities();
+    assert_eq!(entities.entities_with_tag(FLYING).count(), 1);
+    assert_eq!(entities.tags(&ENTITY_O1_ID).count(), 0);
+}
+
+#[test]
+fn sorted_by_statistic() {
+    const STAT_ID: u32 = 1;
+    const CREATURE_3_ID: u32 = 3;
+
+    #[derive(Default)]
+    pub struct CustomCharacterRules {}
+
+    impl CharacterRules<CustomRules> for CustomCharacterRules {
+        type CreatureId = u32;
+        type ObjectId = u32;
+        type Statistic = SimpleStatistic<u32, i32>;
+        type StatisticsSeed = i32;
+        type StatisticsAlteration = ();
+        type Status = EmptyStatus;
+        type StatusesAlteration = ();
+
+        fn generate_statistics(
+            &self,
+            seed: &Option<Self::StatisticsSeed>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) -> Box<dyn Iterator<Item = Self::Statistic>> {
+            // Characters without seed don't have any statistic.
+            let v: Vec<_> = seed
+                .iter()
+                .map(|value| SimpleStatistic::new(STAT_ID, *value))
+                .collect();
+            Box::new(v.into_iter())
+        }
+    }
+
+    battle_rules_with_character! { CustomCharacterRules }
+    // Create the battle.
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    for (id, value) in &[(CREATURE_2_ID, 5), (CREATURE_1_ID, 5), (CREATURE_3_ID, 10)] {
+        assert_eq!(
+            CreateCreature::trigger(&mut server, *id, TEAM_1_ID, ())
+                .statistics_seed(*value)
+                .fire()
+                .err(),
+            None
+        );
+    }
+    assert_eq!(
+        CreateObject::trigger(&mut server, OBJECT_1_ID, ())
+            .statistics_seed(1)
+            .fire()
+            .err(),
+        None
+    );
+    util::object(&mut server, OBJECT_2_ID, ());
+    // Sort the characters.
+    let entities = server.battle().entities();
+    let ids = |descending| -> Vec<_> {
+        entities
+            .sorted_by_statistic(&STAT_ID, descending, |a, b| a.value().cmp(&b.value()))
+            .into_iter()
+            .map(|character| *character.entity_id())
+            .collect()
+    };
+    assert_eq!(
+        ids(false),
+        vec![
+            EntityId::Object(OBJECT_1_ID),
+            EntityId::Creature(CREATURE_1_ID),
+            EntityId::Creature(CREATURE_2_ID),
+            EntityId::Creature(CREATURE_3_ID)
+        ]
+    );
+    assert_eq!(
+        ids(true),
+        vec![
+            EntityId::Creature(CREATURE_3_ID),
+            EntityId::Creature(CREATURE_1_ID),
+            EntityId::Creature(CREATURE_2_ID),
+            EntityId::Object(OBJECT_1_ID)
+        ]
+    );
+}
+
+#[test]
+fn borrowed_lookups() {
+    #[derive(Default)]
+    pub struct CustomCharacterRules {}
+
+    impl CharacterRules<CustomRules> for CustomCharacterRules {
+        type CreatureId = String;
+        type ObjectId = String;
+        type Statistic = EmptyStat;
+        type StatisticsSeed = ();
+        type StatisticsAlteration = ();
+        type Status = EmptyStatus;
+        type StatusesAlteration = ();
+    }
+
+    battle_rules_with_character! { CustomCharacterRules }
+    // Create the battle.
+    let battle = Battle::builder(CustomRules::new())
+        .entities_capacity(16, 4)
+        .build();
+    let mut server = Server::builder(battle).build();
+    assert!(server.battle().entities().creatures_capacity() >= 16);
+    assert!(server.battle().entities().objects_capacity() >= 4);
+    util::team(&mut server, TEAM_1_ID);
+    assert_eq!(
+        CreateCreature::trigger(&mut server, "creature".to_string(), TEAM_1_ID, ())
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(
+        CreateObject::trigger(&mut server, "object".to_string(), ())
+            .fire()
+            .err(),
+        None
+    );
+    // Look up entities through borrowed ids.
+    let entities = server.battle().entities();
+    assert_eq!(entities.creatures_len(), 1);
+    assert_eq!(entities.objects_len(), 1);
+    assert!(entities.creature("creature").is_some());
+    assert!(entities.creature("object").is_none());
+    assert!(entities.object("object").is_some());
+    assert!(entities.team(&TEAM_1_ID).is_some());
+}
+
+#[cfg(feature = "arena")]
+#[test]
+fn arena_handles() {
+    use weasel::creature::{ReidentifyCreature, RemoveCreature};
+    const CREATURE_3_ID: u32 = 3;
+    // Create a battle with three creatures.
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    for id in &[CREATURE_1_ID, CREATURE_2_ID] {
+        util::creature(&mut server, *id, TEAM_1_ID, ());
+    }
+    let entities = server.battle().entities();
+    let handle_1 = entities.creature_handle(&CREATURE_1_ID).unwrap();
+    let handle_2 = entities.creature_handle(&CREATURE_2_ID).unwrap();
+    // Handles survive the removal of other creatures and changes of id.
+    assert_eq!(
+        RemoveCreature::trigger(&mut server, CREATURE_1_ID)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(
+        ReidentifyCreature::trigger(&mut server, CREATURE_2_ID, CREATURE_3_ID)
+            .fire()
+            .err(),
+        None
+    );
+    let entities = server.battle().entities();
+    assert!(entities.creature_by_handle(handle_1).is_none());
+    let creature = entities.creature_by_handle(handle_2).unwrap();
+    assert_eq!(*creature.entity_id(), EntityId::Creature(CREATURE_3_ID));
+    assert_eq!(entities.creature_handle(&CREATURE_3_ID), Some(handle_2));
+    // Handles of removed creatures are never reused.
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    let entities = server.battle().entities();
+    assert!(entities.creature_by_handle(handle_1).is_none());
+    assert_ne!(entities.creature_handle(&CREATURE_1_ID), Some(handle_1));
+}
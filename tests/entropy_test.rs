@@ -118,6 +118,8 @@ fn use_entropy() {
     let server = scenario!();
     // Check that statistics and abilities have been randomized.
     stat_abi_randomness_check!(server);
+    // Checkpoints are disabled by default.
+    assert_eq!(server.battle().entropy().checkpoints_len(), 0);
 }
 
 #[cfg(feature = "serialization")]
@@ -134,3 +136,70 @@ fn entropy_reload() {
     // Verify that randomization is the same.
     stat_abi_randomness_check!(server);
 }
+
+#[test]
+fn rewind_entropy() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use weasel::entropy::EntropyRules;
+    use weasel::UndoManager;
+
+    // Every server is created with a different entropy, seeded outside of the history.
+    let seed = Rc::new(Cell::new(SEED));
+    let factory = {
+        let seed = seed.clone();
+        move || {
+            let mut rules = CustomRules::new();
+            rules.entropy_rules = Some(UniformDistribution::default().checkpointing());
+            let mut battle = Battle::builder(rules).build();
+            let model = battle.entropy().rules().generate_model(&Some(seed.get()));
+            *battle.entropy_mut().model_mut() = model;
+            seed.set(seed.get() + 1);
+            Server::builder(battle).build()
+        }
+    };
+    let mut manager = UndoManager::new(Box::new(factory));
+    util::team(manager.server_mut(), TEAM_1_ID);
+    util::creature(manager.server_mut(), CREATURE_1_ID, TEAM_1_ID, ());
+    stat_abi_randomness_check!(manager.server());
+    // A checkpoint is taken before each event.
+    let entropy = manager.server().battle().entropy();
+    assert_eq!(entropy.checkpoints_len(), 2);
+    assert!(entropy.checkpoint(1).is_some());
+    assert!(entropy.checkpoint(2).is_none());
+    // Undo the creature's creation and redo it with a new action.
+    assert_eq!(manager.undo().err(), None);
+    assert!(manager
+        .server()
+        .battle()
+        .entities()
+        .creature(&CREATURE_1_ID)
+        .is_none());
+    util::creature(manager.server_mut(), CREATURE_1_ID, TEAM_1_ID, ());
+    // Entropy was rewound, thus the creature is randomized in the same way.
+    stat_abi_randomness_check!(manager.server());
+}
+
+#[test]
+fn prune_checkpoints() {
+    use weasel::UndoManager;
+
+    // Every server is created with a team that can't be undone.
+    let factory = || {
+        let mut rules = CustomRules::new();
+        rules.entropy_rules = Some(UniformDistribution::default().checkpointing());
+        let mut server = Server::builder(Battle::builder(rules).build()).build();
+        util::team(&mut server, TEAM_1_ID);
+        assert_eq!(server.battle().entropy().checkpoints_len(), 1);
+        server
+    };
+    let mut manager = UndoManager::new(Box::new(factory));
+    // Checkpoints older than the undo floor are dropped.
+    assert_eq!(manager.server().battle().entropy().checkpoints_len(), 0);
+    util::creature(manager.server_mut(), CREATURE_1_ID, TEAM_1_ID, ());
+    assert!(manager.server().battle().entropy().checkpoint(1).is_some());
+    assert_eq!(manager.undo().err(), None);
+    assert_eq!(manager.server().battle().entropy().checkpoints_len(), 0);
+    util::creature(manager.server_mut(), CREATURE_1_ID, TEAM_1_ID, ());
+    assert_eq!(manager.server().battle().entropy().checkpoints_len(), 1);
+}
@@ -2,35 +2,52 @@
 use serde::{Deserialize, Serialize};
 use std::any::Any;
 use std::marker::PhantomData;
-use weasel::ability::ActivateAbility;
-use weasel::actor::{Action, Actor, ActorRules, AlterAbilities, RegenerateAbilities};
-use weasel::battle::{Battle, BattleController, BattleRules, BattleState, EndBattle};
+use weasel::ability::{ActivateAbility, InterruptAbility};
+use weasel::actor::{
+    Action, Actor, ActorRules, AlterAbilities, RechargeAbilities, RegenerateAbilities,
+};
+use weasel::battle::{
+    Battle, BattleController, BattleRules, BattleState, EndBattle, StartBattle, VerifyState,
+};
 use weasel::character::{AlterStatistics, RegenerateStatistics};
-use weasel::creature::{ConvertCreature, CreateCreature, RemoveCreature};
-use weasel::entity::EntityId;
+use weasel::creature::{
+    ConvertCreature, CreateCreature, ReidentifyCreature, RemoveCreature, SpawnWave,
+    TransferOwnership, TransformCreature,
+};
+use weasel::entity::{AlterTags, EntityId, SetTimeToLive};
 use weasel::entropy::{Entropy, EntropyModel, ResetEntropy};
 use weasel::event::{
     ClientEventPrototype, Conditional, DefaultOutput, DummyEvent, Event, EventKind, EventProcessor,
-    EventQueue, EventSink, EventSinkId, EventTrigger, ServerSink,
+    EventQueue, EventSink, EventSinkId, EventTrigger, EventVisitor, Priority, ServerSink,
+    WithPriority,
 };
 use weasel::fight::ApplyImpact;
 use weasel::metric::WriteMetrics;
-use weasel::object::{CreateObject, RemoveObject};
+use weasel::object::{CreateObject, RemoveObject, TransferObjectOwnership};
+use weasel::player::{PlayerId, RegisterPlayer, RemovePlayer};
 use weasel::power::InvokePower;
-use weasel::round::{EndRound, EndTurn, EnvironmentTurn, ResetRounds, RoundsModel, StartTurn};
+use weasel::round::{
+    AddTurnActor, EndRound, EndTurn, EnvironmentTurn, RemoveTurnActor, ResetRounds, RestoreRounds,
+    RoundsModel, ScheduleEvent, StartTurn, Tick, TurnTimedOut,
+};
 use weasel::rules::ability::SimpleAbility;
 #[cfg(feature = "serialization")]
 use weasel::serde::FlatEvent;
-use weasel::space::{AlterSpace, MoveEntity, ResetSpace, SpaceModel};
-use weasel::status::{AlterStatuses, ClearStatus, InflictStatus};
+use weasel::space::{AlterSpace, MoveEntity, MoveEntityAlongPath, ResetSpace, SpaceModel};
+use weasel::status::{
+    AlterStatusDuration, AlterStatuses, ClearStatus, DurationChange, InflictStatus,
+};
 use weasel::team::{
-    AlterPowers, ConcludeObjectives, Conclusion, CreateTeam, RegeneratePowers, Relation,
-    RemoveTeam, ResetObjectives, SetRelations,
+    AddScore, AlterPowers, ConcludeObjectives, Conclusion, CreateTeam, RegeneratePowers, Relation,
+    RemoveTeam, RenameTeam, ResetObjectives, SetRelations, TradeProposal, TransferResource,
 };
 #[cfg(feature = "serialization")]
 use weasel::user::UserEventPacker;
-use weasel::user::{UserMetricId, UserRules};
-use weasel::{battle_rules, battle_rules_with_actor, battle_rules_with_user, rules::empty::*};
+use weasel::user::{UserEventRegistry, UserMetricId, UserRules};
+use weasel::visibility::{HideEntity, RevealEntity};
+use weasel::{
+    battle_rules, battle_rules_with_actor, battle_rules_with_user, match_event, rules::empty::*,
+};
 use weasel::{WeaselError, WeaselResult};
 
 #[cfg(feature = "serialization")]
@@ -38,6 +55,7 @@ mod helper;
 
 const TEAM_1_ID: u32 = 1;
 const CREATURE_1_ID: u32 = 1;
+const PLAYER_1_ID: PlayerId = 1;
 
 /// Declare an user event.
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
@@ -242,6 +260,92 @@ fn conditional() {
     assert_eq!(events[events.len() - 1].kind(), EventKind::DummyEvent);
 }
 
+#[test]
+fn event_priority() {
+    #[derive(Default)]
+    pub struct CustomActorRules {}
+
+    impl ActorRules<CustomRules> for CustomActorRules {
+        type Ability = SimpleAbility<u32, u32>;
+        type AbilitiesSeed = ();
+        type Activation = u32;
+        type AbilitiesAlteration = u32;
+
+        fn generate_abilities(
+            &self,
+            _: &Option<Self::AbilitiesSeed>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) -> Box<dyn Iterator<Item = Self::Ability>> {
+            let v = vec![SimpleAbility::new(ABILITY_ID, 0)];
+            Box::new(v.into_iter())
+        }
+
+        fn activate(
+            &self,
+            _state: &BattleState<CustomRules>,
+            _action: Action<CustomRules>,
+            event_queue: &mut Option<EventQueue<CustomRules>>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) {
+            // Fire events in the opposite order of their priority.
+            ResetEntropy::trigger(
+                &mut event_queue
+                    .as_mut()
+                    .map(|queue| WithPriority::new(queue, Priority::Low)),
+            )
+            .fire();
+            DummyEvent::trigger(event_queue).fire();
+            AlterAbilities::trigger(
+                &mut event_queue
+                    .as_mut()
+                    .map(|queue| WithPriority::new(queue, Priority::High)),
+                ENTITY_1_ID,
+                1,
+            )
+            .fire();
+            DummyEvent::trigger(
+                &mut event_queue
+                    .as_mut()
+                    .map(|queue| WithPriority::new(queue, Priority::High)),
+            )
+            .fire();
+        }
+    }
+
+    const ENTITY_1_ID: EntityId<CustomRules> = EntityId::Creature(CREATURE_1_ID);
+    const ABILITY_ID: u32 = 1;
+
+    battle_rules_with_actor! { CustomActorRules }
+
+    // Create a battle with one creature.
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    util::start_turn(&mut server, &ENTITY_1_ID);
+    // Derived events are processed by priority, then in insertion order.
+    assert_eq!(
+        ActivateAbility::trigger(&mut server, ENTITY_1_ID, ABILITY_ID)
+            .fire()
+            .err(),
+        None
+    );
+    let kinds: Vec<_> = server.battle().history().events()[4..]
+        .iter()
+        .map(|event| event.kind())
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            EventKind::AlterAbilities,
+            EventKind::DummyEvent,
+            EventKind::DummyEvent,
+            EventKind::ResetEntropy
+        ]
+    );
+}
+
 macro_rules! user_event_check {
     ($server: expr, $data: expr) => {{
         let event = &$server.battle().history().events()[0];
@@ -364,13 +468,32 @@ macro_rules! events_vec {
         events.push(DummyEvent::trigger(&mut ()).event());
         events.push(CreateTeam::trigger(&mut (), TEAM_1_ID).event());
         events.push(CreateCreature::trigger(&mut (), TEAM_1_ID, CREATURE_1_ID, ()).event());
+        events.push(
+            SpawnWave::trigger(&mut ())
+                .creature(CREATURE_1_ID, TEAM_1_ID, (), None, None)
+                .event(),
+        );
         events.push(CreateObject::trigger(&mut (), OBJECT_1_ID, ()).event());
         events.push(MoveEntity::trigger(&mut (), ENTITY_1_ID, ()).event());
+        events.push(MoveEntityAlongPath::trigger(&mut (), ENTITY_1_ID, vec![()]).event());
+        events.push(RechargeAbilities::trigger(&mut (), ENTITY_1_ID).event());
+        events.push(
+            SetTimeToLive::trigger(&mut (), ENTITY_1_ID)
+                .rounds(1)
+                .event(),
+        );
+        events.push(AlterTags::trigger(&mut (), ENTITY_1_ID).add(1).event());
         events.push(StartTurn::trigger(&mut (), ENTITY_1_ID).event());
         events.push(EndTurn::trigger(&mut ()).event());
+        events.push(TurnTimedOut::trigger(&mut (), vec![ENTITY_1_ID]).event());
+        events.push(AddTurnActor::trigger(&mut (), ENTITY_1_ID).event());
+        events.push(RemoveTurnActor::trigger(&mut (), ENTITY_1_ID).event());
         events.push(EndRound::trigger(&mut ()).event());
         events.push(EnvironmentTurn::trigger(&mut ()).event());
+        events.push(Tick::trigger(&mut (), 1).event());
+        events.push(RestoreRounds::trigger(&mut (), 1, 1).event());
         events.push(ActivateAbility::trigger(&mut (), ENTITY_1_ID, ABILITY_1_ID).event());
+        events.push(InterruptAbility::trigger(&mut (), ENTITY_1_ID).event());
         events.push(InvokePower::trigger(&mut (), TEAM_1_ID, POWER_1_ID).event());
         events.push(ApplyImpact::trigger(&mut (), ()).event());
         events.push(AlterStatistics::trigger(&mut (), ENTITY_1_ID, ()).event());
@@ -382,20 +505,55 @@ macro_rules! events_vec {
         events.push(RegeneratePowers::trigger(&mut (), TEAM_1_ID.clone()).event());
         events.push(InflictStatus::trigger(&mut (), ENTITY_1_ID.clone(), STATUS_1_ID).event());
         events.push(ClearStatus::trigger(&mut (), ENTITY_1_ID.clone(), STATUS_1_ID).event());
+        events.push(
+            AlterStatusDuration::trigger(&mut (), ENTITY_1_ID, STATUS_1_ID, DurationChange::Reset)
+                .event(),
+        );
         events.push(ConvertCreature::trigger(&mut (), CREATURE_1_ID, TEAM_1_ID).event());
+        events.push(ReidentifyCreature::trigger(&mut (), CREATURE_1_ID, CREATURE_1_ID).event());
+        events.push(TransformCreature::trigger(&mut (), CREATURE_1_ID).event());
+        events.push(
+            TransferOwnership::trigger(&mut (), CREATURE_1_ID)
+                .owner(0)
+                .event(),
+        );
+        events.push(
+            TransferObjectOwnership::trigger(&mut (), OBJECT_1_ID)
+                .team(TEAM_1_ID)
+                .event(),
+        );
         events.push(
             SetRelations::trigger(&mut (), &[(TEAM_1_ID, TEAM_1_ID, Relation::Ally)]).event(),
         );
         events.push(ConcludeObjectives::trigger(&mut (), TEAM_1_ID, Conclusion::Victory).event());
+        events.push(AddScore::trigger(&mut (), TEAM_1_ID, 1).event());
+        events.push(TransferResource::trigger(&mut (), TEAM_1_ID, TEAM_1_ID, 0, 1).event());
+        events.push(TradeProposal::trigger(&mut (), TEAM_1_ID, TEAM_1_ID).event());
         events.push(RemoveCreature::trigger(&mut (), CREATURE_1_ID).event());
         events.push(RemoveObject::trigger(&mut (), OBJECT_1_ID).event());
         events.push(RemoveTeam::trigger(&mut (), TEAM_1_ID).event());
+        events.push(RenameTeam::trigger(&mut (), TEAM_1_ID, TEAM_1_ID).event());
         events.push(AlterSpace::trigger(&mut (), ()).event());
+        events.push(RevealEntity::trigger(&mut (), ENTITY_1_ID, TEAM_1_ID).event());
+        events.push(
+            HideEntity::trigger(&mut (), ENTITY_1_ID)
+                .team(TEAM_1_ID)
+                .event(),
+        );
         events.push(ResetEntropy::trigger(&mut ()).event());
         events.push(ResetObjectives::trigger(&mut (), TEAM_1_ID).event());
         events.push(ResetRounds::trigger(&mut ()).event());
         events.push(ResetSpace::trigger(&mut ()).event());
+        events.push(StartBattle::trigger(&mut ()).event());
         events.push(EndBattle::trigger(&mut ()).event());
+        events.push(VerifyState::trigger(&mut (), 0).event());
+        events.push(RegisterPlayer::trigger(&mut (), PLAYER_1_ID, String::new()).event());
+        events.push(RemovePlayer::trigger(&mut (), PLAYER_1_ID).event());
+        events.push(
+            ScheduleEvent::trigger(&mut (), DummyEvent::trigger(&mut ()).event())
+                .after_rounds(1)
+                .event(),
+        );
         events
     }};
 }
@@ -413,6 +571,49 @@ fn events_debug() {
     }
 }
 
+#[test]
+fn events_visitor() {
+    #[derive(Default)]
+    struct Visitor {
+        visited: Vec<EventKind>,
+    }
+
+    impl<R: BattleRules + 'static> EventVisitor<R> for Visitor {
+        fn visit_dummy_event(&mut self, event: &DummyEvent<R>) {
+            self.visited.push(event.kind());
+        }
+
+        fn visit_inflict_status(&mut self, event: &InflictStatus<R>) {
+            self.visited.push(event.kind());
+        }
+
+        fn visit_schedule_event(&mut self, event: &ScheduleEvent<R>) {
+            self.visited.push(event.kind());
+        }
+    }
+
+    battle_rules! {}
+    let events = vec![
+        DummyEvent::trigger(&mut ()).event(),
+        CreateTeam::trigger(&mut (), TEAM_1_ID).event(),
+        InflictStatus::<CustomRules>::trigger(&mut (), EntityId::Creature(CREATURE_1_ID), 1)
+            .event(),
+        ScheduleEvent::trigger(&mut (), DummyEvent::trigger(&mut ()).event()).event(),
+    ];
+    let mut visitor = Visitor::default();
+    for event in &events {
+        visitor.visit(event);
+    }
+    assert_eq!(
+        visitor.visited,
+        vec![
+            EventKind::DummyEvent,
+            EventKind::InflictStatus,
+            EventKind::ScheduleEvent
+        ]
+    );
+}
+
 #[cfg(feature = "serialization")]
 #[test]
 fn events_serde() {
@@ -435,6 +636,34 @@ fn events_serde() {
     assert_eq!(deserialized_events, events);
 }
 
+#[cfg(feature = "serialization")]
+#[test]
+fn legacy_round_events_serde() {
+    battle_rules! {}
+    // Events as they were serialized before `StartRound` became `StartTurn`
+    // and the meaning of `EndRound` and `EndTurn` was swapped.
+    let json = r#"[
+        {"StartRound":{"ids":[{"Creature":1}]}},
+        {"EndRound":{}},
+        {"EndTurn":{}},
+        {"EnvironmentRound":{}}
+    ]"#;
+    let flat_events: Vec<FlatEvent<CustomRules>> = serde_json::from_str(json).unwrap();
+    let kinds: Vec<_> = flat_events
+        .into_iter()
+        .map(|e| e.migrate_legacy_rounds().boxed().kind())
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            EventKind::StartTurn,
+            EventKind::EndTurn,
+            EventKind::EndRound,
+            EventKind::EnvironmentTurn
+        ]
+    );
+}
+
 fn fire_event<R, P>(processor: &mut P) -> WeaselResult<(), R>
 where
     R: BattleRules + 'static,
@@ -469,3 +698,87 @@ fn event_processor_generic() {
     assert!(fire_event(&mut server).is_ok());
     assert!(fire_event(&mut client).is_ok());
 }
+
+#[test]
+fn iter_as_and_match_event() {
+    #[derive(Default)]
+    struct CustomUserRules {}
+
+    impl UserRules<CustomRules> for CustomUserRules {
+        type UserMetricId = u32;
+        #[cfg(feature = "serialization")]
+        type UserEventPackage = ();
+    }
+
+    battle_rules_with_user! { CustomUserRules }
+    // Create a server with a team, a creature and an user event.
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    assert_eq!(
+        MyEvent::trigger(&mut server, "data".to_string())
+            .fire()
+            .err(),
+        None
+    );
+    let history = server.battle().history();
+    // Iterate over events of a single type.
+    let ids: Vec<_> = history
+        .iter_as::<CreateCreature<_>>()
+        .map(|e| *e.id())
+        .collect();
+    assert_eq!(ids, vec![CREATURE_1_ID]);
+    assert_eq!(history.iter_as::<MyEvent<_>>().count(), 1);
+    assert_eq!(history.iter_as::<EndBattle<_>>().count(), 0);
+    // Match events on their concrete type.
+    let descriptions: Vec<_> = history
+        .events()
+        .iter()
+        .map(|event| {
+            match_event!(event, {
+                CreateTeam(e) => format!("team {}", e.id()),
+                CreateCreature(e) => format!("creature {}", e.id()),
+                MyEvent<CustomRules>(e) => e.data.clone(),
+                _ => "other".to_string(),
+            })
+        })
+        .collect();
+    assert_eq!(descriptions, vec!["team 1", "creature 1", "data"]);
+    // Without a default arm the macro evaluates to unit.
+    let mut teams = 0;
+    for event in history.events() {
+        match_event!(event, {
+            CreateTeam(_) => teams += 1,
+        });
+    }
+    assert_eq!(teams, 1);
+}
+
+#[test]
+#[should_panic(expected = "duplicated user event")]
+fn user_event_duplicated_id() {
+    #[derive(Default)]
+    struct CustomUserRules {}
+
+    impl UserRules<CustomRules> for CustomUserRules {
+        type UserMetricId = u32;
+        #[cfg(feature = "serialization")]
+        type UserEventPackage = ();
+
+        fn register_user_events(
+            &self,
+            registry: &mut UserEventRegistry<CustomRules>,
+        ) -> WeaselResult<(), CustomRules> {
+            registry.register(0, "first")?;
+            assert_eq!(
+                registry.register(0, "second").err(),
+                Some(WeaselError::DuplicatedUserEvent(0, "first".to_string()))
+            );
+            assert_eq!(registry.name(0), Some("first"));
+            registry.register(0, "third")
+        }
+    }
+
+    battle_rules_with_user! { CustomUserRules }
+    util::server(CustomRules::new());
+}
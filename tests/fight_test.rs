@@ -1,17 +1,25 @@
 use weasel::ability::ActivateAbility;
-use weasel::actor::{Action, Actor, ActorRules, AlterAbilities};
+use weasel::actor::{Action, ActionSuggestion, Actor, ActorRules, AlterAbilities, TrackedAbility};
 use weasel::battle::{BattleController, BattleRules, BattleState};
 use weasel::character::{AlterStatistics, Character, CharacterRules};
+use weasel::creature::CreateCreature;
 use weasel::entity::{EntityId, Transmutation};
 use weasel::entropy::Entropy;
-use weasel::event::{EventKind, EventQueue, EventTrigger};
+use weasel::event::{DummyEvent, EventKind, EventQueue, EventTrigger};
 use weasel::fight::{ApplyImpact, FightRules};
 use weasel::metric::WriteMetrics;
+use weasel::round::{EndTurn, TurnsCount};
 use weasel::rules::ability::SimpleAbility;
+use weasel::rules::fight::{Damage, SimpleFight, SimpleImpact};
+use weasel::rules::mixin::Chain;
 use weasel::rules::statistic::SimpleStatistic;
-use weasel::{battle_rules, rules::empty::*};
+use weasel::rules::status::SimpleStatus;
+use weasel::status::{InflictStatus, Potency, Status, StatusId};
+use weasel::team::CreateTeam;
+use weasel::{battle_rules, rules::empty::*, Server, WeaselError, WeaselResult};
 
 const TEAM_1_ID: u32 = 1;
+const TEAM_2_ID: u32 = 2;
 const CREATURE_1_ID: u32 = 1;
 const CREATURE_2_ID: u32 = 2;
 const ENTITY_1_ID: EntityId<CustomRules> = EntityId::Creature(CREATURE_1_ID);
@@ -179,3 +187,398 @@ fn default_works() {
     // ApplyImpact with default rules does not return an error.
     assert_eq!(ApplyImpact::trigger(&mut server, ()).fire().err(), None);
 }
+
+#[test]
+fn chained_rules() {
+    #[derive(Default)]
+    pub struct FirstActorRules {}
+
+    impl ActorRules<CustomRules> for FirstActorRules {
+        type Ability = EmptyAbility;
+        type AbilitiesSeed = ();
+        type Activation = ();
+        type AbilitiesAlteration = ();
+
+        fn generate_abilities(
+            &self,
+            _: &Option<Self::AbilitiesSeed>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) -> Box<dyn Iterator<Item = Self::Ability>> {
+            Box::new(std::iter::once(EmptyAbility { id: 1 }))
+        }
+
+        fn max_activations(
+            &self,
+            _state: &BattleState<CustomRules>,
+            _actor: &dyn Actor<CustomRules>,
+        ) -> Option<u32> {
+            Some(3)
+        }
+
+        fn max_recent_activations(&self) -> usize {
+            0
+        }
+    }
+
+    #[derive(Default)]
+    pub struct SecondActorRules {}
+
+    impl ActorRules<CustomRules> for SecondActorRules {
+        type Ability = EmptyAbility;
+        type AbilitiesSeed = ();
+        type Activation = ();
+        type AbilitiesAlteration = ();
+
+        fn generate_abilities(
+            &self,
+            _: &Option<Self::AbilitiesSeed>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) -> Box<dyn Iterator<Item = Self::Ability>> {
+            Box::new(std::iter::once(EmptyAbility { id: 2 }))
+        }
+
+        fn activable(
+            &self,
+            _state: &BattleState<CustomRules>,
+            action: Action<CustomRules>,
+        ) -> WeaselResult<(), CustomRules> {
+            if action.ability.id == 2 {
+                Err(WeaselError::GenericError)
+            } else {
+                Ok(())
+            }
+        }
+
+        fn max_activations(
+            &self,
+            _state: &BattleState<CustomRules>,
+            _actor: &dyn Actor<CustomRules>,
+        ) -> Option<u32> {
+            Some(1)
+        }
+
+        fn max_recent_activations(&self) -> usize {
+            0
+        }
+
+        fn valid_activations(
+            &self,
+            _state: &BattleState<CustomRules>,
+            _actor: &dyn Actor<CustomRules>,
+            _ability: &EmptyAbility,
+        ) -> Vec<Option<()>> {
+            vec![Some(())]
+        }
+
+        fn suggest_action(
+            &self,
+            _state: &BattleState<CustomRules>,
+            _actor: &dyn Actor<CustomRules>,
+        ) -> Option<ActionSuggestion<CustomRules>> {
+            Some(DummyEvent::trigger(&mut ()).prototype().into())
+        }
+
+        fn recharge(
+            &self,
+            _state: &BattleState<CustomRules>,
+            _actor: &dyn Actor<CustomRules>,
+            ability: &TrackedAbility<CustomRules>,
+        ) -> TurnsCount {
+            // Abilities never recharge once used.
+            if ability.last_used_turn().is_some() {
+                1
+            } else {
+                0
+            }
+        }
+
+        fn activate(
+            &self,
+            _state: &BattleState<CustomRules>,
+            _action: Action<CustomRules>,
+            mut event_queue: &mut Option<EventQueue<CustomRules>>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) {
+            ApplyImpact::trigger(&mut event_queue, ()).fire();
+        }
+    }
+
+    #[derive(Default)]
+    pub struct FirstFightRules {}
+
+    impl FightRules<CustomRules> for FirstFightRules {
+        type Impact = ();
+        type Potency = ();
+
+        fn apply_impact(
+            &self,
+            _state: &BattleState<CustomRules>,
+            _impact: &Self::Impact,
+            mut event_queue: &mut Option<EventQueue<CustomRules>>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) {
+            DummyEvent::trigger(&mut event_queue).fire();
+        }
+
+        fn features(&self) -> Vec<&'static str> {
+            vec!["first"]
+        }
+    }
+
+    #[derive(Default)]
+    pub struct SecondFightRules {}
+
+    impl FightRules<CustomRules> for SecondFightRules {
+        type Impact = ();
+        type Potency = ();
+
+        fn apply_impact(
+            &self,
+            _state: &BattleState<CustomRules>,
+            _impact: &Self::Impact,
+            mut event_queue: &mut Option<EventQueue<CustomRules>>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) {
+            CreateTeam::trigger(&mut event_queue, TEAM_2_ID).fire();
+        }
+
+        fn features(&self) -> Vec<&'static str> {
+            vec!["second"]
+        }
+    }
+
+    battle_rules! {
+        EmptyTeamRules,
+        EmptyCharacterRules,
+        Chain<FirstActorRules, SecondActorRules>,
+        Chain<FirstFightRules, SecondFightRules>,
+        EmptyUserRules,
+        EmptySpaceRules,
+        EmptyRoundsRules,
+        EmptyEntropyRules
+    }
+
+    let entity_1_id: EntityId<CustomRules> = EntityId::Creature(CREATURE_1_ID);
+    // Create scenario.
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    // The creature should know the abilities generated by both rules.
+    let creature = server.battle().entities().creature(&CREATURE_1_ID).unwrap();
+    assert_eq!(creature.abilities().count(), 2);
+    util::start_turn(&mut server, &entity_1_id);
+    // Activation profiles of both rules are considered, for the only activable ability.
+    let kinds: Vec<_> = server
+        .battle()
+        .legal_actions(&entity_1_id)
+        .iter()
+        .map(|event| event.kind())
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            EventKind::ActivateAbility,
+            EventKind::ActivateAbility,
+            EventKind::EndTurn
+        ]
+    );
+    // Any rules can prevent the activation.
+    assert_eq!(
+        ActivateAbility::trigger(&mut server, entity_1_id, 2)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::AbilityNotActivable(
+            entity_1_id,
+            2,
+            Box::new(WeaselError::GenericError)
+        ))
+    );
+    // Hooks are invoked in order.
+    assert_eq!(
+        ActivateAbility::trigger(&mut server, entity_1_id, 1)
+            .fire()
+            .err(),
+        None
+    );
+    let events = server.battle().history().events();
+    let kinds: Vec<_> = events[events.len() - 3..]
+        .iter()
+        .map(|event| event.kind())
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            EventKind::ApplyImpact,
+            EventKind::DummyEvent,
+            EventKind::CreateTeam
+        ]
+    );
+    // Activations are not remembered, since neither rules asks so.
+    let creature = server.battle().entities().creature(&CREATURE_1_ID).unwrap();
+    assert_eq!(creature.recent_activations().count(), 0);
+    // The strictest limit on activations is enforced.
+    assert_eq!(
+        ActivateAbility::trigger(&mut server, entity_1_id, 1)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::ActivationsExhausted(entity_1_id))
+    );
+    // The longest recharge is enforced.
+    assert_eq!(EndTurn::trigger(&mut server).fire().err(), None);
+    util::start_turn(&mut server, &entity_1_id);
+    assert_eq!(
+        ActivateAbility::trigger(&mut server, entity_1_id, 1)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::AbilityOnCooldown(entity_1_id, 1, 1))
+    );
+    // The suggestion of the second rules is used when the first suggests nothing.
+    assert_eq!(EndTurn::trigger(&mut server).fire().err(), None);
+    assert_eq!(server.run_ai_turn(entity_1_id).ok(), Some(1));
+    // Features of both rules are reported.
+    let description = server.battle().describe();
+    assert_eq!(
+        description
+            .module("fight")
+            .unwrap()
+            .features()
+            .collect::<Vec<_>>(),
+        vec!["first", "second"]
+    );
+}
+
+#[test]
+fn simple_fight() {
+    const ATTACK: &str = "attack";
+    const DEFENSE: &str = "defense";
+    const STATUS_ID: u32 = 1;
+
+    #[derive(Default)]
+    pub struct CustomCharacterRules {}
+
+    impl CharacterRules<CustomRules> for CustomCharacterRules {
+        type CreatureId = u32;
+        type ObjectId = ();
+        type Statistic = SimpleStatistic<String, i32>;
+        // Pair of (attack, defense).
+        type StatisticsSeed = (i32, i32);
+        type StatisticsAlteration = Damage<CustomRules>;
+        type Status = SimpleStatus<u32, i32>;
+        type StatusesAlteration = ();
+
+        fn generate_statistics(
+            &self,
+            seed: &Option<Self::StatisticsSeed>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) -> Box<dyn Iterator<Item = Self::Statistic>> {
+            let (attack, defense) = seed.unwrap_or_default();
+            let v = vec![
+                SimpleStatistic::new(ATTACK.to_string(), attack),
+                SimpleStatistic::new(DEFENSE.to_string(), defense),
+                SimpleStatistic::new(HEALTH_ID.to_string(), HEALTH),
+            ];
+            Box::new(v.into_iter())
+        }
+
+        fn alter_statistics(
+            &self,
+            character: &mut dyn Character<CustomRules>,
+            alteration: &Self::StatisticsAlteration,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) -> Option<Transmutation> {
+            let statistic = character.statistic_mut(alteration.statistic()).unwrap();
+            statistic.set_value(statistic.value() - alteration.amount() as i32);
+            None
+        }
+
+        fn generate_status(
+            &self,
+            _character: &dyn Character<CustomRules>,
+            status_id: &StatusId<CustomRules>,
+            _potency: &Option<Potency<CustomRules>>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) -> Option<Status<CustomRules>> {
+            Some(SimpleStatus::new(*status_id, 2, Some(2)))
+        }
+    }
+
+    battle_rules! {
+        EmptyTeamRules,
+        CustomCharacterRules,
+        EmptyActorRules,
+        SimpleFight<CustomRules>,
+        EmptyUserRules,
+        EmptySpaceRules,
+        EmptyRoundsRules,
+        EmptyEntropyRules
+    }
+
+    let entity_1_id: EntityId<CustomRules> = EntityId::Creature(CREATURE_1_ID);
+    let entity_2_id: EntityId<CustomRules> = EntityId::Creature(CREATURE_2_ID);
+    let health = |server: &Server<CustomRules>| {
+        let creature = server.battle().entities().creature(&CREATURE_2_ID).unwrap();
+        creature.statistic(&HEALTH_ID.to_string()).unwrap().value()
+    };
+    // Create scenario.
+    let mut rules = CustomRules::new();
+    rules.fight_rules = SimpleFight::new(
+        ATTACK.to_string(),
+        DEFENSE.to_string(),
+        HEALTH_ID.to_string(),
+    );
+    let mut server = util::server(rules);
+    util::team(&mut server, TEAM_1_ID);
+    for (id, seed) in &[(CREATURE_1_ID, (5, 0)), (CREATURE_2_ID, (0, 2))] {
+        assert_eq!(
+            CreateCreature::trigger(&mut server, *id, TEAM_1_ID, ())
+                .statistics_seed(*seed)
+                .fire()
+                .err(),
+            None
+        );
+    }
+    // Attack adds to the impact's power and defense reduces it.
+    assert_eq!(
+        ApplyImpact::trigger(
+            &mut server,
+            SimpleImpact::with_source(entity_1_id, entity_2_id, 3)
+        )
+        .fire()
+        .err(),
+        None
+    );
+    assert_eq!(health(&server), HEALTH - 6);
+    // Damage is never negative.
+    assert_eq!(
+        ApplyImpact::trigger(&mut server, SimpleImpact::new(entity_2_id, 1))
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(health(&server), HEALTH - 6);
+    // Statuses deal their effect on each update, until they expire.
+    assert_eq!(
+        InflictStatus::trigger(&mut server, entity_2_id, STATUS_ID)
+            .fire()
+            .err(),
+        None
+    );
+    for _ in 0..3 {
+        util::start_turn(&mut server, &entity_2_id);
+        util::end_turn(&mut server);
+    }
+    assert_eq!(health(&server), HEALTH - 10);
+    let creature = server.battle().entities().creature(&CREATURE_2_ID).unwrap();
+    assert!(creature.status(&STATUS_ID).is_none());
+}
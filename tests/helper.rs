@@ -13,8 +13,8 @@ where
 {
     let events: Vec<FlatVersionedEvent<R>> = battle
         .versioned_events(std::ops::Range {
-            start: 0,
-            end: battle.history().len() as usize,
+            start: battle.history().first_id() as usize,
+            end: (battle.history().first_id() + battle.history().len()) as usize,
         })
         .map(|e| e.into())
         .collect();
@@ -1,8 +1,11 @@
 use std::convert::TryInto;
 use weasel::battle::{BattleController, BattleRules};
+use weasel::creature::CreateCreature;
 use weasel::entropy::ResetEntropy;
-use weasel::event::{EventId, EventKind, EventTrigger};
+use weasel::event::{DummyEvent, EventId, EventKind, EventReceiver, EventTrigger};
+use weasel::history::HistoryBuilder;
 use weasel::round::EndTurn;
+use weasel::team::CreateTeam;
 use weasel::{battle_rules, rules::empty::*};
 
 const TEAM_1_ID: u32 = 1;
@@ -30,3 +33,304 @@ fn timeline_populated() {
     assert_eq!(events[2].kind(), EventKind::ResetEntropy);
     assert_eq!(events[2].id(), len - 1);
 }
+
+#[test]
+fn history_builder() {
+    let version = *CustomRules::new().version();
+    // Build a synthetic timeline.
+    let events = HistoryBuilder::<CustomRules>::new(version)
+        .event(CreateTeam::trigger(&mut (), TEAM_1_ID).event())
+        .derived(CreateCreature::trigger(&mut (), CREATURE_1_ID, TEAM_1_ID, ()).event())
+        .event(ResetEntropy::trigger(&mut ()).event())
+        .derived(DummyEvent::trigger(&mut ()).event())
+        .build();
+    // Check ids and origins.
+    let ids: Vec<_> = events.iter().map(|e| (e.id(), e.origin())).collect();
+    assert_eq!(ids, vec![(0, None), (1, Some(0)), (2, None), (3, Some(2))]);
+    assert!(events.iter().all(|e| !e.synthetic()));
+    // The timeline must be accepted by a server.
+    let mut server = util::server(CustomRules::new());
+    for event in events {
+        assert_eq!(server.receive(event).err(), None);
+    }
+    let history = server.battle().history();
+    assert_eq!(history.len(), 4);
+    assert_eq!(history.events()[1].kind(), EventKind::CreateCreature);
+    assert_eq!(history.events()[3].origin(), Some(2));
+}
+
+#[test]
+fn composite_origins() {
+    let version = *CustomRules::new().version();
+    // Build a timeline with an event caused by two others.
+    let events = HistoryBuilder::<CustomRules>::new(version)
+        .event(DummyEvent::trigger(&mut ()).event())
+        .event(DummyEvent::trigger(&mut ()).event())
+        .caused_by(DummyEvent::trigger(&mut ()).event(), &[1, 0])
+        .caused_by(DummyEvent::trigger(&mut ()).event(), &[2])
+        .event(DummyEvent::trigger(&mut ()).event())
+        .build();
+    assert_eq!(events[2].origin(), Some(1));
+    assert_eq!(events[2].secondary_origins(), &[0]);
+    assert_eq!(events[2].origins().collect::<Vec<_>>(), vec![1, 0]);
+    // Origins must be preserved by the server.
+    let mut server = util::server(CustomRules::new());
+    for event in events {
+        assert_eq!(server.receive(event).err(), None);
+    }
+    // Traverse the cause graph.
+    let history = server.battle().history();
+    let children: Vec<_> = history.children(0).map(|e| e.id()).collect();
+    assert_eq!(children, vec![2]);
+    assert_eq!(history.ancestors(3), vec![0, 1, 2]);
+    assert_eq!(history.ancestors(4), Vec::<EventId>::new());
+    assert_eq!(history.descendants(0), vec![2, 3]);
+    assert_eq!(history.descendants(3), Vec::<EventId>::new());
+    // Follow only primary origins.
+    assert_eq!(history.derived_events(0).count(), 0);
+    let derived: Vec<_> = history.derived_events(1).map(|e| e.id()).collect();
+    assert_eq!(derived, vec![2]);
+    assert_eq!(history.origin_chain(3), vec![2, 1]);
+    assert_eq!(history.origin_chain(0), Vec::<EventId>::new());
+    assert_eq!(history.origin_chain(99), Vec::<EventId>::new());
+    // Origins must survive serialization.
+    #[cfg(feature = "serialization")]
+    {
+        use weasel::serde::FlatVersionedEvent;
+        let event: FlatVersionedEvent<CustomRules> = server
+            .battle()
+            .versioned_events(2..3)
+            .next()
+            .unwrap()
+            .into();
+        let json = serde_json::to_string(&event).unwrap();
+        let event: FlatVersionedEvent<CustomRules> = serde_json::from_str(&json).unwrap();
+        assert_eq!(event.origin(), Some(1));
+        assert_eq!(event.secondary_origins(), &[0]);
+    }
+}
+
+#[test]
+fn history_builder_first_id() {
+    let version = *CustomRules::new().version();
+    // Build a timeline starting from a non zero id.
+    let events = HistoryBuilder::<CustomRules>::new(version)
+        .first_id(5)
+        .event(CreateTeam::trigger(&mut (), TEAM_1_ID).event())
+        .event(DummyEvent::trigger(&mut ()).event())
+        .build();
+    assert_eq!(events[0].id(), 5);
+    assert!(events[0].synthetic());
+    assert_eq!(events[1].id(), 6);
+    assert!(!events[1].synthetic());
+    // The timeline must be accepted by a server.
+    let mut server = util::server(CustomRules::new());
+    for event in events {
+        assert_eq!(server.receive(event).err(), None);
+    }
+    assert_eq!(server.battle().history().first_id(), 5);
+    assert_eq!(server.battle().history().len(), 2);
+}
+
+#[cfg(feature = "serialization")]
+#[test]
+fn write_events() {
+    use std::io::{self, Write};
+    use weasel::serde::{EventFormat, FlatVersionedEvent};
+    use weasel::WeaselError;
+
+    /// Writes events as a json array.
+    struct JsonArray;
+
+    impl<R: BattleRules + 'static> EventFormat<R> for JsonArray {
+        fn begin(&mut self, writer: &mut dyn Write) -> io::Result<()> {
+            write!(writer, "[")
+        }
+
+        fn write_event(
+            &mut self,
+            writer: &mut dyn Write,
+            event: &FlatVersionedEvent<R>,
+            index: usize,
+        ) -> io::Result<()> {
+            if index > 0 {
+                write!(writer, ",")?;
+            }
+            serde_json::to_writer(writer, event).map_err(io::Error::from)
+        }
+
+        fn end(&mut self, writer: &mut dyn Write) -> io::Result<()> {
+            write!(writer, "]")
+        }
+    }
+
+    /// A writer that always fails.
+    struct BrokenWriter;
+
+    impl Write for BrokenWriter {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::BrokenPipe, "broken"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    // Create a server with a few events.
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    util::dummy(&mut server);
+    // Write a portion of the history and track the progress.
+    let mut buffer = Vec::new();
+    let mut progress = Vec::new();
+    assert_eq!(
+        server
+            .battle()
+            .write_events_with_progress(1..3, &mut buffer, &mut JsonArray, |written, total| {
+                progress.push((written, total))
+            })
+            .err(),
+        None
+    );
+    assert_eq!(progress, vec![(1, 2), (2, 2)]);
+    let events: Vec<FlatVersionedEvent<CustomRules>> = serde_json::from_slice(&buffer).unwrap();
+    let ids: Vec<_> = events.iter().map(|e| e.id()).collect();
+    assert_eq!(ids, vec![1, 2]);
+    // Write the whole history with a closure and replay it on another server.
+    let mut buffer = Vec::new();
+    let mut format = |writer: &mut dyn Write, event: &FlatVersionedEvent<CustomRules>| {
+        serde_json::to_writer(&mut *writer, event)?;
+        writeln!(writer)
+    };
+    assert_eq!(
+        server
+            .battle()
+            .write_events(0..3, &mut buffer, &mut format)
+            .err(),
+        None
+    );
+    let mut other_server = util::server(CustomRules::new());
+    for line in String::from_utf8(buffer).unwrap().lines() {
+        let event: FlatVersionedEvent<CustomRules> = serde_json::from_str(line).unwrap();
+        assert_eq!(other_server.receive(event.into()).err(), None);
+    }
+    assert_eq!(other_server.battle().history().len(), 3);
+    // Check errors.
+    assert_eq!(
+        server
+            .battle()
+            .write_events(0..4, &mut Vec::new(), &mut JsonArray)
+            .err(),
+        Some(WeaselError::InvalidEventRange(0..4, 3))
+    );
+    assert_eq!(
+        server
+            .battle()
+            .write_events(0..3, &mut BrokenWriter, &mut format)
+            .err(),
+        Some(WeaselError::EventWriteError(0, "broken".to_string()))
+    );
+}
+
+#[cfg(feature = "serialization")]
+#[test]
+fn tolerant_events() {
+    use weasel::serde::{FlatVersionedEvent, TolerantEvent, FLAT_EVENT_SCHEMA};
+
+    // Create a server with a few events.
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    util::dummy(&mut server);
+    util::dummy(&mut server);
+    let mut events: Vec<serde_json::Value> = server
+        .battle()
+        .versioned_events(0..3)
+        .map(|e| serde_json::to_value(FlatVersionedEvent::from(e)).unwrap())
+        .collect();
+    // Replace the second event with one of an unknown kind.
+    events[1]["event"] = serde_json::json!({"Teleport": {"to": 3}});
+    let json = serde_json::to_string(&events).unwrap();
+    // Strict deserialization fails.
+    assert!(serde_json::from_str::<Vec<FlatVersionedEvent<CustomRules>>>(&json).is_err());
+    // Tolerant deserialization preserves the unknown event.
+    let events: Vec<TolerantEvent<CustomRules>> = serde_json::from_str(&json).unwrap();
+    let ids: Vec<_> = events.iter().map(|e| e.id()).collect();
+    assert_eq!(ids, vec![0, 1, 2]);
+    match &events[1] {
+        TolerantEvent::Unknown(event) => {
+            assert_eq!(event.schema(), FLAT_EVENT_SCHEMA);
+            assert_eq!(
+                serde_json::to_value(event.payload()).unwrap(),
+                serde_json::json!({"Teleport": {"to": 3}})
+            );
+        }
+        TolerantEvent::Known(_) => panic!("event should be unknown"),
+    }
+    assert!(matches!(events[2], TolerantEvent::Known(_)));
+    // Unknown events are replaced by placeholders, keeping ids contiguous.
+    let mut other_server = util::server(CustomRules::new());
+    for event in events {
+        assert_eq!(other_server.receive(event.into()).err(), None);
+    }
+    assert_eq!(other_server.battle().history().len(), 3);
+    assert_eq!(
+        other_server.battle().history().events()[1].kind(),
+        EventKind::DummyEvent
+    );
+    // Events without a valid header can't be deserialized.
+    assert!(serde_json::from_str::<TolerantEvent<CustomRules>>(r#"{"event":{}}"#).is_err());
+}
+
+#[cfg(feature = "serialization")]
+#[test]
+fn export() {
+    use weasel::history::ExportFormat;
+    // Create a server with a creature.
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    // Export as JSON Lines.
+    let mut buffer = Vec::new();
+    assert_eq!(
+        server
+            .battle()
+            .export_history(&mut buffer, ExportFormat::JsonLines)
+            .err(),
+        None
+    );
+    let json = String::from_utf8(buffer).unwrap();
+    let lines: Vec<serde_json::Value> = json
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["id"], 0);
+    assert_eq!(lines[0]["kind"], "CreateTeam");
+    assert_eq!(lines[0]["origin"], serde_json::Value::Null);
+    assert_eq!(lines[1]["id"], 1);
+    assert_eq!(lines[1]["kind"], "CreateCreature");
+    assert!(lines[1]["summary"]
+        .as_str()
+        .unwrap()
+        .contains("CreateCreature"));
+    // Export as CSV, with custom summaries.
+    util::dummy(&mut server);
+    let mut buffer = Vec::new();
+    let result =
+        server
+            .battle()
+            .history()
+            .export_with_summary(&mut buffer, ExportFormat::Csv, |event| {
+                format!("event \"{}\", ok", event.id())
+            });
+    assert_eq!(result.err(), None);
+    assert_eq!(
+        String::from_utf8(buffer).unwrap(),
+        "id,kind,origin,summary\n\
+         0,CreateTeam,,\"event \"\"0\"\", ok\"\n\
+         1,CreateCreature,,\"event \"\"1\"\", ok\"\n\
+         2,DummyEvent,,\"event \"\"2\"\", ok\"\n"
+    );
+}
@@ -1,19 +1,23 @@
+use std::any::Any;
 use std::cell::RefCell;
 use std::collections::HashSet;
 use weasel::ability::ActivateAbility;
-use weasel::battle::{BattleController, BattleRules, BattleState};
+use weasel::battle::{Battle, BattleController, BattleRules, BattleState};
 use weasel::character::{
     AlterStatistics, Character, CharacterRules, RegenerateStatistics, StatisticId,
 };
 use weasel::entity::{EntityId, RemoveEntity, Transmutation};
 use weasel::entropy::Entropy;
-use weasel::event::{EventQueue, EventTrigger};
+use weasel::event::{Event, EventKind, EventQueue, EventRights, EventServer, EventTrigger};
 use weasel::metric::{system::*, WriteMetrics};
-use weasel::object::{CreateObject, RemoveObject};
+use weasel::object::{CreateObject, ObjectOwner, RemoveObject, TransferObjectOwnership};
+use weasel::player::PlayerId;
 use weasel::round::StartTurn;
 use weasel::rules::empty::EmptyStat;
 use weasel::rules::statistic::SimpleStatistic;
+use weasel::server::Server;
 use weasel::space::{PositionClaim, SpaceRules};
+use weasel::team::{RemoveTeam, RenameTeam};
 use weasel::{
     battle_rules, battle_rules_with_character, battle_rules_with_space, rules::empty::*,
     WeaselError, WeaselResult,
@@ -221,6 +225,7 @@ fn remove_object() {
     impl SpaceRules<CustomRules> for CustomSpaceRules {
         type Position = u32;
         type SpaceSeed = ();
+        type Area = ();
         type SpaceModel = HashSet<Self::Position>;
         type SpaceAlteration = ();
 
@@ -401,3 +406,149 @@ fn remove_entity() {
     let entities = server.battle().entities();
     assert!(entities.object(&OBJECT_1_ID).is_none());
 }
+
+#[test]
+fn object_ownership() {
+    battle_rules! {}
+    const TEAM_1_ID: u32 = 1;
+    const TEAM_2_ID: u32 = 2;
+    const PLAYER_1_ID: PlayerId = 1;
+    const PLAYER_2_ID: PlayerId = 2;
+    const ENTITY_1_ID: EntityId<CustomRules> = EntityId::Object(OBJECT_1_ID);
+
+    /// An event that can be fired by whoever controls the object.
+    #[derive(Debug, Clone)]
+    struct OpenChest {
+        id: EntityId<CustomRules>,
+    }
+
+    impl Event<CustomRules> for OpenChest {
+        fn verify(&self, _: &Battle<CustomRules>) -> WeaselResult<(), CustomRules> {
+            Ok(())
+        }
+
+        fn apply(&self, _: &mut Battle<CustomRules>, _: &mut Option<EventQueue<CustomRules>>) {}
+
+        fn kind(&self) -> EventKind {
+            EventKind::UserEvent(0)
+        }
+
+        fn box_clone(&self) -> Box<dyn Event<CustomRules> + Send> {
+            Box::new(self.clone())
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn rights<'a>(&'a self, _: &'a Battle<CustomRules>) -> EventRights<'a, CustomRules> {
+            EventRights::Entity(&self.id)
+        }
+    }
+
+    /// Trigger to fire an `OpenChest` event.
+    struct OpenChestTrigger<'a> {
+        server: &'a mut Server<CustomRules>,
+    }
+
+    impl<'a> EventTrigger<'a, CustomRules, Server<CustomRules>> for OpenChestTrigger<'a> {
+        fn processor(&'a mut self) -> &'a mut Server<CustomRules> {
+            self.server
+        }
+
+        fn event(&self) -> Box<dyn Event<CustomRules> + Send> {
+            Box::new(OpenChest { id: ENTITY_1_ID })
+        }
+    }
+
+    let open_chest = |server: &mut Server<CustomRules>, player| {
+        let prototype = OpenChestTrigger { server }
+            .prototype()
+            .client_prototype(0, Some(player));
+        server.process_client(prototype).err().map(|e| e.unfold())
+    };
+    // Create a server with one object. Require authentication.
+    let mut server = Server::builder(Battle::builder(CustomRules::new()).build())
+        .enforce_authentication()
+        .build();
+    util::team(&mut server, TEAM_1_ID);
+    util::object(&mut server, OBJECT_1_ID, ());
+    assert_eq!(server.rights_mut().add(PLAYER_1_ID, &TEAM_1_ID).err(), None);
+    let object_owner = |server: &Server<CustomRules>| {
+        server
+            .battle()
+            .entities()
+            .object(&OBJECT_1_ID)
+            .unwrap()
+            .owner()
+            .cloned()
+    };
+    // Objects without an owner are controlled only by the server.
+    assert_eq!(
+        open_chest(&mut server, PLAYER_1_ID),
+        Some(WeaselError::OwnershipError(Some(PLAYER_1_ID), ENTITY_1_ID))
+    );
+    // Check that the object and the team must exist.
+    assert_eq!(
+        TransferObjectOwnership::trigger(&mut server, OBJECT_ERR_ID)
+            .player(PLAYER_1_ID)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::ObjectNotFound(OBJECT_ERR_ID))
+    );
+    assert_eq!(
+        TransferObjectOwnership::trigger(&mut server, OBJECT_1_ID)
+            .team(TEAM_2_ID)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::TeamNotFound(TEAM_2_ID))
+    );
+    // Give the object to a player.
+    assert_eq!(
+        TransferObjectOwnership::trigger(&mut server, OBJECT_1_ID)
+            .player(PLAYER_2_ID)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(
+        object_owner(&server),
+        Some(ObjectOwner::Player(PLAYER_2_ID))
+    );
+    assert_eq!(
+        open_chest(&mut server, PLAYER_1_ID),
+        Some(WeaselError::OwnershipError(Some(PLAYER_1_ID), ENTITY_1_ID))
+    );
+    assert_eq!(open_chest(&mut server, PLAYER_2_ID), None);
+    // Give the object to a team.
+    assert_eq!(
+        TransferObjectOwnership::trigger(&mut server, OBJECT_1_ID)
+            .team(TEAM_1_ID)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(open_chest(&mut server, PLAYER_1_ID), None);
+    assert_eq!(
+        open_chest(&mut server, PLAYER_2_ID),
+        Some(WeaselError::AuthenticationError(
+            Some(PLAYER_2_ID),
+            TEAM_1_ID
+        ))
+    );
+    // Renaming the team updates the owner, removing it clears the owner.
+    assert_eq!(
+        RenameTeam::trigger(&mut server, TEAM_1_ID, TEAM_2_ID)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(object_owner(&server), Some(ObjectOwner::Team(TEAM_2_ID)));
+    assert_eq!(
+        RemoveTeam::trigger(&mut server, TEAM_2_ID).fire().err(),
+        None
+    );
+    assert_eq!(object_owner(&server), None);
+}
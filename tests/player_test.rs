@@ -0,0 +1,120 @@
+use weasel::battle::{BattleController, BattleRules, BattleState};
+use weasel::event::{DummyEvent, EventKind, EventReceiver, EventTrigger};
+use weasel::player::{Player, PlayerId, PlayerRules, RegisterPlayer, RemovePlayer};
+use weasel::{battle_rules, battle_rules_with_player, rules::empty::*};
+use weasel::{WeaselError, WeaselResult};
+
+const PLAYER_1_ID: PlayerId = 1;
+const PLAYER_2_ID: PlayerId = 2;
+const PLAYER_ERR_ID: PlayerId = 99;
+
+#[test]
+fn register_and_remove() {
+    battle_rules! {}
+    let mut server = util::server(CustomRules::new());
+    // Register two players.
+    assert_eq!(
+        RegisterPlayer::trigger(&mut server, PLAYER_1_ID, "Alice".to_string())
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(
+        RegisterPlayer::trigger(&mut server, PLAYER_2_ID, "Bob".to_string())
+            .fire()
+            .err(),
+        None
+    );
+    let players = server.battle().players();
+    assert_eq!(players.len(), 2);
+    assert_eq!(players.player(PLAYER_2_ID).unwrap().name(), "Bob");
+    assert_eq!(
+        players.players().map(|p| p.id()).collect::<Vec<_>>(),
+        vec![PLAYER_1_ID, PLAYER_2_ID]
+    );
+    // Player ids must be unique.
+    assert_eq!(
+        RegisterPlayer::trigger(&mut server, PLAYER_1_ID, "Carl".to_string())
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::DuplicatedPlayer(PLAYER_1_ID))
+    );
+    // Remove a player.
+    assert_eq!(
+        RemovePlayer::trigger(&mut server, PLAYER_ERR_ID)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::PlayerNotFound(PLAYER_ERR_ID))
+    );
+    assert_eq!(
+        RemovePlayer::trigger(&mut server, PLAYER_1_ID).fire().err(),
+        None
+    );
+    let players = server.battle().players();
+    assert_eq!(players.len(), 1);
+    assert!(players.player(PLAYER_1_ID).is_none());
+}
+
+#[test]
+fn player_rules() {
+    #[derive(Default)]
+    struct CustomPlayerRules {}
+
+    impl<R: BattleRules> PlayerRules<R> for CustomPlayerRules {
+        type Rating = u32;
+        type PlayerData = Vec<String>;
+
+        fn allow_new_player(
+            &self,
+            _state: &BattleState<R>,
+            player: &Player<R>,
+        ) -> WeaselResult<(), R> {
+            if player.name().is_empty() {
+                Err(WeaselError::UserError("empty name".to_string()))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    battle_rules_with_player! { CustomPlayerRules }
+
+    let mut server = util::server(CustomRules::new());
+    // Rules can reject new players.
+    assert_eq!(
+        RegisterPlayer::trigger(&mut server, PLAYER_1_ID, String::new())
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::UserError("empty name".to_string()))
+    );
+    // Register a player with rating and custom data.
+    assert_eq!(
+        RegisterPlayer::trigger(&mut server, PLAYER_1_ID, "Alice".to_string())
+            .rating(1500)
+            .data(vec!["mage".to_string()])
+            .fire()
+            .err(),
+        None
+    );
+    let player = server.battle().players().player(PLAYER_1_ID).unwrap();
+    assert_eq!(*player.rating(), 1500);
+    assert_eq!(*player.data(), vec!["mage".to_string()]);
+    // Players are reproduced in the events to bootstrap a new client.
+    for _ in 0..5 {
+        assert_eq!(DummyEvent::trigger(&mut server).fire().err(), None);
+    }
+    let events = server.bootstrap_events_for_new_client();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].kind(), EventKind::RegisterPlayer);
+    let mut other = util::server(CustomRules::new());
+    for event in events {
+        assert_eq!(other.receive(event).err(), None);
+    }
+    let player = other.battle().players().player(PLAYER_1_ID).unwrap();
+    assert_eq!(player.name(), "Alice");
+    assert_eq!(*player.rating(), 1500);
+    assert_eq!(*player.data(), vec!["mage".to_string()]);
+}
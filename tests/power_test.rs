@@ -27,6 +27,7 @@ fn powers_generated() {
         type PowersAlteration = ();
         type ObjectivesSeed = ();
         type Objectives = ();
+        type TeamData = ();
 
         fn generate_powers(
             &self,
@@ -69,6 +70,7 @@ fn alter_powers() {
         type PowersAlteration = (u32, u32);
         type ObjectivesSeed = ();
         type Objectives = ();
+        type TeamData = ();
 
         fn generate_powers(
             &self,
@@ -154,6 +156,7 @@ fn regenerate_powers() {
         type PowersAlteration = ();
         type ObjectivesSeed = ();
         type Objectives = ();
+        type TeamData = ();
 
         fn generate_powers(
             &self,
@@ -245,6 +248,7 @@ impl TeamRules<CustomRules> for CustomTeamRules {
     type PowersAlteration = ();
     type ObjectivesSeed = ();
     type Objectives = ();
+    type TeamData = ();
 
     fn generate_powers(
         &self,
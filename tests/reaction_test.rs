@@ -0,0 +1,87 @@
+use weasel::battle::{BattleController, BattleRules, BattleState};
+use weasel::entropy::Entropy;
+use weasel::event::{EventKind, EventQueue, EventTrigger, EventWrapper};
+use weasel::fight::{ApplyImpact, FightRules};
+use weasel::metric::WriteMetrics;
+use weasel::reaction::ReactionRules;
+use weasel::{battle_rules, rules::empty::*};
+
+const TEAM_1_ID: u32 = 1;
+
+#[derive(Default)]
+pub struct CustomFightRules {}
+
+impl<R: BattleRules> FightRules<R> for CustomFightRules {
+    // Tells whether the impact is a counterattack.
+    type Impact = bool;
+    type Potency = ();
+}
+
+#[derive(Default)]
+pub struct CustomReactionRules {}
+
+impl ReactionRules<CustomRules> for CustomReactionRules {
+    fn features(&self) -> Vec<&'static str> {
+        vec!["counterattack"]
+    }
+
+    fn react(
+        &self,
+        _state: &BattleState<CustomRules>,
+        event: &EventWrapper<CustomRules>,
+        event_queue: &mut Option<EventQueue<CustomRules>>,
+        _entropy: &mut Entropy<CustomRules>,
+        _metrics: &mut WriteMetrics<CustomRules>,
+    ) {
+        // Counterattack every attack, except counterattacks themselves.
+        if let Some(impact) = event.as_any().downcast_ref::<ApplyImpact<CustomRules>>() {
+            if !impact.impact() {
+                ApplyImpact::trigger(event_queue, true).fire();
+            }
+        }
+    }
+}
+
+battle_rules! {
+    EmptyTeamRules,
+    EmptyCharacterRules,
+    EmptyActorRules,
+    CustomFightRules,
+    EmptyUserRules,
+    EmptySpaceRules,
+    EmptyRoundsRules,
+    EmptyEntropyRules,
+    CustomReactionRules
+}
+
+#[test]
+fn reactions() {
+    let mut server = util::server(CustomRules::new());
+    // Events that don't match any reaction are left alone.
+    util::team(&mut server, TEAM_1_ID);
+    assert_eq!(server.battle().history().len(), 1);
+    // Attacks are countered.
+    assert_eq!(ApplyImpact::trigger(&mut server, false).fire().err(), None);
+    let events = server.battle().history().events();
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[1].kind(), EventKind::ApplyImpact);
+    assert_eq!(events[2].kind(), EventKind::ApplyImpact);
+    assert_eq!(events[2].origin(), Some(1));
+    assert_eq!(
+        events[2]
+            .as_any()
+            .downcast_ref::<ApplyImpact<CustomRules>>()
+            .map(|event| *event.impact()),
+        Some(true)
+    );
+    // Reaction rules are described.
+    let description = server.battle().describe();
+    assert_eq!(
+        description
+            .module("reaction")
+            .unwrap()
+            .features()
+            .collect::<Vec<_>>(),
+        vec!["counterattack"]
+    );
+}
@@ -0,0 +1,124 @@
+use weasel::battle::{BattleController, BattleRules};
+use weasel::entity::EntityId;
+use weasel::error::WeaselError;
+use weasel::event::{EventKind, EventTrigger};
+use weasel::replay::Replay;
+use weasel::round::{EndTurn, StartTurn};
+use weasel::{battle_rules, rules::empty::*};
+
+const TEAM_1_ID: u32 = 1;
+const CREATURE_1_ID: u32 = 1;
+const ENTITY_1_ID: EntityId<CustomRules> = EntityId::Creature(CREATURE_1_ID);
+
+battle_rules! {}
+
+/// Creates a replay of a short battle with four events.
+fn replay() -> Replay<CustomRules> {
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    assert_eq!(
+        StartTurn::trigger(&mut server, ENTITY_1_ID).fire().err(),
+        None
+    );
+    assert_eq!(EndTurn::trigger(&mut server).fire().err(), None);
+    let events = server.battle().versioned_events(0..4).collect();
+    Replay::new(Box::new(CustomRules::new), events)
+}
+
+#[test]
+fn step() {
+    let mut replay = replay();
+    assert_eq!(replay.len(), 4);
+    assert_eq!(replay.position(), 0);
+    assert!(replay.battle().entities().teams().next().is_none());
+    // Apply events one by one.
+    assert_eq!(replay.step().ok(), Some(true));
+    assert_eq!(replay.position(), 1);
+    assert!(replay.battle().entities().team(&TEAM_1_ID).is_some());
+    assert!(replay
+        .battle()
+        .entities()
+        .creature(&CREATURE_1_ID)
+        .is_none());
+    assert_eq!(replay.step().ok(), Some(true));
+    assert!(replay
+        .battle()
+        .entities()
+        .creature(&CREATURE_1_ID)
+        .is_some());
+    assert_eq!(replay.step().ok(), Some(true));
+    assert_eq!(replay.step().ok(), Some(true));
+    assert!(replay.is_finished());
+    // Stepping past the end has no effect.
+    assert_eq!(replay.step().ok(), Some(false));
+    assert_eq!(replay.position(), 4);
+    assert_eq!(
+        replay.battle().history().events()[3].kind(),
+        EventKind::EndTurn
+    );
+}
+
+#[test]
+fn jump_and_rewind() {
+    let mut replay = replay();
+    // Jump forward.
+    assert_eq!(replay.jump_to(2).err(), None);
+    assert_eq!(replay.position(), 3);
+    assert!(replay.battle().rounds().is_acting(&ENTITY_1_ID));
+    // Jump backward.
+    assert_eq!(replay.jump_to(0).err(), None);
+    assert_eq!(replay.position(), 1);
+    assert!(replay
+        .battle()
+        .entities()
+        .creature(&CREATURE_1_ID)
+        .is_none());
+    // Jump to a missing event.
+    assert_eq!(
+        replay.jump_to(4).err(),
+        Some(WeaselError::InvalidEventRange(4..5, 4))
+    );
+    assert_eq!(replay.position(), 1);
+    // Rewind.
+    replay.rewind();
+    assert_eq!(replay.position(), 0);
+    assert!(replay.battle().entities().teams().next().is_none());
+}
+
+#[test]
+fn checkpoint() {
+    let mut replay = replay();
+    assert_eq!(replay.jump_to(3).err(), None);
+    // Materialize an independent battle.
+    let battle = replay.checkpoint(1).unwrap();
+    assert_eq!(battle.history().len(), 2);
+    assert!(battle.entities().creature(&CREATURE_1_ID).is_some());
+    assert!(!battle.rounds().is_acting(&ENTITY_1_ID));
+    // The replay keeps its position.
+    assert_eq!(replay.position(), 4);
+    assert!(replay.checkpoint(10).is_err());
+}
+
+#[cfg(feature = "serialization")]
+#[test]
+fn from_flat_events() {
+    use weasel::serde::FlatVersionedEvent;
+
+    let events: Vec<FlatVersionedEvent<_>> = replay()
+        .events()
+        .iter()
+        .cloned()
+        .map(|e| e.into())
+        .collect();
+    let json = serde_json::to_string(&events).unwrap();
+    let events = serde_json::from_str(&json).unwrap();
+    let mut replay = Replay::from_flat_events(Box::new(CustomRules::new), events);
+    assert_eq!(replay.jump_to(3).err(), None);
+    assert!(replay.is_finished());
+    assert!(replay
+        .battle()
+        .entities()
+        .creature(&CREATURE_1_ID)
+        .is_some());
+}
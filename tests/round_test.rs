@@ -1,23 +1,36 @@
 use indexmap::indexset;
 #[cfg(feature = "serialization")]
 use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
 use weasel::actor::Actor;
-use weasel::battle::{Battle, BattleController, BattleRules};
+use weasel::battle::{Battle, BattleController, BattlePhase, BattleRules, EndReason};
+use weasel::character::CharacterRules;
+use weasel::creature::{CreateCreature, RemoveCreature};
 use weasel::entity::{Entities, EntityId};
 use weasel::entropy::Entropy;
-use weasel::event::{EventProcessor, EventRights, EventServer, EventTrigger};
-use weasel::metric::WriteMetrics;
+use weasel::event::{
+    ClientSink, DummyEvent, EventKind, EventProcessor, EventQueue, EventRights, EventServer,
+    EventSink, EventSinkId, EventTrigger, VersionedEventWrapper,
+};
+use weasel::metric::{Metric, MetricChanged, WriteMetrics};
 use weasel::player::PlayerId;
 use weasel::round::{
-    EndRound, EndTurn, EnvironmentTurn, ResetRounds, RoundsRules, StartTurn, TurnState,
+    AddTurnActor, Delay, EndRound, EndTurn, EnvironmentTurn, RemoveTurnActor, ResetRounds,
+    RoundsRules, ScheduleEvent, StartTurn, Tick, TicksCount, TurnState, TurnTimedOut,
 };
+use weasel::rules::rounds::{AtbRounds, DEFAULT_CHARGE_THRESHOLD};
+use weasel::rules::statistic::SimpleStatistic;
 use weasel::server::Server;
 use weasel::space::Space;
-use weasel::WeaselError;
+use weasel::team::{AddScore, ConcludeObjectives, Conclusion, CreateTeam};
 use weasel::{battle_rules, battle_rules_with_rounds, rules::empty::*};
+use weasel::{WeaselError, WeaselResult};
 
 const TEAM_1_ID: u32 = 1;
 const TEAM_2_ID: u32 = 2;
+const TEAM_3_ID: u32 = 3;
 const CREATURE_1_ID: u32 = 1;
 const ENTITY_1_ID: EntityId<CustomRules> = EntityId::Creature(CREATURE_1_ID);
 const CREATURE_2_ID: u32 = 2;
@@ -27,6 +40,7 @@ const ENTITY_3_ID: EntityId<CustomRules> = EntityId::Creature(CREATURE_3_ID);
 const CREATURE_ERR_ID: u32 = 99;
 const ENTITY_ERR_ID: EntityId<CustomRules> = EntityId::Creature(CREATURE_ERR_ID);
 const PLAYER_1_ID: PlayerId = 1;
+const TURN_STARTS_METRIC: u16 = 0;
 
 #[derive(Clone, Default, Debug)]
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
@@ -34,6 +48,7 @@ struct Model {
     starts: u32,
     ends: u32,
     adds: usize,
+    ticks: u32,
     last: Option<EntityId<CustomRules>>,
 }
 
@@ -72,8 +87,11 @@ impl RoundsRules<CustomRules> for CustomRoundsRules {
         model: &mut Self::RoundsModel,
         actor: &dyn Actor<CustomRules>,
         _entropy: &mut Entropy<CustomRules>,
-        _metrics: &mut WriteMetrics<CustomRules>,
+        metrics: &mut WriteMetrics<CustomRules>,
     ) {
+        metrics
+            .add_user_u64_for_turn(TURN_STARTS_METRIC, 1)
+            .unwrap();
         model.starts += 1;
         model.last = Some(*actor.entity_id());
     }
@@ -99,6 +117,22 @@ impl RoundsRules<CustomRules> for CustomRoundsRules {
     ) {
         model.adds += 1;
     }
+
+    fn on_tick(
+        &self,
+        _entities: &Entities<CustomRules>,
+        model: &mut Self::RoundsModel,
+        tick: TicksCount,
+        event_queue: &mut Option<EventQueue<CustomRules>>,
+        _entropy: &mut Entropy<CustomRules>,
+        _metrics: &mut WriteMetrics<CustomRules>,
+    ) {
+        model.ticks += 1;
+        // Entity 3 acts at the second tick.
+        if tick == 2 {
+            StartTurn::trigger(event_queue, ENTITY_3_ID).fire();
+        }
+    }
 }
 
 battle_rules_with_rounds! { CustomRoundsRules }
@@ -204,6 +238,93 @@ fn end_turn() {
     util::start_turn(&mut server, &ENTITY_2_ID);
 }
 
+#[test]
+fn add_remove_turn_actor() {
+    // Initialize the battle.
+    let mut server = server!();
+    // Actors can't be added or removed outside of a turn.
+    assert_eq!(
+        AddTurnActor::trigger(&mut server, ENTITY_3_ID)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::NoTurnInProgress)
+    );
+    assert_eq!(
+        RemoveTurnActor::trigger(&mut server, ENTITY_1_ID)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::NoTurnInProgress)
+    );
+    // Start a turn.
+    util::start_turn(&mut server, &ENTITY_1_ID);
+    // Check add is prevented for faulty conditions.
+    assert_eq!(
+        AddTurnActor::trigger(&mut server, ENTITY_ERR_ID)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::EntityNotFound(ENTITY_ERR_ID))
+    );
+    assert_eq!(
+        AddTurnActor::trigger(&mut server, ENTITY_1_ID)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::ActorAlreadyInTurn(ENTITY_1_ID))
+    );
+    // Add an actor to the turn.
+    assert_eq!(
+        AddTurnActor::trigger(&mut server, ENTITY_3_ID).fire().err(),
+        None
+    );
+    assert_eq!(
+        *server.battle().rounds().state(),
+        TurnState::<_>::Started(indexset! {ENTITY_1_ID, ENTITY_3_ID})
+    );
+    assert_eq!(server.battle().rounds().model().starts, 2);
+    // Eligibility is still enforced.
+    assert_eq!(
+        AddTurnActor::trigger(&mut server, ENTITY_2_ID)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::ActorNotEligible(ENTITY_2_ID))
+    );
+    // Check remove is prevented for actors not in the turn.
+    assert_eq!(
+        RemoveTurnActor::trigger(&mut server, ENTITY_2_ID)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::ActorNotReady(ENTITY_2_ID))
+    );
+    // Remove actors one by one.
+    assert_eq!(
+        RemoveTurnActor::trigger(&mut server, ENTITY_1_ID)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(
+        *server.battle().rounds().state(),
+        TurnState::<_>::Started(indexset! {ENTITY_3_ID})
+    );
+    assert_eq!(server.battle().rounds().model().ends, 1);
+    assert_eq!(server.battle().rounds().completed_turns(), 0);
+    // Removing the last actor ends the turn.
+    assert_eq!(
+        RemoveTurnActor::trigger(&mut server, ENTITY_3_ID)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(*server.battle().rounds().state(), TurnState::<_>::Ready);
+    assert_eq!(server.battle().rounds().model().ends, 2);
+    assert_eq!(server.battle().rounds().completed_turns(), 1);
+}
+
 #[test]
 fn reset_rounds() {
     // Initialize the battle.
@@ -320,7 +441,7 @@ fn player_rights() {
     let event = prototype.clone().client_prototype(0, Some(PLAYER_1_ID));
     assert_eq!(
         event.rights(server.battle()),
-        EventRights::Teams(vec![&TEAM_1_ID, &TEAM_2_ID])
+        EventRights::Entities(vec![&ENTITY_1_ID, &ENTITY_3_ID])
     );
     // StartTurn should be blocked.
     assert_eq!(
@@ -339,7 +460,7 @@ fn player_rights() {
         .client_prototype(0, Some(PLAYER_1_ID));
     assert_eq!(
         event.rights(server.battle()),
-        EventRights::Teams(vec![&TEAM_1_ID, &TEAM_2_ID])
+        EventRights::Entities(vec![&ENTITY_1_ID, &ENTITY_3_ID])
     );
     // EndTurn should be blocked.
     assert_eq!(
@@ -358,6 +479,46 @@ fn player_rights() {
     assert_eq!(server.process_client(event).err(), None);
 }
 
+#[test]
+fn add_turn_actor_rights() {
+    // Create a server with two creatures in different teams. Require authentication.
+    let mut server = Server::builder(Battle::builder(CustomRules::new()).build())
+        .enforce_authentication()
+        .build();
+    util::team(&mut server, TEAM_1_ID);
+    util::team(&mut server, TEAM_2_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    util::creature(&mut server, CREATURE_3_ID, TEAM_2_ID, ());
+    // Give to the player rights to only the team of the actor to add.
+    assert_eq!(server.rights_mut().add(PLAYER_1_ID, &TEAM_2_ID).err(), None);
+    util::start_turn(&mut server, &ENTITY_1_ID);
+    let event = AddTurnActor::trigger(&mut server, ENTITY_3_ID)
+        .prototype()
+        .client_prototype(0, Some(PLAYER_1_ID));
+    assert_eq!(
+        event.rights(server.battle()),
+        EventRights::All(vec![
+            EventRights::Entity(&ENTITY_3_ID),
+            EventRights::Any(vec![EventRights::Entity(&ENTITY_1_ID)])
+        ])
+    );
+    // AddTurnActor should be blocked, because the player controls none of the current actors.
+    assert_eq!(
+        server
+            .process_client(event.clone())
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::AuthenticationError(
+            Some(PLAYER_1_ID),
+            TEAM_1_ID
+        ))
+    );
+    // Give rights to the player.
+    assert_eq!(server.rights_mut().add(PLAYER_1_ID, &TEAM_1_ID).err(), None);
+    assert_eq!(server.process_client(event).err(), None);
+    assert!(server.battle().rounds().is_acting(&ENTITY_3_ID));
+}
+
 #[test]
 fn end_round() {
     // Initialize the battle.
@@ -377,3 +538,409 @@ fn end_round() {
     assert_eq!(EndRound::trigger(&mut server).fire().err(), None);
     assert_eq!(server.battle().rounds().completed_rounds(), 1);
 }
+
+#[test]
+fn metrics_series() {
+    let mut server = server!();
+    // Play two turns in the first round and one in the second.
+    util::start_turn(&mut server, &ENTITY_1_ID);
+    util::end_turn(&mut server);
+    util::start_turn(&mut server, &ENTITY_2_ID);
+    util::end_turn(&mut server);
+    assert_eq!(EndRound::trigger(&mut server).fire().err(), None);
+    util::start_turn(&mut server, &ENTITY_1_ID);
+    // Check the metrics aggregated by turn and by round.
+    let metrics = server.battle().metrics();
+    assert_eq!(metrics.user_u64(TURN_STARTS_METRIC), Some(3));
+    let turns: Vec<_> = metrics.user_u64_series(TURN_STARTS_METRIC).collect();
+    assert_eq!(turns, vec![(0, 1), (1, 1), (2, 1)]);
+    let rounds: Vec<_> = metrics.user_u64_round_series(TURN_STARTS_METRIC).collect();
+    assert_eq!(rounds, vec![(1, 2)]);
+}
+
+#[test]
+fn metrics_forwarding() {
+    struct Sink(Arc<Mutex<Vec<MetricChanged<CustomRules>>>>);
+
+    impl EventSink for Sink {
+        fn id(&self) -> EventSinkId {
+            1
+        }
+    }
+
+    impl ClientSink<CustomRules> for Sink {
+        fn send(
+            &mut self,
+            _: &VersionedEventWrapper<CustomRules>,
+        ) -> WeaselResult<(), CustomRules> {
+            Ok(())
+        }
+
+        fn send_metric(
+            &mut self,
+            change: &MetricChanged<CustomRules>,
+        ) -> WeaselResult<(), CustomRules> {
+            self.0.lock().unwrap().push(change.clone());
+            Ok(())
+        }
+    }
+
+    let mut server = server!();
+    let changes = Arc::new(Mutex::new(Vec::new()));
+    assert_eq!(
+        server
+            .client_sinks_mut()
+            .add_sink(Box::new(Sink(changes.clone())))
+            .err(),
+        None
+    );
+    // Metrics are not forwarded by default.
+    assert!(!server.forwards_metrics());
+    util::start_turn(&mut server, &ENTITY_1_ID);
+    util::end_turn(&mut server);
+    assert!(changes.lock().unwrap().is_empty());
+    // Enable forwarding.
+    server.set_forward_metrics(true);
+    assert!(server.forwards_metrics());
+    util::start_turn(&mut server, &ENTITY_2_ID);
+    {
+        let changes = changes.lock().unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].event_id(), server.battle().history().len() - 1);
+        assert_eq!(*changes[0].id(), TURN_STARTS_METRIC);
+        assert_eq!(changes[0].value(), Some(Metric::CounterU64(2)));
+    }
+    util::end_turn(&mut server);
+    assert_eq!(changes.lock().unwrap().len(), 1);
+    // Disable forwarding.
+    server.set_forward_metrics(false);
+    util::start_turn(&mut server, &ENTITY_1_ID);
+    assert_eq!(changes.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn tick() {
+    // Initialize the battle.
+    let mut server = server!();
+    // Fire a tick.
+    assert_eq!(Tick::trigger(&mut server, 1).fire().err(), None);
+    assert_eq!(server.battle().rounds().ticks(), 1);
+    assert_eq!(server.battle().rounds().model().ticks, 1);
+    assert_eq!(*server.battle().rounds().state(), TurnState::Ready);
+    // Check that ticks must increase.
+    assert_eq!(
+        Tick::trigger(&mut server, 1)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::InvalidTick(1, 1))
+    );
+    // Rules can start a turn during a tick.
+    assert_eq!(server.fire_tick().err(), None);
+    assert_eq!(server.battle().rounds().ticks(), 2);
+    assert_eq!(server.battle().rounds().model().ticks, 2);
+    assert!(server.battle().rounds().is_acting(&ENTITY_3_ID));
+    // Without a tick interval no ticks are fired automatically.
+    assert_eq!(server.update_ticks().err(), None);
+    assert_eq!(server.battle().rounds().ticks(), 2);
+}
+
+#[test]
+fn tick_interval() {
+    battle_rules! {}
+    let battle = Battle::builder(CustomRules::new()).build();
+    let mut server = Server::builder(battle)
+        .tick_interval(Duration::from_millis(1))
+        .build();
+    assert_eq!(server.tick_interval(), Some(Duration::from_millis(1)));
+    thread::sleep(Duration::from_millis(5));
+    let ticks = server.update_ticks().unwrap();
+    assert!(ticks >= 5);
+    assert_eq!(server.battle().rounds().ticks(), ticks);
+    assert_eq!(server.battle().history().len() as u64, ticks);
+}
+
+#[test]
+fn turn_timeout() {
+    battle_rules! {}
+    let entity_1_id = EntityId::Creature(CREATURE_1_ID);
+    let entity_2_id = EntityId::Creature(CREATURE_2_ID);
+    let battle = Battle::builder(CustomRules::new()).build();
+    let mut server = Server::builder(battle)
+        .turn_timeout(Duration::from_millis(50))
+        .build();
+    assert_eq!(server.turn_timeout(), Some(Duration::from_millis(50)));
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    // Nothing times out without a turn in progress.
+    thread::sleep(Duration::from_millis(60));
+    assert_eq!(server.check_timeouts().err(), None);
+    assert!(!server.check_timeouts().unwrap());
+    // The timeout is measured from the start of the turn.
+    util::start_turn(&mut server, &entity_1_id);
+    assert!(!server.check_timeouts().unwrap());
+    thread::sleep(Duration::from_millis(60));
+    assert!(server.check_timeouts().unwrap());
+    assert_eq!(*server.battle().rounds().state(), TurnState::Ready);
+    assert_eq!(server.battle().rounds().completed_turns(), 1);
+    // The timeout is recorded in the history.
+    let event = server.battle().history().events().last().unwrap();
+    assert_eq!(event.kind(), EventKind::TurnTimedOut);
+    let timed_out: &TurnTimedOut<_> = event.downcast().unwrap();
+    assert_eq!(*timed_out.ids(), vec![entity_1_id]);
+    // Timed out actors must be acting.
+    util::start_turn(&mut server, &entity_1_id);
+    assert_eq!(
+        TurnTimedOut::trigger(&mut server, vec![entity_2_id])
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::ActorNotReady(entity_2_id))
+    );
+}
+
+#[test]
+fn atb_rounds() {
+    const SPEED_ID: u32 = 0;
+
+    #[derive(Default)]
+    struct CustomCharacterRules {}
+
+    impl CharacterRules<CustomRules> for CustomCharacterRules {
+        type CreatureId = u32;
+        type ObjectId = ();
+        type Statistic = SimpleStatistic<u32, u32>;
+        type StatisticsSeed = u32;
+        type StatisticsAlteration = ();
+        type Status = EmptyStatus;
+        type StatusesAlteration = ();
+
+        fn generate_statistics(
+            &self,
+            seed: &Option<Self::StatisticsSeed>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) -> Box<dyn Iterator<Item = Self::Statistic>> {
+            let v = vec![SimpleStatistic::new(SPEED_ID, seed.unwrap())];
+            Box::new(v.into_iter())
+        }
+    }
+
+    battle_rules! {
+        EmptyTeamRules,
+        CustomCharacterRules,
+        EmptyActorRules,
+        EmptyFightRules,
+        EmptyUserRules,
+        EmptySpaceRules,
+        AtbRounds<CustomRules>,
+        EmptyEntropyRules
+    }
+
+    let entity_1_id = EntityId::<CustomRules>::Creature(CREATURE_1_ID);
+    let entity_2_id = EntityId::<CustomRules>::Creature(CREATURE_2_ID);
+    // Create two creatures with different speed.
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    for (id, speed) in &[(CREATURE_1_ID, 50), (CREATURE_2_ID, 30)] {
+        assert_eq!(
+            CreateCreature::trigger(&mut server, *id, TEAM_1_ID, ())
+                .statistics_seed(*speed)
+                .fire()
+                .err(),
+            None
+        );
+    }
+    // Creatures start with no charge.
+    let model = server.battle().rounds().model();
+    assert_eq!(model.threshold(), DEFAULT_CHARGE_THRESHOLD);
+    assert_eq!(model.charge(&entity_1_id), Some(0));
+    assert_eq!(model.ready().count(), 0);
+    assert_eq!(
+        StartTurn::trigger(&mut server, entity_1_id)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::ActorNotEligible(entity_1_id))
+    );
+    // Charge the creatures.
+    assert_eq!(server.fire_tick().err(), None);
+    assert_eq!(server.fire_tick().err(), None);
+    let model = server.battle().rounds().model();
+    assert_eq!(model.charge(&entity_1_id), Some(100));
+    assert_eq!(model.charge(&entity_2_id), Some(60));
+    assert_eq!(model.progress(&entity_2_id), Some(0.6));
+    assert_eq!(model.ready().collect::<Vec<_>>(), vec![&entity_1_id]);
+    // Only the charged creature can act.
+    assert_eq!(
+        StartTurn::trigger(&mut server, entity_2_id)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::ActorNotEligible(entity_2_id))
+    );
+    assert_eq!(server.next_actor(), Some(entity_1_id));
+    util::start_turn(&mut server, &entity_1_id);
+    util::end_turn(&mut server);
+    assert_eq!(
+        server.battle().rounds().model().charge(&entity_1_id),
+        Some(0)
+    );
+    // Actors are ordered by charge.
+    assert_eq!(
+        server.battle().rounds().order(server.battle().entities()),
+        vec![entity_2_id, entity_1_id]
+    );
+    // Charge never exceeds the threshold.
+    assert_eq!(server.fire_tick().err(), None);
+    assert_eq!(server.fire_tick().err(), None);
+    let model = server.battle().rounds().model();
+    assert_eq!(model.charge(&entity_1_id), Some(100));
+    assert_eq!(model.charge(&entity_2_id), Some(100));
+    // Removed creatures are forgotten.
+    assert_eq!(
+        RemoveCreature::trigger(&mut server, CREATURE_2_ID)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(server.battle().rounds().model().charges().count(), 1);
+}
+
+#[test]
+fn turn_order() {
+    let mut server = server!();
+    // Eligible actors are returned in order of insertion.
+    assert_eq!(
+        server.battle().rounds().order(server.battle().entities()),
+        vec![ENTITY_1_ID, ENTITY_3_ID]
+    );
+    assert_eq!(server.next_actor(), Some(ENTITY_1_ID));
+    util::start_turn(&mut server, &ENTITY_1_ID);
+    util::end_turn(&mut server);
+    assert_eq!(server.next_actor(), Some(ENTITY_2_ID));
+    // Without actors, nobody acts next.
+    for id in &[CREATURE_1_ID, CREATURE_2_ID, CREATURE_3_ID] {
+        assert_eq!(RemoveCreature::trigger(&mut server, *id).fire().err(), None);
+    }
+    assert_eq!(server.next_actor(), None);
+}
+
+#[test]
+fn round_limit() {
+    battle_rules! {}
+    // Create a battle ending after two rounds, with three teams.
+    let battle = Battle::builder(CustomRules::new()).round_limit(2).build();
+    assert_eq!(battle.rounds().round_limit(), Some(2));
+    let mut server = Server::builder(battle).build();
+    util::team(&mut server, TEAM_1_ID);
+    util::team(&mut server, TEAM_2_ID);
+    util::team(&mut server, TEAM_3_ID);
+    // One team has already a conclusion.
+    assert_eq!(
+        ConcludeObjectives::trigger(&mut server, TEAM_3_ID, Conclusion::Defeat)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(
+        AddScore::trigger(&mut server, TEAM_3_ID, 5).fire().err(),
+        None
+    );
+    assert_eq!(
+        AddScore::trigger(&mut server, TEAM_2_ID, 2).fire().err(),
+        None
+    );
+    // The battle goes on until the limit is reached.
+    assert_eq!(EndRound::trigger(&mut server).fire().err(), None);
+    assert_eq!(server.battle().phase(), BattlePhase::Started);
+    assert_eq!(EndRound::trigger(&mut server).fire().err(), None);
+    assert_eq!(server.battle().phase(), BattlePhase::Ended);
+    assert_eq!(server.battle().end_reason(), Some(EndReason::Timeout));
+    // Teams without a conclusion are concluded by score.
+    let entities = server.battle().entities();
+    assert_eq!(
+        entities.team(&TEAM_1_ID).unwrap().conclusion(),
+        Some(Conclusion::Defeat)
+    );
+    assert_eq!(
+        entities.team(&TEAM_2_ID).unwrap().conclusion(),
+        Some(Conclusion::Victory)
+    );
+    assert_eq!(
+        entities.team(&TEAM_3_ID).unwrap().conclusion(),
+        Some(Conclusion::Defeat)
+    );
+}
+
+#[test]
+fn scheduled_events() {
+    battle_rules! {}
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    // Schedule events after some turns and rounds.
+    let event = RemoveCreature::trigger(&mut (), CREATURE_1_ID).event();
+    assert_eq!(
+        ScheduleEvent::trigger(&mut server, event)
+            .after_turns(2)
+            .fire()
+            .err(),
+        None
+    );
+    let event = CreateTeam::trigger(&mut (), TEAM_2_ID).event();
+    assert_eq!(
+        ScheduleEvent::trigger(&mut server, event)
+            .after_rounds(1)
+            .fire()
+            .err(),
+        None
+    );
+    let delays: Vec<_> = server
+        .battle()
+        .rounds()
+        .scheduled_events()
+        .map(|scheduled| scheduled.delay())
+        .collect();
+    assert_eq!(delays, vec![Delay::Turns(2), Delay::Rounds(1)]);
+    // Events are fired once their delay expires.
+    util::start_turn(&mut server, &EntityId::Creature(CREATURE_1_ID));
+    util::end_turn(&mut server);
+    assert!(server
+        .battle()
+        .entities()
+        .creature(&CREATURE_1_ID)
+        .is_some());
+    assert_eq!(EnvironmentTurn::trigger(&mut server).fire().err(), None);
+    assert!(server
+        .battle()
+        .entities()
+        .creature(&CREATURE_1_ID)
+        .is_none());
+    assert_eq!(server.battle().rounds().scheduled_events().count(), 1);
+    assert_eq!(EndRound::trigger(&mut server).fire().err(), None);
+    assert!(server.battle().entities().team(&TEAM_2_ID).is_some());
+    assert_eq!(server.battle().rounds().scheduled_events().count(), 0);
+    // Fired events are linked to the event that scheduled them.
+    let events = server.battle().history().events();
+    let last = events.last().unwrap();
+    assert_eq!(last.kind(), EventKind::CreateTeam);
+    assert_eq!(last.origin(), Some(3));
+    // Scheduled events are verified when fired.
+    let event = CreateTeam::trigger(&mut (), TEAM_2_ID).event();
+    assert_eq!(
+        ScheduleEvent::trigger(&mut server, event).fire().err(),
+        None
+    );
+    assert_eq!(
+        EnvironmentTurn::trigger(&mut server)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::DuplicatedTeam(TEAM_2_ID))
+    );
+    // Only the server can schedule events.
+    let event = DummyEvent::trigger(&mut ()).event();
+    let event = ScheduleEvent::trigger(&mut (), event).event();
+    assert_eq!(event.rights(server.battle()), EventRights::Server);
+}
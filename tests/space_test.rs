@@ -3,11 +3,15 @@ use weasel::battle::{BattleController, BattleRules};
 use weasel::battle_rules_with_space;
 use weasel::creature::CreateCreature;
 use weasel::entity::{Entities, Entity, EntityId};
+use weasel::entropy::Entropy;
 use weasel::event::{EventQueue, EventTrigger};
 use weasel::metric::WriteMetrics;
-use weasel::round::Rounds;
+use weasel::object::Object;
+use weasel::round::{EnvironmentTurn, Rounds};
 use weasel::server::Server;
-use weasel::space::{AlterSpace, MoveEntity, PositionClaim, ResetSpace, SpaceRules};
+use weasel::space::{
+    AlterSpace, Distance, MoveEntity, MoveEntityAlongPath, PositionClaim, ResetSpace, SpaceRules,
+};
 use weasel::{battle_rules, rules::empty::*, WeaselError, WeaselResult};
 
 const TEAM_1_ID: u32 = 1;
@@ -25,6 +29,8 @@ struct CustomSpaceRules {}
 impl SpaceRules<CustomRules> for CustomSpaceRules {
     type Position = u32;
     type SpaceSeed = ();
+    // Areas are inclusive intervals of positions.
+    type Area = (u32, u32);
     type SpaceModel = HashSet<Self::Position>;
     type SpaceAlteration = Self::Position;
 
@@ -85,6 +91,80 @@ impl SpaceRules<CustomRules> for CustomSpaceRules {
         // Make the position inside 'alteration' inaccessible.
         model.insert(*alteration);
     }
+
+    fn on_environment_turn(
+        &self,
+        _model: &Self::SpaceModel,
+        object: &Object<CustomRules>,
+        event_queue: &mut Option<EventQueue<CustomRules>>,
+        _entropy: &mut Entropy<CustomRules>,
+        _metrics: &mut WriteMetrics<CustomRules>,
+    ) {
+        // Objects drift forward by one position.
+        MoveEntity::trigger(event_queue, *object.entity_id(), object.position() + 1).fire();
+    }
+
+    fn is_in_area(
+        &self,
+        _model: &Self::SpaceModel,
+        area: &Self::Area,
+        position: &Self::Position,
+    ) -> bool {
+        *position >= area.0 && *position <= area.1
+    }
+
+    fn distance(
+        &self,
+        _model: &Self::SpaceModel,
+        from: &Self::Position,
+        to: &Self::Position,
+    ) -> Option<Distance> {
+        // Position zero is unreachable.
+        if *from == 0 || *to == 0 {
+            None
+        } else {
+            Some(from.abs_diff(*to))
+        }
+    }
+
+    fn line_of_sight(
+        &self,
+        model: &Self::SpaceModel,
+        from: &Self::Position,
+        to: &Self::Position,
+    ) -> bool {
+        // Sight is blocked by any occupied position in between.
+        let (low, high) = (*from.min(to), *from.max(to));
+        (low + 1..high).all(|position| !model.contains(&position))
+    }
+
+    fn path(
+        &self,
+        _model: &Self::SpaceModel,
+        from: &Self::Position,
+        to: &Self::Position,
+    ) -> Option<Vec<Self::Position>> {
+        if from < to {
+            Some((from + 1..=*to).collect())
+        } else {
+            Some((*to..*from).rev().collect())
+        }
+    }
+
+    fn check_step(
+        &self,
+        _model: &Self::SpaceModel,
+        _entity: &dyn Entity<CustomRules>,
+        from: &Self::Position,
+        to: &Self::Position,
+    ) -> WeaselResult<(), CustomRules> {
+        // Entities move one position at a time.
+        if from.abs_diff(*to) == 1 {
+            Ok(())
+        } else {
+            Err(WeaselError::UserError("step too long".to_string()))
+        }
+    }
 }
 
 battle_rules_with_space! { CustomSpaceRules }
@@ -193,6 +273,19 @@ fn move_object() {
     );
 }
 
+#[test]
+fn environment_moves_objects() {
+    let mut server = init_custom_game();
+    // Create an object.
+    util::object(&mut server, OBJECT_1_ID, POSITION_2);
+    // The environment turn makes the object drift.
+    assert_eq!(EnvironmentTurn::trigger(&mut server).fire().err(), None);
+    let object = server.battle().entities().object(&OBJECT_1_ID).unwrap();
+    assert_eq!(*object.position(), POSITION_2 + 1);
+    assert!(server.battle().space().model().contains(&(POSITION_2 + 1)));
+    assert!(!server.battle().space().model().contains(&POSITION_2));
+}
+
 #[test]
 fn reset_space() {
     // Create a scenario.
@@ -234,3 +327,96 @@ fn alter_space() {
         ))
     );
 }
+
+#[test]
+fn spatial_queries() {
+    let mut server = init_custom_game();
+    util::creature(&mut server, CREATURE_2_ID, TEAM_1_ID, POSITION_2);
+    util::creature(&mut server, 3, TEAM_1_ID, 5);
+    util::creature(&mut server, 4, TEAM_1_ID, 0);
+    let (space, entities) = (server.battle().space(), server.battle().entities());
+    let ids = |it: &mut dyn Iterator<Item = &dyn Entity<CustomRules>>| {
+        let mut ids: Vec<_> = it.map(|entity| *entity.entity_id()).collect();
+        ids.sort_by_key(|id| id.creature().unwrap());
+        ids
+    };
+    // Find entities inside an area.
+    assert_eq!(
+        ids(&mut space.entities_in_area(entities, &(0, 2))),
+        vec![
+            ENTITY_1_ID,
+            EntityId::Creature(CREATURE_2_ID),
+            EntityId::Creature(4)
+        ]
+    );
+    assert!(space.entities_in_area(entities, &(10, 20)).next().is_none());
+    // Find neighbors, excluding unreachable positions and the center.
+    assert_eq!(
+        ids(&mut space.neighbors(entities, &POSITION_2, 1)),
+        vec![ENTITY_1_ID]
+    );
+    assert_eq!(
+        ids(&mut space.neighbors(entities, &POSITION_2, 3)),
+        vec![ENTITY_1_ID, EntityId::Creature(3)]
+    );
+}
+
+#[test]
+fn move_along_path() {
+    let mut server = init_custom_game();
+    util::creature(&mut server, CREATURE_2_ID, TEAM_1_ID, 4);
+    // Check line of sight.
+    let space = server.battle().space();
+    assert!(space.line_of_sight(&POSITION_1, &3));
+    assert!(!space.line_of_sight(&POSITION_1, &5));
+    // Move along a path computed by the rules.
+    let path = space.path(&POSITION_1, &3).unwrap();
+    assert_eq!(path, vec![2, 3]);
+    assert_eq!(
+        MoveEntityAlongPath::trigger(&mut server, ENTITY_1_ID, path)
+            .fire()
+            .err(),
+        None
+    );
+    let position = |server: &Server<CustomRules>| {
+        *server
+            .battle()
+            .entities()
+            .entity(&ENTITY_1_ID)
+            .unwrap()
+            .position()
+    };
+    assert_eq!(position(&server), 3);
+    assert!(!server.battle().space().model().contains(&2));
+    // Each step is verified.
+    assert_eq!(
+        MoveEntityAlongPath::trigger(&mut server, ENTITY_1_ID, vec![2, 0])
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::PositionError(
+            Some(2),
+            0,
+            Box::new(WeaselError::UserError("step too long".to_string()))
+        ))
+    );
+    assert_eq!(
+        MoveEntityAlongPath::trigger(&mut server, ENTITY_1_ID, vec![4, 5])
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::PositionError(
+            Some(3),
+            4,
+            Box::new(WeaselError::GenericError)
+        ))
+    );
+    assert_eq!(
+        MoveEntityAlongPath::trigger(&mut server, ENTITY_1_ID, Vec::new())
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::EmptyPath(ENTITY_1_ID))
+    );
+    assert_eq!(position(&server), 3);
+}
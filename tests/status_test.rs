@@ -1,17 +1,17 @@
 use std::cell::RefCell;
 use weasel::battle::{Battle, BattleController, BattleRules, BattleState};
 use weasel::character::{AlterStatistics, Character, CharacterRules};
-use weasel::entity::{EntityId, Transmutation};
+use weasel::entity::{Entity, EntityId, Transmutation};
 use weasel::entropy::Entropy;
-use weasel::event::{EventKind, EventQueue, EventTrigger, LinkedQueue};
+use weasel::event::{EventKind, EventQueue, EventReceiver, EventTrigger, LinkedQueue};
 use weasel::fight::FightRules;
 use weasel::metric::WriteMetrics;
 use weasel::round::EnvironmentTurn;
 use weasel::rules::statistic::SimpleStatistic;
 use weasel::rules::status::SimpleStatus;
 use weasel::status::{
-    AlterStatuses, Application, AppliedStatus, ClearStatus, InflictStatus, Potency, Status,
-    StatusDuration, StatusId,
+    AlterStatusDuration, AlterStatuses, Application, AppliedStatus, ClearStatus, DurationChange,
+    InflictStatus, Potency, Status, StatusDuration, StatusId,
 };
 use weasel::{battle_rules, rules::empty::*, Server, WeaselError};
 
@@ -357,6 +357,61 @@ fn status_update() {
     );
 }
 
+#[test]
+fn status_duration() {
+    let duration =
+        |server: &Server<CustomRules>| creature!(server).status(&STATUS_1_ID).unwrap().duration();
+    let mut server = scenario!();
+    // Check that altering statuses not present fails.
+    assert_eq!(
+        AlterStatusDuration::trigger(
+            &mut server,
+            ENTITY_C1_ID,
+            STATUS_1_ID,
+            DurationChange::Reset
+        )
+        .fire()
+        .err()
+        .map(|e| e.unfold()),
+        Some(WeaselError::StatusNotPresent(ENTITY_C1_ID, STATUS_1_ID))
+    );
+    // Inflict the status to the creature and do a turn.
+    assert_eq!(
+        InflictStatus::trigger(&mut server, ENTITY_C1_ID, STATUS_1_ID)
+            .potency((STATUS_INTENSITY, STATUS_DURATION))
+            .fire()
+            .err(),
+        None
+    );
+    let origin = creature!(server).status(&STATUS_1_ID).unwrap().origin();
+    util::start_turn(&mut server, &ENTITY_C1_ID);
+    util::end_turn(&mut server);
+    assert_eq!(duration(&server), 1);
+    // Change the duration in all possible ways.
+    let mut alter = |change| {
+        assert_eq!(
+            AlterStatusDuration::trigger(&mut server, ENTITY_C1_ID, STATUS_1_ID, change)
+                .fire()
+                .err(),
+            None
+        );
+        duration(&server)
+    };
+    assert_eq!(alter(DurationChange::Reset), 0);
+    assert_eq!(alter(DurationChange::Shorten(3)), 3);
+    assert_eq!(alter(DurationChange::Extend(2)), 1);
+    assert_eq!(alter(DurationChange::Extend(2)), 0);
+    assert_eq!(alter(DurationChange::Set(1)), 1);
+    // The status keeps its origin and it expires according to the new duration.
+    assert_eq!(
+        creature!(server).status(&STATUS_1_ID).unwrap().origin(),
+        origin
+    );
+    util::start_turn(&mut server, &ENTITY_C1_ID);
+    util::end_turn(&mut server);
+    assert!(creature!(server).status(&STATUS_1_ID).is_none());
+}
+
 #[test]
 fn status_for_objects() {
     let mut server = scenario!();
@@ -532,3 +587,437 @@ fn statuses_alteration() {
         STATUS_INTENSITY + ALTERATION
     );
 }
+
+#[test]
+fn status_order_and_combination() {
+    use weasel::fight::ApplyImpact;
+    use weasel::status::StatusCombination;
+    use weasel::util::Id;
+
+    const WET_ID: u32 = 10;
+    const FROZEN_ID: u32 = 11;
+    const FIRE_ID: u32 = 12;
+
+    #[derive(Default)]
+    pub struct CustomCharacterRules {}
+
+    impl CharacterRules<CustomRules> for CustomCharacterRules {
+        type CreatureId = u32;
+        type ObjectId = u32;
+        type Statistic = EmptyStat;
+        type StatisticsSeed = ();
+        type StatisticsAlteration = ();
+        type Status = SimpleStatus<u32, i32>;
+        type StatusesAlteration = ();
+
+        fn generate_status(
+            &self,
+            _character: &dyn Character<CustomRules>,
+            status_id: &StatusId<CustomRules>,
+            _potency: &Option<Potency<CustomRules>>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) -> Option<Status<CustomRules>> {
+            Some(SimpleStatus::new(*status_id, 1, None))
+        }
+    }
+
+    #[derive(Default)]
+    pub struct CustomFightRules {}
+
+    impl FightRules<CustomRules> for CustomFightRules {
+        type Impact = ();
+        type Potency = ();
+
+        fn apply_impact(
+            &self,
+            _state: &BattleState<CustomRules>,
+            _impact: &Self::Impact,
+            event_queue: &mut Option<EventQueue<CustomRules>>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) {
+            // Inflict frozen before wet.
+            InflictStatus::trigger(event_queue, EntityId::Creature(CREATURE_1_ID), FROZEN_ID)
+                .fire();
+            InflictStatus::trigger(event_queue, EntityId::Creature(CREATURE_1_ID), WET_ID).fire();
+        }
+
+        fn status_order(&self, status_id: &StatusId<CustomRules>) -> i32 {
+            if *status_id == WET_ID {
+                0
+            } else {
+                1
+            }
+        }
+
+        fn combine_statuses(
+            &self,
+            _state: &BattleState<CustomRules>,
+            character: &dyn Character<CustomRules>,
+            mut status: Status<CustomRules>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) -> StatusCombination<CustomRules> {
+            match *status.id() {
+                // Freezing a wet character consumes the water and makes the ice stronger.
+                FROZEN_ID if character.status(&WET_ID).is_some() => {
+                    status.set_effect(2);
+                    StatusCombination::Inflict(status, vec![WET_ID])
+                }
+                // Fire melts the ice, but doesn't burn.
+                FIRE_ID if character.status(&FROZEN_ID).is_some() => {
+                    StatusCombination::Discard(vec![FROZEN_ID])
+                }
+                _ => StatusCombination::Inflict(status, Vec::new()),
+            }
+        }
+    }
+
+    battle_rules! {
+        EmptyTeamRules,
+        CustomCharacterRules,
+        EmptyActorRules,
+        CustomFightRules,
+        EmptyUserRules,
+        EmptySpaceRules,
+        EmptyRoundsRules,
+        EmptyEntropyRules
+    }
+
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    // Wet is applied first, then it's consumed by frozen.
+    assert_eq!(ApplyImpact::trigger(&mut server, ()).fire().err(), None);
+    let statuses: Vec<_> = server
+        .battle()
+        .history()
+        .iter_as::<InflictStatus<_>>()
+        .map(|event| *event.status_id())
+        .collect();
+    assert_eq!(statuses, vec![WET_ID, FROZEN_ID]);
+    let creature = server.battle().entities().creature(&CREATURE_1_ID).unwrap();
+    assert!(creature.status(&WET_ID).is_none());
+    assert_eq!(creature.status(&FROZEN_ID).unwrap().effect(), 2);
+    // Fire is discarded, but it still melts the ice.
+    assert_eq!(
+        InflictStatus::trigger(&mut server, EntityId::Creature(CREATURE_1_ID), FIRE_ID)
+            .fire()
+            .err(),
+        None
+    );
+    let creature = server.battle().entities().creature(&CREATURE_1_ID).unwrap();
+    assert_eq!(creature.statuses().count(), 0);
+}
+
+#[test]
+fn chained_status_order_and_combination() {
+    use weasel::fight::ApplyImpact;
+    use weasel::rules::mixin::Chain;
+    use weasel::status::StatusCombination;
+    use weasel::util::Id;
+
+    const WET_ID: u32 = 10;
+    const FROZEN_ID: u32 = 11;
+
+    #[derive(Default)]
+    pub struct CustomCharacterRules {}
+
+    impl CharacterRules<CustomRules> for CustomCharacterRules {
+        type CreatureId = u32;
+        type ObjectId = u32;
+        type Statistic = EmptyStat;
+        type StatisticsSeed = ();
+        type StatisticsAlteration = ();
+        type Status = SimpleStatus<u32, i32>;
+        type StatusesAlteration = ();
+
+        fn generate_status(
+            &self,
+            _character: &dyn Character<CustomRules>,
+            status_id: &StatusId<CustomRules>,
+            _potency: &Option<Potency<CustomRules>>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) -> Option<Status<CustomRules>> {
+            Some(SimpleStatus::new(*status_id, 1, None))
+        }
+    }
+
+    #[derive(Default)]
+    pub struct OrderRules {}
+
+    impl FightRules<CustomRules> for OrderRules {
+        type Impact = ();
+        type Potency = ();
+
+        fn apply_impact(
+            &self,
+            _state: &BattleState<CustomRules>,
+            _impact: &Self::Impact,
+            event_queue: &mut Option<EventQueue<CustomRules>>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) {
+            // Inflict frozen before wet.
+            InflictStatus::trigger(event_queue, EntityId::Creature(CREATURE_1_ID), FROZEN_ID)
+                .fire();
+            InflictStatus::trigger(event_queue, EntityId::Creature(CREATURE_1_ID), WET_ID).fire();
+        }
+
+        fn status_order(&self, status_id: &StatusId<CustomRules>) -> i32 {
+            if *status_id == WET_ID {
+                -1
+            } else {
+                0
+            }
+        }
+    }
+
+    #[derive(Default)]
+    pub struct CombinationRules {}
+
+    impl FightRules<CustomRules> for CombinationRules {
+        type Impact = ();
+        type Potency = ();
+
+        fn combine_statuses(
+            &self,
+            _state: &BattleState<CustomRules>,
+            character: &dyn Character<CustomRules>,
+            status: Status<CustomRules>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) -> StatusCombination<CustomRules> {
+            // Freezing a wet character consumes the water.
+            if *status.id() == FROZEN_ID && character.status(&WET_ID).is_some() {
+                StatusCombination::Inflict(status, vec![WET_ID])
+            } else {
+                StatusCombination::Inflict(status, Vec::new())
+            }
+        }
+    }
+
+    battle_rules! {
+        EmptyTeamRules,
+        CustomCharacterRules,
+        EmptyActorRules,
+        Chain<OrderRules, CombinationRules>,
+        EmptyUserRules,
+        EmptySpaceRules,
+        EmptyRoundsRules,
+        EmptyEntropyRules
+    }
+
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    // Both the order and the combination of the chained rules are respected.
+    assert_eq!(ApplyImpact::trigger(&mut server, ()).fire().err(), None);
+    let statuses: Vec<_> = server
+        .battle()
+        .history()
+        .iter_as::<InflictStatus<_>>()
+        .map(|event| *event.status_id())
+        .collect();
+    assert_eq!(statuses, vec![WET_ID, FROZEN_ID]);
+    let creature = server.battle().entities().creature(&CREATURE_1_ID).unwrap();
+    assert!(creature.status(&WET_ID).is_none());
+    assert!(creature.status(&FROZEN_ID).is_some());
+}
+
+#[test]
+fn auras() {
+    use weasel::aura::Aura;
+    use weasel::rules::mixin::Chain;
+    use weasel::space::{MoveEntity, SpaceRules};
+    use weasel::util::Id;
+
+    const AURA_ID: u32 = 10;
+    const BLESSED_ID: u32 = 11;
+    const CREATURE_2_ID: u32 = 2;
+    const ENTITY_C1_ID: EntityId<CustomRules> = EntityId::Creature(CREATURE_1_ID);
+    const ENTITY_C2_ID: EntityId<CustomRules> = EntityId::Creature(CREATURE_2_ID);
+
+    #[derive(Default)]
+    pub struct CustomCharacterRules {}
+
+    impl CharacterRules<CustomRules> for CustomCharacterRules {
+        type CreatureId = u32;
+        type ObjectId = u32;
+        type Statistic = EmptyStat;
+        type StatisticsSeed = ();
+        type StatisticsAlteration = ();
+        type Status = SimpleStatus<u32, i32>;
+        type StatusesAlteration = ();
+
+        fn generate_status(
+            &self,
+            _character: &dyn Character<CustomRules>,
+            status_id: &StatusId<CustomRules>,
+            potency: &Option<Potency<CustomRules>>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) -> Option<Status<CustomRules>> {
+            Some(SimpleStatus::new(*status_id, potency.unwrap_or(0), None))
+        }
+    }
+
+    #[derive(Default)]
+    pub struct BaseFightRules {}
+
+    impl FightRules<CustomRules> for BaseFightRules {
+        type Impact = ();
+        type Potency = i32;
+    }
+
+    #[derive(Default)]
+    pub struct AuraFightRules {}
+
+    impl FightRules<CustomRules> for AuraFightRules {
+        type Impact = ();
+        type Potency = i32;
+
+        fn aura(
+            &self,
+            _state: &BattleState<CustomRules>,
+            source: &dyn Character<CustomRules>,
+            status: &AppliedStatus<CustomRules>,
+        ) -> Option<Aura<CustomRules>> {
+            // The aura blesses everyone within one step of its source.
+            if *status.id() == AURA_ID {
+                let position = *source.position();
+                let area = (position - 1, position + 1);
+                Some(Aura::new(area, BLESSED_ID).with_potency(status.effect()))
+            } else {
+                None
+            }
+        }
+    }
+
+    #[derive(Default)]
+    pub struct CustomSpaceRules {}
+
+    impl SpaceRules<CustomRules> for CustomSpaceRules {
+        type Position = i32;
+        type SpaceSeed = ();
+        type SpaceAlteration = ();
+        // Areas are inclusive intervals of positions.
+        type Area = (i32, i32);
+        type SpaceModel = ();
+
+        fn generate_model(&self, _: &Option<Self::SpaceSeed>) -> Self::SpaceModel {}
+
+        fn is_in_area(
+            &self,
+            _model: &Self::SpaceModel,
+            area: &Self::Area,
+            position: &Self::Position,
+        ) -> bool {
+            *position >= area.0 && *position <= area.1
+        }
+    }
+
+    battle_rules! {
+        EmptyTeamRules,
+        CustomCharacterRules,
+        EmptyActorRules,
+        // Auras must be forwarded by chained rules.
+        Chain<BaseFightRules, AuraFightRules>,
+        EmptyUserRules,
+        CustomSpaceRules,
+        EmptyRoundsRules,
+        EmptyEntropyRules
+    }
+
+    let blessing = |server: &Server<CustomRules>, id| {
+        let creature = server.battle().entities().creature(&id).unwrap();
+        creature.status(&BLESSED_ID).map(|status| {
+            assert!(status.aura_source().is_some());
+            status.effect()
+        })
+    };
+
+    let battle = Battle::builder(CustomRules::new()).build();
+    let mut server = Server::builder(battle)
+        .bootstrap_callback(Box::new(|battle, queue| {
+            // Restore only the auras, derived statuses are synthesized by the server.
+            for creature in battle.entities().creatures() {
+                for status in creature.statuses() {
+                    if status.aura_source().is_none() {
+                        InflictStatus::trigger(queue, *creature.entity_id(), *status.id())
+                            .potency(status.effect())
+                            .fire();
+                    }
+                }
+            }
+        }))
+        .build();
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, 0);
+    util::creature(&mut server, CREATURE_2_ID, TEAM_1_ID, 5);
+    // Inflicting the aura projects it on its source.
+    assert_eq!(
+        InflictStatus::trigger(&mut server, ENTITY_C1_ID, AURA_ID)
+            .potency(3)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(blessing(&server, CREATURE_1_ID), Some(3));
+    assert_eq!(blessing(&server, CREATURE_2_ID), None);
+    let last = server.battle().history().events().last().unwrap();
+    let event: &InflictStatus<_> = last.as_any().downcast_ref().unwrap();
+    assert_eq!(*event.aura_source(), Some(ENTITY_C1_ID));
+    // Entering the area inflicts the derived status.
+    assert_eq!(
+        MoveEntity::trigger(&mut server, ENTITY_C2_ID, 1)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(blessing(&server, CREATURE_2_ID), Some(3));
+    // Moving inside the area doesn't inflict the status again.
+    let events = server.battle().history().len();
+    assert_eq!(
+        MoveEntity::trigger(&mut server, ENTITY_C2_ID, -1)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(server.battle().history().len(), events + 1);
+    // Leaving the area clears the derived status.
+    assert_eq!(
+        MoveEntity::trigger(&mut server, ENTITY_C2_ID, 2)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(blessing(&server, CREATURE_2_ID), None);
+    // The area follows the source.
+    assert_eq!(
+        MoveEntity::trigger(&mut server, ENTITY_C1_ID, 3)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(blessing(&server, CREATURE_2_ID), Some(3));
+    // Late joiners receive both the aura and the derived statuses.
+    let mut new_server = util::server(CustomRules::new());
+    for event in server.bootstrap_events_for_new_client() {
+        assert_eq!(new_server.receive(event).err(), None);
+    }
+    assert!(new_server.battle().history().events()[0].synthetic());
+    assert_eq!(blessing(&new_server, CREATURE_1_ID), Some(3));
+    assert_eq!(blessing(&new_server, CREATURE_2_ID), Some(3));
+    // Clearing the aura clears all derived statuses.
+    assert_eq!(
+        ClearStatus::trigger(&mut server, ENTITY_C1_ID, AURA_ID)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(blessing(&server, CREATURE_1_ID), None);
+    assert_eq!(blessing(&server, CREATURE_2_ID), None);
+}
@@ -9,10 +9,13 @@ use weasel::event::{DummyEvent, EventKind, EventQueue, EventTrigger};
 use weasel::metric::{system::*, ReadMetrics, WriteMetrics};
 use weasel::player::PlayerId;
 use weasel::team::{
-    ConcludeObjectives, Conclusion, CreateTeam, EntityAddition, Relation, RemoveTeam,
-    ResetObjectives, SetRelations, Team, TeamRules,
+    AddScore, ConcludeObjectives, Conclusion, CreateTeam, EntityAddition, ObjectiveProgress,
+    Relation, RemoveTeam, RenameTeam, ResetObjectives, SetRelations, TargetRelation, Team,
+    TeamRules,
+};
+use weasel::{
+    battle_rules, battle_rules_with_team, rules::empty::*, Id, WeaselError, WeaselResult,
 };
-use weasel::{battle_rules, battle_rules_with_team, rules::empty::*, WeaselError, WeaselResult};
 
 #[derive(Default)]
 struct CustomTeamRules {
@@ -28,6 +31,7 @@ impl<R: BattleRules> TeamRules<R> for CustomTeamRules {
     type PowersAlteration = ();
     type ObjectivesSeed = ();
     type Objectives = ();
+    type TeamData = ();
 
     fn allow_new_entity(
         &self,
@@ -36,7 +40,9 @@ impl<R: BattleRules> TeamRules<R> for CustomTeamRules {
         mode: EntityAddition<R>,
     ) -> WeaselResult<(), R> {
         let allowed = match mode {
-            EntityAddition::CreatureSpawn => *self.allow_new_entities.borrow(),
+            EntityAddition::CreatureSpawn | EntityAddition::CreatureWaveSpawn(_) => {
+                *self.allow_new_entities.borrow()
+            }
             EntityAddition::CreatureConversion(_) => *self.allow_converted_entities.borrow(),
         };
         if allowed {
@@ -52,6 +58,7 @@ const TEAM_2_ID: u32 = 2;
 const TEAM_3_ID: u32 = 3;
 const TEAM_ERR_ID: u32 = 99;
 const CREATURE_1_ID: u32 = 1;
+const CREATURE_2_ID: u32 = 2;
 const CREATURE_ERR_ID: u32 = 99;
 
 #[test]
@@ -522,6 +529,7 @@ fn reset_objectives() {
         type PowersAlteration = ();
         type ObjectivesSeed = u32;
         type Objectives = u32;
+        type TeamData = ();
 
         fn generate_objectives(&self, seed: &Option<Self::ObjectivesSeed>) -> Self::Objectives {
             seed.unwrap_or_default()
@@ -646,6 +654,7 @@ fn check_objectives() {
         type PowersAlteration = ();
         type ObjectivesSeed = ();
         type Objectives = ();
+        type TeamData = ();
 
         fn check_objectives_on_event(
             &self,
@@ -769,6 +778,103 @@ fn check_objectives() {
     );
 }
 
+#[test]
+fn objectives_progress() {
+    #[derive(Debug)]
+    struct CreaturesProgress {
+        count: usize,
+        target: usize,
+    }
+
+    impl ObjectiveProgress for CreaturesProgress {
+        fn value(&self) -> f32 {
+            (self.count as f32 / self.target as f32).min(1.0)
+        }
+
+        fn description(&self) -> String {
+            format!("{}/{} creatures", self.count, self.target)
+        }
+    }
+
+    #[derive(Default)]
+    struct CustomTeamRules {}
+
+    impl TeamRules<CustomRules> for CustomTeamRules {
+        type Id = u32;
+        type Power = EmptyPower;
+        type PowersSeed = ();
+        type Invocation = ();
+        type PowersAlteration = ();
+        type ObjectivesSeed = usize;
+        type Objectives = usize;
+        type TeamData = ();
+
+        fn generate_objectives(&self, seed: &Option<Self::ObjectivesSeed>) -> Self::Objectives {
+            seed.unwrap_or(0)
+        }
+
+        fn objectives_progress(
+            &self,
+            _state: &BattleState<CustomRules>,
+            team: &Team<CustomRules>,
+            _metrics: &ReadMetrics<CustomRules>,
+        ) -> Option<Box<dyn ObjectiveProgress>> {
+            if *team.objectives() == 0 {
+                return None;
+            }
+            Some(Box::new(CreaturesProgress {
+                count: team.creatures().count(),
+                target: *team.objectives(),
+            }))
+        }
+    }
+
+    battle_rules_with_team! { CustomTeamRules }
+
+    let mut server = util::server(CustomRules::new());
+    // Team without objectives has no progress.
+    util::team(&mut server, TEAM_1_ID);
+    assert!(server
+        .battle()
+        .entities()
+        .team(&TEAM_1_ID)
+        .unwrap()
+        .progress()
+        .is_none());
+    // Create a team whose objective is to have two creatures.
+    assert_eq!(
+        CreateTeam::trigger(&mut server, TEAM_2_ID)
+            .objectives_seed(2)
+            .fire()
+            .err(),
+        None
+    );
+    let progress = |server: &weasel::Server<CustomRules>| {
+        server
+            .battle()
+            .entities()
+            .team(&TEAM_2_ID)
+            .unwrap()
+            .progress()
+            .map(|progress| (progress.value(), progress.description()))
+    };
+    assert_eq!(progress(&server), Some((0.0, "0/2 creatures".to_string())));
+    // Progress is updated after each event.
+    util::creature(&mut server, CREATURE_1_ID, TEAM_2_ID, ());
+    assert_eq!(progress(&server), Some((0.5, "1/2 creatures".to_string())));
+    util::creature(&mut server, CREATURE_2_ID, TEAM_2_ID, ());
+    assert_eq!(progress(&server), Some((1.0, "2/2 creatures".to_string())));
+    // Resetting the objectives resets the progress as well.
+    assert_eq!(
+        ResetObjectives::trigger(&mut server, TEAM_2_ID)
+            .seed(4)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(progress(&server), Some((0.5, "2/4 creatures".to_string())));
+}
+
 #[test]
 fn remove_team() {
     const PLAYER_1_ID: PlayerId = 1;
@@ -821,3 +927,392 @@ fn remove_team() {
     assert!(!server.rights().check(PLAYER_1_ID, &TEAM_1_ID));
     assert!(server.battle().entities().team(&TEAM_1_ID).is_none());
 }
+
+#[test]
+fn rename_team() {
+    const PLAYER_1_ID: PlayerId = 1;
+    battle_rules! {}
+    // Create a battle with three teams and one creature.
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    util::team(&mut server, TEAM_2_ID);
+    assert_eq!(
+        SetRelations::trigger(&mut server, &[(TEAM_1_ID, TEAM_2_ID, Relation::Ally)])
+            .fire()
+            .err(),
+        None
+    );
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    assert_eq!(server.rights_mut().add(PLAYER_1_ID, &TEAM_1_ID).err(), None);
+    // Renaming should fail if the team doesn't exist or the new id is taken.
+    assert_eq!(
+        RenameTeam::trigger(&mut server, TEAM_ERR_ID, TEAM_3_ID)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::TeamNotFound(TEAM_ERR_ID))
+    );
+    assert_eq!(
+        RenameTeam::trigger(&mut server, TEAM_1_ID, TEAM_2_ID)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::DuplicatedTeam(TEAM_2_ID))
+    );
+    // Rename the team.
+    assert_eq!(
+        RenameTeam::trigger(&mut server, TEAM_1_ID, TEAM_3_ID)
+            .fire()
+            .err(),
+        None
+    );
+    // Check that all references have been updated.
+    let entities = server.battle().entities();
+    assert!(entities.team(&TEAM_1_ID).is_none());
+    assert!(entities.team(&TEAM_3_ID).is_some());
+    assert_eq!(
+        *entities.creature(&CREATURE_1_ID).unwrap().team_id(),
+        TEAM_3_ID
+    );
+    assert_eq!(
+        entities.relation(&TEAM_3_ID, &TEAM_2_ID),
+        Some(Relation::Ally)
+    );
+    assert_eq!(entities.relation(&TEAM_1_ID, &TEAM_2_ID), None);
+    assert!(!server.rights().check(PLAYER_1_ID, &TEAM_1_ID));
+    assert!(server.rights().check(PLAYER_1_ID, &TEAM_3_ID));
+}
+
+#[test]
+fn score_leaderboard() {
+    battle_rules! {}
+    // Create a battle with three teams.
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    util::team(&mut server, TEAM_2_ID);
+    util::team(&mut server, TEAM_3_ID);
+    // Adding score to a non existent team should fail.
+    assert_eq!(
+        AddScore::trigger(&mut server, TEAM_ERR_ID, 1)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::TeamNotFound(TEAM_ERR_ID))
+    );
+    // Add some points.
+    assert_eq!(
+        AddScore::trigger(&mut server, TEAM_2_ID, 5).fire().err(),
+        None
+    );
+    assert_eq!(
+        AddScore::trigger(&mut server, TEAM_3_ID, 5).fire().err(),
+        None
+    );
+    assert_eq!(
+        AddScore::trigger(&mut server, TEAM_1_ID, 3).fire().err(),
+        None
+    );
+    assert_eq!(
+        AddScore::trigger(&mut server, TEAM_1_ID, -4).fire().err(),
+        None
+    );
+    // Check the scores and the leaderboard.
+    let entities = server.battle().entities();
+    assert_eq!(entities.team(&TEAM_1_ID).unwrap().score(), -1);
+    assert_eq!(entities.team(&TEAM_2_ID).unwrap().score(), 5);
+    let leaderboard: Vec<_> = entities
+        .leaderboard()
+        .iter()
+        .map(|team| (*team.id(), team.score()))
+        .collect();
+    assert_eq!(
+        leaderboard,
+        vec![(TEAM_2_ID, 5), (TEAM_3_ID, 5), (TEAM_1_ID, -1)]
+    );
+}
+
+#[test]
+fn trade_resources() {
+    use weasel::event::{EventRights, EventServer};
+    use weasel::team::{TradeProposal, TransferResource};
+    use weasel::{Battle, Server};
+    battle_rules! {}
+    const GOLD: u16 = 0;
+    const WOOD: u16 = 1;
+    const PLAYER_1_ID: PlayerId = 1;
+    const PLAYER_2_ID: PlayerId = 2;
+    // Create a server with two teams, each with some resources. Require authentication.
+    let mut server = Server::builder(Battle::builder(CustomRules::new()).build())
+        .enforce_authentication()
+        .build();
+    assert_eq!(
+        CreateTeam::trigger(&mut server, TEAM_1_ID)
+            .resources(&[(GOLD, 10)])
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(
+        CreateTeam::trigger(&mut server, TEAM_2_ID)
+            .resources(&[(WOOD, 5)])
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(server.rights_mut().add(PLAYER_1_ID, &TEAM_1_ID).err(), None);
+    assert_eq!(server.rights_mut().add(PLAYER_2_ID, &TEAM_2_ID).err(), None);
+    // Transfers are checked.
+    assert_eq!(
+        TransferResource::trigger(&mut server, TEAM_1_ID, TEAM_2_ID, GOLD, 11)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::InsufficientResources(TEAM_1_ID, GOLD))
+    );
+    assert_eq!(
+        TransferResource::trigger(&mut server, TEAM_1_ID, TEAM_1_ID, GOLD, 1)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::SelfTrade(TEAM_1_ID))
+    );
+    // Only the giving team can transfer resources.
+    let event = TransferResource::trigger(&mut server, TEAM_1_ID, TEAM_2_ID, GOLD, 2)
+        .prototype()
+        .client_prototype(0, Some(PLAYER_2_ID));
+    assert_eq!(
+        event.event().rights(server.battle()),
+        EventRights::Team(&TEAM_1_ID)
+    );
+    assert_eq!(
+        server.process_client(event).err().map(|e| e.unfold()),
+        Some(WeaselError::AuthenticationError(
+            Some(PLAYER_2_ID),
+            TEAM_1_ID
+        ))
+    );
+    let event = TransferResource::trigger(&mut server, TEAM_1_ID, TEAM_2_ID, GOLD, 2)
+        .prototype()
+        .client_prototype(0, Some(PLAYER_1_ID));
+    assert_eq!(server.process_client(event).err(), None);
+    let team_2 = server.battle().entities().team(&TEAM_2_ID).unwrap();
+    assert_eq!(team_2.resource(GOLD), 2);
+    // The first team proposes a trade.
+    let event = TradeProposal::trigger(&mut server, TEAM_1_ID, TEAM_2_ID)
+        .offer(&[(GOLD, 3)])
+        .request(&[(WOOD, 4)])
+        .prototype()
+        .client_prototype(0, Some(PLAYER_1_ID));
+    assert_eq!(server.process_client(event).err(), None);
+    let team_1 = server.battle().entities().team(&TEAM_1_ID).unwrap();
+    assert_eq!(team_1.trade_proposals().count(), 1);
+    assert_eq!(team_1.resource(GOLD), 8);
+    // A proposal not matching the first doesn't conclude the trade.
+    let event = TradeProposal::trigger(&mut server, TEAM_2_ID, TEAM_1_ID)
+        .offer(&[(WOOD, 2)])
+        .request(&[(GOLD, 3)])
+        .prototype()
+        .client_prototype(0, Some(PLAYER_2_ID));
+    assert_eq!(server.process_client(event).err(), None);
+    assert_eq!(
+        server
+            .battle()
+            .entities()
+            .team(&TEAM_2_ID)
+            .unwrap()
+            .resource(WOOD),
+        5
+    );
+    // The second team accepts by mirroring the proposal.
+    let event = TradeProposal::trigger(&mut server, TEAM_2_ID, TEAM_1_ID)
+        .offer(&[(WOOD, 4)])
+        .request(&[(GOLD, 3)])
+        .prototype()
+        .client_prototype(0, Some(PLAYER_2_ID));
+    assert_eq!(server.process_client(event).err(), None);
+    let entities = server.battle().entities();
+    let team_1 = entities.team(&TEAM_1_ID).unwrap();
+    let team_2 = entities.team(&TEAM_2_ID).unwrap();
+    assert_eq!(team_1.resource(GOLD), 5);
+    assert_eq!(team_1.resource(WOOD), 4);
+    assert_eq!(team_2.resource(GOLD), 5);
+    assert_eq!(team_2.resource(WOOD), 1);
+    assert_eq!(team_1.trade_proposals().count(), 0);
+    assert_eq!(team_2.trade_proposals().count(), 0);
+    // A proposal can be withdrawn.
+    assert_eq!(
+        TradeProposal::trigger(&mut server, TEAM_1_ID, TEAM_2_ID)
+            .offer(&[(GOLD, 1)])
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(
+        TradeProposal::trigger(&mut server, TEAM_1_ID, TEAM_2_ID)
+            .fire()
+            .err(),
+        None
+    );
+    let team_1 = server.battle().entities().team(&TEAM_1_ID).unwrap();
+    assert!(team_1.trade_proposal(&TEAM_2_ID).is_none());
+}
+
+#[test]
+fn target_relation() {
+    battle_rules! {}
+    let creature_1 = EntityId::Creature(CREATURE_1_ID);
+    let creature_2 = EntityId::Creature(CREATURE_2_ID);
+    let creature_3 = EntityId::Creature(3);
+    let object = EntityId::Object(1);
+    let mut server = util::server(CustomRules::new());
+    // Create three teams, with team two allied and team three enemy of team one.
+    util::team(&mut server, TEAM_1_ID);
+    assert_eq!(
+        CreateTeam::trigger(&mut server, TEAM_2_ID)
+            .relations(&[(TEAM_1_ID, Relation::Ally)])
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(
+        CreateTeam::trigger(&mut server, TEAM_3_ID)
+            .relations(&[(TEAM_1_ID, Relation::Enemy)])
+            .fire()
+            .err(),
+        None
+    );
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    util::creature(&mut server, CREATURE_2_ID, TEAM_2_ID, ());
+    util::creature(&mut server, 3, TEAM_3_ID, ());
+    util::object(&mut server, 1, ());
+    let entities = server.battle().entities();
+    // Check relations between entities.
+    assert_eq!(
+        entities.entity_relation(&creature_1, &creature_1),
+        Some(Relation::Kin)
+    );
+    assert_eq!(
+        entities.entity_relation(&creature_1, &creature_3),
+        Some(Relation::Enemy)
+    );
+    assert_eq!(entities.entity_relation(&creature_1, &object), None);
+    // Verify targets.
+    assert_eq!(
+        entities
+            .verify_target(&creature_1, &creature_3, TargetRelation::Enemy)
+            .err(),
+        None
+    );
+    assert_eq!(
+        entities
+            .verify_target(&creature_1, &creature_2, TargetRelation::Enemy)
+            .err(),
+        Some(WeaselError::InvalidTargetRelation(
+            creature_1,
+            creature_2,
+            TargetRelation::Enemy
+        ))
+    );
+    assert_eq!(
+        entities
+            .verify_target(&creature_1, &creature_1, TargetRelation::Ally)
+            .err(),
+        None
+    );
+    assert_eq!(
+        entities
+            .verify_target(&creature_1, &creature_2, TargetRelation::Ally)
+            .err(),
+        None
+    );
+    assert_eq!(
+        entities
+            .verify_target(&creature_1, &object, TargetRelation::Ally)
+            .err(),
+        Some(WeaselError::InvalidTargetRelation(
+            creature_1,
+            object,
+            TargetRelation::Ally
+        ))
+    );
+    assert_eq!(
+        entities
+            .verify_target(&creature_1, &object, TargetRelation::Any)
+            .err(),
+        None
+    );
+    assert_eq!(
+        entities
+            .verify_target(
+                &creature_1,
+                &EntityId::Creature(CREATURE_ERR_ID),
+                TargetRelation::Any
+            )
+            .err(),
+        Some(WeaselError::EntityNotFound(EntityId::Creature(
+            CREATURE_ERR_ID
+        )))
+    );
+}
+
+#[test]
+fn round_upkeep() {
+    use weasel::round::EndRound;
+    use weasel::rules::empty::EmptyPower;
+
+    #[derive(Default)]
+    struct CustomTeamRules {}
+
+    impl TeamRules<CustomRules> for CustomTeamRules {
+        type Id = u32;
+        type Power = EmptyPower;
+        type PowersSeed = ();
+        type Invocation = ();
+        type PowersAlteration = ();
+        type ObjectivesSeed = ();
+        type Objectives = ();
+        // The gold owned by the team.
+        type TeamData = u32;
+
+        fn on_round_start(
+            &self,
+            team: &mut Team<CustomRules>,
+            _event_queue: &mut Option<EventQueue<CustomRules>>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) {
+            // Gain one gold per round.
+            *team.data_mut() += 1;
+        }
+
+        fn on_round_end(
+            &self,
+            team: &mut Team<CustomRules>,
+            event_queue: &mut Option<EventQueue<CustomRules>>,
+            _entropy: &mut Entropy<CustomRules>,
+            _metrics: &mut WriteMetrics<CustomRules>,
+        ) {
+            // Score as many points as the gold owned.
+            AddScore::trigger(event_queue, *team.id(), i64::from(*team.data())).fire();
+        }
+    }
+
+    battle_rules_with_team! { CustomTeamRules }
+
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    util::team(&mut server, TEAM_2_ID);
+    assert_eq!(
+        *server.battle().entities().team(&TEAM_1_ID).unwrap().data(),
+        0
+    );
+    // Each round grants gold to every team.
+    for _ in 0..3 {
+        assert_eq!(EndRound::trigger(&mut server).fire().err(), None);
+    }
+    for id in &[TEAM_1_ID, TEAM_2_ID] {
+        let team = server.battle().entities().team(id).unwrap();
+        assert_eq!(*team.data(), 3);
+        assert_eq!(team.score(), 3);
+    }
+}
@@ -0,0 +1,87 @@
+use weasel::battle::{Battle, BattleController, BattleRules};
+use weasel::entity::EntityId;
+use weasel::event::{DummyEvent, EventKind, EventTrigger};
+use weasel::round::{EndTurn, StartTurn};
+use weasel::server::Server;
+use weasel::{battle_rules, rules::empty::*};
+
+const TEAM_1_ID: u32 = 1;
+const CREATURE_1_ID: u32 = 1;
+const ENTITY_1_ID: EntityId<CustomRules> = EntityId::Creature(CREATURE_1_ID);
+
+battle_rules! {}
+
+/// Creates a server with a team and a creature.
+fn create_server() -> Server<CustomRules> {
+    let battle = Battle::builder(CustomRules::new()).build();
+    let mut server = Server::builder(battle).build();
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    server
+}
+
+/// Returns the kinds of all events in the history of `server`.
+fn kinds(server: &Server<CustomRules>) -> Vec<EventKind> {
+    server
+        .battle()
+        .history()
+        .events()
+        .iter()
+        .map(|event| event.kind())
+        .collect()
+}
+
+#[test]
+fn commit() {
+    let mut server = create_server();
+    util::dummy(&mut server);
+    let mut transaction = server.transaction(&create_server).unwrap();
+    assert_eq!(transaction.projection().history().len(), 3);
+    // Events are verified against the projected state.
+    assert!(EndTurn::trigger(&mut transaction).fire().is_err());
+    assert_eq!(
+        StartTurn::trigger(&mut transaction, ENTITY_1_ID)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(EndTurn::trigger(&mut transaction).fire().err(), None);
+    // Failed events are not part of the transaction.
+    assert_eq!(transaction.events().len(), 2);
+    assert_eq!(transaction.server().battle().history().len(), 3);
+    // Commit the transaction.
+    assert_eq!(transaction.commit().err(), None);
+    assert_eq!(
+        kinds(&server),
+        vec![
+            EventKind::CreateTeam,
+            EventKind::CreateCreature,
+            EventKind::DummyEvent,
+            EventKind::StartTurn,
+            EventKind::EndTurn
+        ]
+    );
+}
+
+#[test]
+fn rollback() {
+    let mut server = create_server();
+    let mut transaction = server.transaction(&create_server).unwrap();
+    assert_eq!(
+        StartTurn::trigger(&mut transaction, ENTITY_1_ID)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(DummyEvent::trigger(&mut transaction).fire().err(), None);
+    transaction.rollback();
+    assert_eq!(server.battle().history().len(), 2);
+    // No turn is in progress on the server.
+    util::start_turn(&mut server, &ENTITY_1_ID);
+}
+
+#[test]
+fn factory_ahead_of_server() {
+    let mut server = Server::builder(Battle::builder(CustomRules::new()).build()).build();
+    assert!(server.transaction(&create_server).is_err());
+}
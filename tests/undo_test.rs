@@ -0,0 +1,129 @@
+use weasel::battle::{Battle, BattleController, BattleRules};
+use weasel::entity::EntityId;
+use weasel::event::{DummyEvent, EventKind, EventTrigger};
+use weasel::round::{EndTurn, StartTurn};
+use weasel::server::Server;
+use weasel::undo::UndoManager;
+use weasel::{battle_rules, rules::empty::*};
+
+const TEAM_1_ID: u32 = 1;
+const CREATURE_1_ID: u32 = 1;
+const ENTITY_1_ID: EntityId<CustomRules> = EntityId::Creature(CREATURE_1_ID);
+
+battle_rules! {}
+
+/// Creates a server with a team and a creature.
+fn create_server() -> Server<CustomRules> {
+    let battle = Battle::builder(CustomRules::new()).build();
+    let mut server = Server::builder(battle).build();
+    util::team(&mut server, TEAM_1_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    server
+}
+
+/// Fires a turn containing a dummy event.
+fn turn(server: &mut Server<CustomRules>) {
+    util::start_turn(server, &ENTITY_1_ID);
+    util::dummy(server);
+    util::end_turn(server);
+}
+
+/// Returns the kinds of all events in the history of the managed server.
+fn kinds(manager: &UndoManager<CustomRules>) -> Vec<EventKind> {
+    manager
+        .server()
+        .battle()
+        .history()
+        .events()
+        .iter()
+        .map(|event| event.kind())
+        .collect()
+}
+
+#[test]
+fn undo_redo_turns() {
+    let mut manager = UndoManager::new(Box::new(create_server));
+    // Events fired by the factory can't be undone.
+    assert!(!manager.can_undo());
+    assert!(!manager.can_redo());
+    assert_eq!(manager.undo().ok(), Some(false));
+    assert_eq!(manager.server().battle().history().len(), 2);
+    // Fire two turns and an event outside turns.
+    turn(manager.server_mut());
+    turn(manager.server_mut());
+    assert_eq!(DummyEvent::trigger(manager.server_mut()).fire().err(), None);
+    assert_eq!(manager.server().battle().history().len(), 9);
+    // Undo the single event.
+    assert_eq!(manager.undo().ok(), Some(true));
+    assert_eq!(manager.server().battle().history().len(), 8);
+    assert!(manager.can_redo());
+    // Undo whole turns.
+    assert_eq!(manager.undo().ok(), Some(true));
+    assert_eq!(manager.server().battle().history().len(), 5);
+    assert_eq!(manager.undo().ok(), Some(true));
+    assert_eq!(
+        kinds(&manager),
+        vec![EventKind::CreateTeam, EventKind::CreateCreature]
+    );
+    assert!(!manager.can_undo());
+    // Redo a turn.
+    assert_eq!(manager.redo().ok(), Some(true));
+    assert_eq!(
+        kinds(&manager)[2..],
+        [
+            EventKind::StartTurn,
+            EventKind::DummyEvent,
+            EventKind::EndTurn
+        ]
+    );
+    assert!(!manager.server().battle().rounds().is_acting(&ENTITY_1_ID));
+    // Redo everything.
+    assert_eq!(manager.redo().ok(), Some(true));
+    assert_eq!(manager.redo().ok(), Some(true));
+    assert_eq!(manager.server().battle().history().len(), 9);
+    assert!(!manager.can_redo());
+    assert_eq!(manager.redo().ok(), Some(false));
+}
+
+#[test]
+fn open_turn() {
+    let mut manager = UndoManager::new(Box::new(create_server));
+    turn(manager.server_mut());
+    // An open turn is undone as a whole.
+    util::start_turn(manager.server_mut(), &ENTITY_1_ID);
+    assert_eq!(DummyEvent::trigger(manager.server_mut()).fire().err(), None);
+    assert_eq!(manager.undo().ok(), Some(true));
+    assert_eq!(manager.server().battle().history().len(), 5);
+    assert!(!manager.server().battle().rounds().is_acting(&ENTITY_1_ID));
+    // Redo reopens the turn.
+    assert_eq!(manager.redo().ok(), Some(true));
+    assert!(manager.server().battle().rounds().is_acting(&ENTITY_1_ID));
+    assert_eq!(manager.server().battle().history().len(), 7);
+}
+
+#[test]
+fn new_events_invalidate_redo() {
+    let mut manager = UndoManager::new(Box::new(create_server));
+    turn(manager.server_mut());
+    turn(manager.server_mut());
+    assert_eq!(manager.undo().ok(), Some(true));
+    assert!(manager.can_redo());
+    // Fire a new event.
+    assert_eq!(
+        StartTurn::trigger(manager.server_mut(), ENTITY_1_ID)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(EndTurn::trigger(manager.server_mut()).fire().err(), None);
+    assert!(!manager.can_redo());
+    assert_eq!(manager.redo().ok(), Some(false));
+    // Undo works on the new timeline.
+    assert_eq!(manager.undo().ok(), Some(true));
+    assert_eq!(manager.server().battle().history().len(), 5);
+    assert_eq!(manager.redo().ok(), Some(true));
+    assert_eq!(
+        kinds(&manager)[5..],
+        [EventKind::StartTurn, EventKind::EndTurn]
+    );
+}
@@ -0,0 +1,166 @@
+use weasel::battle::{BattleController, BattleRules};
+use weasel::creature::{ReidentifyCreature, RemoveCreature};
+use weasel::entity::EntityId;
+use weasel::event::EventTrigger;
+use weasel::team::{RemoveTeam, RenameTeam};
+use weasel::visibility::{HideEntity, RevealEntity};
+use weasel::{battle_rules, rules::empty::*, WeaselError};
+
+const TEAM_1_ID: u32 = 1;
+const TEAM_2_ID: u32 = 2;
+const TEAM_3_ID: u32 = 3;
+const TEAM_ERR_ID: u32 = 99;
+const CREATURE_1_ID: u32 = 1;
+const CREATURE_2_ID: u32 = 2;
+const CREATURE_3_ID: u32 = 3;
+const OBJECT_1_ID: u32 = 1;
+const ENTITY_1_ID: EntityId<CustomRules> = EntityId::Creature(CREATURE_1_ID);
+const ENTITY_2_ID: EntityId<CustomRules> = EntityId::Creature(CREATURE_2_ID);
+const ENTITY_3_ID: EntityId<CustomRules> = EntityId::Creature(CREATURE_3_ID);
+const ENTITY_O1_ID: EntityId<CustomRules> = EntityId::Object(OBJECT_1_ID);
+const ENTITY_ERR_ID: EntityId<CustomRules> = EntityId::Creature(99);
+
+battle_rules! {}
+
+#[test]
+fn reveal_and_hide() {
+    // Create a scenario.
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    util::team(&mut server, TEAM_2_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    util::object(&mut server, OBJECT_1_ID, ());
+    // Check that events referring to non existing teams or entities are rejected.
+    assert_eq!(
+        RevealEntity::trigger(&mut server, ENTITY_ERR_ID, TEAM_2_ID)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::EntityNotFound(ENTITY_ERR_ID))
+    );
+    assert_eq!(
+        RevealEntity::trigger(&mut server, ENTITY_1_ID, TEAM_ERR_ID)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::TeamNotFound(TEAM_ERR_ID))
+    );
+    assert_eq!(
+        HideEntity::trigger(&mut server, ENTITY_1_ID)
+            .team(TEAM_ERR_ID)
+            .fire()
+            .err()
+            .map(|e| e.unfold()),
+        Some(WeaselError::TeamNotFound(TEAM_ERR_ID))
+    );
+    // Reveal entities.
+    for (entity_id, team_id) in &[
+        (ENTITY_1_ID, TEAM_2_ID),
+        (ENTITY_O1_ID, TEAM_1_ID),
+        (ENTITY_O1_ID, TEAM_2_ID),
+    ] {
+        assert_eq!(
+            RevealEntity::trigger(&mut server, *entity_id, *team_id)
+                .fire()
+                .err(),
+            None
+        );
+    }
+    let visibility = server.battle().visibility();
+    assert!(visibility.is_revealed(&TEAM_2_ID, &ENTITY_1_ID));
+    assert!(!visibility.is_revealed(&TEAM_1_ID, &ENTITY_1_ID));
+    assert_eq!(visibility.revealed_to(&TEAM_2_ID).count(), 2);
+    assert_eq!(visibility.observers(&ENTITY_O1_ID).count(), 2);
+    // Hide an entity from a single team.
+    assert_eq!(
+        HideEntity::trigger(&mut server, ENTITY_O1_ID)
+            .team(TEAM_1_ID)
+            .fire()
+            .err(),
+        None
+    );
+    let visibility = server.battle().visibility();
+    assert!(!visibility.is_revealed(&TEAM_1_ID, &ENTITY_O1_ID));
+    assert!(visibility.is_revealed(&TEAM_2_ID, &ENTITY_O1_ID));
+    // Hide an entity from all teams.
+    assert_eq!(
+        HideEntity::trigger(&mut server, ENTITY_O1_ID).fire().err(),
+        None
+    );
+    assert_eq!(
+        server
+            .battle()
+            .visibility()
+            .observers(&ENTITY_O1_ID)
+            .count(),
+        0
+    );
+}
+
+#[test]
+fn visibility_follows_entities_and_teams() {
+    // Create a scenario.
+    let mut server = util::server(CustomRules::new());
+    util::team(&mut server, TEAM_1_ID);
+    util::team(&mut server, TEAM_2_ID);
+    util::creature(&mut server, CREATURE_1_ID, TEAM_1_ID, ());
+    util::creature(&mut server, CREATURE_2_ID, TEAM_2_ID, ());
+    assert_eq!(
+        RevealEntity::trigger(&mut server, ENTITY_1_ID, TEAM_2_ID)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(
+        RevealEntity::trigger(&mut server, ENTITY_2_ID, TEAM_1_ID)
+            .fire()
+            .err(),
+        None
+    );
+    // Reidentify a creature.
+    assert_eq!(
+        ReidentifyCreature::trigger(&mut server, CREATURE_1_ID, CREATURE_3_ID)
+            .fire()
+            .err(),
+        None
+    );
+    assert!(server
+        .battle()
+        .visibility()
+        .is_revealed(&TEAM_2_ID, &ENTITY_3_ID));
+    // Rename a team.
+    assert_eq!(
+        RenameTeam::trigger(&mut server, TEAM_2_ID, TEAM_3_ID)
+            .fire()
+            .err(),
+        None
+    );
+    assert!(server
+        .battle()
+        .visibility()
+        .is_revealed(&TEAM_3_ID, &ENTITY_3_ID));
+    // Remove a creature.
+    assert_eq!(
+        RemoveCreature::trigger(&mut server, CREATURE_3_ID)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(
+        server.battle().visibility().revealed_to(&TEAM_3_ID).count(),
+        0
+    );
+    // Remove a team.
+    util::object(&mut server, OBJECT_1_ID, ());
+    assert_eq!(
+        RevealEntity::trigger(&mut server, ENTITY_O1_ID, TEAM_1_ID)
+            .fire()
+            .err(),
+        None
+    );
+    assert_eq!(
+        RemoveTeam::trigger(&mut server, TEAM_1_ID).fire().err(),
+        None
+    );
+    assert_eq!(server.battle().visibility().revealed().count(), 0);
+}